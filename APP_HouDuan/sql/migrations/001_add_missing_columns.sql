@@ -35,34 +35,9 @@ CREATE TABLE IF NOT EXISTS posts (
 );
 
 -- ========================================
--- 标签系统（如果不存在则创建）
+-- 标签系统：tags / post_tags / package_tags 由迁移 024 创建
 -- ========================================
 
-CREATE TABLE IF NOT EXISTS tags (
-    id INTEGER PRIMARY KEY AUTOINCREMENT,
-    name VARCHAR(50) UNIQUE NOT NULL,
-    description TEXT,
-    color VARCHAR(10),
-    category VARCHAR(50),
-    usage_count INTEGER DEFAULT 0,
-    is_system BOOLEAN DEFAULT 0,
-    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
-);
-
-CREATE TABLE IF NOT EXISTS package_tags (
-    package_id INTEGER NOT NULL,
-    tag_name VARCHAR(50) NOT NULL,
-    PRIMARY KEY (package_id, tag_name),
-    FOREIGN KEY (package_id) REFERENCES packages(id) ON DELETE CASCADE
-);
-
-CREATE TABLE IF NOT EXISTS post_tags (
-    post_id INTEGER NOT NULL,
-    tag_name VARCHAR(50) NOT NULL,
-    PRIMARY KEY (post_id, tag_name),
-    FOREIGN KEY (post_id) REFERENCES posts(id) ON DELETE CASCADE
-);
-
 -- ========================================
 -- 通知系统（如果不存在则创建）
 -- ========================================
@@ -0,0 +1,93 @@
+-- 迁移脚本: 多语言邮件模板
+-- 版本: 005
+-- 说明: mail_templates 增加 language 列，唯一约束改为 (template_type, language)，补充默认模板
+
+-- ========================================
+-- 重建 mail_templates 表
+-- ========================================
+
+CREATE TABLE IF NOT EXISTS mail_templates (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    template_type TEXT NOT NULL UNIQUE,
+    subject TEXT NOT NULL,
+    content TEXT NOT NULL,
+    variables TEXT,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- 旧表先补 language 列，使重复执行时保留已有的语言
+ALTER TABLE mail_templates ADD COLUMN language TEXT NOT NULL DEFAULT 'zh-CN';
+
+DROP TABLE IF EXISTS mail_templates_new;
+CREATE TABLE mail_templates_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    template_type TEXT NOT NULL,
+    language TEXT NOT NULL DEFAULT 'zh-CN',
+    subject TEXT NOT NULL,
+    content TEXT NOT NULL,
+    variables TEXT,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE (template_type, language)
+);
+
+INSERT INTO mail_templates_new (id, template_type, language, subject, content, variables, enabled, created_at, updated_at)
+SELECT id, template_type, language, subject, content, variables, enabled, created_at, updated_at FROM mail_templates;
+
+DROP TABLE mail_templates;
+ALTER TABLE mail_templates_new RENAME TO mail_templates;
+
+-- ========================================
+-- 默认模板（已存在则跳过）
+-- ========================================
+
+INSERT OR IGNORE INTO mail_templates (template_type, language, subject, content, variables) VALUES
+('verification', 'zh-CN', '【绳包管理器】邮箱验证码',
+'<h2>验证码</h2><p>您的验证码是：<strong style="color: #409EFF; font-size: 24px;">{{code}}</strong></p><p>此验证码5分钟内有效，请及时使用。</p>',
+'["code"]'),
+
+('reset_password', 'zh-CN', '【绳包管理器】密码重置',
+'<h2>密码重置</h2><p>您已请求重置密码，请点击以下链接重置：</p><p><a href="{{reset_link}}" style="color: #409EFF;">重置密码</a></p><p>如果您没有请求重置密码，请忽略此邮件。</p>',
+'["reset_link"]'),
+
+('notification', 'zh-CN', '【绳包管理器】新资源通知',
+'<h2>新资源发布</h2><p>有新的资源发布：</p><h3>{{resource_name}}</h3>{{#if resource_description}}<p>{{resource_description}}</p>{{/if}}<p><a href="{{resource_link}}" style="color: #409EFF;">查看详情</a></p>',
+'["resource_description","resource_link","resource_name"]'),
+
+('admin_notification', 'zh-CN', '【绳包管理器】新资源待审核',
+'<h2>新资源待审核</h2><p>用户 <strong>{{author}}</strong> 提交了新资源：</p><h3>{{resource_name}}</h3><p><a href="{{review_link}}" style="color: #409EFF;">前往审核</a></p>',
+'["author","resource_name","review_link"]'),
+
+('category_notification', 'zh-CN', '【绳包社区】{{title}}',
+'<div style="font-family: Arial, sans-serif; max-width: 600px; margin: 0 auto; padding: 20px;">
+    <div style="background: linear-gradient(135deg, #409EFF, #36CFC9); padding: 30px; border-radius: 10px 10px 0 0; text-align: center;">
+        <h1 style="color: white; margin: 0; font-size: 24px;">绳包社区通知</h1>
+        <p style="color: white; margin: 10px 0 0 0; opacity: 0.9;">{{title}}</p>
+    </div>
+    <div style="background: white; padding: 30px; border-radius: 0 0 10px 10px; box-shadow: 0 2px 10px rgba(0,0,0,0.1);">
+        <p style="margin: 0; line-height: 1.6; color: #333; white-space: pre-line;">{{content}}</p>
+        {{#if link}}<div style="text-align: center; margin: 30px 0;">
+            <a href="{{link}}" style="display: inline-block; background: #409EFF; color: white; padding: 12px 30px; text-decoration: none; border-radius: 6px; font-weight: bold;">查看更多内容</a>
+        </div>{{/if}}
+        <div style="border-top: 1px solid #eee; padding-top: 20px; margin-top: 30px; text-align: center; color: #999; font-size: 12px;">
+            <p>此邮件由绳包社区系统自动发送，请勿直接回复。</p>
+            <p>如需取消订阅，请登录您的账户进行设置。</p>
+        </div>
+    </div>
+</div>',
+'["content","link","title"]'),
+
+('test', 'zh-CN', '【绳包管理器】测试邮件',
+'<h2>测试邮件</h2><p>这是一封测试邮件，如果您收到此邮件，说明邮件服务配置正确。</p><p>发送时间：{{send_time}}</p>',
+'["send_time"]');
+
+-- ========================================
+-- 记录迁移完成
+-- ========================================
+
+INSERT OR REPLACE INTO system_settings (key, value, description) VALUES
+('migration_005_completed', datetime('now'), '迁移005完成时间'),
+('last_migration', '005_localized_mail_templates', '最后执行的迁移');
@@ -0,0 +1,62 @@
+-- 迁移脚本: 通知偏好与邮件摘要
+-- 版本: 006
+-- 说明: 新增通知偏好、摘要设置表，补充摘要邮件模板，分类通知邮件增加退订链接
+
+-- ========================================
+-- 通知偏好与摘要设置
+-- ========================================
+
+CREATE TABLE IF NOT EXISTS notification_preferences (
+    user_id INTEGER NOT NULL,
+    category TEXT NOT NULL,
+    channel TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    PRIMARY KEY (user_id, category)
+);
+
+CREATE TABLE IF NOT EXISTS notification_digest_settings (
+    user_id INTEGER PRIMARY KEY,
+    frequency TEXT NOT NULL DEFAULT 'off',
+    last_sent_at TEXT,
+    unsubscribe_token TEXT NOT NULL UNIQUE
+);
+
+-- ========================================
+-- 邮件模板
+-- ========================================
+
+INSERT OR IGNORE INTO mail_templates (template_type, language, subject, content, variables) VALUES
+('digest', 'zh-CN', '【绳包社区】{{period}}通知摘要（{{count}} 条未读）',
+'<div style="font-family: Arial, sans-serif; max-width: 600px; margin: 0 auto; padding: 20px;">
+    <h2 style="color: #409EFF;">{{period}}通知摘要</h2>
+    <p>{{#if username}}{{username}}，{{/if}}您有 {{count}} 条未读通知：</p>
+    <ul style="padding-left: 20px; line-height: 1.6; color: #333;">
+        {{#each items}}<li style="margin-bottom: 12px;">
+            <strong>{{#if link}}<a href="{{link}}" style="color: #409EFF;">{{title}}</a>{{else}}{{title}}{{/if}}</strong>
+            <span style="color: #999; font-size: 12px;">{{time}}</span>
+            <div style="white-space: pre-line;">{{content}}</div>
+        </li>{{/each}}
+    </ul>
+    {{#if link}}<p><a href="{{link}}" style="color: #409EFF;">查看全部通知</a></p>{{/if}}
+    <div style="border-top: 1px solid #eee; padding-top: 20px; margin-top: 30px; text-align: center; color: #999; font-size: 12px;">
+        <p>此邮件由绳包社区系统自动发送，请勿直接回复。</p>
+        <p><a href="{{unsubscribe_link}}" style="color: #999;">退订邮件通知</a></p>
+    </div>
+</div>',
+'["count","items","link","period","unsubscribe_link","username"]');
+
+UPDATE mail_templates
+SET content = replace(content,
+        '<p>如需取消订阅，请登录您的账户进行设置。</p>',
+        '<p>{{#if unsubscribe_link}}<a href="{{unsubscribe_link}}" style="color: #999;">退订此类邮件</a>{{else}}如需取消订阅，请登录您的账户进行设置。{{/if}}</p>'),
+    variables = '["content","link","title","unsubscribe_link"]',
+    updated_at = datetime('now')
+WHERE template_type = 'category_notification';
+
+-- ========================================
+-- 记录迁移完成
+-- ========================================
+
+INSERT OR REPLACE INTO system_settings (key, value, description) VALUES
+('migration_006_completed', datetime('now'), '迁移006完成时间'),
+('last_migration', '006_notification_preferences', '最后执行的迁移');
@@ -0,0 +1,32 @@
+-- 迁移脚本: 资源评分与评价
+-- 版本: 007
+-- 说明: 新增 package_ratings 表，packages 表冗余评分汇总（平均分、人数、星级分布）
+
+CREATE TABLE IF NOT EXISTS package_ratings (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    package_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    rating INTEGER NOT NULL CHECK (rating BETWEEN 1 AND 5),
+    review TEXT,
+    review_status TEXT NOT NULL DEFAULT 'visible',
+    moderation_reason TEXT,
+    author_reply TEXT,
+    author_replied_at TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    UNIQUE (package_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_package_ratings_package ON package_ratings(package_id);
+
+ALTER TABLE packages ADD COLUMN rating_avg REAL NOT NULL DEFAULT 0;
+ALTER TABLE packages ADD COLUMN rating_count INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE packages ADD COLUMN rating_distribution TEXT NOT NULL DEFAULT '[0,0,0,0,0]';
+
+-- ========================================
+-- 记录迁移完成
+-- ========================================
+
+INSERT OR REPLACE INTO system_settings (key, value, description) VALUES
+('migration_007_completed', datetime('now'), '迁移007完成时间'),
+('last_migration', '007_package_ratings', '最后执行的迁移');
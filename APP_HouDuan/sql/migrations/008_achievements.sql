@@ -0,0 +1,43 @@
+-- 迁移脚本: 成就系统
+-- 版本: 008
+-- 说明: 成就定义表（按条件类型与阈值判定）与用户成就表，预置默认成就
+
+CREATE TABLE IF NOT EXISTS achievements (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    code TEXT NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    icon TEXT NOT NULL DEFAULT '🏅',
+    description TEXT NOT NULL DEFAULT '',
+    criteria_type TEXT NOT NULL,
+    threshold INTEGER NOT NULL DEFAULT 1,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE TABLE IF NOT EXISTS user_achievements (
+    user_id INTEGER NOT NULL,
+    achievement_id INTEGER NOT NULL,
+    earned_at TEXT NOT NULL,
+    PRIMARY KEY (user_id, achievement_id)
+);
+
+-- criteria_type: packages_uploaded / likes_received / check_in_streak / featured_posts / posts_published / comments_posted
+INSERT OR IGNORE INTO achievements (code, name, icon, description, criteria_type, threshold, sort_order) VALUES
+('first_post', '初来乍到', '🌱', '发布第一篇帖子', 'posts_published', 1, 10),
+('first_package', '资源分享', '📦', '第一个资源通过审核上架', 'packages_uploaded', 1, 20),
+('packages_5', '资源达人', '🧰', '累计上架 5 个资源', 'packages_uploaded', 5, 21),
+('likes_10', '小有人气', '👍', '资源与帖子累计获得 10 个赞', 'likes_received', 10, 30),
+('likes_100', '人气之星', '🌟', '资源与帖子累计获得 100 个赞', 'likes_received', 100, 31),
+('streak_7', '坚持不懈', '📅', '连续签到 7 天', 'check_in_streak', 7, 40),
+('streak_30', '持之以恒', '🔥', '连续签到 30 天', 'check_in_streak', 30, 41),
+('first_featured', '精华作者', '💎', '第一篇帖子被加精', 'featured_posts', 1, 50),
+('comments_50', '热心评论', '💬', '累计发表 50 条评论', 'comments_posted', 50, 60);
+
+-- ========================================
+-- 记录迁移完成
+-- ========================================
+
+INSERT OR REPLACE INTO system_settings (key, value, description) VALUES
+('migration_008_completed', datetime('now'), '迁移008完成时间'),
+('last_migration', '008_achievements', '最后执行的迁移');
@@ -0,0 +1,64 @@
+-- 迁移脚本: 经验、等级与星星
+-- 版本: 009
+-- 说明: 积分规则表（可配置奖励与每日上限）、积分流水表、等级表；users 表新增 experience 经验值，star 作为星星余额
+
+CREATE TABLE IF NOT EXISTS point_rules (
+    action TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    xp INTEGER NOT NULL DEFAULT 0,
+    stars INTEGER NOT NULL DEFAULT 0,
+    daily_cap INTEGER NOT NULL DEFAULT 0,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    updated_at TEXT
+);
+
+CREATE TABLE IF NOT EXISTS point_ledger (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    action TEXT NOT NULL,
+    xp INTEGER NOT NULL DEFAULT 0,
+    stars INTEGER NOT NULL DEFAULT 0,
+    reason TEXT NOT NULL DEFAULT '',
+    related_type TEXT,
+    related_id INTEGER,
+    dedupe_key TEXT,
+    created_at TEXT NOT NULL,
+    UNIQUE (user_id, dedupe_key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_point_ledger_user_time ON point_ledger(user_id, created_at);
+
+CREATE TABLE IF NOT EXISTS user_levels (
+    level INTEGER PRIMARY KEY,
+    title TEXT NOT NULL,
+    min_xp INTEGER NOT NULL
+);
+
+ALTER TABLE users ADD COLUMN experience INTEGER NOT NULL DEFAULT 0;
+
+-- daily_cap: 每日最多计入次数，0 表示不限
+INSERT OR IGNORE INTO point_rules (action, name, xp, stars, daily_cap) VALUES
+('check_in', '每日签到', 5, 1, 1),
+('package_approved', '资源审核通过', 50, 5, 5),
+('post_published', '发布帖子', 10, 1, 5),
+('like_received', '获得点赞', 2, 0, 50),
+('content_featured', '内容被加精', 30, 3, 0),
+('comment_posted', '发表评论', 1, 0, 20),
+('content_removed', '内容被管理员删除', -20, -2, 0);
+
+INSERT OR IGNORE INTO user_levels (level, title, min_xp) VALUES
+(1, '新手绳友', 0),
+(2, '入门绳友', 100),
+(3, '初级开发者', 300),
+(4, '中级开发者', 800),
+(5, '高级开发者', 2000),
+(6, '资深开发者', 5000),
+(7, '绳包大师', 12000);
+
+-- ========================================
+-- 记录迁移完成
+-- ========================================
+
+INSERT OR REPLACE INTO system_settings (key, value, description) VALUES
+('migration_009_completed', datetime('now'), '迁移009完成时间'),
+('last_migration', '009_points_and_levels', '最后执行的迁移');
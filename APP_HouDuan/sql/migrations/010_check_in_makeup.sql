@@ -0,0 +1,28 @@
+-- 迁移脚本: 签到表与补签
+-- 版本: 010
+-- 说明: user_check_ins 由迁移创建（此前在签到请求中按需创建），新增 is_makeup 标记补签；
+--       check_in_date 为社区时区（UTC+8）的自然日；补签消耗的星星由积分规则 check_in_makeup 配置
+
+CREATE TABLE IF NOT EXISTS user_check_ins (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    check_in_date TEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(user_id, check_in_date)
+);
+
+ALTER TABLE user_check_ins ADD COLUMN is_makeup INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_user_check_ins_user_date ON user_check_ins(user_id, check_in_date);
+
+-- stars 为负数表示每次补签扣除的星星；停用该规则即关闭补签
+INSERT OR IGNORE INTO point_rules (action, name, xp, stars, daily_cap) VALUES
+('check_in_makeup', '补签', 0, -5, 0);
+
+-- ========================================
+-- 记录迁移完成
+-- ========================================
+
+INSERT OR REPLACE INTO system_settings (key, value, description) VALUES
+('migration_010_completed', datetime('now'), '迁移010完成时间'),
+('last_migration', '010_check_in_makeup', '最后执行的迁移');
@@ -0,0 +1,50 @@
+-- 迁移脚本: 个人周报
+-- 版本: 011
+-- 说明: 按 ISO 周缓存用户周报；摘要设置新增 weekly_report 开关，开启后每周一发送上周周报邮件
+
+CREATE TABLE IF NOT EXISTS weekly_reports (
+    user_id INTEGER NOT NULL,
+    week TEXT NOT NULL,              -- ISO 周，如 2025-W01
+    data TEXT NOT NULL,              -- 周报 JSON
+    generated_at TEXT NOT NULL,
+    emailed_at TEXT,
+    PRIMARY KEY (user_id, week)
+);
+
+ALTER TABLE notification_digest_settings ADD COLUMN weekly_report INTEGER NOT NULL DEFAULT 0;
+
+-- ========================================
+-- 邮件模板
+-- ========================================
+
+INSERT OR IGNORE INTO mail_templates (template_type, language, subject, content, variables) VALUES
+('weekly_report', 'zh-CN', '【绳包社区】您的 {{week}} 周报',
+'<div style="font-family: Arial, sans-serif; max-width: 600px; margin: 0 auto; padding: 20px;">
+    <h2 style="color: #409EFF;">{{week}} 周报</h2>
+    <p>{{#if username}}{{username}}，{{/if}}这是您 {{week_range}} 在社区的动态：</p>
+    <table style="width: 100%; border-collapse: collapse; color: #333; line-height: 2;">
+        <tr><td>发布帖子</td><td style="text-align: right;">{{posts_published}}</td></tr>
+        <tr><td>发布资源</td><td style="text-align: right;">{{resources_published}}</td></tr>
+        <tr><td>资源被下载</td><td style="text-align: right;">{{downloads_received}}</td></tr>
+        <tr><td>获得点赞</td><td style="text-align: right;">{{likes_received}}</td></tr>
+        <tr><td>新增关注者</td><td style="text-align: right;">{{new_followers}}</td></tr>
+        <tr><td>发表评论</td><td style="text-align: right;">{{comments_posted}}</td></tr>
+        <tr><td>签到天数</td><td style="text-align: right;">{{check_in_days}}</td></tr>
+        <tr><td>获得经验</td><td style="text-align: right;">{{xp_gained}}</td></tr>
+        <tr><td>排行榜名次</td><td style="text-align: right;">第 {{rank}} 名（{{rank_change}}）</td></tr>
+    </table>
+    {{#if link}}<p><a href="{{link}}" style="color: #409EFF;">查看完整周报</a></p>{{/if}}
+    <div style="border-top: 1px solid #eee; padding-top: 20px; margin-top: 30px; text-align: center; color: #999; font-size: 12px;">
+        <p>此邮件由绳包社区系统自动发送，请勿直接回复。</p>
+        <p><a href="{{unsubscribe_link}}" style="color: #999;">退订邮件通知</a></p>
+    </div>
+</div>',
+'["check_in_days","comments_posted","downloads_received","likes_received","link","new_followers","posts_published","rank","rank_change","resources_published","unsubscribe_link","username","week","week_range","xp_gained"]');
+
+-- ========================================
+-- 记录迁移完成
+-- ========================================
+
+INSERT OR REPLACE INTO system_settings (key, value, description) VALUES
+('migration_011_completed', datetime('now'), '迁移011完成时间'),
+('last_migration', '011_weekly_reports', '最后执行的迁移');
@@ -0,0 +1,72 @@
+-- 迁移脚本: 按时间段统计的排行榜
+-- 版本: 012
+-- 说明: 资源（resource_access_stats 新增 like_count）、帖子、用户的每日汇总表；
+--       浏览量只有累计计数，按两次汇总之间的增量计入当天；已结束周期的排行榜保存快照供历史查询
+
+CREATE TABLE IF NOT EXISTS resource_access_stats (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    package_id INTEGER NOT NULL,
+    date TEXT NOT NULL,
+    view_count INTEGER NOT NULL DEFAULT 0,
+    download_count INTEGER NOT NULL DEFAULT 0,
+    unique_visitors INTEGER NOT NULL DEFAULT 0,
+    unique_downloaders INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE(package_id, date)
+);
+
+ALTER TABLE resource_access_stats ADD COLUMN like_count INTEGER NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS post_daily_stats (
+    post_id INTEGER NOT NULL,
+    date TEXT NOT NULL,
+    view_count INTEGER NOT NULL DEFAULT 0,
+    like_count INTEGER NOT NULL DEFAULT 0,
+    comment_count INTEGER NOT NULL DEFAULT 0,
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (post_id, date)
+);
+
+CREATE TABLE IF NOT EXISTS user_daily_stats (
+    user_id INTEGER NOT NULL,
+    date TEXT NOT NULL,
+    xp INTEGER NOT NULL DEFAULT 0,
+    downloads INTEGER NOT NULL DEFAULT 0,    -- 用户资源被下载次数
+    likes INTEGER NOT NULL DEFAULT 0,        -- 用户内容获得的点赞
+    followers_gained INTEGER NOT NULL DEFAULT 0,
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (user_id, date)
+);
+
+-- 累计计数器上次汇总时的值（用于计算浏览量增量）
+CREATE TABLE IF NOT EXISTS ranking_counter_state (
+    counter TEXT NOT NULL,
+    entity_id INTEGER NOT NULL,
+    last_value INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (counter, entity_id)
+);
+
+-- category_id 为 0 表示全部分类
+CREATE TABLE IF NOT EXISTS ranking_snapshots (
+    board TEXT NOT NULL,
+    period TEXT NOT NULL,
+    period_key TEXT NOT NULL,
+    category_id INTEGER NOT NULL DEFAULT 0,
+    entity_id INTEGER NOT NULL,
+    rank INTEGER NOT NULL,
+    score INTEGER NOT NULL DEFAULT 0,
+    data TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (board, period, period_key, category_id, entity_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_ranking_snapshots_rank ON ranking_snapshots(board, period, period_key, category_id, rank);
+
+-- ========================================
+-- 记录迁移完成
+-- ========================================
+
+INSERT OR REPLACE INTO system_settings (key, value, description) VALUES
+('migration_012_completed', datetime('now'), '迁移012完成时间'),
+('last_migration', '012_ranking_rollups', '最后执行的迁移');
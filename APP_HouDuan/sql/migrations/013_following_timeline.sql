@@ -0,0 +1,19 @@
+-- 迁移脚本: 关注时间线
+-- 版本: 013
+-- 说明: 时间线在读取时按 user_follows 与 subscriptions 合并各类动态，这里补充按作者/分类 + 时间查找的复合索引
+--       （user_follows(follower_id) 与 subscriptions 主键已有索引）
+
+CREATE INDEX IF NOT EXISTS idx_posts_author_created ON posts(author_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_posts_category_created ON posts(category_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_packages_author_created ON packages(author, created_at);
+CREATE INDEX IF NOT EXISTS idx_packages_category_created ON packages(category_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_resource_records_type_action ON resource_records(resource_type, action, timestamp);
+CREATE INDEX IF NOT EXISTS idx_user_achievements_user_earned ON user_achievements(user_id, earned_at);
+
+-- ========================================
+-- 记录迁移完成
+-- ========================================
+
+INSERT OR REPLACE INTO system_settings (key, value, description) VALUES
+('migration_013_completed', datetime('now'), '迁移013完成时间'),
+('last_migration', '013_following_timeline', '最后执行的迁移');
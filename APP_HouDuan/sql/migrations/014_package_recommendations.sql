@@ -0,0 +1,43 @@
+-- 迁移脚本: 资源推荐
+-- 版本: 014
+-- 说明: 后台任务根据共同下载、共同点赞/收藏与标签计算的相似资源表与个人推荐表（整表重算）
+
+-- 收藏表此前按需创建，这里保证存在
+CREATE TABLE IF NOT EXISTS package_favorites (
+    user_id INTEGER NOT NULL,
+    package_id INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (CURRENT_TIMESTAMP),
+    PRIMARY KEY (user_id, package_id)
+);
+
+CREATE TABLE IF NOT EXISTS package_similarities (
+    package_id INTEGER NOT NULL,
+    similar_id INTEGER NOT NULL,
+    score REAL NOT NULL,
+    co_downloads INTEGER NOT NULL DEFAULT 0,  -- 同时下载过两者的用户数
+    co_likes INTEGER NOT NULL DEFAULT 0,      -- 同时点赞或收藏过两者的用户数
+    shared_tags INTEGER NOT NULL DEFAULT 0,
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (package_id, similar_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_package_similarities_score ON package_similarities(package_id, score DESC);
+
+CREATE TABLE IF NOT EXISTS user_recommendations (
+    user_id INTEGER NOT NULL,
+    package_id INTEGER NOT NULL,
+    score REAL NOT NULL,
+    source_package_id INTEGER,                -- 贡献最大的历史资源，用于生成推荐理由
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (user_id, package_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_recommendations_score ON user_recommendations(user_id, score DESC);
+
+-- ========================================
+-- 记录迁移完成
+-- ========================================
+
+INSERT OR REPLACE INTO system_settings (key, value, description) VALUES
+('migration_014_completed', datetime('now'), '迁移014完成时间'),
+('last_migration', '014_package_recommendations', '最后执行的迁移');
@@ -0,0 +1,85 @@
+-- 迁移脚本: 私信
+-- 版本: 015
+-- 说明: 一对一会话与消息、已读回执、接收设置、拉黑名单与私信举报
+
+-- 会话：每对用户一条，user_a < user_b
+CREATE TABLE IF NOT EXISTS conversations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_a INTEGER NOT NULL,
+    user_b INTEGER NOT NULL,
+    initiator_id INTEGER NOT NULL,            -- 发起会话的用户，用于新账号限频
+    last_message_id INTEGER,
+    last_message_at TEXT,
+    created_at TEXT NOT NULL DEFAULT (CURRENT_TIMESTAMP),
+    UNIQUE (user_a, user_b)
+);
+
+CREATE INDEX IF NOT EXISTS idx_conversations_user_b ON conversations(user_b);
+CREATE INDEX IF NOT EXISTS idx_conversations_initiator ON conversations(initiator_id, created_at);
+
+-- 会话参与者的个人状态：置顶、清空位置（删除会话只影响自己）
+CREATE TABLE IF NOT EXISTS conversation_members (
+    conversation_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    is_pinned INTEGER NOT NULL DEFAULT 0,
+    cleared_before_id INTEGER NOT NULL DEFAULT 0, -- 删除会话时记录的最后一条消息ID，此前的消息对本人不可见
+    PRIMARY KEY (conversation_id, user_id)
+);
+
+CREATE TABLE IF NOT EXISTS messages (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    conversation_id INTEGER NOT NULL,
+    sender_id INTEGER NOT NULL,
+    receiver_id INTEGER NOT NULL,
+    content TEXT NOT NULL,
+    message_type TEXT NOT NULL DEFAULT 'text', -- text / image / file
+    read_at TEXT,                              -- 已读回执
+    sender_deleted INTEGER NOT NULL DEFAULT 0,
+    receiver_deleted INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (CURRENT_TIMESTAMP)
+);
+
+CREATE INDEX IF NOT EXISTS idx_messages_conversation ON messages(conversation_id, id);
+CREATE INDEX IF NOT EXISTS idx_messages_receiver_unread ON messages(receiver_id, read_at);
+CREATE INDEX IF NOT EXISTS idx_messages_sender_time ON messages(sender_id, created_at);
+
+-- 私信接收设置：everyone（所有人）/ following（仅我关注的人）
+CREATE TABLE IF NOT EXISTS message_settings (
+    user_id INTEGER PRIMARY KEY,
+    allow_from TEXT NOT NULL DEFAULT 'everyone',
+    updated_at TEXT NOT NULL DEFAULT (CURRENT_TIMESTAMP)
+);
+
+-- 拉黑名单：任一方拉黑后双方不能互发私信
+CREATE TABLE IF NOT EXISTS user_blocks (
+    blocker_id INTEGER NOT NULL,
+    blocked_id INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (CURRENT_TIMESTAMP),
+    PRIMARY KEY (blocker_id, blocked_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_blocks_blocked ON user_blocks(blocked_id);
+
+-- 私信举报：管理员只能通过举报查看相关会话
+CREATE TABLE IF NOT EXISTS message_reports (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    message_id INTEGER NOT NULL,
+    reporter_id INTEGER NOT NULL,
+    reason TEXT,
+    status TEXT NOT NULL DEFAULT 'pending',   -- pending / resolved / dismissed
+    handled_by INTEGER,
+    handle_note TEXT,
+    handled_at TEXT,
+    created_at TEXT NOT NULL DEFAULT (CURRENT_TIMESTAMP),
+    UNIQUE (message_id, reporter_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_message_reports_status ON message_reports(status, created_at);
+
+-- ========================================
+-- 记录迁移完成
+-- ========================================
+
+INSERT OR REPLACE INTO system_settings (key, value, description) VALUES
+('migration_015_completed', datetime('now'), '迁移015完成时间'),
+('last_migration', '015_direct_messages', '最后执行的迁移');
@@ -0,0 +1,18 @@
+-- 迁移脚本: 用户屏蔽
+-- 版本: 016
+-- 说明: 屏蔽（mute）关系。被屏蔽用户的内容不出现在我的动态、评论与通知中；拉黑（block）沿用 015 的 user_blocks
+
+CREATE TABLE IF NOT EXISTS user_mutes (
+    muter_id INTEGER NOT NULL,
+    muted_id INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (CURRENT_TIMESTAMP),
+    PRIMARY KEY (muter_id, muted_id)
+);
+
+-- ========================================
+-- 记录迁移完成
+-- ========================================
+
+INSERT OR REPLACE INTO system_settings (key, value, description) VALUES
+('migration_016_completed', datetime('now'), '迁移016完成时间'),
+('last_migration', '016_user_mutes', '最后执行的迁移');
@@ -0,0 +1,56 @@
+-- 迁移脚本: 收藏夹
+-- 版本: 017
+-- 说明: 用户自建收藏夹（名称、简介、公开/私密、排序），收藏条目归入收藏夹，关注他人的公开收藏夹
+
+-- 帖子收藏表此前按需创建，这里保证存在（资源收藏表见 014）
+CREATE TABLE IF NOT EXISTS post_favorites (
+    user_id INTEGER NOT NULL,
+    post_id INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (CURRENT_TIMESTAMP),
+    PRIMARY KEY (user_id, post_id)
+);
+
+CREATE TABLE IF NOT EXISTS favorite_collections (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    description TEXT,
+    is_public INTEGER NOT NULL DEFAULT 0,
+    sort_order INTEGER NOT NULL DEFAULT 0,     -- 越小越靠前
+    created_at TEXT NOT NULL DEFAULT (CURRENT_TIMESTAMP),
+    updated_at TEXT NOT NULL DEFAULT (CURRENT_TIMESTAMP),
+    UNIQUE (user_id, name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_favorite_collections_public ON favorite_collections(is_public, updated_at);
+
+-- 收藏条目的归属：每个收藏在同一用户下最多属于一个收藏夹，未归入任何收藏夹即为"默认收藏"
+-- 收藏关系本身仍记录在 package_favorites / post_favorites 中
+CREATE TABLE IF NOT EXISTS favorite_collection_items (
+    user_id INTEGER NOT NULL,
+    target_type TEXT NOT NULL,                 -- Package / Post
+    target_id INTEGER NOT NULL,
+    collection_id INTEGER NOT NULL,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (CURRENT_TIMESTAMP),
+    PRIMARY KEY (user_id, target_type, target_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_favorite_collection_items_collection ON favorite_collection_items(collection_id, sort_order);
+
+CREATE TABLE IF NOT EXISTS favorite_collection_follows (
+    user_id INTEGER NOT NULL,
+    collection_id INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (CURRENT_TIMESTAMP),
+    PRIMARY KEY (user_id, collection_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_favorite_collection_follows_collection ON favorite_collection_follows(collection_id);
+
+-- ========================================
+-- 记录迁移完成
+-- ========================================
+
+INSERT OR REPLACE INTO system_settings (key, value, description) VALUES
+('migration_017_completed', datetime('now'), '迁移017完成时间'),
+('last_migration', '017_favorite_collections', '最后执行的迁移');
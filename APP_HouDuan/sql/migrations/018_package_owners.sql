@@ -0,0 +1,52 @@
+-- 迁移脚本: 资源所有者与协作维护者
+-- 版本: 018
+-- 说明: packages.owner_id 关联用户账号（按现有作者名回填），协作维护者表与需对方接受的所有权转让
+
+ALTER TABLE packages ADD COLUMN owner_id INTEGER REFERENCES users(id);
+
+-- 先按用户名精确匹配，再按唯一的昵称匹配；仍未匹配的资源保持 owner_id 为空，只能由管理员处理
+UPDATE packages SET owner_id = (SELECT u.id FROM users u WHERE u.username = packages.author)
+WHERE owner_id IS NULL;
+
+UPDATE packages SET owner_id = (SELECT u.id FROM users u WHERE u.nickname = packages.author)
+WHERE owner_id IS NULL
+  AND (SELECT COUNT(*) FROM users u WHERE u.nickname = packages.author) = 1;
+
+CREATE INDEX IF NOT EXISTS idx_packages_owner ON packages(owner_id);
+
+-- 资源维护者：owner（所有者，每个资源一个）/ maintainer（协作维护者，可编辑与上传，不能删除或转让）
+CREATE TABLE IF NOT EXISTS package_maintainers (
+    package_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    role TEXT NOT NULL DEFAULT 'maintainer',
+    added_by INTEGER,
+    created_at TEXT NOT NULL DEFAULT (CURRENT_TIMESTAMP),
+    PRIMARY KEY (package_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_package_maintainers_user ON package_maintainers(user_id);
+
+INSERT OR IGNORE INTO package_maintainers (package_id, user_id, role)
+SELECT id, owner_id, 'owner' FROM packages WHERE owner_id IS NOT NULL;
+
+-- 所有权转让：对方接受后生效，原所有者转为维护者
+CREATE TABLE IF NOT EXISTS package_ownership_transfers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    package_id INTEGER NOT NULL,
+    from_user_id INTEGER NOT NULL,
+    to_user_id INTEGER NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',   -- pending / accepted / declined / cancelled
+    created_at TEXT NOT NULL DEFAULT (CURRENT_TIMESTAMP),
+    responded_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_package_transfers_to ON package_ownership_transfers(to_user_id, status);
+CREATE INDEX IF NOT EXISTS idx_package_transfers_package ON package_ownership_transfers(package_id, status);
+
+-- ========================================
+-- 记录迁移完成
+-- ========================================
+
+INSERT OR REPLACE INTO system_settings (key, value, description) VALUES
+('migration_018_completed', datetime('now'), '迁移018完成时间'),
+('last_migration', '018_package_owners', '最后执行的迁移');
@@ -0,0 +1,38 @@
+-- 迁移脚本: 草稿、自动保存与定时发布
+-- 版本: 019
+-- 说明: 帖子与资源的草稿（内容为发布请求 JSON），自动保存的历史版本，定时发布时间由后台任务处理
+
+CREATE TABLE IF NOT EXISTS content_drafts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    target_type TEXT NOT NULL,                -- Post / Package
+    title TEXT NOT NULL DEFAULT '',
+    payload TEXT NOT NULL DEFAULT '{}',       -- 与 /publish 接口相同的请求体
+    status TEXT NOT NULL DEFAULT 'draft',     -- draft / scheduled / publishing / failed
+    publish_at TEXT,                          -- 定时发布时间（UTC），为空表示手动发布
+    last_error TEXT,                          -- 定时发布失败原因
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_content_drafts_user ON content_drafts(user_id, updated_at);
+CREATE INDEX IF NOT EXISTS idx_content_drafts_due ON content_drafts(status, publish_at);
+
+-- 自动保存的历史版本：间隔一段时间才记录一次，每个草稿只保留最近若干个
+CREATE TABLE IF NOT EXISTS content_draft_revisions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    draft_id INTEGER NOT NULL,
+    title TEXT NOT NULL DEFAULT '',
+    payload TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_content_draft_revisions_draft ON content_draft_revisions(draft_id, id);
+
+-- ========================================
+-- 记录迁移完成
+-- ========================================
+
+INSERT OR REPLACE INTO system_settings (key, value, description) VALUES
+('migration_019_completed', datetime('now'), '迁移019完成时间'),
+('last_migration', '019_content_drafts', '最后执行的迁移');
@@ -0,0 +1,23 @@
+-- 迁移脚本: Markdown 渲染缓存
+-- 版本: 020
+-- 说明: 帖子正文、代码片段与资源描述渲染后的净化 HTML 与纯文本摘要，按源内容哈希与渲染规则版本判断是否过期
+
+CREATE TABLE IF NOT EXISTS rendered_content (
+    target_type TEXT NOT NULL,                -- Post / Package
+    target_id INTEGER NOT NULL,
+    field TEXT NOT NULL,                      -- content / code_snippet / description
+    source_hash TEXT NOT NULL,                -- 源内容哈希，源内容变化后缓存自动失效
+    renderer_version INTEGER NOT NULL,        -- 渲染规则版本
+    html TEXT NOT NULL,
+    excerpt TEXT NOT NULL DEFAULT '',
+    rendered_at TEXT NOT NULL,
+    PRIMARY KEY (target_type, target_id, field)
+);
+
+-- ========================================
+-- 记录迁移完成
+-- ========================================
+
+INSERT OR REPLACE INTO system_settings (key, value, description) VALUES
+('migration_020_completed', datetime('now'), '迁移020完成时间'),
+('last_migration', '020_rendered_content', '最后执行的迁移');
@@ -0,0 +1,24 @@
+-- 迁移脚本: 编辑历史
+-- 版本: 021
+-- 说明: 帖子、资源与评论每次编辑后的受追踪字段快照，首次编辑时先补记原始版本；用于差异对比、已编辑标记与管理员回滚
+
+CREATE TABLE IF NOT EXISTS content_revisions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    target_type TEXT NOT NULL,                -- Post / Package / Comment
+    target_id INTEGER NOT NULL,
+    revision_no INTEGER NOT NULL,             -- 同一内容内从 1 递增
+    editor_id INTEGER,                        -- 为空表示原始版本
+    snapshot TEXT NOT NULL,                   -- 受追踪字段的完整快照 JSON
+    changed_fields TEXT NOT NULL DEFAULT '[]',
+    note TEXT,                                -- 回滚等操作说明
+    created_at TEXT NOT NULL,
+    UNIQUE (target_type, target_id, revision_no)
+);
+
+-- ========================================
+-- 记录迁移完成
+-- ========================================
+
+INSERT OR REPLACE INTO system_settings (key, value, description) VALUES
+('migration_021_completed', datetime('now'), '迁移021完成时间'),
+('last_migration', '021_edit_history', '最后执行的迁移');
@@ -0,0 +1,30 @@
+-- 迁移脚本: 资源依赖与兼容性
+-- 版本: 022
+-- 说明: 资源之间带 semver 版本范围的结构化依赖，以及资源支持的结绳版本范围，供一键安装解析完整安装集合
+
+CREATE TABLE IF NOT EXISTS package_dependencies (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    package_id INTEGER NOT NULL,              -- 声明依赖的资源
+    depends_on_id INTEGER NOT NULL,           -- 被依赖的资源
+    version_req TEXT NOT NULL DEFAULT '*',    -- semver 版本范围，如 ^1.2、>=1.0, <2.0
+    optional INTEGER NOT NULL DEFAULT 0,      -- 可选依赖默认不进入安装集合
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(package_id, depends_on_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_package_dependencies_target ON package_dependencies(depends_on_id);
+
+CREATE TABLE IF NOT EXISTS package_compatibility (
+    package_id INTEGER PRIMARY KEY,
+    min_app_version TEXT,                     -- 支持的最低结绳版本（含）
+    max_app_version TEXT,                     -- 支持的最高结绳版本（含）
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- ========================================
+-- 记录迁移完成
+-- ========================================
+
+INSERT OR REPLACE INTO system_settings (key, value, description) VALUES
+('migration_022_completed', datetime('now'), '迁移022完成时间'),
+('last_migration', '022_package_dependencies', '最后执行的迁移');
@@ -0,0 +1,27 @@
+-- 迁移脚本: 分类层级与分类规则
+-- 版本: 023
+-- 说明: 分类支持父子层级、排序与图标；每个分类可单独设置发布规则，未设置的规则沿父分类继承，
+--       整条链都未设置时使用全局配置
+
+ALTER TABLE categories ADD COLUMN parent_id INTEGER REFERENCES categories(id);
+ALTER TABLE categories ADD COLUMN sort_order INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE categories ADD COLUMN icon TEXT;
+
+-- 发布规则（NULL 表示继承）
+ALTER TABLE categories ADD COLUMN requires_review INTEGER;        -- 资源是否需要审核
+ALTER TABLE categories ADD COLUMN allowed_extensions TEXT;        -- JSON 数组，如 ["tsp","zip"]
+ALTER TABLE categories ADD COLUMN max_file_size INTEGER;          -- 字节
+ALTER TABLE categories ADD COLUMN post_permission TEXT;           -- all / elder / admin
+
+-- 现有分类保持原来的显示顺序
+UPDATE categories SET sort_order = id WHERE sort_order = 0;
+
+CREATE INDEX IF NOT EXISTS idx_categories_parent ON categories(parent_id, sort_order);
+
+-- ========================================
+-- 记录迁移完成
+-- ========================================
+
+INSERT OR REPLACE INTO system_settings (key, value, description) VALUES
+('migration_023_completed', datetime('now'), '迁移023完成时间'),
+('last_migration', '023_category_hierarchy', '最后执行的迁移');
@@ -0,0 +1,146 @@
+-- 迁移脚本: 标签治理
+-- 版本: 024
+-- 说明: 标签按规范化后的匹配键去重，支持别名、合并与审核状态；可开启白名单模式只允许使用已审核的标签。
+--       use_count 改由帖子与资源的标签关联表触发器维护
+
+-- 新库中还没有标签表，按当前结构创建
+CREATE TABLE IF NOT EXISTS tags (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name VARCHAR(50) UNIQUE NOT NULL,
+    description TEXT,
+    color VARCHAR(20),
+    use_count INTEGER DEFAULT 0,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS post_tags (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    post_id INTEGER NOT NULL,
+    tag_id INTEGER NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (post_id) REFERENCES posts(id) ON DELETE CASCADE,
+    FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE,
+    UNIQUE(post_id, tag_id)
+);
+
+CREATE TABLE IF NOT EXISTS package_tags (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    package_id INTEGER NOT NULL,
+    tag_id INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    FOREIGN KEY (package_id) REFERENCES packages(id) ON DELETE CASCADE,
+    FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+);
+
+-- 帖子的标签名 JSON 数组，与 post_tags 同步
+ALTER TABLE posts ADD COLUMN tags TEXT DEFAULT NULL;
+
+-- 匹配键：规范化名称的小写形式，"UI" 与 "ui" 视为同一标签
+ALTER TABLE tags ADD COLUMN slug TEXT;
+-- 审核状态：approved 已审核 / pending 待审核（发帖时自动创建）/ banned 已禁用
+ALTER TABLE tags ADD COLUMN status TEXT NOT NULL DEFAULT 'approved';
+
+-- 标签别名，如 "UI" -> "界面"；输入别名时自动换成目标标签
+CREATE TABLE IF NOT EXISTS tag_aliases (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    alias TEXT NOT NULL,
+    alias_key TEXT NOT NULL UNIQUE,
+    tag_id INTEGER NOT NULL,
+    created_by INTEGER,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_tag_aliases_tag ON tag_aliases(tag_id);
+
+UPDATE tags SET slug = lower(trim(name)) WHERE slug IS NULL;
+
+-- ========================================
+-- 合并只有大小写不同的重复标签：保留 id 最小的一个，关联与别名改指向保留的标签
+-- ========================================
+
+CREATE TEMP TABLE tag_merge AS
+SELECT t.id AS old_id, k.keep_id
+FROM tags t
+JOIN (SELECT slug, MIN(id) AS keep_id FROM tags GROUP BY slug HAVING COUNT(*) > 1) k ON t.slug = k.slug
+WHERE t.id != k.keep_id;
+
+INSERT OR IGNORE INTO post_tags (post_id, tag_id, created_at)
+SELECT pt.post_id, m.keep_id, pt.created_at
+FROM post_tags pt JOIN tag_merge m ON pt.tag_id = m.old_id;
+DELETE FROM post_tags WHERE tag_id IN (SELECT old_id FROM tag_merge);
+
+INSERT INTO package_tags (package_id, tag_id, created_at)
+SELECT DISTINCT pt.package_id, m.keep_id, pt.created_at
+FROM package_tags pt JOIN tag_merge m ON pt.tag_id = m.old_id
+WHERE NOT EXISTS (
+    SELECT 1 FROM package_tags x WHERE x.package_id = pt.package_id AND x.tag_id = m.keep_id
+);
+DELETE FROM package_tags WHERE tag_id IN (SELECT old_id FROM tag_merge);
+
+UPDATE tag_aliases SET tag_id = (SELECT keep_id FROM tag_merge WHERE old_id = tag_aliases.tag_id)
+WHERE tag_id IN (SELECT old_id FROM tag_merge);
+
+-- 帖子的 tags 字段是标签名的 JSON 数组，按合并后的关联重写
+UPDATE posts SET tags = (
+    SELECT json_group_array(name) FROM (
+        SELECT t.name FROM post_tags pt JOIN tags t ON t.id = pt.tag_id
+        WHERE pt.post_id = posts.id ORDER BY pt.id
+    )
+)
+WHERE id IN (SELECT pt.post_id FROM post_tags pt JOIN tag_merge m ON pt.tag_id = m.keep_id);
+
+DELETE FROM tags WHERE id IN (SELECT old_id FROM tag_merge);
+DROP TABLE tag_merge;
+
+DROP INDEX IF EXISTS idx_tags_slug;
+CREATE UNIQUE INDEX idx_tags_slug ON tags(slug);
+CREATE INDEX IF NOT EXISTS idx_tags_status ON tags(status);
+
+-- ========================================
+-- 使用次数由触发器在关联变更的同一事务中维护
+-- ========================================
+
+DROP TRIGGER IF EXISTS update_tag_use_count_insert;
+DROP TRIGGER IF EXISTS update_tag_use_count_delete;
+
+CREATE TRIGGER IF NOT EXISTS tag_use_count_post_insert
+AFTER INSERT ON post_tags
+BEGIN
+    UPDATE tags SET use_count = use_count + 1 WHERE id = NEW.tag_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS tag_use_count_post_delete
+AFTER DELETE ON post_tags
+BEGIN
+    UPDATE tags SET use_count = MAX(use_count - 1, 0) WHERE id = OLD.tag_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS tag_use_count_package_insert
+AFTER INSERT ON package_tags
+BEGIN
+    UPDATE tags SET use_count = use_count + 1 WHERE id = NEW.tag_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS tag_use_count_package_delete
+AFTER DELETE ON package_tags
+BEGIN
+    UPDATE tags SET use_count = MAX(use_count - 1, 0) WHERE id = OLD.tag_id;
+END;
+
+-- 按现有关联重新统计一次
+UPDATE tags SET use_count =
+    (SELECT COUNT(*) FROM post_tags WHERE tag_id = tags.id) +
+    (SELECT COUNT(*) FROM package_tags WHERE tag_id = tags.id);
+
+INSERT OR IGNORE INTO system_settings (key, value, description) VALUES
+('tag_whitelist_mode', 'false', '标签白名单模式：开启后只能使用已审核的标签');
+
+-- ========================================
+-- 记录迁移完成
+-- ========================================
+
+INSERT OR REPLACE INTO system_settings (key, value, description) VALUES
+('migration_024_completed', datetime('now'), '迁移024完成时间'),
+('last_migration', '024_tag_governance', '最后执行的迁移');
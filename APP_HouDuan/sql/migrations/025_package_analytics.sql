@@ -0,0 +1,52 @@
+-- 迁移脚本: 资源数据分析
+-- 版本: 025
+-- 说明: 浏览记录保存来源，下载记录保存下载时的资源版本，供作者查看来源分布与版本采用情况
+
+-- 新库中还没有这两张表，按原有结构创建
+CREATE TABLE IF NOT EXISTS package_views (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    package_id INTEGER NOT NULL,
+    user_id INTEGER, -- 可为空，支持访客访问
+    ip_address TEXT,
+    user_agent TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (package_id) REFERENCES packages(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_package_views_package_id ON package_views(package_id);
+CREATE INDEX IF NOT EXISTS idx_package_views_user_id ON package_views(user_id);
+CREATE INDEX IF NOT EXISTS idx_package_views_ip ON package_views(ip_address);
+CREATE INDEX IF NOT EXISTS idx_package_views_created_at ON package_views(created_at);
+
+-- 浏览记录写入时累加资源的浏览量
+ALTER TABLE packages ADD COLUMN view_count INTEGER DEFAULT 0;
+CREATE TRIGGER IF NOT EXISTS update_package_view_count
+AFTER INSERT ON package_views
+BEGIN
+    UPDATE packages SET view_count = view_count + 1 WHERE id = NEW.package_id;
+END;
+
+CREATE TABLE IF NOT EXISTS download_records (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER,
+    package_id INTEGER NOT NULL,
+    ip_address TEXT NOT NULL,
+    user_agent TEXT,
+    download_time TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+ALTER TABLE package_views ADD COLUMN referrer TEXT;     -- 前端传入的来源（search / feed 等）或 Referer 的域名
+ALTER TABLE download_records ADD COLUMN version TEXT;   -- 下载时资源的版本号
+
+CREATE INDEX IF NOT EXISTS idx_package_views_package_time ON package_views(package_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_download_records_package_time ON download_records(package_id, download_time);
+
+-- ========================================
+-- 记录迁移完成
+-- ========================================
+
+INSERT OR REPLACE INTO system_settings (key, value, description) VALUES
+('migration_025_completed', datetime('now'), '迁移025完成时间'),
+('last_migration', '025_package_analytics', '最后执行的迁移');
@@ -0,0 +1,60 @@
+-- 迁移脚本: 全站数据日汇总
+-- 版本: 026
+-- 说明: 后台任务每天汇总前一天（北京时间）的全站指标，管理后台按日期范围读取汇总表，不再实时扫描明细表；
+--       活跃用户按天落表，用于计算 DAU/WAU/MAU 与按注册周划分的留存
+
+-- 启动记录此前按需创建，这里保证存在
+CREATE TABLE IF NOT EXISTS app_launches (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER,
+    device_id TEXT,
+    app_version TEXT,
+    platform TEXT,
+    created_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS site_daily_metrics (
+    date TEXT PRIMARY KEY,                          -- 北京时间日期 YYYY-MM-DD
+    new_users INTEGER NOT NULL DEFAULT 0,
+    dau INTEGER NOT NULL DEFAULT 0,
+    wau INTEGER NOT NULL DEFAULT 0,                 -- 截至当天的最近 7 天
+    mau INTEGER NOT NULL DEFAULT 0,                 -- 截至当天的最近 30 天
+    app_launches INTEGER NOT NULL DEFAULT 0,
+    uploads INTEGER NOT NULL DEFAULT 0,             -- 新增资源
+    downloads INTEGER NOT NULL DEFAULT 0,
+    unique_downloaders INTEGER NOT NULL DEFAULT 0,
+    new_posts INTEGER NOT NULL DEFAULT 0,
+    new_comments INTEGER NOT NULL DEFAULT 0,
+    total_users INTEGER NOT NULL DEFAULT 0,         -- 当天结束时的累计数
+    total_packages INTEGER NOT NULL DEFAULT 0,
+    total_posts INTEGER NOT NULL DEFAULT 0,
+    total_comments INTEGER NOT NULL DEFAULT 0,
+    computed_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- 每天的活跃用户：启动 App、浏览、下载、点赞、签到、发帖或评论的登录用户
+CREATE TABLE IF NOT EXISTS site_active_users (
+    date TEXT NOT NULL,
+    user_id INTEGER NOT NULL,
+    PRIMARY KEY (date, user_id)
+) WITHOUT ROWID;
+
+CREATE INDEX IF NOT EXISTS idx_site_active_users_user ON site_active_users(user_id, date);
+
+-- 注册周留存：cohort_week 为注册周的周一，week_offset 为注册后第几周（0 为注册当周）
+CREATE TABLE IF NOT EXISTS signup_cohort_retention (
+    cohort_week TEXT NOT NULL,
+    week_offset INTEGER NOT NULL,
+    cohort_size INTEGER NOT NULL DEFAULT 0,
+    retained INTEGER NOT NULL DEFAULT 0,
+    computed_at TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (cohort_week, week_offset)
+);
+
+-- ========================================
+-- 记录迁移完成
+-- ========================================
+
+INSERT OR REPLACE INTO system_settings (key, value, description) VALUES
+('migration_026_completed', datetime('now'), '迁移026完成时间'),
+('last_migration', '026_site_metrics', '最后执行的迁移');
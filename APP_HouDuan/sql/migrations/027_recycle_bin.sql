@@ -0,0 +1,68 @@
+-- 迁移脚本: 回收站
+-- 版本: 027
+-- 说明: 资源、帖子、评论、用户统一改为软删除，记录删除时间与操作人；资源、帖子、评论保留删除前的状态以便恢复。
+--       回收站中的内容超过保留天数后由后台任务彻底删除
+
+ALTER TABLE packages ADD COLUMN deleted_at TEXT;
+ALTER TABLE packages ADD COLUMN deleted_by INTEGER;
+ALTER TABLE packages ADD COLUMN status_before_delete TEXT;
+
+ALTER TABLE posts ADD COLUMN deleted_at TEXT;
+ALTER TABLE posts ADD COLUMN deleted_by INTEGER;
+ALTER TABLE posts ADD COLUMN status_before_delete TEXT;
+
+ALTER TABLE comments ADD COLUMN deleted_at TEXT;
+ALTER TABLE comments ADD COLUMN deleted_by INTEGER;
+ALTER TABLE comments ADD COLUMN status_before_delete TEXT;
+
+ALTER TABLE users ADD COLUMN deleted_at TEXT;
+ALTER TABLE users ADD COLUMN deleted_by INTEGER;
+
+-- 此前通过修改状态删除的内容以最后更新时间作为删除时间
+UPDATE packages SET deleted_at = COALESCE(updated_at, datetime('now')) WHERE status = 'deleted' AND deleted_at IS NULL;
+UPDATE posts SET deleted_at = COALESCE(updated_at, datetime('now')) WHERE status = 'Deleted' AND deleted_at IS NULL;
+UPDATE comments SET deleted_at = COALESCE(updated_at, datetime('now')) WHERE status = 'Deleted' AND deleted_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_packages_deleted_at ON packages(deleted_at);
+CREATE INDEX IF NOT EXISTS idx_posts_deleted_at ON posts(deleted_at);
+CREATE INDEX IF NOT EXISTS idx_comments_deleted_at ON comments(deleted_at);
+CREATE INDEX IF NOT EXISTS idx_users_deleted_at ON users(deleted_at);
+
+-- 帖子评论数不包含回收站中的评论：软删除与恢复时同步增减，彻底删除已在回收站中的评论时不再重复扣减
+DROP TRIGGER IF EXISTS update_post_comment_count_delete;
+
+CREATE TRIGGER IF NOT EXISTS update_post_comment_count_delete
+AFTER DELETE ON comments
+WHEN OLD.target_type = 'Post' AND OLD.status != 'Deleted'
+BEGIN
+    UPDATE posts SET comment_count = MAX(comment_count - 1, 0) WHERE id = OLD.target_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS update_post_comment_count_soft_delete
+AFTER UPDATE OF status ON comments
+WHEN NEW.target_type = 'Post' AND OLD.status != 'Deleted' AND NEW.status = 'Deleted'
+BEGIN
+    UPDATE posts SET comment_count = MAX(comment_count - 1, 0) WHERE id = NEW.target_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS update_post_comment_count_restore
+AFTER UPDATE OF status ON comments
+WHEN NEW.target_type = 'Post' AND OLD.status = 'Deleted' AND NEW.status != 'Deleted'
+BEGIN
+    UPDATE posts SET comment_count = comment_count + 1 WHERE id = NEW.target_id;
+END;
+
+-- 按现有评论重新统计一次
+UPDATE posts SET comment_count =
+    (SELECT COUNT(*) FROM comments WHERE target_type = 'Post' AND target_id = posts.id AND status != 'Deleted');
+
+INSERT OR IGNORE INTO system_settings (key, value, description) VALUES
+('recycle_bin_retention_days', '30', '回收站保留天数：超过后彻底删除');
+
+-- ========================================
+-- 记录迁移完成
+-- ========================================
+
+INSERT OR REPLACE INTO system_settings (key, value, description) VALUES
+('migration_027_completed', datetime('now'), '迁移027完成时间'),
+('last_migration', '027_recycle_bin', '最后执行的迁移');
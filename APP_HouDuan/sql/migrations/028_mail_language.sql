@@ -0,0 +1,13 @@
+-- 迁移脚本: 收件人邮件语言
+-- 版本: 028
+-- 说明: 摘要设置新增 mail_language，通知、摘要与周报邮件按收件人选择的语言渲染模板（为空时使用默认语言）
+
+ALTER TABLE notification_digest_settings ADD COLUMN mail_language TEXT;
+
+-- ========================================
+-- 记录迁移完成
+-- ========================================
+
+INSERT OR REPLACE INTO system_settings (key, value, description) VALUES
+('migration_028_completed', datetime('now'), '迁移028完成时间'),
+('last_migration', '028_mail_language', '最后执行的迁移');
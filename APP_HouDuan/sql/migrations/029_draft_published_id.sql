@@ -0,0 +1,13 @@
+-- 迁移脚本: 草稿发布结果
+-- 版本: 029
+-- 说明: 发布草稿时先记录创建出的帖子/资源ID，服务中断后据此判断草稿是否已发布，避免重复发布
+
+ALTER TABLE content_drafts ADD COLUMN published_id INTEGER;
+
+-- ========================================
+-- 记录迁移完成
+-- ========================================
+
+INSERT OR REPLACE INTO system_settings (key, value, description) VALUES
+('migration_029_completed', datetime('now'), '迁移029完成时间'),
+('last_migration', '029_draft_published_id', '最后执行的迁移');
@@ -0,0 +1,65 @@
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use serde_json::json;
+
+use crate::models::achievement::{CreateAchievementRequest, UpdateAchievementRequest};
+use crate::require_admin;
+use crate::services::achievement_service::AchievementService;
+use crate::utils::error::ServiceError;
+
+// 路由注册在 /users、/me 与 /admin 作用域内（见 user.rs、admin.rs）
+
+
+/// 用户公开主页的成就墙：已获得的带获得时间，未获得的带进度
+pub async fn get_user_achievements(
+    path: web::Path<i32>,
+    achievement_service: web::Data<AchievementService>,
+) -> HttpResponse {
+    match achievement_service.list_user_achievements(path.into_inner()).await {
+        Ok(list) => {
+            let earned = list.iter().filter(|a| a.earned).count();
+            HttpResponse::Ok().json(json!({
+                "code": 0,
+                "message": "success",
+                "data": {"list": list, "total": list.len(), "earned": earned}
+            }))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(json!({"code": 500, "message": e.to_string()})),
+    }
+}
+
+/// 管理员：全部成就定义（含已停用）
+pub async fn admin_list_achievements(
+    req: HttpRequest,
+    achievement_service: web::Data<AchievementService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let _ = require_admin!(&req);
+    match achievement_service.list_definitions().await {
+        Ok(list) => Ok(HttpResponse::Ok().json(json!({"code": 0, "message": "success", "data": list}))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(json!({"code": 500, "message": e.to_string()}))),
+    }
+}
+
+pub async fn admin_create_achievement(
+    req: HttpRequest,
+    body: web::Json<CreateAchievementRequest>,
+    achievement_service: web::Data<AchievementService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let _ = require_admin!(&req);
+    match achievement_service.create_definition(body.into_inner()).await {
+        Ok(def) => Ok(HttpResponse::Ok().json(json!({"code": 0, "message": "创建成功", "data": def}))),
+        Err(e) => Ok(ServiceError::from(e).error_response()),
+    }
+}
+
+pub async fn admin_update_achievement(
+    req: HttpRequest,
+    path: web::Path<i32>,
+    body: web::Json<UpdateAchievementRequest>,
+    achievement_service: web::Data<AchievementService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let _ = require_admin!(&req);
+    match achievement_service.update_definition(path.into_inner(), body.into_inner()).await {
+        Ok(def) => Ok(HttpResponse::Ok().json(json!({"code": 0, "message": "更新成功", "data": def}))),
+        Err(e) => Ok(ServiceError::from(e).error_response()),
+    }
+}
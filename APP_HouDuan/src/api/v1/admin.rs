@@ -12,6 +12,10 @@ use crate::middleware::auth::AuthenticatedUser;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 // MailConfig已移至models/mail.rs
+use crate::models::mail::{
+    MailType, MailTemplateQuery, CreateMailTemplateRequest, UpdateMailTemplateRequest,
+    PreviewMailTemplateRequest, DEFAULT_MAIL_LANGUAGE,
+};
 use crate::services::notification_service::NotificationService;
 use crate::services::user_service::UserService;
 
@@ -79,6 +83,41 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
                 web::resource("/app/dau-stats")
                     .route(web::get().to(get_dau_stats))
             )
+            // 全站数据日汇总与留存
+            .service(
+                web::resource("/metrics")
+                    .route(web::get().to(crate::api::v1::site_metrics::admin_get_metrics))
+            )
+            .service(
+                web::resource("/metrics/retention")
+                    .route(web::get().to(crate::api::v1::site_metrics::admin_get_retention))
+            )
+            .service(
+                web::resource("/metrics/rollup")
+                    .route(web::post().to(crate::api::v1::site_metrics::admin_rollup_metrics))
+            )
+            // 回收站：列表、恢复、彻底删除与保留天数
+            .service(
+                web::resource("/recycle-bin")
+                    .route(web::get().to(crate::api::v1::recycle_bin::admin_list_recycle_bin))
+            )
+            .service(
+                web::resource("/recycle-bin/settings")
+                    .route(web::get().to(crate::api::v1::recycle_bin::admin_get_recycle_settings))
+                    .route(web::put().to(crate::api::v1::recycle_bin::admin_update_recycle_settings))
+            )
+            .service(
+                web::resource("/recycle-bin/purge-expired")
+                    .route(web::post().to(crate::api::v1::recycle_bin::admin_purge_expired))
+            )
+            .service(
+                web::resource("/recycle-bin/{type}/{id}/restore")
+                    .route(web::post().to(crate::api::v1::recycle_bin::admin_restore_recycled))
+            )
+            .service(
+                web::resource("/recycle-bin/{type}/{id}")
+                    .route(web::delete().to(crate::api::v1::recycle_bin::admin_purge_recycled))
+            )
             .service(
                 web::resource("/user-registration-trend")
                     .route(web::get().to(get_user_registration_trend))
@@ -202,6 +241,73 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
                 web::resource("/test-email")
                     .route(web::post().to(send_test_email))
             )
+            // 邮件模板管理
+            .service(
+                web::resource("/mail-templates")
+                    .route(web::get().to(list_mail_templates))
+                    .route(web::post().to(create_mail_template))
+            )
+            .service(
+                web::resource("/mail-templates/types")
+                    .route(web::get().to(get_mail_template_types))
+            )
+            .service(
+                web::resource("/mail-templates/preview")
+                    .route(web::post().to(preview_mail_template))
+            )
+            .service(
+                web::resource("/mail-templates/{id}")
+                    .route(web::get().to(get_mail_template))
+                    .route(web::put().to(update_mail_template))
+                    .route(web::delete().to(delete_mail_template))
+            )
+            // 资源评价审核
+            .service(
+                web::resource("/reviews")
+                    .route(web::get().to(crate::api::v1::rating::admin_list_reviews))
+            )
+            .service(
+                web::resource("/reviews/{id}/status")
+                    .route(web::put().to(crate::api::v1::rating::admin_moderate_review))
+            )
+            // 成就定义
+            .service(
+                web::resource("/achievements")
+                    .route(web::get().to(crate::api::v1::achievement::admin_list_achievements))
+                    .route(web::post().to(crate::api::v1::achievement::admin_create_achievement))
+            )
+            .service(
+                web::resource("/achievements/{id}")
+                    .route(web::put().to(crate::api::v1::achievement::admin_update_achievement))
+            )
+            // 积分规则、等级表与手动调整
+            .service(
+                web::resource("/point-rules")
+                    .route(web::get().to(crate::api::v1::points::admin_list_rules))
+            )
+            .service(
+                web::resource("/point-rules/{action}")
+                    .route(web::put().to(crate::api::v1::points::admin_update_rule))
+            )
+            .service(
+                web::resource("/levels")
+                    .route(web::get().to(crate::api::v1::points::admin_list_levels))
+                    .route(web::put().to(crate::api::v1::points::admin_update_levels))
+            )
+            .service(
+                web::resource("/users/{id}/points")
+                    .route(web::post().to(crate::api::v1::points::admin_adjust_points))
+            )
+            // 私信举报
+            .service(
+                web::resource("/message-reports")
+                    .route(web::get().to(crate::api::v1::message::admin_list_reports))
+            )
+            .service(
+                web::resource("/message-reports/{id}")
+                    .route(web::get().to(crate::api::v1::message::admin_get_report))
+                    .route(web::put().to(crate::api::v1::message::admin_handle_report))
+            )
             .service(
                 web::resource("/community-settings")
                     .route(web::get().to(get_community_settings))
@@ -297,16 +403,20 @@ async fn broadcast_notifications(
 
     let mut success = 0i32;
     let mut failed = 0i32;
+    let template = crate::models::notification::Notification {
+        id: 0,
+        user_id: 0,
+        title: body.title.clone(),
+        content: body.content.clone(),
+        link: body.link.clone(),
+        notif_type: body.notif_type.clone(),
+        related_type: body.related_type.clone(),
+        related_id: body.related_id,
+        is_read: false,
+        created_at: chrono::Utc::now(),
+    };
     for uid in user_ids {
-        match notify.notify(
-            uid,
-            &body.title,
-            &body.content,
-            body.link.as_deref(),
-            body.notif_type.as_deref(),
-            body.related_type.as_deref(),
-            body.related_id,
-        ).await {
+        match notify.broadcast(uid, &template).await {
             Ok(_) => success += 1,
             Err(_) => failed += 1,
         }
@@ -770,13 +880,22 @@ async fn get_announcements(
 async fn create_announcement(
     req: web::Json<serde_json::Value>,
     admin_service: web::Data<AdminService>,
+    notify: web::Data<NotificationService>,
 ) -> Result<HttpResponse, actix_web::Error> {
     match admin_service.create_announcement(&req).await {
-        Ok(announcement) => Ok(HttpResponse::Ok().json(json!({
-            "code": 0,
-            "message": "公告创建成功",
-            "data": announcement
-        }))),
+        Ok(announcement) => {
+            // 实时推送给在线用户
+            if announcement.enabled {
+                if let Ok(value) = serde_json::to_value(&announcement) {
+                    notify.announce(value).await;
+                }
+            }
+            Ok(HttpResponse::Ok().json(json!({
+                "code": 0,
+                "message": "公告创建成功",
+                "data": announcement
+            })))
+        },
         Err(e) => Ok(HttpResponse::BadRequest().json(json!({
             "code": 400,
             "message": e.to_string()
@@ -1276,6 +1395,147 @@ async fn send_test_email(
     }
 }
 
+// 邮件模板列表
+async fn list_mail_templates(
+    req: HttpRequest,
+    query: web::Query<MailTemplateQuery>,
+    email_service: web::Data<Arc<RwLock<EmailService>>>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let _ = require_admin!(&req);
+    let es = email_service.read().await;
+    match es.list_templates(query.template_type.as_deref(), query.language.as_deref()).await {
+        Ok(list) => Ok(HttpResponse::Ok().json(json!({
+            "code": 0,
+            "message": "success",
+            "data": { "list": list }
+        }))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(json!({
+            "code": 500,
+            "message": format!("获取邮件模板失败: {}", e)
+        })))
+    }
+}
+
+// 模板类型及其必需变量、示例变量
+async fn get_mail_template_types(req: HttpRequest) -> Result<HttpResponse, actix_web::Error> {
+    let _ = require_admin!(&req);
+    let types: Vec<Value> = MailType::all().into_iter().map(|t| json!({
+        "template_type": t.to_string(),
+        "required_variables": t.required_variables(),
+        "sample_variables": t.sample_variables(),
+    })).collect();
+    Ok(HttpResponse::Ok().json(json!({
+        "code": 0,
+        "message": "success",
+        "data": { "list": types, "default_language": DEFAULT_MAIL_LANGUAGE }
+    })))
+}
+
+async fn get_mail_template(
+    req: HttpRequest,
+    path: web::Path<i32>,
+    email_service: web::Data<Arc<RwLock<EmailService>>>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let _ = require_admin!(&req);
+    let es = email_service.read().await;
+    match es.get_template(path.into_inner()).await {
+        Ok(Some(template)) => Ok(HttpResponse::Ok().json(json!({
+            "code": 0,
+            "message": "success",
+            "data": template
+        }))),
+        Ok(None) => Ok(HttpResponse::NotFound().json(json!({
+            "code": 404,
+            "message": "模板不存在"
+        }))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(json!({
+            "code": 500,
+            "message": format!("获取邮件模板失败: {}", e)
+        })))
+    }
+}
+
+async fn create_mail_template(
+    req: HttpRequest,
+    body: web::Json<CreateMailTemplateRequest>,
+    email_service: web::Data<Arc<RwLock<EmailService>>>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let _ = require_admin!(&req);
+    let es = email_service.read().await;
+    match es.create_template(&body).await {
+        Ok(template) => Ok(HttpResponse::Ok().json(json!({
+            "code": 0,
+            "message": "模板创建成功",
+            "data": template
+        }))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(json!({
+            "code": 400,
+            "message": e.to_string()
+        })))
+    }
+}
+
+async fn update_mail_template(
+    req: HttpRequest,
+    path: web::Path<i32>,
+    body: web::Json<UpdateMailTemplateRequest>,
+    email_service: web::Data<Arc<RwLock<EmailService>>>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let _ = require_admin!(&req);
+    let es = email_service.read().await;
+    match es.update_template(path.into_inner(), &body).await {
+        Ok(template) => Ok(HttpResponse::Ok().json(json!({
+            "code": 0,
+            "message": "模板更新成功",
+            "data": template
+        }))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(json!({
+            "code": 400,
+            "message": e.to_string()
+        })))
+    }
+}
+
+async fn delete_mail_template(
+    req: HttpRequest,
+    path: web::Path<i32>,
+    email_service: web::Data<Arc<RwLock<EmailService>>>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let _ = require_admin!(&req);
+    let es = email_service.read().await;
+    match es.delete_template(path.into_inner()).await {
+        Ok(_) => Ok(HttpResponse::Ok().json(json!({
+            "code": 0,
+            "message": "模板删除成功"
+        }))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(json!({
+            "code": 400,
+            "message": e.to_string()
+        })))
+    }
+}
+
+// 使用示例变量预览模板
+async fn preview_mail_template(
+    req: HttpRequest,
+    body: web::Json<PreviewMailTemplateRequest>,
+    email_service: web::Data<Arc<RwLock<EmailService>>>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let _ = require_admin!(&req);
+    let es = email_service.read().await;
+    match es.preview_template(&body).await {
+        Ok(rendered) => Ok(HttpResponse::Ok().json(json!({
+            "code": 0,
+            "message": "success",
+            "data": rendered
+        }))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(json!({
+            "code": 400,
+            "message": e.to_string()
+        })))
+    }
+}
+
 // 获取社区设置
 async fn get_community_settings(
     admin_service: web::Data<AdminService>,
@@ -1444,24 +1704,21 @@ struct NotificationRequest {
 async fn send_category_notification(
     req: web::Json<NotificationRequest>,
     subscription_repo: web::Data<SubscriptionRepository>,
-    email_service: web::Data<Arc<RwLock<EmailService>>>,
+    notify: web::Data<NotificationService>,
     _auth_user: AuthenticatedUser,
 ) -> HttpResponse {
-    match subscription_repo.get_subscribed_emails(req.category_id).await {
-        Ok(emails) => {
-            let es = email_service.read().await;
+    // 按订阅者的通知偏好投递（站内 / 邮件 / 摘要 / 关闭）
+    match subscription_repo.get_subscribed_user_ids(req.category_id).await {
+        Ok(user_ids) => {
             let mut success_count = 0;
             let mut error_count = 0;
             
-            for email in &emails {
-                match es.send_category_notification(email, &req.title, &req.content).await {
-                    Ok(_) => {
-                        success_count += 1;
-                        println!("成功发送通知到: {}", email);
-                    },
+            for uid in &user_ids {
+                match notify.notify(*uid, &req.title, &req.content, None, Some("CategoryUpdate"), Some("Category"), Some(req.category_id)).await {
+                    Ok(_) => success_count += 1,
                     Err(e) => {
                         error_count += 1;
-                        println!("发送通知失败: {} -> {}", email, e);
+                        log::warn!("发送分类通知失败: 用户{} -> {}", uid, e);
                     }
                 }
             }
@@ -1477,7 +1734,7 @@ async fn send_category_notification(
         },
         Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
             "code": 500,
-            "message": format!("获取订阅邮箱失败: {}", e)
+            "message": format!("获取订阅用户失败: {}", e)
         }))
     }
 }
@@ -1,47 +1,62 @@
-use actix_web::{web, HttpResponse};
+use actix_web::{web, HttpResponse, ResponseError};
 use serde_json::json;
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::category::MovePackageRequest;
 use crate::models::system::{CreateCategoryRequest, UpdateCategoryRequest};
-use crate::repositories::system_repo::SystemRepository;
 use crate::repositories::package_repo::PackageRepository;
+use crate::services::category_service::CategoryService;
+use crate::services::package_maintainer_service::PackageMaintainerService;
+use crate::services::package_service::PackageService;
+use crate::utils::error::ServiceError;
 
 pub fn configure_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/categories")
             .route("", web::get().to(get_categories))
             .route("", web::post().to(create_category))
+            .route("/tree", web::get().to(get_category_tree))
             .route("/{id}", web::get().to(get_category))
             .route("/{id}", web::put().to(update_category))
             .route("/{id}", web::delete().to(delete_category))
+            .route("/{id}/path", web::get().to(get_category_path))
+            .route("/{id}/rules", web::get().to(get_category_rules))
     );
 }
 
-// 获取所有分类
+
+fn is_admin(user: &AuthenticatedUser) -> bool {
+    matches!(user.role, crate::models::UserRole::Admin | crate::models::UserRole::Elder)
+}
+
+fn forbidden() -> HttpResponse {
+    HttpResponse::Forbidden().json(json!({
+        "code": 403,
+        "message": "只有管理员可以管理分类"
+    }))
+}
+
+// 获取所有分类（平铺，按排序）
 async fn get_categories(
-    system_repo: web::Data<SystemRepository>,
+    category_service: web::Data<CategoryService>,
     package_repo: web::Data<std::sync::Arc<PackageRepository>>,
 ) -> HttpResponse {
     // 从数据库获取分类
-    match system_repo.get_categories().await {
+    match category_service.list().await {
         Ok(categories) => {
             // 手动构建包含count的JSON响应
             let mut categories_with_count = Vec::new();
-            
+
             for cat in categories.iter() {
                 let count = package_repo.count_packages_by_category(cat.id).await
                     .unwrap_or(0);
-                
-                categories_with_count.push(json!({
-                    "id": cat.id,
-                    "name": cat.name,
-                    "description": cat.description,
-                    "enabled": cat.enabled,
-                    "subscription_locked": cat.subscription_locked,
-                    "created_at": cat.created_at,
-                    "updated_at": cat.updated_at,
-                    "count": count
-                }));
+
+                let mut item = serde_json::to_value(cat).unwrap_or_else(|_| json!({}));
+                if let serde_json::Value::Object(ref mut map) = item {
+                    map.insert("count".to_string(), json!(count));
+                }
+                categories_with_count.push(item);
             }
-            
+
             HttpResponse::Ok().json(json!({
             "code": 0,
             "message": "success",
@@ -60,36 +75,82 @@ async fn get_categories(
     }
 }
 
-// 获取单个分类
-async fn get_category(
-    path: web::Path<i32>,
-    system_repo: web::Data<SystemRepository>,
-) -> HttpResponse {
-    let category_id = path.into_inner();
-    
-    match system_repo.get_category_by_id(category_id).await {
-        Ok(Some(category)) => HttpResponse::Ok().json(json!({
+// 分类树，同级按 sort_order 排序
+async fn get_category_tree(category_service: web::Data<CategoryService>) -> HttpResponse {
+    match category_service.tree().await {
+        Ok(tree) => HttpResponse::Ok().json(json!({
             "code": 0,
             "message": "success",
-            "data": category
-        })),
-        Ok(None) => HttpResponse::NotFound().json(json!({
-            "code": 404,
-            "message": "分类不存在"
+            "data": tree
         })),
         Err(e) => HttpResponse::InternalServerError().json(json!({
             "code": 500,
-            "message": format!("获取分类失败: {}", e)
+            "message": format!("获取分类树失败: {}", e)
         }))
     }
 }
 
-// 创建分类
+// 获取单个分类，附带面包屑与实际生效的规则
+async fn get_category(
+    path: web::Path<i32>,
+    category_service: web::Data<CategoryService>,
+) -> HttpResponse {
+    let category_id = path.into_inner();
+
+    let category = match category_service.get(category_id).await {
+        Ok(category) => category,
+        Err(e) => return ServiceError::from(e).error_response(),
+    };
+    let mut data = serde_json::to_value(&category).unwrap_or_else(|_| json!({}));
+    if let serde_json::Value::Object(ref mut map) = data {
+        map.insert("path".to_string(), json!(category_service.path(category_id).await.unwrap_or_default()));
+        if let Ok(rules) = category_service.rules(Some(category_id)).await {
+            map.insert("effective_rules".to_string(), json!(rules));
+        }
+    }
+    HttpResponse::Ok().json(json!({
+        "code": 0,
+        "message": "success",
+        "data": data
+    }))
+}
+
+// 面包屑：从顶级分类到该分类
+async fn get_category_path(
+    path: web::Path<i32>,
+    category_service: web::Data<CategoryService>,
+) -> HttpResponse {
+    match category_service.path(path.into_inner()).await {
+        Ok(crumbs) => HttpResponse::Ok().json(json!({"code": 0, "message": "success", "data": crumbs})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+// 沿父分类继承并补全全局配置后的发布规则
+async fn get_category_rules(
+    path: web::Path<i32>,
+    category_service: web::Data<CategoryService>,
+) -> HttpResponse {
+    let category_id = path.into_inner();
+    if let Err(e) = category_service.get(category_id).await {
+        return ServiceError::from(e).error_response();
+    }
+    match category_service.rules(Some(category_id)).await {
+        Ok(rules) => HttpResponse::Ok().json(json!({"code": 0, "message": "success", "data": rules})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+// 创建分类（管理员）
 async fn create_category(
+    user: AuthenticatedUser,
     req: web::Json<CreateCategoryRequest>,
-    system_repo: web::Data<SystemRepository>,
+    category_service: web::Data<CategoryService>,
 ) -> HttpResponse {
-    match system_repo.create_category(&req).await {
+    if !is_admin(&user) {
+        return forbidden();
+    }
+    match category_service.create(req.into_inner()).await {
         Ok(category) => HttpResponse::Ok().json(json!({
             "code": 0,
             "message": "分类创建成功",
@@ -102,42 +163,76 @@ async fn create_category(
     }
 }
 
-// 更新分类
+// 更新分类（管理员）；parent_id 为 0 时移到顶级，inherit 中的规则恢复为继承
 async fn update_category(
+    user: AuthenticatedUser,
     path: web::Path<i32>,
     req: web::Json<UpdateCategoryRequest>,
-    system_repo: web::Data<SystemRepository>,
+    category_service: web::Data<CategoryService>,
 ) -> HttpResponse {
+    if !is_admin(&user) {
+        return forbidden();
+    }
     let category_id = path.into_inner();
-    
-    match system_repo.update_category(category_id, &req).await {
+
+    match category_service.update(category_id, req.into_inner()).await {
         Ok(category) => HttpResponse::Ok().json(json!({
             "code": 0,
             "message": "分类更新成功",
             "data": category
         })),
-        Err(e) => HttpResponse::BadRequest().json(json!({
-            "code": 400,
-            "message": format!("更新分类失败: {}", e)
-        }))
+        Err(e) => ServiceError::from(e).error_response()
     }
 }
 
-// 删除分类
+// 删除分类（管理员），有子分类时拒绝
 async fn delete_category(
+    user: AuthenticatedUser,
     path: web::Path<i32>,
-    system_repo: web::Data<SystemRepository>,
+    category_service: web::Data<CategoryService>,
 ) -> HttpResponse {
+    if !is_admin(&user) {
+        return forbidden();
+    }
     let category_id = path.into_inner();
-    
-    match system_repo.delete_category(category_id).await {
+
+    match category_service.delete(category_id).await {
         Ok(_) => HttpResponse::Ok().json(json!({
             "code": 0,
             "message": "分类删除成功"
         })),
-        Err(e) => HttpResponse::InternalServerError().json(json!({
-            "code": 500,
-            "message": format!("删除分类失败: {}", e)
-        }))
+        Err(e) => ServiceError::from(e).error_response()
     }
-} 
\ No newline at end of file
+}
+
+/// 将资源移到其他分类，文件目录随之移动（所有者、维护者或管理员）
+/// 路由注册在 package.rs（/packages 与 /resources 作用域）
+pub async fn move_package(
+    user: AuthenticatedUser,
+    path: web::Path<i32>,
+    req: web::Json<MovePackageRequest>,
+    category_service: web::Data<CategoryService>,
+    package_service: web::Data<PackageService>,
+    maintainer_service: web::Data<PackageMaintainerService>,
+) -> HttpResponse {
+    let package_id = path.into_inner();
+    let package = match package_service.get_package_by_id(package_id).await {
+        Ok(Some(package)) => package,
+        Ok(None) => return ServiceError::NotFound("资源不存在".to_string()).error_response(),
+        Err(e) => return HttpResponse::InternalServerError().json(json!({"code": 500, "message": e.to_string()})),
+    };
+    if !is_admin(&user) && !maintainer_service.can_edit(&package, user.id).await.unwrap_or(false) {
+        return HttpResponse::Forbidden().json(json!({
+            "code": 403,
+            "message": "无权限移动该资源"
+        }));
+    }
+    match category_service.move_package(package_id, req.category_id, &user.role).await {
+        Ok(package) => HttpResponse::Ok().json(json!({
+            "code": 0,
+            "message": "资源已移动",
+            "data": package
+        })),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
@@ -0,0 +1,127 @@
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use serde_json::json;
+
+use crate::models::check_in::{CalendarQuery, MakeupRequest};
+use crate::models::user_action::CreateUserActionRequest;
+use crate::services::check_in_service::CheckInService;
+use crate::services::user_action_service::UserActionService;
+use crate::utils::auth_helper::AuthHelper;
+use crate::utils::error::ServiceError;
+
+// 路由注册在 /me 作用域内（见 user.rs）
+
+/// 今日签到，返回连续天数与本次获得的奖励
+pub async fn check_in(
+    http_req: HttpRequest,
+    check_in_service: web::Data<CheckInService>,
+    user_action_service: web::Data<UserActionService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let user = match AuthHelper::verify_user(&http_req).await {
+        Ok(user) => user,
+        Err(e) => return Ok(e.to_response()),
+    };
+
+    match check_in_service.check_in(user.id).await {
+        Ok(result) => {
+            if !result.already_checked_in {
+                // 记录签到行为（触发连续签到类成就评估）
+                let action = CreateUserActionRequest {
+                    user_id: Some(user.id),
+                    action_type: "CheckIn".to_string(),
+                    target_type: None,
+                    target_id: None,
+                    details: None,
+                    ip_address: http_req.connection_info().realip_remote_addr().map(|s| s.to_string()),
+                    user_agent: http_req.headers().get("User-Agent").and_then(|h| h.to_str().ok()).map(|s| s.to_string()),
+                };
+                if let Err(e) = user_action_service.log_user_action(&action).await {
+                    log::error!("记录签到行为失败: {}", e);
+                }
+            }
+            let message = if result.already_checked_in { "今日已签到" } else { "签到成功" };
+            Ok(HttpResponse::Ok().json(json!({
+                "code": 0,
+                "message": message,
+                "data": result
+            })))
+        },
+        Err(e) => Ok(HttpResponse::InternalServerError().json(json!({
+            "code": 500,
+            "message": e.to_string()
+        })))
+    }
+}
+
+/// 签到状态：今天是否已签、连续天数、本月补签剩余次数
+pub async fn get_check_in_status(
+    http_req: HttpRequest,
+    check_in_service: web::Data<CheckInService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let user = match AuthHelper::verify_user(&http_req).await {
+        Ok(user) => user,
+        Err(e) => return Ok(e.to_response()),
+    };
+
+    match check_in_service.status(user.id).await {
+        Ok((streak, makeup)) => Ok(HttpResponse::Ok().json(json!({
+            "code": 0,
+            "message": "success",
+            "data": {
+                "checked_in_today": streak.checked_in_today,
+                "current_streak": streak.current_streak,
+                "longest_streak": streak.longest_streak,
+                "total_days": streak.total_days,
+                "makeup": makeup
+            }
+        }))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(json!({
+            "code": 500,
+            "message": e.to_string()
+        })))
+    }
+}
+
+/// 月度签到日历
+pub async fn get_check_in_calendar(
+    http_req: HttpRequest,
+    query: web::Query<CalendarQuery>,
+    check_in_service: web::Data<CheckInService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let user = match AuthHelper::verify_user(&http_req).await {
+        Ok(user) => user,
+        Err(e) => return Ok(e.to_response()),
+    };
+
+    match check_in_service.calendar(user.id, query.month.as_deref()).await {
+        Ok(calendar) => Ok(HttpResponse::Ok().json(json!({
+            "code": 0,
+            "message": "success",
+            "data": calendar
+        }))),
+        Err(e) => Ok(HttpResponse::BadRequest().json(json!({
+            "code": 400,
+            "message": e.to_string()
+        })))
+    }
+}
+
+/// 消耗星星补签
+pub async fn make_up_check_in(
+    http_req: HttpRequest,
+    body: web::Json<MakeupRequest>,
+    check_in_service: web::Data<CheckInService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let user = match AuthHelper::verify_user(&http_req).await {
+        Ok(user) => user,
+        Err(e) => return Ok(e.to_response()),
+    };
+
+    match check_in_service.make_up(user.id, &body.date).await {
+        Ok(result) => Ok(HttpResponse::Ok().json(json!({
+            "code": 0,
+            "message": "补签成功",
+            "data": result
+        }))),
+        Err(e) => Ok(ServiceError::from(e).error_response()),
+    }
+}
@@ -0,0 +1,220 @@
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use serde_json::json;
+
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::collection::{
+    CollectionItemsQuery, CreateCollectionRequest, FavoriteTarget, MoveFavoritesRequest, MyFavoritesQuery,
+    ReorderCollectionsRequest, ReorderItemsRequest, UpdateCollectionRequest,
+};
+use crate::services::collection_service::CollectionService;
+use crate::utils::auth_helper::AuthHelper;
+use crate::utils::error::ServiceError;
+
+// /me 与 /users/{id} 下的收藏夹列表路由注册在 user.rs
+
+
+fn ok(data: serde_json::Value) -> HttpResponse {
+    HttpResponse::Ok().json(json!({"code": 0, "message": "success", "data": data}))
+}
+
+/// 我的收藏夹
+pub async fn get_my_collections(user: AuthenticatedUser, collection_service: web::Data<CollectionService>) -> HttpResponse {
+    match collection_service.list_mine(user.id).await {
+        Ok(list) => ok(json!(list)),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+/// 我的全部收藏（可只看未归入收藏夹的）
+pub async fn get_my_favorites(
+    user: AuthenticatedUser,
+    query: web::Query<MyFavoritesQuery>,
+    collection_service: web::Data<CollectionService>,
+) -> HttpResponse {
+    let page = query.page.unwrap_or(1).max(1);
+    let page_size = query.page_size.unwrap_or(20).clamp(1, 100);
+    match collection_service
+        .list_favorites(user.id, query.target_type.as_deref(), query.uncategorized.unwrap_or(false), page, page_size)
+        .await
+    {
+        Ok((list, total)) => ok(json!({"list": list, "total": total, "page": page, "page_size": page_size})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+/// 我关注的收藏夹
+pub async fn get_followed_collections(user: AuthenticatedUser, collection_service: web::Data<CollectionService>) -> HttpResponse {
+    match collection_service.list_followed(user.id).await {
+        Ok(list) => ok(json!(list)),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+/// 某用户的公开收藏夹
+pub async fn get_user_collections(
+    req: HttpRequest,
+    path: web::Path<i32>,
+    collection_service: web::Data<CollectionService>,
+) -> HttpResponse {
+    match collection_service.list_by_user(path.into_inner(), AuthHelper::extract_user_id(&req).await).await {
+        Ok(list) => ok(json!(list)),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+async fn create_collection(
+    user: AuthenticatedUser,
+    body: web::Json<CreateCollectionRequest>,
+    collection_service: web::Data<CollectionService>,
+) -> HttpResponse {
+    match collection_service.create(user.id, body.into_inner()).await {
+        Ok(collection) => HttpResponse::Ok().json(json!({"code": 0, "message": "创建成功", "data": collection})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+/// 收藏夹分享页（公开收藏夹无需登录）
+async fn get_collection(
+    req: HttpRequest,
+    path: web::Path<i32>,
+    query: web::Query<CollectionItemsQuery>,
+    collection_service: web::Data<CollectionService>,
+) -> HttpResponse {
+    let page = query.page.unwrap_or(1).max(1);
+    let page_size = query.page_size.unwrap_or(20).clamp(1, 100);
+    match collection_service
+        .get_page(AuthHelper::extract_user_id(&req).await, path.into_inner(), page, page_size)
+        .await
+    {
+        Ok((collection, items, total)) => ok(json!({
+            "collection": collection,
+            "items": { "list": items, "total": total, "page": page, "page_size": page_size }
+        })),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+async fn update_collection(
+    user: AuthenticatedUser,
+    path: web::Path<i32>,
+    body: web::Json<UpdateCollectionRequest>,
+    collection_service: web::Data<CollectionService>,
+) -> HttpResponse {
+    match collection_service.update(user.id, path.into_inner(), body.into_inner()).await {
+        Ok(collection) => HttpResponse::Ok().json(json!({"code": 0, "message": "保存成功", "data": collection})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+/// 删除收藏夹，其中的收藏回到默认收藏
+async fn delete_collection(
+    user: AuthenticatedUser,
+    path: web::Path<i32>,
+    collection_service: web::Data<CollectionService>,
+) -> HttpResponse {
+    match collection_service.delete(user.id, path.into_inner()).await {
+        Ok(_) => HttpResponse::Ok().json(json!({"code": 0, "message": "删除成功"})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+async fn reorder_collections(
+    user: AuthenticatedUser,
+    body: web::Json<ReorderCollectionsRequest>,
+    collection_service: web::Data<CollectionService>,
+) -> HttpResponse {
+    match collection_service.reorder(user.id, &body.ids).await {
+        Ok(list) => ok(json!(list)),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+async fn move_favorites(
+    user: AuthenticatedUser,
+    body: web::Json<MoveFavoritesRequest>,
+    collection_service: web::Data<CollectionService>,
+) -> HttpResponse {
+    match collection_service.move_items(user.id, &body.items, body.collection_id).await {
+        Ok(count) => ok(json!({"count": count})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+/// 收藏并放入收藏夹
+async fn add_collection_item(
+    user: AuthenticatedUser,
+    path: web::Path<i32>,
+    body: web::Json<FavoriteTarget>,
+    collection_service: web::Data<CollectionService>,
+) -> HttpResponse {
+    match collection_service.add_item(user.id, path.into_inner(), body.into_inner()).await {
+        Ok(_) => HttpResponse::Ok().json(json!({"code": 0, "message": "已加入收藏夹"})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+async fn remove_collection_item(
+    user: AuthenticatedUser,
+    path: web::Path<(i32, String, i32)>,
+    collection_service: web::Data<CollectionService>,
+) -> HttpResponse {
+    let (collection_id, target_type, target_id) = path.into_inner();
+    match collection_service
+        .remove_item(user.id, collection_id, FavoriteTarget { target_type, target_id })
+        .await
+    {
+        Ok(_) => HttpResponse::Ok().json(json!({"code": 0, "message": "已移出收藏夹"})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+async fn reorder_collection_items(
+    user: AuthenticatedUser,
+    path: web::Path<i32>,
+    body: web::Json<ReorderItemsRequest>,
+    collection_service: web::Data<CollectionService>,
+) -> HttpResponse {
+    match collection_service.reorder_items(user.id, path.into_inner(), &body.items).await {
+        Ok(_) => HttpResponse::Ok().json(json!({"code": 0, "message": "操作成功"})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+async fn follow_collection(
+    user: AuthenticatedUser,
+    path: web::Path<i32>,
+    collection_service: web::Data<CollectionService>,
+) -> HttpResponse {
+    match collection_service.follow(user.id, path.into_inner()).await {
+        Ok(_) => HttpResponse::Ok().json(json!({"code": 0, "message": "关注成功"})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+async fn unfollow_collection(
+    user: AuthenticatedUser,
+    path: web::Path<i32>,
+    collection_service: web::Data<CollectionService>,
+) -> HttpResponse {
+    match collection_service.unfollow(user.id, path.into_inner()).await {
+        Ok(_) => HttpResponse::Ok().json(json!({"code": 0, "message": "已取消关注"})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/collections")
+            .route("", web::post().to(create_collection))
+            .route("/order", web::put().to(reorder_collections))
+            .route("/move", web::post().to(move_favorites))
+            .route("/{id}", web::get().to(get_collection))
+            .route("/{id}", web::put().to(update_collection))
+            .route("/{id}", web::delete().to(delete_collection))
+            .route("/{id}/items", web::post().to(add_collection_item))
+            .route("/{id}/items/order", web::put().to(reorder_collection_items))
+            .route("/{id}/items/{target_type}/{target_id}", web::delete().to(remove_collection_item))
+            .route("/{id}/follow", web::post().to(follow_collection))
+            .route("/{id}/follow", web::delete().to(unfollow_collection))
+    );
+}
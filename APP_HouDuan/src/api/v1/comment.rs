@@ -2,10 +2,13 @@ use actix_web::{web, HttpResponse, Responder, get, post, put, delete};
 use crate::models::ApiResponse;
 use crate::models::{CreateCommentRequest, CommentListResponse};
 use crate::services::comment_service::CommentService;
+use crate::services::points_service::PointsService;
+use crate::services::edit_history_service::EditHistoryService;
 use crate::middleware::auth::AuthenticatedUser;
 use serde::{Deserialize};
 use actix_web::HttpRequest;
 use crate::utils::auth_helper::AuthHelper;
+use crate::utils::error::ServiceError;
 use crate::services::user_action_service::UserActionService;
 use crate::repositories::user_action_repo::UserActionRepository;
 use crate::models::user_action::CreateUserActionRequest;
@@ -106,7 +109,7 @@ async fn get_all_comments(
     comment_service: web::Data<CommentService>,
 ) -> impl Responder {
     // 管理员：保留原有读取全部能力（通过HttpRequest可选解析）
-    let is_admin = AuthHelper::verify_user(&http_req).map(|u| matches!(u.role, crate::models::UserRole::Admin)).unwrap_or(false);
+    let is_admin = AuthHelper::verify_user(&http_req).await.map(|u| matches!(u.role, crate::models::UserRole::Admin)).unwrap_or(false);
     if is_admin {
         let page = query.page.unwrap_or(1);
         let size = query.size.unwrap_or(20);
@@ -168,7 +171,7 @@ async fn get_all_comments(
         let db_target_type = if ttype_lower == "post" { "Post" } else { "Package" };
         if query.include_replies.unwrap_or(false) {
             // 返回带嵌套回复的结构
-            match comment_service.get_top_level_comments_with_replies(db_target_type, tid, page, size).await {
+            match comment_service.get_top_level_comments_with_replies(db_target_type, tid, page, size, AuthHelper::extract_user_id(&http_req).await).await {
                 Ok((mut comments, total)) => {
                     // 计算前缀
                     let cfg = crate::config::Config::load().unwrap_or_default();
@@ -205,7 +208,7 @@ async fn get_all_comments(
                 }
             }
         }
-        return match comment_service.get_top_level_comments(db_target_type, tid, page, size).await {
+        return match comment_service.get_top_level_comments(db_target_type, tid, page, size, AuthHelper::extract_user_id(&http_req).await).await {
             Ok((mut comments, total)) => {
                 // 计算前缀
                 let cfg = crate::config::Config::load().unwrap_or_default();
@@ -282,6 +285,7 @@ async fn get_all_comments(
 async fn get_comment(
     path: web::Path<i32>,
     comment_service: web::Data<CommentService>,
+    history_service: web::Data<EditHistoryService>,
     auth_user: AuthenticatedUser,
 ) -> impl Responder {
     let comment_id = path.into_inner();
@@ -290,7 +294,9 @@ async fn get_comment(
         Ok(Some(comment)) => {
             // 检查权限：只有评论作者、管理员或长老可以查看
             if comment.user_id == auth_user.id || auth_user.is_admin() || auth_user.is_elder() {
-                HttpResponse::Ok().json(ApiResponse::success(comment))
+                let mut data = serde_json::json!(comment);
+                history_service.attach_markers("Comment", std::slice::from_mut(&mut data)).await;
+                HttpResponse::Ok().json(ApiResponse::success(data))
             } else {
                 HttpResponse::Forbidden().json(ApiResponse::<()>::error(
                     403, "无权查看该评论"
@@ -330,12 +336,13 @@ async fn create_comment(
             HttpResponse::Created().json(ApiResponse::success(comment))
         },
         Err(e) => {
-            let msg = e.to_string();
-            let (http_status, code) = if msg.contains("违禁词") {
-                (actix_web::http::StatusCode::OK, 400)
-            } else {
-                (actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, 500)
+            let err = ServiceError::from(e);
+            let (http_status, code) = match &err {
+                ServiceError::BadRequest(_) => (actix_web::http::StatusCode::OK, 400),
+                ServiceError::Forbidden(_) => (actix_web::http::StatusCode::FORBIDDEN, 403),
+                _ => (actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, 500),
             };
+            let msg = err.to_string();
             log::warn!("创建评论失败: {}", msg);
             HttpResponse::build(http_status).json(ApiResponse::<()>::error(code, &msg))
         }
@@ -348,6 +355,7 @@ async fn update_comment(
     path: web::Path<i32>,
     req: web::Json<UpdateCommentRequest>,
     comment_service: web::Data<CommentService>,
+    history_service: web::Data<EditHistoryService>,
     auth_user: AuthenticatedUser,
 ) -> impl Responder {
     let comment_id = path.into_inner();
@@ -358,14 +366,17 @@ async fn update_comment(
             // 检查权限：只有评论作者或管理员可以修改
             let is_admin = auth_user.is_admin();
             if comment.user_id == auth_user.id || is_admin {
+                // 编辑前快照，编辑历史与更新在同一事务中写入
+                let revision = history_service.pending_revision("Comment", comment_id, auth_user.id).await;
                 // 更新评论
-                match comment_service.update_comment(
+                match comment_service.update_comment_recorded(
                     comment_id,
                     req.content.clone(),
                     req.status.clone(),
                     is_admin,
+                    revision.as_ref(),
                 ).await {
-                    Ok(updated_comment) => {
+                    Ok((updated_comment, _)) => {
                         HttpResponse::Ok().json(ApiResponse::success(updated_comment))
                     },
                     Err(e) => {
@@ -400,18 +411,22 @@ async fn update_comment(
 async fn delete_comment(
     path: web::Path<i32>,
     comment_service: web::Data<CommentService>,
+    points_service: web::Data<PointsService>,
     auth_user: AuthenticatedUser,
 ) -> impl Responder {
     let comment_id = path.into_inner();
     
     // 检查评论是否存在
     match comment_service.get_comment_by_id(comment_id).await {
-        Ok(Some(comment)) => {
+        Ok(Some(comment)) if comment.status != "Deleted" => {
             // 检查权限：只有评论作者、管理员或长老可以删除
             if comment.user_id == auth_user.id || auth_user.is_admin() || auth_user.is_elder() {
                 // 删除评论
-                match comment_service.delete_comment(comment_id, auth_user.is_admin()).await {
+                match comment_service.delete_comment(comment_id, auth_user.id).await {
                     Ok(_) => {
+                        if let Err(e) = points_service.on_content_removed("Comment", comment_id, comment.user_id, Some(auth_user.id)).await {
+                            log::error!("处理删除积分失败: {}", e);
+                        }
                         HttpResponse::Ok().json(ApiResponse::<()>::success_msg("评论删除成功"))
                     },
                     Err(e) => {
@@ -427,7 +442,7 @@ async fn delete_comment(
                 ))
             }
         },
-        Ok(None) => {
+        Ok(_) => {
             HttpResponse::NotFound().json(ApiResponse::<()>::error(
                 404, "评论不存在"
             ))
@@ -444,12 +459,13 @@ async fn delete_comment(
 // 获取评论回复
 #[get("/{comment_id}/replies")]
 async fn get_comment_replies(
+    http_req: HttpRequest,
     path: web::Path<i32>,
     comment_service: web::Data<CommentService>,
 ) -> impl Responder {
     let comment_id = path.into_inner();
     
-    match comment_service.get_comment_replies(comment_id).await {
+    match comment_service.get_comment_replies(comment_id, AuthHelper::extract_user_id(&http_req).await).await {
         Ok(replies) => {
             HttpResponse::Ok().json(ApiResponse::success(replies))
         },
@@ -486,11 +502,19 @@ async fn reply_comment(
                 Ok(comment) => {
                     HttpResponse::Created().json(ApiResponse::success(comment))
                 },
-                Err(e) => {
-                    log::error!("回复评论失败: {}", e);
-                    HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
-                        500, &format!("回复评论失败: {}", e)
-                    ))
+                Err(e) => match ServiceError::from(e) {
+                    ServiceError::Forbidden(msg) => {
+                        HttpResponse::Forbidden().json(ApiResponse::<()>::error(403, &msg))
+                    },
+                    ServiceError::BadRequest(msg) => {
+                        HttpResponse::BadRequest().json(ApiResponse::<()>::error(400, &msg))
+                    },
+                    e => {
+                        log::error!("回复评论失败: {}", e);
+                        HttpResponse::InternalServerError().json(ApiResponse::<()>::error(
+                            500, &format!("回复评论失败: {}", e)
+                        ))
+                    }
                 }
             }
         },
@@ -554,7 +578,7 @@ async fn do_batch_delete_comments(
     if !auth_user.is_admin() {
         return HttpResponse::Forbidden().json(ApiResponse::<()>::error(403, "只有管理员可以批量删除评论"));
     }
-    match comment_service.batch_delete_comments(payload.ids).await {
+    match comment_service.batch_delete_comments(payload.ids, auth_user.id).await {
         Ok(_) => HttpResponse::Ok().json(ApiResponse::<()>::success_msg("批量删除评论成功")),
         Err(e) => {
             log::error!("批量删除评论失败: {}", e);
@@ -713,12 +737,13 @@ async fn get_package_comments(
     path: web::Path<i32>,
     query: web::Query<CommentQueryParams>,
     comment_service: web::Data<CommentService>,
+    history_service: web::Data<EditHistoryService>,
 ) -> impl Responder {
     let package_id = path.into_inner();
     let page = query.page.unwrap_or(1);
     let size = query.size.unwrap_or(20);
     
-    match comment_service.get_package_comments(package_id, page, size).await {
+    match comment_service.get_package_comments(package_id, page, size, AuthHelper::extract_user_id(&http_req).await).await {
         Ok((mut comments, total)) => {
             // 计算前缀（优先 PUBLIC_BASE_URL，否则从请求推断）
             let cfg = crate::config::Config::load().unwrap_or_default();
@@ -740,13 +765,14 @@ async fn get_package_comments(
                     }
                 }
             }
-            let response = CommentListResponse {
-                list: comments,
-                total,
-                page,
-                size,
-            };
-            HttpResponse::Ok().json(ApiResponse::success(response))
+            let mut list: Vec<serde_json::Value> = comments.iter().map(|c| serde_json::json!(c)).collect();
+            history_service.attach_markers("Comment", &mut list).await;
+            HttpResponse::Ok().json(ApiResponse::success(serde_json::json!({
+                "list": list,
+                "total": total,
+                "page": page,
+                "size": size,
+            })))
         },
         Err(e) => {
             log::error!("获取资源评论失败: {}", e);
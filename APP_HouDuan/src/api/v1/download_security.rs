@@ -29,7 +29,7 @@ async fn get_security_stats(
 ) -> Result<HttpResponse, actix_web::Error> {
     // 检查管理员权限
     use crate::utils::auth_helper::AuthHelper;
-    if !AuthHelper::is_admin(&req) {
+    if !AuthHelper::is_admin(&req).await {
         return Ok(HttpResponse::Forbidden().json(json!({
             "code": 403,
             "message": "只有管理员可以查看安全统计"
@@ -57,7 +57,7 @@ async fn get_security_config(
 ) -> Result<HttpResponse, actix_web::Error> {
     // 检查管理员权限
     use crate::utils::auth_helper::AuthHelper;
-    if !AuthHelper::is_admin(&req) {
+    if !AuthHelper::is_admin(&req).await {
         return Ok(HttpResponse::Forbidden().json(json!({
             "code": 403,
             "message": "只有管理员可以查看安全配置"
@@ -86,7 +86,7 @@ async fn update_security_config(
 ) -> Result<HttpResponse, actix_web::Error> {
     // 检查管理员权限
     use crate::utils::auth_helper::AuthHelper;
-    if !AuthHelper::is_admin(&req) {
+    if !AuthHelper::is_admin(&req).await {
         return Ok(HttpResponse::Forbidden().json(json!({
             "code": 403,
             "message": "只有管理员可以更新安全配置"
@@ -115,7 +115,7 @@ async fn get_anomalies(
 ) -> Result<HttpResponse, actix_web::Error> {
     // 检查管理员权限
     use crate::utils::auth_helper::AuthHelper;
-    if !AuthHelper::is_admin(&req) {
+    if !AuthHelper::is_admin(&req).await {
         return Ok(HttpResponse::Forbidden().json(json!({
             "code": 403,
             "message": "只有管理员可以查看异常记录"
@@ -0,0 +1,151 @@
+use actix_web::{web, HttpResponse, ResponseError};
+use serde_json::json;
+
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::draft::{CreateDraftRequest, DraftListQuery, SaveDraftRequest, ScheduleDraftRequest};
+use crate::services::draft_service::DraftService;
+use crate::utils::error::ServiceError;
+
+// /me/drafts 路由注册在 user.rs
+
+
+fn ok(message: &str, data: serde_json::Value) -> HttpResponse {
+    HttpResponse::Ok().json(json!({"code": 0, "message": message, "data": data}))
+}
+
+/// 我的草稿（可按 type=Post / Package 过滤），包含定时发布与发布失败的草稿
+pub async fn get_my_drafts(
+    user: AuthenticatedUser,
+    query: web::Query<DraftListQuery>,
+    draft_service: web::Data<DraftService>,
+) -> HttpResponse {
+    match draft_service.list(user.id, query.target_type.as_deref()).await {
+        Ok(list) => ok("success", json!(list)),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+/// 新建草稿；带 publish_at 时直接进入定时发布
+pub async fn create_draft(
+    user: AuthenticatedUser,
+    body: web::Json<CreateDraftRequest>,
+    draft_service: web::Data<DraftService>,
+) -> HttpResponse {
+    match draft_service.create(user.id, body.into_inner()).await {
+        Ok(draft) => ok("草稿已保存", json!(draft)),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+pub async fn get_draft(
+    user: AuthenticatedUser,
+    path: web::Path<i32>,
+    draft_service: web::Data<DraftService>,
+) -> HttpResponse {
+    match draft_service.get(user.id, path.into_inner()).await {
+        Ok(draft) => ok("success", json!(draft)),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+/// 自动保存草稿内容
+pub async fn save_draft(
+    user: AuthenticatedUser,
+    path: web::Path<i32>,
+    body: web::Json<SaveDraftRequest>,
+    draft_service: web::Data<DraftService>,
+) -> HttpResponse {
+    match draft_service.save(user.id, path.into_inner(), body.into_inner().payload).await {
+        Ok(draft) => ok("草稿已保存", json!(draft)),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+pub async fn delete_draft(
+    user: AuthenticatedUser,
+    path: web::Path<i32>,
+    draft_service: web::Data<DraftService>,
+) -> HttpResponse {
+    match draft_service.delete(user.id, path.into_inner()).await {
+        Ok(_) => HttpResponse::Ok().json(json!({"code": 0, "message": "草稿已删除"})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+/// 设置（或修改）定时发布时间
+pub async fn schedule_draft(
+    user: AuthenticatedUser,
+    path: web::Path<i32>,
+    body: web::Json<ScheduleDraftRequest>,
+    draft_service: web::Data<DraftService>,
+) -> HttpResponse {
+    match draft_service.schedule(user.id, path.into_inner(), body.publish_at).await {
+        Ok(draft) => ok("已设置定时发布", json!(draft)),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+pub async fn unschedule_draft(
+    user: AuthenticatedUser,
+    path: web::Path<i32>,
+    draft_service: web::Data<DraftService>,
+) -> HttpResponse {
+    match draft_service.unschedule(user.id, path.into_inner()).await {
+        Ok(draft) => ok("已取消定时发布", json!(draft)),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+/// 立即发布草稿（与 /publish 接口相同，按分类规则进入审核或直接上架）
+pub async fn publish_draft(
+    user: AuthenticatedUser,
+    path: web::Path<i32>,
+    draft_service: web::Data<DraftService>,
+) -> HttpResponse {
+    match draft_service.publish_now(user.id, path.into_inner()).await {
+        Ok(published) => {
+            let message = if published.pending_review { "已提交发布，等待审核" } else { "已发布" };
+            ok(message, json!(published))
+        }
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+/// 自动保存的历史版本，最新的在前
+pub async fn get_draft_revisions(
+    user: AuthenticatedUser,
+    path: web::Path<i32>,
+    draft_service: web::Data<DraftService>,
+) -> HttpResponse {
+    match draft_service.list_revisions(user.id, path.into_inner()).await {
+        Ok(list) => ok("success", json!(list)),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+pub async fn restore_draft_revision(
+    user: AuthenticatedUser,
+    path: web::Path<(i32, i32)>,
+    draft_service: web::Data<DraftService>,
+) -> HttpResponse {
+    let (draft_id, revision_id) = path.into_inner();
+    match draft_service.restore_revision(user.id, draft_id, revision_id).await {
+        Ok(draft) => ok("已恢复到历史版本", json!(draft)),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/drafts")
+            .route("", web::post().to(create_draft))
+            .route("/{id}", web::get().to(get_draft))
+            .route("/{id}", web::put().to(save_draft))
+            .route("/{id}", web::delete().to(delete_draft))
+            .route("/{id}/schedule", web::put().to(schedule_draft))
+            .route("/{id}/schedule", web::delete().to(unschedule_draft))
+            .route("/{id}/publish", web::post().to(publish_draft))
+            .route("/{id}/revisions", web::get().to(get_draft_revisions))
+            .route("/{id}/revisions/{revision_id}/restore", web::post().to(restore_draft_revision))
+    );
+}
@@ -0,0 +1,107 @@
+use actix_web::{web, HttpResponse, ResponseError};
+use serde_json::json;
+
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::edit_revision::RevisionDiffQuery;
+use crate::services::edit_history_service::EditHistoryService;
+use crate::utils::error::ServiceError;
+
+
+fn ok(message: &str, data: serde_json::Value) -> HttpResponse {
+    HttpResponse::Ok().json(json!({"code": 0, "message": message, "data": data}))
+}
+
+fn is_moderator(user: &AuthenticatedUser) -> bool {
+    user.is_admin() || user.is_elder()
+}
+
+/// 路径中的 post / package(resource) / comment 转为内容类型
+fn parse_target(raw: &str) -> Result<&'static str, HttpResponse> {
+    EditHistoryService::parse_target_type(raw).ok_or_else(|| {
+        HttpResponse::BadRequest().json(json!({"code": 400, "message": "不支持的内容类型"}))
+    })
+}
+
+/// 编辑历史，最新的在前；作者、资源维护者与版主可见
+pub async fn list_revisions(
+    user: AuthenticatedUser,
+    path: web::Path<(String, i32)>,
+    history_service: web::Data<EditHistoryService>,
+) -> HttpResponse {
+    let (raw_type, target_id) = path.into_inner();
+    let target_type = match parse_target(&raw_type) {
+        Ok(t) => t,
+        Err(resp) => return resp,
+    };
+    match history_service.list(target_type, target_id, user.id, is_moderator(&user)).await {
+        Ok(list) => ok("success", json!(list)),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+/// 两个版本间的差异，文本字段按行比较
+pub async fn diff_revisions(
+    user: AuthenticatedUser,
+    path: web::Path<(String, i32)>,
+    query: web::Query<RevisionDiffQuery>,
+    history_service: web::Data<EditHistoryService>,
+) -> HttpResponse {
+    let (raw_type, target_id) = path.into_inner();
+    let target_type = match parse_target(&raw_type) {
+        Ok(t) => t,
+        Err(resp) => return resp,
+    };
+    match history_service
+        .diff(target_type, target_id, (query.from, query.to), user.id, is_moderator(&user))
+        .await
+    {
+        Ok(diff) => ok("success", json!(diff)),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+pub async fn get_revision(
+    user: AuthenticatedUser,
+    path: web::Path<(String, i32, i32)>,
+    history_service: web::Data<EditHistoryService>,
+) -> HttpResponse {
+    let (raw_type, target_id, revision_no) = path.into_inner();
+    let target_type = match parse_target(&raw_type) {
+        Ok(t) => t,
+        Err(resp) => return resp,
+    };
+    match history_service.get(target_type, target_id, revision_no, user.id, is_moderator(&user)).await {
+        Ok(revision) => ok("success", json!(revision)),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+/// 版主回滚到指定版本，回滚会记录为新版本
+pub async fn rollback_revision(
+    user: AuthenticatedUser,
+    path: web::Path<(String, i32, i32)>,
+    history_service: web::Data<EditHistoryService>,
+) -> HttpResponse {
+    if !is_moderator(&user) {
+        return HttpResponse::Forbidden().json(json!({"code": 403, "message": "只有版主可以回滚内容"}));
+    }
+    let (raw_type, target_id, revision_no) = path.into_inner();
+    let target_type = match parse_target(&raw_type) {
+        Ok(t) => t,
+        Err(resp) => return resp,
+    };
+    match history_service.rollback(user.id, target_type, target_id, revision_no).await {
+        Ok(new_revision) => ok("已回滚", json!({"revision_no": new_revision})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/revisions/{target_type}/{target_id}")
+            .route("", web::get().to(list_revisions))
+            .route("/diff", web::get().to(diff_revisions))
+            .route("/{revision_no}", web::get().to(get_revision))
+            .route("/{revision_no}/rollback", web::post().to(rollback_revision))
+    );
+}
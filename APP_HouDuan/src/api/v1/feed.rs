@@ -1,9 +1,13 @@
-use actix_web::{web, HttpResponse};
+use actix_web::{web, HttpRequest, HttpResponse};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use crate::services::{package_service::PackageService, post_service::PostService};
 use crate::services::admin_service::AdminService;
+use crate::services::user_relation_service::UserRelationService;
 use crate::repositories::{UserRepository, SystemRepository};
+use crate::repositories::timeline_repo::{kind_ord, TimelineRepository};
+use crate::models::timeline::{TimelineCursor, TimelinePage, TimelineQuery};
+use crate::utils::auth_helper::AuthHelper;
 // use crate::models::*;  // 移除未使用的导入
 
 pub fn configure_routes(cfg: &mut web::ServiceConfig) {
@@ -16,6 +20,11 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
         web::resource("/feed")
             .route(web::get().to(get_feed))
     );
+    // 关注时间线：关注的用户与订阅的分类
+    cfg.service(
+        web::resource("/feed/following")
+            .route(web::get().to(get_following_feed))
+    );
 }
 
 #[derive(Debug, Deserialize)]
@@ -70,9 +79,11 @@ pub struct CategoryInfo {
 }
 
 async fn get_feed(
+    http_req: HttpRequest,
     package_service: web::Data<PackageService>,
     post_service: web::Data<PostService>,
     admin_service: web::Data<AdminService>,
+    relation_service: web::Data<UserRelationService>,
     query: web::Query<FeedQueryParams>,
 ) -> Result<HttpResponse, actix_web::Error> {
     // 初始化仓库 - 使用与其他服务相同的数据库路径
@@ -152,6 +163,20 @@ async fn get_feed(
         }
     };
 
+    // 过滤我拉黑、拉黑我以及我屏蔽的用户发布的内容
+    let hidden = relation_service
+        .hidden_user_ids(AuthHelper::extract_user_id(&http_req).await)
+        .await
+        .unwrap_or_default();
+    let packages: Vec<_> = packages
+        .into_iter()
+        .filter(|p| p.owner_id.is_none_or(|id| !hidden.contains(&id)))
+        .collect();
+    let posts: Vec<_> = posts
+        .into_iter()
+        .filter(|post| !hidden.contains(&post.author_id))
+        .collect();
+
     // 获取所有分类信息
     let categories = system_repo.get_categories().await.unwrap_or_default();
     
@@ -313,4 +338,63 @@ async fn get_feed(
             "page_size": page_size
         }
     })))
-} 
\ No newline at end of file
+}
+
+/// 关注时间线，按时间倒序、游标分页
+async fn get_following_feed(
+    req: HttpRequest,
+    timeline_repo: web::Data<TimelineRepository>,
+    query: web::Query<TimelineQuery>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let user = match AuthHelper::verify_user(&req).await {
+        Ok(user) => user,
+        Err(e) => return Ok(e.to_response()),
+    };
+    let limit = query.limit.unwrap_or(20).clamp(1, 50) as usize;
+
+    let cursor = match query.cursor.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+        Some(c) => match TimelineCursor::parse(c) {
+            Some(cursor) => Some(cursor),
+            None => return Ok(HttpResponse::BadRequest().json(json!({
+                "code": 400,
+                "message": "cursor 参数无效"
+            }))),
+        },
+        None => None,
+    };
+    let kind = match query.kind.as_deref().map(str::trim).filter(|k| !k.is_empty()) {
+        Some(k) => match kind_ord(k) {
+            Some(ord) => Some(ord),
+            None => return Ok(HttpResponse::BadRequest().json(json!({
+                "code": 400,
+                "message": "type 参数无效，可选 post_published、package_published、package_updated、achievement_earned"
+            }))),
+        },
+        None => None,
+    };
+
+    // 多取一条用于判断是否还有下一页
+    let mut list = match timeline_repo.following_timeline(user.id, kind, cursor, limit as i64 + 1).await {
+        Ok(list) => list,
+        Err(e) => {
+            log::error!("获取关注时间线失败: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(json!({
+                "code": 500,
+                "message": format!("获取关注时间线失败: {}", e)
+            })));
+        }
+    };
+    let has_more = list.len() > limit;
+    list.truncate(limit);
+    let next_cursor = if has_more {
+        list.last().and_then(|item| item.cursor).map(|c| c.encode())
+    } else {
+        None
+    };
+
+    Ok(HttpResponse::Ok().json(json!({
+        "code": 0,
+        "message": "success",
+        "data": TimelinePage { list, next_cursor, has_more }
+    })))
+}
@@ -4,6 +4,8 @@ use serde::{Deserialize, Serialize};
 use crate::repositories::follow_repo::FollowRepository;
 use crate::repositories::user_repo::UserRepository;
 use crate::utils::jwt::JwtUtils;
+use crate::services::notification_service::NotificationService;
+use crate::utils::error::ServiceError;
 use std::sync::Arc;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -51,6 +53,7 @@ pub async fn follow_user(
     follow_repo: web::Data<Arc<FollowRepository>>,
     user_repo: web::Data<Arc<UserRepository>>,
     jwt_utils: web::Data<Arc<JwtUtils>>,
+    notify: web::Data<NotificationService>,
 ) -> Result<HttpResponse> {
     let followed_id = path.into_inner();
     
@@ -118,6 +121,18 @@ pub async fn follow_user(
             let follower_stats = follow_repo.get_follow_stats(follower_id).await.unwrap_or_default();
             
             info!("User {} followed user {}", follower_id, followed_id);
+
+            // 新关注时通知被关注者
+            if success {
+                if let Ok(Some(follower)) = user_repo.find_by_id(follower_id).await {
+                    let name = follower.nickname.as_deref().unwrap_or(&follower.username);
+                    let content = format!("{} 关注了您", name);
+                    let link = format!("/user/{}", follower_id);
+                    if let Err(e) = notify.by_actor(follower_id).notify(followed_id, "新增关注", &content, Some(&link), Some("NewFollower"), Some("User"), Some(follower_id)).await {
+                        error!("发送关注通知失败: {}", e);
+                    }
+                }
+            }
             
             Ok(HttpResponse::Ok().json(FollowResponse {
                 success,
@@ -126,11 +141,18 @@ pub async fn follow_user(
                 following_count: follower_stats.following_count,
             }))
         }
-        Err(e) => {
-            error!("Failed to follow user: {}", e);
-            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": "Failed to follow user"
-            })))
+        Err(e) => match ServiceError::from(e) {
+            ServiceError::Forbidden(msg) => {
+                Ok(HttpResponse::Forbidden().json(serde_json::json!({
+                    "error": msg
+                })))
+            }
+            e => {
+                error!("Failed to follow user: {}", e);
+                Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": "Failed to follow user"
+                })))
+            }
         }
     }
 }
@@ -0,0 +1,241 @@
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::message::{
+    AdminMessageReportQuery, ConversationUserRequest, HandleMessageReportRequest, MarkReadRequest, MessageQuery,
+    MessageSearchQuery, PinConversationRequest, ReportMessageRequest, SendMessageRequest, UpdateMessageSettingsRequest,
+};
+use crate::require_admin;
+use crate::services::message_service::MessageService;
+use crate::utils::error::ServiceError;
+
+// 私信举报的管理员路由注册在 /admin 作用域内（见 admin.rs）
+
+
+fn ok(data: serde_json::Value) -> HttpResponse {
+    HttpResponse::Ok().json(json!({"code": 0, "message": "success", "data": data}))
+}
+
+#[derive(Debug, Deserialize)]
+struct ConversationSearchQuery {
+    q: Option<String>,
+}
+
+async fn get_conversations(user: AuthenticatedUser, message_service: web::Data<MessageService>) -> HttpResponse {
+    match message_service.list_conversations(user.id, None).await {
+        Ok(list) => ok(json!(list)),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+async fn search_conversations(
+    user: AuthenticatedUser,
+    query: web::Query<ConversationSearchQuery>,
+    message_service: web::Data<MessageService>,
+) -> HttpResponse {
+    match message_service.list_conversations(user.id, query.q.as_deref()).await {
+        Ok(list) => ok(json!(list)),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+/// 与某个用户的消息历史（按时间倒序分页）
+async fn get_messages(
+    user: AuthenticatedUser,
+    query: web::Query<MessageQuery>,
+    message_service: web::Data<MessageService>,
+) -> HttpResponse {
+    match message_service.list_messages(user.id, &query).await {
+        Ok((list, total, page, page_size)) => ok(json!({
+            "list": list,
+            "total": total,
+            "page": page,
+            "page_size": page_size
+        })),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+async fn send_message(
+    user: AuthenticatedUser,
+    body: web::Json<SendMessageRequest>,
+    message_service: web::Data<MessageService>,
+) -> HttpResponse {
+    match message_service.send(user.id, body.into_inner()).await {
+        Ok(message) => HttpResponse::Ok().json(json!({"code": 0, "message": "发送成功", "data": message})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+async fn search_messages(
+    user: AuthenticatedUser,
+    query: web::Query<MessageSearchQuery>,
+    message_service: web::Data<MessageService>,
+) -> HttpResponse {
+    match message_service.search_messages(user.id, &query.q, query.user_id).await {
+        Ok(list) => ok(json!(list)),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+async fn mark_read(
+    user: AuthenticatedUser,
+    body: web::Json<MarkReadRequest>,
+    message_service: web::Data<MessageService>,
+) -> HttpResponse {
+    match message_service.mark_read(user.id, &body.message_ids).await {
+        Ok(count) => ok(json!({"count": count})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+async fn mark_conversation_read(
+    user: AuthenticatedUser,
+    body: web::Json<ConversationUserRequest>,
+    message_service: web::Data<MessageService>,
+) -> HttpResponse {
+    match message_service.mark_conversation_read(user.id, body.user_id).await {
+        Ok(count) => ok(json!({"count": count})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+async fn get_unread_count(user: AuthenticatedUser, message_service: web::Data<MessageService>) -> HttpResponse {
+    match message_service.unread_count(user.id).await {
+        Ok(count) => ok(json!({"count": count})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+/// 仅对自己删除消息
+async fn delete_message(
+    user: AuthenticatedUser,
+    path: web::Path<i32>,
+    message_service: web::Data<MessageService>,
+) -> HttpResponse {
+    match message_service.delete_message(user.id, path.into_inner()).await {
+        Ok(_) => HttpResponse::Ok().json(json!({"code": 0, "message": "删除成功"})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+/// 仅对自己删除会话，对方再发消息时会话重新出现
+async fn delete_conversation(
+    user: AuthenticatedUser,
+    path: web::Path<i32>,
+    message_service: web::Data<MessageService>,
+) -> HttpResponse {
+    match message_service.delete_conversation(user.id, path.into_inner()).await {
+        Ok(_) => HttpResponse::Ok().json(json!({"code": 0, "message": "删除成功"})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+async fn pin_conversation(
+    user: AuthenticatedUser,
+    body: web::Json<PinConversationRequest>,
+    message_service: web::Data<MessageService>,
+) -> HttpResponse {
+    match message_service.set_pinned(user.id, body.user_id, body.pinned).await {
+        Ok(_) => HttpResponse::Ok().json(json!({"code": 0, "message": "操作成功"})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+async fn get_settings(user: AuthenticatedUser, message_service: web::Data<MessageService>) -> HttpResponse {
+    match message_service.get_settings(user.id).await {
+        Ok(settings) => ok(json!(settings)),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+async fn update_settings(
+    user: AuthenticatedUser,
+    body: web::Json<UpdateMessageSettingsRequest>,
+    message_service: web::Data<MessageService>,
+) -> HttpResponse {
+    match message_service.update_settings(user.id, &body.allow_from).await {
+        Ok(settings) => HttpResponse::Ok().json(json!({"code": 0, "message": "设置已保存", "data": settings})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+async fn report_message(
+    user: AuthenticatedUser,
+    path: web::Path<i32>,
+    body: web::Json<ReportMessageRequest>,
+    message_service: web::Data<MessageService>,
+) -> HttpResponse {
+    match message_service.report(user.id, path.into_inner(), body.reason.as_deref()).await {
+        Ok(id) => HttpResponse::Ok().json(json!({"code": 0, "message": "举报已提交", "data": {"id": id}})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+/// 管理员：私信举报列表
+pub async fn admin_list_reports(
+    req: HttpRequest,
+    query: web::Query<AdminMessageReportQuery>,
+    message_service: web::Data<MessageService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let _ = require_admin!(&req);
+    let page = query.page.unwrap_or(1).max(1);
+    let page_size = query.page_size.unwrap_or(20).clamp(1, 100);
+    match message_service.list_reports(query.status.as_deref(), page, page_size).await {
+        Ok((list, total)) => Ok(HttpResponse::Ok().json(json!({
+            "code": 0,
+            "message": "success",
+            "data": { "list": list, "total": total, "page": page, "page_size": page_size }
+        }))),
+        Err(e) => Ok(ServiceError::from(e).error_response()),
+    }
+}
+
+/// 管理员：举报详情，附带被举报消息前后的会话内容
+pub async fn admin_get_report(
+    req: HttpRequest,
+    path: web::Path<i32>,
+    message_service: web::Data<MessageService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let _ = require_admin!(&req);
+    match message_service.get_report_detail(path.into_inner()).await {
+        Ok((report, context)) => Ok(ok(json!({ "report": report, "context": context }))),
+        Err(e) => Ok(ServiceError::from(e).error_response()),
+    }
+}
+
+/// 管理员：处理举报
+pub async fn admin_handle_report(
+    req: HttpRequest,
+    path: web::Path<i32>,
+    body: web::Json<HandleMessageReportRequest>,
+    message_service: web::Data<MessageService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let admin = require_admin!(&req);
+    match message_service.handle_report(admin.id, path.into_inner(), &body.status, body.note.as_deref()).await {
+        Ok(_) => Ok(HttpResponse::Ok().json(json!({"code": 0, "message": "操作成功"}))),
+        Err(e) => Ok(ServiceError::from(e).error_response()),
+    }
+}
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/messages")
+            .route("", web::get().to(get_messages))
+            .route("", web::post().to(send_message))
+            .route("/conversations", web::get().to(get_conversations))
+            .route("/search-conversations", web::get().to(search_conversations))
+            .route("/search", web::get().to(search_messages))
+            .route("/read", web::post().to(mark_read))
+            .route("/read-conversation", web::post().to(mark_conversation_read))
+            .route("/pin-conversation", web::post().to(pin_conversation))
+            .route("/unread-count", web::get().to(get_unread_count))
+            .route("/conversation/{user_id}", web::delete().to(delete_conversation))
+            .route("/settings", web::get().to(get_settings))
+            .route("/settings", web::put().to(update_settings))
+            .route("/{id}/report", web::post().to(report_message))
+            .route("/{id}", web::delete().to(delete_message))
+    );
+}
@@ -28,6 +28,36 @@ pub mod publish;
 pub mod ranking;
 // 新增关注模块
 pub mod follow;
+// 资源评分与评价（路由挂在 package / admin 作用域内）
+pub mod rating;
+// 成就（路由挂在 user / admin 作用域内）
+pub mod achievement;
+// 经验、等级与星星（路由挂在 user / admin 作用域内）
+pub mod points;
+// 签到、签到日历与补签（路由挂在 user 作用域内）
+pub mod check_in;
+// 私信（举报处理路由挂在 admin 作用域内）
+pub mod message;
+// 拉黑与屏蔽（路由挂在 user / me 作用域内）
+pub mod user_relation;
+// 收藏夹（我的收藏与用户收藏夹列表挂在 user / me 作用域内）
+pub mod collection;
+// 资源维护者与所有权转让（路由挂在 package / me 作用域内）
+pub mod package_maintainer;
+// 草稿与定时发布（我的草稿列表挂在 me 作用域内）
+pub mod draft;
+// Markdown 预览与代码高亮样式
+pub mod render;
+// 帖子、资源与评论的编辑历史
+pub mod edit_history;
+// 资源依赖与安装解析（路由挂在 package 作用域内）
+pub mod package_dependency;
+// 资源数据分析（路由挂在 package 作用域内）
+pub mod package_analytics;
+// 全站数据汇总（路由挂在 admin 作用域内）
+pub mod site_metrics;
+// 回收站（路由挂在 admin 作用域内）
+pub mod recycle_bin;
 
 use actix_web::web;
 
@@ -54,7 +84,12 @@ pub fn configure_api(cfg: &mut web::ServiceConfig) {
         .configure(storage::configure_routes)
         .configure(search::configure_routes)
         .configure(publish::configure_routes) // 添加发布路由
-        .configure(ranking::configure_routes); // 添加排行榜路由
+        .configure(ranking::configure_routes) // 添加排行榜路由
+        .configure(message::configure_routes)
+        .configure(collection::configure_routes)
+        .configure(draft::configure_routes)
+        .configure(render::configure_routes)
+        .configure(edit_history::configure_routes);
         // 关注路由已合并到用户路由中
 
     // 添加公共API路由
@@ -1,14 +1,25 @@
-use actix_web::{web, HttpResponse};
+use actix_web::{web, HttpRequest, HttpResponse};
+use actix_web::web::Bytes;
 use serde_json::json;
+use crate::services::email_service::normalize_language;
 use crate::services::notification_service::NotificationService;
 use crate::middleware::auth::AuthenticatedUser;
-use crate::models::notification::NotificationQuery;
+use crate::models::notification::{NotificationQuery, NotificationStreamQuery, UnsubscribeQuery, UpdateNotificationSettingsRequest};
+use crate::utils::auth_helper::AuthHelper;
+use crate::services::notification_hub::STREAM_TICKET_TTL;
 
 pub fn configure_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/notifications")
             .route("", web::get().to(list_notifications))
+            // 实时推送（SSE），不支持时客户端回退到下方轮询接口
+            .route("/stream", web::get().to(stream_notifications))
+            .route("/stream-ticket", web::post().to(issue_stream_ticket))
             .route("/unread-count", web::get().to(unread_count))
+            .route("/preferences", web::get().to(get_preferences))
+            .route("/preferences", web::put().to(update_preferences))
+            // 邮件中的退订链接，无需登录
+            .route("/unsubscribe", web::get().to(unsubscribe))
             .route("/{id}/read", web::post().to(mark_read))
             .route("/mark-all-read", web::post().to(mark_all_read))
             .route("/delete-read", web::delete().to(delete_read))
@@ -32,6 +43,93 @@ async fn list_notifications(q: web::Query<NotificationQuery>, svc: web::Data<Not
     }
 }
 
+async fn issue_stream_ticket(svc: web::Data<NotificationService>, user: AuthenticatedUser) -> HttpResponse {
+    let ticket = svc.hub().issue_ticket(user.id).await;
+    HttpResponse::Ok().json(json!({"code":0, "data": {"ticket": ticket, "expires_in": STREAM_TICKET_TTL.as_secs()}}))
+}
+
+async fn stream_notifications(
+    req: HttpRequest,
+    q: web::Query<NotificationStreamQuery>,
+    svc: web::Data<NotificationService>,
+) -> HttpResponse {
+    // 优先使用请求头/Cookie 中的 token；否则使用一次性票据，二者都经过封禁与注销检查
+    let user_id = if AuthHelper::extract_token(&req).is_some() {
+        match AuthHelper::verify_user(&req).await {
+            Ok(user) => user.id,
+            Err(e) => return e.to_response(),
+        }
+    } else {
+        let user_id = match &q.ticket {
+            Some(ticket) => svc.hub().redeem_ticket(ticket).await,
+            None => return HttpResponse::Unauthorized().json(json!({"code":401, "message":"需要登录认证"})),
+        };
+        let Some(user_id) = user_id else {
+            return HttpResponse::Unauthorized().json(json!({"code":401, "message":"连接票据无效或已过期"}));
+        };
+        if let Err(e) = AuthHelper::check_account(&req, user_id).await {
+            return e.to_response();
+        }
+        user_id
+    };
+
+    let last_event_id = req.headers().get("Last-Event-ID")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<i32>().ok())
+        .or(q.last_event_id);
+
+    let receiver = svc.hub().register(user_id).await;
+    let initial = match svc.resume_events(user_id, last_event_id).await {
+        Ok(events) => events.iter().map(|e| e.to_sse()).collect::<String>(),
+        Err(e) => return HttpResponse::InternalServerError().json(json!({"code":500, "message": e.to_string()})),
+    };
+
+    let head = futures::stream::once(async move { Ok::<_, actix_web::Error>(Bytes::from(format!("retry: 5000\n\n{}", initial))) });
+    let tail = futures::stream::unfold(receiver, |mut rx| async move {
+        rx.recv().await.map(|bytes| (Ok::<_, actix_web::Error>(bytes), rx))
+    });
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .insert_header(("X-Accel-Buffering", "no"))
+        .streaming(futures::StreamExt::chain(head, tail))
+}
+
+async fn get_preferences(svc: web::Data<NotificationService>, user: AuthenticatedUser) -> HttpResponse {
+    match svc.get_settings(user.id).await {
+        Ok(settings) => HttpResponse::Ok().json(json!({"code":0, "data": settings})),
+        Err(e) => HttpResponse::InternalServerError().json(json!({"code":500, "message": e.to_string()}))
+    }
+}
+
+async fn update_preferences(body: web::Json<UpdateNotificationSettingsRequest>, svc: web::Data<NotificationService>, user: AuthenticatedUser) -> HttpResponse {
+    if let Some(language) = body.mail_language.as_deref().filter(|l| !l.trim().is_empty()) {
+        if let Err(e) = normalize_language(Some(language)) {
+            return HttpResponse::BadRequest().json(json!({"code":400, "message": e.to_string()}));
+        }
+    }
+    match svc.update_settings(user.id, body.into_inner()).await {
+        Ok(settings) => HttpResponse::Ok().json(json!({"code":0, "message":"通知设置已更新", "data": settings})),
+        Err(e) => HttpResponse::InternalServerError().json(json!({"code":500, "message": e.to_string()}))
+    }
+}
+
+async fn unsubscribe(q: web::Query<UnsubscribeQuery>, svc: web::Data<NotificationService>) -> HttpResponse {
+    let q = q.into_inner();
+    match svc.unsubscribe(&q.token, q.category).await {
+        Ok(true) => {
+            let message = match q.category {
+                Some(category) => format!("已退订「{}」邮件通知，站内通知不受影响", category.label()),
+                None => "已退订全部邮件通知及摘要，站内通知不受影响".to_string(),
+            };
+            HttpResponse::Ok().json(json!({"code":0, "message": message}))
+        }
+        Ok(false) => HttpResponse::NotFound().json(json!({"code":404, "message":"退订链接无效或已失效"})),
+        Err(e) => HttpResponse::InternalServerError().json(json!({"code":500, "message": e.to_string()}))
+    }
+}
+
 async fn unread_count(svc: web::Data<NotificationService>, user: Option<AuthenticatedUser>) -> HttpResponse {
     if let Some(user) = user {
         match svc.unread_count(user.id).await {
@@ -12,6 +12,13 @@ use crate::services::user_action_service::UserActionService;
 use crate::models::user_action::CreateUserActionRequest;
 use crate::repositories::user_repo::UserRepository;
 use crate::services::anti_fraud_service::AntiFraudService;
+use crate::services::recommendation_service::RecommendationService;
+use crate::services::package_maintainer_service::PackageMaintainerService;
+use crate::services::render_service::{RenderService, FIELD_PACKAGE_DESCRIPTION};
+use crate::services::edit_history_service::EditHistoryService;
+use crate::services::category_service::CategoryService;
+use crate::repositories::tag_repo::TagTarget;
+use crate::services::tag_service::TagService;
 
 
 #[derive(Debug, Deserialize, Clone)]
@@ -51,6 +58,10 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
                 web::resource("/top-likes")
                     .route(web::get().to(get_top_likes))
             )
+            .service(
+                web::resource("/recommended")
+                    .route(web::get().to(get_recommended_packages))
+            )
             // 审核相关路由 - 必须在 /{id} 之前定义
             .service(
                 web::resource("/pending")
@@ -77,6 +88,10 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
                     .route(web::put().to(update_package))
                     .route(web::delete().to(delete_package))
             )
+            .service(
+                web::resource("/{id}/similar")
+                    .route(web::get().to(get_similar_packages))
+            )
             .service(
                 web::resource("/{id}/download")
                     .route(web::get().to(download_package))
@@ -90,6 +105,22 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
                 web::resource("/{id}/comments")
                     .route(web::get().to(get_package_comments))
             )
+            // 评分与评价 /packages/{id}/ratings
+            .service(
+                web::resource("/{id}/ratings")
+                    .route(web::get().to(crate::api::v1::rating::list_package_ratings))
+                    .route(web::post().to(crate::api::v1::rating::rate_package))
+                    .route(web::put().to(crate::api::v1::rating::rate_package))
+                    .route(web::delete().to(crate::api::v1::rating::delete_my_rating))
+            )
+            .service(
+                web::resource("/{id}/ratings/summary")
+                    .route(web::get().to(crate::api::v1::rating::get_rating_summary))
+            )
+            .service(
+                web::resource("/{id}/ratings/{rating_id}/reply")
+                    .route(web::put().to(crate::api::v1::rating::reply_rating))
+            )
             .service(
                 web::resource("/{id}/report")
                     .route(web::post().to(report_package))
@@ -116,6 +147,50 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
                 web::resource("/{id}/bookmark-status")
                     .route(web::get().to(check_favorite_status_handler))
             )
+            // 维护者与所有权转让
+            .service(
+                web::resource("/{id}/maintainers")
+                    .route(web::get().to(crate::api::v1::package_maintainer::list_maintainers))
+                    .route(web::post().to(crate::api::v1::package_maintainer::add_maintainer))
+            )
+            .service(
+                web::resource("/{id}/maintainers/{user_id}")
+                    .route(web::delete().to(crate::api::v1::package_maintainer::remove_maintainer))
+            )
+            .service(
+                web::resource("/{id}/transfer")
+                    .route(web::get().to(crate::api::v1::package_maintainer::get_pending_transfer))
+                    .route(web::post().to(crate::api::v1::package_maintainer::request_transfer))
+                    .route(web::delete().to(crate::api::v1::package_maintainer::cancel_transfer))
+            )
+            // 依赖与安装解析
+            .service(
+                web::resource("/{id}/dependencies")
+                    .route(web::get().to(crate::api::v1::package_dependency::get_dependencies))
+                    .route(web::put().to(crate::api::v1::package_dependency::update_dependencies))
+            )
+            .service(
+                web::resource("/{id}/dependents")
+                    .route(web::get().to(crate::api::v1::package_dependency::get_dependents))
+            )
+            .service(
+                web::resource("/{id}/resolve")
+                    .route(web::get().to(crate::api::v1::package_dependency::resolve_dependencies))
+            )
+            // 数据分析（所有者、维护者或管理员）
+            .service(
+                web::resource("/{id}/analytics")
+                    .route(web::get().to(crate::api::v1::package_analytics::get_analytics))
+            )
+            .service(
+                web::resource("/{id}/analytics/export")
+                    .route(web::get().to(crate::api::v1::package_analytics::export_analytics_csv))
+            )
+            // 移动到其他分类
+            .service(
+                web::resource("/{id}/category")
+                    .route(web::put().to(crate::api::v1::category::move_package))
+            )
     );
 
     // 新增：/resources 别名，映射到与 /packages 相同的处理器
@@ -162,6 +237,50 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
                 web::resource("/{id}/bookmark-status")
                     .route(web::get().to(check_favorite_status_handler))
             )
+            // 维护者与所有权转让
+            .service(
+                web::resource("/{id}/maintainers")
+                    .route(web::get().to(crate::api::v1::package_maintainer::list_maintainers))
+                    .route(web::post().to(crate::api::v1::package_maintainer::add_maintainer))
+            )
+            .service(
+                web::resource("/{id}/maintainers/{user_id}")
+                    .route(web::delete().to(crate::api::v1::package_maintainer::remove_maintainer))
+            )
+            .service(
+                web::resource("/{id}/transfer")
+                    .route(web::get().to(crate::api::v1::package_maintainer::get_pending_transfer))
+                    .route(web::post().to(crate::api::v1::package_maintainer::request_transfer))
+                    .route(web::delete().to(crate::api::v1::package_maintainer::cancel_transfer))
+            )
+            // 依赖与安装解析
+            .service(
+                web::resource("/{id}/dependencies")
+                    .route(web::get().to(crate::api::v1::package_dependency::get_dependencies))
+                    .route(web::put().to(crate::api::v1::package_dependency::update_dependencies))
+            )
+            .service(
+                web::resource("/{id}/dependents")
+                    .route(web::get().to(crate::api::v1::package_dependency::get_dependents))
+            )
+            .service(
+                web::resource("/{id}/resolve")
+                    .route(web::get().to(crate::api::v1::package_dependency::resolve_dependencies))
+            )
+            // 数据分析（所有者、维护者或管理员）
+            .service(
+                web::resource("/{id}/analytics")
+                    .route(web::get().to(crate::api::v1::package_analytics::get_analytics))
+            )
+            .service(
+                web::resource("/{id}/analytics/export")
+                    .route(web::get().to(crate::api::v1::package_analytics::export_analytics_csv))
+            )
+            // 移动到其他分类
+            .service(
+                web::resource("/{id}/category")
+                    .route(web::put().to(crate::api::v1::category::move_package))
+            )
     );
 }
 
@@ -187,7 +306,7 @@ async fn create_resource_comment(
     body: web::Json<CreateCommentBody>,
     comment_service: web::Data<CommentService>,
 ) -> Result<HttpResponse, actix_web::Error> {
-    let user = match AuthHelper::verify_user(&http_req) { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
+    let user = match AuthHelper::verify_user(&http_req).await { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
     let resource_id = path.into_inner();
     match comment_service.create_comment(
         user.id,
@@ -208,7 +327,7 @@ async fn report_package(
     body: web::Json<ReportBody>,
     ua_service: web::Data<UserActionService>,
 ) -> Result<HttpResponse, actix_web::Error> {
-    let user = match AuthHelper::verify_user(&http_req) { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
+    let user = match AuthHelper::verify_user(&http_req).await { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
     let id = path.into_inner();
     let ip = http_req.connection_info().realip_remote_addr().map(|s| s.to_string());
     let ua = http_req.headers().get("User-Agent").and_then(|h| h.to_str().ok()).map(|s| s.to_string());
@@ -233,7 +352,7 @@ async fn favorite_package_handler(
     path: web::Path<i32>,
     package_service: web::Data<PackageService>,
 ) -> Result<HttpResponse, actix_web::Error> {
-    let user = match AuthHelper::verify_user(&http_req) { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
+    let user = match AuthHelper::verify_user(&http_req).await { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
     let id = path.into_inner();
     match package_service.favorite_package(user.id, id).await {
         Ok(count) => Ok(HttpResponse::Ok().json(json!({"code":0, "message":"success", "data": {"favorite_count": count}}))),
@@ -246,7 +365,7 @@ async fn unfavorite_package_handler(
     path: web::Path<i32>,
     package_service: web::Data<PackageService>,
 ) -> Result<HttpResponse, actix_web::Error> {
-    let user = match AuthHelper::verify_user(&http_req) { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
+    let user = match AuthHelper::verify_user(&http_req).await { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
     let id = path.into_inner();
     match package_service.unfavorite_package(user.id, id).await {
         Ok(count) => Ok(HttpResponse::Ok().json(json!({"code":0, "message":"success", "data": {"favorite_count": count}}))),
@@ -259,7 +378,7 @@ async fn check_favorite_status_handler(
     path: web::Path<i32>,
     package_service: web::Data<PackageService>,
 ) -> Result<HttpResponse, actix_web::Error> {
-    let user = match AuthHelper::verify_user(&http_req) { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
+    let user = match AuthHelper::verify_user(&http_req).await { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
     let id = path.into_inner();
     match package_service.check_favorite_status(user.id, id).await {
         Ok(is_fav) => Ok(HttpResponse::Ok().json(json!({"code":0, "message":"success", "data": {"favorited": is_fav}}))),
@@ -283,6 +402,33 @@ async fn get_top_likes(query: web::Query<TopQuery>, package_service: web::Data<P
     }
 }
 
+// 相似资源：下载/喜欢过此资源的用户也下载了
+async fn get_similar_packages(
+    path: web::Path<i32>,
+    query: web::Query<TopQuery>,
+    recommendation_service: web::Data<RecommendationService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let limit = query.limit.unwrap_or(10).clamp(1, 20);
+    match recommendation_service.similar_packages(path.into_inner(), limit).await {
+        Ok(list) => Ok(HttpResponse::Ok().json(json!({"code":0, "message":"success", "data": {"list": list}}))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(json!({"code":500, "message": e.to_string()})))
+    }
+}
+
+// 为我推荐：根据下载、点赞、收藏历史预计算
+async fn get_recommended_packages(
+    http_req: HttpRequest,
+    query: web::Query<TopQuery>,
+    recommendation_service: web::Data<RecommendationService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let user = match AuthHelper::verify_user(&http_req).await { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
+    let limit = query.limit.unwrap_or(10).clamp(1, 30);
+    match recommendation_service.recommended_for_user(user.id, limit).await {
+        Ok(list) => Ok(HttpResponse::Ok().json(json!({"code":0, "message":"success", "data": {"list": list}}))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(json!({"code":500, "message": e.to_string()})))
+    }
+}
+
 // 审核资源（管理员和元老可用）
 async fn review_resource(
     http_req: HttpRequest,
@@ -292,7 +438,7 @@ async fn review_resource(
 ) -> Result<HttpResponse, actix_web::Error> {
     
     // 验证用户权限：管理员或元老
-    let user = match AuthHelper::verify_user(&http_req) {
+    let user = match AuthHelper::verify_user(&http_req).await {
         Ok(user) => user,
         Err(e) => return Ok(e.to_response()),
     };
@@ -365,7 +511,7 @@ async fn review_resource(
         requirements: None,
         included_files: None,
         // 管理员字段
-        author: None,
+        owner_id: None,
     };
     
     match package_service.update_package(resource_id, &update_req).await {
@@ -393,7 +539,7 @@ async fn get_pending_resources(
     use crate::utils::auth_helper::AuthHelper;
     
     // 验证用户权限：管理员或元老
-    let user = match AuthHelper::verify_user(&http_req) {
+    let user = match AuthHelper::verify_user(&http_req).await {
         Ok(user) => user,
         Err(e) => return Ok(e.to_response()),
     };
@@ -437,6 +583,9 @@ async fn get_pending_resources(
 async fn get_packages(
     http_req: HttpRequest,
     package_service: web::Data<PackageService>,
+    render_service: web::Data<RenderService>,
+    history_service: web::Data<EditHistoryService>,
+    category_service: web::Data<CategoryService>,
     query: web::Query<PackageQueryParams>,
 ) -> Result<HttpResponse, actix_web::Error> {
     
@@ -488,8 +637,8 @@ async fn get_packages(
                         map.insert("comment_count".to_string(), json!(comments));
                     }
                     // 作者信息
-                    if let Some(repo) = &user_repo {
-                        if let Ok(Some(u)) = repo.find_by_username(&p.author).await {
+                    if let (Some(repo), Some(owner_id)) = (&user_repo, p.owner_id) {
+                        if let Ok(Some(u)) = repo.find_by_id(owner_id).await {
                             let name = u.nickname.clone().unwrap_or(u.username.clone());
                             let mut avatar = u.avatar_url.clone().unwrap_or_default();
                             if let Some(bp) = base_prefix_opt.as_ref() {
@@ -553,6 +702,10 @@ async fn get_packages(
                 }
                 enriched.push(v);
             }
+            // 列表摘要（纯文本）
+            render_service.attach_excerpts("Package", FIELD_PACKAGE_DESCRIPTION, "description", &mut enriched).await;
+            history_service.attach_markers("Package", &mut enriched).await;
+            category_service.attach_paths(&mut enriched).await;
             Ok(HttpResponse::Ok().json(json!({
                 "code": 0,
                 "message": "success",
@@ -579,6 +732,9 @@ async fn get_package(
     http_req: HttpRequest,
     path: web::Path<i32>,
     package_service: web::Data<PackageService>,
+    render_service: web::Data<RenderService>,
+    history_service: web::Data<EditHistoryService>,
+    category_service: web::Data<CategoryService>,
 ) -> Result<HttpResponse, actix_web::Error> {
     let package_id = path.into_inner();
 
@@ -605,12 +761,19 @@ async fn get_package(
 
     // 权限检查：普通用户/游客只能访问 Active 资源；管理员和元老可以访问任何资源
     let mut is_admin_or_elder = false;
-    if let Ok(user) = AuthHelper::verify_user(&http_req) {
+    if let Ok(user) = AuthHelper::verify_user(&http_req).await {
         if matches!(user.role, crate::models::UserRole::Admin | crate::models::UserRole::Elder) {
             is_admin_or_elder = true;
         }
     }
 
+    // 回收站中的资源对普通用户视为不存在
+    if !is_admin_or_elder && matches!(package.status, crate::models::PackageStatus::Deleted) {
+        return Ok(HttpResponse::NotFound().json(json!({
+            "code": 404,
+            "message": "绳包不存在"
+        })));
+    }
     if !is_admin_or_elder && !matches!(package.status, crate::models::PackageStatus::Active) {
         return Ok(HttpResponse::Forbidden().json(json!({
             "code": 403,
@@ -620,13 +783,19 @@ async fn get_package(
 
     // 记录访问量（只有已审核的资源才记录）
     if matches!(package.status, crate::models::PackageStatus::Active) {
-        let user_id = AuthHelper::verify_user(&http_req).ok().map(|u| u.id);
+        let user_id = AuthHelper::verify_user(&http_req).await.ok().map(|u| u.id);
         let ip_address = http_req.connection_info().realip_remote_addr().map(|s| s.to_string());
         let user_agent = http_req.headers().get("User-Agent").and_then(|h| h.to_str().ok()).map(|s| s.to_string());
+        // 来源：前端传入的 ?ref=（如 search、feed），否则取 Referer 的域名
+        let source = web::Query::<std::collections::HashMap<String, String>>::from_query(http_req.query_string())
+            .ok()
+            .and_then(|q| q.get("ref").cloned());
+        let referer = http_req.headers().get("Referer").and_then(|h| h.to_str().ok());
+        let referrer = crate::utils::client_info::view_source(source.as_deref(), referer);
         
         // 移除：页面访问不进行风险评估
         // 安全检测通过，正常记录浏览量
-        let _ = package_service.record_view(package_id, user_id, ip_address, user_agent).await;
+        let _ = package_service.record_view(package_id, user_id, ip_address, user_agent, referrer).await;
     }
 
     // 将package序列化为JSON，并添加 files 字段作为 included_files 的别名，便于前端兼容
@@ -659,8 +828,8 @@ async fn get_package(
         }
 
         // 补充作者昵称与头像
-        if let Ok(repo) = UserRepository::new("data.db") {
-            if let Ok(Some(u)) = repo.find_by_username(&package.author).await {
+        if let (Ok(repo), Some(owner_id)) = (UserRepository::new("data.db"), package.owner_id) {
+            if let Ok(Some(u)) = repo.find_by_id(owner_id).await {
                 let name = u.nickname.clone().unwrap_or(u.username.clone());
                 let mut avatar = u.avatar_url.clone().unwrap_or_default();
                 if let Some(bp) = base_prefix_opt.as_ref() {
@@ -674,6 +843,15 @@ async fn get_package(
             }
         }
 
+        // 服务端渲染的描述 HTML 与摘要，原始 description 保持不变供编辑使用
+        let rendered = render_service.render_package(&package).await;
+        let description_html = match base_prefix_opt.as_ref() {
+            Some(bp) => crate::utils::markdown::absolutize_uploads(&rendered.description_html, bp),
+            None => rendered.description_html,
+        };
+        map.insert("description_html".to_string(), json!(description_html));
+        map.insert("excerpt".to_string(), json!(rendered.excerpt));
+
         // 统一将资源内的URL转换为绝对URL
         if let Some(bp) = base_prefix_opt.as_ref() {
             // file_url
@@ -758,6 +936,9 @@ async fn get_package(
         }
     }
 
+    history_service.attach_markers("Package", std::slice::from_mut(&mut pkg_value)).await;
+    category_service.attach_paths(std::slice::from_mut(&mut pkg_value)).await;
+
     Ok(HttpResponse::Ok().json(json!({
             "code": 0,
             "message": "success",
@@ -771,6 +952,8 @@ async fn user_submit_resource(
     req: web::Json<CreateResourceRequest>,
     package_service: web::Data<PackageService>,
     system_repo: web::Data<SystemRepository>,
+    category_service: web::Data<CategoryService>,
+    tag_service: web::Data<TagService>,
 ) -> Result<HttpResponse, actix_web::Error> {
     // 验证用户认证
     let user = require_auth!(&http_req);
@@ -799,16 +982,39 @@ async fn user_submit_resource(
     } else {
         None
     };
+
+    // 分类的发布权限
+    if let Err(e) = category_service.check_can_post(category_id, &user.role).await {
+        return Ok(HttpResponse::Forbidden().json(json!({
+            "code": 403,
+            "message": e.to_string()
+        })));
+    }
+
+    // 标签规范化、别名替换与白名单检查
+    let tags = match req.tags.as_ref() {
+        Some(tags) => match tag_service.check_tags(tags, None).await {
+            Ok(tags) => Some(tags),
+            Err(e) => {
+                return Ok(HttpResponse::BadRequest().json(json!({
+                    "code": 400,
+                    "message": e.to_string()
+                })));
+            }
+        },
+        None => None,
+    };
     
     // 创建资源记录请求，自动设置作者为当前用户
     let create_req = CreatePackageRequest {
         name: req.title.clone(),
         author: user.username.clone(), // 自动使用当前用户名
+        owner_id: Some(user.id),
         version: None,
         description: req.description.clone(),
         category_id,
         file_url: if req.file_url.is_empty() { None } else { Some(req.file_url.clone()) },
-        tags: req.tags.clone(),
+        tags,
         is_pinned: None,
         is_featured: None,
         // 新增字段
@@ -819,11 +1025,19 @@ async fn user_submit_resource(
     };
     
     match package_service.create_package(&create_req).await {
-        Ok(package) => Ok(HttpResponse::Ok().json(json!({
-            "code": 0,
-            "message": "资源提交成功，等待审核",
-            "data": package
-        }))),
+        Ok(package) => {
+            // 分类无需审核时直接上架
+            let package = category_service.apply_review_rule(package.clone()).await.unwrap_or_else(|e| {
+                log::warn!("应用分类审核规则失败: {}", e);
+                package
+            });
+            let message = if package.status == crate::models::PackageStatus::Active { "资源提交成功" } else { "资源提交成功，等待审核" };
+            Ok(HttpResponse::Ok().json(json!({
+                "code": 0,
+                "message": message,
+                "data": package
+            })))
+        },
         Err(e) => Ok(HttpResponse::BadRequest().json(json!({
             "code": 400,
             "message": format!("提交失败: {}", e)
@@ -836,13 +1050,14 @@ async fn admin_create_package(
     http_req: HttpRequest,
     req: web::Json<CreatePackageRequest>,
     package_service: web::Data<PackageService>,
+    tag_service: web::Data<TagService>,
 ) -> Result<HttpResponse, actix_web::Error> {
     use crate::utils::auth_helper::AuthHelper;
     
     log::debug!("🔍 admin_create_package called with data: {:?}", req);
     
     // 验证管理员权限
-    let user = match AuthHelper::verify_user(&http_req) {
+    let user = match AuthHelper::verify_user(&http_req).await {
         Ok(user) => {
             log::debug!("🔍 User verified: {:?}", user.username);
             user
@@ -876,6 +1091,19 @@ async fn admin_create_package(
         }
     }
     
+    let mut req = req.into_inner();
+    if let Some(tags) = req.tags.as_ref() {
+        match tag_service.check_tags(tags, None).await {
+            Ok(tags) => req.tags = Some(tags),
+            Err(e) => {
+                return Ok(HttpResponse::BadRequest().json(json!({
+                    "code": 400,
+                    "message": e.to_string()
+                })));
+            }
+        }
+    }
+
     log::debug!("🔍 URL validation passed, calling package_service.create_package");
     
     match package_service.create_package(&req).await {
@@ -898,16 +1126,42 @@ async fn admin_create_package(
     }
 }
 
+/// 更新请求中除描述与状态外是否还有字段与当前取值不同
+fn changes_beyond_description(package: &crate::models::Package, req: &UpdatePackageRequest) -> bool {
+    fn differs<T: serde::Serialize, U: serde::Serialize>(new: Option<&T>, current: &U) -> bool {
+        new.is_some_and(|new| serde_json::to_value(new).ok() != serde_json::to_value(current).ok())
+    }
+    differs(req.name.as_ref(), &package.name)
+        || differs(req.version.as_ref(), &package.version)
+        || differs(req.category_id.as_ref(), &package.category_id)
+        || differs(req.file_url.as_ref(), &package.file_url)
+        || differs(req.file_size.as_ref(), &package.file_size)
+        || differs(req.tags.as_ref(), &package.tags)
+        || differs(req.is_pinned.as_ref(), &package.is_pinned)
+        || differs(req.is_featured.as_ref(), &package.is_featured)
+        || differs(req.reviewer_id.as_ref(), &package.reviewer_id)
+        || differs(req.reviewed_at.as_ref(), &package.reviewed_at)
+        || differs(req.review_comment.as_ref(), &package.review_comment)
+        || differs(req.screenshots.as_ref(), &package.screenshots)
+        || differs(req.cover_image.as_ref(), &package.cover_image)
+        || differs(req.requirements.as_ref(), &package.requirements)
+        || differs(req.included_files.as_ref(), &package.included_files)
+        || differs(req.owner_id.as_ref(), &package.owner_id)
+}
+
 async fn update_package(
     http_req: HttpRequest,
     path: web::Path<i32>,
     req: web::Json<UpdatePackageRequest>,
     package_service: web::Data<PackageService>,
+    maintainer_service: web::Data<PackageMaintainerService>,
+    history_service: web::Data<EditHistoryService>,
+    category_service: web::Data<CategoryService>,
 ) -> Result<HttpResponse, actix_web::Error> {
     let package_id = path.into_inner();
 
     // 获取调用用户
-    let user = match AuthHelper::verify_user(&http_req) {
+    let user = match AuthHelper::verify_user(&http_req).await {
         Ok(u) => u,
         Err(e) => return Ok(e.to_response()),
     };
@@ -926,35 +1180,89 @@ async fn update_package(
     };
 
     let is_admin = matches!(user.role, crate::models::UserRole::Admin | crate::models::UserRole::Elder);
-    let is_owner = package.author == user.username;
+    let can_edit = maintainer_service.can_edit(&package, user.id).await.map_err(|e| {
+        actix_web::error::ErrorInternalServerError(e.to_string())
+    })?;
 
-    // 权限校验
-    if !is_admin && !is_owner {
+    // 权限校验：所有者、维护者或管理员
+    if !is_admin && !can_edit {
         return Ok(HttpResponse::Forbidden().json(json!({
             "code": 403,
             "message": "无权限更新该资源"
         })));
     }
 
-    // 如果非管理员/元老更新资源，则强制重新进入待审核状态
+    // 非管理员/元老更新资源一律重新进入待审核，唯一例外是已上架资源只对描述做了小的修正；
+    // 所在分类不要求审核时不改变状态
     let mut override_req = req.into_inner();
+    let category_changed = override_req.category_id.is_some_and(|id| Some(id) != package.category_id);
+    if let Some(category_id) = override_req.category_id.filter(|_| category_changed) {
+        if let Err(e) = category_service.get(category_id).await {
+            return Ok(HttpResponse::BadRequest().json(json!({
+                "code": 400,
+                "message": e.to_string()
+            })));
+        }
+    }
+    // 标签规范化、别名替换与白名单检查（已关联的标签可保留）
+    let tag_service = http_req.app_data::<web::Data<TagService>>().cloned();
+    if let (Some(tag_service), Some(tags)) = (tag_service, override_req.tags.as_ref()) {
+        match tag_service.check_tags(tags, Some((TagTarget::Package, package_id))).await {
+            Ok(tags) => override_req.tags = Some(tags),
+            Err(e) => {
+                return Ok(HttpResponse::BadRequest().json(json!({
+                    "code": 400,
+                    "message": e.to_string()
+                })));
+            }
+        }
+    }
     if !is_admin {
-        override_req.status = Some(crate::models::PackageStatus::Pending);
-        // 非管理员不能修改作者
-        if override_req.author.is_some() {
+        if category_changed {
+            if let Err(e) = category_service.check_can_post(override_req.category_id, &user.role).await {
+                return Ok(HttpResponse::Forbidden().json(json!({
+                    "code": 403,
+                    "message": e.to_string()
+                })));
+            }
+        }
+        // 置顶、精选与审核信息只能由管理员设置，非管理员提交的值直接忽略
+        override_req.is_pinned = None;
+        override_req.is_featured = None;
+        override_req.reviewer_id = None;
+        override_req.reviewed_at = None;
+        override_req.review_comment = None;
+        let target_category = override_req.category_id.or(package.category_id);
+        let requires_review = category_service.rules(target_category).await.map(|r| r.requires_review).unwrap_or(true);
+        let minor_description_fix = package.status == crate::models::PackageStatus::Active
+            && !changes_beyond_description(&package, &override_req)
+            && !override_req.description.as_deref().is_some_and(|new_desc| {
+                crate::utils::diff::is_material_edit(package.description.as_deref().unwrap_or(""), new_desc)
+            });
+        let needs_review = requires_review && !minor_description_fix;
+        override_req.status = needs_review.then_some(crate::models::PackageStatus::Pending);
+        // 非管理员不能直接更换所有者
+        if override_req.owner_id.is_some() {
             return Ok(HttpResponse::Forbidden().json(json!({
                 "code": 403,
-                "message": "只有管理员才能修改资源包作者"
+                "message": "请使用所有权转让更换资源所有者"
             })));
         }
     }
 
-    match package_service.update_package(package_id, &override_req).await {
-        Ok(package) => Ok(HttpResponse::Ok().json(json!({
-            "code": 0,
-            "message": "绳包更新成功，等待审核",
-            "data": package
-        }))),
+    // 编辑前快照，编辑历史与更新在同一事务中写入
+    let revision = history_service.pending_revision("Package", package_id, user.id).await;
+    let pending_review = override_req.status == Some(crate::models::PackageStatus::Pending);
+    // 分类变更时文件目录随之移动，移动失败则整个更新不生效
+    match package_service.update_package_recorded(package_id, &override_req, revision.as_ref()).await {
+        Ok(package) => {
+            let message = if pending_review { "绳包更新成功，等待审核" } else { "绳包更新成功" };
+            Ok(HttpResponse::Ok().json(json!({
+                "code": 0,
+                "message": message,
+                "data": package
+            })))
+        },
         Err(e) => Ok(HttpResponse::BadRequest().json(json!({
             "code": 400,
             "message": e.to_string()
@@ -963,15 +1271,52 @@ async fn update_package(
 }
 
 async fn delete_package(
+    req: HttpRequest,
     path: web::Path<i32>,
     package_service: web::Data<PackageService>,
+    points_service: web::Data<crate::services::points_service::PointsService>,
 ) -> Result<HttpResponse, actix_web::Error> {
+    let user = require_auth!(&req);
     let package_id = path.into_inner();
-    match package_service.delete_package(package_id).await {
-        Ok(_) => Ok(HttpResponse::Ok().json(json!({
-            "code": 0,
-            "message": "绳包删除成功"
-        }))),
+
+    // 只有所有者或管理员可以删除，维护者不能删除
+    match package_service.get_package_by_id(package_id).await {
+        Ok(Some(package)) if !matches!(package.status, crate::models::PackageStatus::Deleted) => {
+            let is_admin = matches!(user.role, crate::models::UserRole::Admin | crate::models::UserRole::Elder);
+            if !is_admin && !PackageMaintainerService::is_owner(&package, user.id) {
+                return Ok(HttpResponse::Forbidden().json(json!({
+                    "code": 403,
+                    "message": "只有资源所有者或管理员可以删除资源"
+                })));
+            }
+        },
+        Ok(_) => {
+            return Ok(HttpResponse::NotFound().json(json!({
+                "code": 404,
+                "message": "资源不存在"
+            })));
+        },
+        Err(e) => {
+            return Ok(HttpResponse::InternalServerError().json(json!({
+                "code": 500,
+                "message": e.to_string()
+            })));
+        }
+    }
+    let owner_id = points_service.content_owner("Package", package_id).await.ok().flatten();
+    match package_service.delete_package(package_id, user.id).await {
+        Ok(_) => {
+            // 收回该资源获得的积分，管理员删除他人资源时追加处罚
+            if let Some(owner_id) = owner_id {
+                if let Err(e) = points_service.on_content_removed("Package", package_id, owner_id, Some(user.id)).await {
+                    log::error!("处理删除积分失败: {}", e);
+                }
+            }
+            Ok(HttpResponse::Ok().json(json!({
+                "code": 0,
+                "message": "绳包删除成功"
+            })))
+        },
         Err(e) => Ok(HttpResponse::InternalServerError().json(json!({
             "code": 500,
             "message": e.to_string()
@@ -987,7 +1332,7 @@ async fn download_package(
     let package_id = path.into_inner();
     
     // 获取用户信息
-    let user_id = crate::utils::auth_helper::AuthHelper::extract_user_id(&req);
+    let user_id = crate::utils::auth_helper::AuthHelper::extract_user_id(&req).await;
     
     // 获取IP地址
     let connection_info = req.connection_info();
@@ -1078,6 +1423,8 @@ async fn upload_package_file(
     path: web::Path<i32>,
     mut payload: actix_multipart::Multipart,
     package_service: web::Data<PackageService>,
+    maintainer_service: web::Data<PackageMaintainerService>,
+    category_service: web::Data<CategoryService>,
 ) -> Result<HttpResponse, actix_web::Error> {
     // 验证用户认证
     let user = require_auth!(&http_req);
@@ -1088,11 +1435,12 @@ async fn upload_package_file(
     // 检查包是否存在且用户有权限
     match package_service.get_package_by_id(package_id).await {
         Ok(Some(package)) => {
-            // 检查权限：只有作者或管理员可以上传文件
-            if package.author != user.username && !matches!(user.role, crate::models::UserRole::Admin | crate::models::UserRole::Elder) {
+            // 检查权限：只有所有者、维护者或管理员可以上传文件
+            let is_admin = matches!(user.role, crate::models::UserRole::Admin | crate::models::UserRole::Elder);
+            if !is_admin && !maintainer_service.can_edit(&package, user.id).await.unwrap_or(false) {
                 return Ok(HttpResponse::Forbidden().json(json!({
                     "code": 403,
-                    "message": "只有资源作者或管理员可以上传文件"
+                    "message": "只有资源所有者、维护者或管理员可以上传文件"
                 })));
             }
         },
@@ -1145,6 +1493,14 @@ async fn upload_package_file(
             "message": "没有接收到文件数据"
         })));
     }
+
+    // 分类允许的扩展名与文件大小
+    if let Err(e) = category_service.check_upload(package_id, &file_name, file_data.len()).await {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "code": 400,
+            "message": e.to_string()
+        })));
+    }
     
     // 上传文件到存储服务
     match package_service.upload_package_file(package_id, &file_name, file_data).await {
@@ -1191,11 +1547,12 @@ async fn get_package_comments(
     path: web::Path<i32>,
     query: web::Query<crate::api::v1::comment::CommentQueryParams>,
     comment_service: web::Data<CommentService>,
+    history_service: web::Data<EditHistoryService>,
 ) -> Result<HttpResponse, actix_web::Error> {
     let package_id = path.into_inner();
     let page = query.page.unwrap_or(1);
     let size = query.size.unwrap_or(20);
-    match comment_service.get_package_comments(package_id, page, size).await {
+    match comment_service.get_package_comments(package_id, page, size, AuthHelper::extract_user_id(&http_req).await).await {
         Ok((mut comments, total)) => {
             // 计算前缀（优先 PUBLIC_BASE_URL，否则从请求推断）
             let cfg = crate::config::Config::load().unwrap_or_default();
@@ -1217,11 +1574,13 @@ async fn get_package_comments(
                     }
                 }
             }
+            let mut list: Vec<serde_json::Value> = comments.iter().map(|c| json!(c)).collect();
+            history_service.attach_markers("Comment", &mut list).await;
             Ok(HttpResponse::Ok().json(json!({
                 "code": 0,
                 "message": "success",
                 "data": {
-                  "list": comments,
+                  "list": list,
                   "total": total,
                   "page": page,
                   "size": size
@@ -1240,7 +1599,7 @@ async fn like_package(
     path: web::Path<i32>,
     package_service: web::Data<PackageService>,
 ) -> Result<HttpResponse, actix_web::Error> {
-    let user = match AuthHelper::verify_user(&http_req) { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
+    let user = match AuthHelper::verify_user(&http_req).await { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
     let package_id = path.into_inner();
     match package_service.like_package(user.id, package_id).await {
         Ok(count) => Ok(HttpResponse::Ok().json(json!({"code":0, "message":"success", "data": {"like_count": count}}))),
@@ -1253,7 +1612,7 @@ async fn unlike_package(
     path: web::Path<i32>,
     package_service: web::Data<PackageService>,
 ) -> Result<HttpResponse, actix_web::Error> {
-    let user = match AuthHelper::verify_user(&http_req) { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
+    let user = match AuthHelper::verify_user(&http_req).await { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
     let package_id = path.into_inner();
     match package_service.unlike_package(user.id, package_id).await {
         Ok(count) => Ok(HttpResponse::Ok().json(json!({"code":0, "message":"success", "data": {"like_count": count}}))),
@@ -1266,7 +1625,7 @@ async fn check_like_status(
     path: web::Path<i32>,
     package_service: web::Data<PackageService>,
 ) -> Result<HttpResponse, actix_web::Error> {
-    let user = match AuthHelper::verify_user(&http_req) { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
+    let user = match AuthHelper::verify_user(&http_req).await { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
     let package_id = path.into_inner();
     match package_service.check_like_status(user.id, package_id).await {
         Ok(is_liked) => Ok(HttpResponse::Ok().json(json!({"code":0, "message":"success", "data": {"liked": is_liked}}))),
@@ -0,0 +1,49 @@
+use actix_web::{http::header, web, HttpResponse, ResponseError};
+use serde_json::json;
+
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::package_analytics::AnalyticsQuery;
+use crate::services::package_analytics_service::PackageAnalyticsService;
+use crate::utils::error::ServiceError;
+
+// 路由注册在 package.rs（/packages 与 /resources 作用域）
+
+
+fn is_admin(user: &AuthenticatedUser) -> bool {
+    matches!(user.role, crate::models::UserRole::Admin | crate::models::UserRole::Elder)
+}
+
+/// 资源数据分析（所有者、维护者或管理员）：?days= 统计最近天数，默认 30，最多 365
+pub async fn get_analytics(
+    user: AuthenticatedUser,
+    path: web::Path<i32>,
+    query: web::Query<AnalyticsQuery>,
+    analytics_service: web::Data<PackageAnalyticsService>,
+) -> HttpResponse {
+    match analytics_service
+        .get_analytics(path.into_inner(), query.days, user.id, is_admin(&user))
+        .await
+    {
+        Ok(analytics) => HttpResponse::Ok().json(json!({"code": 0, "message": "success", "data": analytics})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+/// 导出每日数据为 CSV
+pub async fn export_analytics_csv(
+    user: AuthenticatedUser,
+    path: web::Path<i32>,
+    query: web::Query<AnalyticsQuery>,
+    analytics_service: web::Data<PackageAnalyticsService>,
+) -> HttpResponse {
+    match analytics_service
+        .export_csv(path.into_inner(), query.days, user.id, is_admin(&user))
+        .await
+    {
+        Ok((filename, csv)) => HttpResponse::Ok()
+            .content_type("text/csv; charset=utf-8")
+            .insert_header((header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)))
+            .body(csv),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
@@ -0,0 +1,64 @@
+use actix_web::{web, HttpResponse, ResponseError};
+use serde_json::json;
+
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::package_dependency::{ResolveQuery, UpdateDependenciesRequest};
+use crate::services::package_dependency_service::PackageDependencyService;
+use crate::utils::error::ServiceError;
+
+// 路由注册在 package.rs（/packages 与 /resources 作用域）
+
+
+fn is_admin(user: &AuthenticatedUser) -> bool {
+    matches!(user.role, crate::models::UserRole::Admin | crate::models::UserRole::Elder)
+}
+
+/// 资源声明的依赖与兼容的结绳版本
+pub async fn get_dependencies(
+    path: web::Path<i32>,
+    dependency_service: web::Data<PackageDependencyService>,
+) -> HttpResponse {
+    match dependency_service.get(path.into_inner()).await {
+        Ok(info) => HttpResponse::Ok().json(json!({"code": 0, "message": "success", "data": info})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+/// 整体替换依赖与兼容版本（所有者、维护者或管理员）
+pub async fn update_dependencies(
+    user: AuthenticatedUser,
+    path: web::Path<i32>,
+    body: web::Json<UpdateDependenciesRequest>,
+    dependency_service: web::Data<PackageDependencyService>,
+) -> HttpResponse {
+    match dependency_service
+        .update(user.id, is_admin(&user), path.into_inner(), body.into_inner())
+        .await
+    {
+        Ok(info) => HttpResponse::Ok().json(json!({"code": 0, "message": "依赖已更新", "data": info})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+/// 解析完整安装集合：?app_version= 检查结绳版本兼容，?include_optional=true 包含可选依赖
+pub async fn resolve_dependencies(
+    path: web::Path<i32>,
+    query: web::Query<ResolveQuery>,
+    dependency_service: web::Data<PackageDependencyService>,
+) -> HttpResponse {
+    match dependency_service.resolve(path.into_inner(), &query).await {
+        Ok(resolution) => HttpResponse::Ok().json(json!({"code": 0, "message": "success", "data": resolution})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+/// 依赖该资源的其他资源
+pub async fn get_dependents(
+    path: web::Path<i32>,
+    dependency_service: web::Data<PackageDependencyService>,
+) -> HttpResponse {
+    match dependency_service.dependents(path.into_inner()).await {
+        Ok(list) => HttpResponse::Ok().json(json!({"code": 0, "message": "success", "data": list})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
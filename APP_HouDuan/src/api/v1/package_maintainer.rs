@@ -0,0 +1,130 @@
+use actix_web::{web, HttpResponse, ResponseError};
+use serde_json::json;
+
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::package_maintainer::{AddMaintainerRequest, TransferOwnershipRequest};
+use crate::services::package_maintainer_service::PackageMaintainerService;
+use crate::utils::error::ServiceError;
+
+// 路由注册在 package.rs（/packages 与 /resources 作用域）与 user.rs（/me 作用域）
+
+
+fn is_admin(user: &AuthenticatedUser) -> bool {
+    matches!(user.role, crate::models::UserRole::Admin | crate::models::UserRole::Elder)
+}
+
+/// 资源的所有者与维护者
+pub async fn list_maintainers(
+    path: web::Path<i32>,
+    maintainer_service: web::Data<PackageMaintainerService>,
+) -> HttpResponse {
+    match maintainer_service.list_maintainers(path.into_inner()).await {
+        Ok(list) => HttpResponse::Ok().json(json!({"code": 0, "message": "success", "data": list})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+pub async fn add_maintainer(
+    user: AuthenticatedUser,
+    path: web::Path<i32>,
+    body: web::Json<AddMaintainerRequest>,
+    maintainer_service: web::Data<PackageMaintainerService>,
+) -> HttpResponse {
+    match maintainer_service
+        .add_maintainer(user.id, is_admin(&user), path.into_inner(), body.user_id)
+        .await
+    {
+        Ok(list) => HttpResponse::Ok().json(json!({"code": 0, "message": "已添加维护者", "data": list})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+/// 移除维护者；维护者移除自己即退出维护
+pub async fn remove_maintainer(
+    user: AuthenticatedUser,
+    path: web::Path<(i32, i32)>,
+    maintainer_service: web::Data<PackageMaintainerService>,
+) -> HttpResponse {
+    let (package_id, user_id) = path.into_inner();
+    match maintainer_service
+        .remove_maintainer(user.id, is_admin(&user), package_id, user_id)
+        .await
+    {
+        Ok(_) => HttpResponse::Ok().json(json!({"code": 0, "message": "已移除维护者"})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+/// 资源当前待对方确认的转让
+pub async fn get_pending_transfer(
+    user: AuthenticatedUser,
+    path: web::Path<i32>,
+    maintainer_service: web::Data<PackageMaintainerService>,
+) -> HttpResponse {
+    match maintainer_service
+        .pending_transfer(user.id, is_admin(&user), path.into_inner())
+        .await
+    {
+        Ok(transfer) => HttpResponse::Ok().json(json!({"code": 0, "message": "success", "data": transfer})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+pub async fn request_transfer(
+    user: AuthenticatedUser,
+    path: web::Path<i32>,
+    body: web::Json<TransferOwnershipRequest>,
+    maintainer_service: web::Data<PackageMaintainerService>,
+) -> HttpResponse {
+    match maintainer_service
+        .request_transfer(user.id, path.into_inner(), body.to_user_id)
+        .await
+    {
+        Ok(transfer) => HttpResponse::Ok().json(json!({"code": 0, "message": "已发起转让，等待对方确认", "data": transfer})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+pub async fn cancel_transfer(
+    user: AuthenticatedUser,
+    path: web::Path<i32>,
+    maintainer_service: web::Data<PackageMaintainerService>,
+) -> HttpResponse {
+    match maintainer_service.cancel_transfer(user.id, path.into_inner()).await {
+        Ok(_) => HttpResponse::Ok().json(json!({"code": 0, "message": "已取消转让"})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+/// 转给我的待确认转让
+pub async fn get_my_incoming_transfers(
+    user: AuthenticatedUser,
+    maintainer_service: web::Data<PackageMaintainerService>,
+) -> HttpResponse {
+    match maintainer_service.list_incoming(user.id).await {
+        Ok(list) => HttpResponse::Ok().json(json!({"code": 0, "message": "success", "data": list})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+pub async fn accept_transfer(
+    user: AuthenticatedUser,
+    path: web::Path<i32>,
+    maintainer_service: web::Data<PackageMaintainerService>,
+) -> HttpResponse {
+    match maintainer_service.accept_transfer(user.id, path.into_inner()).await {
+        Ok(transfer) => HttpResponse::Ok().json(json!({"code": 0, "message": "已接受转让", "data": transfer})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+pub async fn decline_transfer(
+    user: AuthenticatedUser,
+    path: web::Path<i32>,
+    maintainer_service: web::Data<PackageMaintainerService>,
+) -> HttpResponse {
+    match maintainer_service.decline_transfer(user.id, path.into_inner()).await {
+        Ok(_) => HttpResponse::Ok().json(json!({"code": 0, "message": "已拒绝转让"})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
@@ -0,0 +1,117 @@
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use serde_json::json;
+
+use crate::models::points::{AdjustPointsRequest, LedgerQuery, UpdatePointRuleRequest, UserLevel};
+use crate::require_admin;
+use crate::services::points_service::PointsService;
+use crate::utils::auth_helper::AuthHelper;
+use crate::utils::error::ServiceError;
+
+// 路由注册在 /users、/me 与 /admin 作用域内（见 user.rs、admin.rs）
+
+
+/// 我的等级与升级进度
+pub async fn get_my_level(
+    req: HttpRequest,
+    points_service: web::Data<PointsService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let user = match AuthHelper::verify_user(&req).await { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
+    match points_service.level_progress(user.id).await {
+        Ok(progress) => Ok(HttpResponse::Ok().json(json!({"code": 0, "message": "success", "data": progress}))),
+        Err(e) => Ok(ServiceError::from(e).error_response()),
+    }
+}
+
+/// 我的经验/星星流水
+pub async fn get_my_ledger(
+    req: HttpRequest,
+    query: web::Query<LedgerQuery>,
+    points_service: web::Data<PointsService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let user = match AuthHelper::verify_user(&req).await { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
+    match points_service.ledger(user.id, &query).await {
+        Ok((list, total)) => Ok(HttpResponse::Ok().json(json!({
+            "code": 0,
+            "message": "success",
+            "data": {
+                "list": list,
+                "total": total,
+                "page": query.page.unwrap_or(1).max(1),
+                "page_size": query.page_size.unwrap_or(20).clamp(1, 100)
+            }
+        }))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(json!({"code": 500, "message": e.to_string()}))),
+    }
+}
+
+/// 用户公开的等级信息
+pub async fn get_user_level(
+    path: web::Path<i32>,
+    points_service: web::Data<PointsService>,
+) -> HttpResponse {
+    match points_service.level_progress(path.into_inner()).await {
+        Ok(progress) => HttpResponse::Ok().json(json!({"code": 0, "message": "success", "data": progress})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+pub async fn admin_list_rules(
+    req: HttpRequest,
+    points_service: web::Data<PointsService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let _ = require_admin!(&req);
+    match points_service.list_rules().await {
+        Ok(list) => Ok(HttpResponse::Ok().json(json!({"code": 0, "message": "success", "data": list}))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(json!({"code": 500, "message": e.to_string()}))),
+    }
+}
+
+pub async fn admin_update_rule(
+    req: HttpRequest,
+    path: web::Path<String>,
+    body: web::Json<UpdatePointRuleRequest>,
+    points_service: web::Data<PointsService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let _ = require_admin!(&req);
+    match points_service.update_rule(&path.into_inner(), body.into_inner()).await {
+        Ok(rule) => Ok(HttpResponse::Ok().json(json!({"code": 0, "message": "更新成功", "data": rule}))),
+        Err(e) => Ok(ServiceError::from(e).error_response()),
+    }
+}
+
+pub async fn admin_list_levels(
+    req: HttpRequest,
+    points_service: web::Data<PointsService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let _ = require_admin!(&req);
+    match points_service.list_levels().await {
+        Ok(list) => Ok(HttpResponse::Ok().json(json!({"code": 0, "message": "success", "data": list}))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(json!({"code": 500, "message": e.to_string()}))),
+    }
+}
+
+pub async fn admin_update_levels(
+    req: HttpRequest,
+    body: web::Json<Vec<UserLevel>>,
+    points_service: web::Data<PointsService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let _ = require_admin!(&req);
+    match points_service.update_levels(body.into_inner()).await {
+        Ok(list) => Ok(HttpResponse::Ok().json(json!({"code": 0, "message": "更新成功", "data": list}))),
+        Err(e) => Ok(ServiceError::from(e).error_response()),
+    }
+}
+
+/// 管理员手动增减用户经验/星星
+pub async fn admin_adjust_points(
+    req: HttpRequest,
+    path: web::Path<i32>,
+    body: web::Json<AdjustPointsRequest>,
+    points_service: web::Data<PointsService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let _ = require_admin!(&req);
+    match points_service.adjust(path.into_inner(), body.into_inner()).await {
+        Ok(entry) => Ok(HttpResponse::Ok().json(json!({"code": 0, "message": "调整成功", "data": entry}))),
+        Err(e) => Ok(ServiceError::from(e).error_response()),
+    }
+}
@@ -1,6 +1,12 @@
 use actix_web::{web, HttpRequest, HttpResponse};
 use serde_json::json;
 use crate::services::post_service::PostService;
+use crate::services::points_service::PointsService;
+use crate::services::render_service::{RenderService, FIELD_POST_CONTENT};
+use crate::services::edit_history_service::EditHistoryService;
+use crate::services::category_service::CategoryService;
+use crate::repositories::tag_repo::TagTarget;
+use crate::services::tag_service::TagService;
 use crate::models::{CreatePostRequest, UpdatePostRequest, PostQueryParams};
 use crate::utils::auth_helper::AuthHelper;
 
@@ -76,6 +82,9 @@ async fn get_posts(
     _http_req: HttpRequest,
     query: web::Query<PostQueryParams>,
     post_service: web::Data<PostService>,
+    render_service: web::Data<RenderService>,
+    history_service: web::Data<EditHistoryService>,
+    category_service: web::Data<CategoryService>,
 ) -> Result<HttpResponse, actix_web::Error> {
     let mut q = query.into_inner();
     // 默认仅显示已发布 + 审核通过的帖子
@@ -131,6 +140,10 @@ async fn get_posts(
             } else {
                 enriched = response.list.into_iter().map(|p| serde_json::to_value(p).unwrap_or_else(|_| json!({}))).collect();
             }
+            // 列表摘要（纯文本）
+            render_service.attach_excerpts("Post", FIELD_POST_CONTENT, "content", &mut enriched).await;
+            history_service.attach_markers("Post", &mut enriched).await;
+            category_service.attach_paths(&mut enriched).await;
 
             Ok(HttpResponse::Ok().json(json!({
                 "code": 0,
@@ -160,9 +173,11 @@ async fn create_post(
     http_req: HttpRequest,
     req: web::Json<CreatePostRequest>,
     post_service: web::Data<PostService>,
+    category_service: web::Data<CategoryService>,
+    tag_service: web::Data<TagService>,
 ) -> Result<HttpResponse, actix_web::Error> {
     // 验证用户权限
-    let user = match AuthHelper::verify_user(&http_req) {
+    let user = match AuthHelper::verify_user(&http_req).await {
         Ok(user) => user,
         Err(_) => {
             return Ok(HttpResponse::Unauthorized().json(json!({
@@ -172,7 +187,29 @@ async fn create_post(
         }
     };
 
-    match post_service.create_post(req.into_inner(), user.id).await {
+    // 分类的发布权限
+    if let Err(e) = category_service.check_can_post(req.category_id, &user.role).await {
+        return Ok(HttpResponse::Forbidden().json(json!({
+            "code": 403,
+            "message": e.to_string()
+        })));
+    }
+
+    // 标签规范化、别名替换与白名单检查
+    let mut create_req = req.into_inner();
+    if let Some(tags) = create_req.tags.as_ref() {
+        match tag_service.check_tags(tags, None).await {
+            Ok(tags) => create_req.tags = Some(tags),
+            Err(e) => {
+                return Ok(HttpResponse::BadRequest().json(json!({
+                    "code": 400,
+                    "message": e.to_string()
+                })));
+            }
+        }
+    }
+
+    match post_service.create_post(create_req, user.id).await {
         Ok(post_id) => Ok(HttpResponse::Ok().json(json!({
             "code": 0,
             "message": "帖子创建成功",
@@ -195,20 +232,27 @@ async fn get_post(
     http_req: HttpRequest,
     path: web::Path<i32>,
     post_service: web::Data<PostService>,
+    render_service: web::Data<RenderService>,
+    history_service: web::Data<EditHistoryService>,
+    category_service: web::Data<CategoryService>,
 ) -> Result<HttpResponse, actix_web::Error> {
     let post_id = path.into_inner();
     
     match post_service.get_post(post_id).await {
         Ok(Some(mut post)) => {
             // 非管理员访问未审核通过的帖子：返回403
-            let is_admin = AuthHelper::is_admin(&http_req);
+            let is_admin = AuthHelper::is_admin(&http_req).await;
+            // 回收站中的帖子仅管理员可见
+            if !is_admin && post.status == crate::models::PostStatus::Deleted {
+                return Ok(HttpResponse::NotFound().json(json!({ "code": 404, "message": "帖子不存在" })));
+            }
             if !is_admin {
                 if let Some(rs) = &post.review_status { if rs != "approved" { 
                     return Ok(HttpResponse::Forbidden().json(json!({ "code": 403, "message": "帖子未审核通过" })));
                 }}
             }
             // 安全检测并增加浏览量
-            let user_id = AuthHelper::verify_user(&http_req).ok().map(|u| u.id);
+            let user_id = AuthHelper::verify_user(&http_req).await.ok().map(|u| u.id);
             let ip_address = http_req.connection_info().realip_remote_addr().map(|s| s.to_string());
             let user_agent = http_req.headers().get("User-Agent").and_then(|h| h.to_str().ok()).map(|s| s.to_string());
             
@@ -255,11 +299,26 @@ async fn get_post(
                 }
             }
             
+            // 服务端渲染的正文 HTML、代码高亮与摘要，原始 content 保持不变供编辑使用
+            let rendered = render_service.render_post(&post).await;
+            let content_html = match base_prefix.as_ref() {
+                Some(bp) => crate::utils::markdown::absolutize_uploads(&rendered.content_html, bp),
+                None => rendered.content_html,
+            };
+            let mut data = serde_json::to_value(&post).unwrap_or_else(|_| json!({}));
+            if let serde_json::Value::Object(ref mut map) = data {
+                map.insert("content_html".to_string(), json!(content_html));
+                map.insert("code_snippet_html".to_string(), json!(rendered.code_snippet_html));
+                map.insert("excerpt".to_string(), json!(rendered.excerpt));
+            }
+            history_service.attach_markers("Post", std::slice::from_mut(&mut data)).await;
+            category_service.attach_paths(std::slice::from_mut(&mut data)).await;
+            
             Ok(HttpResponse::Ok().json(json!({
                 "code": 0,
                 "message": "success",
                 "msg": "success",
-                "data": post
+                "data": data
             })))
         },
         Ok(None) => Ok(HttpResponse::NotFound().json(json!({
@@ -282,11 +341,14 @@ async fn update_post(
     path: web::Path<i32>,
     req: web::Json<UpdatePostRequest>,
     post_service: web::Data<PostService>,
+    history_service: web::Data<EditHistoryService>,
+    category_service: web::Data<CategoryService>,
+    tag_service: web::Data<TagService>,
 ) -> Result<HttpResponse, actix_web::Error> {
     let post_id = path.into_inner();
     
     // 验证用户权限
-    let user = match AuthHelper::verify_user(&http_req) {
+    let user = match AuthHelper::verify_user(&http_req).await {
         Ok(user) => user,
         Err(_) => {
             return Ok(HttpResponse::Unauthorized().json(json!({
@@ -297,7 +359,7 @@ async fn update_post(
     };
 
     // 检查帖子是否存在
-    match post_service.get_post(post_id).await {
+    let current_category = match post_service.get_post(post_id).await {
         Ok(Some(post)) => {
             // 检查是否是作者或管理员
             if post.author_id != user.id && user.role != crate::models::UserRole::Admin && user.role != crate::models::UserRole::Elder {
@@ -306,6 +368,7 @@ async fn update_post(
                     "message": "无权限修改此帖子"
                 })));
             }
+            post.category_id
         },
         Ok(None) => {
             return Ok(HttpResponse::NotFound().json(json!({
@@ -320,7 +383,7 @@ async fn update_post(
                 "message": "检查帖子失败"
             })));
         }
-    }
+    };
 
     // 权限检查：只有管理员可以修改作者
     let mut update_req = req.into_inner();
@@ -331,12 +394,42 @@ async fn update_post(
             "message": "只有管理员才能修改帖子作者"
         })));
     }
+    // 移到其他分类时检查目标分类的发布权限
+    if let Some(category_id) = update_req.category_id.filter(|id| Some(*id) != current_category) {
+        let allowed = if is_admin {
+            category_service.get(category_id).await.map(|_| ())
+        } else {
+            category_service.check_can_post(Some(category_id), &user.role).await.map(|_| ())
+        };
+        if let Err(e) = allowed {
+            return Ok(HttpResponse::Forbidden().json(json!({
+                "code": 403,
+                "message": e.to_string()
+            })));
+        }
+    }
 
-    match post_service.update_post(post_id, update_req).await {
-        Ok(_) => Ok(HttpResponse::Ok().json(json!({
-            "code": 0,
-            "message": "帖子更新成功"
-        }))),
+    if let Some(tags) = update_req.tags.as_ref() {
+        match tag_service.check_tags(tags, Some((TagTarget::Post, post_id))).await {
+            Ok(tags) => update_req.tags = Some(tags),
+            Err(e) => {
+                return Ok(HttpResponse::BadRequest().json(json!({
+                    "code": 400,
+                    "message": e.to_string()
+                })));
+            }
+        }
+    }
+
+    // 编辑前快照，编辑历史与更新在同一事务中写入
+    let revision = history_service.pending_revision("Post", post_id, user.id).await;
+    match post_service.update_post_recorded(post_id, update_req, revision.as_ref()).await {
+        Ok(_) => {
+            Ok(HttpResponse::Ok().json(json!({
+                "code": 0,
+                "message": "帖子更新成功"
+            })))
+        },
         Err(e) => {
             log::error!("更新帖子失败: {}", e);
             Ok(HttpResponse::InternalServerError().json(json!({
@@ -358,7 +451,7 @@ async fn review_post(
     post_service: web::Data<PostService>,
 ) -> Result<HttpResponse, actix_web::Error> {
     // 仅管理员/元老
-    let user = match AuthHelper::verify_user(&http_req) { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
+    let user = match AuthHelper::verify_user(&http_req).await { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
     if user.role != crate::models::UserRole::Admin && user.role != crate::models::UserRole::Elder {
         return Ok(HttpResponse::Forbidden().json(json!({ "code": 403, "message": "权限不足" })));
     }
@@ -372,11 +465,23 @@ async fn review_post(
     use rusqlite::{Connection, params};
     let conn = Connection::open(post_service.db_path()).map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
     let new_business_status = if status == "approved" { "Published" } else { "Draft" };
+    let previous_status: Option<String> = conn.query_row("SELECT review_status FROM posts WHERE id = ?", params![post_id], |r| r.get(0)).ok().flatten();
     conn.execute(
         "UPDATE posts SET review_status = ?, review_comment = ?, reviewer_id = ?, reviewed_at = CURRENT_TIMESTAMP, status = ? WHERE id = ?",
         params![status, req.comment.clone().unwrap_or_default(), user.id, new_business_status, post_id]
     ).map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
 
+    // 首次审核通过时通知帖子中被 @ 的用户与作者的粉丝
+    if status == "approved" && previous_status.as_deref() != Some("approved") {
+        if let Err(e) = post_service.notify_post_mentions(post_id).await {
+            log::error!("发送帖子提及通知失败: {}", e);
+        }
+        if let Err(e) = post_service.notify_post_followers(post_id).await {
+            log::error!("发送帖子粉丝通知失败: {}", e);
+        }
+        post_service.record_activity(None, "Approve", post_id).await;
+    }
+
     Ok(HttpResponse::Ok().json(json!({"code":0, "message":"审核成功"})))
 }
 
@@ -385,11 +490,12 @@ async fn delete_post(
     http_req: HttpRequest,
     path: web::Path<i32>,
     post_service: web::Data<PostService>,
+    points_service: web::Data<PointsService>,
 ) -> Result<HttpResponse, actix_web::Error> {
     let post_id = path.into_inner();
     
     // 验证用户权限
-    let user = match AuthHelper::verify_user(&http_req) {
+    let user = match AuthHelper::verify_user(&http_req).await {
         Ok(user) => user,
         Err(_) => {
             return Ok(HttpResponse::Unauthorized().json(json!({
@@ -400,7 +506,7 @@ async fn delete_post(
     };
 
     // 检查帖子是否存在
-    match post_service.get_post(post_id).await {
+    let author_id = match post_service.get_post(post_id).await {
         Ok(Some(post)) => {
             // 检查是否是作者或管理员
             if post.author_id != user.id && user.role != crate::models::UserRole::Admin && user.role != crate::models::UserRole::Elder {
@@ -409,6 +515,7 @@ async fn delete_post(
                     "message": "无权限删除此帖子"
                 })));
             }
+            post.author_id
         },
         Ok(None) => {
             return Ok(HttpResponse::NotFound().json(json!({
@@ -423,13 +530,19 @@ async fn delete_post(
                 "message": "检查帖子失败"
             })));
         }
-    }
+    };
 
-    match post_service.delete_post(post_id).await {
-        Ok(true) => Ok(HttpResponse::Ok().json(json!({
-            "code": 0,
-            "message": "帖子删除成功"
-        }))),
+    match post_service.delete_post(post_id, user.id).await {
+        Ok(true) => {
+            // 收回该帖子获得的积分，管理员删除他人帖子时追加处罚
+            if let Err(e) = points_service.on_content_removed("Post", post_id, author_id, Some(user.id)).await {
+                log::error!("处理删除积分失败: {}", e);
+            }
+            Ok(HttpResponse::Ok().json(json!({
+                "code": 0,
+                "message": "帖子删除成功"
+            })))
+        },
         Ok(false) => Ok(HttpResponse::NotFound().json(json!({
             "code": 404,
             "message": "帖子不存在"
@@ -478,7 +591,7 @@ async fn increment_view_count(
     let post_id = path.into_inner();
     
     // 进行安全检测
-    let user_id = AuthHelper::verify_user(&http_req).ok().map(|u| u.id);
+    let user_id = AuthHelper::verify_user(&http_req).await.ok().map(|u| u.id);
     let ip_address = http_req.connection_info().realip_remote_addr().map(|s| s.to_string());
     let user_agent = http_req.headers().get("User-Agent").and_then(|h| h.to_str().ok()).map(|s| s.to_string());
     
@@ -596,7 +709,7 @@ async fn like_post(
     path: web::Path<i32>,
     post_service: web::Data<PostService>,
 ) -> Result<HttpResponse, actix_web::Error> {
-    let user = match AuthHelper::verify_user(&http_req) { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
+    let user = match AuthHelper::verify_user(&http_req).await { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
     let post_id = path.into_inner();
     match post_service.like_post(user.id, post_id).await {
         Ok(count) => Ok(HttpResponse::Ok().json(json!({"code":0, "message":"success", "data": {"like_count": count}}))),
@@ -609,7 +722,7 @@ async fn unlike_post(
     path: web::Path<i32>,
     post_service: web::Data<PostService>,
 ) -> Result<HttpResponse, actix_web::Error> {
-    let user = match AuthHelper::verify_user(&http_req) { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
+    let user = match AuthHelper::verify_user(&http_req).await { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
     let post_id = path.into_inner();
     match post_service.unlike_post(user.id, post_id).await {
         Ok(count) => Ok(HttpResponse::Ok().json(json!({"code":0, "message":"success", "data": {"like_count": count}}))),
@@ -624,7 +737,7 @@ async fn get_pending_posts(
     post_service: web::Data<PostService>,
 ) -> Result<HttpResponse, actix_web::Error> {
     // 验证权限：仅管理员/元老可以查看待审核帖子
-    let user = match AuthHelper::verify_user(&http_req) { 
+    let user = match AuthHelper::verify_user(&http_req).await { 
         Ok(u) => u, 
         Err(e) => return Ok(e.to_response()) 
     };
@@ -666,7 +779,7 @@ async fn check_like_status(
     post_service: web::Data<PostService>,
 ) -> Result<HttpResponse, actix_web::Error> {
     let post_id = path.into_inner();
-    let user = match AuthHelper::verify_user(&http_req) { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
+    let user = match AuthHelper::verify_user(&http_req).await { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
     match post_service.is_post_liked_by_user(user.id, post_id).await {
         Ok(liked) => Ok(HttpResponse::Ok().json(json!({"code":0, "message":"success", "data": {"liked": liked}}))),
         Err(e) => Ok(HttpResponse::InternalServerError().json(json!({"code":500, "message": e.to_string()})))
@@ -679,7 +792,7 @@ async fn bookmark_post(
     path: web::Path<i32>,
     _post_service: web::Data<PostService>,
 ) -> Result<HttpResponse, actix_web::Error> {
-    let user = match AuthHelper::verify_user(&http_req) { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
+    let user = match AuthHelper::verify_user(&http_req).await { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
     let post_id = path.into_inner();
     use rusqlite::{Connection, params};
     let conn = crate::repositories::get_connection().map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
@@ -698,7 +811,7 @@ async fn unbookmark_post(
     path: web::Path<i32>,
     _post_service: web::Data<PostService>,
 ) -> Result<HttpResponse, actix_web::Error> {
-    let user = match AuthHelper::verify_user(&http_req) { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
+    let user = match AuthHelper::verify_user(&http_req).await { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
     let post_id = path.into_inner();
     use rusqlite::{Connection, params};
     let conn = crate::repositories::get_connection().map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
@@ -706,6 +819,9 @@ async fn unbookmark_post(
         .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
     conn.execute("DELETE FROM post_favorites WHERE user_id = ? AND post_id = ?", params![user.id, post_id])
         .map_err(|e| actix_web::error::ErrorBadRequest(e.to_string()))?;
+    // 取消收藏后同时移出收藏夹
+    conn.execute("DELETE FROM favorite_collection_items WHERE user_id = ? AND target_type = 'Post' AND target_id = ?", params![user.id, post_id])
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
     let cnt: i32 = conn.query_row("SELECT COUNT(*) FROM post_favorites WHERE post_id = ?", params![post_id], |r| r.get(0))
         .unwrap_or(0);
     Ok(HttpResponse::Ok().json(serde_json::json!({"code":0, "message":"success", "data": {"favorite_count": cnt}})))
@@ -716,7 +832,7 @@ async fn check_bookmark_status(
     path: web::Path<i32>,
     _post_service: web::Data<PostService>,
 ) -> Result<HttpResponse, actix_web::Error> {
-    let user = match AuthHelper::verify_user(&http_req) { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
+    let user = match AuthHelper::verify_user(&http_req).await { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
     let post_id = path.into_inner();
     use rusqlite::{Connection, params};
     let conn = crate::repositories::get_connection().map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
@@ -731,7 +847,7 @@ async fn report_post(
     path: web::Path<i32>,
     post_service: web::Data<PostService>,
 ) -> Result<HttpResponse, actix_web::Error> {
-    let user = match AuthHelper::verify_user(&http_req) { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
+    let user = match AuthHelper::verify_user(&http_req).await { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
     let post_id = path.into_inner();
     let ip = http_req.connection_info().realip_remote_addr().map(|s| s.to_string());
     let ua = http_req.headers().get("User-Agent").and_then(|h| h.to_str().ok()).map(|s| s.to_string());
@@ -760,11 +876,12 @@ async fn get_post_comments(
     path: web::Path<i32>,
     query: web::Query<CommentQuery>,
     comment_service: web::Data<crate::services::comment_service::CommentService>,
+    history_service: web::Data<EditHistoryService>,
 ) -> Result<HttpResponse, actix_web::Error> {
     let post_id = path.into_inner();
     let page = query.page.unwrap_or(1);
     let size = query.size.unwrap_or(20);
-    match comment_service.get_post_comments(post_id, page, size).await {
+    match comment_service.get_post_comments(post_id, page, size, AuthHelper::extract_user_id(&http_req).await).await {
         Ok((mut comments, total)) => {
             // 计算前缀（优先 PUBLIC_BASE_URL，否则从请求推断）
             let cfg = crate::config::Config::load().unwrap_or_default();
@@ -786,10 +903,12 @@ async fn get_post_comments(
                     }
                 }
             }
+            let mut list: Vec<serde_json::Value> = comments.iter().map(|c| json!(c)).collect();
+            history_service.attach_markers("Comment", &mut list).await;
             Ok(HttpResponse::Ok().json(json!({
                 "code": 0,
                 "message": "success",
-                "data": {"list": comments, "total": total, "page": page, "size": size}
+                "data": {"list": list, "total": total, "page": page, "size": size}
             })))
         },
         Err(e) => Ok(HttpResponse::InternalServerError().json(json!({
@@ -59,7 +59,7 @@ async fn get_public_comments(
 
     let db_target_type = if ttype == "post" { "Post" } else { "Package" };
 
-    match comment_service.get_top_level_comments(db_target_type, target_id, page, size).await {
+    match comment_service.get_top_level_comments(db_target_type, target_id, page, size, crate::utils::auth_helper::AuthHelper::extract_user_id(&http_req).await).await {
         Ok((mut comments, total)) => {
             // 计算前缀（优先 PUBLIC_BASE_URL，否则从请求推断）
             let cfg = crate::config::Config::load().unwrap_or_default();
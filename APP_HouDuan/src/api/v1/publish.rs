@@ -1,12 +1,11 @@
 use actix_web::{web, HttpRequest, HttpResponse};
 use serde_json::json;
-use crate::models::{
-    PublishResourceRequest, PublishPostRequest, CreatePackageRequest, CreatePostRequest,
-    PackageStatus, PostStatus,
-};
+use crate::models::{PublishResourceRequest, PublishPostRequest};
 use crate::services::package_service::PackageService;
 use crate::services::post_service::PostService;
 use crate::repositories::system_repo::SystemRepository;
+use crate::services::category_service::CategoryService;
+use crate::services::tag_service::TagService;
 use crate::require_auth;
 use crate::utils::auth_helper::AuthHelper;
 
@@ -30,6 +29,8 @@ async fn publish_resource(
     req: web::Json<PublishResourceRequest>,
     package_service: web::Data<PackageService>,
     system_repo: web::Data<SystemRepository>,
+    category_service: web::Data<CategoryService>,
+    tag_service: web::Data<TagService>,
 ) -> Result<HttpResponse, actix_web::Error> {
     // 验证用户认证
     let user = require_auth!(&http_req);
@@ -45,56 +46,58 @@ async fn publish_resource(
     }
     
     // 处理分类ID或分类名称
-    let category_id = if let Some(category_str) = &req.category {
-        // 首先尝试将其解析为ID（数字）
-        if let Ok(id) = category_str.parse::<i32>() {
-            Some(id)
-        } else {
-            // 如果不是数字，则按名称查找
-            match system_repo.get_categories().await {
-                Ok(categories) => {
-                    categories.iter()
-                        .find(|cat| cat.name == *category_str)
-                        .map(|cat| cat.id)
-                },
-                Err(e) => {
-                    log::error!("获取分类失败: {}", e);
-                    None
-                }
-            }
+    let categories = match system_repo.get_categories().await {
+        Ok(categories) => categories,
+        Err(e) => {
+            log::error!("获取分类失败: {}", e);
+            Vec::new()
         }
-    } else {
-        None
     };
+    let category_id = req.resolve_category(&categories);
+
+    // 分类的发布权限
+    if let Err(e) = category_service.check_can_post(category_id, &user.role).await {
+        return Ok(HttpResponse::Forbidden().json(json!({
+            "code": 403,
+            "message": e.to_string()
+        })));
+    }
     
     // 创建Package记录
-    let create_req = CreatePackageRequest {
-        name: req.title.clone(),
-        author: user.username.clone(),
-        version: req.version.clone(),
-        description: Some(req.content.clone()),
-        category_id,
-        file_url: None, // 发布时文件为空，后续通过上传接口填充
-        tags: req.tags.clone(),
-        is_pinned: Some(false),
-        is_featured: Some(false),
-        // 新增字段
-        screenshots: None, // 发布时截图为空，后续通过上传接口填充
-        cover_image: None,
-        requirements: req.requirements.clone(),
-        included_files: None,
-    };
+    let mut create_req = req.to_create_request(user.id, &user.username, category_id);
+    // 标签规范化、别名替换与白名单检查
+    if let Some(tags) = create_req.tags.as_ref() {
+        match tag_service.check_tags(tags, None).await {
+            Ok(tags) => create_req.tags = Some(tags),
+            Err(e) => {
+                return Ok(HttpResponse::BadRequest().json(json!({
+                    "code": 400,
+                    "message": e.to_string()
+                })));
+            }
+        }
+    }
     
     match package_service.create_package(&create_req).await {
         Ok(package) => {
             log::info!("✅ 资源发布成功: package_id={}, title={}", package.id, package.name);
+            // 分类无需审核时直接上架
+            let package = category_service.apply_review_rule(package.clone()).await.unwrap_or_else(|e| {
+                log::warn!("应用分类审核规则失败: {}", e);
+                package
+            });
+            let (message, status) = if package.status == crate::models::PackageStatus::Active {
+                ("资源发布成功", "active")
+            } else {
+                ("资源发布成功，等待审核", "pending")
+            };
             Ok(HttpResponse::Ok().json(json!({
                 "code": 0,
-                "message": "资源发布成功，等待审核",
+                "message": message,
                 "data": {
                     "id": package.id,
                     "title": package.name,
-                    "status": "pending",
+                    "status": status,
                     "created_at": package.created_at
                 }
             })))
@@ -114,6 +117,7 @@ async fn publish_post(
     http_req: HttpRequest,
     req: web::Json<PublishPostRequest>,
     post_service: web::Data<PostService>,
+    tag_service: web::Data<TagService>,
 ) -> Result<HttpResponse, actix_web::Error> {
     // 验证用户认证
     let user = require_auth!(&http_req);
@@ -130,15 +134,18 @@ async fn publish_post(
     }
     
     // 创建Post记录：默认草稿，审核中
-    let create_req = CreatePostRequest {
-        title: req.title.clone(),
-        content: req.content.clone(),
-        category_id: None,
-        tags: req.tags.clone(),
-        status: Some(PostStatus::Draft),
-        images: None, // 发布时不写入图片，占位使用 []
-        code_snippet: req.code_snippet.clone(),
-    };
+    let mut create_req = req.to_create_request();
+    if let Some(tags) = create_req.tags.as_ref() {
+        match tag_service.check_tags(tags, None).await {
+            Ok(tags) => create_req.tags = Some(tags),
+            Err(e) => {
+                return Ok(HttpResponse::BadRequest().json(json!({
+                    "code": 400,
+                    "message": e.to_string()
+                })));
+            }
+        }
+    }
     
     match post_service.create_post(create_req, user.id).await {
         Ok(post_id) => {
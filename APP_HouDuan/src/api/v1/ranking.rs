@@ -4,6 +4,14 @@ use serde::{Deserialize, Serialize};
 use crate::repositories::user_repo::UserRepository;
 use crate::repositories::package_repo::PackageRepository;
 use crate::repositories::post_repo::PostRepository;
+use crate::models::{Package, PackageRankingSort};
+use crate::models::post::Post;
+use crate::models::rating::RatingSummary;
+use crate::models::ranking::{RankedRow, RankingBoard, RankingHistoryQuery, RankingPeriod, RankingWindow};
+use crate::services::rating_service::RatingService;
+use crate::services::points_service::PointsService;
+use crate::services::ranking_service::RankingService;
+use chrono::NaiveDate;
 use std::sync::Arc;
 
 /// 构建完整的头像URL
@@ -24,15 +32,22 @@ pub struct RankingQuery {
     pub page: Option<u32>,
     pub page_size: Option<u32>,
     pub period: Option<String>, // "week", "month", "year", "all"
+    pub sort: Option<String>,   // 资源榜："downloads"（默认）, "rating"
+    pub category_id: Option<i32>, // 资源榜、帖子榜按分类筛选
 }
 
 #[derive(Debug, Serialize)]
 pub struct UserRankingItem {
     pub id: i64,
+    pub rank: i64,
+    pub rank_change: Option<i64>, // 相对上一周期的名次变化，正数上升；累计榜或上期未上榜时为空
     pub username: String,
     pub nickname: Option<String>,
     pub avatar_url: Option<String>,
-    pub score: i64,
+    pub score: i64, // 经验值（按周期统计时为周期内获得的经验）
+    pub level: i32,
+    pub level_title: String,
+    pub stars: i64,
     pub posts_count: i64,
     pub resources_count: i64,
     pub followers_count: i64,
@@ -44,12 +59,16 @@ pub struct UserRankingItem {
 #[derive(Debug, Serialize)]
 pub struct ResourceRankingItem {
     pub id: i64,
+    pub rank: i64,
+    pub rank_change: Option<i64>,
     pub title: String,
     pub description: Option<String>,
     pub author: AuthorInfo,
     pub downloads: i64,
     pub likes: i64,
+    pub views: i64,
     pub rating: f64,
+    pub rating_count: i32,
     pub category: String,
     pub created_at: String,
     pub tags: Vec<String>,
@@ -58,6 +77,8 @@ pub struct ResourceRankingItem {
 #[derive(Debug, Serialize)]
 pub struct PostRankingItem {
     pub id: i64,
+    pub rank: i64,
+    pub rank_change: Option<i64>,
     pub title: String,
     pub content_preview: Option<String>,
     pub author: AuthorInfo,
@@ -83,28 +104,229 @@ pub struct RankingResponse<T> {
     pub page: u32,
     pub page_size: u32,
     pub total_pages: i64,
+    pub period: String,
+    /// 统计周期编号，如 2025-W01、2025-01、2025；累计榜为空
+    pub period_key: Option<String>,
+    pub period_start: Option<NaiveDate>,
+    pub period_end: Option<NaiveDate>,
+    pub category_id: Option<i32>,
+}
+
+impl<T> RankingResponse<T> {
+    fn new(items: Vec<T>, total: i64, page: u32, page_size: u32, window: Option<&RankingWindow>, category_id: Option<i32>) -> Self {
+        Self {
+            items,
+            total,
+            page,
+            page_size,
+            total_pages: (total as f64 / page_size as f64).ceil() as i64,
+            period: window.map(|w| w.period.as_str()).unwrap_or("all").to_string(),
+            period_key: window.map(|w| w.key.clone()),
+            period_start: window.map(|w| w.start),
+            period_end: window.map(|w| w.end),
+            category_id,
+        }
+    }
 }
 
-/// 获取用户排行榜
+fn bad_request(message: String) -> HttpResponse {
+    HttpResponse::BadRequest().json(serde_json::json!({ "error": message }))
+}
+
+/// 按周期排行的用户数据补全为列表项
+async fn user_items(rows: Vec<RankedRow>, user_repo: &UserRepository, points_service: &PointsService) -> Vec<UserRankingItem> {
+    let ids: Vec<i32> = rows.iter().map(|r| r.row.entity_id).collect();
+    let levels = points_service.level_progress_map(&ids).await.unwrap_or_default();
+    let mut items = Vec::new();
+    for ranked in rows {
+        let Ok(Some(user)) = user_repo.find_by_id(ranked.row.entity_id).await else {
+            continue;
+        };
+        let level = levels.get(&user.id);
+        items.push(UserRankingItem {
+            id: user.id as i64,
+            rank: ranked.rank,
+            rank_change: ranked.rank_change,
+            username: user.username,
+            nickname: user.nickname,
+            avatar_url: build_avatar_url(user.avatar_url),
+            score: ranked.row.score,
+            level: level.map(|l| l.level).unwrap_or(1),
+            level_title: level.map(|l| l.title.clone()).unwrap_or_default(),
+            stars: user.star as i64,
+            posts_count: 0,
+            resources_count: 0,
+            followers_count: ranked.row.followers,
+            likes_count: ranked.row.likes,
+            total_views: 0,
+            total_downloads: ranked.row.downloads,
+        });
+    }
+    items
+}
+
+fn resource_item(package: Package, rank: i64, rank_change: Option<i64>, summary: RatingSummary) -> ResourceRankingItem {
+    // 暂时使用简化的作者信息，避免数据库查询问题
+    let author = AuthorInfo {
+        id: 1, // 暂时固定ID
+        username: package.author.clone(),
+        nickname: Some(package.author.clone()),
+        avatar_url: None,
+    };
+
+    // 获取分类名称
+    let category_name = package.category_id
+        .map(|id| {
+            // TODO: 从category表获取分类名称
+            format!("分类{}", id)
+        })
+        .unwrap_or_else(|| "未分类".to_string());
+
+    ResourceRankingItem {
+        id: package.id as i64,
+        rank,
+        rank_change,
+        title: package.name,
+        description: package.description,
+        author,
+        downloads: package.download_count as i64,
+        likes: package.like_count as i64,
+        views: 0,
+        rating: summary.average,
+        rating_count: summary.count,
+        category: category_name,
+        created_at: package.created_at.to_rfc3339(),
+        tags: package.tags.unwrap_or_default(),
+    }
+}
+
+/// 按周期排行的资源数据补全为列表项，下载、点赞、浏览为周期内数据
+async fn resource_items(rows: Vec<RankedRow>, package_repo: &PackageRepository, rating_service: &RatingService) -> Vec<ResourceRankingItem> {
+    let ids: Vec<i32> = rows.iter().map(|r| r.row.entity_id).collect();
+    let summaries = rating_service.get_summaries(&ids).await.unwrap_or_default();
+    let mut items = Vec::new();
+    for ranked in rows {
+        let Ok(Some(package)) = package_repo.find_by_id(ranked.row.entity_id).await else {
+            continue;
+        };
+        let summary = summaries.get(&package.id).cloned().unwrap_or_default();
+        let mut item = resource_item(package, ranked.rank, ranked.rank_change, summary);
+        item.downloads = ranked.row.downloads;
+        item.likes = ranked.row.likes;
+        item.views = ranked.row.views;
+        items.push(item);
+    }
+    items
+}
+
+async fn post_item(post: Post, rank: i64, rank_change: Option<i64>, user_repo: &UserRepository) -> PostRankingItem {
+    // 获取作者信息
+    let author = match user_repo.find_by_id(post.author_id).await {
+        Ok(Some(user)) => AuthorInfo {
+            id: user.id as i64,
+            username: user.username,
+            nickname: user.nickname,
+            avatar_url: build_avatar_url(user.avatar_url),
+        },
+        _ => {
+            // 如果找不到用户，使用post中的author_name作为fallback
+            AuthorInfo {
+                id: post.author_id as i64,
+                username: post.author_name.clone().unwrap_or_else(|| format!("user_{}", post.author_id)),
+                nickname: post.author_name.clone(),
+                avatar_url: Some(format!("https://api.dicebear.com/7.x/avataaars/svg?seed={}", post.author_id)),
+            }
+        }
+    };
+
+    // 生成内容预览（前100个字符，安全处理UTF-8）
+    let content_preview = if post.content.chars().count() > 100 {
+        let truncated: String = post.content.chars().take(100).collect();
+        Some(format!("{}...", truncated))
+    } else {
+        Some(post.content.clone())
+    };
+
+    PostRankingItem {
+        id: post.id as i64,
+        rank,
+        rank_change,
+        title: post.title,
+        content_preview,
+        author,
+        views: post.view_count as i64,
+        likes: post.like_count as i64,
+        comments: post.comment_count as i64,
+        created_at: post.created_at.to_rfc3339(),
+        tags: post.tags.unwrap_or_default(),
+    }
+}
+
+/// 按周期排行的帖子数据补全为列表项，浏览、点赞、评论为周期内数据
+async fn post_items(rows: Vec<RankedRow>, post_repo: &PostRepository, user_repo: &UserRepository) -> Vec<PostRankingItem> {
+    let mut items = Vec::new();
+    for ranked in rows {
+        let Ok(Some(post)) = post_repo.find_by_id(ranked.row.entity_id) else {
+            continue;
+        };
+        let mut item = post_item(post, ranked.rank, ranked.rank_change, user_repo).await;
+        item.views = ranked.row.views;
+        item.likes = ranked.row.likes;
+        item.comments = ranked.row.comments;
+        items.push(item);
+    }
+    items
+}
+
+/// 获取用户排行榜：period=all 按累计经验，week/month/year 按周期内获得的经验
 pub async fn get_user_ranking(
     query: web::Query<RankingQuery>,
     user_repo: web::Data<Arc<UserRepository>>,
+    points_service: web::Data<PointsService>,
+    ranking_service: web::Data<RankingService>,
 ) -> Result<HttpResponse> {
-    let page = query.page.unwrap_or(1);
-    let page_size = query.page_size.unwrap_or(20).min(100); // 限制最大页面大小
+    let page = query.page.unwrap_or(1).max(1);
+    let page_size = query.page_size.unwrap_or(20).clamp(1, 100); // 限制最大页面大小
     let offset = (page - 1) * page_size;
 
-    info!("获取用户排行榜: page={}, page_size={}", page, page_size);
+    info!("获取用户排行榜: page={}, page_size={}, period={:?}", page, page_size, query.period);
+
+    let period = match ranking_service.resolve_period(query.period.as_deref()) {
+        Ok(p) => p,
+        Err(e) => return Ok(bad_request(e.to_string())),
+    };
+    if period != RankingPeriod::All {
+        return match ranking_service.window_ranking(RankingBoard::Users, period, None, offset as i64, page_size as i64).await {
+            Ok(result) => {
+                let items = user_items(result.rows, &user_repo, &points_service).await;
+                Ok(HttpResponse::Ok().json(RankingResponse::new(items, result.total, page, page_size, Some(&result.window), None)))
+            }
+            Err(e) => {
+                error!("获取用户排行榜失败: {}", e);
+                Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": "获取用户排行榜失败"
+                })))
+            }
+        };
+    }
 
     match user_repo.get_user_ranking(offset as i64, page_size as i64).await {
         Ok((users, total)) => {
-            let items: Vec<UserRankingItem> = users.into_iter().map(|user| {
+            let ids: Vec<i32> = users.iter().map(|u| u.id).collect();
+            let levels = points_service.level_progress_map(&ids).await.unwrap_or_default();
+            let items: Vec<UserRankingItem> = users.into_iter().enumerate().map(|(i, user)| {
+                let level = levels.get(&user.id);
                 UserRankingItem {
                     id: user.id as i64,
+                    rank: offset as i64 + i as i64 + 1,
+                    rank_change: None,
                     username: user.username,
                     nickname: user.nickname,
                     avatar_url: build_avatar_url(user.avatar_url),
-                    score: user.star as i64,
+                    score: level.map(|l| l.experience).unwrap_or(0),
+                    level: level.map(|l| l.level).unwrap_or(1),
+                    level_title: level.map(|l| l.title.clone()).unwrap_or_default(),
+                    stars: user.star as i64,
                     posts_count: 0, // TODO: 从统计表获取
                     resources_count: 0, // TODO: 从统计表获取
                     followers_count: 0, // TODO: 从关注表获取
@@ -114,15 +336,7 @@ pub async fn get_user_ranking(
                 }
             }).collect();
 
-            let total_pages = (total as f64 / page_size as f64).ceil() as i64;
-
-            Ok(HttpResponse::Ok().json(RankingResponse {
-                items,
-                total: total as i64,
-                page,
-                page_size,
-                total_pages,
-            }))
+            Ok(HttpResponse::Ok().json(RankingResponse::new(items, total, page, page_size, None, None)))
         }
         Err(e) => {
             error!("获取用户排行榜失败: {}", e);
@@ -133,62 +347,63 @@ pub async fn get_user_ranking(
     }
 }
 
-/// 获取资源排行榜
+/// 获取资源排行榜：period=all 按累计下载（或 sort=rating 按评分），week/month/year 按周期内下载
 pub async fn get_resource_ranking(
     query: web::Query<RankingQuery>,
     package_repo: web::Data<Arc<PackageRepository>>,
-    user_repo: web::Data<Arc<UserRepository>>,
+    rating_service: web::Data<RatingService>,
+    ranking_service: web::Data<RankingService>,
 ) -> Result<HttpResponse> {
-    let page = query.page.unwrap_or(1);
-    let page_size = query.page_size.unwrap_or(20).min(100);
+    let page = query.page.unwrap_or(1).max(1);
+    let page_size = query.page_size.unwrap_or(20).clamp(1, 100);
     let offset = (page - 1) * page_size;
+    let category_id = query.category_id.filter(|id| *id > 0);
 
-    info!("获取资源排行榜: page={}, page_size={}", page, page_size);
+    info!("获取资源排行榜: page={}, page_size={}, sort={:?}, period={:?}, category_id={:?}",
+        page, page_size, query.sort, query.period, category_id);
 
-    match package_repo.get_package_ranking(offset as i64, page_size as i64).await {
-        Ok((packages, total)) => {
-            let mut items = Vec::new();
+    let period = match ranking_service.resolve_period(query.period.as_deref()) {
+        Ok(p) => p,
+        Err(e) => return Ok(bad_request(e.to_string())),
+    };
+    // 评分榜始终按累计评分排序
+    if period != RankingPeriod::All && query.sort.as_deref() != Some("rating") {
+        return match ranking_service.window_ranking(RankingBoard::Resources, period, category_id, offset as i64, page_size as i64).await {
+            Ok(result) => {
+                let items = resource_items(result.rows, &package_repo, &rating_service).await;
+                Ok(HttpResponse::Ok().json(RankingResponse::new(items, result.total, page, page_size, Some(&result.window), category_id)))
+            }
+            Err(e) => {
+                error!("获取资源排行榜失败: {}", e);
+                Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": "获取资源排行榜失败"
+                })))
+            }
+        };
+    }
 
-            for package in packages {
-                // 暂时使用简化的作者信息，避免数据库查询问题
-                let author = AuthorInfo {
-                    id: 1, // 暂时固定ID
-                    username: package.author.clone(),
-                    nickname: Some(package.author.clone()),
-                    avatar_url: None,
-                };
-
-                // 获取分类名称
-                let category_name = package.category_id
-                    .and_then(|id| {
-                        // TODO: 从category表获取分类名称
-                        Some(format!("分类{}", id))
-                    })
-                    .unwrap_or_else(|| "未分类".to_string());
-
-                items.push(ResourceRankingItem {
-                    id: package.id as i64,
-                    title: package.name,
-                    description: package.description,
-                    author,
-                    downloads: package.download_count as i64,
-                    likes: package.like_count as i64,
-                    rating: 4.5, // TODO: 从评分表获取
-                    category: category_name,
-                    created_at: package.created_at.to_rfc3339(),
-                    tags: package.tags.unwrap_or_default(),
-                });
+    let sort = if query.sort.as_deref() == Some("rating") {
+        match rating_service.ranking_sort().await {
+            Ok(sort) => sort,
+            Err(e) => {
+                error!("获取全站平均评分失败: {}", e);
+                PackageRankingSort::Rating { prior_mean: 0.0, prior_weight: 0.0 }
             }
+        }
+    } else {
+        PackageRankingSort::Downloads
+    };
 
-            let total_pages = (total as f64 / page_size as f64).ceil() as i64;
+    match package_repo.get_package_ranking(offset as i64, page_size as i64, &sort, category_id).await {
+        Ok((packages, total)) => {
+            let ids: Vec<i32> = packages.iter().map(|p| p.id).collect();
+            let summaries = rating_service.get_summaries(&ids).await.unwrap_or_default();
+            let items: Vec<ResourceRankingItem> = packages.into_iter().enumerate().map(|(i, package)| {
+                let summary = summaries.get(&package.id).cloned().unwrap_or_default();
+                resource_item(package, offset as i64 + i as i64 + 1, None, summary)
+            }).collect();
 
-            Ok(HttpResponse::Ok().json(RankingResponse {
-                items,
-                total: total as i64,
-                page,
-                page_size,
-                total_pages,
-            }))
+            Ok(HttpResponse::Ok().json(RankingResponse::new(items, total, page, page_size, None, category_id)))
         }
         Err(e) => {
             error!("获取资源排行榜失败: {}", e);
@@ -199,71 +414,47 @@ pub async fn get_resource_ranking(
     }
 }
 
-/// 获取帖子排行榜
+/// 获取帖子排行榜：period=all 按累计浏览，week/month/year 按周期内浏览
 pub async fn get_post_ranking(
     query: web::Query<RankingQuery>,
     post_repo: web::Data<Arc<PostRepository>>,
     user_repo: web::Data<Arc<UserRepository>>,
+    ranking_service: web::Data<RankingService>,
 ) -> Result<HttpResponse> {
-    let page = query.page.unwrap_or(1);
-    let page_size = query.page_size.unwrap_or(20).min(100);
+    let page = query.page.unwrap_or(1).max(1);
+    let page_size = query.page_size.unwrap_or(20).clamp(1, 100);
+    let offset = (page - 1) * page_size;
+    let category_id = query.category_id.filter(|id| *id > 0);
 
-    info!("获取帖子排行榜: page={}, page_size={}", page, page_size);
+    info!("获取帖子排行榜: page={}, page_size={}, period={:?}, category_id={:?}", page, page_size, query.period, category_id);
+
+    let period = match ranking_service.resolve_period(query.period.as_deref()) {
+        Ok(p) => p,
+        Err(e) => return Ok(bad_request(e.to_string())),
+    };
+    if period != RankingPeriod::All {
+        return match ranking_service.window_ranking(RankingBoard::Posts, period, category_id, offset as i64, page_size as i64).await {
+            Ok(result) => {
+                let items = post_items(result.rows, &post_repo, &user_repo).await;
+                Ok(HttpResponse::Ok().json(RankingResponse::new(items, result.total, page, page_size, Some(&result.window), category_id)))
+            }
+            Err(e) => {
+                error!("获取帖子排行榜失败: {}", e);
+                Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": "获取帖子排行榜失败"
+                })))
+            }
+        };
+    }
 
-    match post_repo.get_post_ranking(page, page_size) {
+    match post_repo.get_post_ranking(page, page_size, category_id) {
         Ok((posts, total)) => {
             let mut items = Vec::new();
-
-            for post in posts {
-                // 获取作者信息
-                let author = match user_repo.find_by_id(post.author_id).await {
-                    Ok(Some(user)) => AuthorInfo {
-                        id: user.id as i64,
-                        username: user.username,
-                        nickname: user.nickname,
-                        avatar_url: build_avatar_url(user.avatar_url),
-                    },
-                    _ => {
-                        // 如果找不到用户，使用post中的author_name作为fallback
-                        AuthorInfo {
-                            id: post.author_id as i64,
-                            username: post.author_name.clone().unwrap_or_else(|| format!("user_{}", post.author_id)),
-                            nickname: post.author_name.clone(),
-                            avatar_url: Some(format!("https://api.dicebear.com/7.x/avataaars/svg?seed={}", post.author_id)),
-                        }
-                    }
-                };
-
-                // 生成内容预览（前100个字符，安全处理UTF-8）
-                let content_preview = if post.content.chars().count() > 100 {
-                    let truncated: String = post.content.chars().take(100).collect();
-                    Some(format!("{}...", truncated))
-                } else {
-                    Some(post.content.clone())
-                };
-
-                items.push(PostRankingItem {
-                    id: post.id as i64,
-                    title: post.title,
-                    content_preview,
-                    author,
-                    views: post.view_count as i64,
-                    likes: post.like_count as i64,
-                    comments: post.comment_count as i64,
-                    created_at: post.created_at.to_rfc3339(),
-                    tags: post.tags.unwrap_or_default(),
-                });
+            for (i, post) in posts.into_iter().enumerate() {
+                items.push(post_item(post, offset as i64 + i as i64 + 1, None, &user_repo).await);
             }
 
-            let total_pages = (total as f64 / page_size as f64).ceil() as i64;
-
-            Ok(HttpResponse::Ok().json(RankingResponse {
-                items,
-                total,
-                page,
-                page_size,
-                total_pages,
-            }))
+            Ok(HttpResponse::Ok().json(RankingResponse::new(items, total, page, page_size, None, category_id)))
         }
         Err(e) => {
             error!("获取帖子排行榜失败: {}", e);
@@ -274,11 +465,64 @@ pub async fn get_post_ranking(
     }
 }
 
+/// 获取已结束周期的历史排行榜（快照，最多前 100 名）
+pub async fn get_ranking_history(
+    query: web::Query<RankingHistoryQuery>,
+    user_repo: web::Data<Arc<UserRepository>>,
+    package_repo: web::Data<Arc<PackageRepository>>,
+    post_repo: web::Data<Arc<PostRepository>>,
+    points_service: web::Data<PointsService>,
+    rating_service: web::Data<RatingService>,
+    ranking_service: web::Data<RankingService>,
+) -> Result<HttpResponse> {
+    let page = query.page.unwrap_or(1).max(1);
+    let page_size = query.page_size.unwrap_or(20).clamp(1, 100);
+    let offset = (page - 1) * page_size;
+
+    let Some(board) = RankingBoard::parse(query.board.trim()) else {
+        return Ok(bad_request("排行榜类型错误，可选 users、resources、posts".to_string()));
+    };
+    let period = match RankingPeriod::parse(query.period.trim()) {
+        Some(p) if p != RankingPeriod::All => p,
+        _ => return Ok(bad_request("周期参数错误，可选 week、month、year".to_string())),
+    };
+    let category_id = query.category_id.filter(|id| *id > 0 && board != RankingBoard::Users);
+
+    info!("获取历史排行榜: board={}, period={}, key={}, category_id={:?}", board.as_str(), period.as_str(), query.key, category_id);
+
+    let result = match ranking_service.history(board, period, &query.key, category_id, offset as i64, page_size as i64).await {
+        Ok(r) => r,
+        Err(e) => return Ok(bad_request(e.to_string())),
+    };
+    let window = Some(&result.window);
+    let response = match board {
+        RankingBoard::Users => serde_json::to_value(RankingResponse::new(
+            user_items(result.rows, &user_repo, &points_service).await, result.total, page, page_size, window, category_id,
+        )),
+        RankingBoard::Resources => serde_json::to_value(RankingResponse::new(
+            resource_items(result.rows, &package_repo, &rating_service).await, result.total, page, page_size, window, category_id,
+        )),
+        RankingBoard::Posts => serde_json::to_value(RankingResponse::new(
+            post_items(result.rows, &post_repo, &user_repo).await, result.total, page, page_size, window, category_id,
+        )),
+    };
+    match response {
+        Ok(body) => Ok(HttpResponse::Ok().json(body)),
+        Err(e) => {
+            error!("获取历史排行榜失败: {}", e);
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "获取历史排行榜失败"
+            })))
+        }
+    }
+}
+
 pub fn configure_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/ranking")
             .route("/users", web::get().to(get_user_ranking))
             .route("/resources", web::get().to(get_resource_ranking))
             .route("/posts", web::get().to(get_post_ranking))
+            .route("/history", web::get().to(get_ranking_history))
     );
-} 
\ No newline at end of file
+}
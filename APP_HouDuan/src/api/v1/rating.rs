@@ -0,0 +1,123 @@
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use serde_json::json;
+
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::rating::{
+    AdminReviewQuery, ModerateReviewRequest, RatingQuery, ReplyRatingRequest, UpsertRatingRequest,
+};
+use crate::require_admin;
+use crate::services::rating_service::RatingService;
+use crate::utils::error::ServiceError;
+
+// 路由注册在 /packages 与 /admin 作用域内（见 package.rs、admin.rs）
+
+
+/// 资源评分列表（含汇总，登录时附带自己的评分）
+pub async fn list_package_ratings(
+    path: web::Path<i32>,
+    query: web::Query<RatingQuery>,
+    rating_service: web::Data<RatingService>,
+    user: Option<AuthenticatedUser>,
+) -> HttpResponse {
+    let package_id = path.into_inner();
+    let (list, total) = match rating_service.list_ratings(package_id, &query).await {
+        Ok(r) => r,
+        Err(e) => return ServiceError::from(e).error_response(),
+    };
+    let summary = rating_service.get_summary(package_id).await.unwrap_or_default();
+    let mine = match user {
+        Some(u) => rating_service.get_my_rating(u.id, package_id).await.ok().flatten(),
+        None => None,
+    };
+    HttpResponse::Ok().json(json!({
+        "code": 0,
+        "message": "success",
+        "data": {
+            "list": list,
+            "total": total,
+            "page": query.page.unwrap_or(1).max(1),
+            "page_size": query.page_size.unwrap_or(20).clamp(1, 100),
+            "summary": summary,
+            "mine": mine
+        }
+    }))
+}
+
+pub async fn get_rating_summary(path: web::Path<i32>, rating_service: web::Data<RatingService>) -> HttpResponse {
+    match rating_service.get_summary(path.into_inner()).await {
+        Ok(summary) => HttpResponse::Ok().json(json!({"code": 0, "message": "success", "data": summary})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+/// 提交或修改评分
+pub async fn rate_package(
+    path: web::Path<i32>,
+    body: web::Json<UpsertRatingRequest>,
+    rating_service: web::Data<RatingService>,
+    user: AuthenticatedUser,
+) -> HttpResponse {
+    match rating_service.rate(user.id, path.into_inner(), body.into_inner()).await {
+        Ok(rating) => HttpResponse::Ok().json(json!({"code": 0, "message": "评分成功", "data": rating})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+pub async fn delete_my_rating(
+    path: web::Path<i32>,
+    rating_service: web::Data<RatingService>,
+    user: AuthenticatedUser,
+) -> HttpResponse {
+    match rating_service.delete_my_rating(user.id, path.into_inner()).await {
+        Ok(summary) => HttpResponse::Ok().json(json!({"code": 0, "message": "评分已删除", "data": {"summary": summary}})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+/// 资源作者回复评价
+pub async fn reply_rating(
+    path: web::Path<(i32, i32)>,
+    body: web::Json<ReplyRatingRequest>,
+    rating_service: web::Data<RatingService>,
+    user: AuthenticatedUser,
+) -> HttpResponse {
+    let (package_id, rating_id) = path.into_inner();
+    match rating_service.reply(user.id, rating_id, &body.reply).await {
+        Ok(rating) if rating.package_id == package_id => {
+            HttpResponse::Ok().json(json!({"code": 0, "message": "回复成功", "data": rating}))
+        }
+        Ok(_) => HttpResponse::NotFound().json(json!({"code": 404, "message": "评分不存在"})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+/// 管理员：评价审核列表
+pub async fn admin_list_reviews(
+    req: HttpRequest,
+    query: web::Query<AdminReviewQuery>,
+    rating_service: web::Data<RatingService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let _ = require_admin!(&req);
+    match rating_service.list_reviews(&query).await {
+        Ok((list, total)) => Ok(HttpResponse::Ok().json(json!({
+            "code": 0,
+            "message": "success",
+            "data": { "list": list, "total": total }
+        }))),
+        Err(e) => Ok(ServiceError::from(e).error_response()),
+    }
+}
+
+/// 管理员：隐藏或恢复评价文字
+pub async fn admin_moderate_review(
+    req: HttpRequest,
+    path: web::Path<i32>,
+    body: web::Json<ModerateReviewRequest>,
+    rating_service: web::Data<RatingService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let _ = require_admin!(&req);
+    match rating_service.moderate(path.into_inner(), &body.status, body.reason.as_deref()).await {
+        Ok(_) => Ok(HttpResponse::Ok().json(json!({"code": 0, "message": "操作成功"}))),
+        Err(e) => Ok(ServiceError::from(e).error_response()),
+    }
+}
@@ -0,0 +1,95 @@
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use serde_json::json;
+
+use crate::models::recycle_bin::{RecycleBinQuery, RecycleBinSettings};
+use crate::require_admin;
+use crate::services::recycle_bin_service::RecycleBinService;
+use crate::utils::error::ServiceError;
+
+// 路由注册在 /admin 作用域内（见 admin.rs）
+
+
+/// 管理员：回收站列表，?type=package|post|comment|user&keyword=&page=&page_size=
+pub async fn admin_list_recycle_bin(
+    req: HttpRequest,
+    query: web::Query<RecycleBinQuery>,
+    recycle_service: web::Data<RecycleBinService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let _ = require_admin!(&req);
+    match recycle_service.list(&query).await {
+        Ok((list, total, counts)) => {
+            let counts: serde_json::Map<String, serde_json::Value> =
+                counts.into_iter().map(|(t, c)| (t, json!(c))).collect();
+            Ok(HttpResponse::Ok().json(json!({
+                "code": 0,
+                "message": "success",
+                "data": {"list": list, "total": total, "counts": counts}
+            })))
+        }
+        Err(e) => Ok(ServiceError::from(e).error_response()),
+    }
+}
+
+/// 管理员：恢复回收站中的内容
+pub async fn admin_restore_recycled(
+    req: HttpRequest,
+    path: web::Path<(String, i32)>,
+    recycle_service: web::Data<RecycleBinService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let _ = require_admin!(&req);
+    let (item_type, id) = path.into_inner();
+    match recycle_service.restore(&item_type, id).await {
+        Ok(item) => Ok(HttpResponse::Ok().json(json!({"code": 0, "message": "已恢复", "data": item}))),
+        Err(e) => Ok(ServiceError::from(e).error_response()),
+    }
+}
+
+/// 管理员：立即彻底删除回收站中的内容
+pub async fn admin_purge_recycled(
+    req: HttpRequest,
+    path: web::Path<(String, i32)>,
+    recycle_service: web::Data<RecycleBinService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let _ = require_admin!(&req);
+    let (item_type, id) = path.into_inner();
+    match recycle_service.purge(&item_type, id).await {
+        Ok(item) => Ok(HttpResponse::Ok().json(json!({"code": 0, "message": "已彻底删除", "data": item}))),
+        Err(e) => Ok(ServiceError::from(e).error_response()),
+    }
+}
+
+/// 管理员：立即清理超过保留天数的内容
+pub async fn admin_purge_expired(
+    req: HttpRequest,
+    recycle_service: web::Data<RecycleBinService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let _ = require_admin!(&req);
+    match recycle_service.purge_expired().await {
+        Ok(count) => Ok(HttpResponse::Ok().json(json!({"code": 0, "message": "清理完成", "data": {"purged": count}}))),
+        Err(e) => Ok(ServiceError::from(e).error_response()),
+    }
+}
+
+/// 管理员：回收站设置（保留天数）
+pub async fn admin_get_recycle_settings(
+    req: HttpRequest,
+    recycle_service: web::Data<RecycleBinService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let _ = require_admin!(&req);
+    match recycle_service.settings().await {
+        Ok(settings) => Ok(HttpResponse::Ok().json(json!({"code": 0, "message": "success", "data": settings}))),
+        Err(e) => Ok(ServiceError::from(e).error_response()),
+    }
+}
+
+pub async fn admin_update_recycle_settings(
+    req: HttpRequest,
+    body: web::Json<RecycleBinSettings>,
+    recycle_service: web::Data<RecycleBinService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let _ = require_admin!(&req);
+    match recycle_service.update_settings(&body).await {
+        Ok(settings) => Ok(HttpResponse::Ok().json(json!({"code": 0, "message": "设置已保存", "data": settings}))),
+        Err(e) => Ok(ServiceError::from(e).error_response()),
+    }
+}
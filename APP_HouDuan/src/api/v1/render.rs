@@ -0,0 +1,41 @@
+use actix_web::{web, HttpResponse};
+use serde_json::json;
+
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::render::RenderPreviewRequest;
+use crate::services::render_service::RenderService;
+
+/// 预览内容最大字节数，与草稿内容上限一致
+const MAX_PREVIEW_BYTES: usize = crate::models::draft::MAX_PAYLOAD_BYTES;
+
+/// 编辑器预览：返回与详情页一致的净化 HTML
+pub async fn preview(
+    _user: AuthenticatedUser,
+    body: web::Json<RenderPreviewRequest>,
+    render_service: web::Data<RenderService>,
+) -> HttpResponse {
+    if body.content.len() > MAX_PREVIEW_BYTES {
+        return HttpResponse::BadRequest().json(json!({
+            "code": 400,
+            "message": format!("内容过长，最多{}KB", MAX_PREVIEW_BYTES / 1024)
+        }));
+    }
+    let rendered = render_service.preview(&body.content, body.code, body.language.as_deref());
+    HttpResponse::Ok().json(json!({"code": 0, "message": "success", "data": rendered}))
+}
+
+/// 代码高亮样式表（class 前缀 hl-）
+pub async fn highlight_css(render_service: web::Data<RenderService>) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/css; charset=utf-8")
+        .insert_header(("Cache-Control", "public, max-age=86400"))
+        .body(render_service.highlight_css())
+}
+
+pub fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/render")
+            .route("/preview", web::post().to(preview))
+            .route("/highlight.css", web::get().to(highlight_css))
+    );
+}
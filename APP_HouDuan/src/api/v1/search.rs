@@ -2,6 +2,7 @@ use actix_web::{web, HttpResponse};
 use serde::Deserialize;
 use serde_json::json;
 use crate::services::{post_service::PostService, package_service::PackageService};
+use crate::services::render_service::{RenderService, FIELD_PACKAGE_DESCRIPTION, FIELD_POST_CONTENT};
 use crate::models::TagQueryParams;
 
 pub fn configure_routes(cfg: &mut web::ServiceConfig) {
@@ -20,6 +21,7 @@ async fn search(
 	query: web::Query<SearchQuery>,
 	post_service: web::Data<PostService>,
 	package_service: web::Data<PackageService>,
+	render_service: web::Data<RenderService>,
 ) -> Result<HttpResponse, actix_web::Error> {
 	let q = query.query.clone().unwrap_or_default();
 	let page = query.page.unwrap_or(1);
@@ -32,8 +34,9 @@ async fn search(
 		let mut params = crate::models::PostQueryParams { page: Some(page), page_size: Some(page_size), category_id: None, author_id: None, status: Some("Published".into()), search: None, tags: None, is_pinned: None, is_featured: None };
 		if !q.is_empty() { params.search = Some(q.clone()); }
 		if let Ok(resp) = post_service.get_posts(params).await {
+			let mut post_items: Vec<serde_json::Value> = Vec::new();
 			for p in resp.list {
-				items.push(json!({
+				post_items.push(json!({
 					"id": p.id,
 					"type": "post",
 					"title": p.title,
@@ -43,13 +46,16 @@ async fn search(
 					"publishedAt": p.created_at.to_rfc3339()
 				}));
 			}
+			render_service.attach_excerpts("Post", FIELD_POST_CONTENT, "description", &mut post_items).await;
+			items.extend(post_items);
 		}
 	}
 
 	if t.is_empty() || t == "resource" || t == "package" {
 		if let Ok((packages, _total)) = package_service.get_packages_advanced(page, page_size, None, if q.is_empty(){None}else{Some(q.clone())}, Some("active".into())).await {
+			let mut package_items: Vec<serde_json::Value> = Vec::new();
 			for p in packages {
-				items.push(json!({
+				package_items.push(json!({
 					"id": p.id,
 					"type": "resource",
 					"title": p.name,
@@ -59,6 +65,8 @@ async fn search(
 					"publishedAt": p.created_at.to_rfc3339()
 				}));
 			}
+			render_service.attach_excerpts("Package", FIELD_PACKAGE_DESCRIPTION, "description", &mut package_items).await;
+			items.extend(package_items);
 		}
 	}
 
@@ -94,7 +102,7 @@ struct SuggestQuery { query: Option<String> }
 
 async fn suggest(query: web::Query<SuggestQuery>, tag_service: web::Data<crate::services::tag_service::TagService>) -> Result<HttpResponse, actix_web::Error> {
 	let q = query.query.clone().unwrap_or_default();
-	let params = TagQueryParams { page: Some(1), page_size: Some(10), search: if q.is_empty(){None}else{Some(q)}, sort_by: None, sort_order: None };
+	let params = TagQueryParams { page: Some(1), page_size: Some(10), search: if q.is_empty(){None}else{Some(q)}, sort_by: None, sort_order: None, status: None };
 	match tag_service.get_tags(params).await {
 		Ok(resp) => {
 			let suggestions: Vec<String> = resp.list.into_iter().map(|t| t.name).collect();
@@ -41,7 +41,7 @@ async fn get_ip_bans(
 ) -> Result<HttpResponse, actix_web::Error> {
     // 检查管理员权限
     use crate::utils::auth_helper::AuthHelper;
-    if !AuthHelper::is_admin(&req) {
+    if !AuthHelper::is_admin(&req).await {
         return Ok(HttpResponse::Forbidden().json(json!({
             "code": 403,
             "message": "只有管理员可以查看IP封禁列表"
@@ -71,7 +71,7 @@ async fn ban_ip(
 ) -> Result<HttpResponse, actix_web::Error> {
     // 检查管理员权限
     use crate::utils::auth_helper::AuthHelper;
-    if !AuthHelper::is_admin(&req) {
+    if !AuthHelper::is_admin(&req).await {
         return Ok(HttpResponse::Forbidden().json(json!({
             "code": 403,
             "message": "只有管理员可以封禁IP"
@@ -109,7 +109,7 @@ async fn unban_ip(
 ) -> Result<HttpResponse, actix_web::Error> {
     // 检查管理员权限
     use crate::utils::auth_helper::AuthHelper;
-    if !AuthHelper::is_admin(&req) {
+    if !AuthHelper::is_admin(&req).await {
         return Ok(HttpResponse::Forbidden().json(json!({
             "code": 403,
             "message": "只有管理员可以解除IP封禁"
@@ -117,7 +117,7 @@ async fn unban_ip(
     }
 
     let ip_address = data["ip_address"].as_str().unwrap_or("");
-    let admin_name = AuthHelper::get_username(&req).unwrap_or_else(|| "admin".to_string());
+    let admin_name = AuthHelper::get_username(&req).await.unwrap_or_else(|| "admin".to_string());
 
     if ip_address.is_empty() {
         return Ok(HttpResponse::BadRequest().json(json!({
@@ -145,7 +145,7 @@ async fn get_ip_whitelist(
 ) -> Result<HttpResponse, actix_web::Error> {
     // 检查管理员权限
     use crate::utils::auth_helper::AuthHelper;
-    if !AuthHelper::is_admin(&req) {
+    if !AuthHelper::is_admin(&req).await {
         return Ok(HttpResponse::Forbidden().json(json!({
             "code": 403,
             "message": "只有管理员可以查看IP白名单"
@@ -175,7 +175,7 @@ async fn add_ip_to_whitelist(
 ) -> Result<HttpResponse, actix_web::Error> {
     // 检查管理员权限
     use crate::utils::auth_helper::AuthHelper;
-    if !AuthHelper::is_admin(&req) {
+    if !AuthHelper::is_admin(&req).await {
         return Ok(HttpResponse::Forbidden().json(json!({
             "code": 403,
             "message": "只有管理员可以管理IP白名单"
@@ -184,7 +184,7 @@ async fn add_ip_to_whitelist(
 
     let ip_address = data["ip_address"].as_str().unwrap_or("");
     let description = data["description"].as_str().unwrap_or("管理员添加");
-    let admin_name = AuthHelper::get_username(&req).unwrap_or_else(|| "admin".to_string());
+    let admin_name = AuthHelper::get_username(&req).await.unwrap_or_else(|| "admin".to_string());
 
     if ip_address.is_empty() {
         return Ok(HttpResponse::BadRequest().json(json!({
@@ -213,7 +213,7 @@ async fn remove_ip_from_whitelist(
 ) -> Result<HttpResponse, actix_web::Error> {
     // 检查管理员权限
     use crate::utils::auth_helper::AuthHelper;
-    if !AuthHelper::is_admin(&req) {
+    if !AuthHelper::is_admin(&req).await {
         return Ok(HttpResponse::Forbidden().json(json!({
             "code": 403,
             "message": "只有管理员可以管理IP白名单"
@@ -221,7 +221,7 @@ async fn remove_ip_from_whitelist(
     }
 
     let ip_address = data["ip_address"].as_str().unwrap_or("");
-    let admin_name = AuthHelper::get_username(&req).unwrap_or_else(|| "admin".to_string());
+    let admin_name = AuthHelper::get_username(&req).await.unwrap_or_else(|| "admin".to_string());
 
     if ip_address.is_empty() {
         return Ok(HttpResponse::BadRequest().json(json!({
@@ -249,7 +249,7 @@ async fn get_ban_stats(
 ) -> Result<HttpResponse, actix_web::Error> {
     // 检查管理员权限
     use crate::utils::auth_helper::AuthHelper;
-    if !AuthHelper::is_admin(&req) {
+    if !AuthHelper::is_admin(&req).await {
         return Ok(HttpResponse::Forbidden().json(json!({
             "code": 403,
             "message": "只有管理员可以查看封禁统计"
@@ -276,7 +276,7 @@ async fn get_security_config(
 ) -> Result<HttpResponse, actix_web::Error> {
     // 检查管理员权限
     use crate::utils::auth_helper::AuthHelper;
-    if !AuthHelper::is_admin(&req) {
+    if !AuthHelper::is_admin(&req).await {
         return Ok(HttpResponse::Forbidden().json(json!({
             "code": 403,
             "message": "只有管理员可以查看安全配置"
@@ -305,7 +305,7 @@ async fn update_security_config(
 ) -> Result<HttpResponse, actix_web::Error> {
     // 检查管理员权限
     use crate::utils::auth_helper::AuthHelper;
-    if !AuthHelper::is_admin(&req) {
+    if !AuthHelper::is_admin(&req).await {
         return Ok(HttpResponse::Forbidden().json(json!({
             "code": 403,
             "message": "只有管理员可以更新安全配置"
@@ -334,7 +334,7 @@ async fn get_view_security_stats(
     download_security: web::Data<crate::services::download_security_service::DownloadSecurityService>,
 ) -> Result<HttpResponse, actix_web::Error> {
     use crate::utils::auth_helper::AuthHelper;
-    if !AuthHelper::is_admin(&req) {
+    if !AuthHelper::is_admin(&req).await {
         return Ok(HttpResponse::Forbidden().json(json!({
             "code": 403,
             "message": "只有管理员可以查看"
@@ -0,0 +1,48 @@
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use serde_json::json;
+
+use crate::models::site_metrics::{RetentionQuery, RollupMetricsRequest, SiteMetricsQuery};
+use crate::require_admin;
+use crate::services::site_metrics_service::SiteMetricsService;
+use crate::utils::error::ServiceError;
+
+// 路由注册在 /admin 作用域内（见 admin.rs）
+
+/// 管理员：按日期范围查看全站汇总数据，?from=&to=&compare=previous|year|none
+pub async fn admin_get_metrics(
+    req: HttpRequest,
+    query: web::Query<SiteMetricsQuery>,
+    metrics_service: web::Data<SiteMetricsService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let _ = require_admin!(&req);
+    match metrics_service.report(&query).await {
+        Ok(report) => Ok(HttpResponse::Ok().json(json!({"code": 0, "message": "success", "data": report}))),
+        Err(e) => Ok(ServiceError::from(e).error_response()),
+    }
+}
+
+/// 管理员：注册周留存，?weeks= 为注册周数量
+pub async fn admin_get_retention(
+    req: HttpRequest,
+    query: web::Query<RetentionQuery>,
+    metrics_service: web::Data<SiteMetricsService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let _ = require_admin!(&req);
+    match metrics_service.retention(query.weeks).await {
+        Ok(list) => Ok(HttpResponse::Ok().json(json!({"code": 0, "message": "success", "data": {"list": list}}))),
+        Err(e) => Ok(ServiceError::from(e).error_response()),
+    }
+}
+
+/// 管理员：重新汇总已结束的日期范围
+pub async fn admin_rollup_metrics(
+    req: HttpRequest,
+    body: web::Json<RollupMetricsRequest>,
+    metrics_service: web::Data<SiteMetricsService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let _ = require_admin!(&req);
+    match metrics_service.rollup_range(&body).await {
+        Ok(days) => Ok(HttpResponse::Ok().json(json!({"code": 0, "message": "汇总完成", "data": {"days": days}}))),
+        Err(e) => Ok(ServiceError::from(e).error_response()),
+    }
+}
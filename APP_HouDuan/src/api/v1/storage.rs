@@ -4,6 +4,7 @@ use futures_util::stream::StreamExt as _;
 use crate::models::ApiResponse;
 use crate::services::package_storage_service::{PackageStorageService, StorageStats, CleanupResult};
 use crate::services::package_service::PackageService;
+use crate::services::category_service::CategoryService;
 use crate::middleware::auth::AuthenticatedUser;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -97,6 +98,7 @@ async fn presign_upload(
 async fn upload_file(
     mut payload: Multipart,
     _auth_user: AuthenticatedUser,
+    category_service: web::Data<CategoryService>,
 ) -> Result<HttpResponse> {
     let db_path = "data.db"; // 从配置中获取
     let mut storage_service = match PackageStorageService::get_instance(db_path).await {
@@ -167,6 +169,13 @@ async fn upload_file(
                   file_name.to_lowercase().ends_with(".gif") ||
                   file_name.to_lowercase().ends_with(".webp");
 
+    // 资源文件须符合所在分类允许的扩展名与文件大小
+    if let (false, Some(pkg_id)) = (is_image, package_id) {
+        if let Err(e) = category_service.check_upload(pkg_id, &file_name, file_data.len()).await {
+            return Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(400, &e.to_string())));
+        }
+    }
+
     // 根据文件类型和绑定ID选择上传方法
     let upload_result = if is_image && post_id.is_some() {
         // 帖子图片
@@ -222,7 +231,7 @@ async fn upload_file(
                             cover_image: None,
                             requirements: None,
                             included_files: None,
-                            author: None,
+                            owner_id: None,
                         };
                         
                         match package_service.update_package(pkg_id, &update_req).await {
@@ -293,7 +302,7 @@ async fn upload_file(
                             cover_image: None,
                             requirements: None,
                             included_files: Some(included_files),
-                            author: None,
+                            owner_id: None,
                         };
                         
                         let _ = package_service.update_package(pkg_id, &update_req).await;
@@ -1,8 +1,10 @@
 use actix_web::{web, HttpRequest, HttpResponse};
 use serde_json::json;
 use crate::services::tag_service::TagService;
-use crate::models::{CreateTagRequest, UpdateTagRequest, TagQueryParams};
+use crate::models::{CreateTagRequest, UpdateTagRequest, TagQueryParams, CreateTagAliasRequest, MergeTagRequest, UpdateTagStatusRequest, TagSettings, CheckTagsRequest, UserRole};
 use crate::utils::auth_helper::AuthHelper;
+use crate::utils::error::ServiceError;
+use actix_web::ResponseError;
 
 pub fn configure_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
@@ -28,6 +30,36 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
                 web::resource("/stats")
                     .route(web::get().to(get_tag_stats))
             )
+            .service(
+                web::resource("/settings")
+                    .route(web::get().to(get_tag_settings))
+                    .route(web::put().to(update_tag_settings))
+            )
+            .service(
+                web::resource("/check")
+                    .route(web::post().to(check_tags))
+            )
+            .service(
+                web::resource("/aliases")
+                    .route(web::get().to(get_all_aliases))
+            )
+            .service(
+                web::resource("/aliases/{alias_id}")
+                    .route(web::delete().to(delete_alias))
+            )
+            .service(
+                web::resource("/{id}/aliases")
+                    .route(web::get().to(get_tag_aliases))
+                    .route(web::post().to(add_alias))
+            )
+            .service(
+                web::resource("/{id}/merge")
+                    .route(web::post().to(merge_tag))
+            )
+            .service(
+                web::resource("/{id}/status")
+                    .route(web::put().to(update_tag_status))
+            )
             .service(
                 web::resource("/{id}")
                     .route(web::get().to(get_tag))
@@ -67,7 +99,7 @@ async fn create_tag(
     tag_service: web::Data<TagService>,
 ) -> Result<HttpResponse, actix_web::Error> {
     // 验证用户权限（只有管理员和元老可以创建标签）
-    let user = match AuthHelper::verify_user(&http_req) {
+    let user = match AuthHelper::verify_user(&http_req).await {
         Ok(user) => user,
         Err(_) => {
             return Ok(HttpResponse::Unauthorized().json(json!({
@@ -96,24 +128,14 @@ async fn create_tag(
         Err(e) => {
             log::error!("创建标签失败: {}", e);
             
-            // 检查是否是唯一约束错误
-            let error_message = if let rusqlite::Error::SqliteFailure(_, Some(msg)) = &e {
-                if msg.contains("已存在") {
-                    msg.clone()
-                } else {
-                    "创建标签失败".to_string()
-                }
-            } else if e.to_string().contains("UNIQUE constraint failed") {
-                "标签名称已存在，请使用其他名称".to_string()
-            } else {
-                "创建标签失败".to_string()
-            };
-            
-            Ok(HttpResponse::BadRequest().json(json!({
-                "code": 400,
-                "message": error_message,
-                "msg": error_message
-            })))
+            if e.to_string().contains("UNIQUE constraint failed") {
+                return Ok(HttpResponse::BadRequest().json(json!({
+                    "code": 400,
+                    "message": "标签名称已存在，请使用其他名称",
+                    "msg": "标签名称已存在，请使用其他名称"
+                })));
+            }
+            Ok(error_response(e, "创建标签失败"))
         }
     }
 }
@@ -156,7 +178,7 @@ async fn update_tag(
     let tag_id = path.into_inner();
     
     // 验证用户权限（只有管理员和元老可以更新标签）
-    let user = match AuthHelper::verify_user(&http_req) {
+    let user = match AuthHelper::verify_user(&http_req).await {
         Ok(user) => user,
         Err(_) => {
             return Ok(HttpResponse::Unauthorized().json(json!({
@@ -174,18 +196,15 @@ async fn update_tag(
     }
 
     match tag_service.update_tag(tag_id, req.into_inner()).await {
-        Ok(_) => Ok(HttpResponse::Ok().json(json!({
+        Ok(true) => Ok(HttpResponse::Ok().json(json!({
             "code": 0,
             "message": "标签更新成功",
             "msg": "标签更新成功"
         }))),
+        Ok(false) => Ok(tag_not_found()),
         Err(e) => {
             log::error!("更新标签失败: {}", e);
-            Ok(HttpResponse::InternalServerError().json(json!({
-                "code": 500,
-                "message": "更新标签失败",
-                "msg": "更新标签失败"
-            })))
+            Ok(error_response(e, "更新标签失败"))
         }
     }
 }
@@ -199,7 +218,7 @@ async fn delete_tag(
     let tag_id = path.into_inner();
     
     // 验证用户权限（只有管理员可以删除标签）
-    let user = match AuthHelper::verify_user(&http_req) {
+    let user = match AuthHelper::verify_user(&http_req).await {
         Ok(user) => user,
         Err(_) => {
             return Ok(HttpResponse::Unauthorized().json(json!({
@@ -286,7 +305,7 @@ async fn update_tag_counts(
     tag_service: web::Data<TagService>,
 ) -> Result<HttpResponse, actix_web::Error> {
     // 验证管理员权限
-    let _user = match AuthHelper::require_admin(&http_req) {
+    let _user = match AuthHelper::require_admin(&http_req).await {
         Ok(user) => user,
         Err(e) => return Ok(e.to_response()),
     };
@@ -326,4 +345,224 @@ async fn get_tag_stats(
             })))
         }
     }
-} 
\ No newline at end of file
+}
+
+// 标签治理接口的错误：业务校验错误按其类型返回对应状态码与提示信息，其余返回 500
+fn error_response(e: impl Into<anyhow::Error>, fallback: &str) -> HttpResponse {
+    match ServiceError::from(e.into()) {
+        ServiceError::Internal(_) => HttpResponse::InternalServerError().json(json!({
+            "code": 500,
+            "message": fallback,
+            "msg": fallback
+        })),
+        e => {
+            let msg = e.to_string();
+            HttpResponse::build(e.status_code()).json(json!({
+                "code": e.status_code().as_u16(),
+                "message": msg,
+                "msg": msg
+            }))
+        }
+    }
+}
+
+fn tag_not_found() -> HttpResponse {
+    HttpResponse::NotFound().json(json!({
+        "code": 404,
+        "message": "标签不存在",
+        "msg": "标签不存在"
+    }))
+}
+
+// 获取标签设置（白名单模式）
+async fn get_tag_settings(
+    tag_service: web::Data<TagService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    match tag_service.whitelist_mode().await {
+        Ok(whitelist_mode) => Ok(HttpResponse::Ok().json(json!({
+            "code": 0,
+            "message": "success",
+            "data": TagSettings { whitelist_mode }
+        }))),
+        Err(e) => {
+            log::error!("获取标签设置失败: {}", e);
+            Ok(error_response(e, "获取标签设置失败"))
+        }
+    }
+}
+
+// 更新标签设置（仅管理员）
+async fn update_tag_settings(
+    http_req: HttpRequest,
+    req: web::Json<TagSettings>,
+    tag_service: web::Data<TagService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    if let Err(e) = AuthHelper::require_admin(&http_req).await {
+        return Ok(e.to_response());
+    }
+
+    match tag_service.set_whitelist_mode(req.whitelist_mode).await {
+        Ok(_) => Ok(HttpResponse::Ok().json(json!({
+            "code": 0,
+            "message": "标签设置已更新",
+            "data": req.into_inner()
+        }))),
+        Err(e) => {
+            log::error!("更新标签设置失败: {}", e);
+            Ok(error_response(e, "更新标签设置失败"))
+        }
+    }
+}
+
+// 校验标签：返回规范化、替换别名后的名称，供发布前预览
+async fn check_tags(
+    req: web::Json<CheckTagsRequest>,
+    tag_service: web::Data<TagService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    match tag_service.check_tags(&req.tags, None).await {
+        Ok(tags) => Ok(HttpResponse::Ok().json(json!({
+            "code": 0,
+            "message": "success",
+            "data": { "tags": tags }
+        }))),
+        Err(e) => Ok(error_response(e, "校验标签失败")),
+    }
+}
+
+// 全部别名
+async fn get_all_aliases(
+    tag_service: web::Data<TagService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    match tag_service.list_aliases(None).await {
+        Ok(aliases) => Ok(HttpResponse::Ok().json(json!({
+            "code": 0,
+            "message": "success",
+            "data": aliases
+        }))),
+        Err(e) => {
+            log::error!("获取标签别名失败: {}", e);
+            Ok(error_response(e, "获取标签别名失败"))
+        }
+    }
+}
+
+// 某个标签的别名
+async fn get_tag_aliases(
+    path: web::Path<i32>,
+    tag_service: web::Data<TagService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    match tag_service.list_aliases(Some(path.into_inner())).await {
+        Ok(aliases) => Ok(HttpResponse::Ok().json(json!({
+            "code": 0,
+            "message": "success",
+            "data": aliases
+        }))),
+        Err(e) => {
+            log::error!("获取标签别名失败: {}", e);
+            Ok(error_response(e, "获取标签别名失败"))
+        }
+    }
+}
+
+// 添加别名（管理员和元老）
+async fn add_alias(
+    http_req: HttpRequest,
+    path: web::Path<i32>,
+    req: web::Json<CreateTagAliasRequest>,
+    tag_service: web::Data<TagService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let user = match AuthHelper::require_roles(&http_req, &[UserRole::Admin, UserRole::Elder]).await {
+        Ok(user) => user,
+        Err(e) => return Ok(e.to_response()),
+    };
+
+    match tag_service.add_alias(path.into_inner(), &req.alias, user.id).await {
+        Ok(Some(alias)) => Ok(HttpResponse::Ok().json(json!({
+            "code": 0,
+            "message": "别名添加成功",
+            "data": alias
+        }))),
+        Ok(None) => Ok(tag_not_found()),
+        Err(e) => {
+            log::error!("添加标签别名失败: {}", e);
+            Ok(error_response(e, "添加标签别名失败"))
+        }
+    }
+}
+
+// 删除别名（管理员和元老）
+async fn delete_alias(
+    http_req: HttpRequest,
+    path: web::Path<i32>,
+    tag_service: web::Data<TagService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    if let Err(e) = AuthHelper::require_roles(&http_req, &[UserRole::Admin, UserRole::Elder]).await {
+        return Ok(e.to_response());
+    }
+
+    match tag_service.delete_alias(path.into_inner()).await {
+        Ok(true) => Ok(HttpResponse::Ok().json(json!({
+            "code": 0,
+            "message": "别名已删除"
+        }))),
+        Ok(false) => Ok(HttpResponse::NotFound().json(json!({
+            "code": 404,
+            "message": "别名不存在"
+        }))),
+        Err(e) => {
+            log::error!("删除标签别名失败: {}", e);
+            Ok(error_response(e, "删除标签别名失败"))
+        }
+    }
+}
+
+// 合并标签（仅管理员）：当前标签的帖子与资源关联改到目标标签，当前标签名变为别名
+async fn merge_tag(
+    http_req: HttpRequest,
+    path: web::Path<i32>,
+    req: web::Json<MergeTagRequest>,
+    tag_service: web::Data<TagService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let user = match AuthHelper::require_admin(&http_req).await {
+        Ok(user) => user,
+        Err(e) => return Ok(e.to_response()),
+    };
+
+    match tag_service.merge_tags(path.into_inner(), req.target_id, user.id).await {
+        Ok(Some(tag)) => Ok(HttpResponse::Ok().json(json!({
+            "code": 0,
+            "message": "标签合并成功",
+            "data": tag
+        }))),
+        Ok(None) => Ok(tag_not_found()),
+        Err(e) => {
+            log::error!("合并标签失败: {}", e);
+            Ok(error_response(e, "合并标签失败"))
+        }
+    }
+}
+
+// 审核标签（管理员和元老）：approved / pending / banned
+async fn update_tag_status(
+    http_req: HttpRequest,
+    path: web::Path<i32>,
+    req: web::Json<UpdateTagStatusRequest>,
+    tag_service: web::Data<TagService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    if let Err(e) = AuthHelper::require_roles(&http_req, &[UserRole::Admin, UserRole::Elder]).await {
+        return Ok(e.to_response());
+    }
+
+    match tag_service.set_tag_status(path.into_inner(), &req.status).await {
+        Ok(Some(tag)) => Ok(HttpResponse::Ok().json(json!({
+            "code": 0,
+            "message": "标签状态已更新",
+            "data": tag
+        }))),
+        Ok(None) => Ok(tag_not_found()),
+        Err(e) => {
+            log::error!("更新标签状态失败: {}", e);
+            Ok(error_response(e, "更新标签状态失败"))
+        }
+    }
+}
@@ -2,11 +2,18 @@ use actix_web::{web, HttpResponse, HttpRequest};
 use serde_json::json;
 use crate::services::user_service::UserService;
 use crate::models::UpdateUserRequest;
+use crate::models::user::{MentionCandidate, MentionSuggestionQuery};
 use crate::utils::auth_helper::AuthHelper;
 #[macro_use] use crate::utils::auth_helper;
 use crate::services::user_action_service::UserActionService;
 use crate::services::package_service::PackageService;
 use crate::services::post_service::PostService;
+use crate::services::achievement_service::AchievementService;
+use crate::models::achievement::UserAchievement;
+use crate::services::points_service::PointsService;
+use crate::services::check_in_service::CheckInService;
+use crate::services::weekly_report_service::WeeklyReportService;
+use crate::models::weekly_report::{WeeklyReport, WeeklyReportQuery};
 use actix_multipart::Multipart;
 use futures_util::TryStreamExt;
 use std::io::Write;
@@ -40,17 +47,11 @@ struct WeeklyReportData {
     completed_projects: i32,
     current_streak: i32,
     today_activity: f32,
+    /// 周一到周日每天发布的帖子数（兼容旧字段，同 daily_posts）
     weekly_posts: Vec<i32>,
-    achievements: Vec<Achievement>,
-}
-
-#[derive(serde::Serialize)]
-struct Achievement {
-    id: i32,
-    name: String,
-    icon: String,
-    description: String,
-    earned_at: Option<String>,
+    achievements: Vec<UserAchievement>,
+    #[serde(flatten)]
+    report: WeeklyReport,
 }
 
 #[derive(serde::Serialize)]
@@ -107,6 +108,19 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
                 web::resource("/change-password")
                     .route(web::post().to(change_password))
             )
+            // @ 提及：自动补全与按用户名解析主页
+            .service(
+                web::resource("/mention-suggestions")
+                    .route(web::get().to(get_mention_suggestions))
+            )
+            .service(
+                web::resource("/by-username/{username}")
+                    .route(web::get().to(get_user_by_username))
+            )
+            .service(
+                web::resource("/me/weekly-report")
+                    .route(web::get().to(get_my_weekly_report))
+            )
             .service(
                 web::resource("/{id}/comments")
                     .route(web::get().to(crate::api::v1::comment::get_user_comments))
@@ -133,6 +147,14 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
                 web::resource("/{id}/latest-content")
                     .route(web::get().to(get_user_latest_content))
             )
+            .service(
+                web::resource("/{id}/achievements")
+                    .route(web::get().to(crate::api::v1::achievement::get_user_achievements))
+            )
+            .service(
+                web::resource("/{id}/level")
+                    .route(web::get().to(crate::api::v1::points::get_user_level))
+            )
             // 关注相关路由
             .service(
                 web::resource("/{id}/follow")
@@ -151,6 +173,25 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
                 web::resource("/{id}/following")
                     .route(web::get().to(get_user_following_adapter))
             )
+            // 拉黑与屏蔽
+            .service(
+                web::resource("/{id}/block")
+                    .route(web::post().to(crate::api::v1::user_relation::block_user))
+                    .route(web::delete().to(crate::api::v1::user_relation::unblock_user))
+            )
+            .service(
+                web::resource("/{id}/mute")
+                    .route(web::post().to(crate::api::v1::user_relation::mute_user))
+                    .route(web::delete().to(crate::api::v1::user_relation::unmute_user))
+            )
+            .service(
+                web::resource("/{id}/relation")
+                    .route(web::get().to(crate::api::v1::user_relation::get_relation_status))
+            )
+            .service(
+                web::resource("/{id}/collections")
+                    .route(web::get().to(crate::api::v1::collection::get_user_collections))
+            )
     );
 
     // 新增：/me 别名集合
@@ -177,9 +218,26 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
                 web::resource("/achievements")
                     .route(web::get().to(get_my_achievements))
             )
+            .service(
+                web::resource("/level")
+                    .route(web::get().to(crate::api::v1::points::get_my_level))
+            )
+            .service(
+                web::resource("/points/ledger")
+                    .route(web::get().to(crate::api::v1::points::get_my_ledger))
+            )
             .service(
                 web::resource("/check-in")
-                    .route(web::post().to(user_check_in))
+                    .route(web::get().to(crate::api::v1::check_in::get_check_in_status))
+                    .route(web::post().to(crate::api::v1::check_in::check_in))
+            )
+            .service(
+                web::resource("/check-in/calendar")
+                    .route(web::get().to(crate::api::v1::check_in::get_check_in_calendar))
+            )
+            .service(
+                web::resource("/check-in/makeup")
+                    .route(web::post().to(crate::api::v1::check_in::make_up_check_in))
             )
             .service(
                 web::resource("/resources")
@@ -202,6 +260,45 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
                 web::resource("/following")
                     .route(web::get().to(crate::api::v1::follow::get_my_following))
             )
+            .service(
+                web::resource("/blocks")
+                    .route(web::get().to(crate::api::v1::user_relation::get_my_blocks))
+            )
+            .service(
+                web::resource("/mutes")
+                    .route(web::get().to(crate::api::v1::user_relation::get_my_mutes))
+            )
+            // 收藏与收藏夹
+            .service(
+                web::resource("/favorites")
+                    .route(web::get().to(crate::api::v1::collection::get_my_favorites))
+            )
+            .service(
+                web::resource("/collections")
+                    .route(web::get().to(crate::api::v1::collection::get_my_collections))
+            )
+            .service(
+                web::resource("/followed-collections")
+                    .route(web::get().to(crate::api::v1::collection::get_followed_collections))
+            )
+            // 资源所有权转让
+            .service(
+                web::resource("/package-transfers")
+                    .route(web::get().to(crate::api::v1::package_maintainer::get_my_incoming_transfers))
+            )
+            .service(
+                web::resource("/package-transfers/{id}/accept")
+                    .route(web::post().to(crate::api::v1::package_maintainer::accept_transfer))
+            )
+            .service(
+                web::resource("/package-transfers/{id}/decline")
+                    .route(web::post().to(crate::api::v1::package_maintainer::decline_transfer))
+            )
+            // 草稿与定时发布
+            .service(
+                web::resource("/drafts")
+                    .route(web::get().to(crate::api::v1::draft::get_my_drafts))
+            )
     );
 }
 
@@ -211,7 +308,7 @@ async fn get_users(
     query: web::Query<GetUsersQuery>,
 ) -> Result<HttpResponse, actix_web::Error> {
     // 验证管理员权限
-    match AuthHelper::require_admin(&http_req) {
+    match AuthHelper::require_admin(&http_req).await {
         Ok(_admin_user) => {
             match user_service.get_users().await {
                 Ok(mut users) => {
@@ -273,12 +370,62 @@ async fn get_user(
     }
 }
 
+async fn get_mention_suggestions(
+    query: web::Query<MentionSuggestionQuery>,
+    user_service: web::Data<UserService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let prefix = query.q.as_deref().unwrap_or("").trim().trim_start_matches('@');
+    if prefix.is_empty() {
+        return Ok(HttpResponse::Ok().json(json!({ "code": 0, "message": "success", "data": [] })));
+    }
+    match user_service.suggest_mentions(prefix, query.limit.unwrap_or(8)).await {
+        Ok(list) => Ok(HttpResponse::Ok().json(json!({
+            "code": 0,
+            "message": "success",
+            "data": list
+        }))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(json!({
+            "code": 500,
+            "message": e.to_string()
+        })))
+    }
+}
+
+// 按用户名解析 @ 提及对应的用户，供前端生成主页链接
+async fn get_user_by_username(
+    path: web::Path<String>,
+    user_service: web::Data<UserService>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let username = path.into_inner();
+    match user_service.get_user_by_username(&username).await {
+        Ok(Some(user)) => Ok(HttpResponse::Ok().json(json!({
+            "code": 0,
+            "message": "success",
+            "data": MentionCandidate {
+                id: user.id,
+                username: user.username,
+                nickname: user.nickname,
+                avatar_url: user.avatar_url,
+            }
+        }))),
+        Ok(None) => Ok(HttpResponse::NotFound().json(json!({
+            "code": 404,
+            "message": "用户不存在"
+        }))),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(json!({
+            "code": 500,
+            "message": e.to_string()
+        })))
+    }
+}
+
 async fn get_user_profile(
     path: web::Path<i32>,
     req: HttpRequest,
     user_service: web::Data<UserService>,
     follow_repo: web::Data<Arc<FollowRepository>>,
     jwt_utils: web::Data<Arc<JwtUtils>>,
+    points_service: web::Data<PointsService>,
 ) -> Result<HttpResponse, actix_web::Error> {
     let user_id = path.into_inner();
     
@@ -301,9 +448,11 @@ async fn get_user_profile(
             let conn = crate::repositories::get_connection().map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
             
             let posts_count: i64 = conn.query_row("SELECT COUNT(*) FROM posts WHERE author_id = ? AND status = 'Published'", rusqlite::params![user.id], |r| r.get(0)).unwrap_or(0);
-            let resources_count: i64 = conn.query_row("SELECT COUNT(*) FROM packages WHERE author = ? AND status = 'active'", rusqlite::params![user.username], |r| r.get(0)).unwrap_or(0);
-            let total_views: i64 = conn.query_row("SELECT COALESCE(SUM(view_count),0) FROM posts WHERE author_id = ?", rusqlite::params![user.id], |r| r.get(0)).unwrap_or(0);
-            let total_likes: i64 = conn.query_row("SELECT COALESCE(SUM(like_count),0) FROM posts WHERE author_id = ?", rusqlite::params![user.id], |r| r.get(0)).unwrap_or(0);
+            let resources_count: i64 = conn.query_row("SELECT COUNT(*) FROM packages WHERE owner_id = ? AND status = 'active'", rusqlite::params![user.id], |r| r.get(0)).unwrap_or(0);
+            let total_views: i64 = conn.query_row("SELECT COALESCE(SUM(view_count),0) FROM posts WHERE author_id = ? AND status != 'Deleted'", rusqlite::params![user.id], |r| r.get(0)).unwrap_or(0);
+            let total_likes: i64 = conn.query_row("SELECT COALESCE(SUM(like_count),0) FROM posts WHERE author_id = ? AND status != 'Deleted'", rusqlite::params![user.id], |r| r.get(0)).unwrap_or(0);
+            
+            let level = points_service.level_progress(user.id).await.ok();
             
             // 获取关注统计
             let follow_stats = follow_repo.get_follow_stats(user_id).await.unwrap_or_default();
@@ -346,6 +495,10 @@ async fn get_user_profile(
                 "total_likes": total_likes as i32,
                 "total_views": total_views as i32,
                 "total_downloads": user.download_count,
+                "level": level.as_ref().map(|l| l.level),
+                "level_title": level.as_ref().map(|l| l.title.clone()),
+                "experience": level.as_ref().map(|l| l.experience),
+                "stars": level.as_ref().map(|l| l.stars),
                 "created_at": user.created_at.format("%Y-%m-%d").to_string(),
                 "is_following": is_following
             });
@@ -374,7 +527,7 @@ async fn update_user(
     user_service: web::Data<UserService>,
 ) -> Result<HttpResponse, actix_web::Error> {
     // 验证管理员权限
-    match AuthHelper::require_admin(&http_req) {
+    match AuthHelper::require_admin(&http_req).await {
         Ok(_admin_user) => {
             let user_id = path.into_inner();
             match user_service.update_user(user_id, &req).await {
@@ -401,10 +554,10 @@ async fn delete_user(
     user_service: web::Data<UserService>,
 ) -> Result<HttpResponse, actix_web::Error> {
     // 验证管理员权限
-    match AuthHelper::require_admin(&http_req) {
-        Ok(_admin_user) => {
+    match AuthHelper::require_admin(&http_req).await {
+        Ok(admin_user) => {
             let user_id = path.into_inner();
-            match user_service.delete_user(user_id).await {
+            match user_service.delete_user(user_id, admin_user.id).await {
                 Ok(_) => Ok(HttpResponse::Ok().json(json!({
                     "code": 0,
                     "message": "删除成功"
@@ -428,7 +581,7 @@ async fn get_current_user_profile(
     user_service: web::Data<UserService>,
 ) -> Result<HttpResponse, actix_web::Error> {
     // 验证用户认证并获取当前用户ID
-    let user = match AuthHelper::verify_user(&http_req) {
+    let user = match AuthHelper::verify_user(&http_req).await {
         Ok(user) => user,
         Err(e) => return Ok(e.to_response()),
     };
@@ -476,7 +629,7 @@ async fn update_current_user_profile(
     user_service: web::Data<UserService>,
 ) -> Result<HttpResponse, actix_web::Error> {
     // 验证用户认证并获取当前用户ID
-    let user = match AuthHelper::verify_user(&http_req) {
+    let user = match AuthHelper::verify_user(&http_req).await {
         Ok(user) => user,
         Err(e) => return Ok(e.to_response()),
     };
@@ -500,7 +653,7 @@ async fn get_my_resources(
     user_service: web::Data<UserService>,
 ) -> Result<HttpResponse, actix_web::Error> {
     // 验证用户认证并获取当前用户ID
-    let user = match AuthHelper::verify_user(&http_req) {
+    let user = match AuthHelper::verify_user(&http_req).await {
         Ok(user) => user,
         Err(e) => return Ok(e.to_response()),
     };
@@ -546,7 +699,7 @@ async fn get_my_comments(
     user_service: web::Data<UserService>,
 ) -> Result<HttpResponse, actix_web::Error> {
     // 验证用户认证并获取当前用户ID
-    let user = match AuthHelper::verify_user(&http_req) {
+    let user = match AuthHelper::verify_user(&http_req).await {
         Ok(user) => user,
         Err(e) => return Ok(e.to_response()),
     };
@@ -647,15 +800,20 @@ async fn create_user(
 
 // 批量删除用户（支持 ids 或 usernames）
 async fn batch_delete_users(
+    http_req: HttpRequest,
     req: web::Json<serde_json::Value>,
     user_service: web::Data<UserService>,
 ) -> Result<HttpResponse, actix_web::Error> {
+    let admin_user = match AuthHelper::require_admin(&http_req).await {
+        Ok(user) => user,
+        Err(_) => return Ok(HttpResponse::Forbidden().json(json!({"code":403,"message":"需要管理员权限"}))),
+    };
     // 优先按ids删除
     if let Some(ids_val) = req.get("ids") {
         if let Some(arr) = ids_val.as_array() {
             let ids: Vec<i32> = arr.iter().filter_map(|v| v.as_i64().map(|x| x as i32)).collect();
             if !ids.is_empty() {
-                match user_service.batch_delete_users_by_ids(ids).await {
+                match user_service.batch_delete_users_by_ids(ids, admin_user.id).await {
                     Ok(_) => return Ok(HttpResponse::Ok().json(json!({"code":0,"message":"批量删除成功"}))),
                     Err(e) => return Ok(HttpResponse::InternalServerError().json(json!({"code":500,"message":e.to_string()})))
                 }
@@ -669,7 +827,7 @@ async fn batch_delete_users(
     if usernames.is_empty() {
         return Ok(HttpResponse::BadRequest().json(json!({"code":400,"message":"缺少 ids 或 usernames"})));
     }
-    match user_service.batch_delete_users(usernames).await {
+    match user_service.batch_delete_users(usernames, admin_user.id).await {
         Ok(_) => Ok(HttpResponse::Ok().json(json!({"code":0,"message":"批量删除成功"}))),
         Err(e) => Ok(HttpResponse::InternalServerError().json(json!({"code":500,"message":e.to_string()})))
     }
@@ -686,7 +844,7 @@ async fn get_my_likes(
     package_service: web::Data<PackageService>,
     post_service: web::Data<PostService>,
 ) -> Result<HttpResponse, actix_web::Error> {
-    let user = match AuthHelper::verify_user(&http_req) { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
+    let user = match AuthHelper::verify_user(&http_req).await { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
     let page = query.page.unwrap_or(1).max(1);
     let page_size = query.page_size.unwrap_or(10).max(1);
     let target = query.target.clone().unwrap_or_default().to_lowercase();
@@ -746,7 +904,7 @@ async fn get_my_likes_stats(
     http_req: HttpRequest,
     _ua_service: web::Data<UserActionService>,
 ) -> Result<HttpResponse, actix_web::Error> {
-    let user = match AuthHelper::verify_user(&http_req) { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
+    let user = match AuthHelper::verify_user(&http_req).await { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
     use rusqlite::Connection;
     let conn = crate::repositories::get_connection().map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
     let like_pkg: i64 = conn.query_row("SELECT COUNT(*) FROM package_likes WHERE user_id = ?", rusqlite::params![user.id], |r| r.get(0)).unwrap_or(0);
@@ -770,60 +928,45 @@ async fn get_my_stats(
     post_service: web::Data<PostService>,
     package_service: web::Data<PackageService>,
 ) -> Result<HttpResponse, actix_web::Error> {
-    let user = match AuthHelper::verify_user(&http_req) { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
+    let user = match AuthHelper::verify_user(&http_req).await { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
     use rusqlite::Connection;
     let conn = crate::repositories::get_connection().map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
-    let posts: i64 = conn.query_row("SELECT COUNT(*) FROM posts WHERE author_id = ?", rusqlite::params![user.id], |r| r.get(0)).unwrap_or(0);
-    let resources: i64 = conn.query_row("SELECT COUNT(*) FROM packages WHERE author = (SELECT username FROM users WHERE id = ?)", rusqlite::params![user.id], |r| r.get(0)).unwrap_or(0);
-    let views: i64 = conn.query_row("SELECT COALESCE(SUM(view_count),0) FROM posts WHERE author_id = ?", rusqlite::params![user.id], |r| r.get(0)).unwrap_or(0);
-    let likes: i64 = conn.query_row("SELECT COALESCE(SUM(like_count),0) FROM posts WHERE author_id = ?", rusqlite::params![user.id], |r| r.get(0)).unwrap_or(0);
+    let posts: i64 = conn.query_row("SELECT COUNT(*) FROM posts WHERE author_id = ? AND status != 'Deleted'", rusqlite::params![user.id], |r| r.get(0)).unwrap_or(0);
+    let resources: i64 = conn.query_row("SELECT COUNT(*) FROM packages WHERE owner_id = ? AND status != 'deleted'", rusqlite::params![user.id], |r| r.get(0)).unwrap_or(0);
+    let views: i64 = conn.query_row("SELECT COALESCE(SUM(view_count),0) FROM posts WHERE author_id = ? AND status != 'Deleted'", rusqlite::params![user.id], |r| r.get(0)).unwrap_or(0);
+    let likes: i64 = conn.query_row("SELECT COALESCE(SUM(like_count),0) FROM posts WHERE author_id = ? AND status != 'Deleted'", rusqlite::params![user.id], |r| r.get(0)).unwrap_or(0);
     Ok(HttpResponse::Ok().json(json!({"code":0, "message":"success", "data": {"posts": posts, "resources": resources, "views": views, "likes": likes}})))
 }
 
-// 新增：我的周报（占位）
+// 我的周报：GET /me/weekly-report?week=2025-W01（默认本周）
 async fn get_my_weekly_report(
     http_req: HttpRequest,
-    user_service: web::Data<UserService>,
+    query: web::Query<WeeklyReportQuery>,
+    weekly_report_service: web::Data<WeeklyReportService>,
+    check_in_service: web::Data<CheckInService>,
+    achievement_service: web::Data<AchievementService>,
 ) -> Result<HttpResponse, actix_web::Error> {
-    let user = match AuthHelper::verify_user(&http_req) { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
-    use rusqlite::Connection;
+    let user = match AuthHelper::verify_user(&http_req).await { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
+    let week = match weekly_report_service.resolve_week(query.week.as_deref()) {
+        Ok(w) => w,
+        Err(e) => return Ok(HttpResponse::BadRequest().json(json!({"code": 400, "message": e.to_string()}))),
+    };
+    let report = match weekly_report_service.get_report(user.id, week).await {
+        Ok(r) => r,
+        Err(e) => return Ok(HttpResponse::InternalServerError().json(json!({"code": 500, "message": e.to_string()}))),
+    };
     let conn = crate::repositories::get_connection().map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
-    
-    // 获取本周的统计数据
-    let total_posts: i64 = conn.query_row("SELECT COUNT(*) FROM posts WHERE author_id = ?", rusqlite::params![user.id], |r| r.get(0)).unwrap_or(0);
-    let total_resources: i64 = conn.query_row("SELECT COUNT(*) FROM packages WHERE author = (SELECT username FROM users WHERE id = ?)", rusqlite::params![user.id], |r| r.get(0)).unwrap_or(0);
-    
-    // 获取本周每天的发布数量（示例数据）
-    let weekly_posts = vec![2, 1, 3, 0, 2, 4, 1]; // 周一到周日的发布数量
-    
-    // 计算连续签到天数（示例）
-    let current_streak = 12;
-    
-    // 今日活跃度（示例）
-    let today_activity = 85.0;
-    
+    let total_posts: i64 = conn.query_row("SELECT COUNT(*) FROM posts WHERE author_id = ? AND status != 'Deleted'", rusqlite::params![user.id], |r| r.get(0)).unwrap_or(0);
+    let total_resources: i64 = conn.query_row("SELECT COUNT(*) FROM packages WHERE owner_id = ? AND status != 'deleted'", rusqlite::params![user.id], |r| r.get(0)).unwrap_or(0);
+
     let report_data = WeeklyReportData {
         total_posts: total_posts as i32,
         completed_projects: total_resources as i32,
-        current_streak,
-        today_activity,
-        weekly_posts,
-        achievements: vec![
-            Achievement {
-                id: 1,
-                name: "初学者".to_string(),
-                icon: "🌱".to_string(),
-                description: "完成第一个课程".to_string(),
-                earned_at: Some("2024-01-15T10:30:00Z".to_string()),
-            },
-            Achievement {
-                id: 2,
-                name: "勤奋学习".to_string(),
-                icon: "📚".to_string(),
-                description: "连续学习7天".to_string(),
-                earned_at: Some("2024-01-20T15:45:00Z".to_string()),
-            },
-        ],
+        current_streak: check_in_service.status(user.id).await.map(|(s, _)| s.current_streak).unwrap_or(0),
+        today_activity: weekly_report_service.today_activity(user.id).await.unwrap_or(0.0),
+        weekly_posts: report.daily_posts.clone(),
+        achievements: achievement_service.list_earned_achievements(user.id).await.unwrap_or_default(),
+        report,
     };
     
     Ok(HttpResponse::Ok().json(json!({
@@ -837,16 +980,20 @@ async fn get_my_weekly_report(
 async fn get_my_activity_stats(
     http_req: HttpRequest,
     user_service: web::Data<UserService>,
+    points_service: web::Data<PointsService>,
 ) -> Result<HttpResponse, actix_web::Error> {
-    let user = match AuthHelper::verify_user(&http_req) { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
+    let user = match AuthHelper::verify_user(&http_req).await { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
     use rusqlite::Connection;
     let conn = crate::repositories::get_connection().map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
-    let posts: i64 = conn.query_row("SELECT COUNT(*) FROM posts WHERE author_id = ?", rusqlite::params![user.id], |r| r.get(0)).unwrap_or(0);
-    let resources: i64 = conn.query_row("SELECT COUNT(*) FROM packages WHERE author = (SELECT username FROM users WHERE id = ?)", rusqlite::params![user.id], |r| r.get(0)).unwrap_or(0);
-    let comments: i64 = conn.query_row("SELECT COUNT(*) FROM comments WHERE user_id = ?", rusqlite::params![user.id], |r| r.get(0)).unwrap_or(0);
-    let views: i64 = conn.query_row("SELECT COALESCE(SUM(view_count),0) FROM posts WHERE author_id = ?", rusqlite::params![user.id], |r| r.get(0)).unwrap_or(0);
-    let likes: i64 = conn.query_row("SELECT COALESCE(SUM(like_count),0) FROM posts WHERE author_id = ?", rusqlite::params![user.id], |r| r.get(0)).unwrap_or(0);
-    let downloads: i64 = conn.query_row("SELECT COALESCE(SUM(download_count),0) FROM packages WHERE author = (SELECT username FROM users WHERE id = ?)", rusqlite::params![user.id], |r| r.get(0)).unwrap_or(0);
+    let posts: i64 = conn.query_row("SELECT COUNT(*) FROM posts WHERE author_id = ? AND status != 'Deleted'", rusqlite::params![user.id], |r| r.get(0)).unwrap_or(0);
+    let resources: i64 = conn.query_row("SELECT COUNT(*) FROM packages WHERE owner_id = ? AND status != 'deleted'", rusqlite::params![user.id], |r| r.get(0)).unwrap_or(0);
+    let comments: i64 = conn.query_row("SELECT COUNT(*) FROM comments WHERE user_id = ? AND status != 'Deleted'", rusqlite::params![user.id], |r| r.get(0)).unwrap_or(0);
+    let views: i64 = conn.query_row("SELECT COALESCE(SUM(view_count),0) FROM posts WHERE author_id = ? AND status != 'Deleted'", rusqlite::params![user.id], |r| r.get(0)).unwrap_or(0);
+    let likes: i64 = conn.query_row("SELECT COALESCE(SUM(like_count),0) FROM posts WHERE author_id = ? AND status != 'Deleted'", rusqlite::params![user.id], |r| r.get(0)).unwrap_or(0);
+    let downloads: i64 = conn.query_row("SELECT COALESCE(SUM(download_count),0) FROM packages WHERE owner_id = ? AND status != 'deleted'", rusqlite::params![user.id], |r| r.get(0)).unwrap_or(0);
+
+    let level = points_service.level_progress(user.id).await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
 
     Ok(HttpResponse::Ok().json(json!({
         "code": 0,
@@ -858,60 +1005,39 @@ async fn get_my_activity_stats(
             "total_views": views as i32,
             "total_likes": likes as i32,
             "total_downloads": downloads as i32,
-            "level": "初级开发者", // 示例级别
-            "experience": 1200, // 示例经验值
-            "next_level_exp": 2000 // 示例下一级所需经验
+            "level": level.title,
+            "level_num": level.level,
+            "experience": level.experience,
+            "stars": level.stars,
+            // 已是最高等级时与当前经验相同
+            "next_level_exp": level.next_level_xp.unwrap_or(level.experience),
+            "level_progress": level.progress
         }
     })))
 }
 
-// 新增：我的成就
+// 新增：我的成就（先补发历史数据已满足条件的成就）
 async fn get_my_achievements(
     http_req: HttpRequest,
-    user_service: web::Data<UserService>,
+    achievement_service: web::Data<AchievementService>,
 ) -> Result<HttpResponse, actix_web::Error> {
-    let user = match AuthHelper::verify_user(&http_req) { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
-    use rusqlite::Connection;
-    let conn = crate::repositories::get_connection().map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
-    let mut achievements: Vec<Achievement> = Vec::new();
-
-    // 示例：获取用户已完成的成就
-    // 实际应用中，这些成就应该从数据库或配置文件加载
-    let completed_achievements = vec![
-        Achievement { id: 1, name: "新手入门".to_string(), icon: "🎉".to_string(), description: "完成首次登录".to_string(), earned_at: None },
-        Achievement { id: 2, name: "点赞达人".to_string(), icon: "👍".to_string(), description: "点赞超过100次".to_string(), earned_at: None },
-        Achievement { id: 3, name: "资源分享".to_string(), icon: "📦".to_string(), description: "分享超过5个资源".to_string(), earned_at: None },
-    ];
-
-    // 实际应用中，这里需要从数据库或配置文件加载所有可能的成就
-    // 例如：let all_achievements = load_all_achievements();
-    // 然后过滤出用户已完成的成就
-
-    Ok(HttpResponse::Ok().json(json!({
-        "code": 0,
-        "message": "success",
-        "data": {
-            "list": completed_achievements,
-            "total": completed_achievements.len() as i32
-        }
-    })))
-}
-
-// 新增：用户签到
-async fn user_check_in(
-    http_req: HttpRequest,
-    user_service: web::Data<UserService>,
-) -> Result<HttpResponse, actix_web::Error> {
-    let user = match AuthHelper::verify_user(&http_req) {
-        Ok(user) => user,
-        Err(e) => return Ok(e.to_response()),
-    };
-
-    match user_service.user_check_in(user.id).await {
-        Ok(_) => Ok(HttpResponse::Ok().json(json!({
-            "code": 0,
-            "message": "签到成功"
-        }))),
+    let user = match AuthHelper::verify_user(&http_req).await { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
+    if let Err(e) = achievement_service.evaluate(user.id, None).await {
+        log::error!("评估成就失败: {}", e);
+    }
+    match achievement_service.list_user_achievements(user.id).await {
+        Ok(list) => {
+            let earned = list.iter().filter(|a| a.earned).count();
+            Ok(HttpResponse::Ok().json(json!({
+                "code": 0,
+                "message": "success",
+                "data": {
+                    "list": list,
+                    "total": list.len(),
+                    "earned": earned
+                }
+            })))
+        },
         Err(e) => Ok(HttpResponse::InternalServerError().json(json!({
             "code": 500,
             "message": e.to_string()
@@ -925,7 +1051,7 @@ async fn get_my_posts(
     query: web::Query<serde_json::Value>,
     post_service: web::Data<PostService>,
 ) -> Result<HttpResponse, actix_web::Error> {
-    let user = match AuthHelper::verify_user(&http_req) { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
+    let user = match AuthHelper::verify_user(&http_req).await { Ok(u) => u, Err(e) => return Ok(e.to_response()) };
     let page = query.get("page").and_then(|v| v.as_u64()).unwrap_or(1) as i32;
     let page_size = query.get("pageSize").and_then(|v| v.as_u64()).unwrap_or(10) as i32;
     let params = crate::models::PostQueryParams { page: Some((page as u32)), page_size: Some((page_size as u32)), category_id: None, author_id: Some(user.id), status: None, search: None, tags: None, is_pinned: None, is_featured: None };
@@ -941,7 +1067,7 @@ async fn upload_avatar(
     http_req: HttpRequest,
     user_service: web::Data<UserService>,
 ) -> Result<HttpResponse, actix_web::Error> {
-    let user = match AuthHelper::verify_user(&http_req) {
+    let user = match AuthHelper::verify_user(&http_req).await {
         Ok(user) => user,
         Err(e) => return Ok(e.to_response()),
     };
@@ -1201,8 +1327,9 @@ async fn follow_user_adapter(
     follow_repo: web::Data<Arc<FollowRepository>>,
     user_repo: web::Data<Arc<UserRepository>>,
     jwt_utils: web::Data<Arc<JwtUtils>>,
+    notify: web::Data<crate::services::notification_service::NotificationService>,
 ) -> Result<HttpResponse, actix_web::Error> {
-    crate::api::v1::follow::follow_user(path, req, follow_repo, user_repo, jwt_utils).await
+    crate::api::v1::follow::follow_user(path, req, follow_repo, user_repo, jwt_utils, notify).await
 }
 
 async fn unfollow_user_adapter(
@@ -1246,6 +1373,7 @@ async fn get_user_profile_adapter(
     user_service: web::Data<UserService>,
     follow_repo: web::Data<Arc<FollowRepository>>,
     jwt_utils: web::Data<Arc<JwtUtils>>,
+    points_service: web::Data<PointsService>,
 ) -> Result<HttpResponse, actix_web::Error> {
-    get_user_profile(path, req, user_service, follow_repo, jwt_utils).await
+    get_user_profile(path, req, user_service, follow_repo, jwt_utils, points_service).await
 } 
\ No newline at end of file
@@ -0,0 +1,81 @@
+use actix_web::{web, HttpResponse, ResponseError};
+use serde_json::json;
+
+use crate::middleware::auth::AuthenticatedUser;
+use crate::services::user_relation_service::UserRelationService;
+use crate::utils::error::ServiceError;
+
+// 路由注册在 /users 与 /me 作用域内（见 user.rs）
+
+
+/// 拉黑用户（同时解除双方关注）
+pub async fn block_user(
+    path: web::Path<i32>,
+    relation_service: web::Data<UserRelationService>,
+    user: AuthenticatedUser,
+) -> HttpResponse {
+    match relation_service.block(user.id, path.into_inner()).await {
+        Ok(_) => HttpResponse::Ok().json(json!({"code": 0, "message": "已拉黑"})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+pub async fn unblock_user(
+    path: web::Path<i32>,
+    relation_service: web::Data<UserRelationService>,
+    user: AuthenticatedUser,
+) -> HttpResponse {
+    match relation_service.unblock(user.id, path.into_inner()).await {
+        Ok(_) => HttpResponse::Ok().json(json!({"code": 0, "message": "已取消拉黑"})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+/// 屏蔽用户（对方无感知）
+pub async fn mute_user(
+    path: web::Path<i32>,
+    relation_service: web::Data<UserRelationService>,
+    user: AuthenticatedUser,
+) -> HttpResponse {
+    match relation_service.mute(user.id, path.into_inner()).await {
+        Ok(_) => HttpResponse::Ok().json(json!({"code": 0, "message": "已屏蔽"})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+pub async fn unmute_user(
+    path: web::Path<i32>,
+    relation_service: web::Data<UserRelationService>,
+    user: AuthenticatedUser,
+) -> HttpResponse {
+    match relation_service.unmute(user.id, path.into_inner()).await {
+        Ok(_) => HttpResponse::Ok().json(json!({"code": 0, "message": "已取消屏蔽"})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+/// 我与某个用户的拉黑/屏蔽状态
+pub async fn get_relation_status(
+    path: web::Path<i32>,
+    relation_service: web::Data<UserRelationService>,
+    user: AuthenticatedUser,
+) -> HttpResponse {
+    match relation_service.status(user.id, path.into_inner()).await {
+        Ok(status) => HttpResponse::Ok().json(json!({"code": 0, "message": "success", "data": status})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+pub async fn get_my_blocks(relation_service: web::Data<UserRelationService>, user: AuthenticatedUser) -> HttpResponse {
+    match relation_service.list_blocked(user.id).await {
+        Ok(list) => HttpResponse::Ok().json(json!({"code": 0, "message": "success", "data": {"list": list, "total": list.len()}})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
+
+pub async fn get_my_mutes(relation_service: web::Data<UserRelationService>, user: AuthenticatedUser) -> HttpResponse {
+    match relation_service.list_muted(user.id).await {
+        Ok(list) => HttpResponse::Ok().json(json!({"code": 0, "message": "success", "data": {"list": list, "total": list.len()}})),
+        Err(e) => ServiceError::from(e).error_response(),
+    }
+}
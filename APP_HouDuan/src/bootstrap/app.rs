@@ -135,11 +135,31 @@ impl AppBuilder {
             .app_data(web::Data::new(services.post_service.clone()))
             .app_data(web::Data::new(services.tag_service.clone()))
             .app_data(web::Data::new(services.notification_service.clone()))
+            .app_data(web::Data::new(services.rating_service.clone()))
+            .app_data(web::Data::new(services.achievement_service.clone()))
+            .app_data(web::Data::new(services.points_service.clone()))
+            .app_data(web::Data::new(services.check_in_service.clone()))
+            .app_data(web::Data::new(services.weekly_report_service.clone()))
+            .app_data(web::Data::new(services.ranking_service.clone()))
+            .app_data(web::Data::new(services.recommendation_service.clone()))
+            .app_data(web::Data::new(services.message_service.clone()))
+            .app_data(web::Data::new(services.user_relation_service.clone()))
+            .app_data(web::Data::new(services.collection_service.clone()))
+            .app_data(web::Data::new(services.draft_service.clone()))
+            .app_data(web::Data::new(services.render_service.clone()))
+            .app_data(web::Data::new(services.edit_history_service.clone()))
+            .app_data(web::Data::new(services.package_dependency_service.clone()))
+            .app_data(web::Data::new(services.category_service.clone()))
+            .app_data(web::Data::new(services.package_analytics_service.clone()))
+            .app_data(web::Data::new(services.site_metrics_service.clone()))
+            .app_data(web::Data::new(services.recycle_bin_service.clone()))
+            .app_data(web::Data::new(services.package_maintainer_service.clone()))
             .app_data(web::Data::new(services.download_security_service.clone()))
             .app_data(web::Data::new(services.security_action_service.clone()))
             .app_data(web::Data::new(services.anti_fraud_service.clone()))
             .app_data(web::Data::new(Arc::new(services.follow_repo.clone())))
             .app_data(web::Data::new(Arc::new(services.user_repo.clone())))
-            .app_data(web::Data::new(Arc::new(services.post_repo.clone())));
+            .app_data(web::Data::new(Arc::new(services.post_repo.clone())))
+            .app_data(web::Data::new(services.timeline_repo.clone()));
     }
 }
\ No newline at end of file
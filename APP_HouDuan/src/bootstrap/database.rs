@@ -7,6 +7,66 @@ use std::fs;
 use crate::config::Config;
 use super::BootstrapError;
 
+/// 启动时按顺序执行的迁移脚本：(版本, 名称, SQL)
+const MIGRATIONS: &[(&str, &str, &str)] = &[
+    ("001", "001_add_missing_columns", include_str!("../../sql/migrations/001_add_missing_columns.sql")),
+    ("005", "005_localized_mail_templates", include_str!("../../sql/migrations/005_localized_mail_templates.sql")),
+    ("006", "006_notification_preferences", include_str!("../../sql/migrations/006_notification_preferences.sql")),
+    ("007", "007_package_ratings", include_str!("../../sql/migrations/007_package_ratings.sql")),
+    ("008", "008_achievements", include_str!("../../sql/migrations/008_achievements.sql")),
+    ("009", "009_points_and_levels", include_str!("../../sql/migrations/009_points_and_levels.sql")),
+    ("010", "010_check_in_makeup", include_str!("../../sql/migrations/010_check_in_makeup.sql")),
+    ("011", "011_weekly_reports", include_str!("../../sql/migrations/011_weekly_reports.sql")),
+    ("012", "012_ranking_rollups", include_str!("../../sql/migrations/012_ranking_rollups.sql")),
+    ("013", "013_following_timeline", include_str!("../../sql/migrations/013_following_timeline.sql")),
+    ("014", "014_package_recommendations", include_str!("../../sql/migrations/014_package_recommendations.sql")),
+    ("015", "015_direct_messages", include_str!("../../sql/migrations/015_direct_messages.sql")),
+    ("016", "016_user_mutes", include_str!("../../sql/migrations/016_user_mutes.sql")),
+    ("017", "017_favorite_collections", include_str!("../../sql/migrations/017_favorite_collections.sql")),
+    ("018", "018_package_owners", include_str!("../../sql/migrations/018_package_owners.sql")),
+    ("019", "019_content_drafts", include_str!("../../sql/migrations/019_content_drafts.sql")),
+    ("020", "020_rendered_content", include_str!("../../sql/migrations/020_rendered_content.sql")),
+    ("021", "021_edit_history", include_str!("../../sql/migrations/021_edit_history.sql")),
+    ("022", "022_package_dependencies", include_str!("../../sql/migrations/022_package_dependencies.sql")),
+    ("023", "023_category_hierarchy", include_str!("../../sql/migrations/023_category_hierarchy.sql")),
+    ("024", "024_tag_governance", include_str!("../../sql/migrations/024_tag_governance.sql")),
+    ("025", "025_package_analytics", include_str!("../../sql/migrations/025_package_analytics.sql")),
+    ("026", "026_site_metrics", include_str!("../../sql/migrations/026_site_metrics.sql")),
+    ("027", "027_recycle_bin", include_str!("../../sql/migrations/027_recycle_bin.sql")),
+    ("028", "028_mail_language", include_str!("../../sql/migrations/028_mail_language.sql")),
+    ("029", "029_draft_published_id", include_str!("../../sql/migrations/029_draft_published_id.sql")),
+];
+
+/// 注释掉目标列已存在的 `ALTER TABLE <表> ADD COLUMN <列> ...;` 语句（每条语句需独占一行）
+fn skip_existing_columns(conn: &Connection, sql: &str) -> String {
+    sql.lines()
+        .map(|line| {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let is_add_column = words.len() >= 6
+                && words[0].eq_ignore_ascii_case("ALTER")
+                && words[1].eq_ignore_ascii_case("TABLE")
+                && words[3].eq_ignore_ascii_case("ADD")
+                && words[4].eq_ignore_ascii_case("COLUMN");
+            if is_add_column && column_exists(conn, words[2], words[5].trim_end_matches(';')) {
+                format!("-- 列已存在，跳过: {}", line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn column_exists(conn: &Connection, table: &str, column: &str) -> bool {
+    conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info(?1) WHERE name = ?2",
+        [table, column],
+        |row| row.get::<_, i64>(0),
+    )
+    .map(|count| count > 0)
+    .unwrap_or(false)
+}
+
 /// 数据库管理器
 pub struct DatabaseManager {
     config: Config,
@@ -70,19 +130,35 @@ impl DatabaseManager {
     }
     
     /// 执行迁移脚本
+    ///
+    /// 已在 system_settings 中记录 `migration_<版本>_completed` 的迁移会被跳过，
+    /// 其余迁移在事务中执行；目标列已存在的 `ALTER TABLE ... ADD COLUMN` 语句会被跳过，
+    /// 使迁移可重复执行。失败时回滚并记录错误，不阻止启动。
     async fn run_migrations(&self, conn: &Connection) -> Result<(), BootstrapError> {
         info!("🔄 检查并执行数据库迁移...");
         
-        match conn.execute_batch(include_str!("../../sql/migrations/001_add_missing_columns.sql")) {
-            Ok(_) => {
-                info!("✅ 数据库迁移执行成功");
+        for (version, name, sql) in MIGRATIONS {
+            let completed: bool = conn
+                .query_row(
+                    "SELECT COUNT(*) FROM system_settings WHERE key = ?1",
+                    [format!("migration_{}_completed", version)],
+                    |row| row.get::<_, i64>(0),
+                )
+                .map(|count| count > 0)
+                .unwrap_or(false);
+            if completed {
+                continue;
             }
-            Err(e) => {
-                if e.to_string().contains("already exists") || e.to_string().contains("duplicate column") {
-                    info!("ℹ️ 数据库结构已是最新版本");
-                } else {
-                    warn!("⚠️ 迁移执行警告: {}", e);
-                    // 迁移失败不阻止启动
+            
+            let sql = skip_existing_columns(conn, sql);
+            match conn.execute_batch(&format!("BEGIN;\n{}\nCOMMIT;", sql)) {
+                Ok(_) => {
+                    info!("✅ 数据库迁移 {} 执行成功", name);
+                }
+                Err(e) => {
+                    let _ = conn.execute_batch("ROLLBACK;");
+                    // 迁移失败不阻止启动，下次启动会重试
+                    error!("❌ 数据库迁移 {} 执行失败: {}", name, e);
                 }
             }
         }
@@ -18,6 +18,25 @@ use crate::services::{
     post_service::PostService,
     tag_service::TagService,
     notification_service::NotificationService,
+    rating_service::RatingService,
+    achievement_service::AchievementService,
+    points_service::PointsService,
+    check_in_service::CheckInService,
+    weekly_report_service::WeeklyReportService,
+    ranking_service::RankingService,
+    recommendation_service::RecommendationService,
+    message_service::MessageService,
+    user_relation_service::UserRelationService,
+    collection_service::CollectionService,
+    package_maintainer_service::PackageMaintainerService,
+    draft_service::DraftService,
+    render_service::RenderService,
+    edit_history_service::EditHistoryService,
+    package_dependency_service::PackageDependencyService,
+    category_service::CategoryService,
+    package_analytics_service::PackageAnalyticsService,
+    site_metrics_service::SiteMetricsService,
+    recycle_bin_service::RecycleBinService,
     download_security_service::DownloadSecurityService,
     security_action_service::SecurityActionService,
     email_service::EmailService,
@@ -34,6 +53,24 @@ use crate::repositories::{
     forbidden_word_repo::ForbiddenWordRepository,
     user_action_repo::UserActionRepository,
     notification_repo::NotificationRepository,
+    rating_repo::RatingRepository,
+    achievement_repo::AchievementRepository,
+    points_repo::PointsRepository,
+    weekly_report_repo::WeeklyReportRepository,
+    ranking_repo::RankingRepository,
+    timeline_repo::TimelineRepository,
+    recommendation_repo::RecommendationRepository,
+    message_repo::MessageRepository,
+    user_relation_repo::UserRelationRepository,
+    collection_repo::CollectionRepository,
+    package_maintainer_repo::PackageMaintainerRepository,
+    draft_repo::DraftRepository,
+    render_cache_repo::RenderCacheRepository,
+    edit_revision_repo::EditRevisionRepository,
+    package_dependency_repo::PackageDependencyRepository,
+    package_analytics_repo::PackageAnalyticsRepository,
+    site_metrics_repo::SiteMetricsRepository,
+    recycle_bin_repo::RecycleBinRepository,
     mail_repo::MailRepository,
     follow_repo::FollowRepository,
     post_repo::PostRepository,
@@ -54,6 +91,25 @@ pub struct ServiceContainer {
     pub post_service: PostService,
     pub tag_service: TagService,
     pub notification_service: NotificationService,
+    pub rating_service: RatingService,
+    pub achievement_service: AchievementService,
+    pub points_service: PointsService,
+    pub check_in_service: CheckInService,
+    pub weekly_report_service: WeeklyReportService,
+    pub ranking_service: RankingService,
+    pub recommendation_service: RecommendationService,
+    pub message_service: MessageService,
+    pub user_relation_service: UserRelationService,
+    pub collection_service: CollectionService,
+    pub package_maintainer_service: PackageMaintainerService,
+    pub draft_service: DraftService,
+    pub render_service: RenderService,
+    pub edit_history_service: EditHistoryService,
+    pub package_dependency_service: PackageDependencyService,
+    pub category_service: CategoryService,
+    pub package_analytics_service: PackageAnalyticsService,
+    pub site_metrics_service: SiteMetricsService,
+    pub recycle_bin_service: RecycleBinService,
     pub download_security_service: DownloadSecurityService,
     pub security_action_service: SecurityActionService,
     pub email_service: Arc<RwLock<EmailService>>,
@@ -66,6 +122,7 @@ pub struct ServiceContainer {
     pub subscription_repo: SubscriptionRepository,
     pub follow_repo: FollowRepository,
     pub post_repo: PostRepository,
+    pub timeline_repo: TimelineRepository,
     
     // JWT工具
     pub jwt_utils: std::sync::Arc<crate::utils::jwt::JwtUtils>,
@@ -94,7 +151,7 @@ impl ServiceContainer {
         let anti_fraud_service = Self::create_anti_fraud_service(&db_url).await?;
         
         // 创建通知服务
-        let notification_service = Self::create_notification_service(&db_url).await?;
+        let notification_service = Self::create_notification_service(config, &db_url, email_service.clone()).await?;
         
         // 创建业务服务
         let services = Self::create_business_services(
@@ -123,6 +180,25 @@ impl ServiceContainer {
             user_action_service: services.user_action_service,
             post_service: services.post_service,
             tag_service: services.tag_service,
+            rating_service: services.rating_service,
+            achievement_service: services.achievement_service,
+            points_service: services.points_service,
+            check_in_service: services.check_in_service,
+            weekly_report_service: services.weekly_report_service,
+            ranking_service: services.ranking_service,
+            recommendation_service: services.recommendation_service,
+            message_service: services.message_service,
+            user_relation_service: services.user_relation_service,
+            collection_service: services.collection_service,
+            package_maintainer_service: services.package_maintainer_service,
+            draft_service: services.draft_service,
+            render_service: services.render_service,
+            edit_history_service: services.edit_history_service,
+            package_dependency_service: services.package_dependency_service,
+            category_service: services.category_service,
+            package_analytics_service: services.package_analytics_service,
+            site_metrics_service: services.site_metrics_service,
+            recycle_bin_service: services.recycle_bin_service,
             notification_service,
             download_security_service,
             security_action_service,
@@ -134,6 +210,7 @@ impl ServiceContainer {
             subscription_repo: repositories.subscription_repo,
             follow_repo: repositories.follow_repo,
             post_repo: repositories.post_repo,
+            timeline_repo: repositories.timeline_repo,
             jwt_utils: std::sync::Arc::new(crate::utils::jwt::JwtUtils::new(jwt_secret)),
         })
     }
@@ -179,6 +256,60 @@ impl ServiceContainer {
         let post_repo = PostRepository::new(db_url)
             .map_err(|e| BootstrapError::Service(format!("创建帖子仓库失败: {}", e)))?;
         
+        let rating_repo = RatingRepository::new(db_url)
+            .map_err(|e| BootstrapError::Service(format!("创建评分仓库失败: {}", e)))?;
+        
+        let achievement_repo = AchievementRepository::new(db_url)
+            .map_err(|e| BootstrapError::Service(format!("创建成就仓库失败: {}", e)))?;
+        
+        let points_repo = PointsRepository::new(db_url)
+            .map_err(|e| BootstrapError::Service(format!("创建积分仓库失败: {}", e)))?;
+        
+        let weekly_report_repo = WeeklyReportRepository::new(db_url)
+            .map_err(|e| BootstrapError::Service(format!("创建周报仓库失败: {}", e)))?;
+        
+        let ranking_repo = RankingRepository::new(db_url)
+            .map_err(|e| BootstrapError::Service(format!("创建排行榜仓库失败: {}", e)))?;
+        
+        let timeline_repo = TimelineRepository::new(db_url)
+            .map_err(|e| BootstrapError::Service(format!("创建时间线仓库失败: {}", e)))?;
+        
+        let recommendation_repo = RecommendationRepository::new(db_url)
+            .map_err(|e| BootstrapError::Service(format!("创建推荐仓库失败: {}", e)))?;
+        
+        let message_repo = MessageRepository::new(db_url)
+            .map_err(|e| BootstrapError::Service(format!("创建私信仓库失败: {}", e)))?;
+        
+        let user_relation_repo = UserRelationRepository::new(db_url)
+            .map_err(|e| BootstrapError::Service(format!("创建拉黑屏蔽仓库失败: {}", e)))?;
+        
+        let collection_repo = CollectionRepository::new(db_url)
+            .map_err(|e| BootstrapError::Service(format!("创建收藏夹仓库失败: {}", e)))?;
+        
+        let package_maintainer_repo = PackageMaintainerRepository::new(db_url)
+            .map_err(|e| BootstrapError::Service(format!("创建资源维护者仓库失败: {}", e)))?;
+        
+        let draft_repo = DraftRepository::new(db_url)
+            .map_err(|e| BootstrapError::Service(format!("创建草稿仓库失败: {}", e)))?;
+        
+        let render_cache_repo = RenderCacheRepository::new(db_url)
+            .map_err(|e| BootstrapError::Service(format!("创建渲染缓存仓库失败: {}", e)))?;
+        
+        let edit_revision_repo = EditRevisionRepository::new(db_url)
+            .map_err(|e| BootstrapError::Service(format!("创建编辑历史仓库失败: {}", e)))?;
+        
+        let package_dependency_repo = PackageDependencyRepository::new(db_url)
+            .map_err(|e| BootstrapError::Service(format!("创建资源依赖仓库失败: {}", e)))?;
+        
+        let package_analytics_repo = PackageAnalyticsRepository::new(db_url)
+            .map_err(|e| BootstrapError::Service(format!("创建资源数据分析仓库失败: {}", e)))?;
+        
+        let site_metrics_repo = SiteMetricsRepository::new(db_url)
+            .map_err(|e| BootstrapError::Service(format!("创建全站数据仓库失败: {}", e)))?;
+        
+        let recycle_bin_repo = RecycleBinRepository::new(db_url)
+            .map_err(|e| BootstrapError::Service(format!("创建回收站仓库失败: {}", e)))?;
+        
         Ok(RepositoryContainer {
             user_repo,
             package_repo,
@@ -191,6 +322,24 @@ impl ServiceContainer {
             notification_repo,
             follow_repo,
             post_repo,
+            rating_repo,
+            achievement_repo,
+            points_repo,
+            weekly_report_repo,
+            ranking_repo,
+            timeline_repo,
+            recommendation_repo,
+            message_repo,
+            user_relation_repo,
+            collection_repo,
+            package_maintainer_repo,
+            draft_repo,
+            render_cache_repo,
+            edit_revision_repo,
+            package_dependency_repo,
+            package_analytics_repo,
+            site_metrics_repo,
+            recycle_bin_repo,
         })
     }
     
@@ -254,13 +403,25 @@ impl ServiceContainer {
     }
     
     /// 创建通知服务
-    async fn create_notification_service(db_url: &str) -> Result<NotificationService, BootstrapError> {
+    async fn create_notification_service(
+        config: &Config,
+        db_url: &str,
+        email_service: Arc<RwLock<EmailService>>,
+    ) -> Result<NotificationService, BootstrapError> {
         info!("🔔 初始化通知服务...");
         
         let notification_repo = NotificationRepository::new(db_url)
             .map_err(|e| BootstrapError::Service(format!("创建通知仓库失败: {}", e)))?;
         
-        Ok(NotificationService::new(notification_repo))
+        let public_base_url = config.public_base_url()
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| format!("http://{}", config.server_address()));
+        let notification_service = NotificationService::new(notification_repo)
+            .with_email_service(email_service)
+            .with_public_base_url(public_base_url);
+        notification_service.hub().start_heartbeat();
+        notification_service.start_digest_job();
+        Ok(notification_service)
     }
     
     /// 创建业务服务
@@ -275,6 +436,44 @@ impl ServiceContainer {
     ) -> Result<BusinessServices, BootstrapError> {
         info!("💼 创建业务服务...");
         
+        let achievement_service = AchievementService::new(
+            repos.achievement_repo.clone(),
+            repos.user_repo.clone()
+        )
+        .with_notification_service(notification_service.clone());
+        
+        let points_service = PointsService::new(
+            repos.points_repo.clone(),
+            repos.user_repo.clone()
+        )
+        .with_notification_service(notification_service.clone());
+        
+        let check_in_service = CheckInService::new(
+            repos.user_repo.clone(),
+            points_service.clone()
+        )
+        .with_achievement_service(achievement_service.clone());
+        
+        let weekly_report_service = WeeklyReportService::new(
+            repos.weekly_report_repo.clone(),
+            repos.user_repo.clone()
+        )
+        .with_notification_service(notification_service.clone())
+        .with_email_service(email_service.clone());
+        weekly_report_service.start_email_job();
+        
+        let ranking_service = RankingService::new(repos.ranking_repo.clone());
+        ranking_service.start_rollup_job();
+        
+        let recommendation_service = RecommendationService::new(
+            repos.recommendation_repo.clone(),
+            repos.package_repo.clone()
+        );
+        recommendation_service.start_recompute_job();
+        
+        let site_metrics_service = SiteMetricsService::new(repos.site_metrics_repo.clone());
+        site_metrics_service.start_rollup_job();
+        
         let auth_service = AuthService::new(
             repos.user_repo.clone(),
             jwt_secret.to_string(),
@@ -292,11 +491,14 @@ impl ServiceContainer {
             repos.package_repo.clone(),
             upload_path.to_string()
         )
+        .with_db_path(db_url)
         .with_system_repo(repos.system_repo.clone())
         .with_notifier(repos.subscription_repo.clone(), email_service.clone())
         .with_user_repo(repos.user_repo.clone())
         .with_download_security_service(download_security_service)
-        .with_notification_service(notification_service.clone());
+        .with_notification_service(notification_service.clone())
+        .with_points_service(points_service.clone())
+        .with_achievement_service(achievement_service.clone());
         
         let comment_service = CommentService::new(
             repos.comment_repo.clone(),
@@ -304,8 +506,11 @@ impl ServiceContainer {
         )
         .with_package_repo(repos.package_repo.clone())
         .with_user_action_repo(repos.user_action_repo.clone())
+        .with_relation_repo(repos.user_relation_repo.clone())
         .with_notification_service(notification_service.clone())
-        .with_forbidden_service(forbidden_word_service.clone());
+        .with_forbidden_service(forbidden_word_service.clone())
+        .with_points_service(points_service.clone())
+        .with_achievement_service(achievement_service.clone());
         
         let community_service = CommunityService::new(
             repos.comment_repo.clone()
@@ -316,12 +521,98 @@ impl ServiceContainer {
         );
         
         let post_service = PostService::new(db_url.to_string())
-            .with_notifier(notification_service.clone());
+            .with_notifier(notification_service.clone())
+            .with_points_service(points_service.clone())
+            .with_achievement_service(achievement_service.clone());
         
         let tag_service = TagService::new(db_url.to_string());
         
         let admin_service = AdminService::new(db_url);
         
+        let rating_service = RatingService::new(
+            repos.rating_repo.clone(),
+            repos.package_repo.clone()
+        )
+        .with_forbidden_service(forbidden_word_service.clone())
+        .with_notification_service(notification_service.clone());
+        
+        let message_service = MessageService::new(
+            repos.message_repo.clone(),
+            repos.user_repo.clone(),
+            repos.follow_repo.clone(),
+            repos.user_relation_repo.clone()
+        )
+        .with_forbidden_service(forbidden_word_service.clone())
+        .with_notification_service(notification_service.clone());
+        
+        let user_relation_service = UserRelationService::new(
+            repos.user_relation_repo.clone(),
+            repos.user_repo.clone()
+        );
+        
+        let collection_service = CollectionService::new(
+            repos.collection_repo.clone(),
+            repos.user_repo.clone()
+        )
+        .with_forbidden_service(forbidden_word_service.clone())
+        .with_notification_service(notification_service.clone());
+        
+        let package_maintainer_service = PackageMaintainerService::new(
+            repos.package_maintainer_repo.clone(),
+            repos.package_repo.clone(),
+            repos.user_repo.clone()
+        )
+        .with_notification_service(notification_service.clone());
+        
+        let category_service = CategoryService::new(
+            repos.system_repo.clone(),
+            package_service.clone()
+        );
+        
+        let draft_service = DraftService::new(
+            repos.draft_repo.clone(),
+            repos.user_repo.clone(),
+            repos.system_repo.clone(),
+            post_service.clone(),
+            package_service.clone()
+        )
+        .with_notification_service(notification_service.clone())
+        .with_category_service(category_service.clone())
+        .with_tag_service(tag_service.clone());
+        draft_service.start_publish_job();
+        
+        let render_service = RenderService::new(repos.render_cache_repo.clone());
+        
+        let edit_history_service = EditHistoryService::new(
+            repos.edit_revision_repo.clone(),
+            post_service.clone(),
+            package_service.clone(),
+            comment_service.clone()
+        )
+        .with_maintainer_service(package_maintainer_service.clone());
+        
+        let package_dependency_service = PackageDependencyService::new(
+            repos.package_dependency_repo.clone(),
+            repos.package_repo.clone(),
+            package_maintainer_service.clone()
+        );
+        
+        let package_analytics_service = PackageAnalyticsService::new(
+            repos.package_analytics_repo.clone(),
+            repos.package_repo.clone(),
+            package_maintainer_service.clone()
+        );
+        
+        let recycle_bin_service = RecycleBinService::new(
+            repos.recycle_bin_repo.clone(),
+            package_service.clone(),
+            post_service.clone(),
+            repos.comment_repo.clone(),
+            repos.user_repo.clone(),
+            points_service.clone()
+        );
+        recycle_bin_service.start_purge_job();
+        
         Ok(BusinessServices {
             auth_service,
             user_service,
@@ -333,6 +624,25 @@ impl ServiceContainer {
             user_action_service,
             post_service,
             tag_service,
+            rating_service,
+            achievement_service,
+            points_service,
+            check_in_service,
+            weekly_report_service,
+            ranking_service,
+            recommendation_service,
+            message_service,
+            user_relation_service,
+            collection_service,
+            package_maintainer_service,
+            draft_service,
+            render_service,
+            edit_history_service,
+            package_dependency_service,
+            category_service,
+            package_analytics_service,
+            site_metrics_service,
+            recycle_bin_service,
         })
     }
     
@@ -390,6 +700,24 @@ struct RepositoryContainer {
     notification_repo: NotificationRepository,
     follow_repo: FollowRepository,
     post_repo: PostRepository,
+    rating_repo: RatingRepository,
+    achievement_repo: AchievementRepository,
+    points_repo: PointsRepository,
+    weekly_report_repo: WeeklyReportRepository,
+    ranking_repo: RankingRepository,
+    timeline_repo: TimelineRepository,
+    recommendation_repo: RecommendationRepository,
+    message_repo: MessageRepository,
+    user_relation_repo: UserRelationRepository,
+    collection_repo: CollectionRepository,
+    package_maintainer_repo: PackageMaintainerRepository,
+    draft_repo: DraftRepository,
+    render_cache_repo: RenderCacheRepository,
+    edit_revision_repo: EditRevisionRepository,
+    package_dependency_repo: PackageDependencyRepository,
+    package_analytics_repo: PackageAnalyticsRepository,
+    site_metrics_repo: SiteMetricsRepository,
+    recycle_bin_repo: RecycleBinRepository,
 }
 
 /// 业务服务容器
@@ -404,4 +732,23 @@ struct BusinessServices {
     user_action_service: UserActionService,
     post_service: PostService,
     tag_service: TagService,
+    rating_service: RatingService,
+    achievement_service: AchievementService,
+    points_service: PointsService,
+    check_in_service: CheckInService,
+    weekly_report_service: WeeklyReportService,
+    ranking_service: RankingService,
+    recommendation_service: RecommendationService,
+    message_service: MessageService,
+    user_relation_service: UserRelationService,
+    collection_service: CollectionService,
+    package_maintainer_service: PackageMaintainerService,
+    draft_service: DraftService,
+    render_service: RenderService,
+    edit_history_service: EditHistoryService,
+    package_dependency_service: PackageDependencyService,
+    category_service: CategoryService,
+    package_analytics_service: PackageAnalyticsService,
+    site_metrics_service: SiteMetricsService,
+    recycle_bin_service: RecycleBinService,
 }
\ No newline at end of file
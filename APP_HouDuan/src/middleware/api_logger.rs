@@ -49,7 +49,7 @@ where
         let query = req.query_string().to_string();
         
         // 获取用户信息（如果已认证）
-        let user_info = if let Ok(user) = AuthHelper::verify_user(req.request()) {
+        let user_info = if let Ok(user) = AuthHelper::token_user(req.request()) {
             Some(format!("user:{}", user.id))
         } else {
             None
@@ -1,6 +1,9 @@
 use actix_web::{dev::Payload, Error, FromRequest, HttpRequest};
-use futures::future::{ready, Ready};
+use futures::future::{ready, LocalBoxFuture};
+use futures::FutureExt;
+use actix_web::error::InternalError;
 use crate::models::user::UserRole;
+use crate::utils::auth_helper::AuthHelper;
 
 pub struct AuthMiddleware;
 
@@ -30,7 +33,7 @@ impl AuthenticatedUser {
 // 为AuthenticatedUser实现FromRequest特性，使其可以在请求处理器中被提取
 impl FromRequest for AuthenticatedUser {
     type Error = Error;
-    type Future = Ready<Result<Self, Self::Error>>;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
     
     // 从请求中提取已认证的用户信息
     // 这里为了简化，我们暂时返回一个mock用户
@@ -75,11 +78,20 @@ impl FromRequest for AuthenticatedUser {
                                     "elder" => UserRole::Elder,
                                     _ => UserRole::User,
                                 };
-                                return ready(Ok(AuthenticatedUser {
+                                let user = AuthenticatedUser {
                                     id: claims.user_id,
                                     username: claims.username,
                                     role,
-                                }));
+                                };
+                                // 与 AuthHelper::verify_user 相同的账号检查：已删除、封禁的账号拒绝访问
+                                let req = req.clone();
+                                return async move {
+                                    match AuthHelper::check_account(&req, user.id).await {
+                                        Ok(()) => Ok(user),
+                                        Err(e) => Err(InternalError::from_response("账号不可用", e.to_response()).into()),
+                                    }
+                                }
+                                .boxed_local();
                             }
                             Err(e) => {
                                 log::warn!("JWT验证失败: {}", e);
@@ -91,7 +103,7 @@ impl FromRequest for AuthenticatedUser {
         }
 
         // 未携带或验证失败时，返回未认证错误
-        ready(Err(actix_web::error::ErrorUnauthorized("未认证用户")))
+        ready(Err(actix_web::error::ErrorUnauthorized("未认证用户"))).boxed_local()
     }
 }
 
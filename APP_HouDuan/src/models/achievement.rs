@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// 成就判定条件类型，对应 achievements.criteria_type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AchievementCriteria {
+    /// 已上架（审核通过）的资源数
+    PackagesUploaded,
+    /// 资源与帖子累计获得的点赞数
+    LikesReceived,
+    /// 当前连续签到天数
+    CheckInStreak,
+    /// 被加精的帖子数
+    FeaturedPosts,
+    /// 已发布的帖子数
+    PostsPublished,
+    /// 发表的评论数
+    CommentsPosted,
+}
+
+impl AchievementCriteria {
+    pub fn all() -> &'static [AchievementCriteria] {
+        &[
+            AchievementCriteria::PackagesUploaded,
+            AchievementCriteria::LikesReceived,
+            AchievementCriteria::CheckInStreak,
+            AchievementCriteria::FeaturedPosts,
+            AchievementCriteria::PostsPublished,
+            AchievementCriteria::CommentsPosted,
+        ]
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AchievementCriteria::PackagesUploaded => "packages_uploaded",
+            AchievementCriteria::LikesReceived => "likes_received",
+            AchievementCriteria::CheckInStreak => "check_in_streak",
+            AchievementCriteria::FeaturedPosts => "featured_posts",
+            AchievementCriteria::PostsPublished => "posts_published",
+            AchievementCriteria::CommentsPosted => "comments_posted",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<AchievementCriteria> {
+        Self::all().iter().copied().find(|c| c.as_str() == s)
+    }
+}
+
+/// 成就定义
+#[derive(Debug, Clone, Serialize)]
+pub struct AchievementDefinition {
+    pub id: i32,
+    pub code: String,
+    pub name: String,
+    pub icon: String,
+    pub description: String,
+    pub criteria_type: AchievementCriteria,
+    pub threshold: i64,
+    pub enabled: bool,
+    pub sort_order: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 用户视角的成就：已获得的带 earned_at，未获得的带进度
+#[derive(Debug, Clone, Serialize)]
+pub struct UserAchievement {
+    pub id: i32,
+    pub code: String,
+    pub name: String,
+    pub icon: String,
+    pub description: String,
+    pub criteria_type: AchievementCriteria,
+    pub threshold: i64,
+    /// 当前进度，不超过 threshold
+    pub progress: i64,
+    pub earned: bool,
+    pub earned_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateAchievementRequest {
+    pub code: String,
+    pub name: String,
+    pub icon: Option<String>,
+    pub description: Option<String>,
+    pub criteria_type: String,
+    pub threshold: i64,
+    pub enabled: Option<bool>,
+    pub sort_order: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateAchievementRequest {
+    pub name: Option<String>,
+    pub icon: Option<String>,
+    pub description: Option<String>,
+    pub threshold: Option<i64>,
+    pub enabled: Option<bool>,
+    pub sort_order: Option<i32>,
+}
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::system::Category;
+
+/// 分类最大层级（顶级为第 1 层）
+pub const MAX_CATEGORY_DEPTH: usize = 4;
+
+/// 发布权限：所有用户 / 元老及以上 / 仅管理员
+pub const POST_PERMISSIONS: &[&str] = &["all", "elder", "admin"];
+
+/// 可恢复为继承的规则名
+pub const CATEGORY_RULE_FIELDS: &[&str] = &["requires_review", "allowed_extensions", "max_file_size", "post_permission"];
+
+/// 面包屑中的一级分类
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryCrumb {
+    pub id: i32,
+    pub name: String,
+    pub icon: Option<String>,
+}
+
+/// 分类树节点，同级按 sort_order 排序
+#[derive(Debug, Clone, Serialize)]
+pub struct CategoryNode {
+    #[serde(flatten)]
+    pub category: Category,
+    /// 直接属于本分类的已上架资源数（不含子分类）
+    pub count: i32,
+    pub children: Vec<CategoryNode>,
+}
+
+/// 沿父分类继承后实际生效的规则；扩展名与文件大小整条链都未设置时为空，表示不限制
+#[derive(Debug, Clone, Serialize)]
+pub struct CategoryRules {
+    pub category_id: Option<i32>,
+    pub requires_review: bool,
+    pub allowed_extensions: Option<Vec<String>>,
+    pub max_file_size: Option<i64>,
+    pub post_permission: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MovePackageRequest {
+    pub category_id: i32,
+}
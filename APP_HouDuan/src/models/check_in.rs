@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+use chrono::NaiveDate;
+
+use crate::models::points::PointLedgerEntry;
+
+/// 可补签的最近天数（不含今天）
+pub const MAKEUP_WINDOW_DAYS: i64 = 7;
+/// 每个自然月最多补签次数
+pub const MAKEUP_MONTHLY_LIMIT: i64 = 3;
+
+/// 一条签到记录
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckInRecord {
+    pub date: NaiveDate,
+    pub is_makeup: bool,
+}
+
+/// 连续签到统计
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct CheckInStreak {
+    /// 截至今天（今天未签则截至昨天）的连续天数
+    pub current_streak: i32,
+    pub longest_streak: i32,
+    pub total_days: i32,
+    pub checked_in_today: bool,
+}
+
+impl CheckInStreak {
+    /// 根据按日期升序排列的签到日期计算连续天数
+    pub fn compute(dates: &[NaiveDate], today: NaiveDate) -> CheckInStreak {
+        let mut longest = 0;
+        let mut run = 0;
+        let mut prev: Option<NaiveDate> = None;
+        for &date in dates {
+            run = match prev {
+                Some(p) if date.signed_duration_since(p).num_days() == 1 => run + 1,
+                Some(p) if p == date => run,
+                _ => 1,
+            };
+            longest = longest.max(run);
+            prev = Some(date);
+        }
+        let current = match prev {
+            Some(last) if (today - last).num_days() <= 1 && last <= today => run,
+            _ => 0,
+        };
+        CheckInStreak {
+            current_streak: current,
+            longest_streak: longest,
+            total_days: dates.len() as i32,
+            checked_in_today: prev == Some(today),
+        }
+    }
+}
+
+/// 签到/补签结果
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckInResult {
+    pub date: NaiveDate,
+    /// 今天已签过时为 true，此时不会重复发放奖励
+    pub already_checked_in: bool,
+    #[serde(flatten)]
+    pub streak: CheckInStreak,
+    /// 本次签到获得的经验/星星；补签时为扣除的星星
+    pub reward: Option<PointLedgerEntry>,
+}
+
+/// 补签配置与本月剩余次数
+#[derive(Debug, Clone, Serialize)]
+pub struct MakeupInfo {
+    pub enabled: bool,
+    /// 每次补签消耗的星星
+    pub cost: i64,
+    pub window_days: i64,
+    pub monthly_limit: i64,
+    pub remaining: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CalendarDay {
+    pub date: NaiveDate,
+    pub checked_in: bool,
+    pub is_makeup: bool,
+    /// 是否可以补签
+    pub can_makeup: bool,
+}
+
+/// 月度签到日历
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckInCalendar {
+    /// YYYY-MM
+    pub month: String,
+    pub days: Vec<CalendarDay>,
+    pub checked_days: i32,
+    #[serde(flatten)]
+    pub streak: CheckInStreak,
+    pub makeup: MakeupInfo,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CalendarQuery {
+    /// YYYY-MM，默认为本月
+    pub month: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MakeupRequest {
+    /// YYYY-MM-DD
+    pub date: String,
+}
@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// 可收藏的内容类型
+pub const FAVORITE_TARGET_TYPES: [&str; 2] = ["Package", "Post"];
+
+/// 每个用户最多创建的收藏夹数量
+pub const MAX_COLLECTIONS_PER_USER: i64 = 50;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CollectionOwner {
+    pub id: i32,
+    pub username: String,
+    pub nickname: Option<String>,
+    pub avatar: Option<String>,
+}
+
+/// 收藏夹
+#[derive(Debug, Clone, Serialize)]
+pub struct FavoriteCollection {
+    pub id: i32,
+    pub user_id: i32,
+    pub name: String,
+    pub description: Option<String>,
+    pub is_public: bool,
+    pub sort_order: i32,
+    pub item_count: i64,
+    pub follower_count: i64,
+    /// 当前用户是否已关注（未登录或查看自己的收藏夹时为 false）
+    pub is_following: bool,
+    pub owner: Option<CollectionOwner>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 收藏条目（资源或帖子）
+#[derive(Debug, Clone, Serialize)]
+pub struct FavoriteItem {
+    pub target_type: String, // Package / Post
+    pub target_id: i32,
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub cover_image: Option<String>,
+    /// 内容已删除或下架时为 false，条目仍保留以便用户自行清理
+    pub available: bool,
+    /// 所属收藏夹，None 表示默认收藏
+    pub collection_id: Option<i32>,
+    pub favorited_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateCollectionRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub is_public: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateCollectionRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub is_public: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FavoriteTarget {
+    pub target_type: String,
+    pub target_id: i32,
+}
+
+/// 将收藏条目移入收藏夹；collection_id 为空表示移回默认收藏
+#[derive(Debug, Deserialize)]
+pub struct MoveFavoritesRequest {
+    pub items: Vec<FavoriteTarget>,
+    pub collection_id: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReorderCollectionsRequest {
+    pub ids: Vec<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReorderItemsRequest {
+    pub items: Vec<FavoriteTarget>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CollectionItemsQuery {
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MyFavoritesQuery {
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+    /// Package / Post
+    #[serde(rename = "type")]
+    pub target_type: Option<String>,
+    /// 仅返回未归入任何收藏夹的条目
+    pub uncategorized: Option<bool>,
+}
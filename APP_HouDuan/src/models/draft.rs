@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// 草稿可发布的内容类型，payload 分别对应 PublishPostRequest / PublishResourceRequest
+pub const DRAFT_TARGET_TYPES: [&str; 2] = ["Post", "Package"];
+
+pub const DRAFT_STATUS_DRAFT: &str = "draft";
+pub const DRAFT_STATUS_SCHEDULED: &str = "scheduled";
+/// 发布中：后台任务或手动发布已认领，避免重复发布
+pub const DRAFT_STATUS_PUBLISHING: &str = "publishing";
+pub const DRAFT_STATUS_FAILED: &str = "failed";
+
+/// 每个用户最多保留的草稿数
+pub const MAX_DRAFTS_PER_USER: i64 = 50;
+/// 每个草稿最多保留的历史版本数
+pub const MAX_REVISIONS_PER_DRAFT: i64 = 20;
+/// 自动保存时距上一个历史版本超过该间隔才记录新版本（秒）
+pub const REVISION_INTERVAL_SECS: i64 = 5 * 60;
+/// 草稿内容（JSON）最大字节数
+pub const MAX_PAYLOAD_BYTES: usize = 200 * 1024;
+/// 定时发布最远可设置的天数
+pub const MAX_SCHEDULE_DAYS: i64 = 90;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Draft {
+    pub id: i32,
+    pub user_id: i32,
+    pub target_type: String,
+    pub title: String,
+    pub payload: serde_json::Value,
+    pub status: String,
+    pub publish_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DraftRevision {
+    pub id: i32,
+    pub draft_id: i32,
+    pub title: String,
+    pub payload: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 发布结果：帖子进入审核，资源按所在分类的规则进入审核或直接上架
+#[derive(Debug, Clone, Serialize)]
+pub struct PublishedContent {
+    pub target_type: String,
+    pub target_id: i32,
+    pub title: String,
+    /// 是否需要等待审核后才公开
+    pub pending_review: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateDraftRequest {
+    pub target_type: String,
+    #[serde(default)]
+    pub payload: serde_json::Value,
+    pub publish_at: Option<DateTime<Utc>>,
+}
+
+/// 自动保存：整体替换草稿内容
+#[derive(Debug, Deserialize)]
+pub struct SaveDraftRequest {
+    pub payload: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScheduleDraftRequest {
+    pub publish_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DraftListQuery {
+    #[serde(rename = "type")]
+    pub target_type: Option<String>,
+}
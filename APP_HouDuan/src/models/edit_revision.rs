@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use chrono::{DateTime, Utc};
+
+use crate::models::{Package, Post};
+
+/// 内容的一个版本：snapshot 为受追踪字段在该版本的完整取值
+#[derive(Debug, Clone, Serialize)]
+pub struct ContentRevision {
+    pub id: i32,
+    pub target_type: String,
+    pub target_id: i32,
+    /// 同一内容内从 1 递增；1 为首次编辑前的原始版本
+    pub revision_no: i32,
+    /// 为空表示原始版本
+    pub editor_id: Option<i32>,
+    pub editor_name: Option<String>,
+    pub snapshot: serde_json::Value,
+    pub changed_fields: Vec<String>,
+    /// 回滚等操作说明
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 与内容更新在同一事务中写入的编辑记录；before 为编辑前受追踪字段的快照
+#[derive(Debug, Clone)]
+pub struct PendingRevision {
+    pub editor_id: i32,
+    pub before: serde_json::Value,
+    pub note: Option<String>,
+}
+
+/// 帖子受追踪字段
+pub fn post_snapshot(post: &Post) -> serde_json::Value {
+    json!({
+        "title": post.title,
+        "content": post.content,
+        "code_snippet": post.code_snippet,
+        "tags": post.tags.clone().unwrap_or_default(),
+    })
+}
+
+/// 资源受追踪字段
+pub fn package_snapshot(package: &Package) -> serde_json::Value {
+    json!({
+        "name": package.name,
+        "version": package.version,
+        "description": package.description,
+        "category_id": package.category_id,
+        "tags": package.tags.clone().unwrap_or_default(),
+        "requirements": package.requirements.clone().unwrap_or_default(),
+    })
+}
+
+/// 评论受追踪字段
+pub fn comment_snapshot(content: &str) -> serde_json::Value {
+    json!({ "content": content })
+}
+
+/// 列表与详情中的“已编辑”标记
+#[derive(Debug, Clone, Serialize)]
+pub struct EditMarker {
+    pub edit_count: i64,
+    pub last_edited_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiffLine {
+    /// equal / insert / delete
+    pub op: String,
+    pub text: String,
+}
+
+/// 单个字段的差异；文本字段附带按行比较结果
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldDiff {
+    pub field: String,
+    pub before: serde_json::Value,
+    pub after: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lines: Option<Vec<DiffLine>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RevisionDiff {
+    pub from: i32,
+    pub to: i32,
+    pub fields: Vec<FieldDiff>,
+}
+
+/// from / to 为版本号；缺省时比较最新版本与其上一版本
+#[derive(Debug, Deserialize)]
+pub struct RevisionDiffQuery {
+    pub from: Option<i32>,
+    pub to: Option<i32>,
+}
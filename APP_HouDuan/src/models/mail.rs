@@ -53,9 +53,100 @@ pub enum MailType {
     ResetPassword,
     Notification,
     AdminNotification, // 管理员通知（新资源待审核等）
+    CategoryNotification, // 分类订阅通知
+    Digest, // 通知摘要（每日/每周）
+    WeeklyReport, // 个人周报（每周一）
     Test,
 }
 
+/// 模板默认语言，其他语言缺少模板时回退到该语言
+pub const DEFAULT_MAIL_LANGUAGE: &str = "zh-CN";
+
+impl MailType {
+    pub fn all() -> Vec<MailType> {
+        vec![
+            MailType::Verification,
+            MailType::ResetPassword,
+            MailType::Notification,
+            MailType::AdminNotification,
+            MailType::CategoryNotification,
+            MailType::Digest,
+            MailType::WeeklyReport,
+            MailType::Test,
+        ]
+    }
+
+    /// 按模板类型解析，未知类型返回 None
+    pub fn from_template_type(s: &str) -> Option<MailType> {
+        Self::all().into_iter().find(|t| t.to_string() == s)
+    }
+
+    /// 保存模板时必须引用的变量
+    pub fn required_variables(&self) -> &'static [&'static str] {
+        match self {
+            MailType::Verification => &["code"],
+            MailType::ResetPassword => &["reset_link"],
+            MailType::Notification => &["resource_name", "resource_link"],
+            MailType::AdminNotification => &["resource_name", "author", "review_link"],
+            MailType::CategoryNotification => &["title", "content"],
+            MailType::Digest => &["items", "unsubscribe_link"],
+            MailType::WeeklyReport => &["week", "unsubscribe_link"],
+            MailType::Test => &[],
+        }
+    }
+
+    /// 预览模板时使用的示例变量
+    pub fn sample_variables(&self) -> serde_json::Value {
+        match self {
+            MailType::Verification => serde_json::json!({"code": "123456"}),
+            MailType::ResetPassword => serde_json::json!({"reset_link": "https://example.com/reset?token=abc"}),
+            MailType::Notification => serde_json::json!({
+                "resource_name": "示例绳包",
+                "resource_description": "这是一个示例资源描述",
+                "resource_link": "https://example.com/resource/1"
+            }),
+            MailType::AdminNotification => serde_json::json!({
+                "resource_name": "示例绳包",
+                "author": "示例作者",
+                "review_link": "https://example.com/admin/review/1"
+            }),
+            MailType::CategoryNotification => serde_json::json!({
+                "title": "分类更新",
+                "content": "订阅的分类有新内容发布",
+                "link": "https://example.com"
+            }),
+            MailType::Digest => serde_json::json!({
+                "username": "示例用户",
+                "period": "今日",
+                "count": 2,
+                "items": [
+                    {"title": "收到新评论", "content": "有人评论了你的绳包", "link": "https://example.com/resource/1", "time": "2025-01-01 08:00"},
+                    {"title": "新增关注", "content": "示例用户 关注了你", "link": "", "time": "2025-01-01 09:30"}
+                ],
+                "unsubscribe_link": "https://example.com/api/v1/notifications/unsubscribe?token=abc"
+            }),
+            MailType::WeeklyReport => serde_json::json!({
+                "username": "示例用户",
+                "week": "2025-W01",
+                "week_range": "01-01 ~ 01-07",
+                "posts_published": 3,
+                "resources_published": 1,
+                "downloads_received": 42,
+                "likes_received": 18,
+                "new_followers": 2,
+                "comments_posted": 7,
+                "check_in_days": 5,
+                "xp_gained": 120,
+                "rank": 12,
+                "rank_change": 3,
+                "link": "https://example.com/profile",
+                "unsubscribe_link": "https://example.com/api/v1/notifications/unsubscribe?token=abc"
+            }),
+            MailType::Test => serde_json::json!({"send_time": "2025-01-01 00:00:00 UTC"}),
+        }
+    }
+}
+
 impl ToString for MailType {
     fn to_string(&self) -> String {
         match self {
@@ -63,6 +154,9 @@ impl ToString for MailType {
             MailType::ResetPassword => "reset_password".to_string(),
             MailType::Notification => "notification".to_string(),
             MailType::AdminNotification => "admin_notification".to_string(),
+            MailType::CategoryNotification => "category_notification".to_string(),
+            MailType::Digest => "digest".to_string(),
+            MailType::WeeklyReport => "weekly_report".to_string(),
             MailType::Test => "test".to_string(),
         }
     }
@@ -75,6 +169,9 @@ impl From<&str> for MailType {
             "reset_password" => MailType::ResetPassword,
             "notification" => MailType::Notification,
             "admin_notification" => MailType::AdminNotification,
+            "category_notification" => MailType::CategoryNotification,
+            "digest" => MailType::Digest,
+            "weekly_report" => MailType::WeeklyReport,
             "test" => MailType::Test,
             _ => MailType::Test,
         }
@@ -113,6 +210,7 @@ impl From<&str> for MailStatus {
 pub struct MailTemplate {
     pub id: Option<i32>,
     pub template_type: String,
+    pub language: String,
     pub subject: String,
     pub content: String,
     pub variables: Option<String>,
@@ -121,6 +219,45 @@ pub struct MailTemplate {
     pub updated_at: Option<DateTime<Utc>>,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateMailTemplateRequest {
+    pub template_type: String,
+    pub language: Option<String>,
+    pub subject: String,
+    pub content: String,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateMailTemplateRequest {
+    pub subject: Option<String>,
+    pub content: Option<String>,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MailTemplateQuery {
+    pub template_type: Option<String>,
+    pub language: Option<String>,
+}
+
+/// 预览请求：未提供 subject/content 时使用已保存的模板，未提供 variables 时使用示例变量
+#[derive(Debug, Clone, Deserialize)]
+pub struct PreviewMailTemplateRequest {
+    pub template_type: String,
+    pub language: Option<String>,
+    pub subject: Option<String>,
+    pub content: Option<String>,
+    pub variables: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RenderedMail {
+    pub subject: String,
+    pub html: String,
+    pub text: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SendMailRequest {
     pub to_email: String,
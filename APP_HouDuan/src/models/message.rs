@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// 私信接收设置
+pub const ALLOW_FROM_EVERYONE: &str = "everyone";
+pub const ALLOW_FROM_FOLLOWING: &str = "following";
+
+/// 私信举报处理状态
+pub const REPORT_PENDING: &str = "pending";
+pub const REPORT_RESOLVED: &str = "resolved";
+pub const REPORT_DISMISSED: &str = "dismissed";
+
+/// 消息类型
+pub const MESSAGE_TYPES: [&str; 3] = ["text", "image", "file"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MessageUser {
+    pub id: i32,
+    pub username: String,
+    pub nickname: Option<String>,
+    pub avatar: Option<String>,
+}
+
+/// 私信
+#[derive(Debug, Clone, Serialize)]
+pub struct Message {
+    pub id: i32,
+    pub conversation_id: i32,
+    pub sender_id: i32,
+    pub receiver_id: i32,
+    pub content: String,
+    pub message_type: String, // text / image / file
+    pub is_read: bool,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub sender: Option<MessageUser>,
+    pub receiver: Option<MessageUser>,
+}
+
+/// 会话列表项（以当前用户视角）
+#[derive(Debug, Clone, Serialize)]
+pub struct Conversation {
+    pub id: i32,
+    pub participant_id: i32,
+    pub participant_username: String,
+    pub participant_nickname: Option<String>,
+    pub participant_avatar: Option<String>,
+    pub last_message: Option<String>,
+    pub last_message_type: Option<String>,
+    pub last_message_time: Option<DateTime<Utc>>,
+    pub unread_count: i32,
+    pub is_pinned: bool,
+    pub is_online: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MessageSettings {
+    pub allow_from: String, // everyone / following
+}
+
+/// 私信举报
+#[derive(Debug, Clone, Serialize)]
+pub struct MessageReport {
+    pub id: i32,
+    pub message_id: i32,
+    pub reporter_id: i32,
+    pub reporter_username: Option<String>,
+    pub reason: Option<String>,
+    pub status: String, // pending / resolved / dismissed
+    pub handled_by: Option<i32>,
+    pub handle_note: Option<String>,
+    pub handled_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    /// 被举报的消息（管理员视角，不受双方删除影响）
+    pub message: Option<Message>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SendMessageRequest {
+    pub receiver_id: i32,
+    pub content: String,
+    pub message_type: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MessageQuery {
+    pub user_id: i32,
+    pub page: Option<i32>,
+    pub page_size: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MessageSearchQuery {
+    pub q: String,
+    pub user_id: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MarkReadRequest {
+    pub message_ids: Vec<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConversationUserRequest {
+    pub user_id: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PinConversationRequest {
+    pub user_id: i32,
+    pub pinned: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateMessageSettingsRequest {
+    pub allow_from: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReportMessageRequest {
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AdminMessageReportQuery {
+    pub page: Option<i32>,
+    pub page_size: Option<i32>,
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HandleMessageReportRequest {
+    pub status: String, // resolved / dismissed
+    pub note: Option<String>,
+}
@@ -9,6 +9,26 @@ pub mod post;
 pub mod tag;
 pub mod user_action;
 pub mod mail;
+pub mod rating;
+pub mod achievement;
+pub mod points;
+pub mod check_in;
+pub mod weekly_report;
+pub mod ranking;
+pub mod timeline;
+pub mod recommendation;
+pub mod message;
+pub mod user_relation;
+pub mod collection;
+pub mod package_maintainer;
+pub mod draft;
+pub mod render;
+pub mod edit_revision;
+pub mod package_dependency;
+pub mod category;
+pub mod package_analytics;
+pub mod site_metrics;
+pub mod recycle_bin;
 
 use serde::{Serialize, Deserialize};
 
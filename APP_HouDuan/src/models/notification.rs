@@ -19,4 +19,249 @@ pub struct Notification {
 pub struct NotificationQuery {
     pub page: Option<i32>,
     pub size: Option<i32>,
-} 
\ No newline at end of file
+} 
+/// 实时推送事件（SSE）
+#[derive(Debug, Clone)]
+pub enum NotificationEvent {
+    /// 新通知，事件 id 为通知 id，用于断线续传
+    Notification(Notification),
+    /// 管理员广播的站内通知
+    Broadcast(Notification),
+    /// 新发布的公告（不落库到通知表，无事件 id）
+    Announcement(serde_json::Value),
+    /// 未读数变化
+    UnreadCount(i32),
+    /// 收到新私信（不落库到通知表，无事件 id）
+    DirectMessage(serde_json::Value),
+    /// 对方已读了自己发出的私信
+    MessageRead(serde_json::Value),
+}
+
+impl NotificationEvent {
+    pub fn event_name(&self) -> &'static str {
+        match self {
+            NotificationEvent::Notification(_) => "notification",
+            NotificationEvent::Broadcast(_) | NotificationEvent::Announcement(_) => "announcement",
+            NotificationEvent::UnreadCount(_) => "unread_count",
+            NotificationEvent::DirectMessage(_) => "message",
+            NotificationEvent::MessageRead(_) => "message_read",
+        }
+    }
+
+    /// 序列化为 SSE 报文
+    pub fn to_sse(&self) -> String {
+        let (id, data) = match self {
+            NotificationEvent::Notification(n) | NotificationEvent::Broadcast(n) => {
+                (Some(n.id), serde_json::to_string(n).unwrap_or_default())
+            }
+            NotificationEvent::Announcement(v)
+            | NotificationEvent::DirectMessage(v)
+            | NotificationEvent::MessageRead(v) => (None, v.to_string()),
+            NotificationEvent::UnreadCount(count) => (None, serde_json::json!({ "count": count }).to_string()),
+        };
+        let mut out = String::new();
+        if let Some(id) = id {
+            out.push_str(&format!("id: {}\n", id));
+        }
+        out.push_str(&format!("event: {}\ndata: {}\n\n", self.event_name(), data));
+        out
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NotificationStreamQuery {
+    /// EventSource 无法设置请求头时，通过 POST /notifications/stream-ticket 换取的一次性票据
+    pub ticket: Option<String>,
+    /// 断线续传：也可通过 Last-Event-ID 请求头传递
+    pub last_event_id: Option<i32>,
+}
+
+/// 可由用户配置投递方式的通知类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationCategory {
+    Comment,
+    Reply,
+    Mention,
+    Like,
+    Follow,
+    ReviewResult,
+    SubscribedResource,
+}
+
+impl NotificationCategory {
+    pub fn all() -> Vec<NotificationCategory> {
+        vec![
+            NotificationCategory::Comment,
+            NotificationCategory::Reply,
+            NotificationCategory::Mention,
+            NotificationCategory::Like,
+            NotificationCategory::Follow,
+            NotificationCategory::ReviewResult,
+            NotificationCategory::SubscribedResource,
+        ]
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationCategory::Comment => "comment",
+            NotificationCategory::Reply => "reply",
+            NotificationCategory::Mention => "mention",
+            NotificationCategory::Like => "like",
+            NotificationCategory::Follow => "follow",
+            NotificationCategory::ReviewResult => "review_result",
+            NotificationCategory::SubscribedResource => "subscribed_resource",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            NotificationCategory::Comment => "收到评论",
+            NotificationCategory::Reply => "评论回复",
+            NotificationCategory::Mention => "被 @ 提及",
+            NotificationCategory::Like => "收到点赞",
+            NotificationCategory::Follow => "关注与关注动态",
+            NotificationCategory::ReviewResult => "审核结果",
+            NotificationCategory::SubscribedResource => "订阅分类新资源",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<NotificationCategory> {
+        Self::all().into_iter().find(|c| c.as_str() == s)
+    }
+
+    /// 由通知的 notif_type 归类；系统类通知（公告、置顶等）不受偏好控制，返回 None
+    pub fn from_notif_type(notif_type: &str) -> Option<NotificationCategory> {
+        match notif_type {
+            "CommentReceived" | "PackageReviewed" => Some(NotificationCategory::Comment),
+            "CommentReply" | "ReviewReply" => Some(NotificationCategory::Reply),
+            "Mention" => Some(NotificationCategory::Mention),
+            "PackageLiked" | "PostLiked" => Some(NotificationCategory::Like),
+            "NewFollower" | "FollowingPublished" => Some(NotificationCategory::Follow),
+            "ResourceApproved" | "ResourceRejected" | "PostApproved" | "PostRejected" => Some(NotificationCategory::ReviewResult),
+            "CategoryUpdate" => Some(NotificationCategory::SubscribedResource),
+            _ => None,
+        }
+    }
+
+    /// 用户未设置时的默认投递方式
+    pub fn default_channel(&self) -> NotificationChannel {
+        match self {
+            NotificationCategory::SubscribedResource => NotificationChannel::Email,
+            _ => NotificationChannel::InApp,
+        }
+    }
+}
+
+/// 投递方式：仅站内 / 站内+邮件 / 关闭
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationChannel {
+    InApp,
+    Email,
+    Off,
+}
+
+impl NotificationChannel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationChannel::InApp => "in_app",
+            NotificationChannel::Email => "email",
+            NotificationChannel::Off => "off",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<NotificationChannel> {
+        match s {
+            "in_app" => Some(NotificationChannel::InApp),
+            "email" => Some(NotificationChannel::Email),
+            "off" => Some(NotificationChannel::Off),
+            _ => None,
+        }
+    }
+}
+
+/// 邮件摘要频率：off 表示邮件类通知即时发送
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DigestFrequency {
+    Off,
+    Daily,
+    Weekly,
+}
+
+impl DigestFrequency {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DigestFrequency::Off => "off",
+            DigestFrequency::Daily => "daily",
+            DigestFrequency::Weekly => "weekly",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<DigestFrequency> {
+        match s {
+            "off" => Some(DigestFrequency::Off),
+            "daily" => Some(DigestFrequency::Daily),
+            "weekly" => Some(DigestFrequency::Weekly),
+            _ => None,
+        }
+    }
+}
+
+/// 用户的邮件摘要设置
+#[derive(Debug, Clone)]
+pub struct DigestSetting {
+    pub user_id: i32,
+    pub frequency: DigestFrequency,
+    pub last_sent_at: Option<DateTime<Utc>>,
+    pub unsubscribe_token: String,
+    /// 每周一发送个人周报邮件
+    pub weekly_report: bool,
+    /// 邮件模板语言，为空时使用默认语言
+    pub mail_language: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NotificationPreferenceItem {
+    pub category: NotificationCategory,
+    pub label: String,
+    pub channel: NotificationChannel,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NotificationSettings {
+    pub preferences: Vec<NotificationPreferenceItem>,
+    pub digest_frequency: DigestFrequency,
+    pub weekly_report_email: bool,
+    pub mail_language: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateNotificationSettingsRequest {
+    /// 类别 -> 投递方式
+    pub preferences: Option<std::collections::HashMap<NotificationCategory, NotificationChannel>>,
+    pub digest_frequency: Option<DigestFrequency>,
+    pub weekly_report_email: Option<bool>,
+    /// 邮件语言，如 zh-CN / en；传空字符串恢复默认
+    pub mail_language: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UnsubscribeQuery {
+    pub token: String,
+    /// 仅退订某一类别的邮件；为空时退订全部邮件
+    pub category: Option<NotificationCategory>,
+}
+
+/// @ 提及与粉丝通知的来源（评论、帖子或资源）
+#[derive(Debug, Clone)]
+pub struct MentionSource<'a> {
+    pub actor_id: i32,
+    pub actor_name: &'a str,
+    /// 提及发生的位置描述，如「帖子《标题》」
+    pub place: &'a str,
+    pub link: &'a str,
+    pub related_type: &'a str,
+    pub related_id: i32,
+}
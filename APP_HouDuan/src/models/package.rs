@@ -24,7 +24,11 @@ where
 pub struct Package {
     pub id: i32,
     pub name: String,
+    /// 作者显示名，有所有者时与所有者用户名保持一致
     pub author: String,
+    /// 所有者用户ID，权限判断以此为准；历史数据未能匹配到用户时为空
+    #[serde(default)]
+    pub owner_id: Option<i32>,
     pub version: Option<String>,
     pub description: Option<String>,
     pub file_url: Option<String>,
@@ -54,6 +58,14 @@ pub struct Package {
     pub included_files: Option<Vec<PackageFile>>, // 包含的文件列表
 }
 
+/// 资源排行榜排序方式
+#[derive(Debug, Clone)]
+pub enum PackageRankingSort {
+    Downloads,
+    /// 贝叶斯加权评分：(n * 平均分 + 先验权重 * 全站平均分) / (n + 先验权重)
+    Rating { prior_mean: f64, prior_weight: f64 },
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum PackageStatus {
     Pending,    // 待审核
@@ -73,6 +85,9 @@ impl Default for PackageStatus {
 pub struct CreatePackageRequest {
     pub name: String,
     pub author: String,
+    /// 所有者用户ID；为空时按 author 匹配用户名
+    #[serde(default)]
+    pub owner_id: Option<i32>,
     pub version: Option<String>,
     pub description: Option<String>,
     pub category_id: Option<i32>,
@@ -88,7 +103,7 @@ pub struct CreatePackageRequest {
     pub included_files: Option<Vec<PackageFile>>, // 包含的文件列表
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct UpdatePackageRequest {
     pub name: Option<String>,
     pub version: Option<String>,
@@ -110,8 +125,8 @@ pub struct UpdatePackageRequest {
     pub cover_image: Option<String>,      // 封面图片URL
     pub requirements: Option<Vec<String>>, // 系统要求列表
     pub included_files: Option<Vec<PackageFile>>, // 包含的文件列表
-    // 管理员可修改作者字段
-    pub author: Option<String>,
+    // 管理员可直接指定所有者（其他用户需通过所有权转让）
+    pub owner_id: Option<i32>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -136,6 +151,37 @@ pub struct PublishResourceRequest {
     pub screenshots: Option<Vec<PublishFileInfo>>,
 }
 
+impl PublishResourceRequest {
+    /// 解析分类：可以是分类ID或分类名称
+    pub fn resolve_category(&self, categories: &[crate::models::Category]) -> Option<i32> {
+        let category = self.category.as_deref()?;
+        if let Ok(id) = category.parse::<i32>() {
+            return Some(id);
+        }
+        categories.iter().find(|cat| cat.name == category).map(|cat| cat.id)
+    }
+
+    /// 转为资源创建请求：作者为发布者本人，文件与截图后续通过上传接口填充
+    pub fn to_create_request(&self, owner_id: i32, owner_name: &str, category_id: Option<i32>) -> CreatePackageRequest {
+        CreatePackageRequest {
+            name: self.title.clone(),
+            author: owner_name.to_string(),
+            owner_id: Some(owner_id),
+            version: self.version.clone(),
+            description: Some(self.content.clone()),
+            category_id,
+            file_url: None,
+            tags: self.tags.clone(),
+            is_pinned: Some(false),
+            is_featured: Some(false),
+            screenshots: None,
+            cover_image: None,
+            requirements: self.requirements.clone(),
+            included_files: None,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PublishFileInfo {
     pub name: String,
@@ -152,4 +198,19 @@ pub struct PublishPostRequest {
     pub tags: Option<Vec<String>>,
     pub images: Option<Vec<PublishFileInfo>>,
     pub code_snippet: Option<String>,
-} 
\ No newline at end of file
+}
+
+impl PublishPostRequest {
+    /// 转为帖子创建请求：默认草稿，审核中；图片暂不写入，占位使用 []
+    pub fn to_create_request(&self) -> crate::models::CreatePostRequest {
+        crate::models::CreatePostRequest {
+            title: self.title.clone(),
+            content: self.content.clone(),
+            category_id: None,
+            tags: self.tags.clone(),
+            status: Some(crate::models::PostStatus::Draft),
+            images: None,
+            code_snippet: self.code_snippet.clone(),
+        }
+    }
+}
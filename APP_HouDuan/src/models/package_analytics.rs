@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+
+/// 未指定天数时统计最近 30 天，最多 365 天
+pub const DEFAULT_ANALYTICS_DAYS: i64 = 30;
+pub const MAX_ANALYTICS_DAYS: i64 = 365;
+
+#[derive(Debug, Deserialize)]
+pub struct AnalyticsQuery {
+    pub days: Option<i64>,
+}
+
+/// 某一天（社区时区）的数据
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PackageDailyStats {
+    pub date: String,
+    pub views: i64,
+    pub unique_visitors: i64,
+    pub downloads: i64,
+    pub unique_downloaders: i64,
+    pub new_likes: i64,
+    pub new_favorites: i64,
+    // 当天结束时的点赞与收藏数（按现存记录推算，取消的点赞与收藏不计入）
+    pub like_total: i64,
+    pub favorite_total: i64,
+}
+
+/// 统计区间内的合计
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PackageAnalyticsTotals {
+    pub views: i64,
+    pub unique_visitors: i64,
+    pub downloads: i64,
+    pub unique_downloaders: i64,
+    pub new_likes: i64,
+    pub new_favorites: i64,
+    pub like_count: i64,
+    pub favorite_count: i64,
+    // 下载量 / 浏览量
+    pub download_rate: f64,
+}
+
+/// 来源分布
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceCount {
+    pub name: String,
+    pub count: i64,
+    pub percent: f64,
+}
+
+/// 平台或客户端分布
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentCount {
+    pub name: String,
+    pub views: i64,
+    pub downloads: i64,
+}
+
+/// 各版本的下载情况（下载记录保存了当时的版本号）
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionAdoption {
+    pub version: Option<String>,
+    pub downloads: i64,
+    pub unique_downloaders: i64,
+    pub first_download_at: String,
+    pub last_download_at: String,
+    pub is_current: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PackageAnalytics {
+    pub package_id: i32,
+    pub package_name: String,
+    pub current_version: Option<String>,
+    pub from: String,
+    pub to: String,
+    pub days: i64,
+    pub totals: PackageAnalyticsTotals,
+    pub daily: Vec<PackageDailyStats>,
+    pub referrers: Vec<SourceCount>,
+    pub platforms: Vec<AgentCount>,
+    pub clients: Vec<AgentCount>,
+    pub versions: Vec<VersionAdoption>,
+}
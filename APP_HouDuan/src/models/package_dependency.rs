@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+
+/// 单个资源最多声明的依赖数
+pub const MAX_DEPENDENCIES_PER_PACKAGE: usize = 50;
+/// 解析时最多展开的资源数，防止异常数据导致无限展开
+pub const MAX_RESOLVE_PACKAGES: usize = 500;
+
+/// 资源声明的一个依赖
+#[derive(Debug, Clone, Serialize)]
+pub struct PackageDependency {
+    pub depends_on_id: i32,
+    /// 被依赖资源已删除时为空
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub status: Option<String>,
+    pub version_req: String,
+    pub optional: bool,
+    /// 被依赖资源的当前版本是否满足版本范围
+    pub satisfied: bool,
+}
+
+/// 资源支持的结绳版本范围，两端均可为空
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AppCompatibility {
+    pub min_app_version: Option<String>,
+    pub max_app_version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PackageDependencyInfo {
+    pub package_id: i32,
+    pub version: Option<String>,
+    #[serde(flatten)]
+    pub compatibility: AppCompatibility,
+    pub dependencies: Vec<PackageDependency>,
+}
+
+/// 反向依赖：依赖某资源的其他资源
+#[derive(Debug, Clone, Serialize)]
+pub struct DependentPackage {
+    pub package_id: i32,
+    pub name: String,
+    pub version: Option<String>,
+    pub owner_id: Option<i32>,
+    pub version_req: String,
+    pub optional: bool,
+    /// 被依赖资源的当前版本是否仍满足其版本范围
+    pub satisfied: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DependencyInput {
+    pub package_id: i32,
+    /// 缺省为任意版本
+    #[serde(default)]
+    pub version_req: Option<String>,
+    #[serde(default)]
+    pub optional: bool,
+}
+
+/// 整体替换资源的依赖与兼容版本
+#[derive(Debug, Deserialize)]
+pub struct UpdateDependenciesRequest {
+    #[serde(default)]
+    pub dependencies: Vec<DependencyInput>,
+    pub min_app_version: Option<String>,
+    pub max_app_version: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResolveQuery {
+    /// 目标结绳版本，提供时检查每个资源的兼容范围
+    pub app_version: Option<String>,
+    /// 是否把可选依赖计入安装集合
+    #[serde(default)]
+    pub include_optional: bool,
+}
+
+/// 安装集合中的一个资源
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolvedPackage {
+    pub package_id: i32,
+    pub name: String,
+    pub version: Option<String>,
+    pub file_url: Option<String>,
+    pub file_size: Option<i64>,
+    /// 直接依赖它的资源；根资源为空
+    pub required_by: Vec<i32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyConflict {
+    pub package_id: i32,
+    pub name: Option<String>,
+    pub version: Option<String>,
+    /// 版本范围冲突时为要求的范围，兼容性冲突时为目标结绳版本
+    pub requirement: Option<String>,
+    pub required_by: Option<i32>,
+    pub reason: String,
+}
+
+/// 解析结果：install_order 按安装顺序排列（依赖在前，根资源最后）
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyResolution {
+    pub package_id: i32,
+    pub version: Option<String>,
+    pub app_version: Option<String>,
+    pub resolvable: bool,
+    pub install_order: Vec<ResolvedPackage>,
+    pub conflicts: Vec<DependencyConflict>,
+    /// 每个环按依赖方向列出资源ID，首尾相同
+    pub cycles: Vec<Vec<i32>>,
+}
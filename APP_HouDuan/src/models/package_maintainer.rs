@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// 所有者：每个资源一个，可管理维护者、转让与删除资源
+pub const ROLE_OWNER: &str = "owner";
+/// 协作维护者：可编辑资源与上传文件
+pub const ROLE_MAINTAINER: &str = "maintainer";
+
+pub const TRANSFER_PENDING: &str = "pending";
+pub const TRANSFER_ACCEPTED: &str = "accepted";
+pub const TRANSFER_DECLINED: &str = "declined";
+pub const TRANSFER_CANCELLED: &str = "cancelled";
+
+/// 单个资源最多的协作维护者数（不含所有者）
+pub const MAX_MAINTAINERS_PER_PACKAGE: i64 = 20;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PackageMaintainer {
+    pub user_id: i32,
+    pub username: String,
+    pub nickname: Option<String>,
+    pub avatar: Option<String>,
+    pub role: String,
+    pub added_by: Option<i32>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 所有权转让请求
+#[derive(Debug, Clone, Serialize)]
+pub struct OwnershipTransfer {
+    pub id: i32,
+    pub package_id: i32,
+    pub package_name: Option<String>,
+    pub from_user_id: i32,
+    pub from_username: Option<String>,
+    pub to_user_id: i32,
+    pub to_username: Option<String>,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub responded_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddMaintainerRequest {
+    pub user_id: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TransferOwnershipRequest {
+    pub to_user_id: i32,
+}
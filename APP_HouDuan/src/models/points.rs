@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// 积分规则的行为编码
+pub const RULE_CHECK_IN: &str = "check_in";
+pub const RULE_PACKAGE_APPROVED: &str = "package_approved";
+pub const RULE_POST_PUBLISHED: &str = "post_published";
+pub const RULE_LIKE_RECEIVED: &str = "like_received";
+pub const RULE_CONTENT_FEATURED: &str = "content_featured";
+pub const RULE_COMMENT_POSTED: &str = "comment_posted";
+pub const RULE_CONTENT_REMOVED: &str = "content_removed";
+/// 补签消耗（stars 为负数）
+pub const RULE_CHECK_IN_MAKEUP: &str = "check_in_makeup";
+/// 内容被删除时冲销该内容此前获得的奖励（不走规则表）
+pub const LEDGER_REVOKE: &str = "revoke";
+/// 内容从回收站恢复时退回删除时收回与处罚的积分（不走规则表）
+pub const LEDGER_RESTORE: &str = "restore";
+/// 管理员手动调整（不走规则表）
+pub const LEDGER_ADMIN_ADJUST: &str = "admin_adjust";
+
+/// 积分规则：某个行为奖励/扣除的经验与星星，daily_cap 为每日最多计入次数（0 表示不限）
+#[derive(Debug, Clone, Serialize)]
+pub struct PointRule {
+    pub action: String,
+    pub name: String,
+    pub xp: i64,
+    pub stars: i64,
+    pub daily_cap: i32,
+    pub enabled: bool,
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdatePointRuleRequest {
+    pub name: Option<String>,
+    pub xp: Option<i64>,
+    pub stars: Option<i64>,
+    pub daily_cap: Option<i32>,
+    pub enabled: Option<bool>,
+}
+
+/// 积分流水
+#[derive(Debug, Clone, Serialize)]
+pub struct PointLedgerEntry {
+    pub id: i64,
+    pub user_id: i32,
+    pub action: String,
+    pub xp: i64,
+    pub stars: i64,
+    pub reason: String,
+    pub related_type: Option<String>,
+    pub related_id: Option<i32>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LedgerQuery {
+    pub page: Option<i32>,
+    pub page_size: Option<i32>,
+}
+
+/// 等级定义：经验达到 min_xp 即为该等级
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserLevel {
+    pub level: i32,
+    pub title: String,
+    pub min_xp: i64,
+}
+
+/// 用户当前等级与升级进度
+#[derive(Debug, Clone, Serialize)]
+pub struct LevelProgress {
+    pub level: i32,
+    pub title: String,
+    pub experience: i64,
+    pub stars: i64,
+    pub current_level_xp: i64,
+    /// 已是最高等级时为 None
+    pub next_level_xp: Option<i64>,
+    pub next_title: Option<String>,
+    /// 当前等级内的进度百分比（0-100）
+    pub progress: f64,
+}
+
+impl LevelProgress {
+    /// 根据经验值与等级表（按 min_xp 升序）计算等级进度
+    pub fn compute(experience: i64, stars: i64, levels: &[UserLevel]) -> LevelProgress {
+        let idx = levels.iter().rposition(|l| experience >= l.min_xp).unwrap_or(0);
+        let (level, title, current_level_xp) = match levels.get(idx) {
+            Some(l) => (l.level, l.title.clone(), l.min_xp),
+            None => (1, String::new(), 0),
+        };
+        let next = levels.get(idx + 1);
+        let progress = match next {
+            Some(n) if n.min_xp > current_level_xp => {
+                ((experience - current_level_xp) as f64 / (n.min_xp - current_level_xp) as f64 * 100.0).clamp(0.0, 100.0)
+            }
+            _ => 100.0,
+        };
+        LevelProgress {
+            level,
+            title,
+            experience,
+            stars,
+            current_level_xp,
+            next_level_xp: next.map(|n| n.min_xp),
+            next_title: next.map(|n| n.title.clone()),
+            progress: (progress * 10.0).round() / 10.0,
+        }
+    }
+}
+
+/// 管理员手动调整积分
+#[derive(Debug, Deserialize)]
+pub struct AdjustPointsRequest {
+    pub xp: Option<i64>,
+    pub stars: Option<i64>,
+    pub reason: String,
+}
@@ -0,0 +1,159 @@
+use serde::{Deserialize, Serialize};
+use chrono::{Datelike, Duration, NaiveDate};
+
+use crate::models::weekly_report::ReportWeek;
+
+/// 排行榜类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RankingBoard {
+    Users,
+    Resources,
+    Posts,
+}
+
+impl RankingBoard {
+    pub fn all() -> [RankingBoard; 3] {
+        [RankingBoard::Users, RankingBoard::Resources, RankingBoard::Posts]
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RankingBoard::Users => "users",
+            RankingBoard::Resources => "resources",
+            RankingBoard::Posts => "posts",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<RankingBoard> {
+        Self::all().into_iter().find(|b| b.as_str() == s)
+    }
+}
+
+/// 统计周期：按社区时区的自然周（ISO 周）、自然月、自然年；all 为累计数据
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RankingPeriod {
+    Week,
+    Month,
+    Year,
+    All,
+}
+
+impl RankingPeriod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RankingPeriod::Week => "week",
+            RankingPeriod::Month => "month",
+            RankingPeriod::Year => "year",
+            RankingPeriod::All => "all",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<RankingPeriod> {
+        match s {
+            "week" => Some(RankingPeriod::Week),
+            "month" => Some(RankingPeriod::Month),
+            "year" => Some(RankingPeriod::Year),
+            "all" => Some(RankingPeriod::All),
+            _ => None,
+        }
+    }
+
+    /// 包含某一天的统计窗口，all 没有窗口
+    pub fn window_containing(&self, date: NaiveDate) -> Option<RankingWindow> {
+        let start = match self {
+            RankingPeriod::Week => ReportWeek::containing(date).monday,
+            RankingPeriod::Month => date.with_day(1)?,
+            RankingPeriod::Year => NaiveDate::from_ymd_opt(date.year(), 1, 1)?,
+            RankingPeriod::All => return None,
+        };
+        let end = match self {
+            RankingPeriod::Week => start + Duration::days(6),
+            RankingPeriod::Month => {
+                let next = if start.month() == 12 {
+                    NaiveDate::from_ymd_opt(start.year() + 1, 1, 1)?
+                } else {
+                    NaiveDate::from_ymd_opt(start.year(), start.month() + 1, 1)?
+                };
+                next - Duration::days(1)
+            }
+            RankingPeriod::Year => NaiveDate::from_ymd_opt(start.year(), 12, 31)?,
+            RankingPeriod::All => return None,
+        };
+        let key = match self {
+            RankingPeriod::Week => ReportWeek { monday: start }.key(),
+            RankingPeriod::Month => start.format("%Y-%m").to_string(),
+            _ => start.format("%Y").to_string(),
+        };
+        Some(RankingWindow { period: *self, key, start, end })
+    }
+
+    /// 按周期编号解析窗口：周 2025-W01、月 2025-01、年 2025
+    pub fn window_for_key(&self, key: &str) -> Option<RankingWindow> {
+        let key = key.trim();
+        let date = match self {
+            RankingPeriod::Week => ReportWeek::parse(key)?.monday,
+            RankingPeriod::Month => NaiveDate::parse_from_str(&format!("{}-01", key), "%Y-%m-%d").ok()?,
+            RankingPeriod::Year => NaiveDate::from_ymd_opt(key.parse().ok()?, 1, 1)?,
+            RankingPeriod::All => return None,
+        };
+        self.window_containing(date)
+    }
+}
+
+/// 统计窗口 [start, end]（本地日期，含首尾）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RankingWindow {
+    pub period: RankingPeriod,
+    pub key: String,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl RankingWindow {
+    pub fn previous(&self) -> RankingWindow {
+        self.period.window_containing(self.start - Duration::days(1)).expect("windowed period")
+    }
+}
+
+/// 窗口内的聚合数据，score 为排序主指标（用户：经验；资源：下载；帖子：浏览）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RankingRow {
+    pub entity_id: i32,
+    pub score: i64,
+    pub downloads: i64,
+    pub likes: i64,
+    pub views: i64,
+    pub comments: i64,
+    pub followers: i64,
+}
+
+/// 带名次的排行数据；rank_change 为相对上一周期的名次变化（正数上升，None 表示上期未上榜）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RankedRow {
+    pub rank: i64,
+    pub rank_change: Option<i64>,
+    #[serde(flatten)]
+    pub row: RankingRow,
+}
+
+#[derive(Debug, Clone)]
+pub struct RankingPage {
+    pub window: RankingWindow,
+    pub rows: Vec<RankedRow>,
+    pub total: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RankingHistoryQuery {
+    /// users / resources / posts
+    pub board: String,
+    /// week / month / year
+    pub period: String,
+    /// 周期编号，如 2025-W01、2025-01、2025
+    pub key: String,
+    pub category_id: Option<i32>,
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+}
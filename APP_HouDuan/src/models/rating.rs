@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// 评价文字的审核状态
+pub const REVIEW_VISIBLE: &str = "visible";
+pub const REVIEW_HIDDEN: &str = "hidden";
+
+/// 用户对资源的评分与评价（每人每个资源一条）
+#[derive(Debug, Clone, Serialize)]
+pub struct PackageRating {
+    pub id: i32,
+    pub package_id: i32,
+    pub user_id: i32,
+    pub username: Option<String>,
+    pub nickname: Option<String>,
+    pub avatar_url: Option<String>,
+    pub rating: i32, // 1-5 星
+    pub review: Option<String>,
+    pub review_status: String, // visible / hidden
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub moderation_reason: Option<String>,
+    pub author_reply: Option<String>,
+    pub author_replied_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 资源评分汇总（同时冗余存储在 packages 表）
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RatingSummary {
+    pub average: f64,
+    pub count: i32,
+    /// 1~5 星各自的数量，下标 0 对应 1 星
+    pub distribution: [i32; 5],
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpsertRatingRequest {
+    pub rating: i32,
+    pub review: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReplyRatingRequest {
+    pub reply: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RatingQuery {
+    pub page: Option<i32>,
+    pub page_size: Option<i32>,
+    /// 按星级筛选
+    pub rating: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AdminReviewQuery {
+    pub page: Option<i32>,
+    pub page_size: Option<i32>,
+    pub status: Option<String>,
+    pub package_id: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ModerateReviewRequest {
+    pub status: String, // visible / hidden
+    pub reason: Option<String>,
+}
@@ -0,0 +1,61 @@
+use serde::Serialize;
+
+use crate::models::Package;
+
+/// 用户对资源的交互：下载、点赞、收藏
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InteractionKind {
+    Download,
+    Like,
+    Favorite,
+}
+
+impl InteractionKind {
+    /// 交互权重：收藏 > 点赞 > 下载
+    pub fn weight(&self) -> f64 {
+        match self {
+            InteractionKind::Download => 1.0,
+            InteractionKind::Like => 2.0,
+            InteractionKind::Favorite => 3.0,
+        }
+    }
+}
+
+/// 一条交互记录
+#[derive(Debug, Clone, Copy)]
+pub struct Interaction {
+    pub user_id: i32,
+    pub package_id: i32,
+    pub kind: InteractionKind,
+}
+
+/// 两个资源的相似度
+#[derive(Debug, Clone, Serialize)]
+pub struct PackageSimilarity {
+    pub package_id: i32,
+    pub similar_id: i32,
+    pub score: f64,
+    pub co_downloads: i32,
+    pub co_likes: i32,
+    pub shared_tags: i32,
+}
+
+/// 为用户预计算的推荐
+#[derive(Debug, Clone)]
+pub struct UserRecommendation {
+    pub user_id: i32,
+    pub package_id: i32,
+    pub score: f64,
+    pub source_package_id: Option<i32>,
+}
+
+/// 返回给前端的推荐资源
+#[derive(Debug, Clone, Serialize)]
+pub struct RecommendedPackage {
+    #[serde(flatten)]
+    pub package: Package,
+    pub score: f64,
+    /// 推荐理由，例如“下载过《X》的用户也下载了”
+    pub reason: Option<String>,
+}
+
@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+/// 回收站中的内容类型，与积分、编辑历史中的 target_type 一致
+pub const RECYCLE_TYPES: [&str; 4] = ["Package", "Post", "Comment", "User"];
+
+/// 回收站保留天数：默认 30 天，可设置为 1~365 天
+pub const RECYCLE_RETENTION_KEY: &str = "recycle_bin_retention_days";
+pub const DEFAULT_RETENTION_DAYS: i64 = 30;
+pub const MIN_RETENTION_DAYS: i64 = 1;
+pub const MAX_RETENTION_DAYS: i64 = 365;
+
+/// 路径中的类型不区分大小写，返回规范写法
+pub fn parse_recycle_type(s: &str) -> Option<&'static str> {
+    RECYCLE_TYPES.iter().copied().find(|t| t.eq_ignore_ascii_case(s.trim()))
+}
+
+/// 回收站中的一项；title 为资源名、帖子标题、评论内容摘要或用户名
+#[derive(Debug, Clone, Serialize)]
+pub struct RecycleBinItem {
+    pub item_type: String,
+    pub id: i32,
+    pub title: String,
+    /// 内容作者；用户本身为空
+    pub owner_id: Option<i32>,
+    pub owner_name: Option<String>,
+    pub deleted_at: String,
+    pub deleted_by: Option<i32>,
+    pub deleted_by_name: Option<String>,
+    /// 到期后由清理任务彻底删除
+    pub purge_at: String,
+}
+
+/// type 为空时列出全部类型；keyword 匹配标题
+#[derive(Debug, Deserialize)]
+pub struct RecycleBinQuery {
+    #[serde(rename = "type")]
+    pub item_type: Option<String>,
+    pub keyword: Option<String>,
+    pub page: Option<i32>,
+    pub page_size: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecycleBinSettings {
+    pub retention_days: i64,
+}
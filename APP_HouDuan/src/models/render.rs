@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+/// 渲染缓存：源内容哈希与渲染规则版本一致时直接使用
+#[derive(Debug, Clone)]
+pub struct CachedRender {
+    pub source_hash: String,
+    pub renderer_version: i32,
+    pub html: String,
+    pub excerpt: String,
+}
+
+/// 单个字段的渲染结果
+#[derive(Debug, Clone, Serialize)]
+pub struct RenderedField {
+    pub html: String,
+    pub excerpt: String,
+}
+
+/// 帖子详情附带的渲染结果
+#[derive(Debug, Clone, Serialize)]
+pub struct RenderedPost {
+    pub content_html: String,
+    pub code_snippet_html: Option<String>,
+    pub excerpt: String,
+}
+
+/// 资源详情附带的渲染结果
+#[derive(Debug, Clone, Serialize)]
+pub struct RenderedPackage {
+    pub description_html: String,
+    pub excerpt: String,
+}
+
+/// 编辑器预览：渲染 Markdown，或按 language 高亮代码片段
+#[derive(Debug, Deserialize)]
+pub struct RenderPreviewRequest {
+    pub content: String,
+    /// 为 true 时按代码片段处理
+    #[serde(default)]
+    pub code: bool,
+    pub language: Option<String>,
+}
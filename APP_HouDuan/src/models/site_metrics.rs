@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+
+/// 未指定范围时查看最近 30 个已汇总的日期；单次查询与手动重算最多 366 天
+pub const DEFAULT_METRICS_DAYS: i64 = 30;
+pub const MAX_METRICS_DAYS: i64 = 366;
+/// 留存默认看最近 12 个注册周，最多 52 个；每个注册周跟踪到注册后第 12 周
+pub const DEFAULT_RETENTION_COHORTS: i64 = 12;
+pub const MAX_RETENTION_COHORTS: i64 = 52;
+pub const RETENTION_TRACK_WEEKS: i64 = 12;
+
+/// 某一天（北京时间）的全站指标
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SiteDailyMetrics {
+    pub date: String,
+    pub new_users: i64,
+    pub dau: i64,
+    pub wau: i64,
+    pub mau: i64,
+    pub app_launches: i64,
+    pub uploads: i64,
+    pub downloads: i64,
+    pub unique_downloaders: i64,
+    pub new_posts: i64,
+    pub new_comments: i64,
+    pub total_users: i64,
+    pub total_packages: i64,
+    pub total_posts: i64,
+    pub total_comments: i64,
+    pub computed_at: String,
+}
+
+/// 日期范围内的汇总：新增类指标求和，活跃类取平均与最后一天，累计类取最后一天
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SiteMetricsSummary {
+    pub days: i64,
+    pub new_users: i64,
+    pub avg_dau: f64,
+    pub peak_dau: i64,
+    pub wau: i64,
+    pub mau: i64,
+    pub app_launches: i64,
+    pub uploads: i64,
+    pub downloads: i64,
+    pub new_posts: i64,
+    pub new_comments: i64,
+    pub total_users: i64,
+    pub total_packages: i64,
+    pub total_posts: i64,
+    pub total_comments: i64,
+}
+
+/// 与对比区间相比的变化，对比值为 0 时不计算百分比
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricChange {
+    pub metric: String,
+    pub current: f64,
+    pub previous: f64,
+    pub change_percent: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SiteMetricsComparison {
+    pub mode: String,
+    pub from: String,
+    pub to: String,
+    pub summary: SiteMetricsSummary,
+    pub changes: Vec<MetricChange>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SiteMetricsReport {
+    pub from: String,
+    pub to: String,
+    // 最近一次汇总到的日期，之后的日期尚未汇总
+    pub last_rollup_date: Option<String>,
+    pub summary: SiteMetricsSummary,
+    pub daily: Vec<SiteDailyMetrics>,
+    pub comparison: Option<SiteMetricsComparison>,
+}
+
+/// from / to 为北京时间日期；compare 可选 previous（上一个等长区间，默认）、year（去年同期）、none
+#[derive(Debug, Deserialize)]
+pub struct SiteMetricsQuery {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub compare: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RetentionWeek {
+    pub week_offset: i64,
+    pub retained: i64,
+    pub rate: f64,
+}
+
+/// 一个注册周的留存曲线
+#[derive(Debug, Clone, Serialize)]
+pub struct SignupCohort {
+    pub cohort_week: String,
+    pub cohort_size: i64,
+    pub weeks: Vec<RetentionWeek>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RetentionQuery {
+    pub weeks: Option<i64>,
+}
+
+/// 手动重算日期范围（含两端），只能是已结束的日期
+#[derive(Debug, Deserialize)]
+pub struct RollupMetricsRequest {
+    pub from: String,
+    pub to: String,
+}
@@ -22,6 +22,16 @@ pub struct Category {
     pub subscription_locked: bool,
     pub created_at: String,
     pub updated_at: Option<String>,
+    // 层级与显示
+    pub parent_id: Option<i32>,
+    #[serde(default)]
+    pub sort_order: i32,
+    pub icon: Option<String>,
+    // 发布规则：为空时沿父分类继承，整条链都未设置时使用全局配置
+    pub requires_review: Option<bool>,
+    pub allowed_extensions: Option<Vec<String>>,
+    pub max_file_size: Option<i64>,
+    pub post_permission: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -30,6 +40,13 @@ pub struct CreateCategoryRequest {
     pub description: Option<String>,
     pub enabled: Option<bool>,
     pub subscription_locked: Option<bool>,
+    pub parent_id: Option<i32>,
+    pub sort_order: Option<i32>,
+    pub icon: Option<String>,
+    pub requires_review: Option<bool>,
+    pub allowed_extensions: Option<Vec<String>>,
+    pub max_file_size: Option<i64>,
+    pub post_permission: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -38,6 +55,16 @@ pub struct UpdateCategoryRequest {
     pub description: Option<String>,
     pub enabled: Option<bool>,
     pub subscription_locked: Option<bool>,
+    /// 0 表示移到顶级
+    pub parent_id: Option<i32>,
+    pub sort_order: Option<i32>,
+    pub icon: Option<String>,
+    pub requires_review: Option<bool>,
+    pub allowed_extensions: Option<Vec<String>>,
+    pub max_file_size: Option<i64>,
+    pub post_permission: Option<String>,
+    /// 恢复为继承的规则名，如 ["requires_review", "max_file_size"]
+    pub inherit: Option<Vec<String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -8,6 +8,7 @@ pub struct Tag {
     pub description: Option<String>,
     pub color: Option<String>,
     pub use_count: i32,
+    pub status: String, // approved / pending / banned
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -24,6 +25,7 @@ pub struct UpdateTagRequest {
     pub name: Option<String>,
     pub description: Option<String>,
     pub color: Option<String>,
+    pub keep_alias: Option<bool>, // 改名时把旧名称保留为别名，默认保留
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -41,6 +43,7 @@ pub struct TagQueryParams {
     pub search: Option<String>,
     pub sort_by: Option<String>, // "name", "use_count", "created_at"
     pub sort_order: Option<String>, // "asc", "desc"
+    pub status: Option<String>, // 不传时不含已禁用的标签，"all" 表示全部
 }
 
 // 帖子标签关联表模型
@@ -56,4 +59,48 @@ pub struct PostTag {
 pub struct PostTagRequest {
     pub post_id: i32,
     pub tag_ids: Vec<i32>,
-} 
\ No newline at end of file
+}
+
+/// 标签审核状态
+pub const TAG_STATUSES: [&str; 3] = ["approved", "pending", "banned"];
+
+/// 规范化后标签名的最大字符数
+pub const MAX_TAG_LENGTH: usize = 20;
+
+// 标签别名
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagAlias {
+    pub id: i32,
+    pub alias: String,
+    pub tag_id: i32,
+    pub tag_name: String,
+    pub created_by: Option<i32>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateTagAliasRequest {
+    pub alias: String,
+}
+
+// 把当前标签合并到 target_id，当前标签名变为目标标签的别名
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MergeTagRequest {
+    pub target_id: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateTagStatusRequest {
+    pub status: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TagSettings {
+    pub whitelist_mode: bool,
+}
+
+// 校验一组标签，返回规范化并替换别名后的名称
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CheckTagsRequest {
+    pub tags: Vec<String>,
+}
@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// 关注动态类型
+pub const KIND_POST_PUBLISHED: &str = "post_published";
+pub const KIND_PACKAGE_PUBLISHED: &str = "package_published";
+pub const KIND_PACKAGE_UPDATED: &str = "package_updated";
+pub const KIND_ACHIEVEMENT_EARNED: &str = "achievement_earned";
+
+/// 时间线游标：按 (时间, 类型序号, 条目ID) 倒序翻页，格式为 "纳秒级时间戳-类型序号-ID"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimelineCursor {
+    pub ts: i64,
+    pub ord: i32,
+    pub id: i64,
+}
+
+impl TimelineCursor {
+    pub fn parse(s: &str) -> Option<TimelineCursor> {
+        let mut parts = s.trim().splitn(3, '-');
+        Some(TimelineCursor {
+            ts: parts.next()?.parse().ok()?,
+            ord: parts.next()?.parse().ok()?,
+            id: parts.next()?.parse().ok()?,
+        })
+    }
+
+    pub fn encode(&self) -> String {
+        format!("{}-{}-{}", self.ts, self.ord, self.id)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineActor {
+    pub id: i32,
+    pub username: String,
+    pub nickname: Option<String>,
+    pub avatar_url: Option<String>,
+}
+
+/// 关注时间线条目
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineItem {
+    /// post_published / package_published / package_updated / achievement_earned
+    pub kind: String,
+    pub actor: Option<TimelineActor>,
+    /// Post / Package / Achievement
+    pub target_type: String,
+    pub target_id: i32,
+    pub title: String,
+    /// 帖子或资源摘要、更新后的版本号、成就说明
+    pub summary: Option<String>,
+    pub category_id: Option<i32>,
+    /// 出现在时间线的原因：following（关注的用户）或 category（订阅的分类）
+    pub reason: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(skip)]
+    pub cursor: Option<TimelineCursor>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TimelinePage {
+    pub list: Vec<TimelineItem>,
+    /// 下一页游标，没有更多时为空
+    pub next_cursor: Option<String>,
+    pub has_more: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TimelineQuery {
+    /// 上一页返回的 next_cursor，首页留空
+    pub cursor: Option<String>,
+    pub limit: Option<u32>,
+    /// 只看某一类动态
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+}
@@ -184,4 +184,19 @@ pub struct UpdateUserRequest {
     pub location: Option<String>,
     pub website: Option<String>,
     pub skills: Option<String>,
-} 
\ No newline at end of file
+} 
+
+/// @ 提及候选用户（自动补全 / 按用户名解析主页链接）
+#[derive(Debug, Clone, Serialize)]
+pub struct MentionCandidate {
+    pub id: i32,
+    pub username: String,
+    pub nickname: Option<String>,
+    pub avatar_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MentionSuggestionQuery {
+    pub q: Option<String>,
+    pub limit: Option<i32>,
+}
@@ -0,0 +1,23 @@
+use serde::Serialize;
+use chrono::{DateTime, Utc};
+
+/// 拉黑或屏蔽列表中的用户
+#[derive(Debug, Clone, Serialize)]
+pub struct RelatedUser {
+    pub id: i32,
+    pub username: String,
+    pub nickname: Option<String>,
+    pub avatar: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 我与某个用户之间的拉黑/屏蔽状态
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RelationStatus {
+    /// 我拉黑了对方
+    pub is_blocked: bool,
+    /// 对方拉黑了我
+    pub blocked_me: bool,
+    /// 我屏蔽了对方
+    pub is_muted: bool,
+}
@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc, Weekday};
+
+/// ISO 周（周一至周日，按社区时区划分）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReportWeek {
+    pub monday: NaiveDate,
+}
+
+impl ReportWeek {
+    pub fn containing(date: NaiveDate) -> ReportWeek {
+        ReportWeek { monday: date - Duration::days(date.weekday().num_days_from_monday() as i64) }
+    }
+
+    /// 解析 2025-W01 或 2025-01 形式的 ISO 周
+    pub fn parse(s: &str) -> Option<ReportWeek> {
+        let s = s.trim();
+        let (year, week) = s.split_once("-W").or_else(|| s.split_once('-'))?;
+        let monday = NaiveDate::from_isoywd_opt(year.parse().ok()?, week.parse().ok()?, Weekday::Mon)?;
+        Some(ReportWeek { monday })
+    }
+
+    pub fn key(&self) -> String {
+        let iso = self.monday.iso_week();
+        format!("{}-W{:02}", iso.year(), iso.week())
+    }
+
+    pub fn sunday(&self) -> NaiveDate {
+        self.monday + Duration::days(6)
+    }
+
+    pub fn previous(&self) -> ReportWeek {
+        ReportWeek { monday: self.monday - Duration::days(7) }
+    }
+}
+
+/// 个人周报
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeeklyReport {
+    /// ISO 周，如 2025-W01
+    pub week: String,
+    pub week_start: NaiveDate,
+    pub week_end: NaiveDate,
+    /// 本周尚未结束时为 false，数据截至生成时间
+    pub complete: bool,
+    /// 周一到周日每天发布的帖子数
+    pub daily_posts: Vec<i32>,
+    /// 周一到周日每天发布的资源数
+    pub daily_resources: Vec<i32>,
+    pub posts_published: i32,
+    pub resources_published: i32,
+    pub downloads_received: i32,
+    pub likes_received: i32,
+    pub new_followers: i32,
+    pub comments_posted: i32,
+    pub check_in_days: i32,
+    pub xp_gained: i64,
+    pub stars_gained: i64,
+    /// 周初与周末（或当前）的经验排行榜名次
+    pub rank_start: i32,
+    pub rank_end: i32,
+    /// 名次变化，正数表示上升
+    pub rank_change: i32,
+    pub generated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WeeklyReportQuery {
+    /// ISO 周，如 2025-W01，默认为本周
+    pub week: Option<String>,
+}
@@ -0,0 +1,167 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::models::achievement::{AchievementCriteria, AchievementDefinition, CreateAchievementRequest, UpdateAchievementRequest};
+use crate::utils::time::parse_db_time;
+
+const DEFINITION_COLUMNS: &str = "id, code, name, icon, description, criteria_type, threshold, enabled, sort_order, created_at";
+
+#[derive(Clone)]
+pub struct AchievementRepository {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl AchievementRepository {
+    pub fn new(db_path: &str) -> Result<Self> {
+        let conn = Connection::open(db_path)?;
+        let repo = Self { conn: Arc::new(Mutex::new(conn)) };
+        futures::executor::block_on(repo.init())?;
+        Ok(repo)
+    }
+
+    pub async fn init(&self) -> Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS achievements (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                code TEXT NOT NULL UNIQUE,
+                name TEXT NOT NULL,
+                icon TEXT NOT NULL DEFAULT '🏅',
+                description TEXT NOT NULL DEFAULT '',
+                criteria_type TEXT NOT NULL,
+                threshold INTEGER NOT NULL DEFAULT 1,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                sort_order INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS user_achievements (
+                user_id INTEGER NOT NULL,
+                achievement_id INTEGER NOT NULL,
+                earned_at TEXT NOT NULL,
+                PRIMARY KEY (user_id, achievement_id)
+            )",
+            [],
+        )?;
+        Ok(())
+    }
+
+    fn map_definition(row: &rusqlite::Row) -> rusqlite::Result<Option<AchievementDefinition>> {
+        let criteria: String = row.get(5)?;
+        // 未知的条件类型（例如被新版本移除）直接忽略
+        let Some(criteria_type) = AchievementCriteria::parse(&criteria) else { return Ok(None) };
+        Ok(Some(AchievementDefinition {
+            id: row.get(0)?,
+            code: row.get(1)?,
+            name: row.get(2)?,
+            icon: row.get(3)?,
+            description: row.get(4)?,
+            criteria_type,
+            threshold: row.get(6)?,
+            enabled: row.get(7)?,
+            sort_order: row.get(8)?,
+            created_at: parse_db_time(&row.get::<_, String>(9)?).unwrap_or_else(Utc::now),
+        }))
+    }
+
+    pub async fn list_definitions(&self, include_disabled: bool) -> Result<Vec<AchievementDefinition>> {
+        let conn = self.conn.lock().await;
+        let filter = if include_disabled { "" } else { "WHERE enabled = 1" };
+        let sql = format!("SELECT {} FROM achievements {} ORDER BY sort_order, id", DEFINITION_COLUMNS, filter);
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map([], Self::map_definition)?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?.into_iter().flatten().collect())
+    }
+
+    pub async fn get_definition(&self, id: i32) -> Result<Option<AchievementDefinition>> {
+        let conn = self.conn.lock().await;
+        let sql = format!("SELECT {} FROM achievements WHERE id = ?", DEFINITION_COLUMNS);
+        let def = conn.query_row(&sql, params![id], Self::map_definition).optional()?;
+        Ok(def.flatten())
+    }
+
+    pub async fn create_definition(&self, req: &CreateAchievementRequest) -> Result<i32> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "INSERT INTO achievements (code, name, icon, description, criteria_type, threshold, enabled, sort_order)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                req.code,
+                req.name,
+                req.icon.as_deref().unwrap_or("🏅"),
+                req.description.as_deref().unwrap_or(""),
+                req.criteria_type,
+                req.threshold,
+                req.enabled.unwrap_or(true),
+                req.sort_order.unwrap_or(0),
+            ],
+        )?;
+        Ok(conn.last_insert_rowid() as i32)
+    }
+
+    pub async fn update_definition(&self, id: i32, req: &UpdateAchievementRequest) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let affected = conn.execute(
+            "UPDATE achievements SET
+                name = COALESCE(?, name),
+                icon = COALESCE(?, icon),
+                description = COALESCE(?, description),
+                threshold = COALESCE(?, threshold),
+                enabled = COALESCE(?, enabled),
+                sort_order = COALESCE(?, sort_order)
+             WHERE id = ?",
+            params![req.name, req.icon, req.description, req.threshold, req.enabled, req.sort_order, id],
+        )?;
+        Ok(affected > 0)
+    }
+
+    /// 用户已获得的成就：achievement_id -> earned_at
+    pub async fn list_earned(&self, user_id: i32) -> Result<HashMap<i32, DateTime<Utc>>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare("SELECT achievement_id, earned_at FROM user_achievements WHERE user_id = ?")?;
+        let rows = stmt.query_map(params![user_id], |row| Ok((row.get::<_, i32>(0)?, row.get::<_, String>(1)?)))?;
+        let mut map = HashMap::new();
+        for row in rows {
+            let (id, earned_at) = row?;
+            map.insert(id, parse_db_time(&earned_at).unwrap_or_else(Utc::now));
+        }
+        Ok(map)
+    }
+
+    /// 授予成就，已获得过返回 false
+    pub async fn award(&self, user_id: i32, achievement_id: i32) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO user_achievements (user_id, achievement_id, earned_at) VALUES (?, ?, ?)",
+            params![user_id, achievement_id, Utc::now().to_rfc3339()],
+        )?;
+        Ok(inserted > 0)
+    }
+
+    /// 统计用户在某个条件上的当前数值（连续签到由 UserRepository 计算）
+    pub async fn metric(&self, user_id: i32, criteria: AchievementCriteria) -> Result<i64> {
+        let conn = self.conn.lock().await;
+        let sql = match criteria {
+            AchievementCriteria::PackagesUploaded =>
+                "SELECT COUNT(*) FROM packages WHERE owner_id = ?1 AND status = 'active'",
+            AchievementCriteria::LikesReceived =>
+                "SELECT (SELECT COALESCE(SUM(like_count), 0) FROM packages WHERE owner_id = ?1 AND status = 'active')
+                      + (SELECT COALESCE(SUM(like_count), 0) FROM posts WHERE author_id = ?1 AND status = 'Published')",
+            AchievementCriteria::FeaturedPosts =>
+                "SELECT COUNT(*) FROM posts WHERE author_id = ?1 AND is_featured = 1 AND status = 'Published'",
+            AchievementCriteria::PostsPublished =>
+                "SELECT COUNT(*) FROM posts WHERE author_id = ?1 AND status = 'Published' AND review_status = 'approved'",
+            AchievementCriteria::CommentsPosted =>
+                "SELECT COUNT(*) FROM comments WHERE user_id = ?1 AND status = 'Active'",
+            AchievementCriteria::CheckInStreak => return Ok(0),
+        };
+        let value: i64 = conn.query_row(sql, params![user_id], |row| row.get(0))?;
+        Ok(value)
+    }
+}
@@ -0,0 +1,371 @@
+use anyhow::Result;
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::models::collection::{CollectionOwner, FavoriteCollection, FavoriteItem, FavoriteTarget};
+use crate::utils::time::parse_db_time;
+
+/// ?1 为当前查看者（未登录时传 0），用于计算 is_following
+const COLLECTION_SELECT: &str = "SELECT c.id, c.user_id, c.name, c.description, c.is_public, c.sort_order,
+        (SELECT COUNT(*) FROM favorite_collection_items i WHERE i.collection_id = c.id),
+        (SELECT COUNT(*) FROM favorite_collection_follows f WHERE f.collection_id = c.id),
+        EXISTS(SELECT 1 FROM favorite_collection_follows f WHERE f.collection_id = c.id AND f.user_id = ?1),
+        c.created_at, c.updated_at, u.username, u.nickname, u.avatar_url
+    FROM favorite_collections c LEFT JOIN users u ON u.id = c.user_id";
+
+/// 用户的全部收藏（资源与帖子），附带所属收藏夹与内容摘要
+const FAVORITE_SELECT: &str = "SELECT f.target_type, f.target_id, COALESCE(k.name, p.title) AS title,
+        COALESCE(k.author, pu.username) AS author, k.cover_image,
+        CASE WHEN k.status = 'active' OR p.status = 'Published' THEN 1 ELSE 0 END AS available,
+        ci.collection_id, f.created_at, ci.sort_order AS item_order
+    FROM (SELECT user_id, 'Package' AS target_type, package_id AS target_id, created_at FROM package_favorites
+          UNION ALL
+          SELECT user_id, 'Post', post_id, created_at FROM post_favorites) f
+    LEFT JOIN favorite_collection_items ci
+        ON ci.user_id = f.user_id AND ci.target_type = f.target_type AND ci.target_id = f.target_id
+    LEFT JOIN packages k ON f.target_type = 'Package' AND k.id = f.target_id
+    LEFT JOIN posts p ON f.target_type = 'Post' AND p.id = f.target_id
+    LEFT JOIN users pu ON pu.id = p.author_id";
+
+/// 收藏夹及其条目归属
+#[derive(Clone)]
+pub struct CollectionRepository {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl CollectionRepository {
+    pub fn new(db_path: &str) -> Result<Self> {
+        let conn = Connection::open(db_path)?;
+        Ok(Self { conn: Arc::new(Mutex::new(conn)) })
+    }
+
+    fn map_collection(row: &Row) -> rusqlite::Result<FavoriteCollection> {
+        let user_id: i32 = row.get(1)?;
+        let created_at: String = row.get(9)?;
+        let updated_at: String = row.get(10)?;
+        let username: Option<String> = row.get(11)?;
+        Ok(FavoriteCollection {
+            id: row.get(0)?,
+            user_id,
+            name: row.get(2)?,
+            description: row.get(3)?,
+            is_public: row.get::<_, i32>(4)? != 0,
+            sort_order: row.get(5)?,
+            item_count: row.get(6)?,
+            follower_count: row.get(7)?,
+            is_following: row.get::<_, i32>(8)? != 0,
+            owner: username.map(|username| CollectionOwner {
+                id: user_id,
+                username,
+                nickname: row.get(12).ok().flatten(),
+                avatar: row.get(13).ok().flatten(),
+            }),
+            created_at: parse_db_time(&created_at).unwrap_or_else(Utc::now),
+            updated_at: parse_db_time(&updated_at).unwrap_or_else(Utc::now),
+        })
+    }
+
+    fn map_item(row: &Row) -> rusqlite::Result<FavoriteItem> {
+        let favorited_at: String = row.get(7)?;
+        Ok(FavoriteItem {
+            target_type: row.get(0)?,
+            target_id: row.get(1)?,
+            title: row.get(2)?,
+            author: row.get(3)?,
+            cover_image: row.get(4)?,
+            available: row.get::<_, i32>(5)? != 0,
+            collection_id: row.get(6)?,
+            favorited_at: parse_db_time(&favorited_at).unwrap_or_else(Utc::now),
+        })
+    }
+
+    // ========== 收藏夹 ==========
+
+    pub async fn count_by_user(&self, user_id: i32) -> Result<i64> {
+        let conn = self.conn.lock().await;
+        let n = conn.query_row(
+            "SELECT COUNT(*) FROM favorite_collections WHERE user_id = ?",
+            params![user_id],
+            |row| row.get(0),
+        )?;
+        Ok(n)
+    }
+
+    pub async fn name_exists(&self, user_id: i32, name: &str, except_id: Option<i32>) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let exists = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM favorite_collections WHERE user_id = ? AND name = ? AND id IS NOT ?)",
+            params![user_id, name, except_id],
+            |row| row.get(0),
+        )?;
+        Ok(exists)
+    }
+
+    /// 新建的收藏夹排在最后
+    pub async fn create(&self, user_id: i32, name: &str, description: Option<&str>, is_public: bool) -> Result<i32> {
+        let conn = self.conn.lock().await;
+        let id = conn.query_row(
+            "INSERT INTO favorite_collections (user_id, name, description, is_public, sort_order)
+             VALUES (?1, ?2, ?3, ?4, (SELECT COALESCE(MAX(sort_order), 0) + 1 FROM favorite_collections WHERE user_id = ?1))
+             RETURNING id",
+            params![user_id, name, description, is_public as i32],
+            |row| row.get(0),
+        )?;
+        Ok(id)
+    }
+
+    pub async fn find(&self, id: i32, viewer_id: Option<i32>) -> Result<Option<FavoriteCollection>> {
+        let conn = self.conn.lock().await;
+        let collection = conn
+            .query_row(
+                &format!("{} WHERE c.id = ?2", COLLECTION_SELECT),
+                params![viewer_id.unwrap_or(0), id],
+                Self::map_collection,
+            )
+            .optional()?;
+        Ok(collection)
+    }
+
+    /// 某用户的收藏夹；public_only 为 true 时仅返回公开的
+    pub async fn list_by_user(&self, owner_id: i32, viewer_id: Option<i32>, public_only: bool) -> Result<Vec<FavoriteCollection>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(&format!(
+            "{} WHERE c.user_id = ?2 AND (?3 = 0 OR c.is_public = 1) ORDER BY c.sort_order, c.id",
+            COLLECTION_SELECT
+        ))?;
+        let list = stmt
+            .query_map(params![viewer_id.unwrap_or(0), owner_id, public_only as i32], Self::map_collection)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(list)
+    }
+
+    pub async fn update(&self, id: i32, name: &str, description: Option<&str>, is_public: bool) -> Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "UPDATE favorite_collections SET name = ?, description = ?, is_public = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+            params![name, description, is_public as i32, id],
+        )?;
+        Ok(())
+    }
+
+    /// 删除收藏夹：其中的收藏回到默认收藏，关注关系一并删除
+    pub async fn delete(&self, id: i32) -> Result<()> {
+        let mut conn = self.conn.lock().await;
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM favorite_collection_items WHERE collection_id = ?", params![id])?;
+        tx.execute("DELETE FROM favorite_collection_follows WHERE collection_id = ?", params![id])?;
+        tx.execute("DELETE FROM favorite_collections WHERE id = ?", params![id])?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 按给定顺序重排自己的收藏夹，未列出的保持原有相对顺序排在后面
+    pub async fn reorder(&self, user_id: i32, ids: &[i32]) -> Result<()> {
+        let mut conn = self.conn.lock().await;
+        let tx = conn.transaction()?;
+        tx.execute(
+            "UPDATE favorite_collections SET sort_order = sort_order + ? WHERE user_id = ?",
+            params![ids.len() as i32, user_id],
+        )?;
+        for (index, id) in ids.iter().enumerate() {
+            tx.execute(
+                "UPDATE favorite_collections SET sort_order = ? WHERE id = ? AND user_id = ?",
+                params![index as i32, id, user_id],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    // ========== 收藏条目 ==========
+
+    pub async fn target_exists(&self, target: &FavoriteTarget) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let sql = match target.target_type.as_str() {
+            "Package" => "SELECT EXISTS(SELECT 1 FROM packages WHERE id = ?)",
+            _ => "SELECT EXISTS(SELECT 1 FROM posts WHERE id = ?)",
+        };
+        let exists = conn.query_row(sql, params![target.target_id], |row| row.get(0))?;
+        Ok(exists)
+    }
+
+    /// 收藏（已收藏时不变），资源同时刷新收藏数
+    pub async fn add_favorite(&self, user_id: i32, target: &FavoriteTarget) -> Result<()> {
+        let conn = self.conn.lock().await;
+        match target.target_type.as_str() {
+            "Package" => {
+                conn.execute(
+                    "INSERT OR IGNORE INTO package_favorites (user_id, package_id) VALUES (?, ?)",
+                    params![user_id, target.target_id],
+                )?;
+                conn.execute(
+                    "UPDATE packages SET favorite_count = (SELECT COUNT(*) FROM package_favorites WHERE package_id = ?1) WHERE id = ?1",
+                    params![target.target_id],
+                )?;
+            }
+            _ => {
+                conn.execute(
+                    "INSERT OR IGNORE INTO post_favorites (user_id, post_id) VALUES (?, ?)",
+                    params![user_id, target.target_id],
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 将已收藏的条目移入收藏夹（None 为移回默认收藏），返回实际移动的数量
+    pub async fn move_items(&self, user_id: i32, items: &[FavoriteTarget], collection_id: Option<i32>) -> Result<usize> {
+        let mut conn = self.conn.lock().await;
+        let tx = conn.transaction()?;
+        let mut moved = 0;
+        // 逐个插到最前，倒序处理以保持请求中的顺序
+        for item in items.iter().rev() {
+            moved += match collection_id {
+                // 移入的条目排在收藏夹最前
+                Some(cid) => tx.execute(
+                    "INSERT OR REPLACE INTO favorite_collection_items (user_id, target_type, target_id, collection_id, sort_order)
+                     SELECT ?1, ?2, ?3, ?4, (SELECT COALESCE(MIN(sort_order), 0) - 1 FROM favorite_collection_items WHERE collection_id = ?4)
+                     WHERE EXISTS(
+                         SELECT 1 FROM package_favorites WHERE ?2 = 'Package' AND user_id = ?1 AND package_id = ?3
+                         UNION ALL
+                         SELECT 1 FROM post_favorites WHERE ?2 = 'Post' AND user_id = ?1 AND post_id = ?3
+                     )",
+                    params![user_id, item.target_type, item.target_id, cid],
+                )?,
+                None => tx.execute(
+                    "DELETE FROM favorite_collection_items WHERE user_id = ? AND target_type = ? AND target_id = ?",
+                    params![user_id, item.target_type, item.target_id],
+                )?,
+            };
+        }
+        if let Some(cid) = collection_id {
+            tx.execute(
+                "UPDATE favorite_collections SET updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+                params![cid],
+            )?;
+        }
+        tx.commit()?;
+        Ok(moved)
+    }
+
+    /// 从收藏夹移出（仍保留收藏）
+    pub async fn remove_item(&self, collection_id: i32, user_id: i32, target: &FavoriteTarget) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let n = conn.execute(
+            "DELETE FROM favorite_collection_items WHERE collection_id = ? AND user_id = ? AND target_type = ? AND target_id = ?",
+            params![collection_id, user_id, target.target_type, target.target_id],
+        )?;
+        Ok(n > 0)
+    }
+
+    /// 按给定顺序重排收藏夹内的条目，未列出的排在后面
+    pub async fn reorder_items(&self, collection_id: i32, items: &[FavoriteTarget]) -> Result<()> {
+        let mut conn = self.conn.lock().await;
+        let tx = conn.transaction()?;
+        tx.execute(
+            "UPDATE favorite_collection_items
+             SET sort_order = sort_order - (SELECT MIN(sort_order) FROM favorite_collection_items WHERE collection_id = ?2) + ?1
+             WHERE collection_id = ?2",
+            params![items.len() as i32, collection_id],
+        )?;
+        for (index, item) in items.iter().enumerate() {
+            tx.execute(
+                "UPDATE favorite_collection_items SET sort_order = ? WHERE collection_id = ? AND target_type = ? AND target_id = ?",
+                params![index as i32, collection_id, item.target_type, item.target_id],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 收藏夹内的条目；only_available 为 true 时跳过已删除或下架的内容（他人查看时）
+    pub async fn list_items(&self, collection_id: i32, only_available: bool, page: i64, page_size: i64) -> Result<(Vec<FavoriteItem>, i64)> {
+        let conn = self.conn.lock().await;
+        let filtered = format!(
+            "SELECT * FROM ({} WHERE ci.collection_id = ?1) WHERE (?2 = 0 OR available = 1)",
+            FAVORITE_SELECT
+        );
+        let total: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM ({})", filtered),
+            params![collection_id, only_available as i32],
+            |row| row.get(0),
+        )?;
+        let mut stmt = conn.prepare(&format!(
+            "{} ORDER BY item_order, created_at DESC LIMIT ?3 OFFSET ?4",
+            filtered
+        ))?;
+        let list = stmt
+            .query_map(
+                params![collection_id, only_available as i32, page_size, (page - 1) * page_size],
+                Self::map_item,
+            )?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok((list, total))
+    }
+
+    /// 我的全部收藏，可按类型筛选或只看未归入收藏夹的
+    pub async fn list_favorites(
+        &self,
+        user_id: i32,
+        target_type: Option<&str>,
+        uncategorized: bool,
+        page: i64,
+        page_size: i64,
+    ) -> Result<(Vec<FavoriteItem>, i64)> {
+        let conn = self.conn.lock().await;
+        let condition = "WHERE f.user_id = ?1 AND (?2 IS NULL OR f.target_type = ?2) AND (?3 = 0 OR ci.collection_id IS NULL)";
+        let total: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM ({} {})", FAVORITE_SELECT, condition),
+            params![user_id, target_type, uncategorized as i32],
+            |row| row.get(0),
+        )?;
+        let mut stmt = conn.prepare(&format!(
+            "{} {} ORDER BY f.created_at DESC, f.target_id DESC LIMIT ?4 OFFSET ?5",
+            FAVORITE_SELECT, condition
+        ))?;
+        let list = stmt
+            .query_map(
+                params![user_id, target_type, uncategorized as i32, page_size, (page - 1) * page_size],
+                Self::map_item,
+            )?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok((list, total))
+    }
+
+    // ========== 关注收藏夹 ==========
+
+    pub async fn follow(&self, user_id: i32, collection_id: i32) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let n = conn.execute(
+            "INSERT OR IGNORE INTO favorite_collection_follows (user_id, collection_id) VALUES (?, ?)",
+            params![user_id, collection_id],
+        )?;
+        Ok(n > 0)
+    }
+
+    pub async fn unfollow(&self, user_id: i32, collection_id: i32) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let n = conn.execute(
+            "DELETE FROM favorite_collection_follows WHERE user_id = ? AND collection_id = ?",
+            params![user_id, collection_id],
+        )?;
+        Ok(n > 0)
+    }
+
+    /// 我关注的收藏夹；已转为私密的不再返回
+    pub async fn list_followed(&self, user_id: i32) -> Result<Vec<FavoriteCollection>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(&format!(
+            "{} JOIN favorite_collection_follows cf ON cf.collection_id = c.id
+             WHERE cf.user_id = ?1 AND c.is_public = 1
+             ORDER BY c.updated_at DESC, c.id DESC",
+            COLLECTION_SELECT
+        ))?;
+        let list = stmt
+            .query_map(params![user_id], Self::map_collection)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(list)
+    }
+}
@@ -1,6 +1,9 @@
 use anyhow::Result;
 use rusqlite::{Connection, params};
 use crate::models::Comment;
+use crate::models::edit_revision::{comment_snapshot, PendingRevision};
+use crate::repositories::edit_revision_repo::EditRevisionRepository;
+use crate::repositories::user_relation_repo::HIDDEN_FROM_VIEWER;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use chrono::Utc;
@@ -155,39 +158,48 @@ impl CommentRepository {
 
     // 获取评论（兼容旧方法，使用target_id替代package_id）
     pub async fn get_comments_by_package(&self, package_id: i32) -> Result<Vec<Comment>> {
-        self.get_comments_by_target("Package", package_id, 1, 100).await.map(|(comments, _)| comments)
+        self.get_comments_by_target("Package", package_id, 1, 100, None).await.map(|(comments, _)| comments)
     }
 
-    // 获取特定目标的评论
+    // 获取特定目标的评论（viewer_id 为查看者，其拉黑、屏蔽的用户及拉黑了查看者的用户的评论不计入）
     pub async fn get_comments_by_target(
         &self,
         target_type: &str,
         target_id: i32,
         page: i32,
-        size: i32
+        size: i32,
+        viewer_id: Option<i32>,
     ) -> Result<(Vec<Comment>, i64)> {
         let conn = self.conn.lock().await;
         
         // 计算总记录数
-        let count_sql = "SELECT COUNT(*) FROM comments WHERE UPPER(target_type) = UPPER(?) AND target_id = ? AND status != 'Deleted'";
+        let count_sql = format!(
+            "SELECT COUNT(*) FROM comments WHERE UPPER(target_type) = UPPER(?2) AND target_id = ?3 AND status != 'Deleted' \
+             AND user_id NOT IN ({})",
+            HIDDEN_FROM_VIEWER
+        );
         let total: i64 = conn.query_row(
-            count_sql,
-            params![target_type, target_id],
+            &count_sql,
+            params![viewer_id, target_type, target_id],
             |row| row.get(0)
         )?;
         
         // 获取评论列表
-        let sql = "SELECT c.id, c.user_id, c.target_type, c.target_id, c.content, c.status, c.parent_id, 
-                          c.likes, c.dislikes, c.pinned, c.created_at, c.updated_at, COALESCE(u.nickname, u.username) as author_name, u.username, u.role, u.avatar_url, u.qq_number 
-                   FROM comments c 
-                   LEFT JOIN users u ON c.user_id = u.id 
-                   WHERE UPPER(c.target_type) = UPPER(?) AND c.target_id = ? AND c.status != 'Deleted' 
-                   ORDER BY c.pinned DESC, c.created_at DESC 
-                   LIMIT ? OFFSET ?";
+        let sql = format!(
+            "SELECT c.id, c.user_id, c.target_type, c.target_id, c.content, c.status, c.parent_id, 
+                    c.likes, c.dislikes, c.pinned, c.created_at, c.updated_at, COALESCE(u.nickname, u.username) as author_name, u.username, u.role, u.avatar_url, u.qq_number 
+             FROM comments c 
+             LEFT JOIN users u ON c.user_id = u.id 
+             WHERE UPPER(c.target_type) = UPPER(?2) AND c.target_id = ?3 AND c.status != 'Deleted' 
+               AND c.user_id NOT IN ({})
+             ORDER BY c.pinned DESC, c.created_at DESC 
+             LIMIT ?4 OFFSET ?5",
+            HIDDEN_FROM_VIEWER
+        );
         
-        let mut stmt = conn.prepare(sql)?;
+        let mut stmt = conn.prepare(&sql)?;
         let comment_iter = stmt.query_map(
-            params![target_type, target_id, size, (page - 1) * size],
+            params![viewer_id, target_type, target_id, size, (page - 1) * size],
             |row| {
                 Ok(Comment {
                     id: row.get(0)?,
@@ -227,25 +239,34 @@ impl CommentRepository {
         target_id: i32,
         page: i32,
         size: i32,
+        viewer_id: Option<i32>,
     ) -> Result<(Vec<Comment>, i64)> {
         let conn = self.conn.lock().await;
 
-        // 计算总记录数（仅顶层，Active）
-        let count_sql = "SELECT COUNT(*) FROM comments WHERE UPPER(target_type) = UPPER(?) AND target_id = ? AND status = 'Active' AND parent_id IS NULL";
-        let total: i64 = conn.query_row(count_sql, params![target_type, target_id], |row| row.get(0))?;
+        // 计算总记录数（仅顶层，Active，排除对查看者隐藏的用户）
+        let count_sql = format!(
+            "SELECT COUNT(*) FROM comments WHERE UPPER(target_type) = UPPER(?2) AND target_id = ?3 AND status = 'Active' AND parent_id IS NULL \
+             AND user_id NOT IN ({})",
+            HIDDEN_FROM_VIEWER
+        );
+        let total: i64 = conn.query_row(&count_sql, params![viewer_id, target_type, target_id], |row| row.get(0))?;
 
         // 查询列表（仅顶层，Active）
-        let sql = "SELECT c.id, c.user_id, c.target_type, c.target_id, c.content, c.status, c.parent_id, \
-                          c.likes, c.dislikes, c.pinned, c.created_at, c.updated_at, COALESCE(u.nickname, u.username) as author_name, u.username, u.role, u.avatar_url, u.qq_number \
-                   FROM comments c \
-                   LEFT JOIN users u ON c.user_id = u.id \
-                   WHERE UPPER(c.target_type) = UPPER(?) AND c.target_id = ? AND c.status = 'Active' AND c.parent_id IS NULL \
-                   ORDER BY c.pinned DESC, c.created_at DESC \
-                   LIMIT ? OFFSET ?";
+        let sql = format!(
+            "SELECT c.id, c.user_id, c.target_type, c.target_id, c.content, c.status, c.parent_id, \
+                    c.likes, c.dislikes, c.pinned, c.created_at, c.updated_at, COALESCE(u.nickname, u.username) as author_name, u.username, u.role, u.avatar_url, u.qq_number \
+             FROM comments c \
+             LEFT JOIN users u ON c.user_id = u.id \
+             WHERE UPPER(c.target_type) = UPPER(?2) AND c.target_id = ?3 AND c.status = 'Active' AND c.parent_id IS NULL \
+               AND c.user_id NOT IN ({}) \
+             ORDER BY c.pinned DESC, c.created_at DESC \
+             LIMIT ?4 OFFSET ?5",
+            HIDDEN_FROM_VIEWER
+        );
 
-        let mut stmt = conn.prepare(sql)?;
+        let mut stmt = conn.prepare(&sql)?;
         let comment_iter = stmt.query_map(
-            params![target_type, target_id, size, (page - 1) * size],
+            params![viewer_id, target_type, target_id, size, (page - 1) * size],
             |row| {
                 Ok(Comment {
                     id: row.get(0)?,
@@ -345,8 +366,14 @@ impl CommentRepository {
 
     // 更新评论
     pub async fn update_comment(&self, comment: &Comment) -> Result<()> {
-        let conn = self.conn.lock().await;
-        conn.execute(
+        self.update_comment_recorded(comment, None).await.map(|_| ())
+    }
+
+    /// 更新评论；传入编辑记录时，编辑历史与更新在同一事务中写入，返回新版本号
+    pub async fn update_comment_recorded(&self, comment: &Comment, revision: Option<&PendingRevision>) -> Result<Option<i32>> {
+        let mut conn = self.conn.lock().await;
+        let tx = conn.transaction()?;
+        tx.execute(
             "UPDATE comments 
              SET content = ?, status = ?, likes = ?, dislikes = ?, pinned = ?, updated_at = ? 
              WHERE id = ?",
@@ -360,13 +387,45 @@ impl CommentRepository {
                 comment.id,
             ]
         )?;
-        
-        Ok(())
+        let revision_no = match revision {
+            Some(revision) => EditRevisionRepository::record_in(&tx, "Comment", comment.id, revision, &comment_snapshot(&comment.content))?,
+            None => None,
+        };
+        tx.commit()?;
+        Ok(revision_no)
     }
 
-    // 删除评论（物理删除）
-    pub async fn delete_comment(&self, comment_id: i32) -> Result<()> {
+    // 删除评论：评论及其直接回复一起移入回收站，保留原状态以便恢复
+    pub async fn soft_delete_comment(&self, comment_id: i32, deleted_by: i32) -> Result<bool> {
+        let mut conn = self.conn.lock().await;
+        let tx = conn.transaction()?;
+        let affected = tx.execute(
+            "UPDATE comments SET status_before_delete = status, status = 'Deleted', deleted_at = datetime('now'), deleted_by = ?
+             WHERE id = ? AND status != 'Deleted'",
+            params![deleted_by, comment_id],
+        )?;
+        if affected > 0 {
+            // 回复与父评论使用相同的删除时间，恢复父评论时一并恢复
+            tx.execute(
+                "UPDATE comments SET status_before_delete = status, status = 'Deleted', deleted_by = ?2,
+                        deleted_at = (SELECT deleted_at FROM comments WHERE id = ?1)
+                 WHERE parent_id = ?1 AND status != 'Deleted'",
+                params![comment_id, deleted_by],
+            )?;
+        }
+        tx.commit()?;
+        Ok(affected > 0)
+    }
+
+    // 彻底删除评论（回收站清理）
+    pub async fn purge_comment(&self, comment_id: i32) -> Result<()> {
         let conn = self.conn.lock().await;
+        // 编辑历史随评论及其直接回复一起删除
+        conn.execute(
+            "DELETE FROM content_revisions WHERE target_type = 'Comment'
+             AND (target_id = ?1 OR target_id IN (SELECT id FROM comments WHERE parent_id = ?1))",
+            params![comment_id],
+        )?;
         // 先删除该评论的直接回复
         conn.execute("DELETE FROM comments WHERE parent_id = ?", params![comment_id])?;
         // 再删除该评论
@@ -375,17 +434,21 @@ impl CommentRepository {
     }
 
     // 获取评论回复
-    pub async fn get_comment_replies(&self, comment_id: i32) -> Result<Vec<Comment>> {
+    pub async fn get_comment_replies(&self, comment_id: i32, viewer_id: Option<i32>) -> Result<Vec<Comment>> {
         let conn = self.conn.lock().await;
-        let sql = "SELECT c.id, c.user_id, c.target_type, c.target_id, c.content, c.status, c.parent_id, 
-                          c.likes, c.dislikes, c.pinned, c.created_at, c.updated_at, COALESCE(u.nickname, u.username) as author_name, u.username, u.role, u.avatar_url, u.qq_number 
-                   FROM comments c 
-                   LEFT JOIN users u ON c.user_id = u.id 
-                   WHERE c.parent_id = ? AND c.status != 'Deleted' 
-                   ORDER BY c.created_at ASC";
+        let sql = format!(
+            "SELECT c.id, c.user_id, c.target_type, c.target_id, c.content, c.status, c.parent_id, 
+                    c.likes, c.dislikes, c.pinned, c.created_at, c.updated_at, COALESCE(u.nickname, u.username) as author_name, u.username, u.role, u.avatar_url, u.qq_number 
+             FROM comments c 
+             LEFT JOIN users u ON c.user_id = u.id 
+             WHERE c.parent_id = ?2 AND c.status != 'Deleted' 
+               AND c.user_id NOT IN ({})
+             ORDER BY c.created_at ASC",
+            HIDDEN_FROM_VIEWER
+        );
         
-        let mut stmt = conn.prepare(sql)?;
-        let comment_iter = stmt.query_map(params![comment_id], |row| {
+        let mut stmt = conn.prepare(&sql)?;
+        let comment_iter = stmt.query_map(params![viewer_id, comment_id], |row| {
             Ok(Comment {
                 id: row.get(0)?,
                 user_id: row.get(1)?,
@@ -5,10 +5,13 @@ use tokio::sync::Mutex;
 use chrono::{DateTime, Utc, Duration};
 use serde_json;
 use crate::models::download_security::*;
+use crate::repositories::ensure_column;
 
 #[derive(Clone)]
 pub struct DownloadSecurityRepository {
     conn: Arc<Mutex<Connection>>,
+    // download_records 是否有 version 列（迁移 025 添加）
+    has_version: bool,
 }
 
 impl DownloadSecurityRepository {
@@ -71,17 +74,26 @@ impl DownloadSecurityRepository {
             "#
         )?;
         
+        let has_version = ensure_column(&conn, "download_records", "version", "TEXT");
+
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
+            has_version,
         })
     }
 
-    // 记录下载行为
+    // 记录下载行为（同时记下当时的资源版本）
     pub async fn record_download(&self, record: &DownloadRecord) -> Result<()> {
         let conn = self.conn.lock().await;
-        conn.execute(
+        let sql = if self.has_version {
+            "INSERT INTO download_records (user_id, package_id, ip_address, user_agent, download_time, created_at, version) 
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, (SELECT version FROM packages WHERE id = ?2))"
+        } else {
             "INSERT INTO download_records (user_id, package_id, ip_address, user_agent, download_time, created_at) 
-             VALUES (?, ?, ?, ?, ?, ?)",
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)"
+        };
+        conn.execute(
+            sql,
             params![
                 record.user_id,
                 record.package_id,
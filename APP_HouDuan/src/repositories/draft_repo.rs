@@ -0,0 +1,292 @@
+use anyhow::Result;
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::models::draft::{
+    Draft, DraftRevision, DRAFT_STATUS_DRAFT, DRAFT_STATUS_FAILED, DRAFT_STATUS_PUBLISHING, DRAFT_STATUS_SCHEDULED,
+    MAX_REVISIONS_PER_DRAFT, REVISION_INTERVAL_SECS,
+};
+use crate::utils::time::parse_db_time;
+
+const DRAFT_SELECT: &str = "SELECT id, user_id, target_type, title, payload, status, publish_at, last_error, created_at, updated_at
+    FROM content_drafts";
+
+/// 帖子与资源草稿、自动保存历史版本与定时发布队列
+#[derive(Clone)]
+pub struct DraftRepository {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl DraftRepository {
+    pub fn new(db_path: &str) -> Result<Self> {
+        let conn = Connection::open(db_path)?;
+        Ok(Self { conn: Arc::new(Mutex::new(conn)) })
+    }
+
+    pub async fn count_by_user(&self, user_id: i32) -> Result<i64> {
+        let conn = self.conn.lock().await;
+        Ok(conn.query_row(
+            "SELECT COUNT(*) FROM content_drafts WHERE user_id = ?",
+            params![user_id],
+            |row| row.get(0),
+        )?)
+    }
+
+    /// 新建草稿并记录第一个历史版本
+    pub async fn create(
+        &self,
+        user_id: i32,
+        target_type: &str,
+        title: &str,
+        payload: &serde_json::Value,
+        publish_at: Option<DateTime<Utc>>,
+    ) -> Result<i32> {
+        let now = to_db_time(Utc::now());
+        let payload = payload.to_string();
+        let status = if publish_at.is_some() { DRAFT_STATUS_SCHEDULED } else { DRAFT_STATUS_DRAFT };
+        let mut conn = self.conn.lock().await;
+        let tx = conn.transaction()?;
+        let id: i32 = tx.query_row(
+            "INSERT INTO content_drafts (user_id, target_type, title, payload, status, publish_at, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7) RETURNING id",
+            params![user_id, target_type, title, payload, status, publish_at.map(to_db_time), now],
+            |row| row.get(0),
+        )?;
+        tx.execute(
+            "INSERT INTO content_draft_revisions (draft_id, title, payload, created_at) VALUES (?, ?, ?, ?)",
+            params![id, title, payload, now],
+        )?;
+        tx.commit()?;
+        Ok(id)
+    }
+
+    pub async fn find(&self, draft_id: i32) -> Result<Option<Draft>> {
+        let conn = self.conn.lock().await;
+        let draft = conn
+            .query_row(&format!("{} WHERE id = ?", DRAFT_SELECT), params![draft_id], map_draft)
+            .optional()?;
+        Ok(draft)
+    }
+
+    pub async fn list_by_user(&self, user_id: i32, target_type: Option<&str>) -> Result<Vec<Draft>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(&format!(
+            "{} WHERE user_id = ?1 AND (?2 IS NULL OR target_type = ?2) ORDER BY updated_at DESC",
+            DRAFT_SELECT
+        ))?;
+        let rows = stmt.query_map(params![user_id, target_type], map_draft)?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// 自动保存：替换内容；距上一个历史版本超过间隔时记录新版本并清理过旧的版本。
+    /// 发布失败的草稿被编辑后回到普通草稿；发布中的草稿不可修改，返回 false
+    pub async fn save(&self, draft_id: i32, title: &str, payload: &serde_json::Value) -> Result<bool> {
+        let now = Utc::now();
+        let payload = payload.to_string();
+        let mut conn = self.conn.lock().await;
+        let tx = conn.transaction()?;
+        let n = tx.execute(
+            "UPDATE content_drafts SET title = ?1, payload = ?2, updated_at = ?3,
+                 status = CASE status WHEN ?4 THEN ?5 ELSE status END,
+                 publish_at = CASE status WHEN ?4 THEN NULL ELSE publish_at END,
+                 last_error = NULL
+             WHERE id = ?6 AND status != ?7",
+            params![
+                title,
+                payload,
+                to_db_time(now),
+                DRAFT_STATUS_FAILED,
+                DRAFT_STATUS_DRAFT,
+                draft_id,
+                DRAFT_STATUS_PUBLISHING
+            ],
+        )?;
+        if n == 0 {
+            return Ok(false);
+        }
+        let last: Option<String> = tx
+            .query_row(
+                "SELECT created_at FROM content_draft_revisions WHERE draft_id = ? ORDER BY id DESC LIMIT 1",
+                params![draft_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let due = match last.as_deref().and_then(parse_db_time) {
+            Some(t) => (now - t).num_seconds() >= REVISION_INTERVAL_SECS,
+            None => true,
+        };
+        if due {
+            tx.execute(
+                "INSERT INTO content_draft_revisions (draft_id, title, payload, created_at) VALUES (?, ?, ?, ?)",
+                params![draft_id, title, payload, to_db_time(now)],
+            )?;
+            tx.execute(
+                "DELETE FROM content_draft_revisions WHERE draft_id = ?1 AND id NOT IN (
+                     SELECT id FROM content_draft_revisions WHERE draft_id = ?1 ORDER BY id DESC LIMIT ?2)",
+                params![draft_id, MAX_REVISIONS_PER_DRAFT],
+            )?;
+        }
+        tx.commit()?;
+        Ok(true)
+    }
+
+    /// 设置或取消定时发布；发布中的草稿不可修改，返回 false
+    pub async fn set_schedule(&self, draft_id: i32, publish_at: Option<DateTime<Utc>>) -> Result<bool> {
+        let status = if publish_at.is_some() { DRAFT_STATUS_SCHEDULED } else { DRAFT_STATUS_DRAFT };
+        let conn = self.conn.lock().await;
+        let n = conn.execute(
+            "UPDATE content_drafts SET status = ?1, publish_at = ?2, last_error = NULL, updated_at = ?3
+             WHERE id = ?4 AND status != ?5",
+            params![status, publish_at.map(to_db_time), to_db_time(Utc::now()), draft_id, DRAFT_STATUS_PUBLISHING],
+        )?;
+        Ok(n > 0)
+    }
+
+    pub async fn list_revisions(&self, draft_id: i32) -> Result<Vec<DraftRevision>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(
+            "SELECT id, draft_id, title, payload, created_at FROM content_draft_revisions
+             WHERE draft_id = ? ORDER BY id DESC",
+        )?;
+        let rows = stmt.query_map(params![draft_id], map_revision)?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    pub async fn find_revision(&self, draft_id: i32, revision_id: i32) -> Result<Option<DraftRevision>> {
+        let conn = self.conn.lock().await;
+        let revision = conn
+            .query_row(
+                "SELECT id, draft_id, title, payload, created_at FROM content_draft_revisions WHERE id = ? AND draft_id = ?",
+                params![revision_id, draft_id],
+                map_revision,
+            )
+            .optional()?;
+        Ok(revision)
+    }
+
+    /// 删除草稿及其历史版本；发布中的草稿不可删除
+    pub async fn delete(&self, draft_id: i32) -> Result<bool> {
+        let mut conn = self.conn.lock().await;
+        let tx = conn.transaction()?;
+        let n = tx.execute(
+            "DELETE FROM content_drafts WHERE id = ? AND status != ?",
+            params![draft_id, DRAFT_STATUS_PUBLISHING],
+        )?;
+        if n > 0 {
+            tx.execute("DELETE FROM content_draft_revisions WHERE draft_id = ?", params![draft_id])?;
+        }
+        tx.commit()?;
+        Ok(n > 0)
+    }
+
+    /// 发布成功后移除草稿及其历史版本
+    pub async fn finish(&self, draft_id: i32) -> Result<()> {
+        let mut conn = self.conn.lock().await;
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM content_drafts WHERE id = ?", params![draft_id])?;
+        tx.execute("DELETE FROM content_draft_revisions WHERE draft_id = ?", params![draft_id])?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 手动发布前认领草稿，已在发布中的返回 false
+    pub async fn claim(&self, draft_id: i32) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let n = conn.execute(
+            "UPDATE content_drafts SET status = ? WHERE id = ? AND status != ?",
+            params![DRAFT_STATUS_PUBLISHING, draft_id, DRAFT_STATUS_PUBLISHING],
+        )?;
+        Ok(n > 0)
+    }
+
+    /// 认领所有到期的定时草稿，返回草稿ID
+    pub async fn claim_due(&self, now: DateTime<Utc>) -> Result<Vec<i32>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(
+            "UPDATE content_drafts SET status = ?1 WHERE status = ?2 AND publish_at <= ?3 RETURNING id",
+        )?;
+        let rows = stmt.query_map(params![DRAFT_STATUS_PUBLISHING, DRAFT_STATUS_SCHEDULED, to_db_time(now)], |row| row.get(0))?;
+        Ok(rows.collect::<Result<Vec<i32>, _>>()?)
+    }
+
+    /// 发布失败：手动发布恢复原状态，定时发布标记为失败并记录原因
+    pub async fn release(&self, draft_id: i32, status: &str, last_error: Option<&str>) -> Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "UPDATE content_drafts SET status = ?, last_error = ? WHERE id = ?",
+            params![status, last_error, draft_id],
+        )?;
+        Ok(())
+    }
+
+    /// 内容创建成功后立即记录其ID，之后即使服务中断也不会重复发布
+    pub async fn mark_published(&self, draft_id: i32, published_id: i32) -> Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "UPDATE content_drafts SET published_id = ? WHERE id = ?",
+            params![published_id, draft_id],
+        )?;
+        Ok(())
+    }
+
+    /// 服务重启后处理上次中断在发布中的草稿：已记录发布结果的视为发布完成并移除，
+    /// 其余无法确定是否已创建内容，标记为失败交由用户确认后重新发布。返回 (已完成, 标记失败) 数量
+    pub async fn recover_publishing(&self) -> Result<(usize, usize)> {
+        let mut conn = self.conn.lock().await;
+        let tx = conn.transaction()?;
+        tx.execute(
+            "DELETE FROM content_draft_revisions WHERE draft_id IN
+             (SELECT id FROM content_drafts WHERE status = ?1 AND published_id IS NOT NULL)",
+            params![DRAFT_STATUS_PUBLISHING],
+        )?;
+        let finished = tx.execute(
+            "DELETE FROM content_drafts WHERE status = ?1 AND published_id IS NOT NULL",
+            params![DRAFT_STATUS_PUBLISHING],
+        )?;
+        let failed = tx.execute(
+            "UPDATE content_drafts SET status = ?1, last_error = ?2 WHERE status = ?3",
+            params![DRAFT_STATUS_FAILED, RECOVER_ERROR, DRAFT_STATUS_PUBLISHING],
+        )?;
+        tx.commit()?;
+        Ok((finished, failed))
+    }
+}
+
+/// 发布过程中服务中断、无法确认是否已发布时记录的失败原因
+const RECOVER_ERROR: &str = "发布过程中服务中断，请确认内容未发布后重新发布";
+
+/// 统一使用精确到秒的 UTC 格式，保证 publish_at 可按字符串比较
+fn to_db_time(t: DateTime<Utc>) -> String {
+    t.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn parse_payload(s: &str) -> serde_json::Value {
+    serde_json::from_str(s).unwrap_or_else(|_| serde_json::json!({}))
+}
+
+fn map_draft(row: &rusqlite::Row) -> rusqlite::Result<Draft> {
+    Ok(Draft {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        target_type: row.get(2)?,
+        title: row.get(3)?,
+        payload: parse_payload(&row.get::<_, String>(4)?),
+        status: row.get(5)?,
+        publish_at: row.get::<_, Option<String>>(6)?.as_deref().and_then(parse_db_time),
+        last_error: row.get(7)?,
+        created_at: parse_db_time(&row.get::<_, String>(8)?).unwrap_or_else(Utc::now),
+        updated_at: parse_db_time(&row.get::<_, String>(9)?).unwrap_or_else(Utc::now),
+    })
+}
+
+fn map_revision(row: &rusqlite::Row) -> rusqlite::Result<DraftRevision> {
+    Ok(DraftRevision {
+        id: row.get(0)?,
+        draft_id: row.get(1)?,
+        title: row.get(2)?,
+        payload: parse_payload(&row.get::<_, String>(3)?),
+        created_at: parse_db_time(&row.get::<_, String>(4)?).unwrap_or_else(Utc::now),
+    })
+}
@@ -0,0 +1,173 @@
+use anyhow::Result;
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::models::edit_revision::{ContentRevision, EditMarker, PendingRevision};
+use crate::utils::time::parse_db_time;
+
+const REVISION_SELECT: &str = "SELECT r.id, r.target_type, r.target_id, r.revision_no, r.editor_id, u.username,
+        r.snapshot, r.changed_fields, r.note, r.created_at
+    FROM content_revisions r
+    LEFT JOIN users u ON u.id = r.editor_id";
+
+/// 帖子、资源与评论的编辑历史
+#[derive(Clone)]
+pub struct EditRevisionRepository {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl EditRevisionRepository {
+    pub fn new(db_path: &str) -> Result<Self> {
+        let conn = Connection::open(db_path)?;
+        Ok(Self { conn: Arc::new(Mutex::new(conn)) })
+    }
+
+    /// 受追踪字段中取值发生变化的字段名
+    pub fn changed_fields(before: &serde_json::Value, after: &serde_json::Value) -> Vec<String> {
+        let Some(after_map) = after.as_object() else {
+            return Vec::new();
+        };
+        after_map
+            .iter()
+            .filter(|(key, value)| before.get(key.as_str()) != Some(*value))
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+
+    /// 在内容更新所在的事务中记录一个版本；受追踪字段没有变化时不记录，返回新版本号。
+    /// 首次编辑时先补记原始版本
+    pub fn record_in(
+        conn: &Connection,
+        target_type: &str,
+        target_id: i32,
+        revision: &PendingRevision,
+        after: &serde_json::Value,
+    ) -> rusqlite::Result<Option<i32>> {
+        let changed = Self::changed_fields(&revision.before, after);
+        if changed.is_empty() {
+            return Ok(None);
+        }
+        let has_revisions = conn
+            .query_row(
+                "SELECT 1 FROM content_revisions WHERE target_type = ? AND target_id = ? LIMIT 1",
+                params![target_type, target_id],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some();
+        if !has_revisions {
+            Self::append_in(conn, target_type, target_id, None, &revision.before, &[], None)?;
+        }
+        let revision_no = Self::append_in(
+            conn,
+            target_type,
+            target_id,
+            Some(revision.editor_id),
+            after,
+            &changed,
+            revision.note.as_deref(),
+        )?;
+        Ok(Some(revision_no))
+    }
+
+    /// 追加一个版本，版本号为当前最大值 + 1
+    fn append_in(
+        conn: &Connection,
+        target_type: &str,
+        target_id: i32,
+        editor_id: Option<i32>,
+        snapshot: &serde_json::Value,
+        changed_fields: &[String],
+        note: Option<&str>,
+    ) -> rusqlite::Result<i32> {
+        let next: i32 = conn.query_row(
+            "SELECT COALESCE(MAX(revision_no), 0) + 1 FROM content_revisions WHERE target_type = ? AND target_id = ?",
+            params![target_type, target_id],
+            |row| row.get(0),
+        )?;
+        conn.execute(
+            "INSERT INTO content_revisions (target_type, target_id, revision_no, editor_id, snapshot, changed_fields, note, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                target_type,
+                target_id,
+                next,
+                editor_id,
+                snapshot.to_string(),
+                serde_json::Value::from(changed_fields.to_vec()).to_string(),
+                note,
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+        Ok(next)
+    }
+
+    /// 版本列表，最新的在前
+    pub async fn list(&self, target_type: &str, target_id: i32) -> Result<Vec<ContentRevision>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(&format!(
+            "{} WHERE r.target_type = ? AND r.target_id = ? ORDER BY r.revision_no DESC",
+            REVISION_SELECT
+        ))?;
+        let rows = stmt.query_map(params![target_type, target_id], map_revision)?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    pub async fn find(&self, target_type: &str, target_id: i32, revision_no: i32) -> Result<Option<ContentRevision>> {
+        let conn = self.conn.lock().await;
+        let revision = conn
+            .query_row(
+                &format!("{} WHERE r.target_type = ? AND r.target_id = ? AND r.revision_no = ?", REVISION_SELECT),
+                params![target_type, target_id, revision_no],
+                map_revision,
+            )
+            .optional()?;
+        Ok(revision)
+    }
+
+    /// 批量读取已编辑标记（不含原始版本），返回 内容ID -> 标记
+    pub async fn markers(&self, target_type: &str, target_ids: &[i32]) -> Result<HashMap<i32, EditMarker>> {
+        if target_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let placeholders = vec!["?"; target_ids.len()].join(",");
+        let sql = format!(
+            "SELECT target_id, COUNT(*), MAX(created_at) FROM content_revisions
+             WHERE target_type = ? AND editor_id IS NOT NULL AND target_id IN ({})
+             GROUP BY target_id",
+            placeholders
+        );
+        let mut values: Vec<rusqlite::types::Value> = vec![target_type.to_string().into()];
+        values.extend(target_ids.iter().map(|id| rusqlite::types::Value::from(*id)));
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(values), |row| {
+            Ok((
+                row.get::<_, i32>(0)?,
+                EditMarker {
+                    edit_count: row.get(1)?,
+                    last_edited_at: parse_db_time(&row.get::<_, String>(2)?).unwrap_or_else(Utc::now),
+                },
+            ))
+        })?;
+        Ok(rows.collect::<Result<HashMap<_, _>, _>>()?)
+    }
+}
+
+fn map_revision(row: &rusqlite::Row) -> rusqlite::Result<ContentRevision> {
+    Ok(ContentRevision {
+        id: row.get(0)?,
+        target_type: row.get(1)?,
+        target_id: row.get(2)?,
+        revision_no: row.get(3)?,
+        editor_id: row.get(4)?,
+        editor_name: row.get(5)?,
+        snapshot: serde_json::from_str(&row.get::<_, String>(6)?).unwrap_or_else(|_| serde_json::json!({})),
+        changed_fields: serde_json::from_str(&row.get::<_, String>(7)?).unwrap_or_default(),
+        note: row.get(8)?,
+        created_at: parse_db_time(&row.get::<_, String>(9)?).unwrap_or_else(Utc::now),
+    })
+}
@@ -5,6 +5,8 @@ use tokio::sync::Mutex;
 use chrono::{DateTime, Utc};
 use serde::{Serialize, Deserialize};
 
+use crate::utils::error::ServiceError;
+
 #[derive(Clone)]
 pub struct FollowRepository {
     conn: Arc<Mutex<Connection>>,
@@ -174,10 +176,20 @@ impl FollowRepository {
         Ok(())
     }
 
-    /// 关注用户
+    /// 关注用户（任一方拉黑了另一方时拒绝）
     pub async fn follow_user(&self, follower_id: i32, followed_id: i32) -> Result<bool> {
         let conn = self.conn.lock().await;
         
+        let blocked: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM user_blocks
+                WHERE (blocker_id = ?1 AND blocked_id = ?2) OR (blocker_id = ?2 AND blocked_id = ?1))",
+            params![follower_id, followed_id],
+            |row| row.get(0)
+        )?;
+        if blocked {
+            return Err(ServiceError::forbidden("无法关注：你与对方存在拉黑关系"));
+        }
+
         // 检查是否已经关注
         let already_following: bool = conn.query_row(
             "SELECT EXISTS(SELECT 1 FROM user_follows WHERE follower_id = ? AND followed_id = ?)",
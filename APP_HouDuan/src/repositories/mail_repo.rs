@@ -1,8 +1,10 @@
 use anyhow::Result;
-use rusqlite::{Connection, params};
+use rusqlite::{Connection, OptionalExtension, params};
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
-use crate::models::mail::{MailSettings, MailLog, MailTemplate, MailType, MailStatus, MailStats};
+use crate::models::mail::{MailSettings, MailLog, MailTemplate, MailType, MailStatus, MailStats, DEFAULT_MAIL_LANGUAGE};
+use crate::utils::template;
+use crate::utils::time::parse_db_time;
 
 #[derive(Clone)]
 pub struct MailRepository {
@@ -172,48 +174,119 @@ impl MailRepository {
     }
 
     // 邮件模板管理
-    pub async fn get_mail_template(&self, template_type: &str) -> Result<Option<MailTemplate>> {
+    const TEMPLATE_COLUMNS: &'static str =
+        "id, template_type, language, subject, content, variables, enabled, created_at, updated_at";
+
+    fn map_template(row: &rusqlite::Row) -> rusqlite::Result<MailTemplate> {
+        let created_at_str: Option<String> = row.get(7)?;
+        let updated_at_str: Option<String> = row.get(8)?;
+        Ok(MailTemplate {
+            id: Some(row.get(0)?),
+            template_type: row.get(1)?,
+            language: row.get(2)?,
+            subject: row.get(3)?,
+            content: row.get(4)?,
+            variables: row.get(5)?,
+            enabled: row.get::<_, i32>(6)? == 1,
+            created_at: created_at_str.as_deref().and_then(parse_db_time),
+            updated_at: updated_at_str.as_deref().and_then(parse_db_time),
+        })
+    }
+
+    /// 获取指定语言的启用模板，缺失时回退到默认语言
+    pub async fn get_mail_template(&self, template_type: &str, language: Option<&str>) -> Result<Option<MailTemplate>> {
         let conn = self.get_connection()?;
-        let mut stmt = conn.prepare(
-            "SELECT id, template_type, subject, content, variables, enabled, created_at, updated_at 
-             FROM mail_templates WHERE template_type = ?1 AND enabled = 1"
-        )?;
+        let language = language.unwrap_or(DEFAULT_MAIL_LANGUAGE);
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM mail_templates
+             WHERE template_type = ?1 AND enabled = 1 AND language IN (?2, ?3)
+             ORDER BY CASE WHEN language = ?2 THEN 0 ELSE 1 END
+             LIMIT 1",
+            Self::TEMPLATE_COLUMNS
+        ))?;
+        let template = stmt
+            .query_row(params![template_type, language, DEFAULT_MAIL_LANGUAGE], Self::map_template)
+            .optional()?;
+        Ok(template)
+    }
 
-        let mut rows = stmt.query_map(params![template_type], |row| {
-            let created_at_str: String = row.get(6)?;
-            let updated_at_str: String = row.get(7)?;
-            
-            Ok(MailTemplate {
-                id: Some(row.get(0)?),
-                template_type: row.get(1)?,
-                subject: row.get(2)?,
-                content: row.get(3)?,
-                variables: row.get(4)?,
-                enabled: row.get::<_, i32>(5)? == 1,
-                created_at: DateTime::parse_from_rfc3339(&created_at_str).ok().map(|dt| dt.with_timezone(&Utc)),
-                updated_at: DateTime::parse_from_rfc3339(&updated_at_str).ok().map(|dt| dt.with_timezone(&Utc)),
-            })
-        })?;
+    pub async fn get_mail_template_by_id(&self, id: i32) -> Result<Option<MailTemplate>> {
+        let conn = self.get_connection()?;
+        let template = conn
+            .query_row(
+                &format!("SELECT {} FROM mail_templates WHERE id = ?1", Self::TEMPLATE_COLUMNS),
+                params![id],
+                Self::map_template,
+            )
+            .optional()?;
+        Ok(template)
+    }
 
-        if let Some(row) = rows.next() {
-            Ok(Some(row?))
-        } else {
-            Ok(None)
+    pub async fn list_mail_templates(&self, template_type: Option<&str>, language: Option<&str>) -> Result<Vec<MailTemplate>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM mail_templates
+             WHERE (?1 IS NULL OR template_type = ?1) AND (?2 IS NULL OR language = ?2)
+             ORDER BY template_type, language",
+            Self::TEMPLATE_COLUMNS
+        ))?;
+        let rows = stmt.query_map(params![template_type, language], Self::map_template)?;
+        let mut templates = Vec::new();
+        for row in rows {
+            templates.push(row?);
         }
+        Ok(templates)
+    }
+
+    pub async fn create_mail_template(&self, template: &MailTemplate) -> Result<i64> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "INSERT INTO mail_templates (template_type, language, subject, content, variables, enabled, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, datetime('now'), datetime('now'))",
+            params![
+                template.template_type,
+                template.language,
+                template.subject,
+                template.content,
+                template.variables,
+                if template.enabled { 1 } else { 0 }
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    pub async fn update_mail_template(&self, template: &MailTemplate) -> Result<()> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE mail_templates SET subject = ?1, content = ?2, variables = ?3, enabled = ?4, updated_at = datetime('now')
+             WHERE id = ?5",
+            params![
+                template.subject,
+                template.content,
+                template.variables,
+                if template.enabled { 1 } else { 0 },
+                template.id
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub async fn delete_mail_template(&self, id: i32) -> Result<()> {
+        let conn = self.get_connection()?;
+        conn.execute("DELETE FROM mail_templates WHERE id = ?1", params![id])?;
+        Ok(())
     }
 
     pub async fn render_template(&self, template_type: &str, variables: &HashMap<String, String>) -> Result<(String, String)> {
-        if let Some(template) = self.get_mail_template(template_type).await? {
-            let mut subject = template.subject;
-            let mut content = template.content;
-            
-            // 简单的模板变量替换
-            for (key, value) in variables {
-                let placeholder = format!("{{{{{}}}}}", key);
-                subject = subject.replace(&placeholder, value);
-                content = content.replace(&placeholder, value);
-            }
-            
+        let context = serde_json::to_value(variables)?;
+        self.render_template_localized(template_type, None, &context).await
+    }
+
+    /// 按语言渲染模板，返回 (主题, HTML正文)
+    pub async fn render_template_localized(&self, template_type: &str, language: Option<&str>, variables: &serde_json::Value) -> Result<(String, String)> {
+        if let Some(template) = self.get_mail_template(template_type, language).await? {
+            let subject = template::render_plain_str(&template.subject, variables)?;
+            let content = template::render_str(&template.content, variables)?;
             Ok((subject, content))
         } else {
             Err(anyhow::anyhow!("邮件模板 {} 不存在或未启用", template_type))
@@ -274,4 +347,4 @@ impl MailRepository {
             last_sent_at: last_sent_at.and_then(|s| DateTime::parse_from_rfc3339(&s).ok().map(|dt| dt.with_timezone(&Utc))),
         })
     }
-} 
\ No newline at end of file
+}
@@ -0,0 +1,407 @@
+use anyhow::Result;
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::models::message::{
+    Conversation, Message, MessageReport, MessageSettings, MessageUser, ALLOW_FROM_EVERYONE,
+};
+use crate::utils::time::parse_db_time;
+
+const MESSAGE_SELECT: &str = "SELECT m.id, m.conversation_id, m.sender_id, m.receiver_id, m.content, m.message_type,
+        m.read_at, m.created_at, s.username, s.nickname, s.avatar_url, r.username, r.nickname, r.avatar_url
+    FROM messages m
+    LEFT JOIN users s ON s.id = m.sender_id
+    LEFT JOIN users r ON r.id = m.receiver_id";
+
+/// 对 ?1 可见的消息：在清空位置之后，且未被本人删除
+const VISIBLE_TO_ME: &str = "m.id > COALESCE((SELECT cm.cleared_before_id FROM conversation_members cm
+        WHERE cm.conversation_id = m.conversation_id AND cm.user_id = ?1), 0)
+    AND ((m.sender_id = ?1 AND m.sender_deleted = 0) OR (m.receiver_id = ?1 AND m.receiver_deleted = 0))";
+
+#[derive(Clone)]
+pub struct MessageRepository {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl MessageRepository {
+    pub fn new(db_path: &str) -> Result<Self> {
+        let conn = Connection::open(db_path)?;
+        Ok(Self { conn: Arc::new(Mutex::new(conn)) })
+    }
+
+    fn map_message(row: &Row) -> rusqlite::Result<Message> {
+        let sender_id: i32 = row.get(2)?;
+        let receiver_id: i32 = row.get(3)?;
+        let read_at: Option<String> = row.get(6)?;
+        let user = |id: i32, base: usize| -> rusqlite::Result<Option<MessageUser>> {
+            Ok(row.get::<_, Option<String>>(base)?.map(|username| MessageUser {
+                id,
+                username,
+                nickname: row.get(base + 1).ok().flatten(),
+                avatar: row.get(base + 2).ok().flatten(),
+            }))
+        };
+        Ok(Message {
+            id: row.get(0)?,
+            conversation_id: row.get(1)?,
+            sender_id,
+            receiver_id,
+            content: row.get(4)?,
+            message_type: row.get(5)?,
+            is_read: read_at.is_some(),
+            read_at: read_at.as_deref().and_then(parse_db_time),
+            created_at: parse_db_time(&row.get::<_, String>(7)?).unwrap_or_else(Utc::now),
+            sender: user(sender_id, 8)?,
+            receiver: user(receiver_id, 11)?,
+        })
+    }
+
+    // ========== 接收设置 ==========
+
+    pub async fn get_settings(&self, user_id: i32) -> Result<MessageSettings> {
+        let conn = self.conn.lock().await;
+        let allow_from: Option<String> = conn
+            .query_row("SELECT allow_from FROM message_settings WHERE user_id = ?", params![user_id], |row| row.get(0))
+            .optional()?;
+        Ok(MessageSettings { allow_from: allow_from.unwrap_or_else(|| ALLOW_FROM_EVERYONE.to_string()) })
+    }
+
+    pub async fn set_settings(&self, user_id: i32, allow_from: &str) -> Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "INSERT INTO message_settings (user_id, allow_from, updated_at) VALUES (?1, ?2, CURRENT_TIMESTAMP)
+             ON CONFLICT(user_id) DO UPDATE SET allow_from = excluded.allow_from, updated_at = excluded.updated_at",
+            params![user_id, allow_from],
+        )?;
+        Ok(())
+    }
+
+    // ========== 会话与消息 ==========
+
+    pub async fn find_conversation(&self, a: i32, b: i32) -> Result<Option<i32>> {
+        let conn = self.conn.lock().await;
+        Ok(conn
+            .query_row(
+                "SELECT id FROM conversations WHERE user_a = ? AND user_b = ?",
+                params![a.min(b), a.max(b)],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    /// 写入消息（必要时创建会话），返回消息与是否新建了会话
+    pub async fn insert_message(
+        &self,
+        sender_id: i32,
+        receiver_id: i32,
+        content: &str,
+        message_type: &str,
+    ) -> Result<(Message, bool)> {
+        let mut conn = self.conn.lock().await;
+        let tx = conn.transaction()?;
+        let (a, b) = (sender_id.min(receiver_id), sender_id.max(receiver_id));
+        let created = tx.execute(
+            "INSERT OR IGNORE INTO conversations (user_a, user_b, initiator_id) VALUES (?, ?, ?)",
+            params![a, b, sender_id],
+        )? > 0;
+        let conversation_id: i32 = tx.query_row(
+            "SELECT id FROM conversations WHERE user_a = ? AND user_b = ?",
+            params![a, b],
+            |row| row.get(0),
+        )?;
+        tx.execute(
+            "INSERT INTO messages (conversation_id, sender_id, receiver_id, content, message_type) VALUES (?, ?, ?, ?, ?)",
+            params![conversation_id, sender_id, receiver_id, content, message_type],
+        )?;
+        let message_id = tx.last_insert_rowid();
+        tx.execute(
+            "UPDATE conversations SET last_message_id = ?1,
+                last_message_at = (SELECT created_at FROM messages WHERE id = ?1) WHERE id = ?2",
+            params![message_id, conversation_id],
+        )?;
+        let message = tx.query_row(&format!("{} WHERE m.id = ?", MESSAGE_SELECT), params![message_id], Self::map_message)?;
+        tx.commit()?;
+        Ok((message, created))
+    }
+
+    pub async fn find_message(&self, id: i32) -> Result<Option<Message>> {
+        let conn = self.conn.lock().await;
+        Ok(conn
+            .query_row(&format!("{} WHERE m.id = ?", MESSAGE_SELECT), params![id], Self::map_message)
+            .optional()?)
+    }
+
+    /// 与某个用户的消息，按时间倒序分页
+    pub async fn list_messages(&self, user_id: i32, other_id: i32, offset: i64, limit: i64) -> Result<(Vec<Message>, i64)> {
+        let conn = self.conn.lock().await;
+        let filter = format!(
+            "((m.sender_id = ?1 AND m.receiver_id = ?2) OR (m.sender_id = ?2 AND m.receiver_id = ?1)) AND {}",
+            VISIBLE_TO_ME
+        );
+        let total: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM messages m WHERE {}", filter),
+            params![user_id, other_id],
+            |row| row.get(0),
+        )?;
+        let mut stmt = conn.prepare(&format!("{} WHERE {} ORDER BY m.id DESC LIMIT ?3 OFFSET ?4", MESSAGE_SELECT, filter))?;
+        let rows = stmt.query_map(params![user_id, other_id, limit, offset], Self::map_message)?;
+        Ok((rows.collect::<Result<Vec<_>, _>>()?, total))
+    }
+
+    /// 在 ?1 可见的消息中搜索内容
+    pub async fn search_messages(&self, user_id: i32, keyword: &str, other_id: Option<i32>, limit: i64) -> Result<Vec<Message>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(&format!(
+            "{} WHERE {} AND m.content LIKE ?2
+               AND (?3 IS NULL OR m.sender_id = ?3 OR m.receiver_id = ?3)
+             ORDER BY m.id DESC LIMIT ?4",
+            MESSAGE_SELECT, VISIBLE_TO_ME
+        ))?;
+        let rows = stmt.query_map(params![user_id, format!("%{}%", keyword), other_id, limit], Self::map_message)?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// 标记发给自己的消息为已读，返回 (消息ID, 发送者ID)
+    pub async fn mark_read(&self, user_id: i32, message_ids: &[i32]) -> Result<Vec<(i32, i32)>> {
+        let mut conn = self.conn.lock().await;
+        let tx = conn.transaction()?;
+        let mut marked = Vec::new();
+        {
+            let mut stmt = tx.prepare(
+                "UPDATE messages SET read_at = CURRENT_TIMESTAMP
+                 WHERE id = ? AND receiver_id = ? AND read_at IS NULL RETURNING id, sender_id",
+            )?;
+            for id in message_ids {
+                if let Some(row) = stmt.query_row(params![id, user_id], |row| Ok((row.get(0)?, row.get(1)?))).optional()? {
+                    marked.push(row);
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(marked)
+    }
+
+    /// 标记某个用户发来的全部消息为已读，返回被标记的消息ID
+    pub async fn mark_conversation_read(&self, user_id: i32, other_id: i32) -> Result<Vec<i32>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(
+            "UPDATE messages SET read_at = CURRENT_TIMESTAMP
+             WHERE receiver_id = ? AND sender_id = ? AND read_at IS NULL RETURNING id",
+        )?;
+        let rows = stmt.query_map(params![user_id, other_id], |row| row.get(0))?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// 仅对自己删除一条消息
+    pub async fn delete_message(&self, user_id: i32, message_id: i32) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let n = conn.execute(
+            "UPDATE messages SET
+                sender_deleted = CASE WHEN sender_id = ?2 THEN 1 ELSE sender_deleted END,
+                receiver_deleted = CASE WHEN receiver_id = ?2 THEN 1 ELSE receiver_deleted END,
+                read_at = CASE WHEN receiver_id = ?2 THEN COALESCE(read_at, CURRENT_TIMESTAMP) ELSE read_at END
+             WHERE id = ?1 AND (sender_id = ?2 OR receiver_id = ?2)",
+            params![message_id, user_id],
+        )?;
+        Ok(n > 0)
+    }
+
+    /// 删除会话：记录当前最后一条消息位置，之前的消息对自己不再可见，未读消息一并置为已读
+    pub async fn clear_conversation(&self, user_id: i32, other_id: i32) -> Result<bool> {
+        let Some(conversation_id) = self.find_conversation(user_id, other_id).await? else {
+            return Ok(false);
+        };
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "INSERT INTO conversation_members (conversation_id, user_id, cleared_before_id)
+             VALUES (?1, ?2, (SELECT COALESCE(MAX(id), 0) FROM messages WHERE conversation_id = ?1))
+             ON CONFLICT(conversation_id, user_id) DO UPDATE SET cleared_before_id = excluded.cleared_before_id, is_pinned = 0",
+            params![conversation_id, user_id],
+        )?;
+        conn.execute(
+            "UPDATE messages SET read_at = CURRENT_TIMESTAMP
+             WHERE conversation_id = ? AND receiver_id = ? AND read_at IS NULL",
+            params![conversation_id, user_id],
+        )?;
+        Ok(true)
+    }
+
+    pub async fn set_pinned(&self, user_id: i32, other_id: i32, pinned: bool) -> Result<bool> {
+        let Some(conversation_id) = self.find_conversation(user_id, other_id).await? else {
+            return Ok(false);
+        };
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "INSERT INTO conversation_members (conversation_id, user_id, is_pinned) VALUES (?1, ?2, ?3)
+             ON CONFLICT(conversation_id, user_id) DO UPDATE SET is_pinned = excluded.is_pinned",
+            params![conversation_id, user_id, pinned],
+        )?;
+        Ok(true)
+    }
+
+    pub async fn unread_count(&self, user_id: i32) -> Result<i32> {
+        let conn = self.conn.lock().await;
+        Ok(conn.query_row(
+            &format!("SELECT COUNT(*) FROM messages m WHERE m.receiver_id = ?1 AND m.read_at IS NULL AND {}", VISIBLE_TO_ME),
+            params![user_id],
+            |row| row.get(0),
+        )?)
+    }
+
+    /// 会话列表：只列出仍有可见消息的会话，置顶优先，其次按最后一条消息倒序。
+    /// keyword 同时匹配对方用户名、昵称与消息内容
+    pub async fn list_conversations(&self, user_id: i32, keyword: Option<&str>) -> Result<Vec<Conversation>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(&format!(
+            "WITH mine AS (
+                SELECT c.id, CASE WHEN c.user_a = ?1 THEN c.user_b ELSE c.user_a END AS other_id, c.created_at,
+                       COALESCE(cm.is_pinned, 0) AS pinned
+                FROM conversations c
+                LEFT JOIN conversation_members cm ON cm.conversation_id = c.id AND cm.user_id = ?1
+                WHERE c.user_a = ?1 OR c.user_b = ?1
+             ),
+             vis AS (SELECT m.* FROM messages m WHERE (m.sender_id = ?1 OR m.receiver_id = ?1) AND {})
+             SELECT mine.id, mine.other_id, u.username, u.nickname, u.avatar_url, l.content, l.message_type, l.created_at,
+                    (SELECT COUNT(*) FROM vis v WHERE v.conversation_id = mine.id AND v.receiver_id = ?1 AND v.read_at IS NULL),
+                    mine.pinned, mine.created_at
+             FROM mine
+             JOIN vis l ON l.id = (SELECT MAX(v.id) FROM vis v WHERE v.conversation_id = mine.id)
+             LEFT JOIN users u ON u.id = mine.other_id
+             WHERE ?2 IS NULL OR u.username LIKE ?2 OR u.nickname LIKE ?2
+                OR EXISTS (SELECT 1 FROM vis v WHERE v.conversation_id = mine.id AND v.content LIKE ?2)
+             ORDER BY mine.pinned DESC, l.id DESC",
+            VISIBLE_TO_ME
+        ))?;
+        let pattern = keyword.map(|k| format!("%{}%", k));
+        let rows = stmt.query_map(params![user_id, pattern], |row| {
+            Ok(Conversation {
+                id: row.get(0)?,
+                participant_id: row.get(1)?,
+                participant_username: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+                participant_nickname: row.get(3)?,
+                participant_avatar: row.get(4)?,
+                last_message: row.get(5)?,
+                last_message_type: row.get(6)?,
+                last_message_time: row.get::<_, Option<String>>(7)?.as_deref().and_then(parse_db_time),
+                unread_count: row.get(8)?,
+                is_pinned: row.get(9)?,
+                is_online: false,
+                created_at: parse_db_time(&row.get::<_, String>(10)?).unwrap_or_else(Utc::now),
+            })
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    // ========== 限频统计 ==========
+
+    /// 最近 seconds 秒内发送的消息数
+    pub async fn count_sent_since(&self, user_id: i32, seconds: i64) -> Result<i64> {
+        let conn = self.conn.lock().await;
+        Ok(conn.query_row(
+            "SELECT COUNT(*) FROM messages WHERE sender_id = ? AND created_at >= datetime('now', ?)",
+            params![user_id, format!("-{} seconds", seconds)],
+            |row| row.get(0),
+        )?)
+    }
+
+    /// 最近 seconds 秒内主动发起的会话数
+    pub async fn count_conversations_started_since(&self, user_id: i32, seconds: i64) -> Result<i64> {
+        let conn = self.conn.lock().await;
+        Ok(conn.query_row(
+            "SELECT COUNT(*) FROM conversations WHERE initiator_id = ? AND created_at >= datetime('now', ?)",
+            params![user_id, format!("-{} seconds", seconds)],
+            |row| row.get(0),
+        )?)
+    }
+
+    // ========== 举报 ==========
+
+    /// 提交举报，同一用户对同一消息只保留一条
+    pub async fn create_report(&self, message_id: i32, reporter_id: i32, reason: Option<&str>) -> Result<Option<i32>> {
+        let conn = self.conn.lock().await;
+        let n = conn.execute(
+            "INSERT OR IGNORE INTO message_reports (message_id, reporter_id, reason) VALUES (?, ?, ?)",
+            params![message_id, reporter_id, reason],
+        )?;
+        Ok(if n > 0 { Some(conn.last_insert_rowid() as i32) } else { None })
+    }
+
+    fn map_report(row: &Row) -> rusqlite::Result<MessageReport> {
+        Ok(MessageReport {
+            id: row.get(0)?,
+            message_id: row.get(1)?,
+            reporter_id: row.get(2)?,
+            reporter_username: row.get(3)?,
+            reason: row.get(4)?,
+            status: row.get(5)?,
+            handled_by: row.get(6)?,
+            handle_note: row.get(7)?,
+            handled_at: row.get::<_, Option<String>>(8)?.as_deref().and_then(parse_db_time),
+            created_at: parse_db_time(&row.get::<_, String>(9)?).unwrap_or_else(Utc::now),
+            message: None,
+        })
+    }
+
+    pub async fn list_reports(&self, status: Option<&str>, offset: i64, limit: i64) -> Result<(Vec<MessageReport>, i64)> {
+        let conn = self.conn.lock().await;
+        let total: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM message_reports WHERE ?1 IS NULL OR status = ?1",
+            params![status],
+            |row| row.get(0),
+        )?;
+        let mut stmt = conn.prepare(
+            "SELECT r.id, r.message_id, r.reporter_id, u.username, r.reason, r.status, r.handled_by, r.handle_note,
+                    r.handled_at, r.created_at
+             FROM message_reports r LEFT JOIN users u ON u.id = r.reporter_id
+             WHERE ?1 IS NULL OR r.status = ?1
+             ORDER BY r.id DESC LIMIT ?2 OFFSET ?3",
+        )?;
+        let rows = stmt.query_map(params![status, limit, offset], Self::map_report)?;
+        Ok((rows.collect::<Result<Vec<_>, _>>()?, total))
+    }
+
+    pub async fn get_report(&self, id: i32) -> Result<Option<MessageReport>> {
+        let conn = self.conn.lock().await;
+        Ok(conn
+            .query_row(
+                "SELECT r.id, r.message_id, r.reporter_id, u.username, r.reason, r.status, r.handled_by, r.handle_note,
+                        r.handled_at, r.created_at
+                 FROM message_reports r LEFT JOIN users u ON u.id = r.reporter_id WHERE r.id = ?",
+                params![id],
+                Self::map_report,
+            )
+            .optional()?)
+    }
+
+    pub async fn handle_report(&self, id: i32, admin_id: i32, status: &str, note: Option<&str>) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let n = conn.execute(
+            "UPDATE message_reports SET status = ?, handled_by = ?, handle_note = ?, handled_at = CURRENT_TIMESTAMP
+             WHERE id = ?",
+            params![status, admin_id, note, id],
+        )?;
+        Ok(n > 0)
+    }
+
+    /// 被举报消息前后的上下文（按时间正序），不受双方删除影响
+    pub async fn message_context(&self, message_id: i32, around: i64) -> Result<Vec<Message>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT * FROM (
+                {0} WHERE m.conversation_id = (SELECT conversation_id FROM messages WHERE id = ?1) AND m.id <= ?1
+                ORDER BY m.id DESC LIMIT ?2 + 1
+             )
+             UNION ALL
+             SELECT * FROM (
+                {0} WHERE m.conversation_id = (SELECT conversation_id FROM messages WHERE id = ?1) AND m.id > ?1
+                ORDER BY m.id ASC LIMIT ?2
+             )
+             ORDER BY 1",
+            MESSAGE_SELECT
+        ))?;
+        let rows = stmt.query_map(params![message_id, around], Self::map_message)?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+}
@@ -11,6 +11,9 @@ pub mod download_security_repo; // 添加下载安全仓库
 pub mod notification_repo; // 新增通知仓库
 pub mod follow_repo; // 新增关注仓库
 pub mod post_repo; // 新增帖子仓库
+pub mod rating_repo; // 资源评分仓库
+pub mod achievement_repo; // 成就仓库
+pub mod points_repo; // 经验与星星流水仓库
 
 pub use user_repo::*;
 pub use package_repo::*;
@@ -33,4 +36,40 @@ pub fn get_connection() -> Result<Connection> {
     }
     
     Connection::open(&db_path)
-} 
\ No newline at end of file
+}
+
+/// 确保表中有某列，缺失时补上；返回该列此刻是否存在（表不存在或补列失败时为 false）
+pub(crate) fn ensure_column(conn: &Connection, table: &str, column: &str, decl: &str) -> bool {
+    let exists = |conn: &Connection| {
+        conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM pragma_table_info(?1) WHERE name = ?2)",
+            [table, column],
+            |row| row.get::<_, bool>(0),
+        )
+        .unwrap_or(false)
+    };
+    if exists(conn) {
+        return true;
+    }
+    if let Err(e) = conn.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, decl), []) {
+        log::warn!("为 {} 表补充 {} 列失败: {}", table, column, e);
+    }
+    exists(conn)
+}
+
+pub mod weekly_report_repo; // 个人周报仓库
+pub mod ranking_repo; // 按时间段统计的排行榜
+pub mod timeline_repo; // 关注时间线
+pub mod recommendation_repo; // 资源推荐
+pub mod message_repo; // 私信
+pub mod user_relation_repo; // 拉黑与屏蔽
+pub mod collection_repo; // 收藏夹
+pub mod package_maintainer_repo; // 资源维护者与所有权转让
+pub mod draft_repo; // 草稿与定时发布
+pub mod render_cache_repo; // Markdown 渲染缓存
+pub mod edit_revision_repo; // 编辑历史
+pub mod package_dependency_repo; // 资源依赖与兼容性
+pub mod package_analytics_repo; // 资源数据分析
+pub mod site_metrics_repo; // 全站数据日汇总
+pub mod recycle_bin_repo; // 回收站
+pub mod tag_repo; // 标签关联与匹配
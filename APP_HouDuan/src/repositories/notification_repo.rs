@@ -4,7 +4,9 @@ use rusqlite::{params, Connection, OptionalExtension};
 use tokio::sync::Mutex;
 use std::sync::Arc;
 
-use crate::models::notification::Notification;
+use crate::models::notification::{
+    DigestFrequency, DigestSetting, Notification, NotificationCategory, NotificationChannel,
+};
 
 #[derive(Clone)]
 pub struct NotificationRepository {
@@ -21,6 +23,7 @@ impl NotificationRepository {
 
     pub async fn init(&self) -> Result<()> {
         let conn = self.conn.lock().await;
+        Self::upgrade_legacy_table(&conn)?;
         conn.execute(
             "CREATE TABLE IF NOT EXISTS notifications (
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -36,6 +39,67 @@ impl NotificationRepository {
             )",
             [],
         )?;
+        // 关注的人发布内容时的通知去重
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_notifications_related ON notifications(related_type, related_id, notif_type)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS notification_preferences (
+                user_id INTEGER NOT NULL,
+                category TEXT NOT NULL,
+                channel TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                PRIMARY KEY (user_id, category)
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS notification_digest_settings (
+                user_id INTEGER PRIMARY KEY,
+                frequency TEXT NOT NULL DEFAULT 'off',
+                last_sent_at TEXT,
+                unsubscribe_token TEXT NOT NULL UNIQUE
+            )",
+            [],
+        )?;
+        Ok(())
+    }
+
+    /// 全新安装时 sql/schema 建出的是旧版 notifications 表（type / data / read_status），
+    /// 与当前结构不兼容：改建为当前结构并迁移已有通知
+    fn upgrade_legacy_table(conn: &Connection) -> Result<()> {
+        let legacy: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM pragma_table_info('notifications') WHERE name = 'read_status')
+                AND NOT EXISTS(SELECT 1 FROM pragma_table_info('notifications') WHERE name = 'notif_type')",
+            [],
+            |row| row.get(0),
+        )?;
+        if !legacy {
+            return Ok(());
+        }
+        conn.execute_batch(
+            "BEGIN;
+             ALTER TABLE notifications RENAME TO notifications_legacy;
+             CREATE TABLE notifications (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id INTEGER NOT NULL,
+                title TEXT NOT NULL,
+                content TEXT NOT NULL,
+                link TEXT,
+                notif_type TEXT,
+                related_type TEXT,
+                related_id INTEGER,
+                is_read INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL
+             );
+             INSERT INTO notifications (id, user_id, title, content, notif_type, is_read, created_at)
+             SELECT id, user_id, title, COALESCE(content, ''), type, COALESCE(read_status, 0), COALESCE(created_at, datetime('now'))
+             FROM notifications_legacy;
+             DROP TABLE notifications_legacy;
+             COMMIT;",
+        )?;
+        log::info!("已将旧版 notifications 表升级为当前结构");
         Ok(())
     }
 
@@ -86,12 +150,49 @@ impl NotificationRepository {
         Ok(list)
     }
 
+    /// 获取指定 id 之后的通知（按 id 升序），用于实时推送断线续传
+    pub async fn list_after(&self, user_id: i32, after_id: i32, limit: i32) -> Result<Vec<Notification>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(
+            "SELECT id,user_id,title,content,link,notif_type,related_type,related_id,is_read,created_at 
+             FROM notifications WHERE user_id=? AND id>? ORDER BY id ASC LIMIT ?",
+        )?;
+        let rows = stmt.query_map(params![user_id, after_id, limit], |row| {
+            Ok(Notification {
+                id: row.get(0)?,
+                user_id: row.get(1)?,
+                title: row.get(2)?,
+                content: row.get(3)?,
+                link: row.get(4).ok(),
+                notif_type: row.get(5).ok(),
+                related_type: row.get(6).ok(),
+                related_id: row.get(7).ok(),
+                is_read: row.get::<_, i32>(8)? != 0,
+                created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(9)?).map(|d| d.with_timezone(&Utc)).unwrap_or_else(|_| Utc::now()),
+            })
+        })?;
+        let mut list = Vec::new();
+        for r in rows { list.push(r?); }
+        Ok(list)
+    }
+
     pub async fn mark_read(&self, user_id: i32, id: i32) -> Result<()> {
         let conn = self.conn.lock().await;
         conn.execute("UPDATE notifications SET is_read=1 WHERE id=? AND user_id=?", params![id, user_id])?;
         Ok(())
     }
 
+    /// actor 对 user 是否不可见：user 屏蔽了 actor，或双方任一方拉黑了另一方
+    pub async fn is_hidden_from(&self, user_id: i32, actor_id: i32) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let hidden: bool = conn.query_row(
+            &format!("SELECT ?2 IN ({})", crate::repositories::user_relation_repo::HIDDEN_FROM_VIEWER),
+            params![user_id, actor_id],
+            |row| row.get(0),
+        )?;
+        Ok(hidden)
+    }
+
     pub async fn unread_count(&self, user_id: i32) -> Result<i32> {
         let conn = self.conn.lock().await;
         let count: i32 = conn.query_row("SELECT COUNT(*) FROM notifications WHERE user_id=? AND is_read=0", params![user_id], |row| row.get(0))?;
@@ -152,4 +253,209 @@ impl NotificationRepository {
         conn.execute("DELETE FROM notifications WHERE id=? AND user_id=?", params![id, user_id])?;
         Ok(())
     }
-} 
\ No newline at end of file
+
+    /// 获取用户已设置的通知偏好（未设置的类别不返回）
+    pub async fn get_preferences(&self, user_id: i32) -> Result<Vec<(NotificationCategory, NotificationChannel)>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare("SELECT category, channel FROM notification_preferences WHERE user_id=?")?;
+        let rows = stmt.query_map(params![user_id], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+        let mut list = Vec::new();
+        for r in rows {
+            let (category, channel) = r?;
+            if let (Some(category), Some(channel)) = (NotificationCategory::parse(&category), NotificationChannel::parse(&channel)) {
+                list.push((category, channel));
+            }
+        }
+        Ok(list)
+    }
+
+    pub async fn get_preference(&self, user_id: i32, category: NotificationCategory) -> Result<Option<NotificationChannel>> {
+        let conn = self.conn.lock().await;
+        let channel: Option<String> = conn.query_row(
+            "SELECT channel FROM notification_preferences WHERE user_id=? AND category=?",
+            params![user_id, category.as_str()],
+            |row| row.get(0),
+        ).optional()?;
+        Ok(channel.as_deref().and_then(NotificationChannel::parse))
+    }
+
+    pub async fn set_preference(&self, user_id: i32, category: NotificationCategory, channel: NotificationChannel) -> Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "INSERT INTO notification_preferences (user_id, category, channel, updated_at) VALUES (?,?,?,?)
+             ON CONFLICT(user_id, category) DO UPDATE SET channel=excluded.channel, updated_at=excluded.updated_at",
+            params![user_id, category.as_str(), channel.as_str(), Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// 获取摘要设置，不存在时按默认值创建（同时生成退订令牌）
+    pub async fn get_digest_setting(&self, user_id: i32) -> Result<DigestSetting> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "INSERT OR IGNORE INTO notification_digest_settings (user_id, frequency, unsubscribe_token) VALUES (?, 'off', ?)",
+            params![user_id, uuid::Uuid::new_v4().simple().to_string()],
+        )?;
+        let setting = conn.query_row(
+            "SELECT user_id, frequency, last_sent_at, unsubscribe_token, weekly_report, mail_language FROM notification_digest_settings WHERE user_id=?",
+            params![user_id],
+            Self::map_digest_setting,
+        )?;
+        Ok(setting)
+    }
+
+    fn map_digest_setting(row: &rusqlite::Row) -> rusqlite::Result<DigestSetting> {
+        Ok(DigestSetting {
+            user_id: row.get(0)?,
+            frequency: DigestFrequency::parse(&row.get::<_, String>(1)?).unwrap_or(DigestFrequency::Off),
+            last_sent_at: row.get::<_, Option<String>>(2)?
+                .and_then(|s| chrono::DateTime::parse_from_rfc3339(&s).ok())
+                .map(|d| d.with_timezone(&Utc)),
+            unsubscribe_token: row.get(3)?,
+            weekly_report: row.get(4)?,
+            mail_language: row.get(5)?,
+        })
+    }
+
+    pub async fn set_digest_frequency(&self, user_id: i32, frequency: DigestFrequency) -> Result<()> {
+        self.get_digest_setting(user_id).await?;
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "UPDATE notification_digest_settings SET frequency=? WHERE user_id=?",
+            params![frequency.as_str(), user_id],
+        )?;
+        Ok(())
+    }
+
+    pub async fn set_weekly_report(&self, user_id: i32, enabled: bool) -> Result<()> {
+        self.get_digest_setting(user_id).await?;
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "UPDATE notification_digest_settings SET weekly_report=? WHERE user_id=?",
+            params![enabled, user_id],
+        )?;
+        Ok(())
+    }
+
+    pub async fn set_mail_language(&self, user_id: i32, language: Option<&str>) -> Result<()> {
+        self.get_digest_setting(user_id).await?;
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "UPDATE notification_digest_settings SET mail_language=? WHERE user_id=?",
+            params![language, user_id],
+        )?;
+        Ok(())
+    }
+
+    pub async fn mark_digest_sent(&self, user_id: i32, sent_at: chrono::DateTime<Utc>) -> Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "UPDATE notification_digest_settings SET last_sent_at=? WHERE user_id=?",
+            params![sent_at.to_rfc3339(), user_id],
+        )?;
+        Ok(())
+    }
+
+    pub async fn find_user_by_unsubscribe_token(&self, token: &str) -> Result<Option<i32>> {
+        let conn = self.conn.lock().await;
+        let user_id = conn.query_row(
+            "SELECT user_id FROM notification_digest_settings WHERE unsubscribe_token=?",
+            params![token],
+            |row| row.get(0),
+        ).optional()?;
+        Ok(user_id)
+    }
+
+    /// 列出开启了指定频率摘要的用户
+    pub async fn list_digest_settings(&self, frequency: DigestFrequency) -> Result<Vec<DigestSetting>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(
+            "SELECT user_id, frequency, last_sent_at, unsubscribe_token, weekly_report, mail_language FROM notification_digest_settings WHERE frequency=?",
+        )?;
+        let rows = stmt.query_map(params![frequency.as_str()], Self::map_digest_setting)?;
+        let mut list = Vec::new();
+        for r in rows { list.push(r?); }
+        Ok(list)
+    }
+
+    /// 列出订阅了周报邮件的用户
+    pub async fn list_weekly_report_settings(&self) -> Result<Vec<DigestSetting>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(
+            "SELECT user_id, frequency, last_sent_at, unsubscribe_token, weekly_report, mail_language FROM notification_digest_settings WHERE weekly_report=1",
+        )?;
+        let rows = stmt.query_map([], Self::map_digest_setting)?;
+        let mut list = Vec::new();
+        for r in rows { list.push(r?); }
+        Ok(list)
+    }
+
+    /// 获取用户在指定时间之后的未读通知
+    pub async fn list_unread_since(&self, user_id: i32, since: Option<chrono::DateTime<Utc>>) -> Result<Vec<Notification>> {
+        let since = since.map(|d| d.to_rfc3339()).unwrap_or_default();
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(
+            "SELECT id,user_id,title,content,link,notif_type,related_type,related_id,is_read,created_at 
+             FROM notifications WHERE user_id=? AND is_read=0 AND created_at>? ORDER BY id ASC",
+        )?;
+        let rows = stmt.query_map(params![user_id, since], |row| {
+            Ok(Notification {
+                id: row.get(0)?,
+                user_id: row.get(1)?,
+                title: row.get(2)?,
+                content: row.get(3)?,
+                link: row.get(4).ok(),
+                notif_type: row.get(5).ok(),
+                related_type: row.get(6).ok(),
+                related_id: row.get(7).ok(),
+                is_read: row.get::<_, i32>(8)? != 0,
+                created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(9)?).map(|d| d.with_timezone(&Utc)).unwrap_or_else(|_| Utc::now()),
+            })
+        })?;
+        let mut list = Vec::new();
+        for r in rows { list.push(r?); }
+        Ok(list)
+    }
+
+    /// 获取用户名与邮箱，用于发送邮件
+    pub async fn get_user_contact(&self, user_id: i32) -> Result<Option<(String, String)>> {
+        let conn = self.conn.lock().await;
+        let contact = conn.query_row(
+            "SELECT username, email FROM users WHERE id=?",
+            params![user_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ).optional()?;
+        Ok(contact)
+    }
+
+    /// 按用户名（忽略大小写）查找用户ID
+    pub async fn find_user_ids_by_usernames(&self, usernames: &[String]) -> Result<Vec<i32>> {
+        if usernames.is_empty() {
+            return Ok(Vec::new());
+        }
+        let conn = self.conn.lock().await;
+        let placeholders = vec!["?"; usernames.len()].join(",");
+        let sql = format!("SELECT id FROM users WHERE username COLLATE NOCASE IN ({})", placeholders);
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(usernames.iter()), |row| row.get(0))?;
+        Ok(rows.collect::<Result<Vec<i32>, _>>()?)
+    }
+
+    /// 关注了该用户的人
+    pub async fn find_follower_ids(&self, user_id: i32) -> Result<Vec<i32>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare("SELECT follower_id FROM user_follows WHERE followed_id = ?")?;
+        let rows = stmt.query_map(params![user_id], |row| row.get(0))?;
+        Ok(rows.collect::<Result<Vec<i32>, _>>()?)
+    }
+
+    /// 是否已为某个对象发过该类型的通知
+    pub async fn exists_for_related(&self, notif_type: &str, related_type: &str, related_id: i32) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        Ok(conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM notifications WHERE related_type = ? AND related_id = ? AND notif_type = ?)",
+            params![related_type, related_id, notif_type],
+            |row| row.get(0),
+        )?)
+    }
+}
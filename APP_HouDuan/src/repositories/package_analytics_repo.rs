@@ -0,0 +1,153 @@
+use anyhow::Result;
+use rusqlite::{params, Connection};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// 访客标识：登录用户按用户ID，访客按IP
+const VISITOR_KEY: &str = "COALESCE('u' || user_id, 'ip' || ip_address)";
+
+/// 按日统计的一行：(日期, 次数, 去重人数)
+pub type DailyCount = (String, i64, i64);
+
+/// 版本采用情况的一行：(版本, 下载次数, 下载人数, 首次下载, 最近下载)
+pub type VersionRow = (Option<String>, i64, i64, String, String);
+
+/// 资源数据分析：直接从浏览、下载、点赞与收藏记录汇总
+/// from 为 UTC 时间字符串，day_modifier 为 SQLite 日期修饰（如 "+28800 seconds"），用于按社区时区分日
+#[derive(Clone)]
+pub struct PackageAnalyticsRepository {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl PackageAnalyticsRepository {
+    pub fn new(db_path: &str) -> Result<Self> {
+        let conn = Connection::open(db_path)?;
+        Ok(Self { conn: Arc::new(Mutex::new(conn)) })
+    }
+
+    pub async fn daily_views(&self, package_id: i32, from: &str, day_modifier: &str) -> Result<Vec<DailyCount>> {
+        let conn = self.conn.lock().await;
+        let sql = format!(
+            "SELECT date(created_at, ?3) AS day, COUNT(*), COUNT(DISTINCT {VISITOR_KEY})
+             FROM package_views WHERE package_id = ?1 AND created_at >= ?2
+             GROUP BY day ORDER BY day"
+        );
+        query_daily(&conn, &sql, package_id, from, day_modifier)
+    }
+
+    pub async fn daily_downloads(&self, package_id: i32, from: &str, day_modifier: &str) -> Result<Vec<DailyCount>> {
+        let conn = self.conn.lock().await;
+        let sql = format!(
+            "SELECT date(download_time, ?3) AS day, COUNT(*), COUNT(DISTINCT {VISITOR_KEY})
+             FROM download_records WHERE package_id = ?1 AND download_time >= ?2
+             GROUP BY day ORDER BY day"
+        );
+        query_daily(&conn, &sql, package_id, from, day_modifier)
+    }
+
+    /// 每天新增的点赞（第三列恒为 0）
+    pub async fn daily_likes(&self, package_id: i32, from: &str, day_modifier: &str) -> Result<Vec<DailyCount>> {
+        let conn = self.conn.lock().await;
+        query_daily(
+            &conn,
+            "SELECT date(created_at, ?3) AS day, COUNT(*), 0
+             FROM package_likes WHERE package_id = ?1 AND created_at >= ?2
+             GROUP BY day ORDER BY day",
+            package_id,
+            from,
+            day_modifier,
+        )
+    }
+
+    /// 每天新增的收藏（第三列恒为 0）
+    pub async fn daily_favorites(&self, package_id: i32, from: &str, day_modifier: &str) -> Result<Vec<DailyCount>> {
+        let conn = self.conn.lock().await;
+        query_daily(
+            &conn,
+            "SELECT date(created_at, ?3) AS day, COUNT(*), 0
+             FROM package_favorites WHERE package_id = ?1 AND created_at >= ?2
+             GROUP BY day ORDER BY day",
+            package_id,
+            from,
+            day_modifier,
+        )
+    }
+
+    /// 区间内的去重访客数与去重下载人数（跨天去重，不能由每日数据相加得到）
+    pub async fn unique_totals(&self, package_id: i32, from: &str) -> Result<(i64, i64)> {
+        let conn = self.conn.lock().await;
+        let visitors: i64 = conn.query_row(
+            &format!("SELECT COUNT(DISTINCT {VISITOR_KEY}) FROM package_views WHERE package_id = ? AND created_at >= ?"),
+            params![package_id, from],
+            |row| row.get(0),
+        )?;
+        let downloaders: i64 = conn.query_row(
+            &format!("SELECT COUNT(DISTINCT {VISITOR_KEY}) FROM download_records WHERE package_id = ? AND download_time >= ?"),
+            params![package_id, from],
+            |row| row.get(0),
+        )?;
+        Ok((visitors, downloaders))
+    }
+
+    /// 当前的点赞数与收藏数
+    pub async fn current_reactions(&self, package_id: i32) -> Result<(i64, i64)> {
+        let conn = self.conn.lock().await;
+        let likes: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM package_likes WHERE package_id = ?",
+            params![package_id],
+            |row| row.get(0),
+        )?;
+        let favorites: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM package_favorites WHERE package_id = ?",
+            params![package_id],
+            |row| row.get(0),
+        )?;
+        Ok((likes, favorites))
+    }
+
+    /// 浏览按 (来源, User-Agent) 分组计数，由服务层归并为来源、平台与客户端分布
+    pub async fn view_agents(&self, package_id: i32, from: &str) -> Result<Vec<(Option<String>, Option<String>, i64)>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(
+            "SELECT referrer, user_agent, COUNT(*) FROM package_views
+             WHERE package_id = ? AND created_at >= ?
+             GROUP BY referrer, user_agent",
+        )?;
+        let rows = stmt.query_map(params![package_id, from], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// 下载按 User-Agent 分组计数
+    pub async fn download_agents(&self, package_id: i32, from: &str) -> Result<Vec<(Option<String>, i64)>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(
+            "SELECT user_agent, COUNT(*) FROM download_records
+             WHERE package_id = ? AND download_time >= ?
+             GROUP BY user_agent",
+        )?;
+        let rows = stmt.query_map(params![package_id, from], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// 全部下载记录按版本分组；记录版本之前的下载版本为 None
+    pub async fn version_adoption(&self, package_id: i32) -> Result<Vec<VersionRow>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT version, COUNT(*), COUNT(DISTINCT {VISITOR_KEY}), MIN(download_time), MAX(download_time)
+             FROM download_records WHERE package_id = ?
+             GROUP BY version ORDER BY MAX(download_time) DESC"
+        ))?;
+        let rows = stmt.query_map(params![package_id], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?))
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+}
+
+fn query_daily(conn: &Connection, sql: &str, package_id: i32, from: &str, day_modifier: &str) -> Result<Vec<DailyCount>> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map(params![package_id, from, day_modifier], |row| {
+        Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+    })?;
+    Ok(rows.collect::<Result<Vec<_>, _>>()?)
+}
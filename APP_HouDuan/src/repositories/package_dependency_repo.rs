@@ -0,0 +1,119 @@
+use anyhow::Result;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::models::package_dependency::{AppCompatibility, DependentPackage, PackageDependency};
+use crate::utils::version::satisfies;
+
+/// 资源依赖与结绳版本兼容范围
+#[derive(Clone)]
+pub struct PackageDependencyRepository {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl PackageDependencyRepository {
+    pub fn new(db_path: &str) -> Result<Self> {
+        let conn = Connection::open(db_path)?;
+        Ok(Self { conn: Arc::new(Mutex::new(conn)) })
+    }
+
+    /// 资源声明的依赖，附带被依赖资源的当前版本
+    pub async fn list(&self, package_id: i32) -> Result<Vec<PackageDependency>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(
+            "SELECT d.depends_on_id, p.name, p.version, p.status, d.version_req, d.optional
+             FROM package_dependencies d LEFT JOIN packages p ON p.id = d.depends_on_id
+             WHERE d.package_id = ?
+             ORDER BY d.id",
+        )?;
+        let rows = stmt.query_map(params![package_id], |row| {
+            let name: Option<String> = row.get(1)?;
+            let version: Option<String> = row.get(2)?;
+            let version_req: String = row.get(4)?;
+            Ok(PackageDependency {
+                depends_on_id: row.get(0)?,
+                satisfied: name.is_some() && satisfies(version.as_deref(), &version_req),
+                name,
+                version,
+                status: row.get(3)?,
+                version_req,
+                optional: row.get(5)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    pub async fn compatibility(&self, package_id: i32) -> Result<AppCompatibility> {
+        let conn = self.conn.lock().await;
+        let compat = conn
+            .query_row(
+                "SELECT min_app_version, max_app_version FROM package_compatibility WHERE package_id = ?",
+                params![package_id],
+                |row| Ok(AppCompatibility { min_app_version: row.get(0)?, max_app_version: row.get(1)? }),
+            )
+            .optional()?;
+        Ok(compat.unwrap_or_default())
+    }
+
+    /// 整体替换依赖列表与兼容范围；dependencies 为 (被依赖资源ID, 版本范围, 是否可选)
+    pub async fn replace(
+        &self,
+        package_id: i32,
+        dependencies: &[(i32, String, bool)],
+        compatibility: &AppCompatibility,
+    ) -> Result<()> {
+        let mut conn = self.conn.lock().await;
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM package_dependencies WHERE package_id = ?", params![package_id])?;
+        for (depends_on_id, version_req, optional) in dependencies {
+            tx.execute(
+                "INSERT INTO package_dependencies (package_id, depends_on_id, version_req, optional) VALUES (?, ?, ?, ?)",
+                params![package_id, depends_on_id, version_req, optional],
+            )?;
+        }
+        if compatibility.min_app_version.is_none() && compatibility.max_app_version.is_none() {
+            tx.execute("DELETE FROM package_compatibility WHERE package_id = ?", params![package_id])?;
+        } else {
+            tx.execute(
+                "INSERT INTO package_compatibility (package_id, min_app_version, max_app_version, updated_at)
+                 VALUES (?, ?, ?, CURRENT_TIMESTAMP)
+                 ON CONFLICT(package_id) DO UPDATE SET
+                    min_app_version = excluded.min_app_version,
+                    max_app_version = excluded.max_app_version,
+                    updated_at = excluded.updated_at",
+                params![package_id, compatibility.min_app_version, compatibility.max_app_version],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 反向依赖：声明依赖该资源的其他资源
+    pub async fn dependents(&self, package_id: i32) -> Result<Vec<DependentPackage>> {
+        let conn = self.conn.lock().await;
+        let target_version: Option<String> = conn
+            .query_row("SELECT version FROM packages WHERE id = ?", params![package_id], |row| row.get::<_, Option<String>>(0))
+            .optional()?
+            .flatten();
+        let mut stmt = conn.prepare(
+            "SELECT p.id, p.name, p.version, p.owner_id, d.version_req, d.optional
+             FROM package_dependencies d JOIN packages p ON p.id = d.package_id
+             WHERE d.depends_on_id = ?
+             ORDER BY p.name COLLATE NOCASE",
+        )?;
+        let rows = stmt.query_map(params![package_id], |row| {
+            let version_req: String = row.get(4)?;
+            Ok(DependentPackage {
+                package_id: row.get(0)?,
+                name: row.get(1)?,
+                version: row.get(2)?,
+                owner_id: row.get(3)?,
+                satisfied: satisfies(target_version.as_deref(), &version_req),
+                version_req,
+                optional: row.get(5)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+}
@@ -0,0 +1,207 @@
+use anyhow::Result;
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::models::package_maintainer::{
+    OwnershipTransfer, PackageMaintainer, ROLE_MAINTAINER, ROLE_OWNER, TRANSFER_ACCEPTED, TRANSFER_CANCELLED, TRANSFER_PENDING,
+};
+use crate::utils::time::parse_db_time;
+
+const TRANSFER_SELECT: &str = "SELECT t.id, t.package_id, p.name, t.from_user_id, fu.username, t.to_user_id, tu.username,
+        t.status, t.created_at, t.responded_at
+    FROM package_ownership_transfers t
+    LEFT JOIN packages p ON p.id = t.package_id
+    LEFT JOIN users fu ON fu.id = t.from_user_id
+    LEFT JOIN users tu ON tu.id = t.to_user_id";
+
+/// 资源所有者、协作维护者与所有权转让
+#[derive(Clone)]
+pub struct PackageMaintainerRepository {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl PackageMaintainerRepository {
+    pub fn new(db_path: &str) -> Result<Self> {
+        let conn = Connection::open(db_path)?;
+        Ok(Self { conn: Arc::new(Mutex::new(conn)) })
+    }
+
+    /// 用户在资源上的角色（owner / maintainer），无关时为 None
+    pub async fn role_of(&self, package_id: i32, user_id: i32) -> Result<Option<String>> {
+        let conn = self.conn.lock().await;
+        let role = conn
+            .query_row(
+                "SELECT role FROM package_maintainers WHERE package_id = ? AND user_id = ?",
+                params![package_id, user_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(role)
+    }
+
+    /// 维护者列表，所有者排在最前
+    pub async fn list(&self, package_id: i32) -> Result<Vec<PackageMaintainer>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(
+            "SELECT m.user_id, u.username, u.nickname, u.avatar_url, m.role, m.added_by, m.created_at
+             FROM package_maintainers m JOIN users u ON u.id = m.user_id
+             WHERE m.package_id = ?
+             ORDER BY CASE m.role WHEN 'owner' THEN 0 ELSE 1 END, m.created_at",
+        )?;
+        let rows = stmt.query_map(params![package_id], |row| {
+            Ok(PackageMaintainer {
+                user_id: row.get(0)?,
+                username: row.get(1)?,
+                nickname: row.get(2)?,
+                avatar: row.get(3)?,
+                role: row.get(4)?,
+                added_by: row.get(5)?,
+                created_at: parse_db_time(&row.get::<_, String>(6)?).unwrap_or_else(Utc::now),
+            })
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    pub async fn count_maintainers(&self, package_id: i32) -> Result<i64> {
+        let conn = self.conn.lock().await;
+        Ok(conn.query_row(
+            "SELECT COUNT(*) FROM package_maintainers WHERE package_id = ? AND role = ?",
+            params![package_id, ROLE_MAINTAINER],
+            |row| row.get(0),
+        )?)
+    }
+
+    pub async fn add_maintainer(&self, package_id: i32, user_id: i32, added_by: i32) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let n = conn.execute(
+            "INSERT OR IGNORE INTO package_maintainers (package_id, user_id, role, added_by) VALUES (?, ?, ?, ?)",
+            params![package_id, user_id, ROLE_MAINTAINER, added_by],
+        )?;
+        Ok(n > 0)
+    }
+
+    /// 移除协作维护者（所有者不能通过此方法移除）
+    pub async fn remove_maintainer(&self, package_id: i32, user_id: i32) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let n = conn.execute(
+            "DELETE FROM package_maintainers WHERE package_id = ? AND user_id = ? AND role = ?",
+            params![package_id, user_id, ROLE_MAINTAINER],
+        )?;
+        Ok(n > 0)
+    }
+
+    /// 发起转让，同一资源之前未处理的转让自动取消
+    pub async fn create_transfer(&self, package_id: i32, from_user_id: i32, to_user_id: i32) -> Result<i32> {
+        let mut conn = self.conn.lock().await;
+        let tx = conn.transaction()?;
+        tx.execute(
+            "UPDATE package_ownership_transfers SET status = ?, responded_at = CURRENT_TIMESTAMP
+             WHERE package_id = ? AND status = ?",
+            params![TRANSFER_CANCELLED, package_id, TRANSFER_PENDING],
+        )?;
+        let id: i32 = tx.query_row(
+            "INSERT INTO package_ownership_transfers (package_id, from_user_id, to_user_id, status)
+             VALUES (?, ?, ?, ?) RETURNING id",
+            params![package_id, from_user_id, to_user_id, TRANSFER_PENDING],
+            |row| row.get(0),
+        )?;
+        tx.commit()?;
+        Ok(id)
+    }
+
+    pub async fn find_transfer(&self, transfer_id: i32) -> Result<Option<OwnershipTransfer>> {
+        let conn = self.conn.lock().await;
+        let transfer = conn
+            .query_row(&format!("{} WHERE t.id = ?", TRANSFER_SELECT), params![transfer_id], map_transfer)
+            .optional()?;
+        Ok(transfer)
+    }
+
+    /// 资源当前未处理的转让
+    pub async fn pending_transfer(&self, package_id: i32) -> Result<Option<OwnershipTransfer>> {
+        let conn = self.conn.lock().await;
+        let transfer = conn
+            .query_row(
+                &format!("{} WHERE t.package_id = ? AND t.status = ? ORDER BY t.id DESC LIMIT 1", TRANSFER_SELECT),
+                params![package_id, TRANSFER_PENDING],
+                map_transfer,
+            )
+            .optional()?;
+        Ok(transfer)
+    }
+
+    /// 转给我且未处理的转让
+    pub async fn list_incoming(&self, user_id: i32) -> Result<Vec<OwnershipTransfer>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(&format!(
+            "{} WHERE t.to_user_id = ? AND t.status = ? ORDER BY t.created_at DESC",
+            TRANSFER_SELECT
+        ))?;
+        let rows = stmt.query_map(params![user_id, TRANSFER_PENDING], map_transfer)?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// 接受转让：更新资源所有者与作者名，原所有者降为维护者；转让已处理时返回 false
+    pub async fn accept_transfer(&self, transfer_id: i32) -> Result<bool> {
+        let mut conn = self.conn.lock().await;
+        let tx = conn.transaction()?;
+        let transfer: Option<(i32, i32)> = tx
+            .query_row(
+                "SELECT package_id, to_user_id FROM package_ownership_transfers WHERE id = ? AND status = ?",
+                params![transfer_id, TRANSFER_PENDING],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        let Some((package_id, to_user_id)) = transfer else {
+            return Ok(false);
+        };
+        tx.execute(
+            "UPDATE packages SET owner_id = ?1, author = (SELECT username FROM users WHERE id = ?1), updated_at = ?2
+             WHERE id = ?3",
+            params![to_user_id, Utc::now().to_rfc3339(), package_id],
+        )?;
+        tx.execute(
+            "UPDATE package_maintainers SET role = ?1 WHERE package_id = ?2 AND role = ?3 AND user_id != ?4",
+            params![ROLE_MAINTAINER, package_id, ROLE_OWNER, to_user_id],
+        )?;
+        tx.execute(
+            "INSERT INTO package_maintainers (package_id, user_id, role) VALUES (?1, ?2, ?3)
+             ON CONFLICT(package_id, user_id) DO UPDATE SET role = ?3",
+            params![package_id, to_user_id, ROLE_OWNER],
+        )?;
+        tx.execute(
+            "UPDATE package_ownership_transfers SET status = ?, responded_at = CURRENT_TIMESTAMP WHERE id = ?",
+            params![TRANSFER_ACCEPTED, transfer_id],
+        )?;
+        tx.commit()?;
+        Ok(true)
+    }
+
+    /// 拒绝或取消未处理的转让
+    pub async fn close_transfer(&self, transfer_id: i32, status: &str) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let n = conn.execute(
+            "UPDATE package_ownership_transfers SET status = ?, responded_at = CURRENT_TIMESTAMP
+             WHERE id = ? AND status = ?",
+            params![status, transfer_id, TRANSFER_PENDING],
+        )?;
+        Ok(n > 0)
+    }
+}
+
+fn map_transfer(row: &rusqlite::Row) -> rusqlite::Result<OwnershipTransfer> {
+    Ok(OwnershipTransfer {
+        id: row.get(0)?,
+        package_id: row.get(1)?,
+        package_name: row.get(2)?,
+        from_user_id: row.get(3)?,
+        from_username: row.get(4)?,
+        to_user_id: row.get(5)?,
+        to_username: row.get(6)?,
+        status: row.get(7)?,
+        created_at: parse_db_time(&row.get::<_, String>(8)?).unwrap_or_else(Utc::now),
+        responded_at: row.get::<_, Option<String>>(9)?.and_then(|s| parse_db_time(&s)),
+    })
+}
@@ -1,7 +1,11 @@
 use anyhow::Result;
 use rusqlite::{Connection, params, OptionalExtension};
-use crate::models::{Package, Category, PackageFile};
+use crate::models::{Package, Category, PackageFile, PackageRankingSort};
 use crate::models::Tag; // 需要Tag模型
+use crate::repositories::ensure_column;
+use crate::repositories::tag_repo::{self, TagTarget};
+use crate::models::edit_revision::{package_snapshot, PendingRevision};
+use crate::repositories::edit_revision_repo::EditRevisionRepository;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
@@ -9,33 +13,45 @@ use tokio::sync::Mutex;
 #[derive(Debug)]
 pub struct PackageRepository {
     conn: Arc<Mutex<Connection>>,
+    // package_views 是否有 referrer 列（迁移 025 添加）
+    has_view_referrer: bool,
 }
 
 impl PackageRepository {
     pub fn new(db_path: &str) -> Result<Self> {
         let conn = Connection::open(db_path)?;
+        let has_view_referrer = ensure_column(&conn, "package_views", "referrer", "TEXT");
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
+            has_view_referrer,
         })
     }
 
-    /// 获取资源排行榜
-    pub async fn get_package_ranking(&self, offset: i64, limit: i64) -> Result<(Vec<Package>, i64)> {
+    /// 获取资源排行榜，category_id 为 None 时不限分类
+    pub async fn get_package_ranking(&self, offset: i64, limit: i64, sort: &PackageRankingSort, category_id: Option<i32>) -> Result<(Vec<Package>, i64)> {
         let conn = self.conn.lock().await;
         
-        // 获取资源排行榜数据（按下载量排序）
-        let sql = "SELECT id, name, author, version, description, file_url, file_size, \
+        // 获取资源排行榜数据（默认按下载量排序，评分榜按贝叶斯加权评分排序）
+        let order_by = match sort {
+            PackageRankingSort::Downloads => "download_count DESC, like_count DESC, created_at DESC".to_string(),
+            PackageRankingSort::Rating { prior_mean, prior_weight } => format!(
+                "(COALESCE(rating_count, 0) * COALESCE(rating_avg, 0) + {w} * {m}) / (COALESCE(rating_count, 0) + {w}) DESC, \
+                 rating_count DESC, download_count DESC",
+                w = prior_weight, m = prior_mean
+            ),
+        };
+        let sql = format!("SELECT id, name, author, version, description, file_url, file_size, \
                    download_count, like_count, favorite_count, category_id, status, \
                    created_at, updated_at, reviewer_id, reviewed_at, review_comment, \
-                   is_pinned, is_featured, screenshots, cover_image, requirements, included_files \
+                   is_pinned, is_featured, screenshots, cover_image, requirements, included_files, owner_id \
                    FROM packages \
-                   WHERE status = 'active' \
-                   ORDER BY download_count DESC, like_count DESC, created_at DESC \
-                   LIMIT ? OFFSET ?";
+                   WHERE status = 'active' AND (?3 IS NULL OR category_id = ?3) \
+                   ORDER BY {} \
+                   LIMIT ?1 OFFSET ?2", order_by);
         
-        let mut stmt = conn.prepare(sql)?;
+        let mut stmt = conn.prepare(&sql)?;
 
-        let packages = stmt.query_map(params![limit, offset], |row| {
+        let packages = stmt.query_map(params![limit, offset, category_id], |row| {
             // 解析JSON字段的辅助函数
             let parse_json_array = |json_str: Option<String>| -> Option<Vec<String>> {
                 json_str.and_then(|s| serde_json::from_str(&s).ok())
@@ -50,6 +66,7 @@ impl PackageRepository {
                 id: row.get(0)?,
                 name: row.get(1)?,
                 author: row.get(2)?,
+                owner_id: row.get(23).ok().flatten(),
                 version: row.get(3)?,
                 description: row.get(4)?,
                 file_url: row.get(5)?,
@@ -83,8 +100,8 @@ impl PackageRepository {
 
         // 获取总数
         let total: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM packages WHERE status = 'active'",
-            [],
+            "SELECT COUNT(*) FROM packages WHERE status = 'active' AND (?1 IS NULL OR category_id = ?1)",
+            params![category_id],
             |row| row.get(0)
         )?;
 
@@ -99,7 +116,7 @@ impl PackageRepository {
             .unwrap_or(0);
         let comment_count: i32 = conn
             .query_row(
-                "SELECT COUNT(1) FROM comments WHERE target_type = 'Package' AND target_id = ?",
+                "SELECT COUNT(1) FROM comments WHERE target_type = 'Package' AND target_id = ? AND status != 'Deleted'",
                 params![package_id],
                 |r| r.get(0),
             )
@@ -130,8 +147,8 @@ impl PackageRepository {
         let sql = "SELECT id, name, author, version, description, file_url, file_size, \
                     download_count, like_count, favorite_count, category_id, status, \
                     created_at, updated_at, reviewer_id, reviewed_at, review_comment, \
-                    is_pinned, is_featured, screenshots, cover_image, requirements \
-             FROM packages ORDER BY is_pinned DESC, is_featured DESC, created_at DESC";
+                    is_pinned, is_featured, screenshots, cover_image, requirements, owner_id \
+             FROM packages WHERE status != 'deleted' ORDER BY is_pinned DESC, is_featured DESC, created_at DESC";
         log::debug!("🗄️ SQL: get_all_packages: {}", sql);
         let mut stmt = match conn.prepare(sql) {
             Ok(s) => s,
@@ -150,6 +167,7 @@ impl PackageRepository {
                 id: row.get(0)?,
                 name: row.get(1)?,
                 author: row.get(2)?,
+                owner_id: row.get(22).ok().flatten(),
                 version: row.get(3)?,
                 description: row.get(4)?,
                 file_url: row.get(5)?,
@@ -205,7 +223,7 @@ impl PackageRepository {
         let sql = "SELECT id, name, author, version, description, file_url, file_size, \
                     download_count, like_count, favorite_count, category_id, status, \
                     created_at, updated_at, reviewer_id, reviewed_at, review_comment, \
-                    is_pinned, is_featured, screenshots, cover_image, requirements, included_files \
+                    is_pinned, is_featured, screenshots, cover_image, requirements, included_files, owner_id \
              FROM packages WHERE id = ?";
         log::debug!("🗄️ SQL: find_by_id: {} | id={}", sql, id);
         let mut stmt = match conn.prepare(sql) {
@@ -228,6 +246,7 @@ impl PackageRepository {
                 id: row.get(0)?,
                 name: row.get(1)?,
                 author: row.get(2)?,
+                owner_id: row.get(23).ok().flatten(),
                 version: row.get(3)?,
                 description: row.get(4)?,
                 file_url: row.get(5)?,
@@ -278,8 +297,8 @@ impl PackageRepository {
         let conn = self.conn.lock().await;
         let sql = "INSERT INTO packages (name, author, version, description, file_url, file_size, \
                                   download_count, like_count, favorite_count, category_id, status, \
-                                  created_at, updated_at, is_pinned, is_featured, screenshots, cover_image, requirements, included_files) \
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)";
+                                  created_at, updated_at, is_pinned, is_featured, screenshots, cover_image, requirements, included_files, owner_id) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)";
         log::debug!("🗄️ SQL: create_package: {}", sql);
         
         // 序列化数组字段为JSON
@@ -319,6 +338,7 @@ impl PackageRepository {
             package.cover_image,
             requirements_json,
             included_files_json,
+            package.owner_id,
         ];
         match conn.execute(sql, params) {
             Ok(rows) => println!("[SQL] create_package affected rows: {}", rows),
@@ -334,6 +354,9 @@ impl PackageRepository {
         // 创建包含ID的新包对象
         let mut created_package = package.clone();
         created_package.id = last_id;
+        if let Some(owner_id) = created_package.owner_id {
+            Self::set_owner_internal(&conn, created_package.id, owner_id)?;
+        }
         // 插入标签关联
         if let Some(ref tags) = created_package.tags {
             Self::replace_tags_for_package_internal(&conn, created_package.id, tags)?;
@@ -342,12 +365,18 @@ impl PackageRepository {
     }
 
     pub async fn update_package(&self, package: &Package) -> Result<()> {
-        let conn = self.conn.lock().await;
+        self.update_package_recorded(package, None).await.map(|_| ())
+    }
+
+    /// 更新资源；传入编辑记录时，编辑历史与更新在同一事务中写入，返回新版本号
+    pub async fn update_package_recorded(&self, package: &Package, revision: Option<&PendingRevision>) -> Result<Option<i32>> {
+        let mut conn = self.conn.lock().await;
+        let tx = conn.transaction()?;
         let sql = "UPDATE packages SET name = ?, author = ?, version = ?, description = ?, \
                     file_url = ?, file_size = ?, download_count = ?, like_count = ?, \
                     favorite_count = ?, category_id = ?, status = ?, created_at = ?, \
                     updated_at = ?, is_pinned = ?, is_featured = ?, screenshots = ?, \
-                    cover_image = ?, requirements = ?, included_files = ? WHERE id = ?";
+                    cover_image = ?, requirements = ?, included_files = ?, owner_id = ? WHERE id = ?";
         log::debug!("🗄️ SQL: update_package: {} | id={}", sql, package.id);
         
         // 序列化JSON字段
@@ -387,23 +416,84 @@ impl PackageRepository {
             package.cover_image,
             requirements_json,
             included_files_json,
+            package.owner_id,
             package.id,
         ];
-        match conn.execute(sql, params) {
+        match tx.execute(sql, params) {
             Ok(rows) => println!("[SQL] update_package affected rows: {}", rows),
             Err(e) => {
                 log::error!("❌ update_package failed: {}", e);
                 return Err(e.into());
             }
         }
-        // 更新标签关联
+        // 更新标签关联（与资源更新同一事务）
         if let Some(ref tags) = package.tags {
-            Self::replace_tags_for_package_internal(&conn, package.id, tags)?;
+            tag_repo::link_tags(&tx, TagTarget::Package, package.id, tags)?;
         }
+        if let Some(owner_id) = package.owner_id {
+            Self::set_owner_internal(&tx, package.id, owner_id)?;
+        }
+        let revision_no = match revision {
+            Some(revision) => EditRevisionRepository::record_in(&tx, "Package", package.id, revision, &package_snapshot(package))?,
+            None => None,
+        };
+        tx.commit()?;
+        Ok(revision_no)
+    }
+
+    /// 同步维护者表中的所有者：原所有者降为维护者
+    fn set_owner_internal(conn: &Connection, package_id: i32, owner_id: i32) -> Result<()> {
+        conn.execute(
+            "UPDATE package_maintainers SET role = 'maintainer' WHERE package_id = ? AND role = 'owner' AND user_id != ?",
+            params![package_id, owner_id],
+        )?;
+        conn.execute(
+            "INSERT INTO package_maintainers (package_id, user_id, role) VALUES (?, ?, 'owner')
+             ON CONFLICT(package_id, user_id) DO UPDATE SET role = 'owner'",
+            params![package_id, owner_id],
+        )?;
         Ok(())
     }
 
-    pub async fn delete_package(&self, package_id: i32) -> Result<()> {
+    /// 删除资源：移入回收站，保留原状态与全部关联记录以便恢复；已在回收站中时返回 false
+    pub async fn soft_delete_package(&self, package_id: i32, deleted_by: i32) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let affected = conn.execute(
+            "UPDATE packages SET status_before_delete = status, status = 'deleted', deleted_at = datetime('now'), deleted_by = ?
+             WHERE id = ? AND status != 'deleted'",
+            params![deleted_by, package_id],
+        )?;
+        Ok(affected > 0)
+    }
+
+    /// 撤销软删除，恢复删除前的状态（文件目录移入回收站失败时回滚）
+    pub async fn undo_soft_delete_package(&self, package_id: i32) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let affected = conn.execute(
+            "UPDATE packages SET status = COALESCE(status_before_delete, 'active'), status_before_delete = NULL,
+                    deleted_at = NULL, deleted_by = NULL
+             WHERE id = ? AND status = 'deleted'",
+            params![package_id],
+        )?;
+        Ok(affected > 0)
+    }
+
+    /// 文件目录移动后只改写资源中的文件地址（file_url、cover_image、screenshots、included_files）
+    pub async fn update_file_urls(&self, package: &Package) -> Result<()> {
+        let conn = self.conn.lock().await;
+        let screenshots_json = package.screenshots.as_ref()
+            .map(|v| serde_json::to_string(v).unwrap_or_else(|_| "[]".to_string()));
+        let included_files_json = package.included_files.as_ref()
+            .map(|v| serde_json::to_string(v).unwrap_or_else(|_| "[]".to_string()));
+        conn.execute(
+            "UPDATE packages SET file_url = ?, cover_image = ?, screenshots = ?, included_files = ? WHERE id = ?",
+            params![package.file_url, package.cover_image, screenshots_json, included_files_json, package.id],
+        )?;
+        Ok(())
+    }
+
+    /// 彻底删除资源及所有关联记录（回收站清理）
+    pub async fn purge_package(&self, package_id: i32) -> Result<()> {
         // 尝试修复历史错误触发器，避免删除时报 NEW.* 列不存在
         let _ = self.fix_broken_triggers().await;
         let conn = self.conn.lock().await;
@@ -432,7 +522,17 @@ impl PackageRepository {
             log::debug!("🗄️ 删除download_records中的相关记录: package_id={}", package_id);
             let _ = conn.execute("DELETE FROM download_records WHERE package_id = ?", params![package_id]).ok();
             
-            // 6. 最后删除packages表中的记录
+            // 6. 删除维护者与未完成的所有权转让
+            log::debug!("🗄️ 删除package_maintainers中的相关记录: package_id={}", package_id);
+            let _ = conn.execute("DELETE FROM package_maintainers WHERE package_id = ?", params![package_id]).ok();
+            let _ = conn.execute("DELETE FROM package_ownership_transfers WHERE package_id = ?", params![package_id]).ok();
+            let _ = conn.execute("DELETE FROM rendered_content WHERE target_type = 'Package' AND target_id = ?", params![package_id]).ok();
+            let _ = conn.execute("DELETE FROM content_revisions WHERE target_type = 'Package' AND target_id = ?", params![package_id]).ok();
+            // 保留其他资源对它的依赖声明，解析时报告为缺失
+            let _ = conn.execute("DELETE FROM package_dependencies WHERE package_id = ?", params![package_id]).ok();
+            let _ = conn.execute("DELETE FROM package_compatibility WHERE package_id = ?", params![package_id]).ok();
+            
+            // 7. 最后删除packages表中的记录
             let sql = "DELETE FROM packages WHERE id = ?";
             log::debug!("🗄️ SQL: delete_package: {} | id={}", sql, package_id);
             match conn.execute(sql, params![package_id]) {
@@ -491,7 +591,7 @@ impl PackageRepository {
         let conn = self.conn.lock().await;
         log::debug!("🔍 get_packages_advanced called");
         log::debug!("🔍 page: {}, page_size: {}, category: {:?}, search: {:?}, status: {:?}", page, page_size, category, search, status);
-        let mut sql = String::from("SELECT id, name, author, version, description, file_url, file_size, download_count, like_count, favorite_count, category_id, status, created_at, updated_at, reviewer_id, reviewed_at, review_comment, is_pinned, is_featured, screenshots, cover_image, requirements FROM packages WHERE 1=1");
+        let mut sql = String::from("SELECT id, name, author, version, description, file_url, file_size, download_count, like_count, favorite_count, category_id, status, created_at, updated_at, reviewer_id, reviewed_at, review_comment, is_pinned, is_featured, screenshots, cover_image, requirements, owner_id FROM packages WHERE 1=1");
         let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
         if let Some(category_id) = category {
             sql.push_str(" AND category_id = ?");
@@ -507,6 +607,9 @@ impl PackageRepository {
         if let Some(ref status) = status {
             sql.push_str(" AND status = ?");
             params.push(Box::new(status.clone()));
+        } else {
+            // 未指定状态时不包含回收站中的资源
+            sql.push_str(" AND status != 'deleted'");
         }
         log::debug!("🔍 SQL before count: {}", sql);
         println!("[DEBUG] Params before count: {:?}", params.len());
@@ -549,6 +652,7 @@ impl PackageRepository {
                 id: row.get(0)?,
                 name: row.get(1)?,
                 author: row.get(2)?,
+                owner_id: row.get(22).ok().flatten(),
                 version: row.get(3)?,
                 description: row.get(4)?,
                 file_url: row.get(5)?,
@@ -606,27 +710,21 @@ impl PackageRepository {
     pub async fn get_categories(&self) -> Result<Vec<Category>> {
         let conn = self.conn.lock().await;
         
-        let mut stmt = conn.prepare("SELECT id, name, description, enabled, subscription_locked, created_at, updated_at FROM categories")?;
+        let mut stmt = conn.prepare(
+            "SELECT id, name, description, enabled, subscription_locked, created_at, updated_at,
+                    parent_id, sort_order, icon, requires_review, allowed_extensions, max_file_size, post_permission
+             FROM categories ORDER BY sort_order, id",
+        )?;
         
-        let categories = stmt.query_map([], |row| {
-            Ok(Category {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                description: row.get(2)?,
-                enabled: row.get(3)?,
-                subscription_locked: row.get(4)?,
-                created_at: row.get(5)?,
-                updated_at: row.get(6)?,
-            })
-        })?
-        .collect::<Result<Vec<_>, _>>()?;
+        let categories = stmt.query_map([], crate::repositories::system_repo::map_category)?
+            .collect::<Result<Vec<_>, _>>()?;
         
         Ok(categories)
     }
 
     pub async fn check_package_exists(&self, package_id: i32) -> Result<bool> {
         let conn = self.conn.lock().await;
-        let sql = "SELECT 1 FROM packages WHERE id = ?";
+        let sql = "SELECT 1 FROM packages WHERE id = ? AND status != 'deleted'";
         log::debug!("🗄️ SQL: check_package_exists: {} | id={}", sql, package_id);
         
         let exists = conn.query_row(
@@ -658,7 +756,7 @@ impl PackageRepository {
         let sql = "SELECT id, name, author, version, description, file_url, file_size, \
                     download_count, like_count, favorite_count, category_id, status, \
                     created_at, updated_at, reviewer_id, reviewed_at, review_comment, \
-                    is_pinned, is_featured, screenshots, cover_image, requirements, included_files \
+                    is_pinned, is_featured, screenshots, cover_image, requirements, included_files, owner_id \
              FROM packages WHERE status = 'active' ORDER BY download_count DESC LIMIT ?";
         let mut stmt = conn.prepare(sql)?;
         let list = stmt.query_map(params![limit], |row| {
@@ -666,6 +764,7 @@ impl PackageRepository {
                 id: row.get(0)?,
                 name: row.get(1)?,
                 author: row.get(2)?,
+                owner_id: row.get(23).ok().flatten(),
                 version: row.get(3)?,
                 description: row.get(4)?,
                 file_url: row.get(5)?,
@@ -718,7 +817,7 @@ impl PackageRepository {
         let sql = "SELECT id, name, author, version, description, file_url, file_size, \
                     download_count, like_count, favorite_count, category_id, status, \
                     created_at, updated_at, reviewer_id, reviewed_at, review_comment, \
-                    is_pinned, is_featured, screenshots, cover_image, requirements, included_files \
+                    is_pinned, is_featured, screenshots, cover_image, requirements, included_files, owner_id \
              FROM packages WHERE status = 'active' ORDER BY like_count DESC, download_count DESC LIMIT ?";
         let mut stmt = conn.prepare(sql)?;
         let list = stmt.query_map(params![limit], |row| {
@@ -726,6 +825,7 @@ impl PackageRepository {
                 id: row.get(0)?,
                 name: row.get(1)?,
                 author: row.get(2)?,
+                owner_id: row.get(23).ok().flatten(),
                 version: row.get(3)?,
                 description: row.get(4)?,
                 file_url: row.get(5)?,
@@ -870,8 +970,8 @@ impl PackageRepository {
         Ok(is_liked)
     }
 
-    // 记录资源访问
-    pub async fn record_view(&self, package_id: i32, user_id: Option<i32>, ip_address: Option<String>, user_agent: Option<String>) -> Result<()> {
+    // 记录资源访问；referrer 为来源，用于作者的数据分析
+    pub async fn record_view(&self, package_id: i32, user_id: Option<i32>, ip_address: Option<String>, user_agent: Option<String>, referrer: Option<String>) -> Result<()> {
         let conn = self.conn.lock().await;
         
         // 防重复访问检查（同一用户/IP在24小时内只记录一次访问）
@@ -897,10 +997,17 @@ impl PackageRepository {
         
         if should_record {
             // 插入访问记录（触发器会自动更新view_count）
-            conn.execute(
-                "INSERT INTO package_views (package_id, user_id, ip_address, user_agent) VALUES (?, ?, ?, ?)",
-                params![package_id, user_id, ip_address, user_agent],
-            )?;
+            if self.has_view_referrer {
+                conn.execute(
+                    "INSERT INTO package_views (package_id, user_id, ip_address, user_agent, referrer) VALUES (?, ?, ?, ?, ?)",
+                    params![package_id, user_id, ip_address, user_agent, referrer],
+                )?;
+            } else {
+                conn.execute(
+                    "INSERT INTO package_views (package_id, user_id, ip_address, user_agent) VALUES (?, ?, ?, ?)",
+                    params![package_id, user_id, ip_address, user_agent],
+                )?;
+            }
             
             // 记录用户行为
             if let Some(uid) = user_id {
@@ -929,30 +1036,12 @@ impl PackageRepository {
         Ok(tags)
     }
 
-    // 替换一个包的标签（先删后插）
+    // 替换一个包的标签：规范化、别名替换与白名单规则见 tag_repo::link_tags，
+    // 只增删有变化的关联，use_count 由触发器在同一事务中维护
     fn replace_tags_for_package_internal(conn: &Connection, package_id: i32, tags: &[String]) -> anyhow::Result<()> {
-        // 删除旧关联
-        conn.execute("DELETE FROM package_tags WHERE package_id = ?", params![package_id])?;
-
-        if tags.is_empty() {
-            return Ok(());
-        }
-
-        // 为每个标签名称找到ID，如果不存在则跳过
-        for tag_name in tags {
-            let tag_id: Option<i32> = conn.query_row(
-                "SELECT id FROM tags WHERE name = ?",
-                params![tag_name],
-                |row| row.get(0),
-            ).optional()?;
-
-            if let Some(id) = tag_id {
-                conn.execute(
-                    "INSERT OR IGNORE INTO package_tags(package_id, tag_id) VALUES(?, ?)",
-                    params![package_id, id],
-                )?;
-            }
-        }
+        let tx = conn.unchecked_transaction()?;
+        tag_repo::link_tags(&tx, TagTarget::Package, package_id, tags)?;
+        tx.commit()?;
         Ok(())
     }
 } 
\ No newline at end of file
@@ -0,0 +1,330 @@
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::models::points::{PointLedgerEntry, PointRule, UpdatePointRuleRequest, UserLevel, LEDGER_RESTORE, LEDGER_REVOKE, RULE_CONTENT_REMOVED};
+use crate::utils::error::ServiceError;
+
+/// 一次积分变动
+pub struct PointGrant<'a> {
+    pub user_id: i32,
+    pub action: &'a str,
+    pub xp: i64,
+    pub stars: i64,
+    pub reason: &'a str,
+    pub related_type: Option<&'a str>,
+    pub related_id: Option<i32>,
+    /// 去重键：同一用户相同键只记一次（例如同一帖子只奖励一次发布）
+    pub dedupe_key: Option<&'a str>,
+    /// 扣减超过余额时只扣到 0（用于收回奖励与处罚）；为 false 时余额不足直接报错
+    pub cap_at_balance: bool,
+}
+
+#[derive(Clone)]
+pub struct PointsRepository {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl PointsRepository {
+    pub fn new(db_path: &str) -> Result<Self> {
+        let conn = Connection::open(db_path)?;
+        let repo = Self { conn: Arc::new(Mutex::new(conn)) };
+        futures::executor::block_on(repo.init())?;
+        Ok(repo)
+    }
+
+    pub async fn init(&self) -> Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS point_rules (
+                action TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                xp INTEGER NOT NULL DEFAULT 0,
+                stars INTEGER NOT NULL DEFAULT 0,
+                daily_cap INTEGER NOT NULL DEFAULT 0,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                updated_at TEXT
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS point_ledger (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id INTEGER NOT NULL,
+                action TEXT NOT NULL,
+                xp INTEGER NOT NULL DEFAULT 0,
+                stars INTEGER NOT NULL DEFAULT 0,
+                reason TEXT NOT NULL DEFAULT '',
+                related_type TEXT,
+                related_id INTEGER,
+                dedupe_key TEXT,
+                created_at TEXT NOT NULL,
+                UNIQUE (user_id, dedupe_key)
+            )",
+            [],
+        )?;
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_point_ledger_user_time ON point_ledger(user_id, created_at)", [])?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS user_levels (
+                level INTEGER PRIMARY KEY,
+                title TEXT NOT NULL,
+                min_xp INTEGER NOT NULL
+            )",
+            [],
+        )?;
+        Ok(())
+    }
+
+    fn map_rule(row: &rusqlite::Row) -> rusqlite::Result<PointRule> {
+        Ok(PointRule {
+            action: row.get(0)?,
+            name: row.get(1)?,
+            xp: row.get(2)?,
+            stars: row.get(3)?,
+            daily_cap: row.get(4)?,
+            enabled: row.get(5)?,
+            updated_at: row.get(6)?,
+        })
+    }
+
+    pub async fn list_rules(&self) -> Result<Vec<PointRule>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare("SELECT action, name, xp, stars, daily_cap, enabled, updated_at FROM point_rules ORDER BY action")?;
+        let rows = stmt.query_map([], Self::map_rule)?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    pub async fn get_rule(&self, action: &str) -> Result<Option<PointRule>> {
+        let conn = self.conn.lock().await;
+        let rule = conn.query_row(
+            "SELECT action, name, xp, stars, daily_cap, enabled, updated_at FROM point_rules WHERE action = ?",
+            params![action],
+            Self::map_rule,
+        ).optional()?;
+        Ok(rule)
+    }
+
+    pub async fn update_rule(&self, action: &str, req: &UpdatePointRuleRequest) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let affected = conn.execute(
+            "UPDATE point_rules SET
+                name = COALESCE(?, name),
+                xp = COALESCE(?, xp),
+                stars = COALESCE(?, stars),
+                daily_cap = COALESCE(?, daily_cap),
+                enabled = COALESCE(?, enabled),
+                updated_at = datetime('now')
+             WHERE action = ?",
+            params![req.name, req.xp, req.stars, req.daily_cap, req.enabled, action],
+        )?;
+        Ok(affected > 0)
+    }
+
+    /// 等级表，按所需经验升序
+    pub async fn list_levels(&self) -> Result<Vec<UserLevel>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare("SELECT level, title, min_xp FROM user_levels ORDER BY min_xp, level")?;
+        let rows = stmt.query_map([], |row| Ok(UserLevel { level: row.get(0)?, title: row.get(1)?, min_xp: row.get(2)? }))?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    pub async fn replace_levels(&self, levels: &[UserLevel]) -> Result<()> {
+        let mut conn = self.conn.lock().await;
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM user_levels", [])?;
+        for l in levels {
+            tx.execute("INSERT INTO user_levels (level, title, min_xp) VALUES (?, ?, ?)", params![l.level, l.title, l.min_xp])?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 某时间点之后用户某个行为已计入的次数（用于每日上限）
+    pub async fn count_since(&self, user_id: i32, action: &str, since: DateTime<Utc>) -> Result<i32> {
+        let conn = self.conn.lock().await;
+        let count = conn.query_row(
+            "SELECT COUNT(*) FROM point_ledger WHERE user_id = ? AND action = ? AND created_at >= ?",
+            params![user_id, action, since.to_rfc3339()],
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+
+    /// 记一笔积分变动并更新用户余额，余额不会被扣成负数（流水记录实际变动值）。
+    /// 去重键已存在时不做任何变动，返回 None
+    pub async fn grant(&self, grant: &PointGrant<'_>) -> Result<Option<PointLedgerEntry>> {
+        let mut conn = self.conn.lock().await;
+        let tx = conn.transaction()?;
+        let entry = Self::grant_in(&tx, grant)?;
+        tx.commit()?;
+        Ok(entry)
+    }
+
+    /// 补签：在同一事务内校验本月已补签次数（按补签操作时间计，month_start 为本月开始时刻）、
+    /// 写入补签记录并按 charge 扣除星星，任一步失败整体回滚
+    pub async fn make_up_check_in(
+        &self,
+        user_id: i32,
+        date: NaiveDate,
+        month_start: DateTime<Utc>,
+        monthly_limit: i64,
+        charge: Option<&PointGrant<'_>>,
+    ) -> Result<Option<PointLedgerEntry>> {
+        let mut conn = self.conn.lock().await;
+        let tx = conn.transaction()?;
+        let used: i64 = tx.query_row(
+            "SELECT COUNT(*) FROM user_check_ins WHERE user_id = ? AND is_makeup = 1 AND created_at >= ?",
+            params![user_id, month_start.format("%Y-%m-%d %H:%M:%S").to_string()],
+            |row| row.get(0),
+        )?;
+        if used >= monthly_limit {
+            return Err(ServiceError::bad_request(format!("本月补签次数已用完（每月 {} 次）", monthly_limit)));
+        }
+        let inserted = tx.execute(
+            "INSERT OR IGNORE INTO user_check_ins (user_id, check_in_date, is_makeup, created_at) VALUES (?, ?, 1, ?)",
+            params![user_id, date.format("%Y-%m-%d").to_string(), Utc::now().format("%Y-%m-%d %H:%M:%S").to_string()],
+        )?;
+        if inserted == 0 {
+            return Err(ServiceError::bad_request("该日期已签到"));
+        }
+        let entry = match charge {
+            Some(grant) => Self::grant_in(&tx, grant)?,
+            None => None,
+        };
+        tx.commit()?;
+        Ok(entry)
+    }
+
+    fn grant_in(tx: &Connection, grant: &PointGrant<'_>) -> Result<Option<PointLedgerEntry>> {
+        if let Some(key) = grant.dedupe_key {
+            let exists: bool = tx.query_row(
+                "SELECT EXISTS(SELECT 1 FROM point_ledger WHERE user_id = ? AND dedupe_key = ?)",
+                params![grant.user_id, key],
+                |row| row.get(0),
+            )?;
+            if exists {
+                return Ok(None);
+            }
+        }
+        let balance: Option<(i64, i64)> = tx.query_row(
+            "SELECT COALESCE(experience, 0), COALESCE(star, 0) FROM users WHERE id = ?",
+            params![grant.user_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ).optional()?;
+        let Some((experience, stars)) = balance else { return Ok(None) };
+        let (xp, stars_delta) = if grant.cap_at_balance {
+            (grant.xp.max(-experience), grant.stars.max(-stars))
+        } else if stars + grant.stars < 0 {
+            return Err(ServiceError::bad_request(format!("星星不足，需要 {} 颗星星，当前 {} 颗", -grant.stars, stars)));
+        } else if experience + grant.xp < 0 {
+            return Err(ServiceError::bad_request(format!("经验不足，需要 {} 点经验，当前 {} 点", -grant.xp, experience)));
+        } else {
+            (grant.xp, grant.stars)
+        };
+        let now = Utc::now();
+        tx.execute(
+            "INSERT INTO point_ledger (user_id, action, xp, stars, reason, related_type, related_id, dedupe_key, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            params![grant.user_id, grant.action, xp, stars_delta, grant.reason, grant.related_type, grant.related_id, grant.dedupe_key, now.to_rfc3339()],
+        )?;
+        let id = tx.last_insert_rowid();
+        tx.execute(
+            "UPDATE users SET experience = COALESCE(experience, 0) + ?, star = COALESCE(star, 0) + ? WHERE id = ?",
+            params![xp, stars_delta, grant.user_id],
+        )?;
+        Ok(Some(PointLedgerEntry {
+            id,
+            user_id: grant.user_id,
+            action: grant.action.to_string(),
+            xp,
+            stars: stars_delta,
+            reason: grant.reason.to_string(),
+            related_type: grant.related_type.map(str::to_string),
+            related_id: grant.related_id,
+            created_at: now,
+        }))
+    }
+
+    /// 某条内容此前为用户带来的奖励合计（不含冲销与处罚）
+    pub async fn sum_content_rewards(&self, user_id: i32, related_type: &str, related_id: i32) -> Result<(i64, i64)> {
+        let conn = self.conn.lock().await;
+        let sums = conn.query_row(
+            "SELECT COALESCE(SUM(xp), 0), COALESCE(SUM(stars), 0) FROM point_ledger
+             WHERE user_id = ? AND related_type = ? AND related_id = ? AND action NOT IN (?, ?, ?)",
+            params![user_id, related_type, related_id, LEDGER_REVOKE, RULE_CONTENT_REMOVED, LEDGER_RESTORE],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        Ok(sums)
+    }
+
+    /// 内容删除时收回与处罚的积分合计（尚未因恢复而退回的部分）
+    pub async fn sum_content_removals(&self, user_id: i32, related_type: &str, related_id: i32) -> Result<(i64, i64)> {
+        let conn = self.conn.lock().await;
+        let sums = conn.query_row(
+            "SELECT COALESCE(SUM(xp), 0), COALESCE(SUM(stars), 0) FROM point_ledger
+             WHERE user_id = ? AND related_type = ? AND related_id = ? AND action IN (?, ?) AND dedupe_key IS NOT NULL",
+            params![user_id, related_type, related_id, LEDGER_REVOKE, RULE_CONTENT_REMOVED],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        Ok(sums)
+    }
+
+    /// 清除删除记录的去重键，内容再次被删除时重新收回
+    pub async fn release_content_removals(&self, user_id: i32, related_type: &str, related_id: i32) -> Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "UPDATE point_ledger SET dedupe_key = NULL
+             WHERE user_id = ? AND related_type = ? AND related_id = ? AND action IN (?, ?)",
+            params![user_id, related_type, related_id, LEDGER_REVOKE, RULE_CONTENT_REMOVED],
+        )?;
+        Ok(())
+    }
+
+    pub async fn list_ledger(&self, user_id: i32, page: i32, page_size: i32) -> Result<(Vec<PointLedgerEntry>, i64)> {
+        let conn = self.conn.lock().await;
+        let total: i64 = conn.query_row("SELECT COUNT(*) FROM point_ledger WHERE user_id = ?", params![user_id], |row| row.get(0))?;
+        let mut stmt = conn.prepare(
+            "SELECT id, user_id, action, xp, stars, reason, related_type, related_id, created_at FROM point_ledger
+             WHERE user_id = ? ORDER BY id DESC LIMIT ? OFFSET ?",
+        )?;
+        let rows = stmt.query_map(params![user_id, page_size, (page - 1).max(0) * page_size], |row| {
+            Ok(PointLedgerEntry {
+                id: row.get(0)?,
+                user_id: row.get(1)?,
+                action: row.get(2)?,
+                xp: row.get(3)?,
+                stars: row.get(4)?,
+                reason: row.get(5)?,
+                related_type: row.get(6)?,
+                related_id: row.get(7)?,
+                created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(8)?)
+                    .map(|d| d.with_timezone(&Utc))
+                    .unwrap_or_else(|_| Utc::now()),
+            })
+        })?;
+        Ok((rows.collect::<Result<Vec<_>, _>>()?, total))
+    }
+
+    /// 用户的经验与星星余额
+    pub async fn get_balances(&self, user_ids: &[i32]) -> Result<HashMap<i32, (i64, i64)>> {
+        let mut map = HashMap::new();
+        if user_ids.is_empty() {
+            return Ok(map);
+        }
+        let conn = self.conn.lock().await;
+        let placeholders = vec!["?"; user_ids.len()].join(",");
+        let sql = format!("SELECT id, COALESCE(experience, 0), COALESCE(star, 0) FROM users WHERE id IN ({})", placeholders);
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(user_ids.iter()), |row| {
+            Ok((row.get::<_, i32>(0)?, (row.get::<_, i64>(1)?, row.get::<_, i64>(2)?)))
+        })?;
+        for row in rows {
+            let (id, balance) = row?;
+            map.insert(id, balance);
+        }
+        Ok(map)
+    }
+}
@@ -1,4 +1,4 @@
-use rusqlite::{Connection, Result, Row};
+use rusqlite::{params, Connection, Result, Row};
 use std::path::Path;
 use crate::models::post::{Post, PostStatus};
 use chrono::{DateTime, Utc};
@@ -81,15 +81,15 @@ impl PostRepository {
         })
     }
 
-    /// 获取帖子排行榜（按浏览量排序）
-    pub fn get_post_ranking(&self, page: u32, page_size: u32) -> Result<(Vec<Post>, i64)> {
+    /// 获取帖子排行榜（按浏览量排序），category_id 为 None 时不限分类
+    pub fn get_post_ranking(&self, page: u32, page_size: u32, category_id: Option<i32>) -> Result<(Vec<Post>, i64)> {
         let conn = self.get_connection()?;
         let offset = (page - 1) * page_size;
 
         // 首先获取总数
         let total: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM posts WHERE status = 'Published'",
-            [],
+            "SELECT COUNT(*) FROM posts WHERE status = 'Published' AND (?1 IS NULL OR category_id = ?1)",
+            params![category_id],
             |row| row.get(0),
         )?;
 
@@ -100,13 +100,13 @@ impl PostRepository {
                    created_at, updated_at, review_status, review_comment, reviewer_id,
                    reviewed_at, images, code_snippet, tags
             FROM posts 
-            WHERE status = 'Published'
+            WHERE status = 'Published' AND (?3 IS NULL OR category_id = ?3)
             ORDER BY view_count DESC, like_count DESC, created_at DESC
-            LIMIT ? OFFSET ?
+            LIMIT ?1 OFFSET ?2
         ";
 
         let mut stmt = conn.prepare(query)?;
-        let posts_iter = stmt.query_map([page_size, offset], Self::map_row_to_post)?;
+        let posts_iter = stmt.query_map(params![page_size, offset, category_id], Self::map_row_to_post)?;
 
         let mut posts = Vec::new();
         for post_result in posts_iter {
@@ -0,0 +1,377 @@
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use rusqlite::{params, Connection, OptionalExtension, ToSql};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::models::ranking::{RankedRow, RankingBoard, RankingRow, RankingWindow};
+
+/// 待汇总的日期范围 [from, to]（本地日期）及对应的 UTC 时间区间
+pub struct RollupRange {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// 社区时区相对 UTC 的秒数
+    pub offset_secs: i32,
+}
+
+#[derive(Clone)]
+pub struct RankingRepository {
+    conn: Arc<Mutex<Connection>>,
+}
+
+fn day(d: NaiveDate) -> String {
+    d.format("%Y-%m-%d").to_string()
+}
+
+/// 统计来源表有的是按需创建的，尚不存在时跳过
+fn exec_if_table(conn: &Connection, sql: &str, params: &[&dyn ToSql]) -> Result<()> {
+    match conn.execute(sql, params) {
+        Ok(_) => Ok(()),
+        Err(e) if e.to_string().contains("no such table") => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn map_row(row: &rusqlite::Row) -> rusqlite::Result<RankingRow> {
+    Ok(RankingRow {
+        entity_id: row.get(0)?,
+        score: row.get(1)?,
+        downloads: row.get(2)?,
+        likes: row.get(3)?,
+        views: row.get(4)?,
+        comments: row.get(5)?,
+        followers: row.get(6)?,
+    })
+}
+
+impl RankingRepository {
+    pub fn new(db_path: &str) -> Result<Self> {
+        let conn = Connection::open(db_path)?;
+        let repo = Self { conn: Arc::new(Mutex::new(conn)) };
+        futures::executor::block_on(repo.init())?;
+        Ok(repo)
+    }
+
+    /// 汇总表由迁移 012 创建（resource_access_stats 的 like_count 列也在其中），这里仅兜底
+    pub async fn init(&self) -> Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS post_daily_stats (
+                post_id INTEGER NOT NULL,
+                date TEXT NOT NULL,
+                view_count INTEGER NOT NULL DEFAULT 0,
+                like_count INTEGER NOT NULL DEFAULT 0,
+                comment_count INTEGER NOT NULL DEFAULT 0,
+                updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+                PRIMARY KEY (post_id, date)
+            );
+            CREATE TABLE IF NOT EXISTS user_daily_stats (
+                user_id INTEGER NOT NULL,
+                date TEXT NOT NULL,
+                xp INTEGER NOT NULL DEFAULT 0,
+                downloads INTEGER NOT NULL DEFAULT 0,
+                likes INTEGER NOT NULL DEFAULT 0,
+                followers_gained INTEGER NOT NULL DEFAULT 0,
+                updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+                PRIMARY KEY (user_id, date)
+            );
+            CREATE TABLE IF NOT EXISTS ranking_counter_state (
+                counter TEXT NOT NULL,
+                entity_id INTEGER NOT NULL,
+                last_value INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (counter, entity_id)
+            );
+            CREATE TABLE IF NOT EXISTS ranking_snapshots (
+                board TEXT NOT NULL,
+                period TEXT NOT NULL,
+                period_key TEXT NOT NULL,
+                category_id INTEGER NOT NULL DEFAULT 0,
+                entity_id INTEGER NOT NULL,
+                rank INTEGER NOT NULL,
+                score INTEGER NOT NULL DEFAULT 0,
+                data TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                PRIMARY KEY (board, period, period_key, category_id, entity_id)
+            );",
+        )?;
+        Ok(())
+    }
+
+    /// 是否已有汇总数据（首次运行时需要回填历史）
+    pub async fn has_rollups(&self) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let exists = conn.query_row("SELECT EXISTS(SELECT 1 FROM user_daily_stats)", [], |row| row.get(0))?;
+        Ok(exists)
+    }
+
+    /// 重新汇总日期范围内可由事件记录得出的数据（下载、点赞、评论、关注、经验），可重复执行
+    pub async fn rollup_events(&self, range: &RollupRange) -> Result<()> {
+        let mut conn = self.conn.lock().await;
+        let tx = conn.transaction()?;
+        let (from, to) = (day(range.from), day(range.to));
+        let (start, end) = (
+            range.start.format("%Y-%m-%d %H:%M:%S").to_string(),
+            range.end.format("%Y-%m-%d %H:%M:%S").to_string(),
+        );
+        let offset = format!("{:+} seconds", range.offset_secs);
+        let p: [&dyn ToSql; 3] = [&offset, &start, &end];
+
+        tx.execute(
+            "UPDATE resource_access_stats SET download_count = 0, unique_downloaders = 0, like_count = 0 WHERE date BETWEEN ? AND ?",
+            params![from, to],
+        )?;
+        tx.execute(
+            "UPDATE post_daily_stats SET like_count = 0, comment_count = 0 WHERE date BETWEEN ? AND ?",
+            params![from, to],
+        )?;
+        tx.execute(
+            "UPDATE user_daily_stats SET xp = 0, downloads = 0, likes = 0, followers_gained = 0 WHERE date BETWEEN ? AND ?",
+            params![from, to],
+        )?;
+
+        // 资源
+        exec_if_table(&tx,
+            "INSERT INTO resource_access_stats (package_id, date, download_count, unique_downloaders, updated_at)
+             SELECT package_id, date(datetime(created_at), ?1) AS d, COUNT(*),
+                    COUNT(DISTINCT COALESCE(CAST(user_id AS TEXT), ip_address)), datetime('now')
+             FROM download_records WHERE datetime(created_at) >= ?2 AND datetime(created_at) < ?3
+             GROUP BY package_id, d
+             ON CONFLICT(package_id, date) DO UPDATE SET download_count = excluded.download_count,
+                unique_downloaders = excluded.unique_downloaders, updated_at = excluded.updated_at",
+            &p)?;
+        exec_if_table(&tx,
+            "INSERT INTO resource_access_stats (package_id, date, like_count, updated_at)
+             SELECT package_id, date(datetime(created_at), ?1) AS d, COUNT(*), datetime('now')
+             FROM package_likes WHERE datetime(created_at) >= ?2 AND datetime(created_at) < ?3
+             GROUP BY package_id, d
+             ON CONFLICT(package_id, date) DO UPDATE SET like_count = excluded.like_count, updated_at = excluded.updated_at",
+            &p)?;
+
+        // 帖子
+        exec_if_table(&tx,
+            "INSERT INTO post_daily_stats (post_id, date, like_count, updated_at)
+             SELECT post_id, date(datetime(created_at), ?1) AS d, COUNT(*), datetime('now')
+             FROM post_likes WHERE datetime(created_at) >= ?2 AND datetime(created_at) < ?3
+             GROUP BY post_id, d
+             ON CONFLICT(post_id, date) DO UPDATE SET like_count = excluded.like_count, updated_at = excluded.updated_at",
+            &p)?;
+        exec_if_table(&tx,
+            "INSERT INTO post_daily_stats (post_id, date, comment_count, updated_at)
+             SELECT target_id, date(datetime(created_at), ?1) AS d, COUNT(*), datetime('now')
+             FROM comments WHERE target_type = 'Post' AND datetime(created_at) >= ?2 AND datetime(created_at) < ?3
+             GROUP BY target_id, d
+             ON CONFLICT(post_id, date) DO UPDATE SET comment_count = excluded.comment_count, updated_at = excluded.updated_at",
+            &p)?;
+
+        // 用户：多个来源累加到同一行
+        exec_if_table(&tx,
+            "INSERT INTO user_daily_stats (user_id, date, xp, updated_at)
+             SELECT user_id, date(datetime(created_at), ?1) AS d, SUM(xp), datetime('now')
+             FROM point_ledger WHERE datetime(created_at) >= ?2 AND datetime(created_at) < ?3
+             GROUP BY user_id, d
+             ON CONFLICT(user_id, date) DO UPDATE SET xp = xp + excluded.xp, updated_at = excluded.updated_at",
+            &p)?;
+        exec_if_table(&tx,
+            "INSERT INTO user_daily_stats (user_id, date, downloads, updated_at)
+             SELECT u.id, date(datetime(r.created_at), ?1) AS d, COUNT(*), datetime('now')
+             FROM download_records r JOIN packages p ON p.id = r.package_id JOIN users u ON u.id = p.owner_id
+             WHERE datetime(r.created_at) >= ?2 AND datetime(r.created_at) < ?3
+             GROUP BY u.id, d
+             ON CONFLICT(user_id, date) DO UPDATE SET downloads = downloads + excluded.downloads, updated_at = excluded.updated_at",
+            &p)?;
+        exec_if_table(&tx,
+            "INSERT INTO user_daily_stats (user_id, date, likes, updated_at)
+             SELECT p.author_id, date(datetime(l.created_at), ?1) AS d, COUNT(*), datetime('now')
+             FROM post_likes l JOIN posts p ON p.id = l.post_id
+             WHERE l.user_id != p.author_id AND datetime(l.created_at) >= ?2 AND datetime(l.created_at) < ?3
+             GROUP BY p.author_id, d
+             ON CONFLICT(user_id, date) DO UPDATE SET likes = likes + excluded.likes, updated_at = excluded.updated_at",
+            &p)?;
+        exec_if_table(&tx,
+            "INSERT INTO user_daily_stats (user_id, date, likes, updated_at)
+             SELECT u.id, date(datetime(l.created_at), ?1) AS d, COUNT(*), datetime('now')
+             FROM package_likes l JOIN packages p ON p.id = l.package_id JOIN users u ON u.id = p.owner_id
+             WHERE l.user_id != u.id AND datetime(l.created_at) >= ?2 AND datetime(l.created_at) < ?3
+             GROUP BY u.id, d
+             ON CONFLICT(user_id, date) DO UPDATE SET likes = likes + excluded.likes, updated_at = excluded.updated_at",
+            &p)?;
+        exec_if_table(&tx,
+            "INSERT INTO user_daily_stats (user_id, date, followers_gained, updated_at)
+             SELECT followed_id, date(datetime(created_at), ?1) AS d, COUNT(*), datetime('now')
+             FROM user_follows WHERE datetime(created_at) >= ?2 AND datetime(created_at) < ?3
+             GROUP BY followed_id, d
+             ON CONFLICT(user_id, date) DO UPDATE SET followers_gained = followers_gained + excluded.followers_gained,
+                updated_at = excluded.updated_at",
+            &p)?;
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 浏览量只有累计计数：把自上次汇总以来的增量计入 today。首次见到的内容只记录基准值
+    pub async fn rollup_view_counters(&self, today: NaiveDate) -> Result<()> {
+        let mut conn = self.conn.lock().await;
+        let tx = conn.transaction()?;
+        let today = day(today);
+        tx.execute(
+            "INSERT INTO resource_access_stats (package_id, date, view_count, updated_at)
+             SELECT p.id, ?1, COALESCE(p.view_count, 0) - s.last_value, datetime('now')
+             FROM packages p JOIN ranking_counter_state s ON s.counter = 'package_views' AND s.entity_id = p.id
+             WHERE COALESCE(p.view_count, 0) > s.last_value
+             ON CONFLICT(package_id, date) DO UPDATE SET view_count = view_count + excluded.view_count, updated_at = excluded.updated_at",
+            params![today],
+        )?;
+        tx.execute(
+            "INSERT INTO ranking_counter_state (counter, entity_id, last_value)
+             SELECT 'package_views', id, COALESCE(view_count, 0) FROM packages WHERE true
+             ON CONFLICT(counter, entity_id) DO UPDATE SET last_value = excluded.last_value",
+            [],
+        )?;
+        tx.execute(
+            "INSERT INTO post_daily_stats (post_id, date, view_count, updated_at)
+             SELECT p.id, ?1, COALESCE(p.view_count, 0) - s.last_value, datetime('now')
+             FROM posts p JOIN ranking_counter_state s ON s.counter = 'post_views' AND s.entity_id = p.id
+             WHERE COALESCE(p.view_count, 0) > s.last_value
+             ON CONFLICT(post_id, date) DO UPDATE SET view_count = view_count + excluded.view_count, updated_at = excluded.updated_at",
+            params![today],
+        )?;
+        tx.execute(
+            "INSERT INTO ranking_counter_state (counter, entity_id, last_value)
+             SELECT 'post_views', id, COALESCE(view_count, 0) FROM posts WHERE true
+             ON CONFLICT(counter, entity_id) DO UPDATE SET last_value = excluded.last_value",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 窗口内的完整排行（按主指标降序），category_id 仅对资源与帖子生效
+    pub async fn window_rows(&self, board: RankingBoard, window: &RankingWindow, category_id: Option<i32>) -> Result<Vec<RankingRow>> {
+        let conn = self.conn.lock().await;
+        let sql = match board {
+            RankingBoard::Users =>
+                "SELECT s.user_id, SUM(s.xp) AS score, SUM(s.downloads), SUM(s.likes) AS lk, 0, 0, SUM(s.followers_gained)
+                 FROM user_daily_stats s JOIN users u ON u.id = s.user_id
+                 WHERE s.date BETWEEN ?1 AND ?2 AND ?3 IS NULL
+                 GROUP BY s.user_id
+                 HAVING SUM(s.xp) > 0 OR SUM(s.downloads) > 0 OR SUM(s.likes) > 0 OR SUM(s.followers_gained) > 0
+                 ORDER BY score DESC, lk DESC, SUM(s.downloads) DESC, s.user_id",
+            RankingBoard::Resources =>
+                "SELECT s.package_id, SUM(s.download_count) AS score, SUM(s.download_count), SUM(s.like_count) AS lk, SUM(s.view_count) AS vw, 0, 0
+                 FROM resource_access_stats s JOIN packages p ON p.id = s.package_id
+                 WHERE p.status = 'active' AND s.date BETWEEN ?1 AND ?2 AND (?3 IS NULL OR p.category_id = ?3)
+                 GROUP BY s.package_id
+                 HAVING score > 0 OR lk > 0 OR vw > 0
+                 ORDER BY score DESC, lk DESC, vw DESC, s.package_id",
+            RankingBoard::Posts =>
+                "SELECT s.post_id, SUM(s.view_count) AS score, 0, SUM(s.like_count) AS lk, SUM(s.view_count), SUM(s.comment_count) AS cm, 0
+                 FROM post_daily_stats s JOIN posts p ON p.id = s.post_id
+                 WHERE p.status = 'Published' AND s.date BETWEEN ?1 AND ?2 AND (?3 IS NULL OR p.category_id = ?3)
+                 GROUP BY s.post_id
+                 HAVING score > 0 OR lk > 0 OR cm > 0
+                 ORDER BY score DESC, lk DESC, cm DESC, s.post_id",
+        };
+        let mut stmt = conn.prepare(sql)?;
+        let rows = stmt.query_map(params![day(window.start), day(window.end), category_id], map_row)?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// 有内容的分类（用于生成分类榜快照）
+    pub async fn category_ids(&self, board: RankingBoard) -> Result<Vec<i32>> {
+        let conn = self.conn.lock().await;
+        let sql = match board {
+            RankingBoard::Resources => "SELECT DISTINCT category_id FROM packages WHERE category_id IS NOT NULL AND status = 'active'",
+            RankingBoard::Posts => "SELECT DISTINCT category_id FROM posts WHERE category_id IS NOT NULL AND status = 'Published'",
+            RankingBoard::Users => return Ok(Vec::new()),
+        };
+        let mut stmt = conn.prepare(sql)?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    pub async fn has_snapshot(&self, board: RankingBoard, window: &RankingWindow, category_id: i32) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let exists: Option<i32> = conn.query_row(
+            "SELECT 1 FROM ranking_snapshots WHERE board = ? AND period = ? AND period_key = ? AND category_id = ? LIMIT 1",
+            params![board.as_str(), window.period.as_str(), window.key, category_id],
+            |row| row.get(0),
+        ).optional()?;
+        Ok(exists.is_some())
+    }
+
+    /// 保存快照（覆盖同一期已有数据）；空榜也写入一行占位，避免重复生成
+    pub async fn save_snapshot(&self, board: RankingBoard, window: &RankingWindow, category_id: i32, rows: &[RankedRow]) -> Result<()> {
+        let mut conn = self.conn.lock().await;
+        let tx = conn.transaction()?;
+        tx.execute(
+            "DELETE FROM ranking_snapshots WHERE board = ? AND period = ? AND period_key = ? AND category_id = ?",
+            params![board.as_str(), window.period.as_str(), window.key, category_id],
+        )?;
+        for r in rows {
+            tx.execute(
+                "INSERT INTO ranking_snapshots (board, period, period_key, category_id, entity_id, rank, score, data)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                params![board.as_str(), window.period.as_str(), window.key, category_id, r.row.entity_id, r.rank, r.row.score, serde_json::to_string(r)?],
+            )?;
+        }
+        if rows.is_empty() {
+            tx.execute(
+                "INSERT INTO ranking_snapshots (board, period, period_key, category_id, entity_id, rank, score, data)
+                 VALUES (?, ?, ?, ?, 0, 0, 0, '{}')",
+                params![board.as_str(), window.period.as_str(), window.key, category_id],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 读取快照（分页）
+    pub async fn load_snapshot(
+        &self,
+        board: RankingBoard,
+        window: &RankingWindow,
+        category_id: i32,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<RankedRow>, i64)> {
+        let conn = self.conn.lock().await;
+        let key: [&dyn ToSql; 4] = [&board.as_str(), &window.period.as_str(), &window.key, &category_id];
+        let total: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM ranking_snapshots WHERE board = ? AND period = ? AND period_key = ? AND category_id = ? AND rank > 0",
+            &key[..],
+            |row| row.get(0),
+        )?;
+        let mut stmt = conn.prepare(
+            "SELECT rank, data FROM ranking_snapshots
+             WHERE board = ?1 AND period = ?2 AND period_key = ?3 AND category_id = ?4 AND rank > 0
+             ORDER BY rank LIMIT ?5 OFFSET ?6",
+        )?;
+        let rows = stmt.query_map(params![board.as_str(), window.period.as_str(), window.key, category_id, limit, offset], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?;
+        let mut list = Vec::new();
+        for row in rows {
+            let (rank, data) = row?;
+            let mut ranked: RankedRow = serde_json::from_str(&data)?;
+            ranked.rank = rank;
+            list.push(ranked);
+        }
+        Ok((list, total))
+    }
+
+    /// 快照中的名次（用于计算名次变化）
+    pub async fn snapshot_ranks(&self, board: RankingBoard, window: &RankingWindow, category_id: i32) -> Result<HashMap<i32, i64>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(
+            "SELECT entity_id, rank FROM ranking_snapshots
+             WHERE board = ? AND period = ? AND period_key = ? AND category_id = ? AND rank > 0",
+        )?;
+        let rows = stmt.query_map(params![board.as_str(), window.period.as_str(), window.key, category_id], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })?;
+        Ok(rows.collect::<Result<HashMap<_, _>, _>>()?)
+    }
+}
@@ -0,0 +1,259 @@
+use anyhow::Result;
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::models::rating::{PackageRating, RatingSummary, REVIEW_VISIBLE};
+use crate::utils::time::parse_db_time;
+
+const RATING_COLUMNS: &str = "r.id, r.package_id, r.user_id, u.username, u.nickname, u.avatar_url, r.rating, r.review, \
+     r.review_status, r.moderation_reason, r.author_reply, r.author_replied_at, r.created_at, r.updated_at";
+
+#[derive(Clone)]
+pub struct RatingRepository {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl RatingRepository {
+    pub fn new(db_path: &str) -> Result<Self> {
+        let conn = Connection::open(db_path)?;
+        let repo = Self { conn: Arc::new(Mutex::new(conn)) };
+        futures::executor::block_on(repo.init())?;
+        Ok(repo)
+    }
+
+    pub async fn init(&self) -> Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS package_ratings (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                package_id INTEGER NOT NULL,
+                user_id INTEGER NOT NULL,
+                rating INTEGER NOT NULL CHECK (rating BETWEEN 1 AND 5),
+                review TEXT,
+                review_status TEXT NOT NULL DEFAULT 'visible',
+                moderation_reason TEXT,
+                author_reply TEXT,
+                author_replied_at TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                UNIQUE (package_id, user_id)
+            )",
+            [],
+        )?;
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_package_ratings_package ON package_ratings(package_id)", [])?;
+        Ok(())
+    }
+
+    fn map_rating(row: &rusqlite::Row) -> rusqlite::Result<PackageRating> {
+        Ok(PackageRating {
+            id: row.get(0)?,
+            package_id: row.get(1)?,
+            user_id: row.get(2)?,
+            username: row.get(3)?,
+            nickname: row.get(4)?,
+            avatar_url: row.get(5)?,
+            rating: row.get(6)?,
+            review: row.get(7)?,
+            review_status: row.get(8)?,
+            moderation_reason: row.get(9)?,
+            author_reply: row.get(10)?,
+            author_replied_at: row.get::<_, Option<String>>(11)?.and_then(|s| parse_db_time(&s)),
+            created_at: parse_db_time(&row.get::<_, String>(12)?).unwrap_or_else(Utc::now),
+            updated_at: parse_db_time(&row.get::<_, String>(13)?).unwrap_or_else(Utc::now),
+        })
+    }
+
+    /// 用户是否下载过该资源（登录状态下的下载会记录到 download_records）
+    pub async fn has_downloaded(&self, user_id: i32, package_id: i32) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let count: i32 = conn.query_row(
+            "SELECT COUNT(*) FROM download_records WHERE user_id = ? AND package_id = ?",
+            params![user_id, package_id],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    }
+
+    /// 新增或更新评分，返回评分ID；评价文字变更时重置审核状态与原因，旧的审核结论不沿用到新内容
+    pub async fn upsert_rating(&self, package_id: i32, user_id: i32, rating: i32, review: Option<&str>) -> Result<i32> {
+        let conn = self.conn.lock().await;
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO package_ratings (package_id, user_id, rating, review, review_status, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6)
+             ON CONFLICT(package_id, user_id) DO UPDATE SET
+                rating = excluded.rating, review = excluded.review, updated_at = excluded.updated_at,
+                review_status = CASE WHEN review IS excluded.review THEN review_status ELSE excluded.review_status END,
+                moderation_reason = CASE WHEN review IS excluded.review THEN moderation_reason ELSE NULL END",
+            params![package_id, user_id, rating, review, REVIEW_VISIBLE, now],
+        )?;
+        let id = conn.query_row(
+            "SELECT id FROM package_ratings WHERE package_id = ? AND user_id = ?",
+            params![package_id, user_id],
+            |row| row.get(0),
+        )?;
+        Ok(id)
+    }
+
+    pub async fn delete_rating(&self, package_id: i32, user_id: i32) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let affected = conn.execute(
+            "DELETE FROM package_ratings WHERE package_id = ? AND user_id = ?",
+            params![package_id, user_id],
+        )?;
+        Ok(affected > 0)
+    }
+
+    pub async fn get_rating(&self, id: i32) -> Result<Option<PackageRating>> {
+        let conn = self.conn.lock().await;
+        let sql = format!("SELECT {} FROM package_ratings r LEFT JOIN users u ON u.id = r.user_id WHERE r.id = ?", RATING_COLUMNS);
+        let rating = conn.query_row(&sql, params![id], Self::map_rating).optional()?;
+        Ok(rating)
+    }
+
+    pub async fn get_user_rating(&self, package_id: i32, user_id: i32) -> Result<Option<PackageRating>> {
+        let conn = self.conn.lock().await;
+        let sql = format!(
+            "SELECT {} FROM package_ratings r LEFT JOIN users u ON u.id = r.user_id WHERE r.package_id = ? AND r.user_id = ?",
+            RATING_COLUMNS
+        );
+        let rating = conn.query_row(&sql, params![package_id, user_id], Self::map_rating).optional()?;
+        Ok(rating)
+    }
+
+    /// 分页列出资源评分，按更新时间倒序
+    pub async fn list_ratings(&self, package_id: i32, star: Option<i32>, page: i32, page_size: i32) -> Result<(Vec<PackageRating>, i64)> {
+        let conn = self.conn.lock().await;
+        let star_filter = if star.is_some() { " AND r.rating = ?2" } else { "" };
+        let total: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM package_ratings r WHERE r.package_id = ?1{}", star_filter),
+            rusqlite::params_from_iter(std::iter::once(package_id).chain(star)),
+            |row| row.get(0),
+        )?;
+        let sql = format!(
+            "SELECT {} FROM package_ratings r LEFT JOIN users u ON u.id = r.user_id WHERE r.package_id = ?1{} 
+             ORDER BY r.updated_at DESC LIMIT {} OFFSET {}",
+            RATING_COLUMNS, star_filter, page_size, (page - 1).max(0) * page_size
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(std::iter::once(package_id).chain(star)), Self::map_rating)?;
+        Ok((rows.collect::<Result<Vec<_>, _>>()?, total))
+    }
+
+    /// 管理员：按审核状态/资源筛选评价（仅含文字评价）
+    pub async fn list_reviews(&self, status: Option<&str>, package_id: Option<i32>, page: i32, page_size: i32) -> Result<(Vec<PackageRating>, i64)> {
+        let conn = self.conn.lock().await;
+        let mut conditions = vec!["r.review IS NOT NULL AND r.review != ''".to_string()];
+        let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+        if let Some(status) = status {
+            conditions.push("r.review_status = ?".to_string());
+            values.push(Box::new(status.to_string()));
+        }
+        if let Some(package_id) = package_id {
+            conditions.push("r.package_id = ?".to_string());
+            values.push(Box::new(package_id));
+        }
+        let where_clause = conditions.join(" AND ");
+        let total: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM package_ratings r WHERE {}", where_clause),
+            rusqlite::params_from_iter(values.iter()),
+            |row| row.get(0),
+        )?;
+        let sql = format!(
+            "SELECT {} FROM package_ratings r LEFT JOIN users u ON u.id = r.user_id WHERE {} 
+             ORDER BY r.updated_at DESC LIMIT {} OFFSET {}",
+            RATING_COLUMNS, where_clause, page_size, (page - 1).max(0) * page_size
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(values.iter()), Self::map_rating)?;
+        Ok((rows.collect::<Result<Vec<_>, _>>()?, total))
+    }
+
+    pub async fn set_author_reply(&self, id: i32, reply: Option<&str>) -> Result<()> {
+        let conn = self.conn.lock().await;
+        let replied_at = reply.map(|_| Utc::now().to_rfc3339());
+        conn.execute(
+            "UPDATE package_ratings SET author_reply = ?, author_replied_at = ? WHERE id = ?",
+            params![reply, replied_at, id],
+        )?;
+        Ok(())
+    }
+
+    pub async fn set_review_status(&self, id: i32, status: &str, reason: Option<&str>) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let affected = conn.execute(
+            "UPDATE package_ratings SET review_status = ?, moderation_reason = ? WHERE id = ?",
+            params![status, reason, id],
+        )?;
+        Ok(affected > 0)
+    }
+
+    /// 重新计算资源的评分汇总并写回 packages 表
+    pub async fn refresh_summary(&self, package_id: i32) -> Result<RatingSummary> {
+        let conn = self.conn.lock().await;
+        let summary = Self::compute_summary(&conn, package_id)?;
+        conn.execute(
+            "UPDATE packages SET rating_avg = ?, rating_count = ?, rating_distribution = ? WHERE id = ?",
+            params![
+                summary.average,
+                summary.count,
+                serde_json::to_string(&summary.distribution)?,
+                package_id
+            ],
+        )?;
+        Ok(summary)
+    }
+
+    fn compute_summary(conn: &Connection, package_id: i32) -> Result<RatingSummary> {
+        let mut stmt = conn.prepare("SELECT rating, COUNT(*) FROM package_ratings WHERE package_id = ? GROUP BY rating")?;
+        let rows = stmt.query_map(params![package_id], |row| Ok((row.get::<_, i32>(0)?, row.get::<_, i32>(1)?)))?;
+        let mut summary = RatingSummary::default();
+        let mut total = 0i64;
+        for row in rows {
+            let (star, count) = row?;
+            if (1..=5).contains(&star) {
+                summary.distribution[(star - 1) as usize] = count;
+                summary.count += count;
+                total += star as i64 * count as i64;
+            }
+        }
+        if summary.count > 0 {
+            summary.average = ((total as f64 / summary.count as f64) * 100.0).round() / 100.0;
+        }
+        Ok(summary)
+    }
+
+    /// 读取 packages 表中冗余的评分汇总
+    pub async fn get_summaries(&self, package_ids: &[i32]) -> Result<HashMap<i32, RatingSummary>> {
+        let mut map = HashMap::new();
+        if package_ids.is_empty() {
+            return Ok(map);
+        }
+        let conn = self.conn.lock().await;
+        let placeholders = vec!["?"; package_ids.len()].join(",");
+        let sql = format!(
+            "SELECT id, COALESCE(rating_avg, 0), COALESCE(rating_count, 0), rating_distribution FROM packages WHERE id IN ({})",
+            placeholders
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(package_ids.iter()), |row| {
+            Ok((row.get::<_, i32>(0)?, row.get::<_, f64>(1)?, row.get::<_, i32>(2)?, row.get::<_, Option<String>>(3)?))
+        })?;
+        for row in rows {
+            let (id, average, count, distribution) = row?;
+            let distribution = distribution.and_then(|s| serde_json::from_str(&s).ok()).unwrap_or_default();
+            map.insert(id, RatingSummary { average, count, distribution });
+        }
+        Ok(map)
+    }
+
+    /// 全站平均评分，用于贝叶斯加权
+    pub async fn global_average(&self) -> Result<f64> {
+        let conn = self.conn.lock().await;
+        let avg: Option<f64> = conn.query_row("SELECT AVG(rating) FROM package_ratings", [], |row| row.get(0))?;
+        Ok(avg.unwrap_or(0.0))
+    }
+}
@@ -0,0 +1,164 @@
+use anyhow::Result;
+use rusqlite::{params, Connection};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::models::recommendation::{Interaction, InteractionKind, PackageSimilarity, UserRecommendation};
+
+#[derive(Clone)]
+pub struct RecommendationRepository {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl RecommendationRepository {
+    pub fn new(db_path: &str) -> Result<Self> {
+        let conn = Connection::open(db_path)?;
+        Ok(Self { conn: Arc::new(Mutex::new(conn)) })
+    }
+
+    /// 登录用户对已上架资源的全部交互（下载记录去重）
+    pub async fn load_interactions(&self) -> Result<Vec<Interaction>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(
+            "SELECT i.user_id, i.package_id, i.kind FROM (
+                SELECT DISTINCT user_id, package_id, 0 AS kind FROM download_records WHERE user_id IS NOT NULL
+                UNION ALL SELECT user_id, package_id, 1 FROM package_likes
+                UNION ALL SELECT user_id, package_id, 2 FROM package_favorites
+             ) i JOIN packages p ON p.id = i.package_id
+             WHERE p.status = 'active'",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            let kind = match row.get::<_, i32>(2)? {
+                0 => InteractionKind::Download,
+                1 => InteractionKind::Like,
+                _ => InteractionKind::Favorite,
+            };
+            Ok(Interaction { user_id: row.get(0)?, package_id: row.get(1)?, kind })
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// 已上架资源的标签集合
+    pub async fn load_package_tags(&self) -> Result<HashMap<i32, HashSet<i32>>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(
+            "SELECT t.package_id, t.tag_id FROM package_tags t JOIN packages p ON p.id = t.package_id
+             WHERE p.status = 'active'",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, i32>(0)?, row.get::<_, i32>(1)?)))?;
+        let mut tags: HashMap<i32, HashSet<i32>> = HashMap::new();
+        for row in rows {
+            let (package_id, tag_id) = row?;
+            tags.entry(package_id).or_default().insert(tag_id);
+        }
+        Ok(tags)
+    }
+
+    /// 用户自己发布的资源（不推荐给本人）
+    pub async fn load_own_packages(&self) -> Result<HashMap<i32, HashSet<i32>>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(
+            "SELECT owner_id, id FROM packages WHERE owner_id IS NOT NULL AND status = 'active'",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, i32>(0)?, row.get::<_, i32>(1)?)))?;
+        let mut own: HashMap<i32, HashSet<i32>> = HashMap::new();
+        for row in rows {
+            let (user_id, package_id) = row?;
+            own.entry(user_id).or_default().insert(package_id);
+        }
+        Ok(own)
+    }
+
+    /// 整表替换相似资源
+    pub async fn replace_similarities(&self, list: &[PackageSimilarity]) -> Result<()> {
+        let mut conn = self.conn.lock().await;
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM package_similarities", [])?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO package_similarities (package_id, similar_id, score, co_downloads, co_likes, shared_tags)
+                 VALUES (?, ?, ?, ?, ?, ?)",
+            )?;
+            for s in list {
+                stmt.execute(params![s.package_id, s.similar_id, s.score, s.co_downloads, s.co_likes, s.shared_tags])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 整表替换个人推荐
+    pub async fn replace_user_recommendations(&self, list: &[UserRecommendation]) -> Result<()> {
+        let mut conn = self.conn.lock().await;
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM user_recommendations", [])?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO user_recommendations (user_id, package_id, score, source_package_id) VALUES (?, ?, ?, ?)",
+            )?;
+            for r in list {
+                stmt.execute(params![r.user_id, r.package_id, r.score, r.source_package_id])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub async fn get_similar(&self, package_id: i32, limit: i32) -> Result<Vec<PackageSimilarity>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(
+            "SELECT s.package_id, s.similar_id, s.score, s.co_downloads, s.co_likes, s.shared_tags
+             FROM package_similarities s JOIN packages p ON p.id = s.similar_id
+             WHERE s.package_id = ? AND p.status = 'active'
+             ORDER BY s.score DESC LIMIT ?",
+        )?;
+        let rows = stmt.query_map(params![package_id, limit], |row| {
+            Ok(PackageSimilarity {
+                package_id: row.get(0)?,
+                similar_id: row.get(1)?,
+                score: row.get(2)?,
+                co_downloads: row.get(3)?,
+                co_likes: row.get(4)?,
+                shared_tags: row.get(5)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// 读取个人推荐，跳过计算之后已交互过的资源
+    pub async fn get_user_recommendations(&self, user_id: i32, limit: i32) -> Result<Vec<UserRecommendation>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(
+            "SELECT r.user_id, r.package_id, r.score, r.source_package_id
+             FROM user_recommendations r JOIN packages p ON p.id = r.package_id
+             WHERE r.user_id = ?1 AND p.status = 'active'
+               AND NOT EXISTS (SELECT 1 FROM download_records d WHERE d.user_id = ?1 AND d.package_id = r.package_id)
+               AND NOT EXISTS (SELECT 1 FROM package_likes l WHERE l.user_id = ?1 AND l.package_id = r.package_id)
+               AND NOT EXISTS (SELECT 1 FROM package_favorites f WHERE f.user_id = ?1 AND f.package_id = r.package_id)
+             ORDER BY r.score DESC LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(params![user_id, limit], |row| {
+            Ok(UserRecommendation {
+                user_id: row.get(0)?,
+                package_id: row.get(1)?,
+                score: row.get(2)?,
+                source_package_id: row.get(3)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// 同分类按下载量排序的资源（没有相似数据时的兜底）
+    pub async fn popular_in_category(&self, package_id: i32, limit: i32) -> Result<Vec<i32>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(
+            "SELECT p.id FROM packages p
+             WHERE p.status = 'active' AND p.id != ?1
+               AND p.category_id = (SELECT category_id FROM packages WHERE id = ?1)
+             ORDER BY p.download_count DESC, p.like_count DESC LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(params![package_id, limit], |row| row.get(0))?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+}
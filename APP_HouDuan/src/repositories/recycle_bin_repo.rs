@@ -0,0 +1,226 @@
+use anyhow::Result;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::models::recycle_bin::{RecycleBinItem, DEFAULT_RETENTION_DAYS, RECYCLE_RETENTION_KEY};
+
+/// 回收站中的全部内容：(类型, id, 标题, 作者, 删除时间, 删除人)；
+/// 早于软删除的数据可能没有 deleted_at，以最后更新时间代替
+const RECYCLED_SQL: &str = "
+    SELECT 'Package' AS item_type, id, name AS title, owner_id, COALESCE(deleted_at, updated_at) AS deleted_at, deleted_by
+    FROM packages WHERE status = 'deleted'
+    UNION ALL
+    SELECT 'Post', id, title, author_id, COALESCE(deleted_at, updated_at), deleted_by
+    FROM posts WHERE status = 'Deleted'
+    UNION ALL
+    SELECT 'Comment', id, substr(content, 1, 80), user_id, COALESCE(deleted_at, updated_at), deleted_by
+    FROM comments WHERE status = 'Deleted'
+    UNION ALL
+    SELECT 'User', id, COALESCE(nickname, username), NULL, deleted_at, deleted_by
+    FROM users WHERE deleted_at IS NOT NULL";
+
+fn map_item(row: &rusqlite::Row) -> rusqlite::Result<RecycleBinItem> {
+    Ok(RecycleBinItem {
+        item_type: row.get(0)?,
+        id: row.get(1)?,
+        title: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+        owner_id: row.get(3)?,
+        owner_name: row.get(4)?,
+        deleted_at: row.get::<_, Option<String>>(5)?.unwrap_or_default(),
+        deleted_by: row.get(6)?,
+        deleted_by_name: row.get(7)?,
+        purge_at: row.get::<_, Option<String>>(8)?.unwrap_or_default(),
+    })
+}
+
+/// 回收站：跨资源、帖子、评论、用户的列表、恢复与到期查询
+#[derive(Clone)]
+pub struct RecycleBinRepository {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl RecycleBinRepository {
+    pub fn new(db_path: &str) -> Result<Self> {
+        let conn = Connection::open(db_path)?;
+        Ok(Self { conn: Arc::new(Mutex::new(conn)) })
+    }
+
+    fn select_sql(where_clause: &str) -> String {
+        format!(
+            "SELECT r.item_type, r.id, r.title, r.owner_id, COALESCE(o.nickname, o.username),
+                    datetime(r.deleted_at), r.deleted_by, COALESCE(d.nickname, d.username),
+                    datetime(r.deleted_at, ?1)
+             FROM ({RECYCLED_SQL}) r
+             LEFT JOIN users o ON o.id = r.owner_id
+             LEFT JOIN users d ON d.id = r.deleted_by
+             WHERE {where_clause}"
+        )
+    }
+
+    /// 按删除时间倒序分页；retention 为到期时间的修饰符，如 '+30 days'
+    pub async fn list(
+        &self,
+        item_type: Option<&str>,
+        keyword: Option<&str>,
+        retention: &str,
+        page: i32,
+        page_size: i32,
+    ) -> Result<(Vec<RecycleBinItem>, i64)> {
+        let conn = self.conn.lock().await;
+        let pattern = keyword.map(|k| format!("%{}%", k));
+        let filter = |t: u8, k: u8| format!("(?{t} IS NULL OR r.item_type = ?{t}) AND (?{k} IS NULL OR r.title LIKE ?{k})");
+        let total: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM ({RECYCLED_SQL}) r WHERE {}", filter(1, 2)),
+            params![item_type, pattern],
+            |row| row.get(0),
+        )?;
+        let mut stmt = conn.prepare(&format!(
+            "{} ORDER BY datetime(r.deleted_at) DESC, r.id DESC LIMIT ?4 OFFSET ?5",
+            Self::select_sql(&filter(2, 3))
+        ))?;
+        let rows = stmt.query_map(
+            params![retention, item_type, pattern, page_size, (page - 1).max(0) * page_size],
+            map_item,
+        )?;
+        Ok((rows.collect::<Result<Vec<_>, _>>()?, total))
+    }
+
+    /// 各类型在回收站中的数量
+    pub async fn count_by_type(&self) -> Result<Vec<(String, i64)>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT item_type, COUNT(*) FROM ({RECYCLED_SQL}) GROUP BY item_type"
+        ))?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    pub async fn find(&self, item_type: &str, id: i32, retention: &str) -> Result<Option<RecycleBinItem>> {
+        let conn = self.conn.lock().await;
+        let item = conn
+            .query_row(
+                &Self::select_sql("r.item_type = ?2 AND r.id = ?3"),
+                params![retention, item_type, id],
+                map_item,
+            )
+            .optional()?;
+        Ok(item)
+    }
+
+    /// 删除时间早于 cutoff 修饰符（如 '-30 days'）的内容；评论排在前面，先于所属帖子清理
+    pub async fn expired(&self, cutoff: &str, limit: i32) -> Result<Vec<(String, i32)>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT item_type, id FROM ({RECYCLED_SQL})
+             WHERE datetime(deleted_at) < datetime('now', ?)
+             ORDER BY CASE item_type WHEN 'Comment' THEN 0 WHEN 'Post' THEN 1 WHEN 'Package' THEN 2 ELSE 3 END, id
+             LIMIT ?"
+        ))?;
+        let rows = stmt.query_map(params![cutoff, limit], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// 恢复资源为删除前的状态；返回是否恢复
+    pub async fn restore_package(&self, package_id: i32) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let affected = conn.execute(
+            "UPDATE packages SET status = COALESCE(status_before_delete, 'active'), status_before_delete = NULL,
+                    deleted_at = NULL, deleted_by = NULL, updated_at = datetime('now')
+             WHERE id = ? AND status = 'deleted'",
+            params![package_id],
+        )?;
+        Ok(affected > 0)
+    }
+
+    /// 撤销资源恢复，重新放回回收站并保留原删除时间与删除人（文件目录移回失败时回滚）
+    pub async fn undo_restore_package(&self, package_id: i32, deleted_at: &str, deleted_by: Option<i32>) -> Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "UPDATE packages SET status_before_delete = status, status = 'deleted', deleted_at = ?, deleted_by = ?
+             WHERE id = ? AND status != 'deleted'",
+            params![deleted_at, deleted_by, package_id],
+        )?;
+        Ok(())
+    }
+
+    pub async fn restore_post(&self, post_id: i32) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let affected = conn.execute(
+            "UPDATE posts SET status = COALESCE(status_before_delete, 'Published'), status_before_delete = NULL,
+                    deleted_at = NULL, deleted_by = NULL
+             WHERE id = ? AND status = 'Deleted'",
+            params![post_id],
+        )?;
+        Ok(affected > 0)
+    }
+
+    /// 恢复评论，并恢复与它一起删除的直接回复
+    pub async fn restore_comment(&self, comment_id: i32) -> Result<bool> {
+        let mut conn = self.conn.lock().await;
+        let tx = conn.transaction()?;
+        let deleted_at: Option<Option<String>> = tx
+            .query_row(
+                "SELECT deleted_at FROM comments WHERE id = ? AND status = 'Deleted'",
+                params![comment_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let Some(deleted_at) = deleted_at else { return Ok(false) };
+        let restore = "UPDATE comments SET status = COALESCE(status_before_delete, 'Active'), status_before_delete = NULL,
+                              deleted_at = NULL, deleted_by = NULL";
+        if let Some(deleted_at) = deleted_at {
+            tx.execute(
+                &format!("{restore} WHERE parent_id = ? AND status = 'Deleted' AND deleted_at = ?"),
+                params![comment_id, deleted_at],
+            )?;
+        }
+        tx.execute(&format!("{restore} WHERE id = ?"), params![comment_id])?;
+        tx.commit()?;
+        Ok(true)
+    }
+
+    /// 父评论仍在回收站中时回复不能单独恢复
+    pub async fn comment_parent_deleted(&self, comment_id: i32) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let deleted = conn
+            .query_row(
+                "SELECT p.status = 'Deleted' FROM comments c JOIN comments p ON p.id = c.parent_id WHERE c.id = ?",
+                params![comment_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(deleted.unwrap_or(false))
+    }
+
+    pub async fn restore_user(&self, user_id: i32) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let affected = conn.execute(
+            "UPDATE users SET deleted_at = NULL, deleted_by = NULL WHERE id = ? AND deleted_at IS NOT NULL",
+            params![user_id],
+        )?;
+        Ok(affected > 0)
+    }
+
+    pub async fn retention_days(&self) -> Result<i64> {
+        let conn = self.conn.lock().await;
+        let value: Option<String> = conn
+            .query_row(
+                "SELECT value FROM system_settings WHERE key = ?",
+                params![RECYCLE_RETENTION_KEY],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(value.and_then(|v| v.trim().parse().ok()).unwrap_or(DEFAULT_RETENTION_DAYS))
+    }
+
+    pub async fn set_retention_days(&self, days: i64) -> Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "INSERT OR REPLACE INTO system_settings (key, value, description, updated_at)
+             VALUES (?, ?, '回收站保留天数：超过后彻底删除', datetime('now'))",
+            params![RECYCLE_RETENTION_KEY, days.to_string()],
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+use anyhow::Result;
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::models::render::CachedRender;
+
+/// Markdown 渲染结果缓存，按 (内容类型, 内容ID, 字段) 存放
+#[derive(Clone)]
+pub struct RenderCacheRepository {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl RenderCacheRepository {
+    pub fn new(db_path: &str) -> Result<Self> {
+        let conn = Connection::open(db_path)?;
+        Ok(Self { conn: Arc::new(Mutex::new(conn)) })
+    }
+
+    pub async fn find(&self, target_type: &str, target_id: i32, field: &str) -> Result<Option<CachedRender>> {
+        let conn = self.conn.lock().await;
+        let cached = conn
+            .query_row(
+                "SELECT source_hash, renderer_version, html, excerpt FROM rendered_content
+                 WHERE target_type = ? AND target_id = ? AND field = ?",
+                params![target_type, target_id, field],
+                map_cached,
+            )
+            .optional()?;
+        Ok(cached)
+    }
+
+    /// 批量读取（列表摘要），返回 内容ID -> 缓存
+    pub async fn find_many(&self, target_type: &str, field: &str, target_ids: &[i32]) -> Result<HashMap<i32, CachedRender>> {
+        if target_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let placeholders = vec!["?"; target_ids.len()].join(",");
+        let sql = format!(
+            "SELECT target_id, source_hash, renderer_version, html, excerpt FROM rendered_content
+             WHERE target_type = ? AND field = ? AND target_id IN ({})",
+            placeholders
+        );
+        let mut values: Vec<rusqlite::types::Value> = vec![target_type.to_string().into(), field.to_string().into()];
+        values.extend(target_ids.iter().map(|id| rusqlite::types::Value::from(*id)));
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(values), |row| {
+            Ok((
+                row.get::<_, i32>(0)?,
+                CachedRender {
+                    source_hash: row.get(1)?,
+                    renderer_version: row.get(2)?,
+                    html: row.get(3)?,
+                    excerpt: row.get(4)?,
+                },
+            ))
+        })?;
+        Ok(rows.collect::<Result<HashMap<_, _>, _>>()?)
+    }
+
+    pub async fn save(&self, target_type: &str, target_id: i32, field: &str, cached: &CachedRender) -> Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "INSERT INTO rendered_content (target_type, target_id, field, source_hash, renderer_version, html, excerpt, rendered_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+             ON CONFLICT(target_type, target_id, field) DO UPDATE SET
+                 source_hash = ?4, renderer_version = ?5, html = ?6, excerpt = ?7, rendered_at = ?8",
+            params![
+                target_type,
+                target_id,
+                field,
+                cached.source_hash,
+                cached.renderer_version,
+                cached.html,
+                cached.excerpt,
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+fn map_cached(row: &rusqlite::Row) -> rusqlite::Result<CachedRender> {
+    Ok(CachedRender {
+        source_hash: row.get(0)?,
+        renderer_version: row.get(1)?,
+        html: row.get(2)?,
+        excerpt: row.get(3)?,
+    })
+}
@@ -0,0 +1,276 @@
+use anyhow::Result;
+use rusqlite::{params, Connection, ToSql};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::models::site_metrics::SiteDailyMetrics;
+
+/// 活跃用户来源：(表名, 用户列, 时间列)
+const ACTIVITY_SOURCES: [(&str, &str, &str); 10] = [
+    ("app_launches", "user_id", "created_at"),
+    ("user_actions", "user_id", "created_at"),
+    ("download_records", "user_id", "download_time"),
+    ("package_views", "user_id", "created_at"),
+    ("post_views", "user_id", "created_at"),
+    ("package_likes", "user_id", "created_at"),
+    ("post_likes", "user_id", "created_at"),
+    ("user_check_ins", "user_id", "created_at"),
+    ("posts", "author_id", "created_at"),
+    ("comments", "user_id", "created_at"),
+];
+
+/// 汇总的一天：北京时间日期及对应的 UTC 区间 [start, end)
+pub struct MetricsDay {
+    pub date: String,
+    pub start: String,
+    pub end: String,
+}
+
+/// 全站指标日汇总、每日活跃用户与注册周留存
+#[derive(Clone)]
+pub struct SiteMetricsRepository {
+    conn: Arc<Mutex<Connection>>,
+}
+
+fn table_exists(conn: &Connection, table: &str) -> Result<bool> {
+    let exists = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?)",
+        params![table],
+        |row| row.get(0),
+    )?;
+    Ok(exists)
+}
+
+/// 统计来源表有的是按需创建的，尚不存在时记为 0
+fn count_if_table(conn: &Connection, sql: &str, params: &[&dyn ToSql]) -> Result<i64> {
+    match conn.query_row(sql, params, |row| row.get(0)) {
+        Ok(count) => Ok(count),
+        Err(e) if e.to_string().contains("no such table") => Ok(0),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn map_metrics(row: &rusqlite::Row) -> rusqlite::Result<SiteDailyMetrics> {
+    Ok(SiteDailyMetrics {
+        date: row.get(0)?,
+        new_users: row.get(1)?,
+        dau: row.get(2)?,
+        wau: row.get(3)?,
+        mau: row.get(4)?,
+        app_launches: row.get(5)?,
+        uploads: row.get(6)?,
+        downloads: row.get(7)?,
+        unique_downloaders: row.get(8)?,
+        new_posts: row.get(9)?,
+        new_comments: row.get(10)?,
+        total_users: row.get(11)?,
+        total_packages: row.get(12)?,
+        total_posts: row.get(13)?,
+        total_comments: row.get(14)?,
+        computed_at: row.get(15)?,
+    })
+}
+
+const METRICS_COLUMNS: &str = "date, new_users, dau, wau, mau, app_launches, uploads, downloads, unique_downloaders,
+     new_posts, new_comments, total_users, total_packages, total_posts, total_comments, computed_at";
+
+impl SiteMetricsRepository {
+    pub fn new(db_path: &str) -> Result<Self> {
+        let conn = Connection::open(db_path)?;
+        Ok(Self { conn: Arc::new(Mutex::new(conn)) })
+    }
+
+    /// 最近一次汇总到的日期
+    pub async fn latest_date(&self) -> Result<Option<String>> {
+        let conn = self.conn.lock().await;
+        let date = conn.query_row("SELECT MAX(date) FROM site_daily_metrics", [], |row| row.get(0))?;
+        Ok(date)
+    }
+
+    pub async fn has_metrics(&self, date: &str) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let exists = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM site_daily_metrics WHERE date = ?)",
+            params![date],
+            |row| row.get(0),
+        )?;
+        Ok(exists)
+    }
+
+    /// 最早注册用户的本地日期，首次运行时从这一天开始回填
+    pub async fn earliest_signup_date(&self, day_modifier: &str) -> Result<Option<String>> {
+        let conn = self.conn.lock().await;
+        let date = conn.query_row(
+            "SELECT MIN(date(datetime(created_at), ?)) FROM users",
+            params![day_modifier],
+            |row| row.get(0),
+        )?;
+        Ok(date)
+    }
+
+    /// 重新记录某天的活跃用户，可重复执行
+    pub async fn refresh_active_users(&self, day: &MetricsDay) -> Result<()> {
+        let mut conn = self.conn.lock().await;
+        let tx = conn.transaction()?;
+        Self::refresh_active_users_internal(&tx, day)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn refresh_active_users_internal(conn: &Connection, day: &MetricsDay) -> Result<()> {
+        conn.execute("DELETE FROM site_active_users WHERE date = ?", params![day.date])?;
+        let mut selects = Vec::new();
+        for (table, user_col, time_col) in ACTIVITY_SOURCES {
+            if table_exists(conn, table)? {
+                selects.push(format!(
+                    "SELECT {user_col} AS user_id FROM {table}
+                     WHERE {user_col} IS NOT NULL AND datetime({time_col}) >= ?2 AND datetime({time_col}) < ?3"
+                ));
+            }
+        }
+        if selects.is_empty() {
+            return Ok(());
+        }
+        conn.execute(
+            &format!(
+                "INSERT OR IGNORE INTO site_active_users (date, user_id) SELECT ?1, user_id FROM ({})",
+                selects.join(" UNION ")
+            ),
+            params![day.date, day.start, day.end],
+        )?;
+        Ok(())
+    }
+
+    /// 汇总某一天：先记录当天活跃用户，再计算各项指标写入汇总表，可重复执行；
+    /// WAU/MAU 依赖此前 29 天的活跃用户记录
+    pub async fn rollup_day(&self, day: &MetricsDay, week_start: &str, month_start: &str) -> Result<()> {
+        let mut conn = self.conn.lock().await;
+        let tx = conn.transaction()?;
+        Self::refresh_active_users_internal(&tx, day)?;
+
+        let range: [&dyn ToSql; 2] = [&day.start, &day.end];
+        let before: [&dyn ToSql; 1] = [&day.end];
+        let in_range = |table: &str, col: &str| {
+            format!("SELECT COUNT(*) FROM {table} WHERE datetime({col}) >= ?1 AND datetime({col}) < ?2")
+        };
+        let total = |table: &str| format!("SELECT COUNT(*) FROM {table} WHERE datetime(created_at) < ?1");
+
+        let active = |from: &str| -> Result<i64> {
+            Ok(tx.query_row(
+                "SELECT COUNT(DISTINCT user_id) FROM site_active_users WHERE date >= ? AND date <= ?",
+                params![from, day.date],
+                |row| row.get(0),
+            )?)
+        };
+        let dau = active(&day.date)?;
+        let wau = active(week_start)?;
+        let mau = active(month_start)?;
+
+        let downloads = count_if_table(&tx, &in_range("download_records", "download_time"), &range)?;
+        let unique_downloaders = count_if_table(
+            &tx,
+            "SELECT COUNT(DISTINCT COALESCE('u' || user_id, 'ip' || ip_address)) FROM download_records
+             WHERE datetime(download_time) >= ?1 AND datetime(download_time) < ?2",
+            &range,
+        )?;
+
+        tx.execute(
+            &format!(
+                "INSERT OR REPLACE INTO site_daily_metrics ({METRICS_COLUMNS})
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, datetime('now'))"
+            ),
+            params![
+                day.date,
+                count_if_table(&tx, &in_range("users", "created_at"), &range)?,
+                dau,
+                wau,
+                mau,
+                count_if_table(&tx, &in_range("app_launches", "created_at"), &range)?,
+                count_if_table(&tx, &in_range("packages", "created_at"), &range)?,
+                downloads,
+                unique_downloaders,
+                count_if_table(&tx, &in_range("posts", "created_at"), &range)?,
+                count_if_table(&tx, &in_range("comments", "created_at"), &range)?,
+                count_if_table(&tx, &total("users"), &before)?,
+                count_if_table(&tx, &total("packages"), &before)?,
+                count_if_table(&tx, &total("posts"), &before)?,
+                count_if_table(&tx, &total("comments"), &before)?,
+            ],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub async fn list(&self, from: &str, to: &str) -> Result<Vec<SiteDailyMetrics>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {METRICS_COLUMNS} FROM site_daily_metrics WHERE date >= ? AND date <= ? ORDER BY date"
+        ))?;
+        let rows = stmt.query_map(params![from, to], map_metrics)?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// 重算 first_week（周一）及之后注册的用户的周留存，只统计已汇总到 last_date 的活跃记录；
+    /// 每个注册周记录第 0 周到第 track_weeks 周中已开始的各周
+    pub async fn refresh_cohorts(
+        &self,
+        first_week: &str,
+        last_date: &str,
+        track_weeks: i64,
+        day_modifier: &str,
+    ) -> Result<()> {
+        let mut conn = self.conn.lock().await;
+        let tx = conn.transaction()?;
+        // 注册周：本地注册日期所在周的周一
+        let cohort_sql = "SELECT id, date(date(datetime(created_at), ?1), 'weekday 0', '-6 days') AS week FROM users
+                          WHERE created_at IS NOT NULL AND date(datetime(created_at), ?1) <= ?3";
+
+        let sizes: Vec<(String, i64)> = {
+            let mut stmt = tx.prepare(&format!(
+                "SELECT week, COUNT(*) FROM ({cohort_sql}) WHERE week >= ?2 GROUP BY week ORDER BY week"
+            ))?;
+            let rows = stmt.query_map(params![day_modifier, first_week, last_date], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect::<Result<Vec<_>, _>>()?
+        };
+        let retained: HashMap<(String, i64), i64> = {
+            let mut stmt = tx.prepare(&format!(
+                "SELECT c.week, CAST((julianday(a.date) - julianday(c.week)) / 7 AS INTEGER) AS k, COUNT(DISTINCT a.user_id)
+                 FROM ({cohort_sql}) c JOIN site_active_users a ON a.user_id = c.id
+                 WHERE c.week >= ?2 AND a.date >= c.week AND a.date <= ?3
+                 GROUP BY c.week, k"
+            ))?;
+            let rows = stmt.query_map(params![day_modifier, first_week, last_date], |row| {
+                Ok(((row.get::<_, String>(0)?, row.get::<_, i64>(1)?), row.get::<_, i64>(2)?))
+            })?;
+            rows.collect::<Result<HashMap<_, _>, _>>()?
+        };
+
+        tx.execute("DELETE FROM signup_cohort_retention WHERE cohort_week >= ?", params![first_week])?;
+        {
+            let mut insert = tx.prepare(
+                "INSERT INTO signup_cohort_retention (cohort_week, week_offset, cohort_size, retained, computed_at)
+                 SELECT ?1, ?2, ?3, ?4, datetime('now') WHERE date(?1, '+' || (?2 * 7) || ' days') <= ?5",
+            )?;
+            for (week, size) in &sizes {
+                for k in 0..=track_weeks {
+                    let count = retained.get(&(week.clone(), k)).copied().unwrap_or(0);
+                    insert.execute(params![week, k, size, count, last_date])?;
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// first_week 及之后的注册周留存：(注册周, 第几周, 注册人数, 留存人数)
+    pub async fn list_cohorts(&self, first_week: &str) -> Result<Vec<(String, i64, i64, i64)>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(
+            "SELECT cohort_week, week_offset, cohort_size, retained FROM signup_cohort_retention
+             WHERE cohort_week >= ? ORDER BY cohort_week DESC, week_offset",
+        )?;
+        let rows = stmt.query_map(params![first_week], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+}
@@ -53,14 +53,6 @@ impl SubscriptionRepository {
         Ok(subscriptions)
     }
 
-    pub async fn get_subscribed_emails(&self, category_id: i32) -> Result<Vec<String>> {
-        let conn = self.conn.lock().await;
-        let sql = "SELECT u.email FROM subscriptions s JOIN users u ON s.user_id = u.id WHERE s.category_id = ? AND s.enabled = 1";
-        let mut stmt = conn.prepare(sql)?;
-        let rows = stmt.query_map(params![category_id], |r| r.get(0))?;
-        Ok(rows.collect::<Result<Vec<String>, _>>()?)
-    }
-
     // 新增：获取订阅某分类的用户ID列表（用于站内通知）
     pub async fn get_subscribed_user_ids(&self, category_id: i32) -> Result<Vec<i32>> {
         let conn = self.conn.lock().await;
@@ -10,6 +10,32 @@ use std::collections::HashMap;
 // 导入所需模型
 use crate::models::user_action::UserAction;
 use crate::models::system::{Category, CreateCategoryRequest, UpdateCategoryRequest};
+use crate::utils::time::local_now;
+
+const CATEGORY_SELECT: &str = "SELECT id, name, description, enabled, subscription_locked, created_at, updated_at,
+        parent_id, sort_order, icon, requires_review, allowed_extensions, max_file_size, post_permission
+    FROM categories";
+
+pub(crate) fn map_category(row: &rusqlite::Row) -> rusqlite::Result<Category> {
+    Ok(Category {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        enabled: row.get::<_, Option<bool>>(3)?.unwrap_or(true),
+        subscription_locked: row.get::<_, Option<bool>>(4)?.unwrap_or(false),
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+        parent_id: row.get(7)?,
+        sort_order: row.get::<_, Option<i32>>(8)?.unwrap_or(0),
+        icon: row.get(9)?,
+        requires_review: row.get(10)?,
+        allowed_extensions: row
+            .get::<_, Option<String>>(11)?
+            .and_then(|s| serde_json::from_str(&s).ok()),
+        max_file_size: row.get(12)?,
+        post_permission: row.get(13)?,
+    })
+}
 
 #[derive(Clone)]
 pub struct SystemRepository {
@@ -24,8 +50,32 @@ impl SystemRepository {
         })
     }
 
+    /// 管理后台统计：读全站日汇总中最近一天的数据，汇总任务尚未跑过时才直接统计各表
     pub async fn get_stats(&self) -> Result<crate::models::Stats> {
         let conn = self.conn.lock().await;
+
+        let latest = conn.query_row(
+            "SELECT total_users, total_packages, total_comments, mau, new_users, uploads
+             FROM site_daily_metrics ORDER BY date DESC LIMIT 1",
+            [],
+            |row| {
+                Ok(crate::models::Stats {
+                    total_users: row.get(0)?,
+                    total_packages: row.get(1)?,
+                    total_comments: row.get(2)?,
+                    active_users: row.get(3)?,
+                    new_users_today: row.get(4)?,
+                    new_packages_today: row.get(5)?,
+                    system_status: "Running".to_string(),
+                    uptime: 0,
+                })
+            },
+        ).optional();
+        match latest {
+            Ok(Some(stats)) => return Ok(stats),
+            Ok(None) => {}
+            Err(e) => log::warn!("读取全站日汇总失败，改为直接统计: {}", e),
+        }
         
         // 获取总用户数
         let total_users: i64 = match conn.query_row("SELECT COUNT(*) FROM users", [], |row| row.get(0)) {
@@ -117,50 +167,8 @@ impl SystemRepository {
             )?;
         }
         
-        // 获取表结构信息
-        let mut has_description = false;
-        let mut has_enabled = false;
-        let mut has_updated_at = false;
-        
-        let mut stmt = conn.prepare("PRAGMA table_info(categories)")?;
-        let cols = stmt.query_map([], |row| {
-            let name: String = row.get(1)?;
-            Ok(name)
-        })?;
-        
-        for col_result in cols {
-            if let Ok(col_name) = col_result {
-                if col_name == "description" {
-                    has_description = true;
-                } else if col_name == "enabled" {
-                    has_enabled = true;
-                } else if col_name == "updated_at" {
-                    has_updated_at = true;
-                }
-            }
-        }
-        
-        // 构建查询
-        let sql = format!(
-            "SELECT id, name, {} {} subscription_locked, created_at {} FROM categories",
-            if has_description { "description," } else { "NULL as description," },
-            if has_enabled { "enabled," } else { "1 as enabled," },
-            if has_updated_at { ", updated_at" } else { ", NULL as updated_at" }
-        );
-        
-        let mut stmt = conn.prepare(&sql)?;
-        
-        let rows = stmt.query_map([], |row| {
-            Ok(Category {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                description: row.get(2)?,
-                enabled: row.get(3).unwrap_or(true),
-                subscription_locked: row.get(4).unwrap_or(false),
-                created_at: row.get(5)?,
-                updated_at: row.get(6).ok(),
-            })
-        })?;
+        let mut stmt = conn.prepare(&format!("{} ORDER BY sort_order, id", CATEGORY_SELECT))?;
+        let rows = stmt.query_map([], map_category)?;
         
         let mut categories = Vec::new();
         for category_result in rows {
@@ -397,27 +405,15 @@ impl SystemRepository {
         Ok(list)
     }
 
-    // 获取最近N天DAU（按 app_launches 中每日去重 user_id 统计）
+    // 获取最近N天DAU（读全站日汇总，某天的数据在次日凌晨汇总后出现）
     pub async fn get_dau_stats(&self, days: i32) -> Result<Vec<crate::models::DailyStats>> {
         let conn = self.conn.lock().await;
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS app_launches (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                user_id INTEGER,
-                device_id TEXT,
-                app_version TEXT,
-                platform TEXT,
-                created_at TEXT NOT NULL
-            )",
-            [],
-        )?;
+        let since = (local_now().date_naive() - chrono::Duration::days(days.max(1) as i64))
+            .format("%Y-%m-%d")
+            .to_string();
         let mut stmt = conn.prepare(
-            "SELECT day, COUNT(DISTINCT user_id) as count FROM (
-                 SELECT date(created_at) as day, user_id FROM app_launches 
-                 WHERE user_id IS NOT NULL AND date(created_at) >= date('now', ?)
-            ) t GROUP BY day ORDER BY day DESC",
+            "SELECT date, dau FROM site_daily_metrics WHERE date >= ? ORDER BY date DESC",
         )?;
-        let since = format!("-{} days", days.max(1));
         let rows = stmt.query_map(rusqlite::params![since], |row| {
             Ok(crate::models::DailyStats { date: row.get(0)?, count: row.get(1)? })
         })?;
@@ -1379,100 +1375,114 @@ impl SystemRepository {
     // 修复get_category_by_id方法
     pub async fn get_category_by_id(&self, id: i32) -> Result<Option<Category>> {
         let conn = self.conn.lock().await;
-        let sql = "SELECT id, name, description, enabled, subscription_locked, created_at, updated_at FROM categories WHERE id = ?";
-        log::debug!("🗄️ SQL: get_category_by_id: {}", sql);
-        
-        let mut stmt = conn.prepare(sql)?;
-        
-        let category = match stmt.query_row(rusqlite::params![id], |row| {
-            Ok(Category {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                description: row.get(2)?,
-                enabled: row.get(3)?,
-                subscription_locked: row.get(4)?,
-                created_at: row.get(5)?,
-                updated_at: row.get(6)?,
-            })
-        }) {
-            Ok(c) => Some(c),
-            Err(rusqlite::Error::QueryReturnedNoRows) => None,
-            Err(e) => {
-                log::error!("❌ get_category_by_id error: {}", e);
-                return Err(e.into());
-            }
-        };
-        
+        let category = conn
+            .query_row(&format!("{} WHERE id = ?", CATEGORY_SELECT), params![id], map_category)
+            .optional()?;
         Ok(category)
     }
 
     // 修复create_category方法
     pub async fn create_category(&self, req: &CreateCategoryRequest) -> Result<Category> {
         let conn = self.conn.lock().await;
-        let sql = "INSERT INTO categories (name, description, enabled, subscription_locked, created_at) VALUES (?, ?, ?, ?, datetime('now'))";
+        let sql = "INSERT INTO categories (name, description, enabled, subscription_locked, parent_id, sort_order, icon,
+                requires_review, allowed_extensions, max_file_size, post_permission, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, datetime('now'))";
         log::debug!("🗄️ SQL: create_category: {}", sql);
         
-        let enabled = req.enabled.unwrap_or(true);
-        let subscription_locked = req.subscription_locked.unwrap_or(false);
-        
-        let mut stmt = conn.prepare(sql)?;
-        stmt.execute(rusqlite::params![
+        // 未指定排序时排在同级最后
+        let sort_order = match req.sort_order {
+            Some(order) => order,
+            None => conn.query_row(
+                "SELECT COALESCE(MAX(sort_order), 0) + 1 FROM categories WHERE parent_id IS ?",
+                params![req.parent_id],
+                |row| row.get(0),
+            )?,
+        };
+        let allowed_extensions = req.allowed_extensions.as_ref().map(serde_json::to_string).transpose()?;
+        conn.execute(sql, params![
             req.name,
             req.description,
-            enabled,
-            subscription_locked
+            req.enabled.unwrap_or(true),
+            req.subscription_locked.unwrap_or(false),
+            req.parent_id,
+            sort_order,
+            req.icon,
+            req.requires_review,
+            allowed_extensions,
+            req.max_file_size,
+            req.post_permission
         ])?;
         
         let id = conn.last_insert_rowid() as i32;
-        let now = chrono::Utc::now().to_rfc3339();
-        
-        let category = Category {
-            id,
-            name: req.name.clone(),
-            description: req.description.clone(),
-            enabled,
-            subscription_locked,
-            created_at: now,
-            updated_at: None,
-        };
-        
+        let category = conn.query_row(&format!("{} WHERE id = ?", CATEGORY_SELECT), params![id], map_category)?;
         Ok(category)
     }
 
     // 修复update_category方法
     pub async fn update_category(&self, id: i32, req: &UpdateCategoryRequest) -> Result<Category> {
         // 先检查分类是否存在
-        let category = self.get_category_by_id(id).await?
+        let mut category = self.get_category_by_id(id).await?
             .ok_or_else(|| anyhow::anyhow!("分类不存在"))?;
         
+        if let Some(name) = &req.name { category.name = name.clone(); }
+        if req.description.is_some() { category.description = req.description.clone(); }
+        if let Some(enabled) = req.enabled { category.enabled = enabled; }
+        if let Some(locked) = req.subscription_locked { category.subscription_locked = locked; }
+        if let Some(parent_id) = req.parent_id { category.parent_id = (parent_id != 0).then_some(parent_id); }
+        if let Some(order) = req.sort_order { category.sort_order = order; }
+        if req.icon.is_some() { category.icon = req.icon.clone(); }
+        if req.requires_review.is_some() { category.requires_review = req.requires_review; }
+        if req.allowed_extensions.is_some() { category.allowed_extensions = req.allowed_extensions.clone(); }
+        if req.max_file_size.is_some() { category.max_file_size = req.max_file_size; }
+        if req.post_permission.is_some() { category.post_permission = req.post_permission.clone(); }
+        for field in req.inherit.iter().flatten() {
+            match field.as_str() {
+                "requires_review" => category.requires_review = None,
+                "allowed_extensions" => category.allowed_extensions = None,
+                "max_file_size" => category.max_file_size = None,
+                "post_permission" => category.post_permission = None,
+                _ => {}
+            }
+        }
+        
         let conn = self.conn.lock().await;
-        let sql = "UPDATE categories SET name = ?, description = ?, enabled = ?, subscription_locked = ?, updated_at = datetime('now') WHERE id = ?";
+        let sql = "UPDATE categories SET name = ?, description = ?, enabled = ?, subscription_locked = ?, parent_id = ?,
+                sort_order = ?, icon = ?, requires_review = ?, allowed_extensions = ?, max_file_size = ?, post_permission = ?,
+                updated_at = datetime('now')
+             WHERE id = ?";
         log::debug!("🗄️ SQL: update_category: {}", sql);
         
-        let mut stmt = conn.prepare(sql)?;
-        stmt.execute(rusqlite::params![
-            req.name.as_ref().unwrap_or(&category.name),
-            req.description.as_ref().or(category.description.as_ref()),
-            req.enabled.unwrap_or(category.enabled),
-            req.subscription_locked.unwrap_or(category.subscription_locked),
+        let allowed_extensions = category.allowed_extensions.as_ref().map(serde_json::to_string).transpose()?;
+        conn.execute(sql, params![
+            category.name,
+            category.description,
+            category.enabled,
+            category.subscription_locked,
+            category.parent_id,
+            category.sort_order,
+            category.icon,
+            category.requires_review,
+            allowed_extensions,
+            category.max_file_size,
+            category.post_permission,
             id
         ])?;
         
         // 获取更新后的分类
-        let now = chrono::Utc::now().to_rfc3339();
-        let updated_category = Category {
-            id,
-            name: req.name.clone().unwrap_or(category.name),
-            description: req.description.clone().or(category.description),
-            enabled: req.enabled.unwrap_or(category.enabled),
-            subscription_locked: req.subscription_locked.unwrap_or(category.subscription_locked),
-            created_at: category.created_at,
-            updated_at: Some(now),
-        };
-        
+        let updated_category = conn.query_row(&format!("{} WHERE id = ?", CATEGORY_SELECT), params![id], map_category)?;
         Ok(updated_category)
     }
 
+    /// 各分类直接包含的已上架资源数
+    pub async fn count_active_packages_by_category(&self) -> Result<HashMap<i32, i32>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(
+            "SELECT category_id, COUNT(*) FROM packages WHERE status = 'active' AND category_id IS NOT NULL GROUP BY category_id",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, i32>(0)?, row.get::<_, i32>(1)?)))?;
+        Ok(rows.collect::<Result<HashMap<_, _>, _>>()?)
+    }
+
     // 删除分类
     pub async fn delete_category(&self, id: i32) -> Result<()> {
         let conn = self.conn.lock().await;
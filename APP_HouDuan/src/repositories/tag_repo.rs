@@ -0,0 +1,245 @@
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension, Result as SqliteResult};
+use std::collections::HashSet;
+
+use crate::models::{Tag, MAX_TAG_LENGTH};
+use crate::utils::time::parse_db_time;
+
+/// 标签白名单模式的设置键（system_settings）
+pub const TAG_WHITELIST_KEY: &str = "tag_whitelist_mode";
+
+pub(crate) const TAG_SELECT: &str = "SELECT id, name, description, color, use_count, status, created_at, updated_at FROM tags";
+
+/// 按 TAG_SELECT 的列顺序映射标签
+pub(crate) fn map_tag(row: &rusqlite::Row) -> SqliteResult<Tag> {
+    Ok(Tag {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        color: row.get(3)?,
+        use_count: row.get(4)?,
+        status: row.get::<_, Option<String>>(5)?.unwrap_or_else(|| "approved".to_string()),
+        created_at: parse_db_time(&row.get::<_, String>(6)?).unwrap_or_else(Utc::now),
+        updated_at: parse_db_time(&row.get::<_, String>(7)?).unwrap_or_else(Utc::now),
+    })
+}
+
+/// 规范化标签名：全角字母数字转半角，去掉首尾空白和开头的 #，连续空白合并为一个空格
+pub fn normalize_tag_name(raw: &str) -> Option<String> {
+    let converted: String = raw
+        .chars()
+        .map(|c| match c {
+            '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+            '\u{3000}' => ' ',
+            _ => c,
+        })
+        .collect();
+    let name = converted
+        .trim()
+        .trim_start_matches('#')
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if name.is_empty() { None } else { Some(name) }
+}
+
+/// 标签的匹配键，"UI" 与 "ui" 视为同一标签
+pub fn tag_key(name: &str) -> String {
+    name.to_lowercase()
+}
+
+/// 标签关联的对象
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagTarget {
+    Post,
+    Package,
+}
+
+impl TagTarget {
+    pub(crate) fn table(self) -> &'static str {
+        match self {
+            TagTarget::Post => "post_tags",
+            TagTarget::Package => "package_tags",
+        }
+    }
+
+    pub(crate) fn column(self) -> &'static str {
+        match self {
+            TagTarget::Post => "post_id",
+            TagTarget::Package => "package_id",
+        }
+    }
+}
+
+/// 按匹配键查到的标签（可能经由别名）
+pub(crate) struct FoundTag {
+    pub id: i32,
+    pub name: String,
+    pub status: String,
+    pub via_alias: bool,
+}
+
+/// 按匹配键查找标签，先匹配标签本身，再匹配别名
+pub(crate) fn find_tag(conn: &Connection, key: &str) -> SqliteResult<Option<FoundTag>> {
+    let direct = conn.query_row(
+        "SELECT id, name, status FROM tags WHERE slug = ?",
+        params![key],
+        |row| Ok(FoundTag { id: row.get(0)?, name: row.get(1)?, status: row.get(2)?, via_alias: false }),
+    ).optional()?;
+    if direct.is_some() {
+        return Ok(direct);
+    }
+    conn.query_row(
+        "SELECT t.id, t.name, t.status FROM tag_aliases a JOIN tags t ON t.id = a.tag_id WHERE a.alias_key = ?",
+        params![key],
+        |row| Ok(FoundTag { id: row.get(0)?, name: row.get(1)?, status: row.get(2)?, via_alias: true }),
+    ).optional()
+}
+
+pub(crate) fn whitelist_enabled(conn: &Connection) -> SqliteResult<bool> {
+    let value: Option<String> = conn.query_row(
+        "SELECT value FROM system_settings WHERE key = ?",
+        params![TAG_WHITELIST_KEY],
+        |row| row.get(0),
+    ).optional()?;
+    Ok(matches!(value.as_deref(), Some("true") | Some("1")))
+}
+
+/// 对象已关联的标签ID
+pub(crate) fn attached_tag_ids(conn: &Connection, target: TagTarget, target_id: i32) -> SqliteResult<Vec<i32>> {
+    let sql = format!("SELECT tag_id FROM {} WHERE {} = ? ORDER BY id", target.table(), target.column());
+    let mut stmt = conn.prepare(&sql)?;
+    let ids = stmt.query_map(params![target_id], |row| row.get(0))?
+        .collect::<Result<Vec<i32>, _>>()?;
+    Ok(ids)
+}
+
+/// 标签解析结果：可用的标签（id 为 None 表示尚不存在，写入时创建为待审核）与被拒绝的标签及原因
+#[derive(Debug, Default)]
+pub struct ResolvedTags {
+    pub accepted: Vec<(Option<i32>, String)>,
+    pub rejected: Vec<(String, String)>,
+}
+
+impl ResolvedTags {
+    pub fn rejection_message(&self) -> Option<String> {
+        if self.rejected.is_empty() {
+            return None;
+        }
+        let parts: Vec<String> = self.rejected.iter()
+            .map(|(name, reason)| format!("「{}」{}", name, reason))
+            .collect();
+        Some(format!("标签不可用：{}", parts.join("；")))
+    }
+}
+
+/// 规范化、按别名换成目标标签并去重；已禁用的标签总是被拒绝，
+/// 白名单模式下未审核的标签也被拒绝（已关联在 attached 中的标签除外）
+pub fn resolve_tags(conn: &Connection, names: &[String], attached: &[i32]) -> SqliteResult<ResolvedTags> {
+    let whitelist = whitelist_enabled(conn)?;
+    let mut resolved = ResolvedTags::default();
+    let mut seen = HashSet::new();
+
+    for raw in names {
+        let name = match normalize_tag_name(raw) {
+            Some(name) => name,
+            None => continue,
+        };
+        if name.chars().count() > MAX_TAG_LENGTH {
+            resolved.rejected.push((name, format!("超过 {} 个字符", MAX_TAG_LENGTH)));
+            continue;
+        }
+        let key = tag_key(&name);
+        match find_tag(conn, &key)? {
+            Some(tag) => {
+                if !seen.insert(tag.id.to_string()) {
+                    continue;
+                }
+                if tag.status == "banned" {
+                    resolved.rejected.push((name, "已被禁用".to_string()));
+                } else if whitelist && tag.status != "approved" && !attached.contains(&tag.id) {
+                    resolved.rejected.push((name, "尚未审核，当前只能使用已审核的标签".to_string()));
+                } else {
+                    resolved.accepted.push((Some(tag.id), tag.name));
+                }
+            }
+            None => {
+                if !seen.insert(key) {
+                    continue;
+                }
+                if whitelist {
+                    resolved.rejected.push((name, "不在已审核的标签中，当前只能使用已审核的标签".to_string()));
+                } else {
+                    resolved.accepted.push((None, name));
+                }
+            }
+        }
+    }
+    Ok(resolved)
+}
+
+/// 把对象的标签替换为 names：不存在的标签创建为待审核，被拒绝的标签跳过，
+/// 只增删有变化的关联（use_count 由触发器维护）。帖子会同步 posts.tags 列。返回实际使用的标签名
+pub fn link_tags(conn: &Connection, target: TagTarget, target_id: i32, names: &[String]) -> SqliteResult<Vec<String>> {
+    let attached = attached_tag_ids(conn, target, target_id)?;
+    let resolved = resolve_tags(conn, names, &attached)?;
+    if let Some(msg) = resolved.rejection_message() {
+        log::warn!("{:?} {} 的部分标签被跳过: {}", target, target_id, msg);
+    }
+
+    let now = Utc::now().to_rfc3339();
+    let mut ids = Vec::new();
+    let mut linked_names = Vec::new();
+    for (id, name) in resolved.accepted {
+        let id = match id {
+            Some(id) => id,
+            None => {
+                conn.execute(
+                    "INSERT INTO tags (name, slug, status, use_count, created_at, updated_at) VALUES (?, ?, 'pending', 0, ?, ?)",
+                    params![name, tag_key(&name), now, now],
+                )?;
+                conn.last_insert_rowid() as i32
+            }
+        };
+        ids.push(id);
+        linked_names.push(name);
+    }
+
+    for old in attached.iter().filter(|id| !ids.contains(id)) {
+        conn.execute(
+            &format!("DELETE FROM {} WHERE {} = ? AND tag_id = ?", target.table(), target.column()),
+            params![target_id, old],
+        )?;
+    }
+    for id in ids.iter().filter(|id| !attached.contains(id)) {
+        conn.execute(
+            &format!("INSERT OR IGNORE INTO {} ({}, tag_id, created_at) VALUES (?, ?, ?)", target.table(), target.column()),
+            params![target_id, id, now],
+        )?;
+    }
+
+    if target == TagTarget::Post {
+        let json = serde_json::to_string(&linked_names).unwrap_or_else(|_| "[]".to_string());
+        conn.execute("UPDATE posts SET tags = ? WHERE id = ?", params![json, target_id])?;
+    }
+    Ok(linked_names)
+}
+
+/// 按关联表重写帖子的 posts.tags 列（改名、合并、删除标签之后）
+pub(crate) fn sync_post_tags_json(conn: &Connection, post_id: i32) -> SqliteResult<()> {
+    let mut stmt = conn.prepare(
+        "SELECT t.name FROM post_tags pt JOIN tags t ON t.id = pt.tag_id WHERE pt.post_id = ? ORDER BY pt.id"
+    )?;
+    let names = stmt.query_map(params![post_id], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    let json = serde_json::to_string(&names).unwrap_or_else(|_| "[]".to_string());
+    conn.execute("UPDATE posts SET tags = ? WHERE id = ?", params![json, post_id])?;
+    Ok(())
+}
+
+pub(crate) fn posts_with_tag(conn: &Connection, tag_id: i32) -> SqliteResult<Vec<i32>> {
+    let mut stmt = conn.prepare("SELECT post_id FROM post_tags WHERE tag_id = ?")?;
+    let ids = stmt.query_map(params![tag_id], |row| row.get(0))?
+        .collect::<Result<Vec<i32>, _>>()?;
+    Ok(ids)
+}
@@ -0,0 +1,215 @@
+use anyhow::Result;
+use chrono::{DateTime, TimeZone, Utc};
+use rusqlite::types::ValueRef;
+use rusqlite::{params, Connection};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::repositories::user_relation_repo::HIDDEN_FROM_VIEWER;
+use crate::utils::time::parse_db_time;
+use crate::models::timeline::{
+    TimelineActor, TimelineCursor, TimelineItem, KIND_ACHIEVEMENT_EARNED, KIND_PACKAGE_PUBLISHED,
+    KIND_PACKAGE_UPDATED, KIND_POST_PUBLISHED,
+};
+
+/// 类型序号（同一时刻的排序依据，同时写入游标）
+const KINDS: [(i32, &str); 4] = [
+    (1, KIND_POST_PUBLISHED),
+    (2, KIND_PACKAGE_PUBLISHED),
+    (3, KIND_PACKAGE_UPDATED),
+    (4, KIND_ACHIEVEMENT_EARNED),
+];
+
+pub fn kind_ord(kind: &str) -> Option<i32> {
+    KINDS.iter().find(|(_, k)| *k == kind).map(|(ord, _)| *ord)
+}
+
+fn kind_name(ord: i32) -> &'static str {
+    KINDS.iter().find(|(o, _)| *o == ord).map(|(_, k)| *k).unwrap_or(KIND_POST_PUBLISHED)
+}
+
+#[derive(Clone)]
+pub struct TimelineRepository {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl TimelineRepository {
+    pub fn new(db_path: &str) -> Result<Self> {
+        let conn = Connection::open(db_path)?;
+        Ok(Self { conn: Arc::new(Mutex::new(conn)) })
+    }
+
+    /// 读取时合并（fan-out-on-read）：关注用户与订阅分类的新帖子、新资源、资源版本更新，以及关注用户获得的成就。
+    /// 版本更新来自 resource_records 中版本号发生变化的 Update 记录。不包含自己的动态
+    ///
+    /// 各分支直接比较原始时间列（游标按各表的存储格式换算），先在分支内排序并各取 limit 条再合并，
+    /// 以便利用索引；时间为空的记录不会进入时间线
+    pub async fn following_timeline(
+        &self,
+        user_id: i32,
+        kind: Option<i32>,
+        before: Option<TimelineCursor>,
+        limit: i64,
+    ) -> Result<Vec<TimelineItem>> {
+        let conn = self.conn.lock().await;
+        let bounds = BranchBounds::new(before);
+        // 拉黑（任一方向）与屏蔽的用户不出现在时间线中
+        let mut stmt = conn.prepare(&format!(
+            "WITH followed AS (SELECT followed_id AS id FROM user_follows WHERE follower_id = ?1),
+             cats AS (SELECT category_id AS id FROM subscriptions WHERE user_id = ?1 AND enabled = 1),
+             hidden AS ({hidden}),
+             items AS (
+                SELECT * FROM (
+                    SELECT 1 AS ord, p.id AS item_id, p.author_id AS actor_id, 'Post' AS target_type, p.id AS target_id,
+                           p.title AS title, substr(p.content, 1, 120) AS summary, p.category_id AS category_id,
+                           p.author_id IN (SELECT id FROM followed) AS via_follow, p.created_at AS at
+                    FROM posts p
+                    WHERE (?2 IS NULL OR ?2 = 1)
+                      AND p.status = 'Published' AND p.author_id != ?1 AND p.created_at IS NOT NULL
+                      AND (p.author_id IN (SELECT id FROM followed) OR p.category_id IN (SELECT id FROM cats))
+                      AND p.author_id NOT IN (SELECT * FROM hidden)
+                      AND (?4 IS NULL OR p.created_at < ?4 OR (p.created_at = ?4 AND p.id < ?5))
+                    ORDER BY p.created_at DESC, p.id DESC
+                    LIMIT ?3
+                )
+                UNION ALL
+                SELECT * FROM (
+                    SELECT 2, k.id, u.id, 'Package', k.id, k.name, substr(k.description, 1, 120), k.category_id,
+                           u.id IN (SELECT id FROM followed), k.created_at
+                    FROM packages k LEFT JOIN users u ON u.id = k.owner_id
+                    WHERE (?2 IS NULL OR ?2 = 2)
+                      AND k.status = 'active' AND u.id IS NOT ?1 AND k.created_at IS NOT NULL
+                      AND (u.id IN (SELECT id FROM followed) OR k.category_id IN (SELECT id FROM cats))
+                      AND (u.id IS NULL OR u.id NOT IN (SELECT * FROM hidden))
+                      AND (?6 IS NULL OR k.created_at < ?6 OR (k.created_at = ?6 AND k.id < ?7))
+                    ORDER BY k.created_at DESC, k.id DESC
+                    LIMIT ?3
+                )
+                UNION ALL
+                SELECT * FROM (
+                    SELECT 3, r.id, u.id, 'Package', k.id, k.name, json_extract(r.new_data, '$.version'), k.category_id,
+                           u.id IN (SELECT id FROM followed), r.timestamp
+                    FROM resource_records r
+                    JOIN packages k ON k.id = r.resource_id
+                    LEFT JOIN users u ON u.id = k.owner_id
+                    WHERE (?2 IS NULL OR ?2 = 3)
+                      AND r.resource_type = 'Package' AND r.action = 'Update' AND k.status = 'active'
+                      AND r.timestamp IS NOT NULL
+                      AND json_valid(r.old_data) AND json_valid(r.new_data)
+                      AND json_extract(r.new_data, '$.version') IS NOT NULL
+                      AND json_extract(r.old_data, '$.version') IS NOT json_extract(r.new_data, '$.version')
+                      AND u.id IS NOT ?1
+                      AND (u.id IN (SELECT id FROM followed) OR k.category_id IN (SELECT id FROM cats))
+                      AND (u.id IS NULL OR u.id NOT IN (SELECT * FROM hidden))
+                      AND (?8 IS NULL OR r.timestamp < ?8 OR (r.timestamp = ?8 AND r.id < ?9))
+                    ORDER BY r.timestamp DESC, r.id DESC
+                    LIMIT ?3
+                )
+                UNION ALL
+                SELECT * FROM (
+                    SELECT 4, ua.rowid, ua.user_id, 'Achievement', a.id, a.name, a.icon || ' ' || a.description, NULL,
+                           1, ua.earned_at
+                    FROM user_achievements ua JOIN achievements a ON a.id = ua.achievement_id
+                    WHERE (?2 IS NULL OR ?2 = 4)
+                      AND ua.user_id IN (SELECT id FROM followed) AND ua.earned_at IS NOT NULL
+                      AND ua.user_id NOT IN (SELECT * FROM hidden)
+                      AND (?10 IS NULL OR ua.earned_at < ?10 OR (ua.earned_at = ?10 AND ua.rowid < ?11))
+                    ORDER BY ua.earned_at DESC, ua.rowid DESC
+                    LIMIT ?3
+                )
+             )
+             SELECT i.ord, i.item_id, i.actor_id, i.target_type, i.target_id, i.title, i.summary, i.category_id,
+                    i.via_follow, i.at, u.username, u.nickname, u.avatar_url
+             FROM items i LEFT JOIN users u ON u.id = i.actor_id",
+            hidden = HIDDEN_FROM_VIEWER
+        ))?;
+        let rows = stmt.query_map(
+            params![
+                user_id, kind, limit,
+                bounds.post.0, bounds.post.1,
+                bounds.package.0, bounds.package.1,
+                bounds.record.0, bounds.record.1,
+                bounds.achievement.0, bounds.achievement.1,
+            ],
+            |row| {
+                let ord: i32 = row.get(0)?;
+                let item_id: i64 = row.get(1)?;
+                let actor_id: Option<i32> = row.get(2)?;
+                let username: Option<String> = row.get(10)?;
+                // 版本更新记录的时间为秒级整数，其余为文本时间
+                let created_at = match row.get_ref(9)? {
+                    ValueRef::Integer(secs) => Utc.timestamp_opt(secs, 0).single(),
+                    ValueRef::Text(text) => std::str::from_utf8(text).ok().and_then(parse_db_time),
+                    _ => None,
+                };
+                let actor = match (actor_id, username) {
+                    (Some(id), Some(username)) => Some(TimelineActor {
+                        id,
+                        username,
+                        nickname: row.get(11)?,
+                        avatar_url: row.get(12)?,
+                    }),
+                    _ => None,
+                };
+                Ok(created_at.map(|created_at| TimelineItem {
+                    kind: kind_name(ord).to_string(),
+                    actor,
+                    target_type: row.get(3).unwrap_or_default(),
+                    target_id: row.get(4).unwrap_or_default(),
+                    title: row.get::<_, Option<String>>(5).ok().flatten().unwrap_or_default(),
+                    summary: row.get(6).ok().flatten(),
+                    category_id: row.get(7).ok().flatten(),
+                    reason: if row.get::<_, Option<bool>>(8).ok().flatten().unwrap_or(false) { "following" } else { "category" }.to_string(),
+                    cursor: Some(TimelineCursor { ts: timestamp_nanos(&created_at), ord, id: item_id }),
+                    created_at,
+                }))
+            },
+        )?;
+        // 时间无法解析的记录直接跳过
+        let mut items: Vec<TimelineItem> = rows.filter_map(|r| r.transpose()).collect::<Result<Vec<_>, _>>()?;
+        items.sort_by_key(|item| std::cmp::Reverse(item.cursor.map(|c| (c.ts, c.ord, c.id))));
+        items.truncate(limit.max(0) as usize);
+        Ok(items)
+    }
+}
+
+fn timestamp_nanos(dt: &DateTime<Utc>) -> i64 {
+    dt.timestamp_nanos_opt().unwrap_or(i64::MAX)
+}
+
+/// 各分支的翻页边界：(按该表存储格式表示的游标时间, 时间相同时允许的最大条目ID)
+struct BranchBounds {
+    post: (Option<String>, i64),
+    package: (Option<String>, i64),
+    record: (Option<i64>, i64),
+    achievement: (Option<String>, i64),
+}
+
+impl BranchBounds {
+    fn new(before: Option<TimelineCursor>) -> Self {
+        let Some(cursor) = before else {
+            return BranchBounds { post: (None, 0), package: (None, 0), record: (None, 0), achievement: (None, 0) };
+        };
+        let at = Utc.timestamp_nanos(cursor.ts);
+        // 同一时刻按类型序号倒序：序号更小的分支整段保留，更大的整段排除，相同的按ID继续
+        let tie = |ord: i32| match ord.cmp(&cursor.ord) {
+            std::cmp::Ordering::Less => i64::MAX,
+            std::cmp::Ordering::Greater => i64::MIN,
+            std::cmp::Ordering::Equal => cursor.id,
+        };
+        // resource_records.timestamp 为秒级：游标带小数秒时，该秒内的记录都早于游标
+        let secs = cursor.ts.div_euclid(1_000_000_000);
+        let record = if cursor.ts.rem_euclid(1_000_000_000) == 0 {
+            (Some(secs), tie(3))
+        } else {
+            (Some(secs + 1), i64::MIN)
+        };
+        BranchBounds {
+            // posts.created_at 由 rusqlite 写入：`YYYY-MM-DD HH:MM:SS.f+00:00`
+            post: (Some(at.format("%Y-%m-%d %H:%M:%S%.f%:z").to_string()), tie(1)),
+            package: (Some(at.to_rfc3339()), tie(2)),
+            record,
+            achievement: (Some(at.to_rfc3339()), tie(4)),
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use anyhow::Result;
+use chrono::Utc;
+use rusqlite::{params, Connection};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::models::user_relation::{RelatedUser, RelationStatus};
+use crate::utils::time::parse_db_time;
+
+/// 对 ?1 隐藏的用户：我拉黑的、拉黑我的、我屏蔽的
+pub const HIDDEN_FROM_VIEWER: &str = "SELECT blocked_id FROM user_blocks WHERE blocker_id = ?1
+    UNION SELECT blocker_id FROM user_blocks WHERE blocked_id = ?1
+    UNION SELECT muted_id FROM user_mutes WHERE muter_id = ?1";
+
+/// 用户之间的拉黑（block）与屏蔽（mute）关系
+#[derive(Clone)]
+pub struct UserRelationRepository {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl UserRelationRepository {
+    pub fn new(db_path: &str) -> Result<Self> {
+        let conn = Connection::open(db_path)?;
+        Ok(Self { conn: Arc::new(Mutex::new(conn)) })
+    }
+
+    /// 拉黑，同时解除双方的关注关系
+    pub async fn block(&self, blocker_id: i32, blocked_id: i32) -> Result<bool> {
+        let mut conn = self.conn.lock().await;
+        let tx = conn.transaction()?;
+        let n = tx.execute(
+            "INSERT OR IGNORE INTO user_blocks (blocker_id, blocked_id) VALUES (?, ?)",
+            params![blocker_id, blocked_id],
+        )?;
+        tx.execute(
+            "DELETE FROM user_follows WHERE (follower_id = ?1 AND followed_id = ?2) OR (follower_id = ?2 AND followed_id = ?1)",
+            params![blocker_id, blocked_id],
+        )?;
+        tx.commit()?;
+        Ok(n > 0)
+    }
+
+    pub async fn unblock(&self, blocker_id: i32, blocked_id: i32) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let n = conn.execute(
+            "DELETE FROM user_blocks WHERE blocker_id = ? AND blocked_id = ?",
+            params![blocker_id, blocked_id],
+        )?;
+        Ok(n > 0)
+    }
+
+    pub async fn mute(&self, muter_id: i32, muted_id: i32) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let n = conn.execute(
+            "INSERT OR IGNORE INTO user_mutes (muter_id, muted_id) VALUES (?, ?)",
+            params![muter_id, muted_id],
+        )?;
+        Ok(n > 0)
+    }
+
+    pub async fn unmute(&self, muter_id: i32, muted_id: i32) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let n = conn.execute(
+            "DELETE FROM user_mutes WHERE muter_id = ? AND muted_id = ?",
+            params![muter_id, muted_id],
+        )?;
+        Ok(n > 0)
+    }
+
+    /// 任一方拉黑了另一方
+    pub async fn is_blocked_either(&self, a: i32, b: i32) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let blocked: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM user_blocks
+                WHERE (blocker_id = ?1 AND blocked_id = ?2) OR (blocker_id = ?2 AND blocked_id = ?1))",
+            params![a, b],
+            |row| row.get(0),
+        )?;
+        Ok(blocked)
+    }
+
+    pub async fn status(&self, user_id: i32, other_id: i32) -> Result<RelationStatus> {
+        let conn = self.conn.lock().await;
+        Ok(conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM user_blocks WHERE blocker_id = ?1 AND blocked_id = ?2),
+                    EXISTS(SELECT 1 FROM user_blocks WHERE blocker_id = ?2 AND blocked_id = ?1),
+                    EXISTS(SELECT 1 FROM user_mutes WHERE muter_id = ?1 AND muted_id = ?2)",
+            params![user_id, other_id],
+            |row| Ok(RelationStatus { is_blocked: row.get(0)?, blocked_me: row.get(1)?, is_muted: row.get(2)? }),
+        )?)
+    }
+
+    pub async fn list_blocked(&self, blocker_id: i32) -> Result<Vec<RelatedUser>> {
+        self.list_related(
+            "SELECT u.id, u.username, u.nickname, u.avatar_url, b.created_at
+             FROM user_blocks b JOIN users u ON u.id = b.blocked_id
+             WHERE b.blocker_id = ? ORDER BY b.created_at DESC",
+            blocker_id,
+        )
+        .await
+    }
+
+    pub async fn list_muted(&self, muter_id: i32) -> Result<Vec<RelatedUser>> {
+        self.list_related(
+            "SELECT u.id, u.username, u.nickname, u.avatar_url, m.created_at
+             FROM user_mutes m JOIN users u ON u.id = m.muted_id
+             WHERE m.muter_id = ? ORDER BY m.created_at DESC",
+            muter_id,
+        )
+        .await
+    }
+
+    async fn list_related(&self, sql: &str, user_id: i32) -> Result<Vec<RelatedUser>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(sql)?;
+        let rows = stmt.query_map(params![user_id], |row| {
+            Ok(RelatedUser {
+                id: row.get(0)?,
+                username: row.get(1)?,
+                nickname: row.get(2)?,
+                avatar: row.get(3)?,
+                created_at: parse_db_time(&row.get::<_, String>(4)?).unwrap_or_else(Utc::now),
+            })
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// 对 viewer 隐藏内容的用户 ID
+    pub async fn hidden_user_ids(&self, viewer_id: i32) -> Result<HashSet<i32>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(HIDDEN_FROM_VIEWER)?;
+        let rows = stmt.query_map(params![viewer_id], |row| row.get(0))?;
+        Ok(rows.collect::<Result<HashSet<_>, _>>()?)
+    }
+}
@@ -1,9 +1,11 @@
 use anyhow::Result;
 use rusqlite::{Connection, params, OptionalExtension};
 use crate::models::{User, Package, Comment};
+use crate::models::user::MentionCandidate;
+use crate::models::check_in::{CheckInRecord, CheckInStreak};
+use crate::utils::time::local_now;
 use std::sync::Arc;
 use tokio::sync::Mutex;
-use chrono::Datelike;
 
 #[derive(Clone)]
 pub struct UserRepository {
@@ -21,14 +23,14 @@ impl UserRepository {
     pub async fn get_user_ranking(&self, offset: i64, limit: i64) -> Result<(Vec<User>, i64)> {
         let conn = self.conn.lock().await;
         
-        // 获取用户排行榜数据（按star排序）
+        // 获取用户排行榜数据（按经验值排序，其次星星）
         let mut stmt = conn.prepare(
             "SELECT id, username, email, password_hash, nickname, bio, location, website, skills, role, star, ban_status, 
                     ban_reason, qq_number, avatar_url, login_count, upload_count, download_count, 
                     created_at, last_login, is_admin 
              FROM users 
-             WHERE ban_status = 'normal' 
-             ORDER BY star DESC, upload_count DESC, download_count DESC 
+             WHERE ban_status = 'normal' AND deleted_at IS NULL 
+             ORDER BY experience DESC, star DESC, upload_count DESC, download_count DESC 
              LIMIT ? OFFSET ?"
         )?;
 
@@ -69,7 +71,7 @@ impl UserRepository {
 
         // 获取总数
         let total: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM users WHERE ban_status = 'normal'",
+            "SELECT COUNT(*) FROM users WHERE ban_status = 'normal' AND deleted_at IS NULL",
             [],
             |row| row.get(0)
         )?;
@@ -83,7 +85,7 @@ impl UserRepository {
             "SELECT id, username, email, password_hash, nickname, bio, location, website, skills, role, star, ban_status, 
                     ban_reason, qq_number, avatar_url, login_count, upload_count, download_count, 
                     created_at, last_login, is_admin 
-             FROM users ORDER BY created_at DESC"
+             FROM users WHERE deleted_at IS NULL ORDER BY created_at DESC"
         )?;
 
         let users = stmt.query_map([], |row| {
@@ -460,36 +462,73 @@ impl UserRepository {
         Ok(())
     }
 
-    pub async fn delete_user(&self, user_id: i32) -> Result<()> {
+    /// 删除用户：移入回收站，账号保留但不能再登录
+    pub async fn soft_delete_user(&self, user_id: i32, deleted_by: i32) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let affected = conn.execute(
+            "UPDATE users SET deleted_at = datetime('now'), deleted_by = ? WHERE id = ? AND deleted_at IS NULL",
+            params![deleted_by, user_id],
+        )?;
+        Ok(affected > 0)
+    }
+
+    /// 是否在回收站中
+    pub async fn is_deleted(&self, user_id: i32) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let deleted = conn.query_row(
+            "SELECT deleted_at IS NOT NULL FROM users WHERE id = ?",
+            params![user_id],
+            |row| row.get(0),
+        ).optional()?;
+        Ok(deleted.unwrap_or(false))
+    }
+
+    /// 账号状态：(封禁状态, 是否在回收站中)，用户不存在时为 None
+    pub async fn account_status(&self, user_id: i32) -> Result<Option<(crate::models::BanStatus, bool)>> {
+        let conn = self.conn.lock().await;
+        let status = conn.query_row(
+            "SELECT COALESCE(ban_status, 'normal'), deleted_at IS NOT NULL FROM users WHERE id = ?",
+            params![user_id],
+            |row| {
+                let ban_status = match row.get::<_, String>(0)?.as_str() {
+                    "suspended" => crate::models::BanStatus::Suspended,
+                    "banned" => crate::models::BanStatus::Banned,
+                    _ => crate::models::BanStatus::Normal,
+                };
+                Ok((ban_status, row.get(1)?))
+            },
+        ).optional()?;
+        Ok(status)
+    }
+
+    /// 彻底删除用户（回收站清理）
+    pub async fn purge_user(&self, user_id: i32) -> Result<()> {
         let conn = self.conn.lock().await;
         conn.execute("DELETE FROM users WHERE id = ?", params![user_id])?;
         Ok(())
     }
 
-    // 新增方法：获取用户资源
+    // 新增方法：获取用户资源（自己拥有或参与维护的）
     pub async fn get_user_packages(&self, user_id: i32) -> Result<Vec<Package>> {
         let conn = self.conn.lock().await;
         
-        // 先获取用户名
-        let username: String = conn.query_row(
-            "SELECT username FROM users WHERE id = ?",
-            params![user_id],
-            |row| row.get(0)
-        )?;
-        
         let mut stmt = conn.prepare(
             "SELECT id, name, author, version, description, file_url, file_size, 
                     download_count, like_count, favorite_count, category_id, status, 
                     created_at, updated_at, reviewer_id, reviewed_at, review_comment,
-                    is_pinned, is_featured, screenshots, cover_image, requirements
-             FROM packages WHERE author = ? ORDER BY is_pinned DESC, is_featured DESC, created_at DESC"
+                    is_pinned, is_featured, screenshots, cover_image, requirements, owner_id
+             FROM packages
+             WHERE (owner_id = ?1 OR id IN (SELECT package_id FROM package_maintainers WHERE user_id = ?1))
+               AND status != 'deleted'
+             ORDER BY is_pinned DESC, is_featured DESC, created_at DESC"
         )?;
 
-        let packages = stmt.query_map(params![username], |row| {
+        let packages = stmt.query_map(params![user_id], |row| {
             Ok(Package {
                 id: row.get(0)?,
                 name: row.get(1)?,
                 author: row.get(2)?,
+                owner_id: row.get(22).ok().flatten(),
                 version: row.get(3)?,
                 description: row.get(4)?,
                 file_url: row.get(5)?,
@@ -631,7 +670,7 @@ impl UserRepository {
     }
 
     // 新增：批量删除用户
-    pub async fn batch_delete_users(&self, usernames: Vec<String>) -> Result<()> {
+    pub async fn batch_delete_users(&self, usernames: Vec<String>, deleted_by: i32) -> Result<()> {
         let conn = self.conn.lock().await;
         for username in usernames {
             // 检查用户是否存在且不是管理员
@@ -643,122 +682,105 @@ impl UserRepository {
             
             if !is_admin {
                 conn.execute(
-                    "DELETE FROM users WHERE username = ?",
-                    params![username]
+                    "UPDATE users SET deleted_at = datetime('now'), deleted_by = ? WHERE username = ? AND deleted_at IS NULL",
+                    params![deleted_by, username]
                 )?;
             }
         }
         Ok(())
     }
 
-    // 新增：用户签到
-    pub async fn user_check_in(&self, user_id: i32) -> Result<()> {
+    /// 写入一条签到记录（日期为社区时区的自然日），当天已有记录时返回 false
+    pub async fn insert_check_in(&self, user_id: i32, date: chrono::NaiveDate, is_makeup: bool) -> Result<bool> {
         let conn = self.conn.lock().await;
-        let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
-        
-        // 检查今天是否已经签到
-        let exists: bool = conn.query_row(
-            "SELECT EXISTS(SELECT 1 FROM user_check_ins WHERE user_id = ? AND check_in_date = ?)",
-            params![user_id, today],
-            |row| row.get(0)
-        ).unwrap_or(false);
-        
-        if !exists {
-            // 创建签到表（如果不存在）
-            conn.execute(
-                "CREATE TABLE IF NOT EXISTS user_check_ins (
-                    id INTEGER PRIMARY KEY AUTOINCREMENT,
-                    user_id INTEGER NOT NULL,
-                    check_in_date TEXT NOT NULL,
-                    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                    UNIQUE(user_id, check_in_date)
-                )",
-                []
-            )?;
-            
-            // 插入签到记录
-            conn.execute(
-                "INSERT INTO user_check_ins (user_id, check_in_date) VALUES (?, ?)",
-                params![user_id, today]
-            )?;
-        }
-        
-        Ok(())
+        let affected = conn.execute(
+            "INSERT OR IGNORE INTO user_check_ins (user_id, check_in_date, is_makeup) VALUES (?, ?, ?)",
+            params![user_id, date.format("%Y-%m-%d").to_string(), is_makeup]
+        )?;
+        Ok(affected > 0)
     }
 
-    // 新增：获取用户连续签到天数
-    pub async fn get_check_in_streak(&self, user_id: i32) -> Result<i32> {
+    /// 内容（资源/帖子/评论）的作者ID
+    pub async fn find_content_owner(&self, target_type: &str, target_id: i32) -> Result<Option<i32>> {
+        let conn = self.conn.lock().await;
+        let sql = match target_type {
+            "Package" => "SELECT owner_id FROM packages WHERE id = ? AND owner_id IS NOT NULL",
+            "Post" => "SELECT author_id FROM posts WHERE id = ?",
+            "Comment" => "SELECT user_id FROM comments WHERE id = ?",
+            _ => return Ok(None),
+        };
+        let owner = conn.query_row(sql, params![target_id], |row| row.get(0)).optional()?;
+        Ok(owner)
+    }
+
+    /// 用户的签到记录（按日期升序），可限定日期范围
+    pub async fn list_check_ins(
+        &self,
+        user_id: i32,
+        from: Option<chrono::NaiveDate>,
+        to: Option<chrono::NaiveDate>,
+    ) -> Result<Vec<CheckInRecord>> {
         let conn = self.conn.lock().await;
-        
-        // 确保表存在
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS user_check_ins (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                user_id INTEGER NOT NULL,
-                check_in_date TEXT NOT NULL,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                UNIQUE(user_id, check_in_date)
-            )",
-            []
-        )?;
-        
         let mut stmt = conn.prepare(
-            "SELECT check_in_date FROM user_check_ins 
-             WHERE user_id = ? 
-             ORDER BY check_in_date DESC"
+            "SELECT check_in_date, COALESCE(is_makeup, 0) FROM user_check_ins
+             WHERE user_id = ? AND (? IS NULL OR check_in_date >= ?) AND (? IS NULL OR check_in_date <= ?)
+             ORDER BY check_in_date ASC"
         )?;
-        
-        let dates: Vec<String> = stmt.query_map(params![user_id], |row| {
-            Ok(row.get::<_, String>(0)?)
-        })?
-        .collect::<Result<Vec<_>, _>>()?;
-        
-        if dates.is_empty() {
-            return Ok(0);
-        }
-        
-        let mut streak = 1;
-        let mut current_date = chrono::NaiveDate::parse_from_str(&dates[0], "%Y-%m-%d")
-            .map_err(|e| anyhow::anyhow!("日期解析错误: {}", e))?;
-        
-        for date_str in dates.iter().skip(1) {
-            let date = chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
+        let from = from.map(|d| d.format("%Y-%m-%d").to_string());
+        let to = to.map(|d| d.format("%Y-%m-%d").to_string());
+        let rows = stmt.query_map(params![user_id, from, from, to, to], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, bool>(1)?))
+        })?;
+        let mut records = Vec::new();
+        for row in rows {
+            let (date, is_makeup) = row?;
+            // 兼容旧数据中带时间部分的日期
+            let date = chrono::NaiveDate::parse_from_str(date.get(..10).unwrap_or(&date), "%Y-%m-%d")
                 .map_err(|e| anyhow::anyhow!("日期解析错误: {}", e))?;
-            
-            if current_date - date == chrono::Duration::days(1) {
-                streak += 1;
-                current_date = date;
-            } else {
-                break;
-            }
+            records.push(CheckInRecord { date, is_makeup });
         }
-        
-        Ok(streak)
+        Ok(records)
     }
 
-    // 新增：获取用户本周发布统计
-    pub async fn get_weekly_posts_count(&self, user_id: i32) -> Result<Vec<i32>> {
+    /// 指定时间之后进行的补签次数（按补签操作时间，而非补签的日期）
+    pub async fn count_makeup_check_ins_since(&self, user_id: i32, since: chrono::DateTime<chrono::Utc>) -> Result<i64> {
         let conn = self.conn.lock().await;
-        let mut weekly_counts = vec![0; 7]; // 周一到周日
-        
-        // 获取本周的开始和结束日期
-        let now = chrono::Utc::now();
-        let days_since_monday = now.weekday().num_days_from_monday();
-        let monday = now - chrono::Duration::days(days_since_monday as i64);
-        
-        for i in 0..7 {
-            let date = monday + chrono::Duration::days(i);
-            let date_str = date.format("%Y-%m-%d").to_string();
-            
-            let count: i64 = conn.query_row(
-                "SELECT COUNT(*) FROM posts WHERE author_id = ? AND DATE(created_at) = ?",
-                params![user_id, date_str],
-                |row| row.get(0)
-            ).unwrap_or(0);
-            
-            weekly_counts[i as usize] = count as i32;
-        }
-        
-        Ok(weekly_counts)
+        let count = conn.query_row(
+            "SELECT COUNT(*) FROM user_check_ins WHERE user_id = ? AND is_makeup = 1 AND created_at >= ?",
+            params![user_id, since.format("%Y-%m-%d %H:%M:%S").to_string()],
+            |row| row.get(0)
+        )?;
+        Ok(count)
     }
-} 
\ No newline at end of file
+
+    /// 当前连续签到天数（按社区时区的自然日计算）
+    pub async fn get_check_in_streak(&self, user_id: i32) -> Result<i32> {
+        let dates: Vec<chrono::NaiveDate> = self.list_check_ins(user_id, None, None).await?
+            .into_iter()
+            .map(|r| r.date)
+            .collect();
+        Ok(CheckInStreak::compute(&dates, local_now().date_naive()).current_streak)
+    }
+
+    /// @ 提及自动补全：按用户名/昵称前缀匹配，排除已封禁用户，用户名完全匹配优先
+    pub async fn search_mention_candidates(&self, prefix: &str, limit: i32) -> Result<Vec<MentionCandidate>> {
+        let conn = self.conn.lock().await;
+        let escaped = prefix.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+        let pattern = format!("{}%", escaped);
+        let mut stmt = conn.prepare(
+            "SELECT id, username, nickname, avatar_url FROM users
+             WHERE (username LIKE ?1 ESCAPE '\\' OR nickname LIKE ?1 ESCAPE '\\') AND COALESCE(ban_status, 'normal') != 'banned'
+             ORDER BY CASE WHEN username = ?2 COLLATE NOCASE THEN 0 ELSE 1 END, LENGTH(username), username
+             LIMIT ?3"
+        )?;
+        let rows = stmt.query_map(params![pattern, prefix, limit], |row| {
+            Ok(MentionCandidate {
+                id: row.get(0)?,
+                username: row.get(1)?,
+                nickname: row.get(2)?,
+                avatar_url: row.get(3)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+}
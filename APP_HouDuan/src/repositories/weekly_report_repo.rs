@@ -0,0 +1,242 @@
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use rusqlite::{params, Connection, OptionalExtension, ToSql};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::models::weekly_report::{ReportWeek, WeeklyReport};
+
+/// 统计区间：[start, end)，UTC；本周尚未结束时 end 为下周一，统计截至当前
+pub struct ReportRange {
+    pub week: ReportWeek,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// 社区时区相对 UTC 的秒数，用于把时间戳折算到本地自然日
+    pub offset_secs: i32,
+}
+
+#[derive(Clone)]
+pub struct WeeklyReportRepository {
+    conn: Arc<Mutex<Connection>>,
+}
+
+fn sql_time(t: DateTime<Utc>) -> String {
+    t.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// 点赞、关注、下载、签到等统计表有的是按需创建的，尚不存在时记为 0
+fn count_if_table<T: rusqlite::types::FromSql + Default>(conn: &Connection, sql: &str, params: &[&dyn ToSql]) -> Result<T> {
+    match conn.query_row(sql, params, |row| row.get(0)) {
+        Ok(count) => Ok(count),
+        Err(e) if e.to_string().contains("no such table") => Ok(T::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+impl WeeklyReportRepository {
+    pub fn new(db_path: &str) -> Result<Self> {
+        let conn = Connection::open(db_path)?;
+        let repo = Self { conn: Arc::new(Mutex::new(conn)) };
+        futures::executor::block_on(repo.init())?;
+        Ok(repo)
+    }
+
+    pub async fn init(&self) -> Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS weekly_reports (
+                user_id INTEGER NOT NULL,
+                week TEXT NOT NULL,
+                data TEXT NOT NULL,
+                generated_at TEXT NOT NULL,
+                emailed_at TEXT,
+                PRIMARY KEY (user_id, week)
+            )",
+            [],
+        )?;
+        Ok(())
+    }
+
+    pub async fn get_cached(&self, user_id: i32, week: &str) -> Result<Option<WeeklyReport>> {
+        let conn = self.conn.lock().await;
+        let data: Option<String> = conn.query_row(
+            "SELECT data FROM weekly_reports WHERE user_id = ? AND week = ?",
+            params![user_id, week],
+            |row| row.get(0),
+        ).optional()?;
+        Ok(data.and_then(|d| serde_json::from_str(&d).ok()))
+    }
+
+    pub async fn save(&self, user_id: i32, report: &WeeklyReport) -> Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "INSERT INTO weekly_reports (user_id, week, data, generated_at) VALUES (?, ?, ?, ?)
+             ON CONFLICT(user_id, week) DO UPDATE SET data = excluded.data, generated_at = excluded.generated_at",
+            params![user_id, report.week, serde_json::to_string(report)?, report.generated_at.to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    pub async fn is_emailed(&self, user_id: i32, week: &str) -> Result<bool> {
+        let conn = self.conn.lock().await;
+        let emailed = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM weekly_reports WHERE user_id = ? AND week = ? AND emailed_at IS NOT NULL)",
+            params![user_id, week],
+            |row| row.get(0),
+        )?;
+        Ok(emailed)
+    }
+
+    pub async fn mark_emailed(&self, user_id: i32, week: &str) -> Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "UPDATE weekly_reports SET emailed_at = ? WHERE user_id = ? AND week = ?",
+            params![Utc::now().to_rfc3339(), user_id, week],
+        )?;
+        Ok(())
+    }
+
+    /// 今日活跃度（0-100）：按社区时区的今天，发帖 20、发资源 30、评论 5、点赞 2、签到 10 分累计
+    pub async fn today_activity(&self, user_id: i32, day_start: DateTime<Utc>, today: NaiveDate) -> Result<f32> {
+        let conn = self.conn.lock().await;
+        let start = sql_time(day_start);
+        let count = |sql: &str| -> Result<i64> { count_if_table(&conn, sql, &[&user_id, &start]) };
+        let posts = count("SELECT COUNT(*) FROM posts WHERE author_id = ? AND status = 'Published' AND datetime(created_at) >= ?")?;
+        let resources = count(
+            "SELECT COUNT(*) FROM packages WHERE owner_id = ? AND status = 'active' AND datetime(created_at) >= ?",
+        )?;
+        let comments = count("SELECT COUNT(*) FROM comments WHERE user_id = ? AND datetime(created_at) >= ?")?;
+        let likes = count("SELECT COUNT(*) FROM post_likes WHERE user_id = ? AND datetime(created_at) >= ?")?
+            + count("SELECT COUNT(*) FROM package_likes WHERE user_id = ? AND datetime(created_at) >= ?")?;
+        let checked_in: i64 = count_if_table(
+            &conn,
+            "SELECT COUNT(*) FROM user_check_ins WHERE user_id = ? AND check_in_date = ?",
+            &[&user_id, &today.format("%Y-%m-%d").to_string()],
+        )?;
+        let score = posts * 20 + resources * 30 + comments * 5 + likes * 2 + checked_in.min(1) * 10;
+        Ok(score.min(100) as f32)
+    }
+
+    /// 从业务表统计周报：只统计已发布的帖子与已上架的资源，按需创建的统计表不存在时计为 0
+    pub async fn compute(&self, user_id: i32, range: &ReportRange) -> Result<WeeklyReport> {
+        let conn = self.conn.lock().await;
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM users WHERE id = ?)",
+            params![user_id],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            return Err(anyhow::anyhow!("用户不存在"));
+        }
+        let (start, end) = (sql_time(range.start), sql_time(range.end));
+        let offset = format!("{:+} seconds", range.offset_secs);
+
+        let count = |sql: &str, key: &dyn ToSql| -> Result<i32> { count_if_table(&conn, sql, &[key, &start, &end]) };
+
+        // 每日发布数：按社区时区的自然日归档
+        let daily = |sql: &str, key: &dyn ToSql| -> Result<Vec<i32>> {
+            let mut days = vec![0; 7];
+            let mut stmt = conn.prepare(sql)?;
+            let rows = stmt.query_map(params![offset, key, start, end], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i32>(1)?))
+            })?;
+            for row in rows {
+                let (day, n) = row?;
+                if let Ok(day) = NaiveDate::parse_from_str(&day, "%Y-%m-%d") {
+                    let idx = (day - range.week.monday).num_days();
+                    if (0..7).contains(&idx) {
+                        days[idx as usize] += n;
+                    }
+                }
+            }
+            Ok(days)
+        };
+        let daily_posts = daily(
+            "SELECT date(datetime(created_at), ?) AS d, COUNT(*) FROM posts
+             WHERE author_id = ? AND status = 'Published' AND datetime(created_at) >= ? AND datetime(created_at) < ? GROUP BY d",
+            &user_id,
+        )?;
+        let daily_resources = daily(
+            "SELECT date(datetime(created_at), ?) AS d, COUNT(*) FROM packages
+             WHERE owner_id = ? AND status = 'active' AND datetime(created_at) >= ? AND datetime(created_at) < ? GROUP BY d",
+            &user_id,
+        )?;
+
+        let downloads_received = count(
+            "SELECT COUNT(*) FROM download_records d JOIN packages p ON p.id = d.package_id
+             WHERE p.owner_id = ? AND datetime(d.created_at) >= ? AND datetime(d.created_at) < ?",
+            &user_id,
+        )?;
+        let likes_received = count(
+            "SELECT COUNT(*) FROM post_likes l JOIN posts p ON p.id = l.post_id
+             WHERE p.author_id = ?1 AND l.user_id != ?1 AND datetime(l.created_at) >= ?2 AND datetime(l.created_at) < ?3",
+            &user_id,
+        )? + count(
+            "SELECT COUNT(*) FROM package_likes l JOIN packages p ON p.id = l.package_id
+             WHERE p.owner_id = ?1 AND l.user_id != ?1 AND datetime(l.created_at) >= ?2 AND datetime(l.created_at) < ?3",
+            &user_id,
+        )?;
+        let new_followers = count(
+            "SELECT COUNT(*) FROM user_follows WHERE followed_id = ? AND datetime(created_at) >= ? AND datetime(created_at) < ?",
+            &user_id,
+        )?;
+        let comments_posted = count(
+            "SELECT COUNT(*) FROM comments WHERE user_id = ? AND datetime(created_at) >= ? AND datetime(created_at) < ?",
+            &user_id,
+        )?;
+        let check_in_days: i32 = count_if_table(
+            &conn,
+            "SELECT COUNT(*) FROM user_check_ins WHERE user_id = ? AND check_in_date >= ? AND check_in_date <= ?",
+            &[&user_id, &range.week.monday.format("%Y-%m-%d").to_string(), &range.week.sunday().format("%Y-%m-%d").to_string()],
+        )?;
+        // 积分流水的 created_at 为 RFC3339，直接按原始列比较以使用 (user_id, created_at) 索引
+        let (ledger_start, ledger_end) = (range.start.to_rfc3339(), range.end.to_rfc3339());
+        let (xp_gained, stars_gained): (i64, i64) = conn.query_row(
+            "SELECT COALESCE(SUM(xp), 0), COALESCE(SUM(stars), 0) FROM point_ledger
+             WHERE user_id = ? AND created_at >= ? AND created_at < ?",
+            params![user_id, ledger_start, ledger_end],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+
+        // 某一时刻的经验排名：用当前经验减去该时刻之后的流水还原当时的经验，流水按用户汇总一次
+        let rank_at = |at: &str| -> Result<i32> {
+            let rank = conn.query_row(
+                "WITH later AS (
+                    SELECT user_id, SUM(xp) AS xp FROM point_ledger WHERE created_at >= ?1 GROUP BY user_id
+                 ),
+                 xp_at AS (
+                    SELECT u.id, COALESCE(u.experience, 0) - COALESCE(l.xp, 0) AS xp
+                    FROM users u LEFT JOIN later l ON l.user_id = u.id
+                 )
+                 SELECT 1 + COUNT(*) FROM xp_at WHERE xp > (SELECT xp FROM xp_at WHERE id = ?2)",
+                params![at, user_id],
+                |row| row.get(0),
+            )?;
+            Ok(rank)
+        };
+        let rank_start = rank_at(&ledger_start)?;
+        let rank_end = rank_at(&ledger_end)?;
+
+        Ok(WeeklyReport {
+            week: range.week.key(),
+            week_start: range.week.monday,
+            week_end: range.week.sunday(),
+            complete: false,
+            posts_published: daily_posts.iter().sum(),
+            resources_published: daily_resources.iter().sum(),
+            daily_posts,
+            daily_resources,
+            downloads_received,
+            likes_received,
+            new_followers,
+            comments_posted,
+            check_in_days,
+            xp_gained,
+            stars_gained,
+            rank_start,
+            rank_end,
+            rank_change: rank_start - rank_end,
+            generated_at: Utc::now(),
+        })
+    }
+}
@@ -0,0 +1,200 @@
+use anyhow::Result;
+
+use crate::models::achievement::{
+    AchievementCriteria, AchievementDefinition, CreateAchievementRequest, UpdateAchievementRequest, UserAchievement,
+};
+use crate::repositories::achievement_repo::AchievementRepository;
+use crate::repositories::user_repo::UserRepository;
+use crate::services::notification_service::NotificationService;
+use crate::utils::error::ServiceError;
+
+/// 行为影响的是谁的成就
+enum Beneficiary {
+    /// 行为发起者本人（签到、发评论）
+    Actor,
+    /// 行为目标的作者（被点赞、被加精、审核通过）
+    TargetOwner,
+}
+
+/// 行为与成就条件的对应关系，未列出的行为不触发评估
+fn criteria_for_action(action_type: &str, target_type: Option<&str>) -> Option<(Beneficiary, AchievementCriteria)> {
+    match (action_type, target_type) {
+        ("CheckIn", _) => Some((Beneficiary::Actor, AchievementCriteria::CheckInStreak)),
+        ("Comment", _) => Some((Beneficiary::Actor, AchievementCriteria::CommentsPosted)),
+        ("Like", Some("Package")) | ("Like", Some("Post")) => Some((Beneficiary::TargetOwner, AchievementCriteria::LikesReceived)),
+        ("Feature", Some("Post")) => Some((Beneficiary::TargetOwner, AchievementCriteria::FeaturedPosts)),
+        ("Approve", Some("Package")) => Some((Beneficiary::TargetOwner, AchievementCriteria::PackagesUploaded)),
+        ("Approve", Some("Post")) => Some((Beneficiary::TargetOwner, AchievementCriteria::PostsPublished)),
+        _ => None,
+    }
+}
+
+#[derive(Clone)]
+pub struct AchievementService {
+    achievement_repo: AchievementRepository,
+    user_repo: UserRepository,
+    notification_service: Option<NotificationService>,
+}
+
+impl AchievementService {
+    pub fn new(achievement_repo: AchievementRepository, user_repo: UserRepository) -> Self {
+        Self { achievement_repo, user_repo, notification_service: None }
+    }
+
+    pub fn with_notification_service(mut self, service: NotificationService) -> Self {
+        self.notification_service = Some(service);
+        self
+    }
+
+    async fn metric(&self, user_id: i32, criteria: AchievementCriteria) -> Result<i64> {
+        match criteria {
+            AchievementCriteria::CheckInStreak => Ok(self.user_repo.get_check_in_streak(user_id).await? as i64),
+            _ => self.achievement_repo.metric(user_id, criteria).await,
+        }
+    }
+
+    /// 记录到一条用户行为后增量评估相关成就，返回新获得的成就
+    pub async fn on_action(
+        &self,
+        actor_id: Option<i32>,
+        action_type: &str,
+        target_type: Option<&str>,
+        target_id: Option<i32>,
+    ) -> Result<Vec<AchievementDefinition>> {
+        let Some((beneficiary, criteria)) = criteria_for_action(action_type, target_type) else {
+            return Ok(Vec::new());
+        };
+        let user_id = match beneficiary {
+            Beneficiary::Actor => actor_id,
+            Beneficiary::TargetOwner => match (target_type, target_id) {
+                (Some(t), Some(id)) => self.user_repo.find_content_owner(t, id).await?,
+                _ => None,
+            },
+        };
+        match user_id {
+            Some(user_id) => self.evaluate(user_id, Some(criteria)).await,
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// 评估用户成就；criteria 为 None 时评估全部条件（用于补发历史数据已满足的成就）
+    pub async fn evaluate(&self, user_id: i32, criteria: Option<AchievementCriteria>) -> Result<Vec<AchievementDefinition>> {
+        let definitions: Vec<AchievementDefinition> = self.achievement_repo.list_definitions(false).await?
+            .into_iter()
+            .filter(|d| criteria.is_none_or(|c| d.criteria_type == c))
+            .collect();
+        if definitions.is_empty() {
+            return Ok(Vec::new());
+        }
+        let earned = self.achievement_repo.list_earned(user_id).await?;
+
+        let mut awarded = Vec::new();
+        let mut metrics = std::collections::HashMap::new();
+        for def in definitions.into_iter().filter(|d| !earned.contains_key(&d.id)) {
+            let value = match metrics.get(&def.criteria_type) {
+                Some(v) => *v,
+                None => {
+                    let v = self.metric(user_id, def.criteria_type).await?;
+                    metrics.insert(def.criteria_type, v);
+                    v
+                }
+            };
+            if value >= def.threshold && self.achievement_repo.award(user_id, def.id).await? {
+                self.announce(user_id, &def).await;
+                awarded.push(def);
+            }
+        }
+        Ok(awarded)
+    }
+
+    async fn announce(&self, user_id: i32, def: &AchievementDefinition) {
+        log::info!("用户 {} 获得成就 {}", user_id, def.code);
+        if let Some(notify) = &self.notification_service {
+            let content = format!("恭喜您获得成就「{} {}」：{}", def.icon, def.name, def.description);
+            let link = format!("/user/{}", user_id);
+            if let Err(e) = notify.notify(user_id, "获得新成就", &content, Some(&link), Some("AchievementEarned"), Some("Achievement"), Some(def.id)).await {
+                log::error!("发送成就通知失败: {}", e);
+            }
+        }
+    }
+
+    /// 用户的全部成就（已启用的定义），已获得的在前，未获得的附带进度
+    pub async fn list_user_achievements(&self, user_id: i32) -> Result<Vec<UserAchievement>> {
+        let definitions = self.achievement_repo.list_definitions(false).await?;
+        let earned = self.achievement_repo.list_earned(user_id).await?;
+        let mut metrics = std::collections::HashMap::new();
+        let mut list = Vec::with_capacity(definitions.len());
+        for def in definitions {
+            let earned_at = earned.get(&def.id).copied();
+            let progress = if earned_at.is_some() {
+                def.threshold
+            } else {
+                let value = match metrics.get(&def.criteria_type) {
+                    Some(v) => *v,
+                    None => {
+                        let v = self.metric(user_id, def.criteria_type).await.unwrap_or(0);
+                        metrics.insert(def.criteria_type, v);
+                        v
+                    }
+                };
+                value.min(def.threshold)
+            };
+            list.push(UserAchievement {
+                id: def.id,
+                code: def.code,
+                name: def.name,
+                icon: def.icon,
+                description: def.description,
+                criteria_type: def.criteria_type,
+                threshold: def.threshold,
+                progress,
+                earned: earned_at.is_some(),
+                earned_at,
+            });
+        }
+        // 已获得的按获得时间倒序排在前面，其余保持定义顺序
+        list.sort_by(|a, b| match (a.earned_at, b.earned_at) {
+            (Some(x), Some(y)) => y.cmp(&x),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        });
+        Ok(list)
+    }
+
+    /// 用户已获得的成就
+    pub async fn list_earned_achievements(&self, user_id: i32) -> Result<Vec<UserAchievement>> {
+        Ok(self.list_user_achievements(user_id).await?.into_iter().filter(|a| a.earned).collect())
+    }
+
+    // 管理员：成就定义维护
+
+    pub async fn list_definitions(&self) -> Result<Vec<AchievementDefinition>> {
+        self.achievement_repo.list_definitions(true).await
+    }
+
+    pub async fn create_definition(&self, req: CreateAchievementRequest) -> Result<AchievementDefinition> {
+        if req.code.trim().is_empty() || req.name.trim().is_empty() {
+            return Err(ServiceError::bad_request("成就编码和名称不能为空"));
+        }
+        if AchievementCriteria::parse(&req.criteria_type).is_none() {
+            return Err(ServiceError::bad_request(format!("不支持的成就条件类型: {}", req.criteria_type)));
+        }
+        if req.threshold < 1 {
+            return Err(ServiceError::bad_request("成就阈值必须大于 0"));
+        }
+        let id = self.achievement_repo.create_definition(&req).await
+            .map_err(|e| if e.to_string().contains("UNIQUE") { ServiceError::bad_request("成就编码已存在") } else { e })?;
+        self.achievement_repo.get_definition(id).await?.ok_or_else(|| ServiceError::not_found("成就不存在"))
+    }
+
+    pub async fn update_definition(&self, id: i32, req: UpdateAchievementRequest) -> Result<AchievementDefinition> {
+        if req.threshold.is_some_and(|t| t < 1) {
+            return Err(ServiceError::bad_request("成就阈值必须大于 0"));
+        }
+        if !self.achievement_repo.update_definition(id, &req).await? {
+            return Err(ServiceError::not_found("成就不存在"));
+        }
+        self.achievement_repo.get_definition(id).await?.ok_or_else(|| ServiceError::not_found("成就不存在"))
+    }
+}
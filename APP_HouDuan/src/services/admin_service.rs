@@ -326,17 +326,6 @@ impl AdminService {
     }
 }
 
-#[derive(Serialize)]
-pub struct DashboardStats {
-    pub system_status: String,
-    pub uptime: String,
-    pub log_today: i64,
-    pub package_total: i64,
-    pub package_available: i64,
-    pub user_total: i64,
-    pub user_active: i64,
-}
-
 #[derive(Serialize)]
 pub struct SystemLog {
     pub id: i32,
@@ -369,19 +358,6 @@ pub struct ThemeSettings {
     pub language: String,
 }
 
-pub async fn get_dashboard_stats() -> DashboardStats {
-    // TODO: 替换为真实数据库查询
-    DashboardStats {
-        system_status: "正常".to_string(),
-        uptime: "15天 8小时 32分钟".to_string(),
-        log_today: 12,
-        package_total: 123,
-        package_available: 120,
-        user_total: 456,
-        user_active: 78,
-    }
-} 
-
 impl AdminService {
     // 获取社区设置
     pub async fn get_community_settings(&self) -> Result<crate::models::system::CommunitySettings> {
@@ -40,6 +40,10 @@ impl AuthService {
         };
         
         let user = user.ok_or_else(|| anyhow::anyhow!("用户不存在"))?;
+        // 回收站中的账号视为不存在
+        if self.user_repo.is_deleted(user.id).await? {
+            return Err(anyhow::anyhow!("用户不存在"));
+        }
 
         // 验证密码
         if !self.password_utils.verify_password(password, &user.password_hash)? {
@@ -70,6 +74,9 @@ impl AuthService {
         // 查找用户
         let user = self.user_repo.find_by_email(email).await?;
         let user = user.ok_or_else(|| anyhow::anyhow!("该邮箱未注册账户"))?;
+        if self.user_repo.is_deleted(user.id).await? {
+            return Err(anyhow::anyhow!("该邮箱未注册账户"));
+        }
 
         // 检查用户状态
         if user.ban_status != crate::models::BanStatus::Normal {
@@ -89,8 +96,9 @@ impl AuthService {
     pub async fn send_login_code(&self, email: &str) -> Result<()> {
         // 检查邮箱是否已注册
         let user = self.user_repo.find_by_email(email).await?;
-        if user.is_none() {
-            return Err(anyhow::anyhow!("该邮箱未注册账户"));
+        match user {
+            Some(user) if !self.user_repo.is_deleted(user.id).await? => {}
+            _ => return Err(anyhow::anyhow!("该邮箱未注册账户")),
         }
 
         // 生成6位验证码
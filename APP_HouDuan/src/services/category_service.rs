@@ -0,0 +1,340 @@
+use anyhow::Result;
+use log::warn;
+use serde_json::json;
+use std::collections::HashMap;
+
+use crate::models::category::{
+    CategoryCrumb, CategoryNode, CategoryRules, CATEGORY_RULE_FIELDS, MAX_CATEGORY_DEPTH, POST_PERMISSIONS,
+};
+use crate::models::system::{Category, CreateCategoryRequest, UpdateCategoryRequest};
+use crate::models::{Package, PackageStatus, UpdatePackageRequest, UserRole};
+use crate::repositories::system_repo::SystemRepository;
+use crate::services::package_service::PackageService;
+use crate::utils::error::ServiceError;
+
+/// 层级分类：树形列表、面包屑与分类规则。
+/// 规则（是否审核、允许的扩展名、文件大小上限、发布权限）为空时沿父分类继承；
+/// 整条链都未设置时默认需要审核、所有人可发布，扩展名与文件大小不做限制
+#[derive(Clone)]
+pub struct CategoryService {
+    system_repo: SystemRepository,
+    package_service: PackageService,
+}
+
+impl CategoryService {
+    pub fn new(system_repo: SystemRepository, package_service: PackageService) -> Self {
+        Self { system_repo, package_service }
+    }
+
+    async fn all(&self) -> Result<HashMap<i32, Category>> {
+        let categories = self.system_repo.get_categories().await?;
+        Ok(categories.into_iter().map(|c| (c.id, c)).collect())
+    }
+
+    pub async fn list(&self) -> Result<Vec<Category>> {
+        self.system_repo.get_categories().await
+    }
+
+    pub async fn get(&self, id: i32) -> Result<Category> {
+        self.system_repo
+            .get_category_by_id(id)
+            .await?
+            .ok_or_else(|| ServiceError::not_found("分类不存在"))
+    }
+
+    /// 从指定分类到顶级的父链（自身在前）；父分类缺失时在该处截止
+    fn chain(categories: &HashMap<i32, Category>, id: i32) -> Vec<&Category> {
+        let mut chain: Vec<&Category> = Vec::new();
+        let mut current = categories.get(&id);
+        while let Some(category) = current {
+            if chain.iter().any(|c| c.id == category.id) {
+                break;
+            }
+            chain.push(category);
+            current = category.parent_id.and_then(|parent| categories.get(&parent));
+        }
+        chain
+    }
+
+    fn crumbs(categories: &HashMap<i32, Category>, id: i32) -> Vec<CategoryCrumb> {
+        Self::chain(categories, id)
+            .into_iter()
+            .rev()
+            .map(|c| CategoryCrumb { id: c.id, name: c.name.clone(), icon: c.icon.clone() })
+            .collect()
+    }
+
+    /// 面包屑：从顶级分类到指定分类
+    pub async fn path(&self, id: i32) -> Result<Vec<CategoryCrumb>> {
+        let categories = self.all().await?;
+        if !categories.contains_key(&id) {
+            return Err(ServiceError::not_found("分类不存在"));
+        }
+        Ok(Self::crumbs(&categories, id))
+    }
+
+    /// 分类树；父分类已不存在的分类挂到顶级
+    pub async fn tree(&self) -> Result<Vec<CategoryNode>> {
+        let categories = self.system_repo.get_categories().await?;
+        let counts = self.system_repo.count_active_packages_by_category().await?;
+        let ids: Vec<i32> = categories.iter().map(|c| c.id).collect();
+        let mut children: HashMap<Option<i32>, Vec<Category>> = HashMap::new();
+        for category in categories {
+            let parent = category.parent_id.filter(|p| ids.contains(p));
+            children.entry(parent).or_default().push(category);
+        }
+
+        fn build(parent: Option<i32>, children: &mut HashMap<Option<i32>, Vec<Category>>, counts: &HashMap<i32, i32>) -> Vec<CategoryNode> {
+            children
+                .remove(&parent)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|category| CategoryNode {
+                    count: counts.get(&category.id).copied().unwrap_or(0),
+                    children: build(Some(category.id), children, counts),
+                    category,
+                })
+                .collect()
+        }
+        Ok(build(None, &mut children, &counts))
+    }
+
+    /// 为已序列化的帖子或资源补充 category_path，无分类时为空数组
+    pub async fn attach_paths(&self, items: &mut [serde_json::Value]) {
+        let categories = self.all().await.unwrap_or_else(|e| {
+            warn!("读取分类失败: {}", e);
+            HashMap::new()
+        });
+        for item in items.iter_mut() {
+            let category_id = item.get("category_id").and_then(|v| v.as_i64()).map(|v| v as i32);
+            let path = category_id.map(|id| Self::crumbs(&categories, id)).unwrap_or_default();
+            if let serde_json::Value::Object(map) = item {
+                map.insert("category_path".to_string(), json!(path));
+            }
+        }
+    }
+
+    /// 统一扩展名格式：去掉前导点、转小写、去重
+    fn normalize_extensions(extensions: &mut Option<Vec<String>>) -> Result<()> {
+        if let Some(list) = extensions {
+            let mut normalized: Vec<String> = Vec::new();
+            for ext in list.iter() {
+                let ext = ext.trim().trim_start_matches('.').to_lowercase();
+                if ext.is_empty() || !ext.chars().all(|c| c.is_ascii_alphanumeric()) {
+                    return Err(ServiceError::bad_request(format!("扩展名无效: {}", ext)));
+                }
+                if !normalized.contains(&ext) {
+                    normalized.push(ext);
+                }
+            }
+            if normalized.is_empty() {
+                return Err(ServiceError::bad_request("允许的扩展名不能为空，恢复默认请使用 inherit"));
+            }
+            *list = normalized;
+        }
+        Ok(())
+    }
+
+    fn check_rules(max_file_size: Option<i64>, post_permission: Option<&str>) -> Result<()> {
+        if max_file_size.is_some_and(|size| size <= 0) {
+            return Err(ServiceError::bad_request("文件大小上限必须大于 0"));
+        }
+        if let Some(permission) = post_permission {
+            if !POST_PERMISSIONS.contains(&permission) {
+                return Err(ServiceError::bad_request(format!("发布权限必须是 {} 之一", POST_PERMISSIONS.join(" / "))));
+            }
+        }
+        Ok(())
+    }
+
+    /// 子树高度（自身为 1）
+    fn height(categories: &HashMap<i32, Category>, id: i32) -> usize {
+        1 + categories
+            .values()
+            .filter(|c| c.parent_id == Some(id) && c.id != id)
+            .map(|c| Self::height(categories, c.id))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// 父分类须存在，不能是自身或子孙分类，移动后层级不超过上限
+    async fn check_parent(&self, id: Option<i32>, parent_id: i32) -> Result<()> {
+        let categories = self.all().await?;
+        if !categories.contains_key(&parent_id) {
+            return Err(ServiceError::bad_request("父分类不存在"));
+        }
+        let parent_chain = Self::chain(&categories, parent_id);
+        let height = match id {
+            Some(id) => {
+                if parent_chain.iter().any(|c| c.id == id) {
+                    return Err(ServiceError::bad_request("不能把分类移到自身或其子分类下"));
+                }
+                Self::height(&categories, id)
+            }
+            None => 1,
+        };
+        if parent_chain.len() + height > MAX_CATEGORY_DEPTH {
+            return Err(ServiceError::bad_request(format!("分类层级不能超过 {} 层", MAX_CATEGORY_DEPTH)));
+        }
+        Ok(())
+    }
+
+    pub async fn create(&self, mut req: CreateCategoryRequest) -> Result<Category> {
+        req.name = req.name.trim().to_string();
+        if req.name.is_empty() {
+            return Err(ServiceError::bad_request("分类名称不能为空"));
+        }
+        Self::normalize_extensions(&mut req.allowed_extensions)?;
+        Self::check_rules(req.max_file_size, req.post_permission.as_deref())?;
+        if req.parent_id == Some(0) {
+            req.parent_id = None;
+        }
+        if let Some(parent_id) = req.parent_id {
+            self.check_parent(None, parent_id).await?;
+        }
+        self.system_repo.create_category(&req).await
+    }
+
+    pub async fn update(&self, id: i32, mut req: UpdateCategoryRequest) -> Result<Category> {
+        self.get(id).await?;
+        if let Some(name) = req.name.as_mut() {
+            *name = name.trim().to_string();
+            if name.is_empty() {
+                return Err(ServiceError::bad_request("分类名称不能为空"));
+            }
+        }
+        Self::normalize_extensions(&mut req.allowed_extensions)?;
+        Self::check_rules(req.max_file_size, req.post_permission.as_deref())?;
+        if let Some(field) = req.inherit.iter().flatten().find(|f| !CATEGORY_RULE_FIELDS.contains(&f.as_str())) {
+            return Err(ServiceError::bad_request(format!("未知的规则: {}", field)));
+        }
+        if let Some(parent_id) = req.parent_id.filter(|p| *p != 0) {
+            self.check_parent(Some(id), parent_id).await?;
+        }
+        self.system_repo.update_category(id, &req).await
+    }
+
+    /// 有子分类时不能删除；分类下的资源保留原分类ID，由管理员另行移动
+    pub async fn delete(&self, id: i32) -> Result<()> {
+        self.get(id).await?;
+        let categories = self.all().await?;
+        if categories.values().any(|c| c.parent_id == Some(id)) {
+            return Err(ServiceError::bad_request("请先移动或删除子分类"));
+        }
+        self.system_repo.delete_category(id).await
+    }
+
+    /// 分类实际生效的规则；category_id 为空时即默认规则
+    pub async fn rules(&self, category_id: Option<i32>) -> Result<CategoryRules> {
+        let categories = self.all().await?;
+        let chain = category_id.map(|id| Self::chain(&categories, id)).unwrap_or_default();
+        Ok(CategoryRules {
+            category_id,
+            requires_review: chain.iter().find_map(|c| c.requires_review).unwrap_or(true),
+            allowed_extensions: chain.iter().find_map(|c| c.allowed_extensions.clone()),
+            max_file_size: chain.iter().find_map(|c| c.max_file_size),
+            post_permission: chain
+                .iter()
+                .find_map(|c| c.post_permission.clone())
+                .unwrap_or_else(|| "all".to_string()),
+        })
+    }
+
+    /// 检查用户能否在分类下发布：分类须存在且启用，角色满足发布权限
+    pub async fn check_can_post(&self, category_id: Option<i32>, role: &UserRole) -> Result<CategoryRules> {
+        if let Some(id) = category_id {
+            if !self.get(id).await?.enabled {
+                return Err(ServiceError::bad_request("分类已停用"));
+            }
+        }
+        let rules = self.rules(category_id).await?;
+        let allowed = match rules.post_permission.as_str() {
+            "admin" => matches!(role, UserRole::Admin),
+            "elder" => matches!(role, UserRole::Admin | UserRole::Elder | UserRole::Moderator),
+            _ => true,
+        };
+        if !allowed {
+            let who = if rules.post_permission == "admin" { "管理员" } else { "元老及以上用户" };
+            return Err(ServiceError::forbidden(format!("只有{}可以在该分类发布", who)));
+        }
+        Ok(rules)
+    }
+
+    /// 检查资源文件是否符合所在分类的扩展名与大小限制
+    pub async fn check_upload(&self, package_id: i32, file_name: &str, file_size: usize) -> Result<()> {
+        let category_id = self
+            .package_service
+            .get_package_by_id(package_id)
+            .await?
+            .ok_or_else(|| ServiceError::not_found("资源不存在"))?
+            .category_id;
+        let rules = self.rules(category_id).await?;
+        let ext = std::path::Path::new(file_name)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+        if let Some(allowed) = rules.allowed_extensions.as_ref().filter(|allowed| !allowed.contains(&ext)) {
+            return Err(ServiceError::bad_request(format!(
+                "该分类不允许上传 .{} 文件，允许的类型: {}",
+                ext,
+                allowed.join(", ")
+            )));
+        }
+        if let Some(max_file_size) = rules.max_file_size.filter(|max| file_size as i64 > *max) {
+            let limit = if max_file_size >= 1024 * 1024 {
+                format!("{:.1} MB", max_file_size as f64 / 1024.0 / 1024.0)
+            } else {
+                format!("{} KB", (max_file_size as f64 / 1024.0).ceil())
+            };
+            return Err(ServiceError::bad_request(format!("文件大小超过该分类上限 {}", limit)));
+        }
+        Ok(())
+    }
+
+    /// 新建资源后按分类规则处理审核：分类不要求审核时直接上架
+    pub async fn apply_review_rule(&self, package: Package) -> Result<Package> {
+        if package.status != PackageStatus::Pending || self.rules(package.category_id).await?.requires_review {
+            return Ok(package);
+        }
+        let req = UpdatePackageRequest {
+            status: Some(PackageStatus::Active),
+            reviewed_at: Some(chrono::Utc::now()),
+            review_comment: Some("分类无需审核，自动上架".to_string()),
+            ..Default::default()
+        };
+        self.package_service.update_package(package.id, &req).await
+    }
+
+    /// 非管理员从免审核分类移到需要审核的分类时，已上架的资源重新进入审核
+    pub async fn needs_review_after_move(&self, from: Option<i32>, to: Option<i32>) -> Result<bool> {
+        Ok(from != to && !self.rules(from).await?.requires_review && self.rules(to).await?.requires_review)
+    }
+
+    /// 将资源移到其他分类，文件目录随之移动（见 PackageService::update_package_recorded）
+    pub async fn move_package(&self, package_id: i32, category_id: i32, role: &UserRole) -> Result<Package> {
+        let package = self
+            .package_service
+            .get_package_by_id(package_id)
+            .await?
+            .ok_or_else(|| ServiceError::not_found("资源不存在"))?;
+        if package.category_id == Some(category_id) {
+            return Ok(package);
+        }
+        let is_admin = matches!(role, UserRole::Admin | UserRole::Elder);
+        if !is_admin {
+            self.check_can_post(Some(category_id), role).await?;
+        } else {
+            self.get(category_id).await?;
+        }
+        let review = !is_admin
+            && package.status == PackageStatus::Active
+            && self.needs_review_after_move(package.category_id, Some(category_id)).await?;
+        let req = UpdatePackageRequest {
+            category_id: Some(category_id),
+            status: review.then_some(PackageStatus::Pending),
+            ..Default::default()
+        };
+        self.package_service.update_package(package_id, &req).await
+    }
+}
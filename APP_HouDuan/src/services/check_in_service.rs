@@ -0,0 +1,180 @@
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Utc};
+
+use crate::models::achievement::AchievementCriteria;
+use crate::models::check_in::{
+    CalendarDay, CheckInCalendar, CheckInRecord, CheckInResult, CheckInStreak, MakeupInfo, MAKEUP_MONTHLY_LIMIT,
+    MAKEUP_WINDOW_DAYS,
+};
+use crate::models::points::RULE_CHECK_IN_MAKEUP;
+use crate::repositories::user_repo::UserRepository;
+use crate::services::achievement_service::AchievementService;
+use crate::services::points_service::PointsService;
+use crate::utils::error::ServiceError;
+use crate::utils::time::{community_offset, local_now};
+
+#[derive(Clone)]
+pub struct CheckInService {
+    user_repo: UserRepository,
+    points_service: PointsService,
+    achievement_service: Option<AchievementService>,
+}
+
+/// 某月第一天与最后一天
+fn month_bounds(first: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let next = if first.month() == 12 {
+        NaiveDate::from_ymd_opt(first.year() + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(first.year(), first.month() + 1, 1)
+    };
+    (first, next.expect("valid date") - Duration::days(1))
+}
+
+/// 社区时区本月第一天零点（UTC）
+fn current_month_start() -> DateTime<Utc> {
+    let first = local_now().date_naive().with_day(1).expect("valid date");
+    let midnight = first.and_hms_opt(0, 0, 0).expect("valid time");
+    community_offset()
+        .from_local_datetime(&midnight)
+        .single()
+        .map(|d| d.with_timezone(&Utc))
+        .unwrap_or_else(Utc::now)
+}
+
+fn streak_of(records: &[CheckInRecord], today: NaiveDate) -> CheckInStreak {
+    let dates: Vec<NaiveDate> = records.iter().map(|r| r.date).collect();
+    CheckInStreak::compute(&dates, today)
+}
+
+impl CheckInService {
+    pub fn new(user_repo: UserRepository, points_service: PointsService) -> Self {
+        Self { user_repo, points_service, achievement_service: None }
+    }
+
+    pub fn with_achievement_service(mut self, service: AchievementService) -> Self {
+        self.achievement_service = Some(service);
+        self
+    }
+
+    /// 今日签到：按社区时区划分自然日，首次签到时按积分规则发放奖励
+    pub async fn check_in(&self, user_id: i32) -> Result<CheckInResult> {
+        let today = local_now().date_naive();
+        let inserted = self.user_repo.insert_check_in(user_id, today, false).await?;
+        // 签到记录已写入，奖励失败不影响签到结果；奖励按日期去重，再次签到时会补发
+        let reward = match self.points_service.on_action(Some(user_id), "CheckIn", None, None).await {
+            Ok(reward) => reward,
+            Err(e) => {
+                log::error!("发放签到奖励失败: {}", e);
+                None
+            }
+        };
+        Ok(CheckInResult {
+            date: today,
+            already_checked_in: !inserted,
+            streak: self.current_streak(user_id, today).await,
+            reward,
+        })
+    }
+
+    /// 补签最近几天漏掉的签到，消耗星星；补签不发放签到奖励，只用于接续连续天数。
+    /// 每月次数按补签操作所在的月份计算
+    pub async fn make_up(&self, user_id: i32, date: &str) -> Result<CheckInResult> {
+        let date = NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+            .map_err(|_| ServiceError::bad_request("日期格式错误，应为 YYYY-MM-DD"))?;
+        let today = local_now().date_naive();
+        let days_ago = (today - date).num_days();
+        if days_ago < 1 {
+            return Err(ServiceError::bad_request("只能补签今天之前的日期"));
+        }
+        if days_ago > MAKEUP_WINDOW_DAYS {
+            return Err(ServiceError::bad_request(format!("只能补签最近 {} 天内的日期", MAKEUP_WINDOW_DAYS)));
+        }
+        let reward = self
+            .points_service
+            .make_up_check_in(user_id, date, current_month_start(), MAKEUP_MONTHLY_LIMIT)
+            .await?;
+
+        if let Some(achievements) = &self.achievement_service {
+            if let Err(e) = achievements.evaluate(user_id, Some(AchievementCriteria::CheckInStreak)).await {
+                log::error!("评估签到成就失败: {}", e);
+            }
+        }
+
+        Ok(CheckInResult {
+            date,
+            already_checked_in: false,
+            streak: self.current_streak(user_id, today).await,
+            reward,
+        })
+    }
+
+    /// 签到已写入后计算连续天数，查询失败时只记录日志
+    async fn current_streak(&self, user_id: i32, today: NaiveDate) -> CheckInStreak {
+        match self.user_repo.list_check_ins(user_id, None, None).await {
+            Ok(records) => streak_of(&records, today),
+            Err(e) => {
+                log::error!("查询签到记录失败: {}", e);
+                CheckInStreak::compute(&[], today)
+            }
+        }
+    }
+
+    /// 补签配置与本月（社区时区）剩余次数
+    async fn makeup_info(&self, user_id: i32) -> Result<MakeupInfo> {
+        let rule = self.points_service.get_rule(RULE_CHECK_IN_MAKEUP).await?;
+        let used = self.user_repo.count_makeup_check_ins_since(user_id, current_month_start()).await?;
+        Ok(MakeupInfo {
+            enabled: rule.as_ref().is_some_and(|r| r.enabled),
+            cost: rule.map(|r| (-r.stars).max(0)).unwrap_or(0),
+            window_days: MAKEUP_WINDOW_DAYS,
+            monthly_limit: MAKEUP_MONTHLY_LIMIT,
+            remaining: (MAKEUP_MONTHLY_LIMIT - used).max(0),
+        })
+    }
+
+    /// 签到状态：连续天数与本月补签信息
+    pub async fn status(&self, user_id: i32) -> Result<(CheckInStreak, MakeupInfo)> {
+        let today = local_now().date_naive();
+        let records = self.user_repo.list_check_ins(user_id, None, None).await?;
+        let makeup = self.makeup_info(user_id).await?;
+        Ok((streak_of(&records, today), makeup))
+    }
+
+    /// 月度签到日历，month 为 YYYY-MM，默认本月
+    pub async fn calendar(&self, user_id: i32, month: Option<&str>) -> Result<CheckInCalendar> {
+        let today = local_now().date_naive();
+        let first = match month.map(str::trim).filter(|m| !m.is_empty()) {
+            Some(m) => NaiveDate::parse_from_str(&format!("{}-01", m), "%Y-%m-%d")
+                .map_err(|_| ServiceError::bad_request("月份格式错误，应为 YYYY-MM"))?,
+            None => today.with_day(1).expect("valid date"),
+        };
+        let (from, to) = month_bounds(first);
+        let records = self.user_repo.list_check_ins(user_id, None, None).await?;
+        let makeup = self.makeup_info(user_id).await?;
+
+        let days: Vec<CalendarDay> = from.iter_days()
+            .take_while(|d| *d <= to)
+            .map(|date| {
+                let record = records.iter().find(|r| r.date == date);
+                let days_ago = (today - date).num_days();
+                CalendarDay {
+                    date,
+                    checked_in: record.is_some(),
+                    is_makeup: record.is_some_and(|r| r.is_makeup),
+                    can_makeup: record.is_none()
+                        && makeup.enabled
+                        && makeup.remaining > 0
+                        && (1..=MAKEUP_WINDOW_DAYS).contains(&days_ago),
+                }
+            })
+            .collect();
+
+        Ok(CheckInCalendar {
+            month: first.format("%Y-%m").to_string(),
+            checked_days: days.iter().filter(|d| d.checked_in).count() as i32,
+            days,
+            streak: streak_of(&records, today),
+            makeup,
+        })
+    }
+}
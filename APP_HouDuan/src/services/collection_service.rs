@@ -0,0 +1,259 @@
+use anyhow::Result;
+
+use crate::models::collection::{
+    CreateCollectionRequest, FavoriteCollection, FavoriteItem, FavoriteTarget, UpdateCollectionRequest,
+    FAVORITE_TARGET_TYPES, MAX_COLLECTIONS_PER_USER,
+};
+use crate::repositories::collection_repo::CollectionRepository;
+use crate::repositories::user_repo::UserRepository;
+use crate::services::forbidden_word_service::ForbiddenWordService;
+use crate::services::notification_service::NotificationService;
+use crate::utils::error::ServiceError;
+
+const MAX_NAME_CHARS: usize = 30;
+const MAX_DESCRIPTION_CHARS: usize = 200;
+
+/// 收藏夹：收藏本身仍是资源/帖子的收藏，收藏夹只负责分组、排序与分享
+#[derive(Clone)]
+pub struct CollectionService {
+    collection_repo: CollectionRepository,
+    user_repo: UserRepository,
+    forbidden_service: Option<ForbiddenWordService>,
+    notification_service: Option<NotificationService>,
+}
+
+impl CollectionService {
+    pub fn new(collection_repo: CollectionRepository, user_repo: UserRepository) -> Self {
+        Self { collection_repo, user_repo, forbidden_service: None, notification_service: None }
+    }
+
+    pub fn with_forbidden_service(mut self, service: ForbiddenWordService) -> Self {
+        self.forbidden_service = Some(service);
+        self
+    }
+
+    pub fn with_notification_service(mut self, service: NotificationService) -> Self {
+        self.notification_service = Some(service);
+        self
+    }
+
+    /// 校验名称与简介，返回去除首尾空白后的值
+    async fn check_fields(&self, name: &str, description: Option<&str>) -> Result<(String, Option<String>)> {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return Err(ServiceError::bad_request("收藏夹名称不能为空"));
+        }
+        if name.chars().count() > MAX_NAME_CHARS {
+            return Err(ServiceError::bad_request(format!("收藏夹名称不能超过{}个字符", MAX_NAME_CHARS)));
+        }
+        let description = description.map(|d| d.trim().to_string()).filter(|d| !d.is_empty());
+        if let Some(d) = &description {
+            if d.chars().count() > MAX_DESCRIPTION_CHARS {
+                return Err(ServiceError::bad_request(format!("收藏夹简介不能超过{}个字符", MAX_DESCRIPTION_CHARS)));
+            }
+        }
+        if let Some(f_service) = &self.forbidden_service {
+            let text = format!("{} {}", name, description.as_deref().unwrap_or(""));
+            if f_service.contains_forbidden_word(&text).await.unwrap_or(false) {
+                return Err(ServiceError::bad_request("收藏夹名称或简介包含违禁词"));
+            }
+        }
+        Ok((name, description))
+    }
+
+    fn check_target(target: &FavoriteTarget) -> Result<()> {
+        if !FAVORITE_TARGET_TYPES.contains(&target.target_type.as_str()) {
+            return Err(ServiceError::bad_request(format!("无效的收藏类型: {}", target.target_type)));
+        }
+        Ok(())
+    }
+
+    /// 取自己的收藏夹，不属于自己时按不存在处理
+    async fn own_collection(&self, user_id: i32, collection_id: i32) -> Result<FavoriteCollection> {
+        match self.collection_repo.find(collection_id, Some(user_id)).await? {
+            Some(c) if c.user_id == user_id => Ok(c),
+            _ => Err(ServiceError::not_found("收藏夹不存在")),
+        }
+    }
+
+    /// 查看收藏夹：私密收藏夹仅本人可见
+    async fn visible_collection(&self, viewer_id: Option<i32>, collection_id: i32) -> Result<FavoriteCollection> {
+        match self.collection_repo.find(collection_id, viewer_id).await? {
+            Some(c) if c.is_public || Some(c.user_id) == viewer_id => Ok(c),
+            _ => Err(ServiceError::not_found("收藏夹不存在")),
+        }
+    }
+
+    // ========== 收藏夹管理 ==========
+
+    pub async fn create(&self, user_id: i32, req: CreateCollectionRequest) -> Result<FavoriteCollection> {
+        let (name, description) = self.check_fields(&req.name, req.description.as_deref()).await?;
+        if self.collection_repo.count_by_user(user_id).await? >= MAX_COLLECTIONS_PER_USER {
+            return Err(ServiceError::bad_request(format!("最多创建{}个收藏夹", MAX_COLLECTIONS_PER_USER)));
+        }
+        if self.collection_repo.name_exists(user_id, &name, None).await? {
+            return Err(ServiceError::bad_request("已存在同名收藏夹"));
+        }
+        let id = self
+            .collection_repo
+            .create(user_id, &name, description.as_deref(), req.is_public.unwrap_or(false))
+            .await?;
+        self.own_collection(user_id, id).await
+    }
+
+    pub async fn update(&self, user_id: i32, collection_id: i32, req: UpdateCollectionRequest) -> Result<FavoriteCollection> {
+        let current = self.own_collection(user_id, collection_id).await?;
+        let name = req.name.unwrap_or(current.name);
+        let description = match req.description {
+            Some(d) => Some(d),
+            None => current.description,
+        };
+        let (name, description) = self.check_fields(&name, description.as_deref()).await?;
+        if self.collection_repo.name_exists(user_id, &name, Some(collection_id)).await? {
+            return Err(ServiceError::bad_request("已存在同名收藏夹"));
+        }
+        let is_public = req.is_public.unwrap_or(current.is_public);
+        self.collection_repo.update(collection_id, &name, description.as_deref(), is_public).await?;
+        self.own_collection(user_id, collection_id).await
+    }
+
+    pub async fn delete(&self, user_id: i32, collection_id: i32) -> Result<()> {
+        self.own_collection(user_id, collection_id).await?;
+        self.collection_repo.delete(collection_id).await
+    }
+
+    pub async fn reorder(&self, user_id: i32, ids: &[i32]) -> Result<Vec<FavoriteCollection>> {
+        self.collection_repo.reorder(user_id, ids).await?;
+        self.collection_repo.list_by_user(user_id, Some(user_id), false).await
+    }
+
+    pub async fn list_mine(&self, user_id: i32) -> Result<Vec<FavoriteCollection>> {
+        self.collection_repo.list_by_user(user_id, Some(user_id), false).await
+    }
+
+    /// 某用户的公开收藏夹（本人查看时包含私密的）
+    pub async fn list_by_user(&self, owner_id: i32, viewer_id: Option<i32>) -> Result<Vec<FavoriteCollection>> {
+        if self.user_repo.find_by_id(owner_id).await?.is_none() {
+            return Err(ServiceError::not_found("用户不存在"));
+        }
+        let public_only = viewer_id != Some(owner_id);
+        self.collection_repo.list_by_user(owner_id, viewer_id, public_only).await
+    }
+
+    /// 收藏夹分享页：收藏夹信息与条目；他人查看时跳过已删除或下架的内容
+    pub async fn get_page(
+        &self,
+        viewer_id: Option<i32>,
+        collection_id: i32,
+        page: i64,
+        page_size: i64,
+    ) -> Result<(FavoriteCollection, Vec<FavoriteItem>, i64)> {
+        let collection = self.visible_collection(viewer_id, collection_id).await?;
+        let only_available = viewer_id != Some(collection.user_id);
+        let (items, total) = self
+            .collection_repo
+            .list_items(collection_id, only_available, page, page_size)
+            .await?;
+        Ok((collection, items, total))
+    }
+
+    // ========== 收藏条目 ==========
+
+    /// 收藏并放入收藏夹（已收藏的条目从原收藏夹移过来）
+    pub async fn add_item(&self, user_id: i32, collection_id: i32, target: FavoriteTarget) -> Result<()> {
+        Self::check_target(&target)?;
+        self.own_collection(user_id, collection_id).await?;
+        if !self.collection_repo.target_exists(&target).await? {
+            return Err(ServiceError::not_found("收藏的内容不存在"));
+        }
+        self.collection_repo.add_favorite(user_id, &target).await?;
+        self.collection_repo.move_items(user_id, &[target], Some(collection_id)).await?;
+        Ok(())
+    }
+
+    /// 从收藏夹移出，收藏保留在默认收藏中
+    pub async fn remove_item(&self, user_id: i32, collection_id: i32, target: FavoriteTarget) -> Result<()> {
+        Self::check_target(&target)?;
+        self.own_collection(user_id, collection_id).await?;
+        if !self.collection_repo.remove_item(collection_id, user_id, &target).await? {
+            return Err(ServiceError::bad_request("该内容不在此收藏夹中"));
+        }
+        Ok(())
+    }
+
+    /// 批量移动已收藏的条目到收藏夹，collection_id 为空表示移回默认收藏；未收藏的条目会被忽略
+    pub async fn move_items(&self, user_id: i32, items: &[FavoriteTarget], collection_id: Option<i32>) -> Result<usize> {
+        if items.is_empty() {
+            return Err(ServiceError::bad_request("请选择要移动的收藏"));
+        }
+        for item in items {
+            Self::check_target(item)?;
+        }
+        if let Some(cid) = collection_id {
+            self.own_collection(user_id, cid).await?;
+        }
+        self.collection_repo.move_items(user_id, items, collection_id).await
+    }
+
+    pub async fn reorder_items(&self, user_id: i32, collection_id: i32, items: &[FavoriteTarget]) -> Result<()> {
+        self.own_collection(user_id, collection_id).await?;
+        self.collection_repo.reorder_items(collection_id, items).await
+    }
+
+    pub async fn list_favorites(
+        &self,
+        user_id: i32,
+        target_type: Option<&str>,
+        uncategorized: bool,
+        page: i64,
+        page_size: i64,
+    ) -> Result<(Vec<FavoriteItem>, i64)> {
+        if let Some(t) = target_type {
+            if !FAVORITE_TARGET_TYPES.contains(&t) {
+                return Err(ServiceError::bad_request(format!("无效的收藏类型: {}", t)));
+            }
+        }
+        self.collection_repo
+            .list_favorites(user_id, target_type, uncategorized, page, page_size)
+            .await
+    }
+
+    // ========== 关注收藏夹 ==========
+
+    /// 关注他人的公开收藏夹，首次关注时通知收藏夹作者
+    pub async fn follow(&self, user_id: i32, collection_id: i32) -> Result<()> {
+        let collection = self.visible_collection(Some(user_id), collection_id).await?;
+        if collection.user_id == user_id {
+            return Err(ServiceError::bad_request("不能关注自己的收藏夹"));
+        }
+        if self.collection_repo.follow(user_id, collection_id).await? {
+            if let Some(notify) = &self.notification_service {
+                let actor = self
+                    .user_repo
+                    .find_by_id(user_id)
+                    .await?
+                    .map(|u| u.nickname.unwrap_or(u.username))
+                    .unwrap_or_default();
+                let content = format!("{} 关注了您的收藏夹《{}》", actor, collection.name);
+                let link = format!("/collections/{}", collection_id);
+                if let Err(e) = notify
+                    .by_actor(user_id)
+                    .notify(collection.user_id, "收藏夹被关注", &content, Some(&link), Some("CollectionFollowed"), Some("Collection"), Some(collection_id))
+                    .await
+                {
+                    log::warn!("发送收藏夹关注通知失败: {}", e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn unfollow(&self, user_id: i32, collection_id: i32) -> Result<()> {
+        self.collection_repo.unfollow(user_id, collection_id).await?;
+        Ok(())
+    }
+
+    pub async fn list_followed(&self, user_id: i32) -> Result<Vec<FavoriteCollection>> {
+        self.collection_repo.list_followed(user_id).await
+    }
+}
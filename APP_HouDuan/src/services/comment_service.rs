@@ -2,12 +2,18 @@ use anyhow::Result;
 use chrono::Utc;
 
 use crate::models::Comment;
+use crate::models::edit_revision::PendingRevision;
+use crate::models::notification::MentionSource;
 use crate::repositories::comment_repo::CommentRepository;
 use crate::repositories::user_repo::UserRepository;
 use crate::repositories::package_repo::PackageRepository;
 use crate::repositories::user_action_repo::UserActionRepository;
+use crate::repositories::user_relation_repo::UserRelationRepository;
 use crate::services::forbidden_word_service::ForbiddenWordService;
 use crate::services::notification_service::NotificationService;
+use crate::services::achievement_service::AchievementService;
+use crate::services::points_service::PointsService;
+use crate::utils::error::ServiceError;
 
 #[derive(Clone)]
 pub struct CommentService {
@@ -17,11 +23,14 @@ pub struct CommentService {
     user_action_repo: Option<UserActionRepository>,
     forbidden_service: Option<ForbiddenWordService>,
     notification_service: Option<NotificationService>,
+    points_service: Option<PointsService>,
+    achievement_service: Option<AchievementService>,
+    relation_repo: Option<UserRelationRepository>,
 }
 
 impl CommentService {
     pub fn new(comment_repo: CommentRepository, user_repo: UserRepository) -> Self {
-        Self { comment_repo, user_repo, package_repo: None, user_action_repo: None, forbidden_service: None, notification_service: None }
+        Self { comment_repo, user_repo, package_repo: None, user_action_repo: None, forbidden_service: None, notification_service: None, points_service: None, achievement_service: None, relation_repo: None }
     }
 
     pub fn with_package_repo(mut self, package_repo: PackageRepository) -> Self {
@@ -44,6 +53,21 @@ impl CommentService {
         self
     }
 
+    pub fn with_points_service(mut self, service: PointsService) -> Self {
+        self.points_service = Some(service);
+        self
+    }
+
+    pub fn with_relation_repo(mut self, relation_repo: UserRelationRepository) -> Self {
+        self.relation_repo = Some(relation_repo);
+        self
+    }
+
+    pub fn with_achievement_service(mut self, service: AchievementService) -> Self {
+        self.achievement_service = Some(service);
+        self
+    }
+
     // 获取所有评论（管理员接口）
     pub async fn get_all_comments(
         &self,
@@ -87,7 +111,16 @@ impl CommentService {
         // 敏感词检测
         if let Some(f_service) = &self.forbidden_service {
             if f_service.contains_forbidden_word(&content).await.unwrap_or(false) {
-                return Err(anyhow::anyhow!("评论内容包含违禁词"));
+                return Err(ServiceError::bad_request("评论内容包含违禁词"));
+            }
+        }
+
+        // 拉黑：双方不能互相回复评论
+        if let (Some(pid), Some(relations)) = (parent_id, &self.relation_repo) {
+            if let Some(parent) = self.comment_repo.get_comment_by_id(pid).await? {
+                if parent.user_id != user_id && relations.is_blocked_either(user_id, parent.user_id).await? {
+                    return Err(ServiceError::forbidden("无法回复：你与对方存在拉黑关系"));
+                }
             }
         }
 
@@ -130,17 +163,35 @@ impl CommentService {
             comment.author_qq = user.qq_number.clone();
         }
 
+        let actor_name = comment.author_name.clone().unwrap_or_default();
+        let link = if comment.target_type == "Post" { format!("/post/{}", comment.target_id) } else { format!("/resource/{}", comment.target_id) };
+        // 已通知的用户，避免同一条评论重复通知
+        let mut notified: Vec<i32> = Vec::new();
+
+        // 回复：通知父评论作者
+        if let (Some(pid), Some(notify)) = (parent_id, &self.notification_service) {
+            if let Ok(Some(parent)) = self.comment_repo.get_comment_by_id(pid).await {
+                if parent.user_id != user_id {
+                    let content = format!("{} 回复了您的评论：{}", actor_name, excerpt(&comment.content));
+                    match notify.by_actor(user_id).notify(parent.user_id, "评论收到回复", &content, Some(&link), Some("CommentReply"), Some("Comment"), Some(comment_id)).await {
+                        Ok(_) => notified.push(parent.user_id),
+                        Err(e) => log::error!("发送回复通知失败: {}", e),
+                    }
+                }
+            }
+        }
+
         // 给资源作者发送站内通知（仅当评论目标为 Package）
         if comment.target_type.eq_ignore_ascii_case("Package") {
             if let (Some(pkg_repo), Some(notify)) = (&self.package_repo, &self.notification_service) {
                 if let Ok(Some(pkg)) = pkg_repo.find_by_id(comment.target_id).await {
-                    if let Ok(Some(author_user)) = self.user_repo.find_by_username(&pkg.author).await {
-                        if author_user.id != user_id {
-                            let link = format!("/resource/{}", pkg.id);
+                    if let Some(owner_id) = pkg.owner_id {
+                        if owner_id != user_id && !notified.contains(&owner_id) {
                             let title = "资源收到新评论";
                             let content = format!("您的资源《{}》有一条新评论", pkg.name);
-                            if let Err(e) = notify.notify(author_user.id, title, &content, Some(&link), Some("CommentReceived"), Some("Package"), Some(pkg.id)).await {
-                                log::error!("发送评论通知失败: {}", e);
+                            match notify.by_actor(user_id).notify(owner_id, title, &content, Some(&link), Some("CommentReceived"), Some("Package"), Some(pkg.id)).await {
+                                Ok(_) => notified.push(owner_id),
+                                Err(e) => log::error!("发送评论通知失败: {}", e),
                             }
                         }
                     }
@@ -148,17 +199,44 @@ impl CommentService {
             }
         }
 
+        // @ 提及
+        if let Some(notify) = &self.notification_service {
+            let source = MentionSource {
+                actor_id: user_id,
+                actor_name: &actor_name,
+                place: "评论",
+                link: &link,
+                related_type: "Comment",
+                related_id: comment_id,
+            };
+            if let Err(e) = notify.notify_mentions(&source, &comment.content, &notified).await {
+                log::error!("发送提及通知失败: {}", e);
+            }
+        }
+
+        if let Some(points) = &self.points_service {
+            if let Err(e) = points.on_action(Some(user_id), "Comment", Some("Comment"), Some(comment_id)).await {
+                log::error!("发放积分失败: {}", e);
+            }
+        }
+        if let Some(achievements) = &self.achievement_service {
+            if let Err(e) = achievements.on_action(Some(user_id), "Comment", Some("Comment"), Some(comment_id)).await {
+                log::error!("评估成就失败: {}", e);
+            }
+        }
+
         Ok(comment)
     }
 
-    // 更新评论
-    pub async fn update_comment(
+    /// 更新评论；传入编辑记录时，编辑历史与更新在同一事务中写入，同时返回新版本号
+    pub async fn update_comment_recorded(
         &self,
         comment_id: i32,
         content: Option<String>,
         status: Option<String>,
         is_admin: bool,
-    ) -> Result<Comment> {
+        revision: Option<&PendingRevision>,
+    ) -> Result<(Comment, Option<i32>)> {
         // 先获取原有评论
         let mut comment = match self.comment_repo.get_comment_by_id(comment_id).await? {
             Some(c) => c,
@@ -181,20 +259,20 @@ impl CommentService {
         comment.updated_at = Utc::now();
 
         // 保存评论
-        self.comment_repo.update_comment(&comment).await?;
+        let revision_no = self.comment_repo.update_comment_recorded(&comment, revision).await?;
 
-        Ok(comment)
+        Ok((comment, revision_no))
     }
 
-    // 删除评论
-    pub async fn delete_comment(&self, comment_id: i32, _is_admin: bool) -> Result<()> {
-        // 改为物理删除（包含直接回复，见仓库层实现）
-        self.comment_repo.delete_comment(comment_id).await
+    // 删除评论：移入回收站（包含直接回复，见仓库层实现）
+    pub async fn delete_comment(&self, comment_id: i32, deleted_by: i32) -> Result<()> {
+        self.comment_repo.soft_delete_comment(comment_id, deleted_by).await?;
+        Ok(())
     }
 
-    // 获取评论回复
-    pub async fn get_comment_replies(&self, comment_id: i32) -> Result<Vec<Comment>> {
-        self.comment_repo.get_comment_replies(comment_id).await
+    // 获取评论回复（不含对 viewer 隐藏的用户的回复）
+    pub async fn get_comment_replies(&self, comment_id: i32, viewer_id: Option<i32>) -> Result<Vec<Comment>> {
+        self.comment_repo.get_comment_replies(comment_id, viewer_id).await
     }
 
     // 批量更新状态
@@ -218,10 +296,10 @@ impl CommentService {
         Ok(())
     }
 
-    // 批量删除评论（物理删除）
-    pub async fn batch_delete_comments(&self, comment_ids: Vec<i32>) -> Result<()> {
+    // 批量删除评论（移入回收站）
+    pub async fn batch_delete_comments(&self, comment_ids: Vec<i32>, deleted_by: i32) -> Result<()> {
         for comment_id in comment_ids {
-            let _ = self.comment_repo.delete_comment(comment_id).await;
+            let _ = self.comment_repo.soft_delete_comment(comment_id, deleted_by).await;
         }
         Ok(())
     }
@@ -314,13 +392,13 @@ impl CommentService {
     }
 
     // 获取资源评论
-    pub async fn get_package_comments(&self, package_id: i32, page: i32, size: i32) -> Result<(Vec<Comment>, i64)> {
-        self.comment_repo.get_comments_by_target("Package", package_id, page, size).await
+    pub async fn get_package_comments(&self, package_id: i32, page: i32, size: i32, viewer_id: Option<i32>) -> Result<(Vec<Comment>, i64)> {
+        self.comment_repo.get_comments_by_target("Package", package_id, page, size, viewer_id).await
     }
 
     // 获取帖子评论（平面列表，包含顶层与回复，过滤 Deleted）
-    pub async fn get_post_comments(&self, post_id: i32, page: i32, size: i32) -> Result<(Vec<Comment>, i64)> {
-        self.comment_repo.get_comments_by_target("Post", post_id, page, size).await
+    pub async fn get_post_comments(&self, post_id: i32, page: i32, size: i32, viewer_id: Option<i32>) -> Result<(Vec<Comment>, i64)> {
+        self.comment_repo.get_comments_by_target("Post", post_id, page, size, viewer_id).await
     }
 
     // 获取用户评论
@@ -328,9 +406,9 @@ impl CommentService {
         self.comment_repo.get_user_comments(user_id, page, size).await
     }
 
-    // 获取特定目标的顶层评论（仅 Active，排除回复）
-    pub async fn get_top_level_comments(&self, target_type: &str, target_id: i32, page: i32, size: i32) -> Result<(Vec<Comment>, i64)> {
-        self.comment_repo.get_top_level_comments_by_target(target_type, target_id, page, size).await
+    // 获取特定目标的顶层评论（仅 Active，排除回复）；viewer 拉黑、屏蔽的用户及拉黑了 viewer 的用户的评论不计入
+    pub async fn get_top_level_comments(&self, target_type: &str, target_id: i32, page: i32, size: i32, viewer_id: Option<i32>) -> Result<(Vec<Comment>, i64)> {
+        self.comment_repo.get_top_level_comments_by_target(target_type, target_id, page, size, viewer_id).await
     }
 
     // 新增：获取顶层评论并附带replies（一次性组装）
@@ -340,12 +418,13 @@ impl CommentService {
         target_id: i32,
         page: i32,
         size: i32,
+        viewer_id: Option<i32>,
     ) -> Result<(Vec<crate::models::CommentResponse>, i64)> {
-        let (parents, total) = self.get_top_level_comments(target_type, target_id, page, size).await?;
+        let (parents, total) = self.get_top_level_comments(target_type, target_id, page, size, viewer_id).await?;
         let mut result: Vec<crate::models::CommentResponse> = Vec::new();
         for p in parents {
             // 查询子回复
-            let replies = self.comment_repo.get_comment_replies(p.id).await.unwrap_or_default();
+            let replies = self.comment_repo.get_comment_replies(p.id, viewer_id).await.unwrap_or_default();
             let reply_nodes: Vec<crate::models::CommentResponse> = replies.into_iter().map(|r| crate::models::CommentResponse {
                 id: r.id,
                 user_id: r.user_id,
@@ -401,7 +480,7 @@ impl CommentService {
             // 获取资源信息检查作者
             if let Some(package_repo) = &self.package_repo {
                 if let Ok(Some(package)) = package_repo.find_by_id(comment.target_id).await {
-                    is_resource_author = package.author == user.username;
+                    is_resource_author = package.owner_id == Some(user.id);
                 }
             }
         }
@@ -424,4 +503,15 @@ impl CommentService {
         
         Ok(updated_comment)
     }
-} 
\ No newline at end of file
+} 
+
+/// 通知中引用的评论摘要
+fn excerpt(content: &str) -> String {
+    const MAX_CHARS: usize = 50;
+    let trimmed = content.trim();
+    if trimmed.chars().count() > MAX_CHARS {
+        format!("{}…", trimmed.chars().take(MAX_CHARS).collect::<String>())
+    } else {
+        trimmed.to_string()
+    }
+}
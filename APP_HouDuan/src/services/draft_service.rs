@@ -0,0 +1,366 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use log::{info, warn};
+
+use crate::models::draft::{
+    CreateDraftRequest, Draft, DraftRevision, PublishedContent, DRAFT_STATUS_FAILED, DRAFT_STATUS_PUBLISHING,
+    DRAFT_TARGET_TYPES, MAX_DRAFTS_PER_USER, MAX_PAYLOAD_BYTES, MAX_SCHEDULE_DAYS,
+};
+use crate::models::{BanStatus, PackageStatus, PublishPostRequest, PublishResourceRequest};
+use crate::repositories::draft_repo::DraftRepository;
+use crate::repositories::system_repo::SystemRepository;
+use crate::repositories::user_repo::UserRepository;
+use crate::services::category_service::CategoryService;
+use crate::services::notification_service::NotificationService;
+use crate::services::package_service::PackageService;
+use crate::services::post_service::PostService;
+use crate::services::tag_service::TagService;
+use crate::utils::error::ServiceError;
+
+/// 定时发布检查间隔
+const PUBLISH_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+/// 列表中显示的标题最大字符数
+const MAX_TITLE_CHARS: usize = 100;
+
+/// 草稿与定时发布：草稿内容与 /publish 接口的请求体相同，发布时走与手动发布相同的流程
+/// （帖子进入审核，资源按所在分类的规则审核或直接上架；粉丝通知在内容真正可见时发送）
+#[derive(Clone)]
+pub struct DraftService {
+    draft_repo: DraftRepository,
+    user_repo: UserRepository,
+    system_repo: SystemRepository,
+    post_service: PostService,
+    package_service: PackageService,
+    notification_service: Option<NotificationService>,
+    category_service: Option<CategoryService>,
+    tag_service: Option<TagService>,
+}
+
+impl DraftService {
+    pub fn new(
+        draft_repo: DraftRepository,
+        user_repo: UserRepository,
+        system_repo: SystemRepository,
+        post_service: PostService,
+        package_service: PackageService,
+    ) -> Self {
+        Self { draft_repo, user_repo, system_repo, post_service, package_service, notification_service: None, category_service: None, tag_service: None }
+    }
+
+    pub fn with_notification_service(mut self, service: NotificationService) -> Self {
+        self.notification_service = Some(service);
+        self
+    }
+
+    pub fn with_category_service(mut self, service: CategoryService) -> Self {
+        self.category_service = Some(service);
+        self
+    }
+
+    pub fn with_tag_service(mut self, service: TagService) -> Self {
+        self.tag_service = Some(service);
+        self
+    }
+
+    /// 与 /publish 接口相同的标签检查：规范化、替换别名，白名单模式下拒绝未审核的标签
+    async fn check_tags(&self, tags: &mut Option<Vec<String>>) -> Result<()> {
+        if let (Some(tag_service), Some(names)) = (&self.tag_service, tags.as_ref()) {
+            let checked = tag_service.check_tags(names, None).await?;
+            *tags = Some(checked);
+        }
+        Ok(())
+    }
+
+    fn title_of(payload: &serde_json::Value) -> String {
+        payload
+            .get("title")
+            .and_then(|t| t.as_str())
+            .map(|t| t.trim().chars().take(MAX_TITLE_CHARS).collect())
+            .unwrap_or_default()
+    }
+
+    fn check_payload(payload: &serde_json::Value) -> Result<()> {
+        if !payload.is_object() {
+            return Err(ServiceError::bad_request("草稿内容格式无效"));
+        }
+        if payload.to_string().len() > MAX_PAYLOAD_BYTES {
+            return Err(ServiceError::bad_request(format!("草稿内容过大，最多{}KB", MAX_PAYLOAD_BYTES / 1024)));
+        }
+        Ok(())
+    }
+
+    fn check_publish_at(publish_at: DateTime<Utc>) -> Result<()> {
+        let now = Utc::now();
+        if publish_at <= now {
+            return Err(ServiceError::bad_request("定时发布时间必须晚于当前时间"));
+        }
+        if publish_at > now + Duration::days(MAX_SCHEDULE_DAYS) {
+            return Err(ServiceError::bad_request(format!("定时发布时间不能超过{}天", MAX_SCHEDULE_DAYS)));
+        }
+        Ok(())
+    }
+
+    /// 校验草稿是否已可发布（标题与内容不能为空）
+    fn check_publishable(draft: &Draft) -> Result<()> {
+        let (title, content) = match draft.target_type.as_str() {
+            "Post" => {
+                let req: PublishPostRequest = serde_json::from_value(draft.payload.clone())
+                    .map_err(|_| ServiceError::bad_request("草稿内容不完整，无法发布"))?;
+                (req.title, req.content)
+            }
+            _ => {
+                let req: PublishResourceRequest = serde_json::from_value(draft.payload.clone())
+                    .map_err(|_| ServiceError::bad_request("草稿内容不完整，无法发布"))?;
+                (req.title, req.content)
+            }
+        };
+        if title.trim().is_empty() || content.trim().is_empty() {
+            return Err(ServiceError::bad_request("标题和内容不能为空"));
+        }
+        Ok(())
+    }
+
+    /// 取自己的草稿，不属于自己时按不存在处理
+    async fn own_draft(&self, user_id: i32, draft_id: i32) -> Result<Draft> {
+        match self.draft_repo.find(draft_id).await? {
+            Some(d) if d.user_id == user_id => Ok(d),
+            _ => Err(ServiceError::not_found("草稿不存在")),
+        }
+    }
+
+    pub async fn create(&self, user_id: i32, req: CreateDraftRequest) -> Result<Draft> {
+        if !DRAFT_TARGET_TYPES.contains(&req.target_type.as_str()) {
+            return Err(ServiceError::bad_request(format!("无效的草稿类型: {}", req.target_type)));
+        }
+        let payload = if req.payload.is_null() { serde_json::json!({}) } else { req.payload };
+        Self::check_payload(&payload)?;
+        if let Some(t) = req.publish_at {
+            Self::check_publish_at(t)?;
+        }
+        if self.draft_repo.count_by_user(user_id).await? >= MAX_DRAFTS_PER_USER {
+            return Err(ServiceError::bad_request(format!("最多保存{}个草稿", MAX_DRAFTS_PER_USER)));
+        }
+        let id = self
+            .draft_repo
+            .create(user_id, &req.target_type, &Self::title_of(&payload), &payload, req.publish_at)
+            .await?;
+        let draft = self.own_draft(user_id, id).await?;
+        if req.publish_at.is_some() {
+            if let Err(e) = Self::check_publishable(&draft) {
+                self.draft_repo.set_schedule(id, None).await?;
+                return Err(e);
+            }
+        }
+        Ok(draft)
+    }
+
+    pub async fn list(&self, user_id: i32, target_type: Option<&str>) -> Result<Vec<Draft>> {
+        if let Some(t) = target_type {
+            if !DRAFT_TARGET_TYPES.contains(&t) {
+                return Err(ServiceError::bad_request(format!("无效的草稿类型: {}", t)));
+            }
+        }
+        self.draft_repo.list_by_user(user_id, target_type).await
+    }
+
+    pub async fn get(&self, user_id: i32, draft_id: i32) -> Result<Draft> {
+        self.own_draft(user_id, draft_id).await
+    }
+
+    /// 自动保存（前端定时调用），按间隔记录历史版本
+    pub async fn save(&self, user_id: i32, draft_id: i32, payload: serde_json::Value) -> Result<Draft> {
+        self.own_draft(user_id, draft_id).await?;
+        Self::check_payload(&payload)?;
+        if !self.draft_repo.save(draft_id, &Self::title_of(&payload), &payload).await? {
+            return Err(ServiceError::conflict("草稿正在发布中，暂不能修改"));
+        }
+        self.own_draft(user_id, draft_id).await
+    }
+
+    pub async fn schedule(&self, user_id: i32, draft_id: i32, publish_at: DateTime<Utc>) -> Result<Draft> {
+        let draft = self.own_draft(user_id, draft_id).await?;
+        Self::check_publish_at(publish_at)?;
+        Self::check_publishable(&draft)?;
+        if !self.draft_repo.set_schedule(draft_id, Some(publish_at)).await? {
+            return Err(ServiceError::conflict("草稿正在发布中，暂不能修改"));
+        }
+        self.own_draft(user_id, draft_id).await
+    }
+
+    pub async fn unschedule(&self, user_id: i32, draft_id: i32) -> Result<Draft> {
+        self.own_draft(user_id, draft_id).await?;
+        if !self.draft_repo.set_schedule(draft_id, None).await? {
+            return Err(ServiceError::conflict("草稿正在发布中，暂不能修改"));
+        }
+        self.own_draft(user_id, draft_id).await
+    }
+
+    pub async fn delete(&self, user_id: i32, draft_id: i32) -> Result<()> {
+        self.own_draft(user_id, draft_id).await?;
+        if !self.draft_repo.delete(draft_id).await? {
+            return Err(ServiceError::conflict("草稿正在发布中，暂不能删除"));
+        }
+        Ok(())
+    }
+
+    pub async fn list_revisions(&self, user_id: i32, draft_id: i32) -> Result<Vec<DraftRevision>> {
+        self.own_draft(user_id, draft_id).await?;
+        self.draft_repo.list_revisions(draft_id).await
+    }
+
+    /// 恢复到某个历史版本（恢复本身也按自动保存记录）
+    pub async fn restore_revision(&self, user_id: i32, draft_id: i32, revision_id: i32) -> Result<Draft> {
+        self.own_draft(user_id, draft_id).await?;
+        let revision = self
+            .draft_repo
+            .find_revision(draft_id, revision_id)
+            .await?
+            .ok_or_else(|| ServiceError::not_found("历史版本不存在"))?;
+        self.save(user_id, draft_id, revision.payload).await
+    }
+
+    /// 立即发布；失败时草稿保持原状态
+    pub async fn publish_now(&self, user_id: i32, draft_id: i32) -> Result<PublishedContent> {
+        let draft = self.own_draft(user_id, draft_id).await?;
+        Self::check_publishable(&draft)?;
+        if !self.draft_repo.claim(draft_id).await? {
+            return Err(ServiceError::conflict("草稿正在发布中"));
+        }
+        match self.publish(&draft).await {
+            Ok(published) => {
+                self.draft_repo.finish(draft_id).await?;
+                Ok(published)
+            }
+            Err(e) => {
+                self.draft_repo.release(draft_id, &draft.status, draft.last_error.as_deref()).await?;
+                Err(e)
+            }
+        }
+    }
+
+    /// 按草稿类型走与 /publish 接口相同的创建流程；内容创建后立即记录其ID（见 recover_publishing），
+    /// 此后的步骤失败只记录日志，不再让发布失败，避免用户重试时重复创建
+    async fn publish(&self, draft: &Draft) -> Result<PublishedContent> {
+        Self::check_publishable(draft)?;
+        // 定时发布时作者可能已被封禁或注销，发布前重新确认账号状态
+        let user = self
+            .user_repo
+            .find_by_id(draft.user_id)
+            .await?
+            .ok_or_else(|| ServiceError::not_found("用户不存在"))?;
+        if user.ban_status != BanStatus::Normal || self.user_repo.is_deleted(user.id).await? {
+            return Err(ServiceError::forbidden("账号已被限制，无法发布"));
+        }
+        match draft.target_type.as_str() {
+            "Post" => {
+                let req: PublishPostRequest = serde_json::from_value(draft.payload.clone())?;
+                let mut create_req = req.to_create_request();
+                self.check_tags(&mut create_req.tags).await?;
+                let post_id = self
+                    .post_service
+                    .create_post(create_req, user.id)
+                    .await
+                    .map_err(|e| ServiceError::bad_request(format!("创建帖子失败: {}", e)))?;
+                self.record_published(draft.id, post_id).await;
+                Ok(PublishedContent { target_type: draft.target_type.clone(), target_id: post_id, title: req.title, pending_review: true })
+            }
+            _ => {
+                let req: PublishResourceRequest = serde_json::from_value(draft.payload.clone())?;
+                let categories = self.system_repo.get_categories().await.unwrap_or_default();
+                let category_id = req.resolve_category(&categories);
+                if let Some(category_service) = &self.category_service {
+                    category_service.check_can_post(category_id, &user.role).await?;
+                }
+                let mut create_req = req.to_create_request(user.id, &user.username, category_id);
+                self.check_tags(&mut create_req.tags).await?;
+                let mut package = self
+                    .package_service
+                    .create_package(&create_req)
+                    .await?;
+                self.record_published(draft.id, package.id).await;
+                if let Some(category_service) = &self.category_service {
+                    match category_service.apply_review_rule(package.clone()).await {
+                        Ok(reviewed) => package = reviewed,
+                        Err(e) => warn!("草稿 {} 发布的资源 {} 应用分类审核规则失败，保持待审核: {}", draft.id, package.id, e),
+                    }
+                }
+                let pending_review = package.status == PackageStatus::Pending;
+                Ok(PublishedContent { target_type: draft.target_type.clone(), target_id: package.id, title: package.name, pending_review })
+            }
+        }
+    }
+
+    async fn record_published(&self, draft_id: i32, published_id: i32) {
+        if let Err(e) = self.draft_repo.mark_published(draft_id, published_id).await {
+            warn!("记录草稿 {} 的发布结果失败: {}", draft_id, e);
+        }
+    }
+
+    async fn notify_owner(&self, user_id: i32, title: &str, content: &str, link: &str, notif_type: &str) {
+        if let Some(notify) = &self.notification_service {
+            if let Err(e) = notify.notify(user_id, title, content, Some(link), Some(notif_type), Some("Draft"), None).await {
+                warn!("发送定时发布通知失败: {}", e);
+            }
+        }
+    }
+
+    /// 发布所有到期的定时草稿，返回成功发布的数量
+    pub async fn publish_due(&self) -> Result<usize> {
+        let mut published_count = 0;
+        for draft_id in self.draft_repo.claim_due(Utc::now()).await? {
+            let draft = match self.draft_repo.find(draft_id).await? {
+                Some(d) => d,
+                None => continue,
+            };
+            match self.publish(&draft).await {
+                Ok(published) => {
+                    self.draft_repo.finish(draft_id).await?;
+                    published_count += 1;
+                    let (kind, link) = match published.target_type.as_str() {
+                        "Post" => ("帖子", format!("/post/{}", published.target_id)),
+                        _ => ("资源", format!("/resource/{}", published.target_id)),
+                    };
+                    let content = if published.pending_review {
+                        format!("您定时发布的{}《{}》已提交，等待审核", kind, published.title)
+                    } else {
+                        format!("您定时发布的{}《{}》已发布", kind, published.title)
+                    };
+                    self.notify_owner(draft.user_id, "定时发布已完成", &content, &link, "DraftPublished").await;
+                }
+                Err(e) => {
+                    let reason = e.to_string();
+                    warn!("定时发布草稿 {} 失败: {}", draft_id, reason);
+                    self.draft_repo.release(draft_id, DRAFT_STATUS_FAILED, Some(&reason)).await?;
+                    let content = format!("草稿《{}》定时发布失败：{}", draft.title, reason);
+                    self.notify_owner(draft.user_id, "定时发布失败", &content, &format!("/drafts/{}", draft_id), "DraftPublishFailed").await;
+                }
+            }
+        }
+        Ok(published_count)
+    }
+
+    /// 启动后台任务：每分钟发布到期的定时草稿；启动时先恢复上次中断在发布中的草稿
+    pub fn start_publish_job(&self) {
+        let svc = self.clone();
+        tokio::spawn(async move {
+            info!("⏰ 定时发布任务已启动");
+            match svc.draft_repo.recover_publishing().await {
+                Ok((finished, failed)) if finished + failed > 0 => info!(
+                    "处理中断在{}状态的草稿: {} 个已发布完成，{} 个标记为失败",
+                    DRAFT_STATUS_PUBLISHING, finished, failed
+                ),
+                Ok(_) => {}
+                Err(e) => warn!("恢复发布中的草稿失败: {}", e),
+            }
+            let mut interval = tokio::time::interval(PUBLISH_CHECK_INTERVAL);
+            loop {
+                interval.tick().await;
+                match svc.publish_due().await {
+                    Ok(n) if n > 0 => info!("定时发布完成: {} 个草稿", n),
+                    Ok(_) => {}
+                    Err(e) => warn!("定时发布失败: {}", e),
+                }
+            }
+        });
+    }
+}
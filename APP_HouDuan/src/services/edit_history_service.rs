@@ -0,0 +1,240 @@
+use anyhow::Result;
+use log::warn;
+use serde_json::json;
+
+use crate::models::edit_revision::{
+    comment_snapshot, package_snapshot, post_snapshot, ContentRevision, FieldDiff, PendingRevision, RevisionDiff,
+};
+use crate::repositories::edit_revision_repo::EditRevisionRepository;
+use crate::services::comment_service::CommentService;
+use crate::services::package_maintainer_service::PackageMaintainerService;
+use crate::services::package_service::PackageService;
+use crate::services::post_service::PostService;
+use crate::utils::diff::diff_lines;
+use crate::utils::error::ServiceError;
+
+/// 受追踪字段的当前取值
+pub struct ContentSnapshot {
+    pub data: serde_json::Value,
+    /// 内容作者（资源为所有者）
+    pub owner_id: Option<i32>,
+}
+
+impl ContentSnapshot {
+    /// 以当前取值作为编辑前快照，生成随更新一同写入的编辑记录
+    pub fn into_revision(self, editor_id: i32, note: Option<String>) -> PendingRevision {
+        PendingRevision { editor_id, before: self.data, note }
+    }
+}
+
+/// 帖子、资源描述与评论的编辑历史：每次编辑保存受追踪字段的完整快照，
+/// 首次编辑时先补记原始版本，因此任意两个版本之间都可以直接比较
+#[derive(Clone)]
+pub struct EditHistoryService {
+    revision_repo: EditRevisionRepository,
+    post_service: PostService,
+    package_service: PackageService,
+    comment_service: CommentService,
+    maintainer_service: Option<PackageMaintainerService>,
+}
+
+impl EditHistoryService {
+    pub fn new(
+        revision_repo: EditRevisionRepository,
+        post_service: PostService,
+        package_service: PackageService,
+        comment_service: CommentService,
+    ) -> Self {
+        Self { revision_repo, post_service, package_service, comment_service, maintainer_service: None }
+    }
+
+    pub fn with_maintainer_service(mut self, service: PackageMaintainerService) -> Self {
+        self.maintainer_service = Some(service);
+        self
+    }
+
+    /// 路径中的类型名转为存储用的内容类型
+    pub fn parse_target_type(raw: &str) -> Option<&'static str> {
+        match raw.to_ascii_lowercase().as_str() {
+            "post" | "posts" => Some("Post"),
+            "package" | "packages" | "resource" | "resources" => Some("Package"),
+            "comment" | "comments" => Some("Comment"),
+            _ => None,
+        }
+    }
+
+    /// 读取受追踪字段的当前取值；内容不存在时返回 None
+    pub async fn snapshot(&self, target_type: &str, target_id: i32) -> Result<Option<ContentSnapshot>> {
+        let snapshot = match target_type {
+            "Post" => self.post_service.get_post(target_id).await?.map(|post| ContentSnapshot {
+                data: post_snapshot(&post),
+                owner_id: Some(post.author_id),
+            }),
+            "Package" => self.package_service.get_package_by_id(target_id).await?.map(|package| ContentSnapshot {
+                data: package_snapshot(&package),
+                owner_id: package.owner_id,
+            }),
+            "Comment" => self.comment_service.get_comment_by_id(target_id).await?.map(|comment| ContentSnapshot {
+                data: comment_snapshot(&comment.content),
+                owner_id: Some(comment.user_id),
+            }),
+            _ => None,
+        };
+        Ok(snapshot)
+    }
+
+    /// 处理函数中使用：读取编辑前快照，交给更新方法在同一事务中记录；读取失败时只写日志，编辑照常进行
+    pub async fn pending_revision(&self, target_type: &str, target_id: i32, editor_id: i32) -> Option<PendingRevision> {
+        match self.snapshot(target_type, target_id).await {
+            Ok(snapshot) => snapshot.map(|snapshot| snapshot.into_revision(editor_id, None)),
+            Err(e) => {
+                warn!("读取编辑前快照失败 {} {}: {}", target_type, target_id, e);
+                None
+            }
+        }
+    }
+
+    /// 作者、资源维护者与版主可以查看编辑历史
+    async fn ensure_can_view(&self, target_type: &str, target_id: i32, viewer_id: i32, is_moderator: bool) -> Result<()> {
+        let current = self
+            .snapshot(target_type, target_id)
+            .await?
+            .ok_or_else(|| ServiceError::not_found("内容不存在"))?;
+        if is_moderator || current.owner_id == Some(viewer_id) {
+            return Ok(());
+        }
+        if target_type == "Package" {
+            if let (Some(maintainers), Some(package)) =
+                (&self.maintainer_service, self.package_service.get_package_by_id(target_id).await?)
+            {
+                if maintainers.can_edit(&package, viewer_id).await? {
+                    return Ok(());
+                }
+            }
+        }
+        Err(ServiceError::forbidden("无权查看编辑历史"))
+    }
+
+    pub async fn list(&self, target_type: &str, target_id: i32, viewer_id: i32, is_moderator: bool) -> Result<Vec<ContentRevision>> {
+        self.ensure_can_view(target_type, target_id, viewer_id, is_moderator).await?;
+        self.revision_repo.list(target_type, target_id).await
+    }
+
+    pub async fn get(
+        &self,
+        target_type: &str,
+        target_id: i32,
+        revision_no: i32,
+        viewer_id: i32,
+        is_moderator: bool,
+    ) -> Result<ContentRevision> {
+        self.ensure_can_view(target_type, target_id, viewer_id, is_moderator).await?;
+        self.revision_repo
+            .find(target_type, target_id, revision_no)
+            .await?
+            .ok_or_else(|| ServiceError::not_found("版本不存在"))
+    }
+
+    /// 比较两个版本；缺省时比较最新版本与其上一版本
+    pub async fn diff(
+        &self,
+        target_type: &str,
+        target_id: i32,
+        (from, to): (Option<i32>, Option<i32>),
+        viewer_id: i32,
+        is_moderator: bool,
+    ) -> Result<RevisionDiff> {
+        let revisions = self.list(target_type, target_id, viewer_id, is_moderator).await?;
+        let latest = revisions.first().map(|r| r.revision_no).ok_or_else(|| ServiceError::not_found("暂无编辑记录"))?;
+        let to = to.unwrap_or(latest);
+        let from = from.unwrap_or((to - 1).max(1));
+        let find = |no: i32| {
+            revisions
+                .iter()
+                .find(|r| r.revision_no == no)
+                .ok_or_else(|| ServiceError::not_found(format!("版本 {} 不存在", no)))
+        };
+        let (old, new) = (find(from)?, find(to)?);
+
+        let mut keys: Vec<&String> = old.snapshot.as_object().map(|m| m.keys().collect()).unwrap_or_default();
+        if let Some(map) = new.snapshot.as_object() {
+            keys.extend(map.keys().filter(|k| !keys.contains(k)).collect::<Vec<_>>());
+        }
+        let fields = keys
+            .into_iter()
+            .filter_map(|key| {
+                let before = old.snapshot.get(key).cloned().unwrap_or(serde_json::Value::Null);
+                let after = new.snapshot.get(key).cloned().unwrap_or(serde_json::Value::Null);
+                if before == after {
+                    return None;
+                }
+                let lines = match (&before, &after) {
+                    (serde_json::Value::String(_) | serde_json::Value::Null, serde_json::Value::String(_) | serde_json::Value::Null) => {
+                        Some(diff_lines(before.as_str().unwrap_or(""), after.as_str().unwrap_or("")))
+                    }
+                    _ => None,
+                };
+                Some(FieldDiff { field: key.clone(), before, after, lines })
+            })
+            .collect();
+        Ok(RevisionDiff { from, to, fields })
+    }
+
+    /// 版主将内容恢复到指定版本，回滚本身作为新版本记录；该版本中为空的字段同样恢复为空
+    pub async fn rollback(&self, moderator_id: i32, target_type: &str, target_id: i32, revision_no: i32) -> Result<i32> {
+        let revision = self
+            .revision_repo
+            .find(target_type, target_id, revision_no)
+            .await?
+            .ok_or_else(|| ServiceError::not_found("版本不存在"))?;
+        let before = self
+            .snapshot(target_type, target_id)
+            .await?
+            .ok_or_else(|| ServiceError::not_found("内容不存在"))?;
+        // 以当前受追踪字段为准，逐个取该版本的取值（版本中缺少的字段视为空）
+        let mut after = before.data.clone();
+        if let Some(map) = after.as_object_mut() {
+            for (key, value) in map.iter_mut() {
+                *value = revision.snapshot.get(key).cloned().unwrap_or(serde_json::Value::Null);
+            }
+        }
+        if EditRevisionRepository::changed_fields(&before.data, &after).is_empty() {
+            return Err(ServiceError::bad_request("当前内容与该版本相同"));
+        }
+        let pending = before.into_revision(moderator_id, Some(format!("回滚到版本 {}", revision_no)));
+        let recorded = match target_type {
+            "Post" => self.post_service.restore_tracked_fields(target_id, &after, &pending).await?,
+            "Package" => self.package_service.restore_tracked_fields(target_id, &after, &pending).await?,
+            "Comment" => {
+                let content = after["content"].as_str().unwrap_or_default().to_string();
+                self.comment_service
+                    .update_comment_recorded(target_id, Some(content), None, true, Some(&pending))
+                    .await?
+                    .1
+            }
+            _ => return Err(ServiceError::bad_request("不支持的内容类型")),
+        };
+        recorded.ok_or_else(|| ServiceError::bad_request("当前内容与该版本相同"))
+    }
+
+    /// 为已序列化的列表项补充 edited / edit_count / last_edited_at
+    pub async fn attach_markers(&self, target_type: &str, items: &mut [serde_json::Value]) {
+        let ids: Vec<i32> = items
+            .iter()
+            .filter_map(|v| v.get("id").and_then(|id| id.as_i64()).map(|id| id as i32))
+            .collect();
+        let markers = self.revision_repo.markers(target_type, &ids).await.unwrap_or_else(|e| {
+            warn!("读取编辑标记失败: {}", e);
+            Default::default()
+        });
+        for item in items.iter_mut() {
+            let id = item.get("id").and_then(|id| id.as_i64()).map(|id| id as i32);
+            let marker = id.and_then(|id| markers.get(&id));
+            if let serde_json::Value::Object(map) = item {
+                map.insert("edited".to_string(), json!(marker.is_some()));
+                map.insert("edit_count".to_string(), json!(marker.map(|m| m.edit_count).unwrap_or(0)));
+                map.insert("last_edited_at".to_string(), json!(marker.map(|m| m.last_edited_at)));
+            }
+        }
+    }
+}
@@ -7,7 +7,12 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use chrono::Utc;
 
-use crate::models::mail::{MailSettings, MailLog, MailType, MailStatus};
+use crate::models::mail::{
+    MailSettings, MailLog, MailType, MailStatus, MailTemplate, RenderedMail,
+    CreateMailTemplateRequest, UpdateMailTemplateRequest, PreviewMailTemplateRequest,
+    DEFAULT_MAIL_LANGUAGE,
+};
+use crate::utils::template;
 use crate::repositories::mail_repo::MailRepository;
 
 #[derive(Clone)]
@@ -157,7 +162,7 @@ impl EmailService {
                                     .singlepart(
                     SinglePart::builder()
                     .header(header::ContentType::TEXT_PLAIN)
-                        .body(html2text::from_read(content.as_bytes(), 80).unwrap_or_else(|_| content.to_string()))
+                        .body(html_to_text(content))
                 )
                     .singlepart(
                         SinglePart::builder()
@@ -188,6 +193,14 @@ impl EmailService {
         self.send_mail(to_email, &subject, &content, mail_type).await
     }
 
+    /// 使用指定语言的模板发送邮件，变量支持嵌套对象与数组
+    pub async fn send_localized_mail(&self, to_email: &str, mail_type: MailType, language: Option<&str>, variables: &serde_json::Value) -> Result<i64> {
+        let (subject, content) = self.mail_repo
+            .render_template_localized(&mail_type.to_string(), language, variables)
+            .await?;
+        self.send_mail(to_email, &subject, &content, mail_type).await
+    }
+
     /// 发送验证码邮件
     pub async fn send_verification_code(&self, to_email: &str, code: &str) -> Result<i64> {
         let mut variables = HashMap::new();
@@ -204,56 +217,6 @@ impl EmailService {
         self.send_templated_mail(to_email, "reset_password", variables, MailType::ResetPassword).await
     }
 
-    /// 发送资源通知邮件
-    pub async fn send_resource_notification(&self, to_email: &str, resource_name: &str, resource_description: &str, resource_link: &str) -> Result<i64> {
-        let mut variables = HashMap::new();
-        variables.insert("resource_name".to_string(), resource_name.to_string());
-        variables.insert("resource_description".to_string(), resource_description.to_string());
-        variables.insert("resource_link".to_string(), resource_link.to_string());
-        
-        self.send_templated_mail(to_email, "notification", variables, MailType::Notification).await
-    }
-
-    /// 发送分类通知
-    pub async fn send_category_notification(&self, to_email: &str, title: &str, content: &str) -> anyhow::Result<i64> {
-        let subject = format!("【绳包社区】{}", title);
-        let html_body = format!(
-            r#"
-            <div style="font-family: Arial, sans-serif; max-width: 600px; margin: 0 auto; padding: 20px;">
-                <div style="background: linear-gradient(135deg, #409EFF, #36CFC9); padding: 30px; border-radius: 10px 10px 0 0; text-align: center;">
-                    <h1 style="color: white; margin: 0; font-size: 24px;">绳包社区通知</h1>
-                    <p style="color: white; margin: 10px 0 0 0; opacity: 0.9;">{}</p>
-                </div>
-                <div style="background: white; padding: 30px; border-radius: 0 0 10px 10px; box-shadow: 0 2px 10px rgba(0,0,0,0.1);">
-                    <div style="margin-bottom: 20px;">
-                        <p style="margin: 0; line-height: 1.6; color: #333; white-space: pre-line;">{}</p>
-                    </div>
-                    <div style="text-align: center; margin: 30px 0;">
-                        <a href="{}" style="display: inline-block; background: #409EFF; color: white; padding: 12px 30px; text-decoration: none; border-radius: 6px; font-weight: bold;">
-                            查看更多内容
-                        </a>
-                    </div>
-                    <div style="border-top: 1px solid #eee; padding-top: 20px; margin-top: 30px; text-align: center; color: #999; font-size: 12px;">
-                        <p>此邮件由绳包社区系统自动发送，请勿直接回复。</p>
-                        <p>如需取消订阅，请登录您的账户进行设置。</p>
-                    </div>
-                </div>
-            </div>
-            "#,
-            title,
-            content,
-            std::env::var("FRONTEND_URL").unwrap_or_else(|_| "http://localhost:5173".to_string())
-        );
-
-        let text_body = format!(
-            "绳包社区通知\n\n{}\n\n{}\n\n访问 {} 查看更多内容\n\n此邮件由绳包社区系统自动发送，请勿直接回复。",
-            title,
-            content,
-            std::env::var("FRONTEND_URL").unwrap_or_else(|_| "http://localhost:5173".to_string())
-        );
-
-        self.send_mail(to_email, &subject, &text_body, MailType::Notification).await
-    }
 
     /// 发送管理员通知邮件（新资源待审核）
     pub async fn send_admin_review_notification(&self, to_email: &str, resource_name: &str, author: &str, review_link: &str) -> Result<i64> {
@@ -273,6 +236,99 @@ impl EmailService {
         self.send_templated_mail(to_email, "test", variables, MailType::Test).await
     }
 
+    /// 获取模板列表
+    pub async fn list_templates(&self, template_type: Option<&str>, language: Option<&str>) -> Result<Vec<MailTemplate>> {
+        self.mail_repo.list_mail_templates(template_type, language).await
+    }
+
+    pub async fn get_template(&self, id: i32) -> Result<Option<MailTemplate>> {
+        self.mail_repo.get_mail_template_by_id(id).await
+    }
+
+    /// 新建模板（同一类型同一语言只能有一个）
+    pub async fn create_template(&self, req: &CreateMailTemplateRequest) -> Result<MailTemplate> {
+        let mail_type = MailType::from_template_type(&req.template_type)
+            .ok_or_else(|| anyhow::anyhow!("未知的模板类型: {}", req.template_type))?;
+        let language = normalize_language(req.language.as_deref())?;
+        if !self.mail_repo.list_mail_templates(Some(&req.template_type), Some(&language)).await?.is_empty() {
+            return Err(anyhow::anyhow!("模板 {} ({}) 已存在", req.template_type, language));
+        }
+        let variables = validate_template(&mail_type, &req.subject, &req.content)?;
+
+        let mut template = MailTemplate {
+            id: None,
+            template_type: req.template_type.clone(),
+            language,
+            subject: req.subject.clone(),
+            content: req.content.clone(),
+            variables: Some(variables),
+            enabled: req.enabled.unwrap_or(true),
+            created_at: None,
+            updated_at: None,
+        };
+        template.id = Some(self.mail_repo.create_mail_template(&template).await? as i32);
+        Ok(template)
+    }
+
+    /// 更新模板
+    pub async fn update_template(&self, id: i32, req: &UpdateMailTemplateRequest) -> Result<MailTemplate> {
+        let mut template = self.mail_repo.get_mail_template_by_id(id).await?
+            .ok_or_else(|| anyhow::anyhow!("模板不存在"))?;
+        let mail_type = MailType::from_template_type(&template.template_type)
+            .ok_or_else(|| anyhow::anyhow!("未知的模板类型: {}", template.template_type))?;
+
+        if let Some(subject) = &req.subject { template.subject = subject.clone(); }
+        if let Some(content) = &req.content { template.content = content.clone(); }
+        if let Some(enabled) = req.enabled { template.enabled = enabled; }
+        if !template.enabled && template.language == DEFAULT_MAIL_LANGUAGE {
+            return Err(anyhow::anyhow!("默认语言模板不能禁用"));
+        }
+        template.variables = Some(validate_template(&mail_type, &template.subject, &template.content)?);
+
+        self.mail_repo.update_mail_template(&template).await?;
+        Ok(template)
+    }
+
+    /// 删除模板，默认语言模板作为回退不允许删除
+    pub async fn delete_template(&self, id: i32) -> Result<()> {
+        let template = self.mail_repo.get_mail_template_by_id(id).await?
+            .ok_or_else(|| anyhow::anyhow!("模板不存在"))?;
+        if template.language == DEFAULT_MAIL_LANGUAGE {
+            return Err(anyhow::anyhow!("默认语言模板不能删除"));
+        }
+        self.mail_repo.delete_mail_template(id).await
+    }
+
+    /// 预览模板渲染结果
+    pub async fn preview_template(&self, req: &PreviewMailTemplateRequest) -> Result<RenderedMail> {
+        let mail_type = MailType::from_template_type(&req.template_type)
+            .ok_or_else(|| anyhow::anyhow!("未知的模板类型: {}", req.template_type))?;
+
+        let (subject, content) = match (&req.subject, &req.content) {
+            (Some(subject), Some(content)) => (subject.clone(), content.clone()),
+            _ => {
+                let stored = self.mail_repo.get_mail_template(&req.template_type, req.language.as_deref()).await?
+                    .ok_or_else(|| anyhow::anyhow!("邮件模板 {} 不存在或未启用", req.template_type))?;
+                (
+                    req.subject.clone().unwrap_or(stored.subject),
+                    req.content.clone().unwrap_or(stored.content),
+                )
+            }
+        };
+
+        let mut variables = mail_type.sample_variables();
+        if let (Some(serde_json::Value::Object(custom)), Some(sample)) = (&req.variables, variables.as_object_mut()) {
+            for (k, v) in custom {
+                sample.insert(k.clone(), v.clone());
+            }
+        }
+
+        let subject = template::render_plain_str(&subject, &variables)?;
+        let html = template::render_str(&content, &variables)?;
+        let text = html_to_text(&html);
+        Ok(RenderedMail { subject, html, text })
+    }
+
     /// 格式化SMTP错误信息
     fn format_smtp_error(&self, error: &lettre::transport::smtp::Error) -> String {
         let error_msg = error.to_string().to_lowercase();
@@ -333,4 +389,42 @@ impl EmailService {
         self.send_mail(to_email, subject, content, MailType::Test).await?;
         Ok(())
     }
-} 
\ No newline at end of file
+}
+
+/// 由HTML正文生成纯文本部分
+pub fn html_to_text(html: &str) -> String {
+    html2text::from_read(html.as_bytes(), 80).unwrap_or_else(|_| html.to_string())
+}
+
+/// 校验模板语法及必需变量，返回模板引用的变量列表（JSON）
+fn validate_template(mail_type: &MailType, subject: &str, content: &str) -> Result<String> {
+    if subject.trim().is_empty() || content.trim().is_empty() {
+        return Err(anyhow::anyhow!("模板主题和内容不能为空"));
+    }
+    let mut variables = template::referenced_variables(&template::parse(subject)?);
+    variables.extend(template::referenced_variables(&template::parse(content)?));
+
+    let missing: Vec<&str> = mail_type
+        .required_variables()
+        .iter()
+        .copied()
+        .filter(|v| !variables.contains(*v))
+        .collect();
+    if !missing.is_empty() {
+        return Err(anyhow::anyhow!("模板缺少必需变量: {}", missing.join(", ")));
+    }
+    Ok(serde_json::to_string(&variables)?)
+}
+
+/// 校验语言代码，为空时返回默认语言
+pub fn normalize_language(language: Option<&str>) -> Result<String> {
+    let language = language.map(|l| l.trim()).filter(|l| !l.is_empty()).unwrap_or(DEFAULT_MAIL_LANGUAGE);
+    let valid = regex::Regex::new(r"^[a-zA-Z]{2,3}(-[a-zA-Z0-9]{2,8})?$")
+        .map(|re| re.is_match(language))
+        .unwrap_or(false);
+    if valid {
+        Ok(language.to_string())
+    } else {
+        Err(anyhow::anyhow!("无效的语言代码: {}", language))
+    }
+}
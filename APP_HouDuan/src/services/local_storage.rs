@@ -176,6 +176,40 @@ impl LocalStorageService {
         })
     }
 
+    /// 移动目录；源目录不存在时返回 false。目标目录已存在时逐项移入，
+    /// 有同名文件或目录时不移动任何内容并返回错误，不会覆盖已有文件
+    pub async fn move_folder(&mut self, from: &str, to: &str) -> Result<bool> {
+        let from_path = self.to_fs_path(from);
+        if !from_path.is_dir() {
+            return Ok(false);
+        }
+        let to_path = self.to_fs_path(to);
+        if !to_path.exists() {
+            self.ensure_parent_dir(&to_path)?;
+            fs::rename(&from_path, &to_path).map_err(|e| anyhow!("移动目录失败: {}", e))?;
+            return Ok(true);
+        }
+        let entries = fs::read_dir(&from_path)?.collect::<std::io::Result<Vec<_>>>()?;
+        if let Some(entry) = entries.iter().find(|entry| to_path.join(entry.file_name()).exists()) {
+            return Err(anyhow!("目标目录 {} 中已存在 {}", to, entry.file_name().to_string_lossy()));
+        }
+        for entry in entries {
+            fs::rename(entry.path(), to_path.join(entry.file_name())).map_err(|e| anyhow!("移动文件失败: {}", e))?;
+        }
+        fs::remove_dir(&from_path)?;
+        Ok(true)
+    }
+
+    /// 删除目录及其中所有文件；目录不存在时返回 false
+    pub async fn delete_folder(&mut self, path: &str) -> Result<bool> {
+        let fs_path = self.to_fs_path(path);
+        if !fs_path.is_dir() {
+            return Ok(false);
+        }
+        fs::remove_dir_all(&fs_path).map_err(|e| anyhow!("删除目录失败: {}", e))?;
+        Ok(true)
+    }
+
     pub async fn list_files(&mut self, path: &str) -> Result<FileListResponse> {
         let fs_path = self.to_fs_path(path);
         let mut list = Vec::new();
@@ -0,0 +1,289 @@
+use anyhow::Result;
+use chrono::Utc;
+use log::warn;
+use serde_json::json;
+
+use crate::models::message::{
+    Conversation, Message, MessageQuery, MessageReport, MessageSettings, SendMessageRequest,
+    ALLOW_FROM_EVERYONE, ALLOW_FROM_FOLLOWING, MESSAGE_TYPES, REPORT_DISMISSED, REPORT_PENDING, REPORT_RESOLVED,
+};
+use crate::models::notification::NotificationEvent;
+use crate::models::{BanStatus, UserRole};
+use crate::repositories::follow_repo::FollowRepository;
+use crate::repositories::message_repo::MessageRepository;
+use crate::repositories::user_relation_repo::UserRelationRepository;
+use crate::repositories::user_repo::UserRepository;
+use crate::services::forbidden_word_service::ForbiddenWordService;
+use crate::services::notification_service::NotificationService;
+use crate::utils::error::ServiceError;
+
+/// 单条私信最大长度
+const MAX_MESSAGE_CHARS: usize = 2000;
+/// 注册未满该天数的账号视为新账号，受发送频率限制
+const NEW_ACCOUNT_DAYS: i64 = 7;
+/// 新账号每小时最多发送的私信数
+const NEW_ACCOUNT_MESSAGES_PER_HOUR: i64 = 20;
+/// 新账号每天最多主动发起的会话数
+const NEW_ACCOUNT_CONVERSATIONS_PER_DAY: i64 = 5;
+/// 搜索结果上限
+const SEARCH_LIMIT: i64 = 50;
+/// 管理员查看举报时被举报消息前后各取的消息数
+const REPORT_CONTEXT: i64 = 10;
+
+#[derive(Clone)]
+pub struct MessageService {
+    message_repo: MessageRepository,
+    user_repo: UserRepository,
+    follow_repo: FollowRepository,
+    relation_repo: UserRelationRepository,
+    forbidden_service: Option<ForbiddenWordService>,
+    notification_service: Option<NotificationService>,
+}
+
+impl MessageService {
+    pub fn new(
+        message_repo: MessageRepository,
+        user_repo: UserRepository,
+        follow_repo: FollowRepository,
+        relation_repo: UserRelationRepository,
+    ) -> Self {
+        Self { message_repo, user_repo, follow_repo, relation_repo, forbidden_service: None, notification_service: None }
+    }
+
+    pub fn with_forbidden_service(mut self, service: ForbiddenWordService) -> Self {
+        self.forbidden_service = Some(service);
+        self
+    }
+
+    pub fn with_notification_service(mut self, service: NotificationService) -> Self {
+        self.notification_service = Some(service);
+        self
+    }
+
+    /// 发送私信：双方未拉黑、满足对方的接收设置、通过违禁词检测，新账号另受频率限制
+    pub async fn send(&self, sender_id: i32, req: SendMessageRequest) -> Result<Message> {
+        let content = req.content.trim();
+        if content.is_empty() {
+            return Err(ServiceError::bad_request("消息内容不能为空"));
+        }
+        if content.chars().count() > MAX_MESSAGE_CHARS {
+            return Err(ServiceError::bad_request(format!("消息内容不能超过 {} 字", MAX_MESSAGE_CHARS)));
+        }
+        let message_type = req.message_type.as_deref().unwrap_or("text");
+        if !MESSAGE_TYPES.contains(&message_type) {
+            return Err(ServiceError::bad_request("不支持的消息类型"));
+        }
+        if req.receiver_id == sender_id {
+            return Err(ServiceError::bad_request("不能给自己发私信"));
+        }
+
+        let sender = self.user_repo.find_by_id(sender_id).await?
+            .ok_or_else(|| ServiceError::not_found("用户不存在"))?;
+        if sender.ban_status != BanStatus::Normal {
+            return Err(ServiceError::bad_request("账号已被限制，无法发送私信"));
+        }
+        // 已注销的账号视同不存在
+        if self.user_repo.find_by_id(req.receiver_id).await?.is_none() || self.user_repo.is_deleted(req.receiver_id).await? {
+            return Err(ServiceError::not_found("接收者不存在"));
+        }
+        if self.relation_repo.is_blocked_either(sender_id, req.receiver_id).await? {
+            return Err(ServiceError::forbidden("无权发送：你与对方存在拉黑关系"));
+        }
+
+        let is_admin = sender.role == UserRole::Admin;
+        if !is_admin {
+            let settings = self.message_repo.get_settings(req.receiver_id).await?;
+            if settings.allow_from == ALLOW_FROM_FOLLOWING
+                && !self.follow_repo.is_following(req.receiver_id, sender_id).await?
+            {
+                return Err(ServiceError::forbidden("无权发送：对方仅接收其关注的人的私信"));
+            }
+            if Utc::now() - sender.created_at < chrono::Duration::days(NEW_ACCOUNT_DAYS) {
+                self.check_new_account_rate(sender_id, req.receiver_id).await?;
+            }
+        }
+
+        if let Some(f_service) = &self.forbidden_service {
+            if f_service.contains_forbidden_word(content).await? {
+                return Err(ServiceError::bad_request("内容包含违禁词"));
+            }
+        }
+
+        let (message, _) = self.message_repo.insert_message(sender_id, req.receiver_id, content, message_type).await?;
+        self.push(req.receiver_id, NotificationEvent::DirectMessage(json!(message))).await;
+        Ok(message)
+    }
+
+    async fn check_new_account_rate(&self, sender_id: i32, receiver_id: i32) -> Result<()> {
+        if self.message_repo.count_sent_since(sender_id, 3600).await? >= NEW_ACCOUNT_MESSAGES_PER_HOUR {
+            return Err(ServiceError::too_many_requests(format!("发送过于频繁：新注册账号每小时最多发送 {} 条私信", NEW_ACCOUNT_MESSAGES_PER_HOUR)));
+        }
+        if self.message_repo.find_conversation(sender_id, receiver_id).await?.is_none()
+            && self.message_repo.count_conversations_started_since(sender_id, 86400).await? >= NEW_ACCOUNT_CONVERSATIONS_PER_DAY
+        {
+            return Err(ServiceError::too_many_requests(format!("发送过于频繁：新注册账号每天最多向 {} 位用户发起私信", NEW_ACCOUNT_CONVERSATIONS_PER_DAY)));
+        }
+        Ok(())
+    }
+
+    /// 通过实时通知连接推送私信事件，对方不在线时忽略
+    async fn push(&self, user_id: i32, event: NotificationEvent) {
+        if let Some(n_service) = &self.notification_service {
+            let hub = n_service.hub();
+            if hub.is_online(user_id).await {
+                hub.send_to_user(user_id, &event).await;
+            }
+        }
+    }
+
+    /// 已读回执：告知各发送者哪些消息已被读
+    async fn push_read_receipts(&self, reader_id: i32, marked: &[(i32, i32)]) {
+        let mut by_sender: Vec<(i32, Vec<i32>)> = Vec::new();
+        for (message_id, sender_id) in marked {
+            match by_sender.iter_mut().find(|(s, _)| s == sender_id) {
+                Some((_, ids)) => ids.push(*message_id),
+                None => by_sender.push((*sender_id, vec![*message_id])),
+            }
+        }
+        for (sender_id, ids) in by_sender {
+            let payload = json!({ "reader_id": reader_id, "message_ids": ids, "read_at": Utc::now() });
+            self.push(sender_id, NotificationEvent::MessageRead(payload)).await;
+        }
+    }
+
+    pub async fn list_conversations(&self, user_id: i32, keyword: Option<&str>) -> Result<Vec<Conversation>> {
+        let keyword = keyword.map(str::trim).filter(|k| !k.is_empty());
+        let mut list = self.message_repo.list_conversations(user_id, keyword).await?;
+        if let Some(n_service) = &self.notification_service {
+            for c in list.iter_mut() {
+                c.is_online = n_service.hub().is_online(c.participant_id).await;
+            }
+        }
+        Ok(list)
+    }
+
+    pub async fn list_messages(&self, user_id: i32, query: &MessageQuery) -> Result<(Vec<Message>, i64, i32, i32)> {
+        let page = query.page.unwrap_or(1).max(1);
+        let page_size = query.page_size.unwrap_or(20).clamp(1, 100);
+        let offset = ((page - 1) * page_size) as i64;
+        let (list, total) = self.message_repo.list_messages(user_id, query.user_id, offset, page_size as i64).await?;
+        Ok((list, total, page, page_size))
+    }
+
+    pub async fn search_messages(&self, user_id: i32, keyword: &str, other_id: Option<i32>) -> Result<Vec<Message>> {
+        let keyword = keyword.trim();
+        if keyword.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.message_repo.search_messages(user_id, keyword, other_id, SEARCH_LIMIT).await
+    }
+
+    pub async fn mark_read(&self, user_id: i32, message_ids: &[i32]) -> Result<usize> {
+        let marked = self.message_repo.mark_read(user_id, message_ids).await?;
+        self.push_read_receipts(user_id, &marked).await;
+        Ok(marked.len())
+    }
+
+    pub async fn mark_conversation_read(&self, user_id: i32, other_id: i32) -> Result<usize> {
+        let ids = self.message_repo.mark_conversation_read(user_id, other_id).await?;
+        let marked: Vec<(i32, i32)> = ids.iter().map(|id| (*id, other_id)).collect();
+        self.push_read_receipts(user_id, &marked).await;
+        Ok(marked.len())
+    }
+
+    pub async fn delete_message(&self, user_id: i32, message_id: i32) -> Result<()> {
+        if !self.message_repo.delete_message(user_id, message_id).await? {
+            return Err(ServiceError::not_found("消息不存在"));
+        }
+        Ok(())
+    }
+
+    pub async fn delete_conversation(&self, user_id: i32, other_id: i32) -> Result<()> {
+        if !self.message_repo.clear_conversation(user_id, other_id).await? {
+            return Err(ServiceError::not_found("会话不存在"));
+        }
+        Ok(())
+    }
+
+    pub async fn set_pinned(&self, user_id: i32, other_id: i32, pinned: bool) -> Result<()> {
+        if !self.message_repo.set_pinned(user_id, other_id, pinned).await? {
+            return Err(ServiceError::not_found("会话不存在"));
+        }
+        Ok(())
+    }
+
+    pub async fn unread_count(&self, user_id: i32) -> Result<i32> {
+        self.message_repo.unread_count(user_id).await
+    }
+
+    pub async fn get_settings(&self, user_id: i32) -> Result<MessageSettings> {
+        self.message_repo.get_settings(user_id).await
+    }
+
+    pub async fn update_settings(&self, user_id: i32, allow_from: &str) -> Result<MessageSettings> {
+        if allow_from != ALLOW_FROM_EVERYONE && allow_from != ALLOW_FROM_FOLLOWING {
+            return Err(ServiceError::bad_request("allow_from 只能为 everyone 或 following"));
+        }
+        self.message_repo.set_settings(user_id, allow_from).await?;
+        Ok(MessageSettings { allow_from: allow_from.to_string() })
+    }
+
+    /// 举报收到或发出的私信
+    pub async fn report(&self, user_id: i32, message_id: i32, reason: Option<&str>) -> Result<i32> {
+        let message = self.message_repo.find_message(message_id).await?
+            .ok_or_else(|| ServiceError::not_found("消息不存在"))?;
+        if message.sender_id != user_id && message.receiver_id != user_id {
+            return Err(ServiceError::not_found("消息不存在"));
+        }
+        let reason = reason.map(str::trim).filter(|r| !r.is_empty());
+        if reason.is_some_and(|r| r.chars().count() > 500) {
+            return Err(ServiceError::bad_request("举报理由不能超过 500 字"));
+        }
+        self.message_repo.create_report(message_id, user_id, reason).await?
+            .ok_or_else(|| ServiceError::bad_request("已举报过该消息，请等待处理"))
+    }
+
+    pub async fn list_reports(&self, status: Option<&str>, page: i32, page_size: i32) -> Result<(Vec<MessageReport>, i64)> {
+        let offset = ((page - 1) * page_size) as i64;
+        let (mut list, total) = self.message_repo.list_reports(status, offset, page_size as i64).await?;
+        for report in list.iter_mut() {
+            report.message = self.message_repo.find_message(report.message_id).await?;
+        }
+        Ok((list, total))
+    }
+
+    /// 管理员查看举报详情：被举报消息及其所在会话的前后消息
+    pub async fn get_report_detail(&self, id: i32) -> Result<(MessageReport, Vec<Message>)> {
+        let mut report = self.message_repo.get_report(id).await?
+            .ok_or_else(|| ServiceError::not_found("举报不存在"))?;
+        report.message = self.message_repo.find_message(report.message_id).await?;
+        let context = self.message_repo.message_context(report.message_id, REPORT_CONTEXT).await?;
+        Ok((report, context))
+    }
+
+    /// 处理举报并通知举报人
+    pub async fn handle_report(&self, admin_id: i32, id: i32, status: &str, note: Option<&str>) -> Result<()> {
+        if status != REPORT_RESOLVED && status != REPORT_DISMISSED && status != REPORT_PENDING {
+            return Err(ServiceError::bad_request("status 只能为 pending、resolved 或 dismissed"));
+        }
+        let report = self.message_repo.get_report(id).await?
+            .ok_or_else(|| ServiceError::not_found("举报不存在"))?;
+        self.message_repo.handle_report(id, admin_id, status, note).await?;
+        if status != REPORT_PENDING && report.status == REPORT_PENDING {
+            if let Some(n_service) = &self.notification_service {
+                let content = if status == REPORT_RESOLVED {
+                    "您举报的私信已核实并处理，感谢您的反馈"
+                } else {
+                    "您举报的私信经核查未发现违规"
+                };
+                if let Err(e) = n_service
+                    .notify(report.reporter_id, "私信举报处理结果", content, None, Some("MessageReport"), Some("MessageReport"), Some(id))
+                    .await
+                {
+                    warn!("发送举报处理通知失败: {}", e);
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -17,7 +17,25 @@ pub mod tag_service; // 添加标签服务
 pub mod download_security_service; // 添加下载安全服务
 pub mod security_action_service; // 添加安全操作服务
 pub mod notification_service; // 新增通知服务
+pub mod notification_hub; // 实时通知连接注册表
 pub mod anti_fraud_service; // 反欺诈服务
 pub mod database_repair_service; // 数据库修复服务
-
- 
\ No newline at end of file
+pub mod rating_service; // 资源评分与评价
+pub mod achievement_service; // 成就引擎
+pub mod points_service; // 经验、等级与星星
+pub mod check_in_service; // 签到、连续天数与补签
+pub mod weekly_report_service; // 个人周报
+pub mod ranking_service; // 按时间段统计的排行榜
+pub mod recommendation_service; // 资源推荐
+pub mod message_service; // 私信
+pub mod user_relation_service; // 拉黑与屏蔽
+pub mod collection_service; // 收藏夹
+pub mod package_maintainer_service; // 资源维护者与所有权转让
+pub mod draft_service; // 草稿与定时发布
+pub mod render_service; // Markdown 渲染与缓存
+pub mod edit_history_service; // 编辑历史与回滚
+pub mod package_dependency_service; // 资源依赖与安装解析
+pub mod category_service; // 层级分类与分类规则
+pub mod package_analytics_service; // 资源数据分析
+pub mod site_metrics_service; // 全站数据日汇总与留存
+pub mod recycle_bin_service; // 回收站与到期清理
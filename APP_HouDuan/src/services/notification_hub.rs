@@ -0,0 +1,136 @@
+use actix_web::web::Bytes;
+use log::{debug, info};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, RwLock};
+
+use crate::models::notification::NotificationEvent;
+
+/// 单个用户允许的最大并发连接数
+const MAX_CONNECTIONS_PER_USER: usize = 5;
+/// 每个连接的待发送事件缓冲
+const CONNECTION_BUFFER: usize = 64;
+/// 心跳间隔
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(25);
+/// 连接票据有效期：票据只用于建立一次 SSE 连接
+pub const STREAM_TICKET_TTL: Duration = Duration::from_secs(60);
+
+struct Connection {
+    id: u64,
+    sender: mpsc::Sender<Bytes>,
+}
+
+/// 实时通知连接注册表：按用户维护 SSE 连接，并负责心跳与失效连接清理
+#[derive(Clone)]
+pub struct NotificationHub {
+    connections: Arc<RwLock<HashMap<i32, Vec<Connection>>>>,
+    next_id: Arc<AtomicU64>,
+    /// 一次性连接票据 -> (用户ID, 签发时间)
+    tickets: Arc<RwLock<HashMap<String, (i32, Instant)>>>,
+}
+
+impl NotificationHub {
+    pub fn new() -> Self {
+        Self {
+            connections: Arc::new(RwLock::new(HashMap::new())),
+            next_id: Arc::new(AtomicU64::new(1)),
+            tickets: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// 签发一次性连接票据，避免 EventSource 在查询参数中携带 JWT
+    pub async fn issue_ticket(&self, user_id: i32) -> String {
+        let ticket = uuid::Uuid::new_v4().simple().to_string();
+        let mut tickets = self.tickets.write().await;
+        tickets.retain(|_, (_, issued)| issued.elapsed() < STREAM_TICKET_TTL);
+        tickets.insert(ticket.clone(), (user_id, Instant::now()));
+        ticket
+    }
+
+    /// 兑换连接票据：无论是否过期都会作废，返回有效票据对应的用户ID
+    pub async fn redeem_ticket(&self, ticket: &str) -> Option<i32> {
+        let (user_id, issued) = self.tickets.write().await.remove(ticket)?;
+        (issued.elapsed() < STREAM_TICKET_TTL).then_some(user_id)
+    }
+
+    /// 注册新连接，超出单用户上限时关闭最早的连接
+    pub async fn register(&self, user_id: i32) -> mpsc::Receiver<Bytes> {
+        let (sender, receiver) = mpsc::channel(CONNECTION_BUFFER);
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let mut connections = self.connections.write().await;
+        let list = connections.entry(user_id).or_default();
+        if list.len() >= MAX_CONNECTIONS_PER_USER {
+            list.remove(0);
+        }
+        list.push(Connection { id, sender });
+        debug!("用户 {} 建立实时通知连接 #{}", user_id, id);
+        receiver
+    }
+
+    /// 向指定用户的所有连接推送事件；缓冲已满的连接跳过本条（客户端可回退轮询）
+    pub async fn send_to_user(&self, user_id: i32, event: &NotificationEvent) {
+        let payload = Bytes::from(event.to_sse());
+        let mut closed = Vec::new();
+        {
+            let connections = self.connections.read().await;
+            if let Some(list) = connections.get(&user_id) {
+                for conn in list {
+                    if let Err(mpsc::error::TrySendError::Closed(_)) = conn.sender.try_send(payload.clone()) {
+                        closed.push(conn.id);
+                    }
+                }
+            }
+        }
+        if !closed.is_empty() {
+            self.remove(user_id, &closed).await;
+        }
+    }
+
+    /// 向所有在线用户推送事件
+    pub async fn broadcast(&self, event: &NotificationEvent) {
+        let user_ids: Vec<i32> = self.connections.read().await.keys().copied().collect();
+        for user_id in user_ids {
+            self.send_to_user(user_id, event).await;
+        }
+    }
+
+    pub async fn is_online(&self, user_id: i32) -> bool {
+        self.connections.read().await.get(&user_id).map(|l| !l.is_empty()).unwrap_or(false)
+    }
+
+    async fn remove(&self, user_id: i32, ids: &[u64]) {
+        let mut connections = self.connections.write().await;
+        if let Some(list) = connections.get_mut(&user_id) {
+            list.retain(|c| !ids.contains(&c.id));
+            if list.is_empty() {
+                connections.remove(&user_id);
+            }
+        }
+    }
+
+    /// 启动心跳任务：定期发送 SSE 注释保持连接，并清理已断开的连接
+    pub fn start_heartbeat(&self) {
+        let hub = self.clone();
+        tokio::spawn(async move {
+            info!("💓 实时通知心跳任务已启动");
+            let mut interval = tokio::time::interval(HEARTBEAT_INTERVAL);
+            loop {
+                interval.tick().await;
+                let ping = Bytes::from_static(b": ping\n\n");
+                let mut connections = hub.connections.write().await;
+                for list in connections.values_mut() {
+                    list.retain(|c| !matches!(c.sender.try_send(ping.clone()), Err(mpsc::error::TrySendError::Closed(_))));
+                }
+                connections.retain(|_, list| !list.is_empty());
+            }
+        });
+    }
+}
+
+impl Default for NotificationHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
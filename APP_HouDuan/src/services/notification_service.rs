@@ -1,17 +1,83 @@
 use anyhow::Result;
-use chrono::Utc;
-use crate::models::notification::{Notification, NotificationQuery};
+use chrono::{Datelike, Timelike, Utc};
+use log::{info, warn};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use crate::models::mail::MailType;
+use crate::models::notification::{
+    DigestFrequency, DigestSetting, MentionSource, Notification, NotificationCategory, NotificationChannel,
+    NotificationEvent, NotificationPreferenceItem, NotificationQuery, NotificationSettings,
+    UpdateNotificationSettingsRequest,
+};
 use crate::repositories::notification_repo::NotificationRepository;
+use crate::services::email_service::{normalize_language, EmailService};
+use crate::services::notification_hub::NotificationHub;
+use crate::utils::mention;
+use crate::utils::time::{community_offset, local_now};
+
+/// 断线续传时最多补发的通知条数
+const MAX_REPLAY: i32 = 100;
+/// 摘要任务检查间隔
+const DIGEST_CHECK_INTERVAL: Duration = Duration::from_secs(3600);
+/// 摘要邮件发送时刻（社区时区的小时）
+const DIGEST_SEND_HOUR: u32 = 8;
+/// 单封摘要邮件最多列出的通知条数
+const MAX_DIGEST_ITEMS: usize = 50;
 
 #[derive(Clone)]
 pub struct NotificationService {
     repo: NotificationRepository,
+    hub: NotificationHub,
+    email_service: Option<Arc<RwLock<EmailService>>>,
+    public_base_url: String,
+    /// 触发通知的用户，见 by_actor
+    actor_id: Option<i32>,
 }
 
 impl NotificationService {
-    pub fn new(repo: NotificationRepository) -> Self { Self { repo } }
+    pub fn new(repo: NotificationRepository) -> Self {
+        Self {
+            repo,
+            hub: NotificationHub::new(),
+            email_service: None,
+            public_base_url: "http://localhost:15201".to_string(),
+            actor_id: None,
+        }
+    }
+
+    pub fn with_email_service(mut self, email_service: Arc<RwLock<EmailService>>) -> Self {
+        self.email_service = Some(email_service);
+        self
+    }
+
+    /// 后端对外地址，用于生成免登录退订链接
+    pub fn with_public_base_url(mut self, base_url: String) -> Self {
+        self.public_base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn hub(&self) -> &NotificationHub { &self.hub }
+
+    /// 标明通知由哪个用户的操作触发：接收者屏蔽了该用户、或双方存在拉黑关系时不再投递
+    pub fn by_actor(&self, actor_id: i32) -> Self {
+        Self { actor_id: Some(actor_id), ..self.clone() }
+    }
 
+    /// 按用户偏好投递通知：关闭时或被接收者屏蔽/拉黑时不写入（返回 0），邮件方式在未开启摘要时即时发送邮件
     pub async fn notify(&self, user_id: i32, title: &str, content: &str, link: Option<&str>, notif_type: Option<&str>, related_type: Option<&str>, related_id: Option<i32>) -> Result<i32> {
+        if let Some(actor_id) = self.actor_id {
+            if actor_id != user_id && self.repo.is_hidden_from(user_id, actor_id).await? {
+                return Ok(0);
+            }
+        }
+        let channel = match notif_type.and_then(NotificationCategory::from_notif_type) {
+            Some(category) => self.channel_for(user_id, category).await?,
+            None => NotificationChannel::InApp,
+        };
+        if channel == NotificationChannel::Off {
+            return Ok(0);
+        }
         let n = Notification {
             id: 0,
             user_id,
@@ -24,16 +90,161 @@ impl NotificationService {
             is_read: false,
             created_at: Utc::now(),
         };
-        self.repo.create(&n).await
+        let id = self.repo.create(&n).await?;
+        let n = Notification { id, ..n };
+        if channel == NotificationChannel::Email {
+            self.send_instant_email(&n).await;
+        }
+        self.push(NotificationEvent::Notification(n)).await;
+        Ok(id)
+    }
+
+    async fn channel_for(&self, user_id: i32, category: NotificationCategory) -> Result<NotificationChannel> {
+        Ok(self.repo.get_preference(user_id, category).await?.unwrap_or_else(|| category.default_channel()))
+    }
+
+    pub fn unsubscribe_link(&self, token: &str, category: Option<NotificationCategory>) -> String {
+        let mut link = format!("{}/api/v1/notifications/unsubscribe?token={}", self.public_base_url, token);
+        if let Some(category) = category {
+            link.push_str("&category=");
+            link.push_str(category.as_str());
+        }
+        link
+    }
+
+    /// 即时邮件：开启摘要的用户改由摘要统一发送
+    async fn send_instant_email(&self, n: &Notification) {
+        let email_service = match &self.email_service {
+            Some(es) => es.clone(),
+            None => return,
+        };
+        let setting = match self.repo.get_digest_setting(n.user_id).await {
+            Ok(s) => s,
+            Err(e) => {
+                warn!("获取用户 {} 摘要设置失败: {}", n.user_id, e);
+                return;
+            }
+        };
+        if setting.frequency != DigestFrequency::Off {
+            return;
+        }
+        let language = setting.mail_language.clone();
+        let email = match self.repo.get_user_contact(n.user_id).await {
+            Ok(Some((_, email))) if !email.trim().is_empty() => email,
+            _ => return,
+        };
+        let category = n.notif_type.as_deref().and_then(NotificationCategory::from_notif_type);
+        let frontend_url = std::env::var("FRONTEND_URL").unwrap_or_else(|_| "http://localhost:5173".to_string());
+        let variables = serde_json::json!({
+            "title": n.title,
+            "content": n.content,
+            "link": n.link.as_deref().map(|l| absolute_link(&frontend_url, l)).unwrap_or(frontend_url),
+            "unsubscribe_link": self.unsubscribe_link(&setting.unsubscribe_token, category),
+        });
+        tokio::spawn(async move {
+            let es = email_service.read().await;
+            if let Err(e) = es.send_localized_mail(&email, MailType::CategoryNotification, language.as_deref(), &variables).await {
+                warn!("发送通知邮件到 {} 失败: {}", email, e);
+            }
+        });
+    }
+
+    /// 通知内容中被 @ 的用户（跳过作者本人及 skip 中已通知的用户），返回实际通知的用户ID
+    pub async fn notify_mentions(&self, source: &MentionSource<'_>, content: &str, skip: &[i32]) -> Result<Vec<i32>> {
+        let usernames = mention::extract_mentions(content);
+        let mut notified = Vec::new();
+        for user_id in self.repo.find_user_ids_by_usernames(&usernames).await? {
+            if user_id == source.actor_id || skip.contains(&user_id) || notified.contains(&user_id) {
+                continue;
+            }
+            let message = format!("{} 在{}中提到了您", source.actor_name, source.place);
+            self.by_actor(source.actor_id).notify(user_id, "有人 @ 了您", &message, Some(source.link), Some("Mention"), Some(source.related_type), Some(source.related_id)).await?;
+            notified.push(user_id);
+        }
+        Ok(notified)
+    }
+
+    /// 内容真正对外可见时通知作者的粉丝（跳过 skip 中已通知的用户）；同一内容只通知一次，返回实际通知的用户ID
+    pub async fn notify_followers(&self, source: &MentionSource<'_>, skip: &[i32]) -> Result<Vec<i32>> {
+        if self.repo.exists_for_related("FollowingPublished", source.related_type, source.related_id).await? {
+            return Ok(Vec::new());
+        }
+        let message = format!("您关注的 {} 发布了{}", source.actor_name, source.place);
+        let mut notified = Vec::new();
+        for user_id in self.repo.find_follower_ids(source.actor_id).await? {
+            if user_id == source.actor_id || skip.contains(&user_id) {
+                continue;
+            }
+            if self.by_actor(source.actor_id).notify(user_id, "关注的人有新发布", &message, Some(source.link), Some("FollowingPublished"), Some(source.related_type), Some(source.related_id)).await? > 0 {
+                notified.push(user_id);
+            }
+        }
+        Ok(notified)
+    }
+
+    /// 管理员广播：按模板为用户写入通知，并以公告事件推送给在线用户
+    pub async fn broadcast(&self, user_id: i32, template: &Notification) -> Result<i32> {
+        let n = Notification { id: 0, user_id, is_read: false, created_at: Utc::now(), ..template.clone() };
+        let id = self.repo.create(&n).await?;
+        self.push(NotificationEvent::Broadcast(Notification { id, ..n })).await;
+        Ok(id)
+    }
+
+    /// 公告发布后推送给所有在线用户
+    pub async fn announce(&self, announcement: serde_json::Value) {
+        self.hub.broadcast(&NotificationEvent::Announcement(announcement)).await;
+    }
+
+    /// 推送通知事件及最新未读数（仅对在线用户）
+    async fn push(&self, event: NotificationEvent) {
+        let user_id = match &event {
+            NotificationEvent::Notification(n) | NotificationEvent::Broadcast(n) => n.user_id,
+            _ => return,
+        };
+        if !self.hub.is_online(user_id).await {
+            return;
+        }
+        self.hub.send_to_user(user_id, &event).await;
+        self.push_unread_count(user_id).await;
+    }
+
+    async fn push_unread_count(&self, user_id: i32) {
+        if !self.hub.is_online(user_id).await {
+            return;
+        }
+        match self.repo.unread_count(user_id).await {
+            Ok(count) => self.hub.send_to_user(user_id, &NotificationEvent::UnreadCount(count)).await,
+            Err(e) => warn!("获取未读通知数失败: {}", e),
+        }
+    }
+
+    /// 建立实时连接时需要补发的事件：last_event_id 之后的通知 + 当前未读数
+    pub async fn resume_events(&self, user_id: i32, last_event_id: Option<i32>) -> Result<Vec<NotificationEvent>> {
+        let mut events = Vec::new();
+        if let Some(last_id) = last_event_id {
+            for n in self.repo.list_after(user_id, last_id, MAX_REPLAY).await? {
+                events.push(NotificationEvent::Notification(n));
+            }
+        }
+        events.push(NotificationEvent::UnreadCount(self.repo.unread_count(user_id).await?));
+        Ok(events)
     }
 
     pub async fn list(&self, user_id: i32, q: NotificationQuery) -> Result<Vec<Notification>> {
         self.repo.list(user_id, q.page.unwrap_or(1), q.size.unwrap_or(20)).await
     }
 
-    pub async fn mark_read(&self, user_id: i32, id: i32) -> Result<()> { self.repo.mark_read(user_id, id).await }
+    pub async fn mark_read(&self, user_id: i32, id: i32) -> Result<()> {
+        self.repo.mark_read(user_id, id).await?;
+        self.push_unread_count(user_id).await;
+        Ok(())
+    }
     pub async fn unread_count(&self, user_id: i32) -> Result<i32> { self.repo.unread_count(user_id).await }
-    pub async fn mark_all_read(&self, user_id: i32) -> Result<()> { self.repo.mark_all_read(user_id).await }
+    pub async fn mark_all_read(&self, user_id: i32) -> Result<()> {
+        self.repo.mark_all_read(user_id).await?;
+        self.push_unread_count(user_id).await;
+        Ok(())
+    }
 
     // 管理员用：全站列表与总数
     pub async fn list_all(&self, page: i32, size: i32) -> Result<Vec<Notification>> { self.repo.list_all(page, size).await }
@@ -43,5 +254,163 @@ impl NotificationService {
     pub async fn delete_read(&self, user_id: i32) -> Result<i32> { self.repo.delete_read(user_id).await }
     
     // 删除单个通知
-    pub async fn delete_by_id(&self, user_id: i32, id: i32) -> Result<()> { self.repo.delete_by_id(user_id, id).await }
-} 
\ No newline at end of file
+    pub async fn delete_by_id(&self, user_id: i32, id: i32) -> Result<()> {
+        self.repo.delete_by_id(user_id, id).await?;
+        self.push_unread_count(user_id).await;
+        Ok(())
+    }
+
+    /// 获取通知偏好（未设置的类别返回默认投递方式）
+    pub async fn get_settings(&self, user_id: i32) -> Result<NotificationSettings> {
+        let stored = self.repo.get_preferences(user_id).await?;
+        let preferences = NotificationCategory::all().into_iter().map(|category| {
+            let channel = stored.iter().find(|(c, _)| *c == category).map(|(_, ch)| *ch).unwrap_or_else(|| category.default_channel());
+            NotificationPreferenceItem { category, label: category.label().to_string(), channel }
+        }).collect();
+        let digest = self.repo.get_digest_setting(user_id).await?;
+        Ok(NotificationSettings {
+            preferences,
+            digest_frequency: digest.frequency,
+            weekly_report_email: digest.weekly_report,
+            mail_language: digest.mail_language,
+        })
+    }
+
+    pub async fn update_settings(&self, user_id: i32, req: UpdateNotificationSettingsRequest) -> Result<NotificationSettings> {
+        if let Some(preferences) = req.preferences {
+            for (category, channel) in preferences {
+                self.repo.set_preference(user_id, category, channel).await?;
+            }
+        }
+        if let Some(frequency) = req.digest_frequency {
+            self.repo.set_digest_frequency(user_id, frequency).await?;
+        }
+        if let Some(enabled) = req.weekly_report_email {
+            self.repo.set_weekly_report(user_id, enabled).await?;
+        }
+        if let Some(language) = req.mail_language {
+            let language = match language.trim() {
+                "" => None,
+                l => Some(normalize_language(Some(l))?),
+            };
+            self.repo.set_mail_language(user_id, language.as_deref()).await?;
+        }
+        self.get_settings(user_id).await
+    }
+
+    /// 通过邮件中的退订令牌退订（无需登录）：指定类别时仅将该类别改为站内通知，否则退订全部邮件、摘要及周报
+    pub async fn unsubscribe(&self, token: &str, category: Option<NotificationCategory>) -> Result<bool> {
+        let user_id = match self.repo.find_user_by_unsubscribe_token(token).await? {
+            Some(id) => id,
+            None => return Ok(false),
+        };
+        let categories = match category {
+            Some(c) => vec![c],
+            None => {
+                self.repo.set_digest_frequency(user_id, DigestFrequency::Off).await?;
+                self.repo.set_weekly_report(user_id, false).await?;
+                NotificationCategory::all()
+            }
+        };
+        for category in categories {
+            if self.channel_for(user_id, category).await? == NotificationChannel::Email {
+                self.repo.set_preference(user_id, category, NotificationChannel::InApp).await?;
+            }
+        }
+        Ok(true)
+    }
+
+    /// 订阅了周报邮件的用户
+    pub async fn weekly_report_subscribers(&self) -> Result<Vec<DigestSetting>> {
+        self.repo.list_weekly_report_settings().await
+    }
+
+    /// 启动摘要任务：每小时检查一次，每日摘要在社区时区 8 点后发送，每周摘要在周一 8 点后发送
+    pub fn start_digest_job(&self) {
+        let svc = self.clone();
+        tokio::spawn(async move {
+            info!("📬 通知摘要任务已启动");
+            let mut interval = tokio::time::interval(DIGEST_CHECK_INTERVAL);
+            loop {
+                interval.tick().await;
+                for frequency in [DigestFrequency::Daily, DigestFrequency::Weekly] {
+                    if let Err(e) = svc.run_digest(frequency).await {
+                        warn!("发送通知摘要失败: {}", e);
+                    }
+                }
+            }
+        });
+    }
+
+    async fn run_digest(&self, frequency: DigestFrequency) -> Result<()> {
+        let email_service = match &self.email_service {
+            Some(es) => es.clone(),
+            None => return Ok(()),
+        };
+        let now = local_now();
+        if now.hour() < DIGEST_SEND_HOUR {
+            return Ok(());
+        }
+        if frequency == DigestFrequency::Weekly && now.weekday() != chrono::Weekday::Mon {
+            return Ok(());
+        }
+        for setting in self.repo.list_digest_settings(frequency).await? {
+            if !is_digest_due(&setting, now) {
+                continue;
+            }
+            if let Err(e) = self.send_digest(&email_service, &setting).await {
+                warn!("发送用户 {} 的通知摘要失败: {}", setting.user_id, e);
+            }
+        }
+        Ok(())
+    }
+
+    async fn send_digest(&self, email_service: &Arc<RwLock<EmailService>>, setting: &DigestSetting) -> Result<()> {
+        let sent_at = Utc::now();
+        let unread = self.repo.list_unread_since(setting.user_id, setting.last_sent_at).await?;
+        let contact = self.repo.get_user_contact(setting.user_id).await?;
+        if let (false, Some((username, email))) = (unread.is_empty(), contact) {
+            if !email.trim().is_empty() {
+                let frontend_url = std::env::var("FRONTEND_URL").unwrap_or_else(|_| "http://localhost:5173".to_string());
+                let items: Vec<serde_json::Value> = unread.iter().rev().take(MAX_DIGEST_ITEMS).map(|n| serde_json::json!({
+                    "title": n.title,
+                    "content": n.content,
+                    "link": n.link.as_deref().map(|l| absolute_link(&frontend_url, l)).unwrap_or_default(),
+                    "time": n.created_at.with_timezone(&community_offset()).format("%Y-%m-%d %H:%M").to_string(),
+                })).collect();
+                let variables = serde_json::json!({
+                    "username": username,
+                    "period": if setting.frequency == DigestFrequency::Weekly { "本周" } else { "今日" },
+                    "count": unread.len(),
+                    "items": items,
+                    "link": format!("{}/notifications", frontend_url.trim_end_matches('/')),
+                    "unsubscribe_link": self.unsubscribe_link(&setting.unsubscribe_token, None),
+                });
+                email_service.read().await.send_localized_mail(&email, MailType::Digest, setting.mail_language.as_deref(), &variables).await?;
+            }
+        }
+        self.repo.mark_digest_sent(setting.user_id, sent_at).await
+    }
+}
+
+/// 按社区时区判断本期摘要是否已发送：每日按自然日，每周按 ISO 周
+fn is_digest_due(setting: &DigestSetting, now: chrono::DateTime<chrono::FixedOffset>) -> bool {
+    let last = match setting.last_sent_at {
+        Some(t) => t.with_timezone(&community_offset()),
+        None => return true,
+    };
+    match setting.frequency {
+        DigestFrequency::Daily => last.date_naive() < now.date_naive(),
+        DigestFrequency::Weekly => last.iso_week() != now.iso_week(),
+        DigestFrequency::Off => false,
+    }
+}
+
+/// 站内通知的链接多为前端相对路径，邮件中需补全为绝对地址
+fn absolute_link(frontend_url: &str, link: &str) -> String {
+    if link.starts_with("http://") || link.starts_with("https://") {
+        link.to_string()
+    } else {
+        format!("{}/{}", frontend_url.trim_end_matches('/'), link.trim_start_matches('/'))
+    }
+}
@@ -0,0 +1,248 @@
+use anyhow::Result;
+use chrono::{Duration, NaiveDate, TimeZone, Utc};
+use std::collections::HashMap;
+
+use crate::models::package_analytics::{
+    AgentCount, PackageAnalytics, PackageAnalyticsTotals, PackageDailyStats, SourceCount, VersionAdoption,
+    DEFAULT_ANALYTICS_DAYS, MAX_ANALYTICS_DAYS,
+};
+use crate::models::Package;
+use crate::repositories::package_analytics_repo::{DailyCount, PackageAnalyticsRepository};
+use crate::repositories::package_repo::PackageRepository;
+use crate::services::package_maintainer_service::PackageMaintainerService;
+use crate::utils::client_info;
+use crate::utils::time::{community_offset, local_now};
+use crate::utils::error::ServiceError;
+
+/// 资源数据分析：所有者、维护者与管理员可查看每日浏览/下载、点赞与收藏趋势、
+/// 来源与平台分布、各版本下载情况，并导出 CSV；按社区时区划分自然日
+#[derive(Clone)]
+pub struct PackageAnalyticsService {
+    analytics_repo: PackageAnalyticsRepository,
+    package_repo: PackageRepository,
+    maintainer_service: PackageMaintainerService,
+}
+
+impl PackageAnalyticsService {
+    pub fn new(
+        analytics_repo: PackageAnalyticsRepository,
+        package_repo: PackageRepository,
+        maintainer_service: PackageMaintainerService,
+    ) -> Self {
+        Self { analytics_repo, package_repo, maintainer_service }
+    }
+
+    async fn find_authorized(&self, package_id: i32, actor_id: i32, is_admin: bool) -> Result<Package> {
+        let package = self
+            .package_repo
+            .find_by_id(package_id)
+            .await?
+            .ok_or_else(|| ServiceError::not_found("资源不存在"))?;
+        if !is_admin && !self.maintainer_service.can_edit(&package, actor_id).await? {
+            return Err(ServiceError::forbidden("只有资源所有者、维护者或管理员可以查看数据分析"));
+        }
+        Ok(package)
+    }
+
+    pub async fn get_analytics(
+        &self,
+        package_id: i32,
+        days: Option<i64>,
+        actor_id: i32,
+        is_admin: bool,
+    ) -> Result<PackageAnalytics> {
+        let package = self.find_authorized(package_id, actor_id, is_admin).await?;
+        let days = days.unwrap_or(DEFAULT_ANALYTICS_DAYS).clamp(1, MAX_ANALYTICS_DAYS);
+
+        // 统计区间为社区时区下包含今天在内的最近 days 天，查询时换算成 UTC 起点
+        let offset = community_offset();
+        let to_date = local_now().date_naive();
+        let from_date = to_date - Duration::days(days - 1);
+        let from_utc = offset
+            .from_local_datetime(&from_date.and_hms_opt(0, 0, 0).expect("valid time"))
+            .single()
+            .expect("fixed offset")
+            .with_timezone(&Utc)
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string();
+        let day_modifier = format!("{:+} seconds", offset.local_minus_utc());
+
+        let views = self.analytics_repo.daily_views(package_id, &from_utc, &day_modifier).await?;
+        let downloads = self.analytics_repo.daily_downloads(package_id, &from_utc, &day_modifier).await?;
+        let likes = self.analytics_repo.daily_likes(package_id, &from_utc, &day_modifier).await?;
+        let favorites = self.analytics_repo.daily_favorites(package_id, &from_utc, &day_modifier).await?;
+        let (unique_visitors, unique_downloaders) = self.analytics_repo.unique_totals(package_id, &from_utc).await?;
+        let (like_count, favorite_count) = self.analytics_repo.current_reactions(package_id).await?;
+
+        let daily = Self::build_daily(from_date, days, &views, &downloads, &likes, &favorites, like_count, favorite_count);
+
+        let total_views: i64 = daily.iter().map(|d| d.views).sum();
+        let total_downloads: i64 = daily.iter().map(|d| d.downloads).sum();
+        let totals = PackageAnalyticsTotals {
+            views: total_views,
+            unique_visitors,
+            downloads: total_downloads,
+            unique_downloaders,
+            new_likes: daily.iter().map(|d| d.new_likes).sum(),
+            new_favorites: daily.iter().map(|d| d.new_favorites).sum(),
+            like_count,
+            favorite_count,
+            download_rate: if total_views > 0 {
+                (total_downloads as f64 / total_views as f64 * 10000.0).round() / 10000.0
+            } else {
+                0.0
+            },
+        };
+
+        // 来源、平台与客户端分布
+        let view_agents = self.analytics_repo.view_agents(package_id, &from_utc).await?;
+        let download_agents = self.analytics_repo.download_agents(package_id, &from_utc).await?;
+
+        let mut referrer_counts: HashMap<String, i64> = HashMap::new();
+        for (referrer, _, count) in &view_agents {
+            let name = referrer.clone().unwrap_or_else(|| "直接访问".to_string());
+            *referrer_counts.entry(name).or_insert(0) += count;
+        }
+        let mut referrers: Vec<SourceCount> = referrer_counts
+            .into_iter()
+            .map(|(name, count)| SourceCount { name, count, percent: Self::percent(count, total_views) })
+            .collect();
+        referrers.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+
+        let platforms = Self::agent_breakdown(&view_agents, &download_agents, client_info::platform);
+        let clients = Self::agent_breakdown(&view_agents, &download_agents, client_info::client);
+
+        let versions = self
+            .analytics_repo
+            .version_adoption(package_id)
+            .await?
+            .into_iter()
+            .map(|(version, downloads, unique_downloaders, first_download_at, last_download_at)| VersionAdoption {
+                is_current: version.is_some() && version == package.version,
+                version,
+                downloads,
+                unique_downloaders,
+                first_download_at,
+                last_download_at,
+            })
+            .collect();
+
+        Ok(PackageAnalytics {
+            package_id,
+            package_name: package.name,
+            current_version: package.version,
+            from: from_date.format("%Y-%m-%d").to_string(),
+            to: to_date.format("%Y-%m-%d").to_string(),
+            days,
+            totals,
+            daily,
+            referrers,
+            platforms,
+            clients,
+            versions,
+        })
+    }
+
+    /// 导出每日数据，返回 (文件名, CSV 内容)；带 BOM 以便 Excel 正确识别中文
+    pub async fn export_csv(
+        &self,
+        package_id: i32,
+        days: Option<i64>,
+        actor_id: i32,
+        is_admin: bool,
+    ) -> Result<(String, String)> {
+        let analytics = self.get_analytics(package_id, days, actor_id, is_admin).await?;
+        let mut csv = String::from("\u{feff}日期,浏览量,访客数,下载量,下载人数,新增点赞,新增收藏,点赞总数,收藏总数\n");
+        for d in &analytics.daily {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{},{}\n",
+                d.date, d.views, d.unique_visitors, d.downloads, d.unique_downloaders,
+                d.new_likes, d.new_favorites, d.like_total, d.favorite_total
+            ));
+        }
+        let filename = format!("package_{}_analytics_{}_{}.csv", package_id, analytics.from, analytics.to);
+        Ok((filename, csv))
+    }
+
+    /// 把查询结果铺满区间内的每一天；点赞与收藏总数从当前数量往前倒推
+    #[allow(clippy::too_many_arguments)]
+    fn build_daily(
+        from_date: NaiveDate,
+        days: i64,
+        views: &[DailyCount],
+        downloads: &[DailyCount],
+        likes: &[DailyCount],
+        favorites: &[DailyCount],
+        like_count: i64,
+        favorite_count: i64,
+    ) -> Vec<PackageDailyStats> {
+        let mut daily: Vec<PackageDailyStats> = (0..days)
+            .map(|i| PackageDailyStats {
+                date: (from_date + Duration::days(i)).format("%Y-%m-%d").to_string(),
+                ..Default::default()
+            })
+            .collect();
+        let index: HashMap<String, usize> = daily.iter().enumerate().map(|(i, d)| (d.date.clone(), i)).collect();
+
+        for (date, count, unique) in views {
+            if let Some(&i) = index.get(date) {
+                daily[i].views = *count;
+                daily[i].unique_visitors = *unique;
+            }
+        }
+        for (date, count, unique) in downloads {
+            if let Some(&i) = index.get(date) {
+                daily[i].downloads = *count;
+                daily[i].unique_downloaders = *unique;
+            }
+        }
+        for (date, count, _) in likes {
+            if let Some(&i) = index.get(date) {
+                daily[i].new_likes = *count;
+            }
+        }
+        for (date, count, _) in favorites {
+            if let Some(&i) = index.get(date) {
+                daily[i].new_favorites = *count;
+            }
+        }
+
+        let (mut like_total, mut favorite_total) = (like_count, favorite_count);
+        for d in daily.iter_mut().rev() {
+            d.like_total = like_total;
+            d.favorite_total = favorite_total;
+            like_total -= d.new_likes;
+            favorite_total -= d.new_favorites;
+        }
+        daily
+    }
+
+    /// 按 User-Agent 归类后合并浏览与下载次数
+    fn agent_breakdown(
+        view_agents: &[(Option<String>, Option<String>, i64)],
+        download_agents: &[(Option<String>, i64)],
+        classify: fn(Option<&str>) -> &'static str,
+    ) -> Vec<AgentCount> {
+        let mut counts: HashMap<&'static str, (i64, i64)> = HashMap::new();
+        for (_, user_agent, count) in view_agents {
+            counts.entry(classify(user_agent.as_deref())).or_insert((0, 0)).0 += count;
+        }
+        for (user_agent, count) in download_agents {
+            counts.entry(classify(user_agent.as_deref())).or_insert((0, 0)).1 += count;
+        }
+        let mut list: Vec<AgentCount> = counts
+            .into_iter()
+            .map(|(name, (views, downloads))| AgentCount { name: name.to_string(), views, downloads })
+            .collect();
+        list.sort_by(|a, b| (b.views + b.downloads).cmp(&(a.views + a.downloads)).then_with(|| a.name.cmp(&b.name)));
+        list
+    }
+
+    fn percent(count: i64, total: i64) -> f64 {
+        if total > 0 {
+            (count as f64 / total as f64 * 1000.0).round() / 10.0
+        } else {
+            0.0
+        }
+    }
+}
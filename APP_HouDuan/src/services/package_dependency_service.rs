@@ -0,0 +1,373 @@
+use anyhow::Result;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
+
+use crate::models::package_dependency::{
+    AppCompatibility, DependencyConflict, DependencyResolution, DependentPackage, PackageDependency, PackageDependencyInfo,
+    ResolveQuery, ResolvedPackage, UpdateDependenciesRequest, MAX_DEPENDENCIES_PER_PACKAGE, MAX_RESOLVE_PACKAGES,
+};
+use crate::models::{Package, PackageStatus};
+use crate::repositories::package_dependency_repo::PackageDependencyRepository;
+use crate::repositories::package_repo::PackageRepository;
+use crate::services::package_maintainer_service::PackageMaintainerService;
+use crate::utils::version::{parse_lenient, parse_requirement};
+use crate::utils::error::ServiceError;
+
+/// 解析时展开的一个资源
+struct ResolveNode {
+    package: Package,
+    dependencies: Vec<PackageDependency>,
+    compatibility: AppCompatibility,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum VisitState {
+    Visiting,
+    Done,
+}
+
+/// 广度优先查找：deps_of 给出一个资源的直接依赖；已有的环不会导致死循环，展开超过 MAX_RESOLVE_PACKAGES 个资源后停止
+async fn reaches_via<F, Fut>(starts: &[i32], target: i32, mut deps_of: F) -> Result<bool>
+where
+    F: FnMut(i32) -> Fut,
+    Fut: Future<Output = Result<Vec<i32>>>,
+{
+    let mut queue: VecDeque<i32> = starts.iter().copied().collect();
+    let mut seen: HashSet<i32> = HashSet::new();
+    while let Some(id) = queue.pop_front() {
+        if id == target {
+            return Ok(true);
+        }
+        if !seen.insert(id) || seen.len() > MAX_RESOLVE_PACKAGES {
+            continue;
+        }
+        queue.extend(deps_of(id).await?);
+    }
+    Ok(false)
+}
+
+/// 资源依赖：所有者、维护者与管理员可声明依赖与兼容的结绳版本；
+/// 解析时展开完整依赖树，给出安装顺序，并报告缺失、未上架、版本不满足、结绳版本不兼容与循环依赖
+#[derive(Clone)]
+pub struct PackageDependencyService {
+    dependency_repo: PackageDependencyRepository,
+    package_repo: PackageRepository,
+    maintainer_service: PackageMaintainerService,
+}
+
+impl PackageDependencyService {
+    pub fn new(
+        dependency_repo: PackageDependencyRepository,
+        package_repo: PackageRepository,
+        maintainer_service: PackageMaintainerService,
+    ) -> Self {
+        Self { dependency_repo, package_repo, maintainer_service }
+    }
+
+    async fn find_package(&self, package_id: i32) -> Result<Package> {
+        self.package_repo
+            .find_by_id(package_id)
+            .await?
+            .ok_or_else(|| ServiceError::not_found("资源不存在"))
+    }
+
+    pub async fn get(&self, package_id: i32) -> Result<PackageDependencyInfo> {
+        let package = self.find_package(package_id).await?;
+        Ok(PackageDependencyInfo {
+            package_id,
+            version: package.version,
+            compatibility: self.dependency_repo.compatibility(package_id).await?,
+            dependencies: self.dependency_repo.list(package_id).await?,
+        })
+    }
+
+    pub async fn dependents(&self, package_id: i32) -> Result<Vec<DependentPackage>> {
+        self.find_package(package_id).await?;
+        self.dependency_repo.dependents(package_id).await
+    }
+
+    fn normalize_app_version(raw: Option<&str>, label: &str) -> Result<Option<String>> {
+        match raw.map(str::trim).filter(|v| !v.is_empty()) {
+            Some(v) if parse_lenient(v).is_none() => Err(ServiceError::bad_request(format!("{}无法识别: {}", label, v))),
+            Some(v) => Ok(Some(v.to_string())),
+            None => Ok(None),
+        }
+    }
+
+    /// 从 starts 出发沿依赖（含可选依赖）能否到达 target
+    async fn reaches(&self, starts: &[i32], target: i32) -> Result<bool> {
+        let repo = &self.dependency_repo;
+        reaches_via(starts, target, |id| async move {
+            Ok(repo.list(id).await?.into_iter().map(|dep| dep.depends_on_id).collect())
+        })
+        .await
+    }
+
+    /// 整体替换依赖与兼容版本
+    pub async fn update(
+        &self,
+        actor_id: i32,
+        is_admin: bool,
+        package_id: i32,
+        req: UpdateDependenciesRequest,
+    ) -> Result<PackageDependencyInfo> {
+        let package = self.find_package(package_id).await?;
+        if !is_admin && !self.maintainer_service.can_edit(&package, actor_id).await? {
+            return Err(ServiceError::forbidden("只有资源所有者或维护者可以修改依赖"));
+        }
+        if req.dependencies.len() > MAX_DEPENDENCIES_PER_PACKAGE {
+            return Err(ServiceError::bad_request(format!("依赖数量不能超过 {} 个", MAX_DEPENDENCIES_PER_PACKAGE)));
+        }
+
+        let mut dependencies: Vec<(i32, String, bool)> = Vec::with_capacity(req.dependencies.len());
+        for dep in &req.dependencies {
+            if dep.package_id == package_id {
+                return Err(ServiceError::bad_request("资源不能依赖自身"));
+            }
+            if dependencies.iter().any(|(id, _, _)| *id == dep.package_id) {
+                return Err(ServiceError::bad_request(format!("依赖资源 {} 重复", dep.package_id)));
+            }
+            if self.package_repo.find_by_id(dep.package_id).await?.is_none() {
+                return Err(ServiceError::bad_request(format!("依赖的资源 {} 不存在", dep.package_id)));
+            }
+            let raw_req = dep.version_req.as_deref().unwrap_or("").trim();
+            parse_requirement(raw_req).map_err(|e| ServiceError::bad_request(format!("版本范围无效 {}: {}", raw_req, e)))?;
+            let version_req = if raw_req.is_empty() { "*".to_string() } else { raw_req.to_string() };
+            dependencies.push((dep.package_id, version_req, dep.optional));
+        }
+
+        let compatibility = AppCompatibility {
+            min_app_version: Self::normalize_app_version(req.min_app_version.as_deref(), "最低结绳版本")?,
+            max_app_version: Self::normalize_app_version(req.max_app_version.as_deref(), "最高结绳版本")?,
+        };
+        if let (Some(min), Some(max)) = (
+            compatibility.min_app_version.as_deref().and_then(parse_lenient),
+            compatibility.max_app_version.as_deref().and_then(parse_lenient),
+        ) {
+            if min > max {
+                return Err(ServiceError::bad_request("最低结绳版本不能高于最高结绳版本"));
+            }
+        }
+
+        let targets: Vec<i32> = dependencies.iter().map(|(id, _, _)| *id).collect();
+        if self.reaches(&targets, package_id).await? {
+            return Err(ServiceError::bad_request("依赖会形成循环，请检查被依赖资源是否已依赖本资源"));
+        }
+
+        self.dependency_repo.replace(package_id, &dependencies, &compatibility).await?;
+        self.get(package_id).await
+    }
+
+    /// 展开依赖树；不存在的资源记入 missing
+    async fn load_graph(&self, root: Package, include_optional: bool) -> Result<(HashMap<i32, ResolveNode>, HashSet<i32>)> {
+        let mut nodes: HashMap<i32, ResolveNode> = HashMap::new();
+        let mut missing: HashSet<i32> = HashSet::new();
+        let mut queue: VecDeque<(i32, Option<Package>)> = VecDeque::from([(root.id, Some(root))]);
+        while let Some((id, known)) = queue.pop_front() {
+            if nodes.contains_key(&id) || missing.contains(&id) {
+                continue;
+            }
+            if nodes.len() >= MAX_RESOLVE_PACKAGES {
+                return Err(ServiceError::bad_request(format!("依赖树过大，超过 {} 个资源", MAX_RESOLVE_PACKAGES)));
+            }
+            let package = match known {
+                Some(p) => p,
+                None => match self.package_repo.find_by_id(id).await? {
+                    Some(p) => p,
+                    None => {
+                        missing.insert(id);
+                        continue;
+                    }
+                },
+            };
+            let dependencies: Vec<PackageDependency> = self
+                .dependency_repo
+                .list(id)
+                .await?
+                .into_iter()
+                .filter(|d| include_optional || !d.optional)
+                .collect();
+            for dep in &dependencies {
+                queue.push_back((dep.depends_on_id, None));
+            }
+            let compatibility = self.dependency_repo.compatibility(id).await?;
+            nodes.insert(id, ResolveNode { package, dependencies, compatibility });
+        }
+        Ok((nodes, missing))
+    }
+
+    /// 深度优先得到安装顺序（依赖在前），遇到正在访问的资源即为环
+    fn visit(
+        id: i32,
+        nodes: &HashMap<i32, ResolveNode>,
+        state: &mut HashMap<i32, VisitState>,
+        stack: &mut Vec<i32>,
+        order: &mut Vec<i32>,
+        cycles: &mut Vec<Vec<i32>>,
+    ) {
+        match state.get(&id) {
+            Some(VisitState::Done) => return,
+            Some(VisitState::Visiting) => {
+                if let Some(pos) = stack.iter().position(|s| *s == id) {
+                    let mut cycle = stack[pos..].to_vec();
+                    cycle.push(id);
+                    cycles.push(cycle);
+                }
+                return;
+            }
+            None => {}
+        }
+        let Some(node) = nodes.get(&id) else {
+            return;
+        };
+        state.insert(id, VisitState::Visiting);
+        stack.push(id);
+        for dep in &node.dependencies {
+            Self::visit(dep.depends_on_id, nodes, state, stack, order, cycles);
+        }
+        stack.pop();
+        state.insert(id, VisitState::Done);
+        order.push(id);
+    }
+
+    fn compatibility_conflict(node: &ResolveNode, app_version: &semver::Version, app_raw: &str) -> Option<DependencyConflict> {
+        let min = node.compatibility.min_app_version.as_deref();
+        let max = node.compatibility.max_app_version.as_deref();
+        let reason = if min.and_then(parse_lenient).is_some_and(|m| *app_version < m) {
+            format!("需要结绳 {} 及以上", min.unwrap_or_default())
+        } else if max.and_then(parse_lenient).is_some_and(|m| *app_version > m) {
+            format!("最高支持结绳 {}", max.unwrap_or_default())
+        } else {
+            return None;
+        };
+        Some(DependencyConflict {
+            package_id: node.package.id,
+            name: Some(node.package.name.clone()),
+            version: node.package.version.clone(),
+            requirement: Some(app_raw.to_string()),
+            required_by: None,
+            reason,
+        })
+    }
+
+    /// 解析资源当前版本的完整安装集合
+    pub async fn resolve(&self, package_id: i32, query: &ResolveQuery) -> Result<DependencyResolution> {
+        let root = self.find_package(package_id).await?;
+        let app_raw = query.app_version.as_deref().map(str::trim).filter(|v| !v.is_empty());
+        let app_version = match app_raw {
+            Some(raw) => Some(parse_lenient(raw).ok_or_else(|| ServiceError::bad_request(format!("结绳版本号无法识别: {}", raw)))?),
+            None => None,
+        };
+        let root_version = root.version.clone();
+        let (nodes, missing) = self.load_graph(root, query.include_optional).await?;
+
+        let mut state = HashMap::new();
+        let mut order = Vec::new();
+        let mut cycles = Vec::new();
+        Self::visit(package_id, &nodes, &mut state, &mut Vec::new(), &mut order, &mut cycles);
+
+        let mut conflicts = Vec::new();
+        let mut required_by: HashMap<i32, Vec<i32>> = HashMap::new();
+        for id in &order {
+            let node = &nodes[id];
+            if *id == package_id && node.package.status != PackageStatus::Active {
+                conflicts.push(DependencyConflict {
+                    package_id: *id,
+                    name: Some(node.package.name.clone()),
+                    version: node.package.version.clone(),
+                    requirement: None,
+                    required_by: None,
+                    reason: "资源未上架".to_string(),
+                });
+            }
+            for dep in &node.dependencies {
+                required_by.entry(dep.depends_on_id).or_default().push(*id);
+                let reason = if missing.contains(&dep.depends_on_id) {
+                    "依赖的资源不存在"
+                } else if nodes[&dep.depends_on_id].package.status != PackageStatus::Active {
+                    "依赖的资源未上架"
+                } else if !dep.satisfied {
+                    "版本不满足要求"
+                } else {
+                    continue;
+                };
+                conflicts.push(DependencyConflict {
+                    package_id: dep.depends_on_id,
+                    name: dep.name.clone(),
+                    version: dep.version.clone(),
+                    requirement: Some(dep.version_req.clone()),
+                    required_by: Some(*id),
+                    reason: reason.to_string(),
+                });
+            }
+            if let (Some(app), Some(raw)) = (app_version.as_ref(), app_raw) {
+                conflicts.extend(Self::compatibility_conflict(node, app, raw));
+            }
+        }
+
+        let install_order = order
+            .iter()
+            .map(|id| {
+                let package = &nodes[id].package;
+                ResolvedPackage {
+                    package_id: *id,
+                    name: package.name.clone(),
+                    version: package.version.clone(),
+                    file_url: package.file_url.clone(),
+                    file_size: package.file_size,
+                    required_by: required_by.remove(id).unwrap_or_default(),
+                }
+            })
+            .collect();
+
+        Ok(DependencyResolution {
+            package_id,
+            version: root_version,
+            app_version: app_raw.map(str::to_string),
+            resolvable: conflicts.is_empty() && cycles.is_empty(),
+            install_order,
+            conflicts,
+            cycles,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+
+    fn reaches_in(edges: &[(i32, i32)], starts: &[i32], target: i32) -> bool {
+        let graph: HashMap<i32, Vec<i32>> = edges.iter().fold(HashMap::new(), |mut graph, (from, to)| {
+            graph.entry(*from).or_default().push(*to);
+            graph
+        });
+        block_on(reaches_via(starts, target, |id| {
+            let deps = graph.get(&id).cloned().unwrap_or_default();
+            async move { Ok(deps) }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn detects_direct_cycle() {
+        // 1 准备依赖 2，而 2 已依赖 1
+        assert!(reaches_in(&[(2, 1)], &[2], 1));
+    }
+
+    #[test]
+    fn detects_indirect_cycle() {
+        assert!(reaches_in(&[(2, 3), (3, 4), (4, 1)], &[5, 2], 1));
+    }
+
+    #[test]
+    fn allows_shared_dependencies() {
+        // 菱形依赖不是环
+        assert!(!reaches_in(&[(2, 4), (3, 4)], &[2, 3], 1));
+    }
+
+    #[test]
+    fn terminates_on_existing_cycle_elsewhere() {
+        assert!(!reaches_in(&[(2, 3), (3, 2)], &[2], 1));
+    }
+}
@@ -0,0 +1,207 @@
+use anyhow::Result;
+
+use crate::models::package_maintainer::{
+    OwnershipTransfer, PackageMaintainer, MAX_MAINTAINERS_PER_PACKAGE, TRANSFER_CANCELLED, TRANSFER_DECLINED,
+    TRANSFER_PENDING,
+};
+use crate::models::Package;
+use crate::repositories::package_maintainer_repo::PackageMaintainerRepository;
+use crate::repositories::package_repo::PackageRepository;
+use crate::repositories::user_repo::UserRepository;
+use crate::services::notification_service::NotificationService;
+use crate::utils::error::ServiceError;
+
+/// 资源权限：所有者（packages.owner_id）可编辑、上传、删除、管理维护者与转让；
+/// 协作维护者可编辑与上传；管理员与元老不受限制（在接口层判断）
+#[derive(Clone)]
+pub struct PackageMaintainerService {
+    maintainer_repo: PackageMaintainerRepository,
+    package_repo: PackageRepository,
+    user_repo: UserRepository,
+    notification_service: Option<NotificationService>,
+}
+
+impl PackageMaintainerService {
+    pub fn new(maintainer_repo: PackageMaintainerRepository, package_repo: PackageRepository, user_repo: UserRepository) -> Self {
+        Self { maintainer_repo, package_repo, user_repo, notification_service: None }
+    }
+
+    pub fn with_notification_service(mut self, service: NotificationService) -> Self {
+        self.notification_service = Some(service);
+        self
+    }
+
+    async fn find_package(&self, package_id: i32) -> Result<Package> {
+        self.package_repo
+            .find_by_id(package_id)
+            .await?
+            .ok_or_else(|| ServiceError::not_found("资源不存在"))
+    }
+
+    async fn display_name(&self, user_id: i32) -> String {
+        match self.user_repo.find_by_id(user_id).await {
+            Ok(Some(u)) => u.nickname.unwrap_or(u.username),
+            _ => String::new(),
+        }
+    }
+
+    /// 维护相关通知，关联对象统一为资源
+    async fn send(&self, actor_id: i32, user_id: i32, title: &str, content: &str, notif_type: &str, package_id: i32) {
+        if let Some(notify) = &self.notification_service {
+            let link = format!("/resource/{}", package_id);
+            if let Err(e) = notify
+                .by_actor(actor_id)
+                .notify(user_id, title, content, Some(&link), Some(notif_type), Some("Package"), Some(package_id))
+                .await
+            {
+                log::warn!("发送资源维护通知失败: {}", e);
+            }
+        }
+    }
+
+    pub fn is_owner(package: &Package, user_id: i32) -> bool {
+        package.owner_id == Some(user_id)
+    }
+
+    /// 所有者或协作维护者
+    pub async fn can_edit(&self, package: &Package, user_id: i32) -> Result<bool> {
+        if Self::is_owner(package, user_id) {
+            return Ok(true);
+        }
+        Ok(self.maintainer_repo.role_of(package.id, user_id).await?.is_some())
+    }
+
+    pub async fn list_maintainers(&self, package_id: i32) -> Result<Vec<PackageMaintainer>> {
+        self.find_package(package_id).await?;
+        self.maintainer_repo.list(package_id).await
+    }
+
+    /// 添加协作维护者：所有者或管理员
+    pub async fn add_maintainer(&self, actor_id: i32, is_admin: bool, package_id: i32, user_id: i32) -> Result<Vec<PackageMaintainer>> {
+        let package = self.find_package(package_id).await?;
+        if !is_admin && !Self::is_owner(&package, actor_id) {
+            return Err(ServiceError::forbidden("只有资源所有者可以管理维护者"));
+        }
+        if package.owner_id.is_none() {
+            return Err(ServiceError::bad_request("资源尚未关联所有者，请先由管理员指定所有者"));
+        }
+        if Self::is_owner(&package, user_id) {
+            return Err(ServiceError::bad_request("该用户已是资源所有者"));
+        }
+        if self.user_repo.find_by_id(user_id).await?.is_none() {
+            return Err(ServiceError::not_found("用户不存在"));
+        }
+        if self.maintainer_repo.count_maintainers(package_id).await? >= MAX_MAINTAINERS_PER_PACKAGE {
+            return Err(ServiceError::bad_request(format!("每个资源最多{}位维护者", MAX_MAINTAINERS_PER_PACKAGE)));
+        }
+        if !self.maintainer_repo.add_maintainer(package_id, user_id, actor_id).await? {
+            return Err(ServiceError::bad_request("该用户已是资源维护者"));
+        }
+        let content = format!("{} 邀请您共同维护资源《{}》", self.display_name(actor_id).await, package.name);
+        self.send(actor_id, user_id, "成为资源维护者", &content, "PackageMaintainerAdded", package_id).await;
+        self.maintainer_repo.list(package_id).await
+    }
+
+    /// 移除协作维护者：所有者或管理员，维护者也可以退出
+    pub async fn remove_maintainer(&self, actor_id: i32, is_admin: bool, package_id: i32, user_id: i32) -> Result<()> {
+        let package = self.find_package(package_id).await?;
+        if !is_admin && !Self::is_owner(&package, actor_id) && actor_id != user_id {
+            return Err(ServiceError::forbidden("只有资源所有者可以管理维护者"));
+        }
+        if Self::is_owner(&package, user_id) {
+            return Err(ServiceError::bad_request("不能移除资源所有者，请使用所有权转让"));
+        }
+        if !self.maintainer_repo.remove_maintainer(package_id, user_id).await? {
+            return Err(ServiceError::bad_request("该用户不是资源维护者"));
+        }
+        Ok(())
+    }
+
+    /// 所有者发起转让，对方接受后生效
+    pub async fn request_transfer(&self, actor_id: i32, package_id: i32, to_user_id: i32) -> Result<OwnershipTransfer> {
+        let package = self.find_package(package_id).await?;
+        if !Self::is_owner(&package, actor_id) {
+            return Err(ServiceError::forbidden("只有资源所有者可以转让资源"));
+        }
+        if to_user_id == actor_id {
+            return Err(ServiceError::bad_request("不能转让给自己"));
+        }
+        if self.user_repo.find_by_id(to_user_id).await?.is_none() {
+            return Err(ServiceError::not_found("用户不存在"));
+        }
+        let id = self.maintainer_repo.create_transfer(package_id, actor_id, to_user_id).await?;
+        let content = format!("{} 希望将资源《{}》转让给您", self.display_name(actor_id).await, package.name);
+        self.send(actor_id, to_user_id, "资源转让请求", &content, "PackageTransferRequested", package_id).await;
+        self.find_transfer(id).await
+    }
+
+    /// 资源当前未处理的转让，仅所有者与管理员可见
+    pub async fn pending_transfer(&self, actor_id: i32, is_admin: bool, package_id: i32) -> Result<Option<OwnershipTransfer>> {
+        let package = self.find_package(package_id).await?;
+        if !is_admin && !Self::is_owner(&package, actor_id) {
+            return Err(ServiceError::forbidden("只有资源所有者可以查看转让"));
+        }
+        self.maintainer_repo.pending_transfer(package_id).await
+    }
+
+    pub async fn cancel_transfer(&self, actor_id: i32, package_id: i32) -> Result<()> {
+        let package = self.find_package(package_id).await?;
+        if !Self::is_owner(&package, actor_id) {
+            return Err(ServiceError::forbidden("只有资源所有者可以取消转让"));
+        }
+        let transfer = self
+            .maintainer_repo
+            .pending_transfer(package_id)
+            .await?
+            .ok_or_else(|| ServiceError::not_found("没有待处理的转让"))?;
+        self.maintainer_repo.close_transfer(transfer.id, TRANSFER_CANCELLED).await?;
+        Ok(())
+    }
+
+    pub async fn list_incoming(&self, user_id: i32) -> Result<Vec<OwnershipTransfer>> {
+        self.maintainer_repo.list_incoming(user_id).await
+    }
+
+    async fn find_transfer(&self, transfer_id: i32) -> Result<OwnershipTransfer> {
+        self.maintainer_repo
+            .find_transfer(transfer_id)
+            .await?
+            .ok_or_else(|| ServiceError::not_found("转让请求不存在"))
+    }
+
+    /// 转给自己的转让，已处理的视为不存在
+    async fn incoming_transfer(&self, user_id: i32, transfer_id: i32) -> Result<OwnershipTransfer> {
+        match self.maintainer_repo.find_transfer(transfer_id).await? {
+            Some(t) if t.to_user_id == user_id && t.status == TRANSFER_PENDING => Ok(t),
+            _ => Err(ServiceError::not_found("转让请求不存在")),
+        }
+    }
+
+    pub async fn accept_transfer(&self, user_id: i32, transfer_id: i32) -> Result<OwnershipTransfer> {
+        let transfer = self.incoming_transfer(user_id, transfer_id).await?;
+        // 发起后所有者已变更（如管理员直接指定）的转让不再有效
+        let package = self.find_package(transfer.package_id).await?;
+        if package.owner_id != Some(transfer.from_user_id) {
+            self.maintainer_repo.close_transfer(transfer_id, TRANSFER_CANCELLED).await?;
+            return Err(ServiceError::bad_request("资源所有者已变更，转让已失效"));
+        }
+        if !self.maintainer_repo.accept_transfer(transfer_id).await? {
+            return Err(ServiceError::not_found("转让请求不存在"));
+        }
+        let content = format!("{} 已接受资源《{}》的转让", self.display_name(user_id).await, package.name);
+        self.send(user_id, transfer.from_user_id, "资源转让完成", &content, "PackageTransferAccepted", package.id).await;
+        self.find_transfer(transfer_id).await
+    }
+
+    pub async fn decline_transfer(&self, user_id: i32, transfer_id: i32) -> Result<()> {
+        let transfer = self.incoming_transfer(user_id, transfer_id).await?;
+        self.maintainer_repo.close_transfer(transfer_id, TRANSFER_DECLINED).await?;
+        let content = format!(
+            "{} 拒绝了资源《{}》的转让",
+            self.display_name(user_id).await,
+            transfer.package_name.as_deref().unwrap_or_default()
+        );
+        self.send(user_id, transfer.from_user_id, "资源转让被拒绝", &content, "PackageTransferDeclined", transfer.package_id).await;
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 use anyhow::Result;
 use crate::models::{Package, CreatePackageRequest, UpdatePackageRequest, Category, CreateResourceRecordRequest};
+use crate::models::edit_revision::PendingRevision;
 use crate::repositories::package_repo::PackageRepository;
 use crate::repositories::system_repo::SystemRepository;
 use crate::utils::file::FileUtils;
@@ -12,6 +13,10 @@ use rusqlite::params;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use crate::services::notification_service::NotificationService;
+use crate::models::notification::MentionSource;
+use crate::services::achievement_service::AchievementService;
+use crate::services::points_service::PointsService;
+use crate::services::package_storage_service::PackageStorageService;
 
 #[derive(Clone)]
 pub struct PackageService {
@@ -23,6 +28,10 @@ pub struct PackageService {
     user_repo: Option<crate::repositories::UserRepository>,
     download_security_service: Option<DownloadSecurityService>,
     notification_service: Option<NotificationService>,
+    points_service: Option<PointsService>,
+    achievement_service: Option<AchievementService>,
+    /// 文件存储服务使用的数据库路径
+    db_path: String,
 }
 
 impl PackageService {
@@ -36,9 +45,17 @@ impl PackageService {
             user_repo: None,
             download_security_service: None,
             notification_service: None,
+            points_service: None,
+            achievement_service: None,
+            db_path: "data.db".to_string(),
         }
     }
 
+    pub fn with_db_path(mut self, db_path: &str) -> Self {
+        self.db_path = db_path.to_string();
+        self
+    }
+
     // 设置系统仓库，用于记录资源操作
     pub fn with_system_repo(mut self, system_repo: SystemRepository) -> Self {
         self.system_repo = Some(system_repo);
@@ -66,6 +83,73 @@ impl PackageService {
         self
     }
 
+    pub fn with_points_service(mut self, service: PointsService) -> Self {
+        self.points_service = Some(service);
+        self
+    }
+
+    pub fn with_achievement_service(mut self, service: AchievementService) -> Self {
+        self.achievement_service = Some(service);
+        self
+    }
+
+    /// 保存资源；分类变更时先把文件目录移到新分类下并改写文件地址，
+    /// 目录移动失败则不做任何更新，数据库更新失败则把目录移回原处
+    async fn save_package(&self, before: &Package, package: &mut Package, revision: Option<&PendingRevision>) -> Result<Option<i32>> {
+        let mut relocated = None;
+        if package.category_id != before.category_id {
+            let mut storage = PackageStorageService::get_instance(&self.db_path).await?;
+            relocated = storage
+                .relocate_package_folder(package.id, package.category_id)
+                .await
+                .map_err(|e| anyhow::anyhow!("移动资源文件目录失败: {}", e))?;
+            if let Some((from_dir, to_dir)) = &relocated {
+                Self::rewrite_dir(package, from_dir, to_dir);
+            }
+        }
+        match self.package_repo.update_package_recorded(package, revision).await {
+            Ok(revision_no) => Ok(revision_no),
+            Err(e) => {
+                if let Some(moved) = &relocated {
+                    let mut storage = PackageStorageService::get_instance(&self.db_path).await?;
+                    if let Err(move_err) = storage.move_package_folder_back(moved).await {
+                        log::error!("资源 {} 更新失败后移回文件目录 {} 失败: {}", package.id, moved.0, move_err);
+                    }
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// 将资源文件地址中的原目录替换为新目录
+    fn rewrite_dir(package: &mut Package, from_dir: &str, to_dir: &str) {
+        let (from_dir, to_dir) = (format!("{}/", from_dir), format!("{}/", to_dir));
+        let fix = |url: &mut String| *url = url.replacen(&from_dir, &to_dir, 1);
+        package.file_url.iter_mut().for_each(fix);
+        package.cover_image.iter_mut().for_each(fix);
+        package.screenshots.iter_mut().flatten().for_each(fix);
+        package
+            .included_files
+            .iter_mut()
+            .flatten()
+            .filter_map(|f| f.download_url.as_mut())
+            .for_each(fix);
+    }
+
+    /// 资源相关行为后发放积分并评估成就，失败只记录日志，不影响主流程
+    async fn record_activity(&self, actor_id: Option<i32>, action_type: &str, package_id: i32) {
+        if let Some(points) = &self.points_service {
+            if let Err(e) = points.on_action(actor_id, action_type, Some("Package"), Some(package_id)).await {
+                log::error!("发放积分失败: {}", e);
+            }
+        }
+        if let Some(achievements) = &self.achievement_service {
+            if let Err(e) = achievements.on_action(actor_id, action_type, Some("Package"), Some(package_id)).await {
+                log::error!("评估成就失败: {}", e);
+            }
+        }
+    }
+
     pub fn db_path(&self) -> &str {
         // package_repo holds a connection opened from a path; we can reuse the environment config through repositories::get_connection, but for simplicity we try to access via connection path is not stored. Here we fallback to the global config by reading from repository helper in main; since not available, we just return an empty str isn't acceptable. Alternative: expose nothing and change user.rs not to open connection via service. We'll instead remove its usage. (But current edit requires getter.)
         "data.db"
@@ -89,12 +173,33 @@ impl PackageService {
         self.package_repo.find_by_id(package_id).await
     }
 
+    /// 资源所有者账号（按 owner_id；未关联账号的资源没有所有者）
+    async fn find_owner(&self, package: &Package) -> Option<crate::models::User> {
+        let user_repo = self.user_repo.as_ref()?;
+        user_repo.find_by_id(package.owner_id?).await.ok().flatten()
+    }
+
     pub async fn create_package(&self, req: &CreatePackageRequest) -> Result<Package> {
+        // 解析所有者：指定 owner_id 时必须是已有用户，否则按作者名匹配账号
+        let mut author = req.author.clone();
+        let mut owner_id = None;
+        if let Some(user_repo) = &self.user_repo {
+            let owner = match req.owner_id {
+                Some(id) => Some(user_repo.find_by_id(id).await?.ok_or_else(|| anyhow::anyhow!("所有者用户不存在"))?),
+                None => user_repo.find_by_username(&req.author).await?,
+            };
+            if let Some(owner) = owner {
+                author = owner.username;
+                owner_id = Some(owner.id);
+            }
+        }
+
         // 创建绳包记录
         let package = Package {
             id: 0, // 数据库会自动生成
             name: req.name.clone(),
-            author: req.author.clone(),
+            author,
+            owner_id,
             version: req.version.clone(),
             description: req.description.clone(),
             file_url: req.file_url.clone(), // 直接使用请求中的file_url，已经是Option<String>类型
@@ -168,16 +273,36 @@ impl PackageService {
     }
 
     pub async fn update_package(&self, package_id: i32, req: &UpdatePackageRequest) -> Result<Package> {
+        self.update_package_recorded(package_id, req, None).await
+    }
+
+    /// 更新资源；传入编辑记录时，编辑历史与更新在同一事务中写入
+    pub async fn update_package_recorded(
+        &self,
+        package_id: i32,
+        req: &UpdatePackageRequest,
+        revision: Option<&PendingRevision>,
+    ) -> Result<Package> {
         let package = self.package_repo.find_by_id(package_id).await?;
         let package = package.ok_or_else(|| anyhow::anyhow!("绳包不存在"))?;
 
         // 克隆package用于记录旧数据
         let old_package = package.clone();
+
+        // 直接指定新所有者（仅管理员入口会传入），作者名同步为所有者用户名
+        let (author, owner_id) = match (req.owner_id, &self.user_repo) {
+            (Some(id), Some(user_repo)) => {
+                let owner = user_repo.find_by_id(id).await?.ok_or_else(|| anyhow::anyhow!("所有者用户不存在"))?;
+                (owner.username, Some(owner.id))
+            }
+            _ => (package.author, package.owner_id),
+        };
         
-        let updated_package = Package {
+        let mut updated_package = Package {
             id: package_id,
             name: req.name.clone().unwrap_or(package.name),
-            author: req.author.clone().unwrap_or(package.author),
+            author,
+            owner_id,
             version: req.version.clone().or(package.version),
             description: req.description.clone().or(package.description),
             category_id: req.category_id.or(package.category_id),
@@ -202,7 +327,7 @@ impl PackageService {
             included_files: req.included_files.clone().or(package.included_files),
         };
 
-        self.package_repo.update_package(&updated_package).await?;
+        self.save_package(&old_package, &mut updated_package, revision).await?;
         
         // 记录资源更新操作
         if let Some(system_repo) = &self.system_repo {
@@ -226,32 +351,9 @@ impl PackageService {
         // 如果状态从非Active变为Active（审核通过），发送订阅者通知
         if old_package.status != crate::models::PackageStatus::Active && 
            updated_package.status == crate::models::PackageStatus::Active {
-            if let (Some(sub_repo), Some(email_srv_arc)) = (&self.subscription_repo, &self.email_service) {
-                if let Some(cat_id) = updated_package.category_id {
-                    if let Ok(emails) = sub_repo.get_subscribed_emails(cat_id).await {
-                        let es = email_srv_arc.read().await;
-                        let resource_url = std::env::var("FRONTEND_URL").unwrap_or_else(|_| "http://localhost:5173".to_string());
-                        let resource_link = format!("{}/resource/{}", resource_url, updated_package.id);
-                        
-                        for email in emails {
-                            if let Err(e) = es.send_resource_notification(
-                                &email, 
-                                &updated_package.name, 
-                                &updated_package.description.as_deref().unwrap_or(""), 
-                                &resource_link
-                            ).await {
-                                log::error!("发送订阅者通知邮件失败: {}", e);
-                            } else {
-                                log::info!("成功发送订阅者通知邮件: {} -> {}", updated_package.name, email);
-                            }
-                        }
-                    }
-                }
-            }
-
             // 审核通过 -> 给作者发送站内通知
-            if let (Some(user_repo), Some(notify)) = (&self.user_repo, &self.notification_service) {
-                if let Ok(Some(author_user)) = user_repo.find_by_username(&updated_package.author).await {
+            if let Some(notify) = &self.notification_service {
+                if let Some(author_user) = self.find_owner(&updated_package).await {
                     let link = format!("/resource/{}", updated_package.id);
                     let title = "资源审核通过";
                     let content = format!("您的资源《{}》已通过审核", updated_package.name);
@@ -261,7 +363,8 @@ impl PackageService {
                 }
             }
 
-            // 站内通知：分类订阅者（不含作者）
+            // 分类订阅者通知（不含作者），按订阅者偏好投递站内通知或邮件
+            let mut subscribers_notified = Vec::new();
             if let (Some(sub_repo), Some(notify)) = (&self.subscription_repo, &self.notification_service) {
                 if let Some(cat_id) = updated_package.category_id {
                     if let Ok(user_ids) = sub_repo.get_subscribed_user_ids(cat_id).await {
@@ -269,13 +372,37 @@ impl PackageService {
                         for uid in user_ids {
                             let title = "订阅更新";
                             let content = format!("您订阅的分类有新资源：《{}》", updated_package.name);
-                            if let Err(e) = notify.notify(uid, title, &content, Some(&link), Some("CategoryUpdate"), Some("Package"), Some(updated_package.id)).await {
-                                log::error!("发送订阅站内通知失败: {}", e);
+                            match notify.notify(uid, title, &content, Some(&link), Some("CategoryUpdate"), Some("Package"), Some(updated_package.id)).await {
+                                Ok(_) => subscribers_notified.push(uid),
+                                Err(e) => log::error!("发送订阅站内通知失败: {}", e),
                             }
                         }
                     }
                 }
             }
+
+            // 所有者的粉丝通知（已收到分类订阅通知的不再重复），同一资源只通知一次
+            if let (Some(owner_id), Some(notify)) = (updated_package.owner_id, &self.notification_service) {
+                let owner_name = match self.find_owner(&updated_package).await {
+                    Some(u) => u.nickname.unwrap_or(u.username),
+                    None => updated_package.author.clone(),
+                };
+                let place = format!("资源《{}》", updated_package.name);
+                let link = format!("/resource/{}", updated_package.id);
+                let source = MentionSource {
+                    actor_id: owner_id,
+                    actor_name: &owner_name,
+                    place: &place,
+                    link: &link,
+                    related_type: "Package",
+                    related_id: updated_package.id,
+                };
+                if let Err(e) = notify.notify_followers(&source, &subscribers_notified).await {
+                    log::error!("发送资源粉丝通知失败: {}", e);
+                }
+            }
+
+            self.record_activity(None, "Approve", updated_package.id).await;
         }
         
         // 如果状态变为Rejected（审核拒绝），删除存储文件并发送通知
@@ -306,8 +433,8 @@ impl PackageService {
             }
 
             // 审核拒绝 -> 给作者发送站内通知
-            if let (Some(user_repo), Some(notify)) = (&self.user_repo, &self.notification_service) {
-                if let Ok(Some(author_user)) = user_repo.find_by_username(&updated_package.author).await {
+            if let Some(notify) = &self.notification_service {
+                if let Some(author_user) = self.find_owner(&updated_package).await {
                     let title = "资源审核未通过";
                     let content = format!(
                         "您的资源《{}》未通过审核。{}",
@@ -334,12 +461,48 @@ impl PackageService {
         Ok(updated_package)
     }
 
-    pub async fn delete_package(&self, package_id: i32) -> Result<()> {
+    /// 将资源受追踪字段恢复为 after 中的取值（为空的字段同样恢复为空），编辑历史在同一事务中写入，返回新版本号
+    pub async fn restore_tracked_fields(
+        &self,
+        package_id: i32,
+        after: &serde_json::Value,
+        revision: &PendingRevision,
+    ) -> Result<Option<i32>> {
+        let before = self
+            .package_repo
+            .find_by_id(package_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("绳包不存在"))?;
+        let mut package = before.clone();
+        let text = |key: &str| after[key].as_str().map(str::to_string);
+        let list = |key: &str| serde_json::from_value::<Vec<String>>(after[key].clone()).ok();
+        if let Some(name) = text("name") {
+            package.name = name;
+        }
+        package.version = text("version");
+        package.description = text("description");
+        package.category_id = after["category_id"].as_i64().map(|id| id as i32);
+        package.tags = list("tags");
+        package.requirements = list("requirements");
+        package.updated_at = Utc::now();
+        self.save_package(&before, &mut package, Some(revision)).await
+    }
+
+    /// 删除资源：移入回收站，文件目录一并移到回收站目录（不再对外提供下载）
+    pub async fn delete_package(&self, package_id: i32, deleted_by: i32) -> Result<()> {
         // 先获取包信息，用于记录
         let package = self.package_repo.find_by_id(package_id).await?;
         
-        // 删除包
-        self.package_repo.delete_package(package_id).await?;
+        if !self.package_repo.soft_delete_package(package_id, deleted_by).await? {
+            return Err(anyhow::anyhow!("资源不存在"));
+        }
+        if let Err(e) = self.move_files_to_recycle(package_id).await {
+            // 文件仍在原目录，恢复资源状态，避免记录在回收站而文件对外可见
+            if let Err(undo) = self.package_repo.undo_soft_delete_package(package_id).await {
+                log::error!("资源 {} 恢复删除前状态失败: {}", package_id, undo);
+            }
+            return Err(anyhow::anyhow!("资源文件目录移入回收站失败: {}", e));
+        }
         
         // 记录资源删除操作
         if let Some(system_repo) = &self.system_repo {
@@ -365,6 +528,57 @@ impl PackageService {
         Ok(())
     }
 
+    async fn move_files_to_recycle(&self, package_id: i32) -> Result<()> {
+        let mut storage = PackageStorageService::get_instance(&self.db_path).await?;
+        let moved = storage.recycle_package_folder(package_id).await?;
+        self.rewrite_moved_files(&mut storage, package_id, moved).await
+    }
+
+    /// 从回收站恢复后将文件目录移回当前分类下
+    pub async fn restore_files_from_recycle(&self, package_id: i32) -> Result<()> {
+        let mut storage = PackageStorageService::get_instance(&self.db_path).await?;
+        let moved = storage.restore_package_folder(package_id).await?;
+        self.rewrite_moved_files(&mut storage, package_id, moved).await
+    }
+
+    /// 目录移动后改写文件地址，改写失败时把目录移回原处
+    async fn rewrite_moved_files(
+        &self,
+        storage: &mut PackageStorageService,
+        package_id: i32,
+        moved: Option<(String, String)>,
+    ) -> Result<()> {
+        let Some(moved) = moved else { return Ok(()) };
+        if let Err(e) = self.rewrite_file_dir(package_id, &moved.0, &moved.1).await {
+            if let Err(back) = storage.move_package_folder_back(&moved).await {
+                log::error!("资源 {} 文件目录移回 {} 失败: {}", package_id, moved.0, back);
+            }
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// 彻底删除资源及其文件目录
+    pub async fn purge_package(&self, package_id: i32) -> Result<()> {
+        // 分类目录名依赖资源记录，先删文件再删记录
+        let mut storage = PackageStorageService::get_instance(&self.db_path).await?;
+        if let Err(e) = storage.purge_package_folder(package_id).await {
+            log::warn!("删除资源 {} 的文件目录失败: {}", package_id, e);
+        }
+        self.package_repo.purge_package(package_id).await
+    }
+
+    /// 文件目录移动后，改写资源中指向原目录的文件地址
+    pub async fn rewrite_file_dir(&self, package_id: i32, from_dir: &str, to_dir: &str) -> Result<()> {
+        let mut package = self
+            .package_repo
+            .find_by_id(package_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("资源不存在"))?;
+        Self::rewrite_dir(&mut package, from_dir, to_dir);
+        self.package_repo.update_file_urls(&package).await
+    }
+
     pub async fn download_package(&self, package_id: i32) -> Result<String> {
         // 首先检查包是否存在
         let exists = self.package_repo.check_package_exists(package_id).await?;
@@ -377,7 +591,6 @@ impl PackageService {
         
         // 通过本地存储服务获取动态下载链接
         let download_url = if file_path.starts_with("/uploads/") || file_path.starts_with("/image/") {
-            use crate::services::package_storage_service::PackageStorageService;
             let mut storage_service = PackageStorageService::new("data.db")?;
             storage_service.get_package_download_url(&file_path).await?
         } else {
@@ -548,6 +761,25 @@ impl PackageService {
 
     pub async fn like_package(&self, user_id: i32, package_id: i32) -> anyhow::Result<i32> {
         let cnt = self.package_repo.like_package(user_id, package_id).await?;
+
+        // 通知作者（不通知自己点赞）
+        if let (Some(user_repo), Some(notify)) = (&self.user_repo, &self.notification_service) {
+            if let Ok(Some(package)) = self.package_repo.find_by_id(package_id).await {
+                if let (Some(author), Ok(Some(liker))) = (
+                    self.find_owner(&package).await,
+                    user_repo.find_by_id(user_id).await,
+                ) {
+                    if author.id != user_id {
+                        let link = format!("/resource/{}", package_id);
+                        let content = format!("{} 赞了您的资源《{}》", liker.nickname.as_deref().unwrap_or(&liker.username), package.name);
+                        if let Err(e) = notify.by_actor(user_id).notify(author.id, "收到点赞", &content, Some(&link), Some("PackageLiked"), Some("Package"), Some(package_id)).await {
+                            log::error!("发送点赞通知失败: {}", e);
+                        }
+                    }
+                }
+            }
+        }
+        self.record_activity(Some(user_id), "Like", package_id).await;
         Ok(cnt)
     }
 
@@ -612,6 +844,11 @@ impl PackageService {
             "DELETE FROM package_favorites WHERE user_id = ? AND package_id = ?",
             params![user_id, package_id],
         )?;
+        // 取消收藏后同时移出收藏夹
+        conn.execute(
+            "DELETE FROM favorite_collection_items WHERE user_id = ? AND target_type = 'Package' AND target_id = ?",
+            params![user_id, package_id],
+        )?;
         let cnt: i32 = conn.query_row(
             "SELECT COUNT(*) FROM package_favorites WHERE package_id = ?",
             params![package_id],
@@ -641,8 +878,8 @@ impl PackageService {
         Ok(is_liked)
     }
 
-    pub async fn record_view(&self, package_id: i32, user_id: Option<i32>, ip_address: Option<String>, user_agent: Option<String>) -> anyhow::Result<()> {
-        self.package_repo.record_view(package_id, user_id, ip_address, user_agent).await?;
+    pub async fn record_view(&self, package_id: i32, user_id: Option<i32>, ip_address: Option<String>, user_agent: Option<String>, referrer: Option<String>) -> anyhow::Result<()> {
+        self.package_repo.record_view(package_id, user_id, ip_address, user_agent, referrer).await?;
         Ok(())
     }
 
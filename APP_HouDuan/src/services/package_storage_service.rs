@@ -671,6 +671,111 @@ impl PackageStorageService {
         Ok((category_name, package.name))
     }
     
+    /// 资源目录名中的分类部分；无分类或分类不存在时为默认分类
+    pub async fn category_folder_name(&self, category_id: Option<i32>) -> String {
+        match category_id {
+            Some(cat_id) => self.get_category_name(cat_id).await.unwrap_or_else(|_| "默认分类".to_string()),
+            None => "默认分类".to_string(),
+        }
+    }
+
+    /// 资源当前的文件目录 /结绳社区/资源/{分类}/{资源id}：按资源已保存的文件地址定位，
+    /// 分类改名或与其他分类重名后仍能找到原目录；没有文件地址时按当前分类名
+    async fn current_package_folder(&self, package_id: i32) -> Result<String> {
+        use rusqlite::{Connection, OptionalExtension};
+
+        let conn = Connection::open(&self.db_path)
+            .map_err(|e| anyhow!("打开数据库连接失败: {}", e))?;
+        let (urls, category_name): (String, Option<String>) = conn
+            .query_row(
+                "SELECT COALESCE(p.file_url, '') || ' ' || COALESCE(p.cover_image, '') || ' ' || \
+                        COALESCE(p.screenshots, '') || ' ' || COALESCE(p.included_files, ''), c.name \
+                 FROM packages p LEFT JOIN categories c ON p.category_id = c.id WHERE p.id = ?",
+                [package_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?
+            .ok_or_else(|| anyhow!("资源不存在: ID={}", package_id))?;
+        let prefix = format!("{}/资源/", self.storage_base_path);
+        let suffix = format!("/{}/", package_id);
+        let stored = urls.match_indices(&prefix).find_map(|(i, _)| {
+            let rest = &urls[i + prefix.len()..];
+            let category = &rest[..rest.find(&suffix)?];
+            (!category.is_empty() && !category.contains(['/', '"'])).then(|| category.to_string())
+        });
+        let folder = stored.or(category_name).unwrap_or_else(|| "默认分类".to_string());
+        Ok(format!("{}{}/{}", prefix, folder, package_id))
+    }
+
+    /// 资源更换分类前，将当前文件目录移到目标分类下；
+    /// 返回 (原目录, 新目录) 供调用方改写文件地址，目录无需移动时返回 None
+    pub async fn relocate_package_folder(&mut self, package_id: i32, to_category_id: Option<i32>) -> Result<Option<(String, String)>> {
+        self.ensure_storage_ready().await?;
+        let from_dir = self.current_package_folder(package_id).await?;
+        let to_name = self.category_folder_name(to_category_id).await;
+        let to_dir = format!("{}/资源/{}/{}", self.storage_base_path, to_name, package_id);
+        if from_dir == to_dir {
+            return Ok(None);
+        }
+        if !self.storage_service.move_folder(&from_dir, &to_dir).await? {
+            log::info!("📁 资源 {} 没有需要移动的文件目录", package_id);
+            return Ok(None);
+        }
+        log::info!("📁 资源 {} 文件目录已移动: {} -> {}", package_id, from_dir, to_dir);
+        Ok(Some((from_dir, to_dir)))
+    }
+
+    /// 撤销一次目录移动（后续数据库更新失败时使用）
+    pub async fn move_package_folder_back(&mut self, (from_dir, to_dir): &(String, String)) -> Result<()> {
+        self.ensure_storage_ready().await?;
+        self.storage_service.move_folder(to_dir, from_dir).await?;
+        Ok(())
+    }
+
+    /// 回收站目录：以 . 开头，静态文件服务不对外提供
+    fn recycle_folder(&self, package_id: i32) -> String {
+        format!("/.recycle{}/资源/{}", self.storage_base_path, package_id)
+    }
+
+    /// 资源移入回收站：将当前文件目录移到回收站目录；
+    /// 返回 (原目录, 新目录) 供调用方改写文件地址，没有文件目录时返回 None
+    pub async fn recycle_package_folder(&mut self, package_id: i32) -> Result<Option<(String, String)>> {
+        self.ensure_storage_ready().await?;
+        let from_dir = self.current_package_folder(package_id).await?;
+        let to_dir = self.recycle_folder(package_id);
+        if !self.storage_service.move_folder(&from_dir, &to_dir).await? {
+            return Ok(None);
+        }
+        log::info!("🗑️ 资源 {} 文件目录已移入回收站: {} -> {}", package_id, from_dir, to_dir);
+        Ok(Some((from_dir, to_dir)))
+    }
+
+    /// 从回收站恢复资源：文件目录移回当前分类下
+    pub async fn restore_package_folder(&mut self, package_id: i32) -> Result<Option<(String, String)>> {
+        self.ensure_storage_ready().await?;
+        let category_name = self.get_package_category_name(package_id).await?;
+        let from_dir = self.recycle_folder(package_id);
+        let to_dir = format!("{}/资源/{}/{}", self.storage_base_path, category_name, package_id);
+        if !self.storage_service.move_folder(&from_dir, &to_dir).await? {
+            return Ok(None);
+        }
+        log::info!("♻️ 资源 {} 文件目录已从回收站恢复: {} -> {}", package_id, from_dir, to_dir);
+        Ok(Some((from_dir, to_dir)))
+    }
+
+    /// 彻底删除资源的文件目录（回收站目录与当前文件目录）
+    pub async fn purge_package_folder(&mut self, package_id: i32) -> Result<()> {
+        self.ensure_storage_ready().await?;
+        let recycle_dir = self.recycle_folder(package_id);
+        let live_dir = self.current_package_folder(package_id).await?;
+        for dir in [recycle_dir, live_dir] {
+            if self.storage_service.delete_folder(&dir).await? {
+                log::info!("🗑️ 资源 {} 文件目录已删除: {}", package_id, dir);
+            }
+        }
+        Ok(())
+    }
+
     /// 获取包的分类名称
     async fn get_package_category_name(&self, package_id: i32) -> Result<String> {
         // 从数据库获取包信息
@@ -0,0 +1,292 @@
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use std::collections::HashMap;
+
+use crate::models::points::{
+    AdjustPointsRequest, LedgerQuery, LevelProgress, PointLedgerEntry, PointRule, UpdatePointRuleRequest, UserLevel,
+    LEDGER_ADMIN_ADJUST, LEDGER_RESTORE, LEDGER_REVOKE, RULE_CHECK_IN, RULE_COMMENT_POSTED, RULE_CONTENT_FEATURED, RULE_CONTENT_REMOVED,
+    RULE_CHECK_IN_MAKEUP, RULE_LIKE_RECEIVED, RULE_PACKAGE_APPROVED, RULE_POST_PUBLISHED,
+};
+use crate::repositories::points_repo::{PointGrant, PointsRepository};
+use crate::repositories::user_repo::UserRepository;
+use crate::services::notification_service::NotificationService;
+use crate::utils::error::ServiceError;
+use crate::utils::time::{community_offset, local_now};
+
+#[derive(Clone)]
+pub struct PointsService {
+    points_repo: PointsRepository,
+    user_repo: UserRepository,
+    notification_service: Option<NotificationService>,
+}
+
+impl PointsService {
+    pub fn new(points_repo: PointsRepository, user_repo: UserRepository) -> Self {
+        Self { points_repo, user_repo, notification_service: None }
+    }
+
+    pub fn with_notification_service(mut self, service: NotificationService) -> Self {
+        self.notification_service = Some(service);
+        self
+    }
+
+    /// 记录到一条用户行为后按规则发放经验与星星（与成就共用同一组行为）
+    pub async fn on_action(
+        &self,
+        actor_id: Option<i32>,
+        action_type: &str,
+        target_type: Option<&str>,
+        target_id: Option<i32>,
+    ) -> Result<Option<PointLedgerEntry>> {
+        let (user_id, rule, dedupe_key) = match (action_type, target_type) {
+            ("CheckIn", _) => (actor_id, RULE_CHECK_IN, format!("{}:{}", RULE_CHECK_IN, local_now().format("%Y-%m-%d"))),
+            ("Comment", _) => (actor_id, RULE_COMMENT_POSTED, format!("{}:{}", RULE_COMMENT_POSTED, target_id.unwrap_or_default())),
+            ("Like", Some(t @ ("Package" | "Post"))) => {
+                let owner_id = self.target_owner(target_type, target_id).await?;
+                // 给自己点赞不计分
+                if owner_id.is_none() || owner_id == actor_id {
+                    return Ok(None);
+                }
+                let key = format!("{}:{}:{}:{}", RULE_LIKE_RECEIVED, t, target_id.unwrap_or_default(), actor_id.unwrap_or_default());
+                (owner_id, RULE_LIKE_RECEIVED, key)
+            }
+            ("Feature", Some(t @ "Post")) => {
+                (self.target_owner(target_type, target_id).await?, RULE_CONTENT_FEATURED, format!("{}:{}:{}", RULE_CONTENT_FEATURED, t, target_id.unwrap_or_default()))
+            }
+            ("Approve", Some(t @ "Package")) => {
+                (self.target_owner(target_type, target_id).await?, RULE_PACKAGE_APPROVED, format!("{}:{}:{}", RULE_PACKAGE_APPROVED, t, target_id.unwrap_or_default()))
+            }
+            ("Approve", Some(t @ "Post")) => {
+                (self.target_owner(target_type, target_id).await?, RULE_POST_PUBLISHED, format!("{}:{}:{}", RULE_POST_PUBLISHED, t, target_id.unwrap_or_default()))
+            }
+            _ => return Ok(None),
+        };
+        let Some(user_id) = user_id else { return Ok(None) };
+        // 评论的积分关联到评论本身，其他奖励关联到被操作的内容
+        self.apply_rule(user_id, rule, target_type, target_id, &dedupe_key).await
+    }
+
+    async fn target_owner(&self, target_type: Option<&str>, target_id: Option<i32>) -> Result<Option<i32>> {
+        match (target_type, target_id) {
+            (Some(t), Some(id)) => self.user_repo.find_content_owner(t, id).await,
+            _ => Ok(None),
+        }
+    }
+
+    /// 按规则发放：规则停用或已达每日上限时跳过
+    pub async fn apply_rule(
+        &self,
+        user_id: i32,
+        action: &str,
+        related_type: Option<&str>,
+        related_id: Option<i32>,
+        dedupe_key: &str,
+    ) -> Result<Option<PointLedgerEntry>> {
+        let Some(rule) = self.points_repo.get_rule(action).await? else { return Ok(None) };
+        if !rule.enabled || (rule.xp == 0 && rule.stars == 0) {
+            return Ok(None);
+        }
+        if rule.daily_cap > 0 {
+            let today_start = local_now().date_naive().and_hms_opt(0, 0, 0).expect("valid time");
+            let since = community_offset().from_local_datetime(&today_start).single()
+                .map(|d| d.with_timezone(&Utc))
+                .unwrap_or_else(Utc::now);
+            if self.points_repo.count_since(user_id, action, since).await? >= rule.daily_cap {
+                return Ok(None);
+            }
+        }
+        let grant = PointGrant {
+            user_id,
+            action,
+            xp: rule.xp,
+            stars: rule.stars,
+            reason: &rule.name,
+            related_type,
+            related_id,
+            dedupe_key: Some(dedupe_key),
+            // 扣分规则（如内容被删除的处罚）最多扣到 0
+            cap_at_balance: true,
+        };
+        self.grant(&grant).await
+    }
+
+    /// 记账，并在升级时通知用户
+    async fn grant(&self, grant: &PointGrant<'_>) -> Result<Option<PointLedgerEntry>> {
+        let levels = self.points_repo.list_levels().await?;
+        let before = self.level_progress_with(grant.user_id, &levels).await?;
+        let entry = self.points_repo.grant(grant).await?;
+        if entry.as_ref().is_some_and(|e| e.xp > 0) {
+            let after = self.level_progress_with(grant.user_id, &levels).await?;
+            if after.level > before.level {
+                if let Some(notify) = &self.notification_service {
+                    let content = format!("恭喜您升级到 Lv.{} {}", after.level, after.title);
+                    let link = format!("/user/{}", grant.user_id);
+                    if let Err(e) = notify.notify(grant.user_id, "等级提升", &content, Some(&link), Some("LevelUp"), Some("User"), Some(grant.user_id)).await {
+                        log::error!("发送升级通知失败: {}", e);
+                    }
+                }
+            }
+        }
+        Ok(entry)
+    }
+
+    /// 补签并按补签规则扣除星星：次数校验、签到记录与扣费在同一事务中完成，星星不足时整体失败
+    pub async fn make_up_check_in(&self, user_id: i32, date: NaiveDate, month_start: DateTime<Utc>, monthly_limit: i64) -> Result<Option<PointLedgerEntry>> {
+        let rule = self
+            .points_repo
+            .get_rule(RULE_CHECK_IN_MAKEUP)
+            .await?
+            .filter(|r| r.enabled)
+            .ok_or_else(|| ServiceError::bad_request("补签功能未开启"))?;
+        let key = format!("{}:{}", RULE_CHECK_IN_MAKEUP, date.format("%Y-%m-%d"));
+        let grant = PointGrant {
+            user_id,
+            action: RULE_CHECK_IN_MAKEUP,
+            xp: rule.xp,
+            stars: rule.stars,
+            reason: &rule.name,
+            related_type: None,
+            related_id: None,
+            dedupe_key: Some(&key),
+            cap_at_balance: false,
+        };
+        let charge = (rule.xp != 0 || rule.stars != 0).then_some(&grant);
+        self.points_repo.make_up_check_in(user_id, date, month_start, monthly_limit, charge).await
+    }
+
+    /// 内容被删除：冲销该内容带来的全部奖励；由管理员/元老删除他人内容时再按处罚规则扣分
+    pub async fn on_content_removed(&self, target_type: &str, target_id: i32, owner_id: i32, removed_by: Option<i32>) -> Result<()> {
+        let (xp, stars) = self.points_repo.sum_content_rewards(owner_id, target_type, target_id).await?;
+        if xp != 0 || stars != 0 {
+            let key = format!("{}:{}:{}", LEDGER_REVOKE, target_type, target_id);
+            let grant = PointGrant {
+                user_id: owner_id,
+                action: LEDGER_REVOKE,
+                xp: -xp,
+                stars: -stars,
+                reason: "内容删除，收回奖励",
+                related_type: Some(target_type),
+                related_id: Some(target_id),
+                dedupe_key: Some(&key),
+                cap_at_balance: true,
+            };
+            self.points_repo.grant(&grant).await?;
+        }
+        if removed_by.is_some_and(|id| id != owner_id) {
+            let key = format!("{}:{}:{}", RULE_CONTENT_REMOVED, target_type, target_id);
+            self.apply_rule(owner_id, RULE_CONTENT_REMOVED, Some(target_type), Some(target_id), &key).await?;
+        }
+        Ok(())
+    }
+
+    /// 内容从回收站恢复：退回删除时收回的奖励与处罚
+    pub async fn on_content_restored(&self, target_type: &str, target_id: i32, owner_id: i32) -> Result<()> {
+        let (xp, stars) = self.points_repo.sum_content_removals(owner_id, target_type, target_id).await?;
+        if xp != 0 || stars != 0 {
+            let grant = PointGrant {
+                user_id: owner_id,
+                action: LEDGER_RESTORE,
+                xp: -xp,
+                stars: -stars,
+                reason: "内容恢复，退回积分",
+                related_type: Some(target_type),
+                related_id: Some(target_id),
+                dedupe_key: None,
+                cap_at_balance: true,
+            };
+            self.points_repo.grant(&grant).await?;
+        }
+        self.points_repo.release_content_removals(owner_id, target_type, target_id).await
+    }
+
+    /// 内容作者，供删除前查询
+    pub async fn content_owner(&self, target_type: &str, target_id: i32) -> Result<Option<i32>> {
+        self.user_repo.find_content_owner(target_type, target_id).await
+    }
+
+    async fn level_progress_with(&self, user_id: i32, levels: &[UserLevel]) -> Result<LevelProgress> {
+        let (experience, stars) = self.points_repo.get_balances(&[user_id]).await?
+            .remove(&user_id)
+            .ok_or_else(|| ServiceError::not_found("用户不存在"))?;
+        Ok(LevelProgress::compute(experience, stars, levels))
+    }
+
+    pub async fn level_progress(&self, user_id: i32) -> Result<LevelProgress> {
+        let levels = self.points_repo.list_levels().await?;
+        self.level_progress_with(user_id, &levels).await
+    }
+
+    /// 批量查询等级（排行榜、列表展示）
+    pub async fn level_progress_map(&self, user_ids: &[i32]) -> Result<HashMap<i32, LevelProgress>> {
+        let levels = self.points_repo.list_levels().await?;
+        Ok(self.points_repo.get_balances(user_ids).await?
+            .into_iter()
+            .map(|(id, (experience, stars))| (id, LevelProgress::compute(experience, stars, &levels)))
+            .collect())
+    }
+
+    pub async fn ledger(&self, user_id: i32, query: &LedgerQuery) -> Result<(Vec<PointLedgerEntry>, i64)> {
+        let page = query.page.unwrap_or(1).max(1);
+        let page_size = query.page_size.unwrap_or(20).clamp(1, 100);
+        self.points_repo.list_ledger(user_id, page, page_size).await
+    }
+
+    // 管理员：规则、等级与手动调整
+
+    pub async fn get_rule(&self, action: &str) -> Result<Option<PointRule>> {
+        self.points_repo.get_rule(action).await
+    }
+
+    pub async fn list_rules(&self) -> Result<Vec<PointRule>> {
+        self.points_repo.list_rules().await
+    }
+
+    pub async fn update_rule(&self, action: &str, req: UpdatePointRuleRequest) -> Result<PointRule> {
+        if req.daily_cap.is_some_and(|c| c < 0) {
+            return Err(ServiceError::bad_request("每日上限不能为负数"));
+        }
+        if !self.points_repo.update_rule(action, &req).await? {
+            return Err(ServiceError::not_found("积分规则不存在"));
+        }
+        self.points_repo.get_rule(action).await?.ok_or_else(|| ServiceError::not_found("积分规则不存在"))
+    }
+
+    pub async fn list_levels(&self) -> Result<Vec<UserLevel>> {
+        self.points_repo.list_levels().await
+    }
+
+    /// 整体替换等级表：等级号与经验门槛都必须严格递增，且第一级从 0 经验开始
+    pub async fn update_levels(&self, mut levels: Vec<UserLevel>) -> Result<Vec<UserLevel>> {
+        levels.sort_by_key(|l| l.level);
+        if levels.first().map(|l| l.min_xp) != Some(0) {
+            return Err(ServiceError::bad_request("第一级所需经验必须为 0"));
+        }
+        if levels.windows(2).any(|w| w[1].min_xp <= w[0].min_xp || w[1].level == w[0].level) {
+            return Err(ServiceError::bad_request("等级与所需经验必须严格递增"));
+        }
+        if levels.iter().any(|l| l.title.trim().is_empty()) {
+            return Err(ServiceError::bad_request("等级称号不能为空"));
+        }
+        self.points_repo.replace_levels(&levels).await?;
+        Ok(levels)
+    }
+
+    pub async fn adjust(&self, user_id: i32, req: AdjustPointsRequest) -> Result<PointLedgerEntry> {
+        let reason = req.reason.trim();
+        if reason.is_empty() {
+            return Err(ServiceError::bad_request("请填写调整原因"));
+        }
+        let grant = PointGrant {
+            user_id,
+            action: LEDGER_ADMIN_ADJUST,
+            xp: req.xp.unwrap_or(0),
+            stars: req.stars.unwrap_or(0),
+            reason,
+            related_type: None,
+            related_id: None,
+            dedupe_key: None,
+            cap_at_balance: false,
+        };
+        self.grant(&grant).await?.ok_or_else(|| ServiceError::not_found("用户不存在"))
+    }
+}
@@ -1,19 +1,28 @@
-use rusqlite::{Connection, Result as SqliteResult, params};
+use rusqlite::{Connection, OptionalExtension, Result as SqliteResult, params};
 use crate::models::{Post, CreatePostRequest, UpdatePostRequest, PostQueryParams, PostListResponse, Tag};
 use crate::repositories::user_repo::UserRepository;
-use chrono::{DateTime, Utc};
+use chrono::Utc;
+use crate::models::notification::MentionSource;
 use crate::services::notification_service::NotificationService;
-use serde_json;
+use crate::services::achievement_service::AchievementService;
+use crate::services::points_service::PointsService;
+use crate::repositories::tag_repo::{self, TagTarget};
+use serde_json::{self, json};
+use crate::models::edit_revision::PendingRevision;
+use crate::repositories::edit_revision_repo::EditRevisionRepository;
+use crate::utils::time::parse_db_time;
 
 #[derive(Clone)]
 pub struct PostService {
     db_path: String,
     notifier: Option<NotificationService>,
+    points: Option<PointsService>,
+    achievements: Option<AchievementService>,
 }
 
 impl PostService {
     pub fn new(db_path: String) -> Self {
-        Self { db_path, notifier: None }
+        Self { db_path, notifier: None, points: None, achievements: None }
     }
 
     pub fn db_path(&self) -> &str { &self.db_path }
@@ -23,6 +32,30 @@ impl PostService {
         self
     }
 
+    pub fn with_points_service(mut self, points: PointsService) -> Self {
+        self.points = Some(points);
+        self
+    }
+
+    pub fn with_achievement_service(mut self, achievements: AchievementService) -> Self {
+        self.achievements = Some(achievements);
+        self
+    }
+
+    /// 帖子相关行为（点赞/加精/审核通过）后发放积分并评估成就，失败只记录日志
+    pub async fn record_activity(&self, actor_id: Option<i32>, action_type: &str, post_id: i32) {
+        if let Some(points) = &self.points {
+            if let Err(e) = points.on_action(actor_id, action_type, Some("Post"), Some(post_id)).await {
+                log::error!("发放积分失败: {}", e);
+            }
+        }
+        if let Some(achievements) = &self.achievements {
+            if let Err(e) = achievements.on_action(actor_id, action_type, Some("Post"), Some(post_id)).await {
+                log::error!("评估成就失败: {}", e);
+            }
+        }
+    }
+
     // 创建帖子
     pub async fn create_post(&self, req: CreatePostRequest, author_id: i32) -> SqliteResult<i32> {
         let conn = Connection::open(&self.db_path)?;
@@ -95,9 +128,82 @@ impl PostService {
         Ok(post_id)
     }
 
+    /// 帖子审核通过后通知正文中被 @ 的用户（待审核内容不对外可见，因此不在创建时通知）
+    pub async fn notify_post_mentions(&self, post_id: i32) -> SqliteResult<()> {
+        let notify = match &self.notifier {
+            Some(n) => n,
+            None => return Ok(()),
+        };
+        let conn = Connection::open(&self.db_path)?;
+        let (author_id, author_name, title, content): (i32, Option<String>, String, String) = conn.query_row(
+            "SELECT author_id, author_name, title, content FROM posts WHERE id = ?",
+            params![post_id],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?))
+        )?;
+        let author_name = author_name.unwrap_or_default();
+        let place = format!("帖子《{}》", title);
+        let link = format!("/post/{}", post_id);
+        let source = MentionSource {
+            actor_id: author_id,
+            actor_name: &author_name,
+            place: &place,
+            link: &link,
+            related_type: "Post",
+            related_id: post_id,
+        };
+        if let Err(e) = notify.notify_mentions(&source, &format!("{}\n{}", title, content), &[]).await {
+            log::error!("发送帖子提及通知失败: {}", e);
+        }
+        Ok(())
+    }
+
+    /// 帖子首次审核通过（真正对外可见）时通知作者的粉丝
+    pub async fn notify_post_followers(&self, post_id: i32) -> SqliteResult<()> {
+        let notify = match &self.notifier {
+            Some(n) => n,
+            None => return Ok(()),
+        };
+        let conn = Connection::open(&self.db_path)?;
+        let (author_id, author_name, title): (i32, Option<String>, String) = conn.query_row(
+            "SELECT author_id, author_name, title FROM posts WHERE id = ?",
+            params![post_id],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?))
+        )?;
+        let author_name = author_name.unwrap_or_default();
+        let place = format!("帖子《{}》", title);
+        let link = format!("/post/{}", post_id);
+        let source = MentionSource {
+            actor_id: author_id,
+            actor_name: &author_name,
+            place: &place,
+            link: &link,
+            related_type: "Post",
+            related_id: post_id,
+        };
+        if let Err(e) = notify.notify_followers(&source, &[]).await {
+            log::error!("发送帖子粉丝通知失败: {}", e);
+        }
+        Ok(())
+    }
+
     // 更新帖子
     pub async fn update_post(&self, post_id: i32, req: UpdatePostRequest) -> SqliteResult<bool> {
-        let conn = Connection::open(&self.db_path)?;
+        self.update_post_recorded(post_id, req, None).await
+    }
+
+    /// 更新帖子；传入编辑记录时，编辑历史与更新在同一事务中写入
+    pub async fn update_post_recorded(&self, post_id: i32, req: UpdatePostRequest, revision: Option<&PendingRevision>) -> SqliteResult<bool> {
+        let mut conn = Connection::open(&self.db_path)?;
+        // 更新后的受追踪字段：在编辑前快照上覆盖本次修改的字段
+        let after = revision.map(|revision| {
+            let mut after = revision.before.clone();
+            if let Some(title) = &req.title { after["title"] = json!(title); }
+            if let Some(content) = &req.content { after["content"] = json!(content); }
+            if let Some(code_snippet) = &req.code_snippet { after["code_snippet"] = json!(code_snippet); }
+            if let Some(tags) = &req.tags { after["tags"] = json!(tags); }
+            after
+        });
+        let tx = conn.transaction()?;
         
         let mut updates = Vec::new();
         let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
@@ -105,9 +211,10 @@ impl PostService {
         // 记录置顶/精华变更以便稍后发通知
         let mut pin_changed: Option<bool> = None;
         let mut feat_changed: Option<bool> = None;
+        let mut published = false;
         // 读取现状
         let (old_pinned, old_featured): (bool, bool) = {
-            let row: (bool, bool) = conn.query_row("SELECT is_pinned, is_featured FROM posts WHERE id = ?", params![post_id], |r| Ok((r.get(0)?, r.get(1)?)))?;
+            let row: (bool, bool) = tx.query_row("SELECT is_pinned, is_featured FROM posts WHERE id = ?", params![post_id], |r| Ok((r.get(0)?, r.get(1)?)))?;
             row
         };
 
@@ -149,6 +256,7 @@ impl PostService {
             params.push(Box::new(status_str.clone()));
             // Published => approved；Draft => pending；其他不改
             if status_str == "Published" {
+                published = true;
                 updates.push("review_status = ?");
                 params.push(Box::new("approved".to_string()));
             } else if status_str == "Draft" {
@@ -175,17 +283,19 @@ impl PostService {
 
             let sql = format!("UPDATE posts SET {} WHERE id = ?", updates.join(", "));
             params.push(Box::new(post_id));
-            let mut stmt = conn.prepare(&sql)?;
-            stmt.execute(rusqlite::params_from_iter(params.iter().map(|p| p.as_ref())))?;
+            tx.execute(&sql, rusqlite::params_from_iter(params.iter().map(|p| p.as_ref())))?;
         }
 
         // 处理标签更新（列 + 关联表）
         if let Some(tags) = req.tags {
             let json = serde_json::to_string(&tags).unwrap_or("[]".to_string());
-            let mut stmt2 = conn.prepare("UPDATE posts SET tags = ? WHERE id = ?")?;
-            stmt2.execute(params![json, post_id])?;
-            self.update_post_tags(post_id, &tags).await?;
+            tx.execute("UPDATE posts SET tags = ? WHERE id = ?", params![json, post_id])?;
+            tag_repo::link_tags(&tx, TagTarget::Post, post_id, &tags)?;
         }
+        if let (Some(revision), Some(after)) = (revision, after.as_ref()) {
+            EditRevisionRepository::record_in(&tx, "Post", post_id, revision, after)?;
+        }
+        tx.commit()?;
 
         // 通知作者：被置顶/加精
         if pin_changed == Some(true) || feat_changed == Some(true) {
@@ -205,10 +315,38 @@ impl PostService {
                 let _ = notify.notify(author_id, "帖子状态更新", &msg, Some(&link), Some("PostFlagChanged"), Some("Post"), Some(post_id)).await;
             }
         }
+        if feat_changed == Some(true) {
+            self.record_activity(None, "Feature", post_id).await;
+        }
+        if published {
+            self.record_activity(None, "Approve", post_id).await;
+        }
 
         Ok(true)
     }
 
+    /// 将帖子受追踪字段恢复为 after 中的取值（为空的字段同样恢复为空），编辑历史在同一事务中写入，返回新版本号
+    pub async fn restore_tracked_fields(&self, post_id: i32, after: &serde_json::Value, revision: &PendingRevision) -> SqliteResult<Option<i32>> {
+        let mut conn = Connection::open(&self.db_path)?;
+        let tx = conn.transaction()?;
+        let tags: Vec<String> = serde_json::from_value(after["tags"].clone()).unwrap_or_default();
+        tx.execute(
+            "UPDATE posts SET title = ?, content = ?, code_snippet = ?, tags = ?, updated_at = ? WHERE id = ?",
+            params![
+                after["title"].as_str().unwrap_or_default(),
+                after["content"].as_str().unwrap_or_default(),
+                after["code_snippet"].as_str(),
+                serde_json::to_string(&tags).unwrap_or_else(|_| "[]".to_string()),
+                Utc::now(),
+                post_id
+            ],
+        )?;
+        tag_repo::link_tags(&tx, TagTarget::Post, post_id, &tags)?;
+        let revision_no = EditRevisionRepository::record_in(&tx, "Post", post_id, revision, after)?;
+        tx.commit()?;
+        Ok(revision_no)
+    }
+
     // 新增：检查用户是否点赞了指定帖子
     pub async fn is_post_liked_by_user(&self, user_id: i32, post_id: i32) -> SqliteResult<bool> {
         let conn = Connection::open(&self.db_path)?;
@@ -245,6 +383,11 @@ impl PostService {
             params.push(Box::new(author_id));
         }
 
+        // 回收站中的帖子只在明确查询 Deleted 状态时返回
+        if query.status.as_deref() != Some("Deleted") {
+            conditions.push("status != 'Deleted'");
+        }
+
         if let Some(status) = query.status {
             // 检查是否是审核状态查询
             if status == "pending" || status == "approved" || status == "rejected" {
@@ -318,12 +461,12 @@ impl PostService {
                     comment_count: row.get(9)?,
                     is_pinned: row.get(10)?,
                     is_featured: row.get(11)?,
-                    created_at: parse_timestamp(row.get::<_, String>(12)?),
-                    updated_at: parse_timestamp(row.get::<_, String>(13)?),
+                    created_at: parse_db_time(&row.get::<_, String>(12)?).unwrap_or_else(Utc::now),
+                    updated_at: parse_db_time(&row.get::<_, String>(13)?).unwrap_or_else(Utc::now),
                     review_status: row.get(14).ok(),
                     review_comment: row.get(15).ok(),
                     reviewer_id: row.get(16).ok(),
-                    reviewed_at: row.get::<_, Option<String>>(17).ok().flatten().map(parse_timestamp),
+                    reviewed_at: row.get::<_, Option<String>>(17).ok().flatten().and_then(|s| parse_db_time(&s)),
                     // 新增字段
                     images: {
                         if let Ok(json_str) = row.get::<_, String>(18) {
@@ -372,12 +515,12 @@ impl PostService {
                 comment_count: row.get(9)?,
                 is_pinned: row.get(10)?,
                 is_featured: row.get(11)?,
-                created_at: parse_timestamp(row.get::<_, String>(12)?),
-                updated_at: parse_timestamp(row.get::<_, String>(13)?),
+                created_at: parse_db_time(&row.get::<_, String>(12)?).unwrap_or_else(Utc::now),
+                updated_at: parse_db_time(&row.get::<_, String>(13)?).unwrap_or_else(Utc::now),
                 review_status: row.get(14).ok(),
                 review_comment: row.get(15).ok(),
                 reviewer_id: row.get(16).ok(),
-                reviewed_at: row.get::<_, Option<String>>(17).ok().flatten().map(parse_timestamp),
+                reviewed_at: row.get::<_, Option<String>>(17).ok().flatten().and_then(|s| parse_db_time(&s)),
                 // 新增字段
                 images: {
                     if let Ok(json_str) = row.get::<_, String>(18) {
@@ -404,11 +547,24 @@ impl PostService {
         }
     }
 
-    // 删除帖子
-    pub async fn delete_post(&self, post_id: i32) -> SqliteResult<bool> {
+    // 删除帖子：移入回收站，保留原状态以便恢复
+    pub async fn delete_post(&self, post_id: i32, deleted_by: i32) -> SqliteResult<bool> {
+        let conn = Connection::open(&self.db_path)?;
+        let result = conn.execute(
+            "UPDATE posts SET status_before_delete = status, status = 'Deleted', deleted_at = datetime('now'), deleted_by = ?
+             WHERE id = ? AND status != 'Deleted'",
+            params![deleted_by, post_id],
+        )?;
+        Ok(result > 0)
+    }
+
+    // 彻底删除帖子（回收站清理）
+    pub async fn purge_post(&self, post_id: i32) -> SqliteResult<bool> {
         let conn = Connection::open(&self.db_path)?;
         
         let result = conn.execute("DELETE FROM posts WHERE id = ?", params![post_id])?;
+        let _ = conn.execute("DELETE FROM rendered_content WHERE target_type = 'Post' AND target_id = ?", params![post_id]);
+        let _ = conn.execute("DELETE FROM content_revisions WHERE target_type = 'Post' AND target_id = ?", params![post_id]);
         Ok(result > 0)
     }
 
@@ -419,35 +575,19 @@ impl PostService {
         Ok(())
     }
 
-    // 添加标签到帖子
+    // 添加标签到帖子（规范化、别名替换与白名单规则见 tag_repo::link_tags）
     pub async fn add_tags_to_post(&self, post_id: i32, tag_names: &[String]) -> SqliteResult<()> {
-        let conn = Connection::open(&self.db_path)?;
-        
-        for tag_name in tag_names {
-            // 查找或创建标签
-            let tag_id = self.get_or_create_tag(&conn, tag_name)?;
-            
-            // 添加帖子标签关联
-            conn.execute(
-                "INSERT OR IGNORE INTO post_tags (post_id, tag_id, created_at) VALUES (?, ?, ?)",
-                params![post_id, tag_id, Utc::now()]
-            )?;
-        }
-        
-        Ok(())
-    }
+        let mut names = self.get_post_tags(post_id).await?
+            .into_iter()
+            .map(|t| t.name)
+            .collect::<Vec<_>>();
+        names.extend(tag_names.iter().cloned());
 
-    // 更新帖子标签
-    pub async fn update_post_tags(&self, post_id: i32, tag_names: &[String]) -> SqliteResult<()> {
-        let conn = Connection::open(&self.db_path)?;
-        
-        // 删除现有标签关联
-        conn.execute("DELETE FROM post_tags WHERE post_id = ?", params![post_id])?;
-        
-        // 添加新标签关联
-        self.add_tags_to_post(post_id, tag_names).await?;
-        
-        Ok(())
+        let mut conn = Connection::open(&self.db_path)?;
+        let tx = conn.transaction()?;
+        tag_repo::link_tags(&tx, TagTarget::Post, post_id, &names)?;
+
+        tx.commit()
     }
 
     // 获取帖子的标签
@@ -455,53 +595,20 @@ impl PostService {
         let conn = Connection::open(&self.db_path)?;
         
         let sql = "
-            SELECT t.id, t.name, t.description, t.color, t.use_count, t.created_at, t.updated_at
+            SELECT t.id, t.name, t.description, t.color, t.use_count, t.status, t.created_at, t.updated_at
             FROM tags t
             INNER JOIN post_tags pt ON t.id = pt.tag_id
             WHERE pt.post_id = ?
-            ORDER BY t.name
+            ORDER BY pt.id
         ";
         
         let tags = conn.prepare(sql)?
-            .query_map(params![post_id], |row| {
-                Ok(Tag {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    description: row.get(2)?,
-                    color: row.get(3)?,
-                    use_count: row.get(4)?,
-                    created_at: parse_timestamp(row.get::<_, String>(5)?),
-                    updated_at: parse_timestamp(row.get::<_, String>(6)?),
-                })
-            })?
+            .query_map(params![post_id], tag_repo::map_tag)?
             .collect::<Result<Vec<_>, _>>()?;
         
         Ok(tags)
     }
 
-    // 获取或创建标签
-    fn get_or_create_tag(&self, conn: &Connection, tag_name: &str) -> SqliteResult<i32> {
-        // 先查找现有标签
-        let result = conn.query_row(
-            "SELECT id FROM tags WHERE name = ?",
-            params![tag_name],
-            |row| row.get(0)
-        );
-
-        match result {
-            Ok(tag_id) => Ok(tag_id),
-            Err(rusqlite::Error::QueryReturnedNoRows) => {
-                // 创建新标签
-                let tag_id = conn.execute(
-                    "INSERT INTO tags (name, use_count, created_at, updated_at) VALUES (?, ?, ?, ?)",
-                    params![tag_name, 1, Utc::now(), Utc::now()]
-                )?;
-                Ok(tag_id as i32)
-            }
-            Err(e) => Err(e),
-        }
-    }
-
     // 解析帖子状态
     fn parse_post_status(&self, status: &str) -> crate::models::PostStatus {
         match status {
@@ -519,7 +626,7 @@ impl PostService {
             "CREATE TABLE IF NOT EXISTS post_likes (user_id INTEGER NOT NULL, post_id INTEGER NOT NULL, created_at TEXT NOT NULL DEFAULT (CURRENT_TIMESTAMP), PRIMARY KEY (user_id, post_id))",
             [],
         )?;
-        conn.execute(
+        let inserted = conn.execute(
             "INSERT OR IGNORE INTO post_likes (user_id, post_id) VALUES (?, ?)",
             params![user_id, post_id],
         )?;
@@ -528,6 +635,27 @@ impl PostService {
             params![post_id, user_id, post_id],
         )?;
         let cnt: i32 = conn.query_row("SELECT COUNT(*) FROM post_likes WHERE post_id = ?", params![post_id], |r| r.get(0))?;
+
+        // 首次点赞时通知作者（不通知自己点赞）
+        if inserted > 0 {
+            if let Some(notify) = &self.notifier {
+                let author: Option<(i32, String, String)> = conn.query_row(
+                    "SELECT p.author_id, p.title, COALESCE(u.nickname, u.username) FROM posts p, users u WHERE p.id = ? AND u.id = ?",
+                    params![post_id, user_id],
+                    |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?))
+                ).optional()?;
+                if let Some((author_id, title, liker)) = author {
+                    if author_id != user_id {
+                        let link = format!("/post/{}", post_id);
+                        let msg = format!("{} 赞了您的帖子《{}》", liker, title);
+                        let _ = notify.by_actor(user_id).notify(author_id, "收到点赞", &msg, Some(&link), Some("PostLiked"), Some("Post"), Some(post_id)).await;
+                    }
+                }
+            }
+        }
+        if inserted > 0 {
+            self.record_activity(Some(user_id), "Like", post_id).await;
+        }
         Ok(cnt)
     }
 
@@ -567,12 +695,12 @@ impl PostService {
                     comment_count: row.get(9)?,
                     is_pinned: row.get(10)?,
                     is_featured: row.get(11)?,
-                    created_at: parse_timestamp(row.get::<_, String>(12)?),
-                    updated_at: parse_timestamp(row.get::<_, String>(13)?),
+                    created_at: parse_db_time(&row.get::<_, String>(12)?).unwrap_or_else(Utc::now),
+                    updated_at: parse_db_time(&row.get::<_, String>(13)?).unwrap_or_else(Utc::now),
                     review_status: row.get(14).ok(),
                     review_comment: row.get(15).ok(),
                     reviewer_id: row.get(16).ok(),
-                    reviewed_at: row.get::<_, Option<String>>(17).ok().flatten().map(parse_timestamp),
+                    reviewed_at: row.get::<_, Option<String>>(17).ok().flatten().and_then(|s| parse_db_time(&s)),
                     // 新增字段
                     images: {
                         if let Ok(json_str) = row.get::<_, String>(18) {
@@ -595,25 +723,3 @@ impl PostService {
         Ok((posts, total))
     }
 } 
-
-// 辅助函数：尽量解析日期时间字符串，优先 RFC3339，失败则按常见 SQLite 默认格式再失败返回当前时间
-fn parse_timestamp(ts: String) -> DateTime<Utc> {
-    // 尝试 RFC3339
-    if let Ok(dt) = DateTime::parse_from_rfc3339(&ts) {
-        return dt.with_timezone(&Utc);
-    }
-
-    // 尝试 "YYYY-MM-DD HH:MM:SS" 格式（SQLite CURRENT_TIMESTAMP 默认格式）
-    if let Ok(naive) = chrono::NaiveDateTime::parse_from_str(&ts, "%Y-%m-%d %H:%M:%S") {
-        return DateTime::<Utc>::from_utc(naive, Utc);
-    }
-
-    // 尝试带毫秒的格式
-    if let Ok(naive) = chrono::NaiveDateTime::parse_from_str(&ts, "%Y-%m-%d %H:%M:%S%.f") {
-        return DateTime::<Utc>::from_utc(naive, Utc);
-    }
-
-    // 解析失败，返回当前时间，避免 panic
-    log::warn!("无法解析日期时间字符串: {}，已使用当前时间代替", ts);
-    Utc::now()
-} 
\ No newline at end of file
@@ -0,0 +1,195 @@
+use anyhow::Result;
+use chrono::{Duration as ChronoDuration, NaiveDate, TimeZone, Utc};
+use log::{info, warn};
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::models::ranking::{RankedRow, RankingBoard, RankingPage, RankingPeriod, RankingWindow};
+use crate::repositories::ranking_repo::{RankingRepository, RollupRange};
+use crate::utils::time::{community_offset, local_now};
+
+/// 汇总任务执行间隔
+const ROLLUP_INTERVAL: Duration = Duration::from_secs(600);
+/// 首次运行时回填的天数
+const BACKFILL_DAYS: i64 = 366;
+/// 历史快照保存的名次数
+const SNAPSHOT_SIZE: usize = 100;
+
+#[derive(Clone)]
+pub struct RankingService {
+    ranking_repo: RankingRepository,
+}
+
+impl RankingService {
+    pub fn new(ranking_repo: RankingRepository) -> Self {
+        Self { ranking_repo }
+    }
+
+    /// 解析周期参数，默认 all
+    pub fn resolve_period(&self, period: Option<&str>) -> Result<RankingPeriod> {
+        match period.map(str::trim).filter(|p| !p.is_empty()) {
+            Some(p) => RankingPeriod::parse(p).ok_or_else(|| anyhow::anyhow!("周期参数错误，可选 week、month、year、all")),
+            None => Ok(RankingPeriod::All),
+        }
+    }
+
+    /// 当前周期的排行（截至上次汇总），附带相对上一周期的名次变化
+    pub async fn window_ranking(
+        &self,
+        board: RankingBoard,
+        period: RankingPeriod,
+        category_id: Option<i32>,
+        offset: i64,
+        limit: i64,
+    ) -> Result<RankingPage> {
+        let window = period
+            .window_containing(local_now().date_naive())
+            .ok_or_else(|| anyhow::anyhow!("累计榜没有统计窗口"))?;
+        let category_id = Self::category_for(board, category_id);
+        let ranked = self.ranked(board, &window, category_id).await?;
+        let total = ranked.len() as i64;
+        let rows = ranked.into_iter().skip(offset.max(0) as usize).take(limit.max(0) as usize).collect();
+        Ok(RankingPage { window, rows, total })
+    }
+
+    /// 已结束周期的历史排行，首次查询时生成快照
+    pub async fn history(
+        &self,
+        board: RankingBoard,
+        period: RankingPeriod,
+        key: &str,
+        category_id: Option<i32>,
+        offset: i64,
+        limit: i64,
+    ) -> Result<RankingPage> {
+        let window = period
+            .window_for_key(key)
+            .ok_or_else(|| anyhow::anyhow!("周期编号格式错误，例如 2025-W01、2025-01、2025"))?;
+        if window.end >= local_now().date_naive() {
+            return Err(anyhow::anyhow!("该周期尚未结束，请查询当前排行榜"));
+        }
+        let category_id = Self::category_for(board, category_id);
+        let snapshot_key = category_id.unwrap_or(0);
+        if !self.ranking_repo.has_snapshot(board, &window, snapshot_key).await? {
+            self.snapshot(board, &window, category_id).await?;
+        }
+        let (rows, total) = self.ranking_repo.load_snapshot(board, &window, snapshot_key, offset, limit).await?;
+        Ok(RankingPage { window, rows, total })
+    }
+
+    /// 用户榜不区分分类
+    fn category_for(board: RankingBoard, category_id: Option<i32>) -> Option<i32> {
+        match board {
+            RankingBoard::Users => None,
+            _ => category_id.filter(|id| *id > 0),
+        }
+    }
+
+    /// 窗口内的完整排行；名次变化优先取上一周期的快照，没有快照时现场计算
+    async fn ranked(&self, board: RankingBoard, window: &RankingWindow, category_id: Option<i32>) -> Result<Vec<RankedRow>> {
+        let rows = self.ranking_repo.window_rows(board, window, category_id).await?;
+        let previous = window.previous();
+        let previous_ranks: HashMap<i32, i64> =
+            if self.ranking_repo.has_snapshot(board, &previous, category_id.unwrap_or(0)).await? {
+                self.ranking_repo.snapshot_ranks(board, &previous, category_id.unwrap_or(0)).await?
+            } else {
+                self.ranking_repo
+                    .window_rows(board, &previous, category_id)
+                    .await?
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, row)| (row.entity_id, i as i64 + 1))
+                    .collect()
+            };
+        Ok(rows
+            .into_iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let rank = i as i64 + 1;
+                RankedRow {
+                    rank,
+                    rank_change: previous_ranks.get(&row.entity_id).map(|prev| prev - rank),
+                    row,
+                }
+            })
+            .collect())
+    }
+
+    async fn snapshot(&self, board: RankingBoard, window: &RankingWindow, category_id: Option<i32>) -> Result<()> {
+        let mut ranked = self.ranked(board, window, category_id).await?;
+        ranked.truncate(SNAPSHOT_SIZE);
+        self.ranking_repo.save_snapshot(board, window, category_id.unwrap_or(0), &ranked).await
+    }
+
+    /// 把本地日期范围换算为 UTC 区间
+    fn rollup_range(from: NaiveDate, to: NaiveDate) -> Result<RollupRange> {
+        let offset = community_offset();
+        let at = |d: NaiveDate| {
+            offset
+                .from_local_datetime(&d.and_hms_opt(0, 0, 0).expect("valid time"))
+                .single()
+                .map(|t| t.with_timezone(&Utc))
+                .ok_or_else(|| anyhow::anyhow!("无效的日期"))
+        };
+        Ok(RollupRange {
+            from,
+            to,
+            start: at(from)?,
+            end: at(to + ChronoDuration::days(1))?,
+            offset_secs: offset.local_minus_utc(),
+        })
+    }
+
+    /// 启动汇总任务：首次运行回填历史，此后每 10 分钟重算昨天和今天的数据，并为刚结束的周期生成快照
+    pub fn start_rollup_job(&self) {
+        let svc = self.clone();
+        tokio::spawn(async move {
+            info!("🏆 排行榜汇总任务已启动");
+            match svc.backfill().await {
+                Ok(true) => info!("排行榜历史数据回填完成"),
+                Ok(false) => {}
+                Err(e) => warn!("排行榜历史数据回填失败: {}", e),
+            }
+            let mut interval = tokio::time::interval(ROLLUP_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(e) = svc.run_rollup().await {
+                    warn!("排行榜汇总失败: {}", e);
+                }
+            }
+        });
+    }
+
+    async fn backfill(&self) -> Result<bool> {
+        if self.ranking_repo.has_rollups().await? {
+            return Ok(false);
+        }
+        let today = local_now().date_naive();
+        let range = Self::rollup_range(today - ChronoDuration::days(BACKFILL_DAYS), today)?;
+        self.ranking_repo.rollup_events(&range).await?;
+        Ok(true)
+    }
+
+    async fn run_rollup(&self) -> Result<()> {
+        let today = local_now().date_naive();
+        let range = Self::rollup_range(today - ChronoDuration::days(1), today)?;
+        self.ranking_repo.rollup_events(&range).await?;
+        self.ranking_repo.rollup_view_counters(today).await?;
+
+        for period in [RankingPeriod::Week, RankingPeriod::Month, RankingPeriod::Year] {
+            let Some(window) = period.window_containing(today).map(|w| w.previous()) else {
+                continue;
+            };
+            for board in RankingBoard::all() {
+                let mut categories = vec![None];
+                categories.extend(self.ranking_repo.category_ids(board).await?.into_iter().map(Some));
+                for category_id in categories {
+                    if !self.ranking_repo.has_snapshot(board, &window, category_id.unwrap_or(0)).await? {
+                        self.snapshot(board, &window, category_id).await?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,185 @@
+use anyhow::Result;
+use std::collections::HashMap;
+
+use crate::models::rating::{
+    AdminReviewQuery, PackageRating, RatingQuery, RatingSummary, UpsertRatingRequest,
+    REVIEW_HIDDEN, REVIEW_VISIBLE,
+};
+use crate::models::{PackageRankingSort, PackageStatus};
+use crate::repositories::package_repo::PackageRepository;
+use crate::repositories::rating_repo::RatingRepository;
+use crate::services::forbidden_word_service::ForbiddenWordService;
+use crate::services::notification_service::NotificationService;
+use crate::utils::error::ServiceError;
+
+/// 评价文字最大长度
+const MAX_REVIEW_CHARS: usize = 1000;
+/// 作者回复最大长度
+const MAX_REPLY_CHARS: usize = 500;
+/// 排行榜贝叶斯加权的先验权重（相当于预设的评分人数）
+const RANKING_PRIOR_WEIGHT: f64 = 5.0;
+
+#[derive(Clone)]
+pub struct RatingService {
+    rating_repo: RatingRepository,
+    package_repo: PackageRepository,
+    forbidden_service: Option<ForbiddenWordService>,
+    notification_service: Option<NotificationService>,
+}
+
+impl RatingService {
+    pub fn new(rating_repo: RatingRepository, package_repo: PackageRepository) -> Self {
+        Self { rating_repo, package_repo, forbidden_service: None, notification_service: None }
+    }
+
+    pub fn with_forbidden_service(mut self, service: ForbiddenWordService) -> Self {
+        self.forbidden_service = Some(service);
+        self
+    }
+
+    pub fn with_notification_service(mut self, service: NotificationService) -> Self {
+        self.notification_service = Some(service);
+        self
+    }
+
+    /// 评分榜排序：以全站平均分为先验的贝叶斯加权
+    pub async fn ranking_sort(&self) -> Result<PackageRankingSort> {
+        Ok(PackageRankingSort::Rating {
+            prior_mean: self.rating_repo.global_average().await?,
+            prior_weight: RANKING_PRIOR_WEIGHT,
+        })
+    }
+
+    pub async fn get_summaries(&self, package_ids: &[i32]) -> Result<HashMap<i32, RatingSummary>> {
+        self.rating_repo.get_summaries(package_ids).await
+    }
+
+    /// 审核钩子：评价与回复文字需通过违禁词检测
+    async fn check_text(&self, text: &str) -> Result<()> {
+        if let Some(f_service) = &self.forbidden_service {
+            if f_service.contains_forbidden_word(text).await? {
+                return Err(ServiceError::bad_request("内容包含违禁词"));
+            }
+        }
+        Ok(())
+    }
+
+    /// 评分/修改评分：仅限下载过该资源的非作者用户，每人每个资源一条
+    pub async fn rate(&self, user_id: i32, package_id: i32, req: UpsertRatingRequest) -> Result<PackageRating> {
+        if !(1..=5).contains(&req.rating) {
+            return Err(ServiceError::bad_request("评分必须为 1~5 星"));
+        }
+        let review = req.review.as_deref().map(str::trim).filter(|s| !s.is_empty());
+        if let Some(text) = review {
+            if text.chars().count() > MAX_REVIEW_CHARS {
+                return Err(ServiceError::bad_request(format!("评价内容不能超过 {} 字", MAX_REVIEW_CHARS)));
+            }
+            self.check_text(text).await?;
+        }
+
+        let package = self.package_repo.find_by_id(package_id).await?
+            .ok_or_else(|| ServiceError::not_found("资源不存在"))?;
+        if package.status != PackageStatus::Active {
+            return Err(ServiceError::bad_request("资源未上架，暂不能评分"));
+        }
+        if package.owner_id == Some(user_id) {
+            return Err(ServiceError::bad_request("不能给自己的资源评分"));
+        }
+        if !self.rating_repo.has_downloaded(user_id, package_id).await? {
+            return Err(ServiceError::bad_request("下载该资源后才能评分"));
+        }
+
+        let is_new = self.rating_repo.get_user_rating(package_id, user_id).await?.is_none();
+        let id = self.rating_repo.upsert_rating(package_id, user_id, req.rating, review).await?;
+        self.rating_repo.refresh_summary(package_id).await?;
+
+        if let (true, Some(owner_id), Some(notify)) = (is_new, package.owner_id, &self.notification_service) {
+            let link = format!("/resource/{}", package_id);
+            let content = format!("您的资源《{}》收到了 {} 星评价", package.name, req.rating);
+            if let Err(e) = notify.by_actor(user_id).notify(owner_id, "资源收到评价", &content, Some(&link), Some("PackageReviewed"), Some("PackageRating"), Some(id)).await {
+                log::error!("发送评价通知失败: {}", e);
+            }
+        }
+
+        self.rating_repo.get_rating(id).await?.ok_or_else(|| ServiceError::not_found("评分不存在"))
+    }
+
+    pub async fn delete_my_rating(&self, user_id: i32, package_id: i32) -> Result<RatingSummary> {
+        if !self.rating_repo.delete_rating(package_id, user_id).await? {
+            return Err(ServiceError::not_found("评分不存在"));
+        }
+        self.rating_repo.refresh_summary(package_id).await
+    }
+
+    pub async fn get_my_rating(&self, user_id: i32, package_id: i32) -> Result<Option<PackageRating>> {
+        self.rating_repo.get_user_rating(package_id, user_id).await
+    }
+
+    /// 公开列表：被隐藏的评价只保留星级，不展示文字
+    pub async fn list_ratings(&self, package_id: i32, query: &RatingQuery) -> Result<(Vec<PackageRating>, i64)> {
+        let page = query.page.unwrap_or(1).max(1);
+        let page_size = query.page_size.unwrap_or(20).clamp(1, 100);
+        let (mut list, total) = self.rating_repo.list_ratings(package_id, query.rating, page, page_size).await?;
+        for rating in list.iter_mut() {
+            if rating.review_status == REVIEW_HIDDEN {
+                rating.review = None;
+            }
+            rating.moderation_reason = None;
+        }
+        Ok((list, total))
+    }
+
+    pub async fn get_summary(&self, package_id: i32) -> Result<RatingSummary> {
+        Ok(self.rating_repo.get_summaries(&[package_id]).await?.remove(&package_id).unwrap_or_default())
+    }
+
+    /// 资源作者回复评价，回复为空时删除回复
+    pub async fn reply(&self, user_id: i32, rating_id: i32, reply: &str) -> Result<PackageRating> {
+        let rating = self.rating_repo.get_rating(rating_id).await?
+            .ok_or_else(|| ServiceError::not_found("评分不存在"))?;
+        let package = self.package_repo.find_by_id(rating.package_id).await?
+            .ok_or_else(|| ServiceError::not_found("资源不存在"))?;
+        if package.owner_id != Some(user_id) {
+            return Err(ServiceError::forbidden("无权回复该评价，仅资源作者可回复"));
+        }
+
+        let reply = reply.trim();
+        if reply.is_empty() {
+            self.rating_repo.set_author_reply(rating_id, None).await?;
+        } else {
+            if reply.chars().count() > MAX_REPLY_CHARS {
+                return Err(ServiceError::bad_request(format!("回复内容不能超过 {} 字", MAX_REPLY_CHARS)));
+            }
+            self.check_text(reply).await?;
+            let is_new = rating.author_reply.is_none();
+            self.rating_repo.set_author_reply(rating_id, Some(reply)).await?;
+            if let (true, Some(notify)) = (is_new, &self.notification_service) {
+                let link = format!("/resource/{}", package.id);
+                let content = format!("资源《{}》的作者回复了您的评价", package.name);
+                if let Err(e) = notify.by_actor(user_id).notify(rating.user_id, "评价收到回复", &content, Some(&link), Some("ReviewReply"), Some("PackageRating"), Some(rating_id)).await {
+                    log::error!("发送评价回复通知失败: {}", e);
+                }
+            }
+        }
+        self.rating_repo.get_rating(rating_id).await?.ok_or_else(|| ServiceError::not_found("评分不存在"))
+    }
+
+    /// 管理员：评价列表（含隐藏原因）
+    pub async fn list_reviews(&self, query: &AdminReviewQuery) -> Result<(Vec<PackageRating>, i64)> {
+        let page = query.page.unwrap_or(1).max(1);
+        let page_size = query.page_size.unwrap_or(20).clamp(1, 100);
+        self.rating_repo.list_reviews(query.status.as_deref(), query.package_id, page, page_size).await
+    }
+
+    /// 管理员：隐藏/恢复评价文字（星级仍计入汇总）
+    pub async fn moderate(&self, rating_id: i32, status: &str, reason: Option<&str>) -> Result<()> {
+        if status != REVIEW_VISIBLE && status != REVIEW_HIDDEN {
+            return Err(ServiceError::bad_request("无效的审核状态"));
+        }
+        let reason = if status == REVIEW_HIDDEN { reason } else { None };
+        if !self.rating_repo.set_review_status(rating_id, status, reason).await? {
+            return Err(ServiceError::not_found("评分不存在"));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,282 @@
+use anyhow::Result;
+use log::{info, warn};
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use crate::models::recommendation::{
+    Interaction, InteractionKind, PackageSimilarity, RecommendedPackage, UserRecommendation,
+};
+use crate::repositories::package_repo::PackageRepository;
+use crate::repositories::recommendation_repo::RecommendationRepository;
+
+/// 推荐重算间隔
+const RECOMPUTE_INTERVAL: Duration = Duration::from_secs(6 * 3600);
+/// 每个资源保留的相似资源数
+const SIMILAR_PER_PACKAGE: usize = 20;
+/// 每个用户保留的推荐数
+const RECOMMENDATIONS_PER_USER: usize = 30;
+/// 标签相似度（Jaccard）在综合得分中的权重
+const TAG_WEIGHT: f64 = 0.3;
+/// 交互过多的用户（多为批量下载）不参与共现统计，避免组合爆炸
+const MAX_INTERACTIONS_PER_USER: usize = 300;
+/// 过于常见的标签不参与标签相似度计算
+const MAX_PACKAGES_PER_TAG: usize = 200;
+
+/// 单个用户对单个资源的汇总交互
+#[derive(Debug, Clone, Copy, Default)]
+struct UserPackageSignal {
+    weight: f64,
+    downloaded: bool,
+    liked: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct PairStats {
+    dot: f64,
+    co_downloads: i32,
+    co_likes: i32,
+}
+
+#[derive(Clone)]
+pub struct RecommendationService {
+    recommendation_repo: RecommendationRepository,
+    package_repo: PackageRepository,
+}
+
+impl RecommendationService {
+    pub fn new(recommendation_repo: RecommendationRepository, package_repo: PackageRepository) -> Self {
+        Self { recommendation_repo, package_repo }
+    }
+
+    /// 相似资源（“下载过此资源的用户也下载了”），没有数据时退化为同分类热门
+    pub async fn similar_packages(&self, package_id: i32, limit: i32) -> Result<Vec<RecommendedPackage>> {
+        let similar = self.recommendation_repo.get_similar(package_id, limit).await?;
+        let mut list = Vec::new();
+        for s in &similar {
+            let reason = if s.co_downloads > 0 {
+                "下载过此资源的用户也下载了"
+            } else if s.co_likes > 0 {
+                "喜欢此资源的用户也喜欢"
+            } else if s.shared_tags > 0 && s.score <= TAG_WEIGHT {
+                "标签相似"
+            } else {
+                "对此资源感兴趣的用户也关注了"
+            };
+            if let Some(package) = self.package_repo.find_by_id(s.similar_id).await? {
+                list.push(RecommendedPackage { package, score: s.score, reason: Some(reason.to_string()) });
+            }
+        }
+        if list.is_empty() {
+            for id in self.recommendation_repo.popular_in_category(package_id, limit).await? {
+                if let Some(package) = self.package_repo.find_by_id(id).await? {
+                    list.push(RecommendedPackage { package, score: 0.0, reason: Some("同分类热门".to_string()) });
+                }
+            }
+        }
+        Ok(list)
+    }
+
+    /// 个人推荐，没有历史记录时退化为下载榜
+    pub async fn recommended_for_user(&self, user_id: i32, limit: i32) -> Result<Vec<RecommendedPackage>> {
+        let recs = self.recommendation_repo.get_user_recommendations(user_id, limit).await?;
+        let mut list = Vec::new();
+        let mut names: HashMap<i32, String> = HashMap::new();
+        for r in &recs {
+            let Some(package) = self.package_repo.find_by_id(r.package_id).await? else {
+                continue;
+            };
+            let reason = match r.source_package_id {
+                Some(source_id) => {
+                    if let std::collections::hash_map::Entry::Vacant(e) = names.entry(source_id) {
+                        if let Some(source) = self.package_repo.find_by_id(source_id).await? {
+                            e.insert(source.name);
+                        }
+                    }
+                    names.get(&source_id).map(|name| format!("与你下载或喜欢过的《{}》相似", name))
+                }
+                None => None,
+            };
+            list.push(RecommendedPackage { package, score: r.score, reason });
+        }
+        if list.is_empty() {
+            list = self.package_repo.top_by_downloads(limit).await?
+                .into_iter()
+                .map(|package| RecommendedPackage { package, score: 0.0, reason: Some("热门资源".to_string()) })
+                .collect();
+        }
+        Ok(list)
+    }
+
+    /// 启动后台任务：启动时计算一次，此后每 6 小时整表重算
+    pub fn start_recompute_job(&self) {
+        let svc = self.clone();
+        tokio::spawn(async move {
+            info!("🎯 资源推荐计算任务已启动");
+            let mut interval = tokio::time::interval(RECOMPUTE_INTERVAL);
+            loop {
+                interval.tick().await;
+                match svc.recompute().await {
+                    Ok((packages, users)) => info!("资源推荐已更新: {} 个资源有相似推荐，{} 个用户有个人推荐", packages, users),
+                    Err(e) => warn!("计算资源推荐失败: {}", e),
+                }
+            }
+        });
+    }
+
+    /// 重算相似资源与个人推荐，返回 (有相似资源的资源数, 有推荐的用户数)
+    pub async fn recompute(&self) -> Result<(usize, usize)> {
+        let interactions = self.recommendation_repo.load_interactions().await?;
+        let tags = self.recommendation_repo.load_package_tags().await?;
+        let own = self.recommendation_repo.load_own_packages().await?;
+
+        let signals = Self::user_signals(&interactions);
+        let similarities = Self::compute_similarities(&signals, &tags);
+        let recommendations = Self::compute_user_recommendations(&signals, &similarities, &own);
+
+        let flat: Vec<PackageSimilarity> = similarities.values().flatten().cloned().collect();
+        self.recommendation_repo.replace_similarities(&flat).await?;
+        self.recommendation_repo.replace_user_recommendations(&recommendations).await?;
+
+        let users: HashSet<i32> = recommendations.iter().map(|r| r.user_id).collect();
+        Ok((similarities.len(), users.len()))
+    }
+
+    /// 按用户汇总交互：同一资源取最高权重
+    fn user_signals(interactions: &[Interaction]) -> HashMap<i32, HashMap<i32, UserPackageSignal>> {
+        let mut signals: HashMap<i32, HashMap<i32, UserPackageSignal>> = HashMap::new();
+        for i in interactions {
+            let signal = signals.entry(i.user_id).or_default().entry(i.package_id).or_default();
+            signal.weight = signal.weight.max(i.kind.weight());
+            match i.kind {
+                InteractionKind::Download => signal.downloaded = true,
+                InteractionKind::Like | InteractionKind::Favorite => signal.liked = true,
+            }
+        }
+        signals
+    }
+
+    /// 相似度 = 加权交互向量的余弦相似度 + TAG_WEIGHT × 标签 Jaccard 系数
+    fn compute_similarities(
+        signals: &HashMap<i32, HashMap<i32, UserPackageSignal>>,
+        tags: &HashMap<i32, HashSet<i32>>,
+    ) -> HashMap<i32, Vec<PackageSimilarity>> {
+        let mut norms: HashMap<i32, f64> = HashMap::new();
+        let mut pairs: HashMap<(i32, i32), PairStats> = HashMap::new();
+        for items in signals.values() {
+            for (package_id, s) in items {
+                *norms.entry(*package_id).or_default() += s.weight * s.weight;
+            }
+            if items.len() > MAX_INTERACTIONS_PER_USER {
+                continue;
+            }
+            for (a, sa) in items {
+                for (b, sb) in items {
+                    if a == b {
+                        continue;
+                    }
+                    let stats = pairs.entry((*a, *b)).or_default();
+                    stats.dot += sa.weight * sb.weight;
+                    if sa.downloaded && sb.downloaded {
+                        stats.co_downloads += 1;
+                    }
+                    if sa.liked && sb.liked {
+                        stats.co_likes += 1;
+                    }
+                }
+            }
+        }
+
+        // 共享标签的资源对
+        let mut by_tag: HashMap<i32, Vec<i32>> = HashMap::new();
+        for (package_id, set) in tags {
+            for tag_id in set {
+                by_tag.entry(*tag_id).or_default().push(*package_id);
+            }
+        }
+        let mut shared: HashMap<(i32, i32), i32> = HashMap::new();
+        for packages in by_tag.values().filter(|p| p.len() <= MAX_PACKAGES_PER_TAG) {
+            for a in packages {
+                for b in packages {
+                    if a != b {
+                        *shared.entry((*a, *b)).or_default() += 1;
+                    }
+                }
+            }
+        }
+
+        let keys: HashSet<(i32, i32)> = pairs.keys().chain(shared.keys()).copied().collect();
+        let mut result: HashMap<i32, Vec<PackageSimilarity>> = HashMap::new();
+        for (a, b) in keys {
+            let stats = pairs.get(&(a, b)).copied().unwrap_or_default();
+            let cosine = match (norms.get(&a), norms.get(&b)) {
+                (Some(na), Some(nb)) if *na > 0.0 && *nb > 0.0 => stats.dot / (na.sqrt() * nb.sqrt()),
+                _ => 0.0,
+            };
+            let shared_tags = shared.get(&(a, b)).copied().unwrap_or(0);
+            let jaccard = if shared_tags > 0 {
+                let union = tags.get(&a).map_or(0, |t| t.len()) + tags.get(&b).map_or(0, |t| t.len()) - shared_tags as usize;
+                shared_tags as f64 / union.max(1) as f64
+            } else {
+                0.0
+            };
+            let score = cosine + TAG_WEIGHT * jaccard;
+            if score <= 0.0 {
+                continue;
+            }
+            result.entry(a).or_default().push(PackageSimilarity {
+                package_id: a,
+                similar_id: b,
+                score,
+                co_downloads: stats.co_downloads,
+                co_likes: stats.co_likes,
+                shared_tags,
+            });
+        }
+        for list in result.values_mut() {
+            list.sort_by(|x, y| y.score.total_cmp(&x.score).then(x.similar_id.cmp(&y.similar_id)));
+            list.truncate(SIMILAR_PER_PACKAGE);
+        }
+        result
+    }
+
+    /// 个人推荐：候选资源得分 = Σ 用户对历史资源的交互权重 × 历史资源与候选的相似度
+    fn compute_user_recommendations(
+        signals: &HashMap<i32, HashMap<i32, UserPackageSignal>>,
+        similarities: &HashMap<i32, Vec<PackageSimilarity>>,
+        own: &HashMap<i32, HashSet<i32>>,
+    ) -> Vec<UserRecommendation> {
+        let mut result = Vec::new();
+        for (user_id, items) in signals {
+            let own_packages = own.get(user_id);
+            // 候选 -> (得分, 贡献最大的历史资源, 最大贡献)
+            let mut candidates: HashMap<i32, (f64, i32, f64)> = HashMap::new();
+            for (source_id, signal) in items {
+                for s in similarities.get(source_id).into_iter().flatten() {
+                    if items.contains_key(&s.similar_id) || own_packages.is_some_and(|o| o.contains(&s.similar_id)) {
+                        continue;
+                    }
+                    let contribution = signal.weight * s.score;
+                    let entry = candidates.entry(s.similar_id).or_insert((0.0, *source_id, 0.0));
+                    entry.0 += contribution;
+                    if contribution > entry.2 {
+                        entry.1 = *source_id;
+                        entry.2 = contribution;
+                    }
+                }
+            }
+            let mut list: Vec<UserRecommendation> = candidates
+                .into_iter()
+                .map(|(package_id, (score, source_id, _))| UserRecommendation {
+                    user_id: *user_id,
+                    package_id,
+                    score,
+                    source_package_id: Some(source_id),
+                })
+                .collect();
+            list.sort_by(|x, y| y.score.total_cmp(&x.score).then(x.package_id.cmp(&y.package_id)));
+            list.truncate(RECOMMENDATIONS_PER_USER);
+            result.extend(list);
+        }
+        result
+    }
+}
@@ -0,0 +1,175 @@
+use anyhow::Result;
+use log::{error, info, warn};
+use std::time::Duration;
+
+use crate::models::recycle_bin::{
+    parse_recycle_type, RecycleBinItem, RecycleBinQuery, RecycleBinSettings, MAX_RETENTION_DAYS, MIN_RETENTION_DAYS,
+};
+use crate::repositories::comment_repo::CommentRepository;
+use crate::repositories::recycle_bin_repo::RecycleBinRepository;
+use crate::repositories::user_repo::UserRepository;
+use crate::services::package_service::PackageService;
+use crate::services::points_service::PointsService;
+use crate::services::post_service::PostService;
+use crate::utils::error::ServiceError;
+
+/// 检查到期内容的间隔
+const PURGE_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// 每次最多清理的条数，剩余的下次继续
+const PURGE_BATCH: i32 = 200;
+
+/// 回收站：资源、帖子、评论、用户删除后先进入回收站，管理员可恢复或彻底删除；
+/// 超过保留天数的内容由后台任务彻底删除（资源连同文件目录）
+#[derive(Clone)]
+pub struct RecycleBinService {
+    recycle_repo: RecycleBinRepository,
+    package_service: PackageService,
+    post_service: PostService,
+    comment_repo: CommentRepository,
+    user_repo: UserRepository,
+    points_service: PointsService,
+}
+
+impl RecycleBinService {
+    pub fn new(
+        recycle_repo: RecycleBinRepository,
+        package_service: PackageService,
+        post_service: PostService,
+        comment_repo: CommentRepository,
+        user_repo: UserRepository,
+        points_service: PointsService,
+    ) -> Self {
+        Self { recycle_repo, package_service, post_service, comment_repo, user_repo, points_service }
+    }
+
+    fn item_type(s: &str) -> Result<&'static str> {
+        parse_recycle_type(s).ok_or_else(|| ServiceError::bad_request(format!("不支持的类型: {}，可选 package、post、comment、user", s)))
+    }
+
+    async fn retention_modifier(&self) -> Result<String> {
+        Ok(format!("+{} days", self.recycle_repo.retention_days().await?))
+    }
+
+    async fn find(&self, item_type: &str, id: i32) -> Result<RecycleBinItem> {
+        let retention = self.retention_modifier().await?;
+        self.recycle_repo
+            .find(item_type, id, &retention)
+            .await?
+            .ok_or_else(|| ServiceError::not_found("回收站中没有该内容"))
+    }
+
+    /// 启动清理任务：每小时彻底删除一批超过保留天数的内容
+    pub fn start_purge_job(&self) {
+        let svc = self.clone();
+        tokio::spawn(async move {
+            info!("🗑️ 回收站清理任务已启动");
+            let mut interval = tokio::time::interval(PURGE_CHECK_INTERVAL);
+            loop {
+                interval.tick().await;
+                match svc.purge_expired().await {
+                    Ok(0) => {}
+                    Ok(count) => info!("回收站已清理 {} 项到期内容", count),
+                    Err(e) => warn!("回收站清理失败: {}", e),
+                }
+            }
+        });
+    }
+
+    /// 彻底删除一批到期内容，返回删除的条数
+    pub async fn purge_expired(&self) -> Result<usize> {
+        let days = self.recycle_repo.retention_days().await?;
+        let expired = self.recycle_repo.expired(&format!("-{} days", days), PURGE_BATCH).await?;
+        let mut purged = 0;
+        for (item_type, id) in expired {
+            match self.purge_item(&item_type, id).await {
+                Ok(()) => purged += 1,
+                Err(e) => warn!("回收站清理 {} {} 失败: {}", item_type, id, e),
+            }
+        }
+        Ok(purged)
+    }
+
+    pub async fn list(&self, query: &RecycleBinQuery) -> Result<(Vec<RecycleBinItem>, i64, Vec<(String, i64)>)> {
+        let item_type = match query.item_type.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            Some(s) => Some(Self::item_type(s)?),
+            None => None,
+        };
+        let keyword = query.keyword.as_deref().map(str::trim).filter(|s| !s.is_empty());
+        let page = query.page.unwrap_or(1).max(1);
+        let page_size = query.page_size.unwrap_or(20).clamp(1, 100);
+        let retention = self.retention_modifier().await?;
+        let (list, total) = self.recycle_repo.list(item_type, keyword, &retention, page, page_size).await?;
+        let counts = self.recycle_repo.count_by_type().await?;
+        Ok((list, total, counts))
+    }
+
+    /// 恢复内容；资源的文件目录移回当前分类下，删除时收回的积分一并退回
+    pub async fn restore(&self, item_type: &str, id: i32) -> Result<RecycleBinItem> {
+        let item_type = Self::item_type(item_type)?;
+        let item = self.find(item_type, id).await?;
+        let restored = match item_type {
+            "Package" => {
+                let restored = self.recycle_repo.restore_package(id).await?;
+                if restored {
+                    if let Err(e) = self.package_service.restore_files_from_recycle(id).await {
+                        // 文件仍在回收站目录，资源放回回收站，避免恢复后文件无法访问
+                        if let Err(undo) = self.recycle_repo.undo_restore_package(id, &item.deleted_at, item.deleted_by).await {
+                            error!("资源 {} 放回回收站失败: {}", id, undo);
+                        }
+                        return Err(anyhow::anyhow!("资源文件目录恢复失败: {}", e));
+                    }
+                }
+                restored
+            }
+            "Post" => self.recycle_repo.restore_post(id).await?,
+            "Comment" => {
+                if self.recycle_repo.comment_parent_deleted(id).await? {
+                    return Err(ServiceError::bad_request("请先恢复该回复所属的评论"));
+                }
+                self.recycle_repo.restore_comment(id).await?
+            }
+            _ => self.recycle_repo.restore_user(id).await?,
+        };
+        if !restored {
+            return Err(ServiceError::not_found("回收站中没有该内容"));
+        }
+        if let (Some(owner_id), true) = (item.owner_id, item_type != "User") {
+            if let Err(e) = self.points_service.on_content_restored(item_type, id, owner_id).await {
+                warn!("退回 {} {} 的积分失败: {}", item_type, id, e);
+            }
+        }
+        Ok(item)
+    }
+
+    /// 立即彻底删除回收站中的内容
+    pub async fn purge(&self, item_type: &str, id: i32) -> Result<RecycleBinItem> {
+        let item_type = Self::item_type(item_type)?;
+        let item = self.find(item_type, id).await?;
+        self.purge_item(item_type, id).await?;
+        Ok(item)
+    }
+
+    async fn purge_item(&self, item_type: &str, id: i32) -> Result<()> {
+        match item_type {
+            "Package" => self.package_service.purge_package(id).await,
+            "Post" => {
+                self.post_service.purge_post(id).await?;
+                Ok(())
+            }
+            "Comment" => self.comment_repo.purge_comment(id).await,
+            _ => self.user_repo.purge_user(id).await,
+        }
+    }
+
+    pub async fn settings(&self) -> Result<RecycleBinSettings> {
+        Ok(RecycleBinSettings { retention_days: self.recycle_repo.retention_days().await? })
+    }
+
+    pub async fn update_settings(&self, req: &RecycleBinSettings) -> Result<RecycleBinSettings> {
+        if !(MIN_RETENTION_DAYS..=MAX_RETENTION_DAYS).contains(&req.retention_days) {
+            return Err(ServiceError::bad_request(format!("保留天数需在 {}~{} 天之间", MIN_RETENTION_DAYS, MAX_RETENTION_DAYS)));
+        }
+        self.recycle_repo.set_retention_days(req.retention_days).await?;
+        self.settings().await
+    }
+}
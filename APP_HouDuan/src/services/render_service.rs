@@ -0,0 +1,138 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use log::warn;
+
+use crate::models::render::{CachedRender, RenderedField, RenderedPackage, RenderedPost};
+use crate::models::{Package, Post};
+use crate::repositories::render_cache_repo::RenderCacheRepository;
+use crate::utils::markdown::{self, EXCERPT_CHARS, RENDERER_VERSION};
+
+pub const FIELD_POST_CONTENT: &str = "content";
+pub const FIELD_POST_CODE_SNIPPET: &str = "code_snippet";
+pub const FIELD_PACKAGE_DESCRIPTION: &str = "description";
+
+/// 内容的渲染方式
+#[derive(Clone, Copy)]
+enum SourceKind {
+    Markdown,
+    Code,
+}
+
+/// 服务端 Markdown 渲染：输出净化后的 HTML 与纯文本摘要，结果按源内容哈希缓存。
+/// 缓存读写失败只记录日志，不影响返回渲染结果
+#[derive(Clone)]
+pub struct RenderService {
+    cache_repo: RenderCacheRepository,
+}
+
+impl RenderService {
+    pub fn new(cache_repo: RenderCacheRepository) -> Self {
+        Self { cache_repo }
+    }
+
+    /// 仅用于判断缓存是否过期，哈希算法变化时最多导致一次重新渲染
+    fn source_hash(source: &str) -> String {
+        let mut hasher = DefaultHasher::new();
+        source.hash(&mut hasher);
+        format!("{:016x}", hasher.finish())
+    }
+
+    fn render_source(source: &str, kind: SourceKind) -> CachedRender {
+        let (html, excerpt) = match kind {
+            SourceKind::Markdown => (markdown::render_markdown(source), markdown::excerpt(source, EXCERPT_CHARS)),
+            SourceKind::Code => (markdown::render_code(source, None), String::new()),
+        };
+        CachedRender { source_hash: Self::source_hash(source), renderer_version: RENDERER_VERSION, html, excerpt }
+    }
+
+    fn is_fresh(cached: &CachedRender, source: &str) -> bool {
+        cached.renderer_version == RENDERER_VERSION && cached.source_hash == Self::source_hash(source)
+    }
+
+    async fn render_field(&self, target_type: &str, target_id: i32, field: &str, source: &str, kind: SourceKind) -> RenderedField {
+        match self.cache_repo.find(target_type, target_id, field).await {
+            Ok(Some(cached)) if Self::is_fresh(&cached, source) => {
+                return RenderedField { html: cached.html, excerpt: cached.excerpt };
+            }
+            Ok(_) => {}
+            Err(e) => warn!("读取渲染缓存失败: {}", e),
+        }
+        let rendered = Self::render_source(source, kind);
+        if let Err(e) = self.cache_repo.save(target_type, target_id, field, &rendered).await {
+            warn!("写入渲染缓存失败: {}", e);
+        }
+        RenderedField { html: rendered.html, excerpt: rendered.excerpt }
+    }
+
+    pub async fn render_post(&self, post: &Post) -> RenderedPost {
+        let content = self
+            .render_field("Post", post.id, FIELD_POST_CONTENT, &post.content, SourceKind::Markdown)
+            .await;
+        let code_snippet_html = match post.code_snippet.as_deref().filter(|c| !c.trim().is_empty()) {
+            Some(code) => Some(
+                self.render_field("Post", post.id, FIELD_POST_CODE_SNIPPET, code, SourceKind::Code)
+                    .await
+                    .html,
+            ),
+            None => None,
+        };
+        RenderedPost { content_html: content.html, code_snippet_html, excerpt: content.excerpt }
+    }
+
+    pub async fn render_package(&self, package: &Package) -> RenderedPackage {
+        let description = package.description.as_deref().unwrap_or("");
+        let rendered = self
+            .render_field("Package", package.id, FIELD_PACKAGE_DESCRIPTION, description, SourceKind::Markdown)
+            .await;
+        RenderedPackage { description_html: rendered.html, excerpt: rendered.excerpt }
+    }
+
+    /// 为列表项（已序列化的帖子或资源）补充 excerpt 字段；
+    /// field 为缓存字段，source_key 为列表项中原文所在的键，未命中缓存的顺带渲染入缓存
+    pub async fn attach_excerpts(&self, target_type: &str, field: &str, source_key: &str, items: &mut [serde_json::Value]) {
+        let ids: Vec<i32> = items
+            .iter()
+            .filter_map(|v| v.get("id").and_then(|id| id.as_i64()).map(|id| id as i32))
+            .collect();
+        let cached = self.cache_repo.find_many(target_type, field, &ids).await.unwrap_or_else(|e| {
+            warn!("读取渲染缓存失败: {}", e);
+            Default::default()
+        });
+        for item in items.iter_mut() {
+            let Some(id) = item.get("id").and_then(|id| id.as_i64()).map(|id| id as i32) else {
+                continue;
+            };
+            let source = item.get(source_key).and_then(|s| s.as_str()).unwrap_or("").to_string();
+            let excerpt = match cached.get(&id) {
+                Some(c) if Self::is_fresh(c, &source) => c.excerpt.clone(),
+                _ => {
+                    let rendered = Self::render_source(&source, SourceKind::Markdown);
+                    if let Err(e) = self.cache_repo.save(target_type, id, field, &rendered).await {
+                        warn!("写入渲染缓存失败: {}", e);
+                    }
+                    rendered.excerpt
+                }
+            };
+            if let serde_json::Value::Object(map) = item {
+                map.insert("excerpt".to_string(), serde_json::json!(excerpt));
+            }
+        }
+    }
+
+    /// 编辑器预览（不缓存）
+    pub fn preview(&self, content: &str, code: bool, language: Option<&str>) -> RenderedField {
+        if code {
+            RenderedField { html: markdown::render_code(content, language), excerpt: String::new() }
+        } else {
+            RenderedField {
+                html: markdown::render_markdown(content),
+                excerpt: markdown::excerpt(content, EXCERPT_CHARS),
+            }
+        }
+    }
+
+    pub fn highlight_css(&self) -> String {
+        markdown::highlight_css()
+    }
+}
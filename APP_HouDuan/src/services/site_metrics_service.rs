@@ -0,0 +1,272 @@
+use anyhow::Result;
+use chrono::{Duration as ChronoDuration, Months, NaiveDate, TimeZone, Utc};
+use log::{info, warn};
+use std::time::Duration;
+
+use crate::models::site_metrics::{
+    MetricChange, RetentionWeek, RollupMetricsRequest, SignupCohort, SiteDailyMetrics, SiteMetricsComparison,
+    SiteMetricsQuery, SiteMetricsReport, SiteMetricsSummary, DEFAULT_METRICS_DAYS, DEFAULT_RETENTION_COHORTS,
+    MAX_METRICS_DAYS, MAX_RETENTION_COHORTS, RETENTION_TRACK_WEEKS,
+};
+use crate::models::weekly_report::ReportWeek;
+use crate::repositories::site_metrics_repo::{MetricsDay, SiteMetricsRepository};
+use crate::utils::error::ServiceError;
+use crate::utils::time::{community_offset, local_now};
+
+/// 检查是否有待汇总日期的间隔
+const ROLLUP_CHECK_INTERVAL: Duration = Duration::from_secs(30 * 60);
+/// 首次运行时最多回填的天数
+const BACKFILL_DAYS: i64 = 366;
+
+fn day(d: NaiveDate) -> String {
+    d.format("%Y-%m-%d").to_string()
+}
+
+fn parse_day(s: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").map_err(|_| ServiceError::bad_request(format!("日期格式错误，应为 YYYY-MM-DD: {}", s)))
+}
+
+/// 全站数据：每天凌晨（北京时间）汇总前一天的新增用户、DAU/WAU/MAU、上传、下载、发帖、评论与注册周留存，
+/// 管理后台按日期范围读取汇总表并与上一区间或去年同期对比
+#[derive(Clone)]
+pub struct SiteMetricsService {
+    metrics_repo: SiteMetricsRepository,
+}
+
+impl SiteMetricsService {
+    pub fn new(metrics_repo: SiteMetricsRepository) -> Self {
+        Self { metrics_repo }
+    }
+
+    fn day_modifier() -> String {
+        format!("{:+} seconds", community_offset().local_minus_utc())
+    }
+
+    /// 本地日期对应的 UTC 区间
+    fn metrics_day(date: NaiveDate) -> Result<MetricsDay> {
+        let offset = community_offset();
+        let at = |d: NaiveDate| {
+            offset
+                .from_local_datetime(&d.and_hms_opt(0, 0, 0).expect("valid time"))
+                .single()
+                .map(|t| t.with_timezone(&Utc).format("%Y-%m-%d %H:%M:%S").to_string())
+                .ok_or_else(|| anyhow::anyhow!("无效的日期"))
+        };
+        Ok(MetricsDay { date: day(date), start: at(date)?, end: at(date + ChronoDuration::days(1))? })
+    }
+
+    /// 启动汇总任务：首次运行回填历史，此后每半小时检查一次，过了零点即汇总刚结束的一天
+    pub fn start_rollup_job(&self) {
+        let svc = self.clone();
+        tokio::spawn(async move {
+            info!("📊 全站数据汇总任务已启动");
+            let mut interval = tokio::time::interval(ROLLUP_CHECK_INTERVAL);
+            loop {
+                interval.tick().await;
+                match svc.run_pending().await {
+                    Ok(0) => {}
+                    Ok(days) => info!("全站数据已汇总 {} 天", days),
+                    Err(e) => warn!("全站数据汇总失败: {}", e),
+                }
+            }
+        });
+    }
+
+    /// 汇总上次汇总之后到昨天为止的日期，返回汇总的天数
+    async fn run_pending(&self) -> Result<usize> {
+        let yesterday = local_now().date_naive() - ChronoDuration::days(1);
+        let from = match self.metrics_repo.latest_date().await? {
+            Some(latest) => parse_day(&latest)? + ChronoDuration::days(1),
+            None => {
+                let earliest = yesterday - ChronoDuration::days(BACKFILL_DAYS - 1);
+                match self.metrics_repo.earliest_signup_date(&Self::day_modifier()).await? {
+                    Some(first) => parse_day(&first)?.max(earliest),
+                    None => yesterday,
+                }
+            }
+        };
+        if from > yesterday {
+            return Ok(0);
+        }
+        self.rollup(from, yesterday).await
+    }
+
+    /// 管理员手动重算已结束的日期范围，例如修正历史数据之后
+    pub async fn rollup_range(&self, req: &RollupMetricsRequest) -> Result<usize> {
+        let (from, to) = (parse_day(&req.from)?, parse_day(&req.to)?);
+        if from > to {
+            return Err(ServiceError::bad_request("开始日期不能晚于结束日期"));
+        }
+        if to >= local_now().date_naive() {
+            return Err(ServiceError::bad_request("只能汇总已结束的日期"));
+        }
+        if (to - from).num_days() + 1 > MAX_METRICS_DAYS {
+            return Err(ServiceError::bad_request(format!("一次最多汇总 {} 天", MAX_METRICS_DAYS)));
+        }
+        self.rollup(from, to).await
+    }
+
+    async fn rollup(&self, from: NaiveDate, to: NaiveDate) -> Result<usize> {
+        // WAU/MAU 需要前 29 天的活跃用户，回填起点之前没有汇总过的日期先补记活跃用户
+        for offset in (1..30).rev() {
+            let date = from - ChronoDuration::days(offset);
+            if !self.metrics_repo.has_metrics(&day(date)).await? {
+                self.metrics_repo.refresh_active_users(&Self::metrics_day(date)?).await?;
+            }
+        }
+        let mut date = from;
+        let mut days = 0;
+        while date <= to {
+            self.metrics_repo
+                .rollup_day(
+                    &Self::metrics_day(date)?,
+                    &day(date - ChronoDuration::days(6)),
+                    &day(date - ChronoDuration::days(29)),
+                )
+                .await?;
+            date += ChronoDuration::days(1);
+            days += 1;
+        }
+
+        // 跟踪期覆盖到本次汇总日期的注册周都要重算
+        let last_date = match self.metrics_repo.latest_date().await? {
+            Some(latest) => latest,
+            None => day(to),
+        };
+        let first_week = ReportWeek::containing(from - ChronoDuration::weeks(RETENTION_TRACK_WEEKS)).monday;
+        self.metrics_repo
+            .refresh_cohorts(&day(first_week), &last_date, RETENTION_TRACK_WEEKS, &Self::day_modifier())
+            .await?;
+        Ok(days)
+    }
+
+    /// 按日期范围读取汇总数据；未指定时为截至最近一次汇总的 30 天
+    pub async fn report(&self, query: &SiteMetricsQuery) -> Result<SiteMetricsReport> {
+        let last_rollup_date = self.metrics_repo.latest_date().await?;
+        let to = match query.to.as_deref().filter(|s| !s.trim().is_empty()) {
+            Some(s) => parse_day(s)?,
+            None => match &last_rollup_date {
+                Some(latest) => parse_day(latest)?,
+                None => local_now().date_naive() - ChronoDuration::days(1),
+            },
+        };
+        let from = match query.from.as_deref().filter(|s| !s.trim().is_empty()) {
+            Some(s) => parse_day(s)?,
+            None => to - ChronoDuration::days(DEFAULT_METRICS_DAYS - 1),
+        };
+        if from > to {
+            return Err(ServiceError::bad_request("开始日期不能晚于结束日期"));
+        }
+        let span = (to - from).num_days() + 1;
+        if span > MAX_METRICS_DAYS {
+            return Err(ServiceError::bad_request(format!("查询范围最多 {} 天", MAX_METRICS_DAYS)));
+        }
+
+        let daily = self.metrics_repo.list(&day(from), &day(to)).await?;
+        let summary = Self::summarize(&daily);
+
+        let compare_range = match query.compare.as_deref().map(str::trim).unwrap_or("previous") {
+            "" | "previous" => Some(("previous", from - ChronoDuration::days(span), from - ChronoDuration::days(1))),
+            "year" => {
+                let shift = |d: NaiveDate| d.checked_sub_months(Months::new(12)).ok_or_else(|| ServiceError::bad_request("无效的日期"));
+                Some(("year", shift(from)?, shift(to)?))
+            }
+            "none" => None,
+            _ => return Err(ServiceError::bad_request("对比方式错误，可选 previous、year、none")),
+        };
+        let comparison = match compare_range {
+            Some((mode, prev_from, prev_to)) => {
+                let prev_daily = self.metrics_repo.list(&day(prev_from), &day(prev_to)).await?;
+                let prev_summary = Self::summarize(&prev_daily);
+                Some(SiteMetricsComparison {
+                    mode: mode.to_string(),
+                    from: day(prev_from),
+                    to: day(prev_to),
+                    changes: Self::changes(&summary, &prev_summary),
+                    summary: prev_summary,
+                })
+            }
+            None => None,
+        };
+
+        Ok(SiteMetricsReport {
+            from: day(from),
+            to: day(to),
+            last_rollup_date,
+            summary,
+            daily,
+            comparison,
+        })
+    }
+
+    fn summarize(daily: &[SiteDailyMetrics]) -> SiteMetricsSummary {
+        let days = daily.len() as i64;
+        let last = daily.last().cloned().unwrap_or_default();
+        let dau_sum: i64 = daily.iter().map(|d| d.dau).sum();
+        SiteMetricsSummary {
+            days,
+            new_users: daily.iter().map(|d| d.new_users).sum(),
+            avg_dau: if days > 0 { (dau_sum as f64 / days as f64 * 10.0).round() / 10.0 } else { 0.0 },
+            peak_dau: daily.iter().map(|d| d.dau).max().unwrap_or(0),
+            wau: last.wau,
+            mau: last.mau,
+            app_launches: daily.iter().map(|d| d.app_launches).sum(),
+            uploads: daily.iter().map(|d| d.uploads).sum(),
+            downloads: daily.iter().map(|d| d.downloads).sum(),
+            new_posts: daily.iter().map(|d| d.new_posts).sum(),
+            new_comments: daily.iter().map(|d| d.new_comments).sum(),
+            total_users: last.total_users,
+            total_packages: last.total_packages,
+            total_posts: last.total_posts,
+            total_comments: last.total_comments,
+        }
+    }
+
+    fn changes(current: &SiteMetricsSummary, previous: &SiteMetricsSummary) -> Vec<MetricChange> {
+        [
+            ("new_users", current.new_users as f64, previous.new_users as f64),
+            ("avg_dau", current.avg_dau, previous.avg_dau),
+            ("peak_dau", current.peak_dau as f64, previous.peak_dau as f64),
+            ("wau", current.wau as f64, previous.wau as f64),
+            ("mau", current.mau as f64, previous.mau as f64),
+            ("app_launches", current.app_launches as f64, previous.app_launches as f64),
+            ("uploads", current.uploads as f64, previous.uploads as f64),
+            ("downloads", current.downloads as f64, previous.downloads as f64),
+            ("new_posts", current.new_posts as f64, previous.new_posts as f64),
+            ("new_comments", current.new_comments as f64, previous.new_comments as f64),
+        ]
+        .into_iter()
+        .map(|(metric, current, previous)| MetricChange {
+            metric: metric.to_string(),
+            current,
+            previous,
+            change_percent: if previous != 0.0 {
+                Some(((current - previous) / previous * 1000.0).round() / 10.0)
+            } else {
+                None
+            },
+        })
+        .collect()
+    }
+
+    /// 最近 cohorts 个注册周的留存，最新的注册周在前
+    pub async fn retention(&self, cohorts: Option<i64>) -> Result<Vec<SignupCohort>> {
+        let cohorts = cohorts.unwrap_or(DEFAULT_RETENTION_COHORTS).clamp(1, MAX_RETENTION_COHORTS);
+        let this_week = ReportWeek::containing(local_now().date_naive()).monday;
+        let first_week = this_week - ChronoDuration::weeks(cohorts - 1);
+
+        let mut list: Vec<SignupCohort> = Vec::new();
+        for (cohort_week, week_offset, cohort_size, retained) in self.metrics_repo.list_cohorts(&day(first_week)).await? {
+            if list.last().map(|c| c.cohort_week != cohort_week).unwrap_or(true) {
+                list.push(SignupCohort { cohort_week, cohort_size, weeks: Vec::new() });
+            }
+            let cohort = list.last_mut().expect("cohort pushed above");
+            cohort.weeks.push(RetentionWeek {
+                week_offset,
+                retained,
+                rate: if cohort_size > 0 { (retained as f64 / cohort_size as f64 * 1000.0).round() / 10.0 } else { 0.0 },
+            });
+        }
+        Ok(list)
+    }
+}
@@ -1,12 +1,26 @@
-use rusqlite::{Connection, Result as SqliteResult, params};
-use crate::models::{Tag, CreateTagRequest, UpdateTagRequest, TagQueryParams, TagListResponse};
-use chrono::{DateTime, Utc};
-
-// 辅助函数，解析RFC3339时间戳，若失败则返回当前时间，避免panic
-fn parse_timestamp(ts: String) -> DateTime<Utc> {
-    DateTime::parse_from_rfc3339(&ts)
-        .map(|dt| dt.with_timezone(&Utc))
-        .unwrap_or_else(|_| Utc::now())
+use anyhow::Result;
+use rusqlite::{Connection, Result as SqliteResult, params, OptionalExtension};
+use crate::models::{Tag, TagAlias, CreateTagRequest, UpdateTagRequest, TagQueryParams, TagListResponse, TAG_STATUSES, MAX_TAG_LENGTH};
+use chrono::Utc;
+use crate::repositories::tag_repo::{
+    attached_tag_ids, find_tag, map_tag, normalize_tag_name, posts_with_tag, resolve_tags, sync_post_tags_json, tag_key,
+    whitelist_enabled, TagTarget, TAG_SELECT, TAG_WHITELIST_KEY,
+};
+use crate::utils::error::ServiceError;
+
+// 新名称不能与其他标签或其他标签的别名冲突
+fn check_name_free(conn: &Connection, key: &str, own_id: Option<i32>) -> Result<()> {
+    if let Some(found) = find_tag(conn, key)? {
+        if Some(found.id) == own_id {
+            return Ok(());
+        }
+        return Err(ServiceError::bad_request(if found.via_alias {
+            format!("该名称已是标签「{}」的别名", found.name)
+        } else {
+            format!("标签名称 '{}' 已存在，如需合并请使用合并功能", found.name)
+        }));
+    }
+    Ok(())
 }
 
 #[derive(Clone)]
@@ -19,30 +33,24 @@ impl TagService {
         Self { db_path }
     }
 
-    // 创建标签
-    pub async fn create_tag(&self, req: CreateTagRequest) -> SqliteResult<i32> {
+    // 创建标签（管理员创建的标签直接为已审核）
+    pub async fn create_tag(&self, req: CreateTagRequest) -> Result<i32> {
         let conn = Connection::open(&self.db_path)?;
-        
-        // 先检查标签名是否已存在
-        let existing_tag = conn.query_row(
-            "SELECT id FROM tags WHERE name = ?",
-            params![req.name],
-            |row| row.get::<_, i32>(0)
-        );
-        
-        if existing_tag.is_ok() {
-            return Err(rusqlite::Error::SqliteFailure(
-                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CONSTRAINT),
-                Some(format!("标签名称 '{}' 已存在", req.name))
-            ));
+
+        let name = normalize_tag_name(&req.name).ok_or_else(|| ServiceError::bad_request("标签名称不能为空"))?;
+        if name.chars().count() > MAX_TAG_LENGTH {
+            return Err(ServiceError::bad_request(format!("标签名称不能超过 {} 个字符", MAX_TAG_LENGTH)));
         }
-        
+        // 先检查标签名或别名是否已存在
+        check_name_free(&conn, &tag_key(&name), None)?;
+
         let now_str = Utc::now().to_rfc3339();
 
         conn.execute(
-            "INSERT INTO tags (name, description, color, use_count, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?)",
+            "INSERT INTO tags (name, slug, description, color, use_count, status, created_at, updated_at) VALUES (?, ?, ?, ?, ?, 'approved', ?, ?)",
             params![
-                req.name,
+                name,
+                tag_key(&name),
                 req.description,
                 req.color,
                 0, // use_count
@@ -56,16 +64,47 @@ impl TagService {
         Ok(tag_id)
     }
 
-    // 更新标签
-    pub async fn update_tag(&self, tag_id: i32, req: UpdateTagRequest) -> SqliteResult<bool> {
-        let conn = Connection::open(&self.db_path)?;
-        
+    // 更新标签；改名时默认把旧名称保留为别名，并同步帖子的标签列。标签不存在时返回 false
+    pub async fn update_tag(&self, tag_id: i32, req: UpdateTagRequest) -> Result<bool> {
+        let mut conn = Connection::open(&self.db_path)?;
+        let tx = conn.transaction()?;
+
+        let old_name: Option<String> = tx.query_row(
+            "SELECT name FROM tags WHERE id = ?",
+            params![tag_id],
+            |row| row.get(0),
+        ).optional()?;
+        let old_name = match old_name {
+            Some(name) => name,
+            None => return Ok(false),
+        };
+
         let mut updates = Vec::new();
         let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+        let mut renamed = false;
 
         if let Some(name) = req.name {
-            updates.push("name = ?");
-            params.push(Box::new(name));
+            let name = normalize_tag_name(&name).ok_or_else(|| ServiceError::bad_request("标签名称不能为空"))?;
+            if name.chars().count() > MAX_TAG_LENGTH {
+                return Err(ServiceError::bad_request(format!("标签名称不能超过 {} 个字符", MAX_TAG_LENGTH)));
+            }
+            if name != old_name {
+                let key = tag_key(&name);
+                check_name_free(&tx, &key, Some(tag_id))?;
+                // 新名称原本是本标签的别名时，别名转正
+                tx.execute("DELETE FROM tag_aliases WHERE alias_key = ?", params![key])?;
+                if req.keep_alias.unwrap_or(true) && tag_key(&old_name) != key {
+                    tx.execute(
+                        "INSERT OR IGNORE INTO tag_aliases (alias, alias_key, tag_id) VALUES (?, ?, ?)",
+                        params![old_name, tag_key(&old_name), tag_id],
+                    )?;
+                }
+                updates.push("name = ?");
+                params.push(Box::new(name));
+                updates.push("slug = ?");
+                params.push(Box::new(key));
+                renamed = true;
+            }
         }
 
         if let Some(description) = req.description {
@@ -85,17 +124,23 @@ impl TagService {
             let sql = format!("UPDATE tags SET {} WHERE id = ?", updates.join(", "));
             params.push(Box::new(tag_id));
 
-            let mut stmt = conn.prepare(&sql)?;
-            stmt.execute(rusqlite::params_from_iter(params.iter().map(|p| p.as_ref())))?;
+            tx.execute(&sql, rusqlite::params_from_iter(params.iter().map(|p| p.as_ref())))?;
+        }
+
+        if renamed {
+            for post_id in posts_with_tag(&tx, tag_id)? {
+                sync_post_tags_json(&tx, post_id)?;
+            }
         }
 
+        tx.commit()?;
         Ok(true)
     }
 
     // 获取标签列表
     pub async fn get_tags(&self, query: TagQueryParams) -> SqliteResult<TagListResponse> {
         let conn = Connection::open(&self.db_path)?;
-        
+
         let page = query.page.unwrap_or(1);
         let page_size = query.page_size.unwrap_or(50);
         let offset = (page - 1) * page_size;
@@ -110,6 +155,15 @@ impl TagService {
             params.push(Box::new(search_pattern));
         }
 
+        match query.status.as_deref() {
+            Some("all") => {}
+            Some(status) => {
+                conditions.push("status = ?");
+                params.push(Box::new(status.to_string()));
+            }
+            None => conditions.push("status != 'banned'"),
+        }
+
         let where_clause = if conditions.is_empty() {
             "".to_string()
         } else {
@@ -131,10 +185,7 @@ impl TagService {
         };
 
         // 获取标签列表
-        let sql = format!(
-            "SELECT id, name, description, color, use_count, created_at, updated_at FROM tags {} {} LIMIT ? OFFSET ?",
-            where_clause, order_clause
-        );
+        let sql = format!("{} {} {} LIMIT ? OFFSET ?", TAG_SELECT, where_clause, order_clause);
 
         let mut stmt = conn.prepare(&sql)?;
         let mut params_with_limit = params;
@@ -143,17 +194,7 @@ impl TagService {
 
         let tags = stmt.query_map(
             rusqlite::params_from_iter(params_with_limit.iter().map(|p| p.as_ref())),
-            |row| {
-                Ok(Tag {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    description: row.get(2)?,
-                    color: row.get(3)?,
-                    use_count: row.get(4)?,
-                    created_at: parse_timestamp(row.get::<_, String>(5)?),
-                    updated_at: parse_timestamp(row.get::<_, String>(6)?),
-                })
-            }
+            map_tag,
         )?.collect::<Result<Vec<_>, _>>()?;
 
         Ok(TagListResponse {
@@ -167,143 +208,105 @@ impl TagService {
     // 获取单个标签
     pub async fn get_tag(&self, tag_id: i32) -> SqliteResult<Option<Tag>> {
         let conn = Connection::open(&self.db_path)?;
-        
-        let sql = "SELECT id, name, description, color, use_count, created_at, updated_at FROM tags WHERE id = ?";
-        
-        let result = conn.query_row(sql, params![tag_id], |row| {
-            Ok(Tag {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                description: row.get(2)?,
-                color: row.get(3)?,
-                use_count: row.get(4)?,
-                created_at: parse_timestamp(row.get::<_, String>(5)?),
-                updated_at: parse_timestamp(row.get::<_, String>(6)?),
-            })
-        });
+        Self::get_tag_internal(&conn, tag_id)
+    }
 
-        match result {
-            Ok(tag) => Ok(Some(tag)),
-            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(e),
-        }
+    fn get_tag_internal(conn: &Connection, tag_id: i32) -> SqliteResult<Option<Tag>> {
+        conn.query_row(&format!("{} WHERE id = ?", TAG_SELECT), params![tag_id], map_tag).optional()
     }
 
-    // 根据名称获取标签
+    // 根据名称获取标签（名称会先规范化，也可以是别名）
     pub async fn get_tag_by_name(&self, name: &str) -> SqliteResult<Option<Tag>> {
         let conn = Connection::open(&self.db_path)?;
-        
-        let sql = "SELECT id, name, description, color, use_count, created_at, updated_at FROM tags WHERE name = ?";
-        
-        let result = conn.query_row(sql, params![name], |row| {
-            Ok(Tag {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                description: row.get(2)?,
-                color: row.get(3)?,
-                use_count: row.get(4)?,
-                created_at: parse_timestamp(row.get::<_, String>(5)?),
-                updated_at: parse_timestamp(row.get::<_, String>(6)?),
-            })
-        });
-
-        match result {
-            Ok(tag) => Ok(Some(tag)),
-            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(e),
+        let name = match normalize_tag_name(name) {
+            Some(name) => name,
+            None => return Ok(None),
+        };
+        match find_tag(&conn, &tag_key(&name))? {
+            Some(found) => Self::get_tag_internal(&conn, found.id),
+            None => Ok(None),
         }
     }
 
-    // 删除标签
+    // 删除标签，同时移除帖子与资源上的关联
     pub async fn delete_tag(&self, tag_id: i32) -> SqliteResult<bool> {
-        let conn = Connection::open(&self.db_path)?;
-        
+        let mut conn = Connection::open(&self.db_path)?;
+        let tx = conn.transaction()?;
+
+        let post_ids = posts_with_tag(&tx, tag_id)?;
         // 先删除标签关联
-        conn.execute("DELETE FROM post_tags WHERE tag_id = ?", params![tag_id])?;
-        
+        tx.execute("DELETE FROM post_tags WHERE tag_id = ?", params![tag_id])?;
+        tx.execute("DELETE FROM package_tags WHERE tag_id = ?", params![tag_id])?;
+        tx.execute("DELETE FROM tag_aliases WHERE tag_id = ?", params![tag_id])?;
+
         // 删除标签
-        let result = conn.execute("DELETE FROM tags WHERE id = ?", params![tag_id])?;
+        let result = tx.execute("DELETE FROM tags WHERE id = ?", params![tag_id])?;
+        for post_id in post_ids {
+            sync_post_tags_json(&tx, post_id)?;
+        }
+        tx.commit()?;
         Ok(result > 0)
     }
 
-    // 增加标签使用次数
-    pub async fn increment_use_count(&self, tag_id: i32) -> SqliteResult<()> {
-        let conn = Connection::open(&self.db_path)?;
-        conn.execute("UPDATE tags SET use_count = use_count + 1 WHERE id = ?", params![tag_id])?;
-        Ok(())
+    // 可供选择的标签：不含已禁用的，白名单模式下只含已审核的
+    fn usable_condition(conn: &Connection) -> SqliteResult<&'static str> {
+        Ok(if whitelist_enabled(conn)? { "status = 'approved'" } else { "status != 'banned'" })
     }
 
     // 获取热门标签
     pub async fn get_popular_tags(&self, limit: Option<i32>) -> SqliteResult<Vec<Tag>> {
         let conn = Connection::open(&self.db_path)?;
-        
+
         let limit = limit.unwrap_or(10);
         // 修改查询：如果没有使用次数大于0的标签，则返回所有标签
-        let sql = "SELECT id, name, description, color, use_count, created_at, updated_at FROM tags ORDER BY use_count DESC, created_at DESC LIMIT ?";
-        
-        let tags = conn.prepare(sql)?
-            .query_map(params![limit], |row| {
-                Ok(Tag {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    description: row.get(2)?,
-                    color: row.get(3)?,
-                    use_count: row.get(4)?,
-                    created_at: parse_timestamp(row.get::<_, String>(5)?),
-                    updated_at: parse_timestamp(row.get::<_, String>(6)?),
-                })
-            })?
+        let sql = format!(
+            "{} WHERE {} ORDER BY use_count DESC, created_at DESC LIMIT ?",
+            TAG_SELECT, Self::usable_condition(&conn)?
+        );
+
+        let tags = conn.prepare(&sql)?
+            .query_map(params![limit], map_tag)?
             .collect::<Result<Vec<_>, _>>()?;
-        
+
         Ok(tags)
     }
 
     // 获取所有标签（用于下拉选择）
     pub async fn get_all_tags(&self) -> SqliteResult<Vec<Tag>> {
         let conn = Connection::open(&self.db_path)?;
-        
-        let sql = "SELECT id, name, description, color, use_count, created_at, updated_at FROM tags ORDER BY name";
-        
-        let tags = conn.prepare(sql)?
-            .query_map([], |row| {
-                Ok(Tag {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    description: row.get(2)?,
-                    color: row.get(3)?,
-                    use_count: row.get(4)?,
-                    created_at: parse_timestamp(row.get::<_, String>(5)?),
-                    updated_at: parse_timestamp(row.get::<_, String>(6)?),
-                })
-            })?
+
+        let sql = format!("{} WHERE {} ORDER BY name", TAG_SELECT, Self::usable_condition(&conn)?);
+
+        let tags = conn.prepare(&sql)?
+            .query_map([], map_tag)?
             .collect::<Result<Vec<_>, _>>()?;
-        
+
         Ok(tags)
     }
 
-    // 更新所有标签的使用次数 - 基于真实关联数据统计
+    // 按关联表重新统计所有标签的使用次数 - 日常由触发器维护，此处用于修复历史数据
     pub async fn update_all_tag_counts(&self) -> SqliteResult<()> {
         let conn = Connection::open(&self.db_path)?;
-        
+
         // 检查 package_tags 表是否存在
         let package_tags_exists = conn.prepare("SELECT name FROM sqlite_master WHERE type='table' AND name='package_tags'")
             .and_then(|mut stmt| stmt.exists([]))
             .unwrap_or(false);
-        
+
         // 根据表是否存在来构建不同的SQL
         let sql = if package_tags_exists {
-            "UPDATE tags 
+            "UPDATE tags
              SET use_count = (
                  COALESCE((
                      SELECT COUNT(*) FROM post_tags WHERE tag_id = tags.id
-                 ), 0) + 
+                 ), 0) +
                  COALESCE((
                      SELECT COUNT(*) FROM package_tags WHERE tag_id = tags.id
                  ), 0)
              ),
              updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')"
         } else {
-            "UPDATE tags 
+            "UPDATE tags
              SET use_count = (
                  COALESCE((
                      SELECT COUNT(*) FROM post_tags WHERE tag_id = tags.id
@@ -311,37 +314,40 @@ impl TagService {
              ),
              updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')"
         };
-        
+
         conn.execute(sql, [])?;
         log::info!("已更新所有标签的使用次数");
-        
+
         Ok(())
     }
 
     // 获取标签使用统计信息
     pub async fn get_tag_usage_stats(&self) -> SqliteResult<TagUsageStats> {
         let conn = Connection::open(&self.db_path)?;
-        
+
         // 检查 package_tags 表是否存在
         let package_tags_exists = conn.prepare("SELECT name FROM sqlite_master WHERE type='table' AND name='package_tags'")
             .and_then(|mut stmt| stmt.exists([]))
             .unwrap_or(false);
-        
+
         // 统计总体信息
         let total_tags: i32 = conn.query_row("SELECT COUNT(*) FROM tags", [], |row| row.get(0))?;
         let used_tags: i32 = conn.query_row("SELECT COUNT(*) FROM tags WHERE use_count > 0", [], |row| row.get(0))?;
         let total_usage: i32 = conn.query_row("SELECT SUM(use_count) FROM tags", [], |row| row.get(0)).unwrap_or(0);
-        
+        let pending_tags: i32 = conn.query_row("SELECT COUNT(*) FROM tags WHERE status = 'pending'", [], |row| row.get(0))?;
+        let banned_tags: i32 = conn.query_row("SELECT COUNT(*) FROM tags WHERE status = 'banned'", [], |row| row.get(0))?;
+        let alias_count: i32 = conn.query_row("SELECT COUNT(*) FROM tag_aliases", [], |row| row.get(0)).unwrap_or(0);
+
         // 统计帖子标签使用情况
         let post_tag_usage: i32 = conn.query_row("SELECT COUNT(*) FROM post_tags", [], |row| row.get(0))?;
-        
+
         // 统计资源包标签使用情况（如果表存在）
         let package_tag_usage: i32 = if package_tags_exists {
             conn.query_row("SELECT COUNT(*) FROM package_tags", [], |row| row.get(0)).unwrap_or(0)
         } else {
             0
         };
-        
+
         Ok(TagUsageStats {
             total_tags,
             used_tags,
@@ -349,8 +355,158 @@ impl TagService {
             total_usage,
             post_tag_usage,
             package_tag_usage,
+            pending_tags,
+            banned_tags,
+            alias_count,
+            whitelist_mode: whitelist_enabled(&conn)?,
         })
     }
+
+    /* ---------------- 标签治理 ---------------- */
+
+    /// 校验一组标签并返回规范化、替换别名后的名称；有不可用的标签时返回错误。
+    /// existing 为正在编辑的帖子或资源，其已关联的标签在白名单模式下仍可保留
+    pub async fn check_tags(&self, names: &[String], existing: Option<(TagTarget, i32)>) -> Result<Vec<String>> {
+        let conn = Connection::open(&self.db_path)?;
+        let attached = match existing {
+            Some((target, id)) => attached_tag_ids(&conn, target, id)?,
+            None => Vec::new(),
+        };
+        let resolved = resolve_tags(&conn, names, &attached)?;
+        if let Some(msg) = resolved.rejection_message() {
+            return Err(ServiceError::bad_request(msg));
+        }
+        Ok(resolved.accepted.into_iter().map(|(_, name)| name).collect())
+    }
+
+    pub async fn whitelist_mode(&self) -> SqliteResult<bool> {
+        let conn = Connection::open(&self.db_path)?;
+        whitelist_enabled(&conn)
+    }
+
+    pub async fn set_whitelist_mode(&self, enabled: bool) -> SqliteResult<()> {
+        let conn = Connection::open(&self.db_path)?;
+        conn.execute(
+            "INSERT OR REPLACE INTO system_settings (key, value, description, updated_at) VALUES (?, ?, '标签白名单模式：开启后只能使用已审核的标签', datetime('now'))",
+            params![TAG_WHITELIST_KEY, if enabled { "true" } else { "false" }],
+        )?;
+        Ok(())
+    }
+
+    // 审核标签：approved / pending / banned
+    pub async fn set_tag_status(&self, tag_id: i32, status: &str) -> Result<Option<Tag>> {
+        if !TAG_STATUSES.contains(&status) {
+            return Err(ServiceError::bad_request(format!("无效的标签状态: {}", status)));
+        }
+        let conn = Connection::open(&self.db_path)?;
+        conn.execute(
+            "UPDATE tags SET status = ?, updated_at = ? WHERE id = ?",
+            params![status, Utc::now().to_rfc3339(), tag_id],
+        )?;
+        Ok(Self::get_tag_internal(&conn, tag_id)?)
+    }
+
+    /// 把 source 合并到 target：在同一事务中把帖子与资源的关联改到目标标签，
+    /// 源标签及其别名都变为目标标签的别名，然后删除源标签。任一标签不存在时返回 None
+    pub async fn merge_tags(&self, source_id: i32, target_id: i32, user_id: i32) -> Result<Option<Tag>> {
+        if source_id == target_id {
+            return Err(ServiceError::bad_request("不能把标签合并到自身"));
+        }
+        let mut conn = Connection::open(&self.db_path)?;
+        let tx = conn.transaction()?;
+
+        let source = match Self::get_tag_internal(&tx, source_id)? {
+            Some(tag) => tag,
+            None => return Ok(None),
+        };
+        if Self::get_tag_internal(&tx, target_id)?.is_none() {
+            return Ok(None);
+        }
+
+        let post_ids = posts_with_tag(&tx, source_id)?;
+        for target in [TagTarget::Post, TagTarget::Package] {
+            // 已同时带有两个标签的对象只保留目标标签
+            tx.execute(
+                &format!(
+                    "INSERT OR IGNORE INTO {table} ({col}, tag_id, created_at) SELECT {col}, ?, created_at FROM {table} WHERE tag_id = ?",
+                    table = target.table(), col = target.column()
+                ),
+                params![target_id, source_id],
+            )?;
+            tx.execute(&format!("DELETE FROM {} WHERE tag_id = ?", target.table()), params![source_id])?;
+        }
+
+        tx.execute("UPDATE tag_aliases SET tag_id = ? WHERE tag_id = ?", params![target_id, source_id])?;
+        tx.execute(
+            "INSERT OR REPLACE INTO tag_aliases (alias, alias_key, tag_id, created_by) VALUES (?, ?, ?, ?)",
+            params![source.name, tag_key(&source.name), target_id, user_id],
+        )?;
+        tx.execute("DELETE FROM tags WHERE id = ?", params![source_id])?;
+
+        for post_id in post_ids {
+            sync_post_tags_json(&tx, post_id)?;
+        }
+        let merged = Self::get_tag_internal(&tx, target_id)?;
+        tx.commit()?;
+        log::info!("标签「{}」已合并到 {}", source.name, target_id);
+        Ok(merged)
+    }
+
+    // 别名列表；tag_id 为空时返回全部
+    pub async fn list_aliases(&self, tag_id: Option<i32>) -> SqliteResult<Vec<TagAlias>> {
+        let conn = Connection::open(&self.db_path)?;
+        let mut stmt = conn.prepare(
+            "SELECT a.id, a.alias, a.tag_id, t.name, a.created_by, a.created_at
+             FROM tag_aliases a JOIN tags t ON t.id = a.tag_id
+             WHERE (?1 IS NULL OR a.tag_id = ?1)
+             ORDER BY t.name, a.alias"
+        )?;
+        let aliases = stmt.query_map(params![tag_id], |row| {
+            Ok(TagAlias {
+                id: row.get(0)?,
+                alias: row.get(1)?,
+                tag_id: row.get(2)?,
+                tag_name: row.get(3)?,
+                created_by: row.get(4)?,
+                created_at: row.get(5)?,
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+        Ok(aliases)
+    }
+
+    // 为标签添加别名；标签不存在时返回 None
+    pub async fn add_alias(&self, tag_id: i32, alias: &str, user_id: i32) -> Result<Option<TagAlias>> {
+        let conn = Connection::open(&self.db_path)?;
+        if Self::get_tag_internal(&conn, tag_id)?.is_none() {
+            return Ok(None);
+        }
+        let alias = normalize_tag_name(alias).ok_or_else(|| ServiceError::bad_request("别名不能为空"))?;
+        if alias.chars().count() > MAX_TAG_LENGTH {
+            return Err(ServiceError::bad_request(format!("别名不能超过 {} 个字符", MAX_TAG_LENGTH)));
+        }
+        let key = tag_key(&alias);
+        if let Some(found) = find_tag(&conn, &key)? {
+            return Err(ServiceError::bad_request(if found.via_alias {
+                format!("「{}」已是标签「{}」的别名", alias, found.name)
+            } else if found.id == tag_id {
+                "别名不能与标签名称相同".to_string()
+            } else {
+                format!("已存在标签「{}」，请改用合并", found.name)
+            }));
+        }
+        conn.execute(
+            "INSERT INTO tag_aliases (alias, alias_key, tag_id, created_by) VALUES (?, ?, ?, ?)",
+            params![alias, key, tag_id, user_id],
+        )?;
+        let id = conn.last_insert_rowid() as i32;
+        Ok(self.list_aliases(Some(tag_id)).await?.into_iter().find(|a| a.id == id))
+    }
+
+    pub async fn delete_alias(&self, alias_id: i32) -> SqliteResult<bool> {
+        let conn = Connection::open(&self.db_path)?;
+        let affected = conn.execute("DELETE FROM tag_aliases WHERE id = ?", params![alias_id])?;
+        Ok(affected > 0)
+    }
 }
 
 // 标签使用统计信息
@@ -362,4 +518,8 @@ pub struct TagUsageStats {
     pub total_usage: i32,       // 总使用次数
     pub post_tag_usage: i32,    // 帖子标签使用次数
     pub package_tag_usage: i32, // 资源包标签使用次数
-} 
\ No newline at end of file
+    pub pending_tags: i32,      // 待审核标签数
+    pub banned_tags: i32,       // 已禁用标签数
+    pub alias_count: i32,       // 别名数
+    pub whitelist_mode: bool,   // 是否开启白名单模式
+}
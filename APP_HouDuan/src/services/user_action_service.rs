@@ -14,7 +14,7 @@ impl UserActionService {
         Self { user_action_repo }
     }
 
-    // 记录用户行为
+    // 记录用户行为；积分与成就只在服务端执行对应操作时发放，不信任客户端上报的行为
     pub async fn log_user_action(&self, req: &CreateUserActionRequest) -> Result<UserAction> {
         self.user_action_repo.create_user_action(req).await
     }
@@ -0,0 +1,69 @@
+use anyhow::Result;
+use std::collections::HashSet;
+
+use crate::models::user_relation::{RelatedUser, RelationStatus};
+use crate::repositories::user_relation_repo::UserRelationRepository;
+use crate::repositories::user_repo::UserRepository;
+use crate::utils::error::ServiceError;
+
+/// 拉黑：双方不能互相关注、回复评论、@ 提及与发私信；
+/// 屏蔽：对方的内容不出现在我的动态、评论与通知中，对方无感知
+#[derive(Clone)]
+pub struct UserRelationService {
+    relation_repo: UserRelationRepository,
+    user_repo: UserRepository,
+}
+
+impl UserRelationService {
+    pub fn new(relation_repo: UserRelationRepository, user_repo: UserRepository) -> Self {
+        Self { relation_repo, user_repo }
+    }
+
+    async fn check_target(&self, user_id: i32, target_id: i32) -> Result<()> {
+        if user_id == target_id {
+            return Err(ServiceError::bad_request("不能对自己执行此操作"));
+        }
+        if self.user_repo.find_by_id(target_id).await?.is_none() {
+            return Err(ServiceError::not_found("用户不存在"));
+        }
+        Ok(())
+    }
+
+    pub async fn block(&self, user_id: i32, target_id: i32) -> Result<bool> {
+        self.check_target(user_id, target_id).await?;
+        self.relation_repo.block(user_id, target_id).await
+    }
+
+    pub async fn unblock(&self, user_id: i32, target_id: i32) -> Result<bool> {
+        self.relation_repo.unblock(user_id, target_id).await
+    }
+
+    pub async fn mute(&self, user_id: i32, target_id: i32) -> Result<bool> {
+        self.check_target(user_id, target_id).await?;
+        self.relation_repo.mute(user_id, target_id).await
+    }
+
+    pub async fn unmute(&self, user_id: i32, target_id: i32) -> Result<bool> {
+        self.relation_repo.unmute(user_id, target_id).await
+    }
+
+    pub async fn status(&self, user_id: i32, target_id: i32) -> Result<RelationStatus> {
+        self.relation_repo.status(user_id, target_id).await
+    }
+
+    pub async fn list_blocked(&self, user_id: i32) -> Result<Vec<RelatedUser>> {
+        self.relation_repo.list_blocked(user_id).await
+    }
+
+    pub async fn list_muted(&self, user_id: i32) -> Result<Vec<RelatedUser>> {
+        self.relation_repo.list_muted(user_id).await
+    }
+
+    /// 对 viewer 隐藏内容的用户；未登录时为空
+    pub async fn hidden_user_ids(&self, viewer_id: Option<i32>) -> Result<HashSet<i32>> {
+        match viewer_id {
+            Some(id) => self.relation_repo.hidden_user_ids(id).await,
+            None => Ok(HashSet::new()),
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use anyhow::Result;
 use crate::models::{User, UpdateUserRequest, Package, Comment};
+use crate::models::user::MentionCandidate;
 use crate::repositories::user_repo::UserRepository;
 use crate::utils::password::PasswordUtils;
 
@@ -26,6 +27,11 @@ impl UserService {
         self.user_repo.find_by_username(username).await
     }
 
+    /// @ 提及自动补全
+    pub async fn suggest_mentions(&self, prefix: &str, limit: i32) -> Result<Vec<MentionCandidate>> {
+        self.user_repo.search_mention_candidates(prefix, limit.clamp(1, 20)).await
+    }
+
     // 新增：按邮箱查询用户
     pub async fn get_user_by_email(&self, email: &str) -> Result<Option<User>> {
         self.user_repo.find_by_email(email).await
@@ -50,8 +56,9 @@ impl UserService {
         self.user_repo.update_user(&updated_user).await
     }
 
-    pub async fn delete_user(&self, user_id: i32) -> Result<()> {
-        self.user_repo.delete_user(user_id).await
+    pub async fn delete_user(&self, user_id: i32, deleted_by: i32) -> Result<()> {
+        self.user_repo.soft_delete_user(user_id, deleted_by).await?;
+        Ok(())
     }
 
     pub async fn get_user_resources(&self, user_id: i32) -> Result<Vec<Package>> {
@@ -112,35 +119,13 @@ impl UserService {
         self.user_repo.create_user(&user).await.map(|_| ())
     }
 
-    pub async fn batch_delete_users(&self, usernames: Vec<String>) -> Result<()> { 
-        self.user_repo.batch_delete_users(usernames).await 
-    }
-
-    // 新增：用户签到
-    pub async fn user_check_in(&self, user_id: i32) -> Result<()> {
-        self.user_repo.user_check_in(user_id).await
-    }
-
-    // 新增：获取用户连续签到天数
-    pub async fn get_user_check_in_streak(&self, user_id: i32) -> Result<i32> {
-        self.user_repo.get_check_in_streak(user_id).await
-    }
-
-    // 新增：获取用户今日活跃度
-    pub async fn get_user_today_activity(&self, user_id: i32) -> Result<f32> {
-        // 基于用户今日的各种活动计算活跃度
-        // 这里可以根据发帖、评论、点赞等行为计算
-        Ok(85.0) // 示例值
-    }
-
-    // 新增：获取用户本周发布统计
-    pub async fn get_user_weekly_posts(&self, user_id: i32) -> Result<Vec<i32>> {
-        self.user_repo.get_weekly_posts_count(user_id).await
+    pub async fn batch_delete_users(&self, usernames: Vec<String>, deleted_by: i32) -> Result<()> { 
+        self.user_repo.batch_delete_users(usernames, deleted_by).await 
     }
 
     // 新增：按ID批量删除
-    pub async fn batch_delete_users_by_ids(&self, ids: Vec<i32>) -> Result<()> {
-        for id in ids { if let Some(user) = self.user_repo.find_by_id(id).await? { if !user.is_admin { self.user_repo.delete_user(user.id).await?; } } }
+    pub async fn batch_delete_users_by_ids(&self, ids: Vec<i32>, deleted_by: i32) -> Result<()> {
+        for id in ids { if let Some(user) = self.user_repo.find_by_id(id).await? { if !user.is_admin { self.user_repo.soft_delete_user(user.id, deleted_by).await?; } } }
         Ok(())
     }
 } 
\ No newline at end of file
@@ -0,0 +1,181 @@
+use anyhow::Result;
+use chrono::{Datelike, TimeZone, Timelike, Utc};
+use log::{info, warn};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+use crate::models::mail::MailType;
+use crate::models::notification::DigestSetting;
+use crate::models::weekly_report::{ReportWeek, WeeklyReport};
+use crate::repositories::user_repo::UserRepository;
+use crate::repositories::weekly_report_repo::{ReportRange, WeeklyReportRepository};
+use crate::services::email_service::EmailService;
+use crate::services::notification_service::NotificationService;
+use crate::utils::time::{community_offset, local_now};
+
+/// 本周周报的缓存有效期（已结束的周永久缓存）
+const CURRENT_WEEK_CACHE_TTL: i64 = 600;
+/// 周报邮件任务检查间隔
+const EMAIL_CHECK_INTERVAL: Duration = Duration::from_secs(3600);
+/// 周报邮件发送时刻（社区时区周一的小时）
+const EMAIL_SEND_HOUR: u32 = 8;
+
+#[derive(Clone)]
+pub struct WeeklyReportService {
+    report_repo: WeeklyReportRepository,
+    user_repo: UserRepository,
+    notification_service: Option<NotificationService>,
+    email_service: Option<Arc<RwLock<EmailService>>>,
+}
+
+impl WeeklyReportService {
+    pub fn new(report_repo: WeeklyReportRepository, user_repo: UserRepository) -> Self {
+        Self { report_repo, user_repo, notification_service: None, email_service: None }
+    }
+
+    pub fn with_notification_service(mut self, service: NotificationService) -> Self {
+        self.notification_service = Some(service);
+        self
+    }
+
+    pub fn with_email_service(mut self, email_service: Arc<RwLock<EmailService>>) -> Self {
+        self.email_service = Some(email_service);
+        self
+    }
+
+    /// 解析周参数，默认本周；不能晚于本周
+    pub fn resolve_week(&self, week: Option<&str>) -> Result<ReportWeek> {
+        let current = ReportWeek::containing(local_now().date_naive());
+        let week = match week.map(str::trim).filter(|w| !w.is_empty()) {
+            Some(w) => ReportWeek::parse(w).ok_or_else(|| anyhow::anyhow!("周格式错误，应为 YYYY-Www，例如 2025-W01"))?,
+            None => current,
+        };
+        if week.monday > current.monday {
+            return Err(anyhow::anyhow!("不能查询未来的周报"));
+        }
+        Ok(week)
+    }
+
+    /// 获取周报：已结束的周读取缓存，本周缓存短时间后重新统计
+    pub async fn get_report(&self, user_id: i32, week: ReportWeek) -> Result<WeeklyReport> {
+        let offset = community_offset();
+        let start = offset.from_local_datetime(&week.monday.and_hms_opt(0, 0, 0).expect("valid time"))
+            .single()
+            .map(|d| d.with_timezone(&Utc))
+            .ok_or_else(|| anyhow::anyhow!("无效的周"))?;
+        let week_end = start + chrono::Duration::days(7);
+        let now = Utc::now();
+        let complete = now >= week_end;
+
+        if let Some(cached) = self.report_repo.get_cached(user_id, &week.key()).await? {
+            let fresh = if complete {
+                cached.complete
+            } else {
+                (now - cached.generated_at).num_seconds() < CURRENT_WEEK_CACHE_TTL
+            };
+            if fresh {
+                return Ok(cached);
+            }
+        }
+
+        let range = ReportRange {
+            week,
+            start,
+            end: week_end,
+            offset_secs: offset.local_minus_utc(),
+        };
+        let mut report = self.report_repo.compute(user_id, &range).await?;
+        report.complete = complete;
+        self.report_repo.save(user_id, &report).await?;
+        Ok(report)
+    }
+
+    pub async fn today_activity(&self, user_id: i32) -> Result<f32> {
+        let today = local_now().date_naive();
+        let day_start = community_offset().from_local_datetime(&today.and_hms_opt(0, 0, 0).expect("valid time"))
+            .single()
+            .map(|d| d.with_timezone(&Utc))
+            .unwrap_or_else(Utc::now);
+        self.report_repo.today_activity(user_id, day_start, today).await
+    }
+
+    /// 启动周报邮件任务：每小时检查一次，周一 8 点后向订阅用户发送上周周报
+    pub fn start_email_job(&self) {
+        if self.email_service.is_none() || self.notification_service.is_none() {
+            return;
+        }
+        let svc = self.clone();
+        tokio::spawn(async move {
+            info!("📊 周报邮件任务已启动");
+            let mut interval = tokio::time::interval(EMAIL_CHECK_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(e) = svc.run_email_job().await {
+                    warn!("发送周报邮件失败: {}", e);
+                }
+            }
+        });
+    }
+
+    async fn run_email_job(&self) -> Result<()> {
+        let (Some(email_service), Some(notification_service)) = (&self.email_service, &self.notification_service) else {
+            return Ok(());
+        };
+        let now = local_now();
+        if now.weekday() != chrono::Weekday::Mon || now.hour() < EMAIL_SEND_HOUR {
+            return Ok(());
+        }
+        let week = ReportWeek::containing(now.date_naive()).previous();
+        for setting in notification_service.weekly_report_subscribers().await? {
+            if self.report_repo.is_emailed(setting.user_id, &week.key()).await? {
+                continue;
+            }
+            if let Err(e) = self.send_report_email(email_service, notification_service, &setting, week).await {
+                warn!("发送用户 {} 的周报邮件失败: {}", setting.user_id, e);
+            }
+        }
+        Ok(())
+    }
+
+    async fn send_report_email(
+        &self,
+        email_service: &Arc<RwLock<EmailService>>,
+        notification_service: &NotificationService,
+        setting: &DigestSetting,
+        week: ReportWeek,
+    ) -> Result<()> {
+        let report = self.get_report(setting.user_id, week).await?;
+        let user = match self.user_repo.find_by_id(setting.user_id).await? {
+            Some(u) => u,
+            None => return Ok(()),
+        };
+        if !user.email.trim().is_empty() {
+            let frontend_url = std::env::var("FRONTEND_URL").unwrap_or_else(|_| "http://localhost:5173".to_string());
+            let rank_change = match report.rank_change {
+                0 => "持平".to_string(),
+                n if n > 0 => format!("上升 {} 名", n),
+                n => format!("下降 {} 名", -n),
+            };
+            let variables = serde_json::json!({
+                "username": user.nickname.clone().filter(|n| !n.trim().is_empty()).unwrap_or(user.username.clone()),
+                "week": report.week,
+                "week_range": format!("{} ~ {}", report.week_start.format("%m-%d"), report.week_end.format("%m-%d")),
+                "posts_published": report.posts_published,
+                "resources_published": report.resources_published,
+                "downloads_received": report.downloads_received,
+                "likes_received": report.likes_received,
+                "new_followers": report.new_followers,
+                "comments_posted": report.comments_posted,
+                "check_in_days": report.check_in_days,
+                "xp_gained": report.xp_gained,
+                "rank": report.rank_end,
+                "rank_change": rank_change,
+                "link": format!("{}/weekly-report?week={}", frontend_url.trim_end_matches('/'), report.week),
+                "unsubscribe_link": notification_service.unsubscribe_link(&setting.unsubscribe_token, None),
+            });
+            email_service.read().await.send_localized_mail(&user.email, MailType::WeeklyReport, setting.mail_language.as_deref(), &variables).await?;
+        }
+        self.report_repo.mark_emailed(setting.user_id, &week.key()).await
+    }
+}
@@ -2,6 +2,7 @@ use actix_web::{HttpRequest, HttpResponse, Result};
 use serde_json::json;
 use crate::models::user::{User, UserRole, BanStatus};
 use crate::utils::jwt::JwtUtils;
+use crate::repositories::user_repo::UserRepository;
 
 /// 权限验证错误
 #[derive(Debug)]
@@ -70,8 +71,9 @@ impl AuthHelper {
         None
     }
 
-    /// 验证并获取用户信息
-    pub fn verify_user(req: &HttpRequest) -> Result<User, AuthError> {
+    /// 只校验 Token 并由其中的信息构造用户，不查询账号状态；
+    /// 仅用于请求日志等不做授权判断的场合，鉴权请使用 verify_user
+    pub fn token_user(req: &HttpRequest) -> Result<User, AuthError> {
         // 1. 提取Token
         let token = Self::extract_token(req).ok_or(AuthError::TokenMissing)?;
         
@@ -79,7 +81,7 @@ impl AuthHelper {
         let jwt_utils = req.app_data::<actix_web::web::Data<std::sync::Arc<JwtUtils>>>()
             .ok_or(AuthError::TokenInvalid)?;
         let claims = jwt_utils.verify_token(&token).map_err(|_| AuthError::TokenInvalid)?;
-        
+
         // 3. 构造用户对象
         let user = User {
             id: claims.user_id,
@@ -110,17 +112,43 @@ impl AuthHelper {
             is_admin: claims.role == "admin",
         };
         
-        // 4. 检查封禁状态
-        if user.ban_status != BanStatus::Normal {
-            return Err(AuthError::UserBanned);
+        Ok(user)
+    }
+
+    /// 检查账号状态：已删除（在回收站中）或不存在的账号视为 Token 无效，封禁或暂停的账号拒绝访问；
+    /// 查询失败时同样按 Token 无效处理
+    pub async fn check_account(req: &HttpRequest, user_id: i32) -> Result<(), AuthError> {
+        let user_repo = req
+            .app_data::<actix_web::web::Data<std::sync::Arc<UserRepository>>>()
+            .ok_or(AuthError::TokenInvalid)?;
+        match user_repo.account_status(user_id).await {
+            Ok(Some((ban_status, deleted))) => {
+                if deleted {
+                    Err(AuthError::TokenInvalid)
+                } else if ban_status != BanStatus::Normal {
+                    Err(AuthError::UserBanned)
+                } else {
+                    Ok(())
+                }
+            }
+            Ok(None) => Err(AuthError::TokenInvalid),
+            Err(e) => {
+                log::error!("查询用户 {} 的账号状态失败: {}", user_id, e);
+                Err(AuthError::TokenInvalid)
+            }
         }
-        
+    }
+
+    /// 验证并获取用户信息
+    pub async fn verify_user(req: &HttpRequest) -> Result<User, AuthError> {
+        let user = Self::token_user(req)?;
+        Self::check_account(req, user.id).await?;
         Ok(user)
     }
 
     /// 要求管理员权限
-    pub fn require_admin(req: &HttpRequest) -> Result<User, AuthError> {
-        let user = Self::verify_user(req)?;
+    pub async fn require_admin(req: &HttpRequest) -> Result<User, AuthError> {
+        let user = Self::verify_user(req).await?;
         
         if user.role != UserRole::Admin {
             return Err(AuthError::AdminRequired);
@@ -130,8 +158,8 @@ impl AuthHelper {
     }
 
     /// 要求特定角色权限
-    pub fn require_roles(req: &HttpRequest, required_roles: &[UserRole]) -> Result<User, AuthError> {
-        let user = Self::verify_user(req)?;
+    pub async fn require_roles(req: &HttpRequest, required_roles: &[UserRole]) -> Result<User, AuthError> {
+        let user = Self::verify_user(req).await?;
         
         if !required_roles.contains(&user.role) {
             return Err(AuthError::InsufficientRole);
@@ -141,23 +169,23 @@ impl AuthHelper {
     }
 
     /// 检查是否为管理员
-    pub fn is_admin(req: &HttpRequest) -> bool {
-        Self::verify_user(req).map(|user| user.role == UserRole::Admin).unwrap_or(false)
+    pub async fn is_admin(req: &HttpRequest) -> bool {
+        Self::verify_user(req).await.map(|user| user.role == UserRole::Admin).unwrap_or(false)
     }
 
     /// 从请求中提取用户ID
-    pub fn extract_user_id(req: &HttpRequest) -> Option<i32> {
-        Self::verify_user(req).ok().map(|user| user.id)
+    pub async fn extract_user_id(req: &HttpRequest) -> Option<i32> {
+        Self::verify_user(req).await.ok().map(|user| user.id)
     }
 
     /// 从请求中获取用户名
-    pub fn get_username(req: &HttpRequest) -> Option<String> {
-        Self::verify_user(req).ok().map(|user| user.username)
+    pub async fn get_username(req: &HttpRequest) -> Option<String> {
+        Self::verify_user(req).await.ok().map(|user| user.username)
     }
 
     /// 检查是否为资源所有者或管理员
-    pub fn require_owner_or_admin(req: &HttpRequest, resource_owner_id: i32) -> Result<User, AuthError> {
-        let user = Self::verify_user(req)?;
+    pub async fn require_owner_or_admin(req: &HttpRequest, resource_owner_id: i32) -> Result<User, AuthError> {
+        let user = Self::verify_user(req).await?;
         
         // 管理员拥有所有权限
         if user.role == UserRole::Admin {
@@ -232,7 +260,7 @@ impl AuthHelper {
 #[macro_export]
 macro_rules! require_auth {
     ($req:expr) => {
-        match crate::utils::auth_helper::AuthHelper::verify_user($req) {
+        match crate::utils::auth_helper::AuthHelper::verify_user($req).await {
             Ok(user) => user,
             Err(e) => return Ok(e.to_response()),
         }
@@ -243,7 +271,7 @@ macro_rules! require_auth {
 #[macro_export]
 macro_rules! require_admin {
     ($req:expr) => {
-        match crate::utils::auth_helper::AuthHelper::require_admin($req) {
+        match crate::utils::auth_helper::AuthHelper::require_admin($req).await {
             Ok(user) => user,
             Err(e) => return Ok(e.to_response()),
         }
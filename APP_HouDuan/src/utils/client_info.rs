@@ -0,0 +1,64 @@
+/// 从 User-Agent 识别操作系统
+pub fn platform(user_agent: Option<&str>) -> &'static str {
+    let ua = match user_agent {
+        Some(ua) if !ua.trim().is_empty() => ua,
+        _ => return "未知",
+    };
+    if ua.contains("HarmonyOS") || ua.contains("OpenHarmony") {
+        "HarmonyOS"
+    } else if ua.contains("Android") {
+        "Android"
+    } else if ua.contains("iPhone") || ua.contains("iPad") || ua.contains("iPod") {
+        "iOS"
+    } else if ua.contains("Windows") {
+        "Windows"
+    } else if ua.contains("Mac OS X") || ua.contains("Macintosh") {
+        "macOS"
+    } else if ua.contains("Linux") {
+        "Linux"
+    } else {
+        "其他"
+    }
+}
+
+/// 从 User-Agent 识别客户端：社区 App 或浏览器类型
+pub fn client(user_agent: Option<&str>) -> &'static str {
+    let ua = match user_agent {
+        Some(ua) if !ua.trim().is_empty() => ua,
+        _ => return "未知",
+    };
+    if ua.contains("CapacitorApp") {
+        "结绳社区 App"
+    } else if ua.contains("MicroMessenger") {
+        "微信"
+    } else if ua.contains("QQ/") || ua.contains("MQQBrowser") {
+        "QQ"
+    } else if ua.contains("Edg/") {
+        "Edge"
+    } else if ua.contains("Firefox/") {
+        "Firefox"
+    } else if ua.contains("Chrome/") {
+        "Chrome"
+    } else if ua.contains("Safari/") {
+        "Safari"
+    } else {
+        "其他"
+    }
+}
+
+/// 访问来源：优先使用前端传入的来源标识（如 search、feed，只保留字母数字、- 与 _），
+/// 否则取 Referer 的域名；都没有时为 None（直接访问）
+pub fn view_source(source: Option<&str>, referer: Option<&str>) -> Option<String> {
+    let source = source
+        .map(|s| s.trim().to_lowercase())
+        .filter(|s| !s.is_empty() && s.len() <= 32 && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    if source.is_some() {
+        return source;
+    }
+    let referer = referer?.trim();
+    let rest = referer.split_once("://").map(|(_, rest)| rest).unwrap_or(referer);
+    let host = rest.split(['/', '?', '#']).next().unwrap_or("");
+    let host = host.rsplit('@').next().unwrap_or(host);
+    let host = host.split(':').next().unwrap_or(host).to_lowercase();
+    if host.is_empty() { None } else { Some(host) }
+}
@@ -0,0 +1,81 @@
+use std::time::{Duration, Instant};
+
+use similar::{ChangeTag, TextDiff};
+
+use crate::models::edit_revision::DiffLine;
+
+/// 描述改动（忽略空白变化）达到该字符数时视为实质性修改，与原文长度无关
+pub const MATERIAL_EDIT_CHARS: usize = 20;
+/// 逐字比较的时间上限；超时后差异结果偏大，按实质性修改处理
+const MATERIAL_EDIT_DIFF_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// 按行比较两段文本；末行是否带换行不算差异
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let terminate = |text: &str| if text.is_empty() || text.ends_with('\n') { text.to_string() } else { format!("{}\n", text) };
+    let (old, new) = (terminate(old), terminate(new));
+    TextDiff::from_lines(&old, &new)
+        .iter_all_changes()
+        .map(|change| DiffLine {
+            op: match change.tag() {
+                ChangeTag::Equal => "equal",
+                ChangeTag::Insert => "insert",
+                ChangeTag::Delete => "delete",
+            }
+            .to_string(),
+            text: change.value().trim_end_matches('\n').to_string(),
+        })
+        .collect()
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// 文本中引用的外部地址：裸链接、Markdown 链接/图片目标与 HTML 的 src/href 属性值
+fn references(text: &str) -> Vec<&str> {
+    let mut refs: Vec<&str> = text
+        .split(|c: char| c.is_whitespace() || matches!(c, '(' | ')' | '<' | '>' | '"' | '\''))
+        .filter(|w| w.starts_with("http://") || w.starts_with("https://"))
+        .collect();
+    let target = |rest: &'static [char]| move |s: &str| -> usize { s.find(|c: char| c.is_whitespace() || rest.contains(&c)).unwrap_or(s.len()) };
+    let until_paren = target(&[')']);
+    refs.extend(text.match_indices("](").map(|(i, _)| {
+        let rest = &text[i + 2..];
+        &rest[..until_paren(rest)]
+    }));
+    let lower = text.to_ascii_lowercase();
+    let until_quote = target(&['"', '\'', '>']);
+    for attr in ["src=", "href="] {
+        refs.extend(lower.match_indices(attr).map(|(i, _)| {
+            let rest = text[i + attr.len()..].trim_start_matches(['"', '\'']);
+            &rest[..until_quote(rest)]
+        }));
+    }
+    refs.retain(|r| !r.is_empty());
+    refs
+}
+
+/// 图片数量：Markdown 图片与 <img> 标签
+fn image_count(text: &str) -> usize {
+    text.matches("![").count() + text.to_ascii_lowercase().matches("<img").count()
+}
+
+/// 实质性修改：忽略空白变化后改动的字符数达到 MATERIAL_EDIT_CHARS，或新增了原文没有的链接/图片
+pub fn is_material_edit(old: &str, new: &str) -> bool {
+    let (old_norm, new_norm) = (normalize(old), normalize(new));
+    if old_norm == new_norm {
+        return false;
+    }
+    let old_refs = references(old);
+    if references(new).iter().any(|r| !old_refs.contains(r)) || image_count(new) > image_count(old) {
+        return true;
+    }
+    let changed: usize = TextDiff::configure()
+        .deadline(Instant::now() + MATERIAL_EDIT_DIFF_TIMEOUT)
+        .diff_chars(old_norm.as_str(), new_norm.as_str())
+        .iter_all_changes()
+        .filter(|c| c.tag() != ChangeTag::Equal)
+        .map(|c| c.value().chars().count())
+        .sum();
+    changed >= MATERIAL_EDIT_CHARS
+}
@@ -0,0 +1,77 @@
+use actix_web::http::StatusCode;
+use actix_web::{HttpResponse, ResponseError};
+use serde_json::json;
+
+/// 业务错误，按类型映射 HTTP 状态码，响应体为 `{"code": 状态码, "message": 错误信息}`
+///
+/// 服务层仍返回 `anyhow::Result`，用 `ServiceError::not_found(..)` 等构造带类型的错误；
+/// 处理器中通过 `ServiceError::from(e)` 还原类型，未标注类型的错误（如数据库错误）按 500 处理。
+#[derive(Debug, thiserror::Error)]
+pub enum ServiceError {
+    #[error("{0}")]
+    BadRequest(String),
+    #[error("{0}")]
+    Forbidden(String),
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    Conflict(String),
+    #[error("{0}")]
+    TooManyRequests(String),
+    #[error("{0}")]
+    Internal(anyhow::Error),
+}
+
+impl ServiceError {
+    /// 参数或业务规则校验失败（400）
+    pub fn bad_request(msg: impl Into<String>) -> anyhow::Error {
+        ServiceError::BadRequest(msg.into()).into()
+    }
+
+    /// 无权操作（403）
+    pub fn forbidden(msg: impl Into<String>) -> anyhow::Error {
+        ServiceError::Forbidden(msg.into()).into()
+    }
+
+    /// 目标不存在（404）
+    pub fn not_found(msg: impl Into<String>) -> anyhow::Error {
+        ServiceError::NotFound(msg.into()).into()
+    }
+
+    /// 与当前状态冲突（409）
+    pub fn conflict(msg: impl Into<String>) -> anyhow::Error {
+        ServiceError::Conflict(msg.into()).into()
+    }
+
+    /// 操作过于频繁（429）
+    pub fn too_many_requests(msg: impl Into<String>) -> anyhow::Error {
+        ServiceError::TooManyRequests(msg.into()).into()
+    }
+}
+
+impl From<anyhow::Error> for ServiceError {
+    fn from(e: anyhow::Error) -> Self {
+        match e.downcast::<ServiceError>() {
+            Ok(e) => e,
+            Err(e) => ServiceError::Internal(e),
+        }
+    }
+}
+
+impl ResponseError for ServiceError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ServiceError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ServiceError::Forbidden(_) => StatusCode::FORBIDDEN,
+            ServiceError::NotFound(_) => StatusCode::NOT_FOUND,
+            ServiceError::Conflict(_) => StatusCode::CONFLICT,
+            ServiceError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+            ServiceError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let status = self.status_code();
+        HttpResponse::build(status).json(json!({"code": status.as_u16(), "message": self.to_string()}))
+    }
+}
@@ -0,0 +1,253 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+
+use once_cell::sync::Lazy;
+use pulldown_cmark::{html, CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use syntect::highlighting::ThemeSet;
+use syntect::html::{css_for_theme_with_class_style, ClassStyle, ClassedHTMLGenerator};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+/// 渲染规则版本，修改白名单或高亮方式时递增，使已缓存的渲染结果失效
+pub const RENDERER_VERSION: i32 = 2;
+/// 列表与搜索摘要的默认长度（字符）
+pub const EXCERPT_CHARS: usize = 140;
+/// 图片只允许引用本站上传的文件
+pub const ALLOWED_IMAGE_PREFIX: &str = "/uploads/";
+/// 代码高亮 span 的 class 前缀，前端通过 /render/highlight.css 获取对应样式
+const HIGHLIGHT_CLASS_STYLE: ClassStyle = ClassStyle::SpacedPrefixed { prefix: "hl-" };
+const HIGHLIGHT_THEME: &str = "InspiredGitHub";
+
+static SYNTAX_SET: Lazy<SyntaxSet> = Lazy::new(SyntaxSet::load_defaults_newlines);
+
+static SANITIZER: Lazy<ammonia::Builder<'static>> = Lazy::new(|| {
+    let tags: HashSet<&str> = [
+        "p", "br", "hr", "h1", "h2", "h3", "h4", "h5", "h6", "strong", "em", "del", "s", "code", "pre",
+        "blockquote", "ul", "ol", "li", "a", "img", "table", "thead", "tbody", "tr", "th", "td", "span",
+    ]
+    .into_iter()
+    .collect();
+    let tag_attributes: HashMap<&str, HashSet<&str>> = [
+        ("a", ["href", "title"].into_iter().collect()),
+        ("img", ["src", "alt", "title"].into_iter().collect()),
+        ("ol", ["start"].into_iter().collect()),
+        ("pre", ["class"].into_iter().collect()),
+        ("code", ["class"].into_iter().collect()),
+        ("span", ["class"].into_iter().collect()),
+    ]
+    .into_iter()
+    .collect();
+    let mut builder = ammonia::Builder::empty();
+    builder
+        .tags(tags)
+        .tag_attributes(tag_attributes)
+        .generic_attributes(HashSet::new())
+        .url_schemes(["http", "https", "mailto"].into_iter().collect())
+        .link_rel(Some("nofollow noopener noreferrer"))
+        .attribute_filter(filter_attribute);
+    builder
+});
+
+/// 图片地址限制在本站上传目录；class 只保留代码高亮使用的 hl- 前缀
+fn filter_attribute<'u>(element: &str, attribute: &str, value: &'u str) -> Option<Cow<'u, str>> {
+    match (element, attribute) {
+        ("img", "src") => is_upload_path(value).then_some(Cow::Borrowed(value)),
+        (_, "class") => {
+            let classes: Vec<&str> = value.split_whitespace().filter(|c| c.starts_with("hl-")).collect();
+            (!classes.is_empty()).then(|| Cow::Owned(classes.join(" ")))
+        }
+        _ => Some(Cow::Borrowed(value)),
+    }
+}
+
+/// 是否为本站上传目录下的路径：按解码后的路径判断，含 `.`、`..` 段或反斜杠的一律拒绝，
+/// 避免 `/uploads/../` 或 `/uploads/%2e%2e/` 通过前缀检查后跳出上传目录
+fn is_upload_path(value: &str) -> bool {
+    let path = value.split(['?', '#']).next().unwrap_or_default();
+    let Ok(decoded) = urlencoding::decode(path) else {
+        return false;
+    };
+    decoded.starts_with(ALLOWED_IMAGE_PREFIX)
+        && !decoded.contains('\\')
+        && !decoded.split('/').any(|segment| segment == "." || segment == "..")
+}
+
+fn markdown_options() -> Options {
+    Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH
+}
+
+/// 代码高亮为带 class 的 span；语言未知时按第一行猜测，仍无法识别则按纯文本输出
+pub fn highlight_code(code: &str, lang: Option<&str>) -> String {
+    let syntax = lang
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .and_then(|l| SYNTAX_SET.find_syntax_by_token(l))
+        .or_else(|| SYNTAX_SET.find_syntax_by_first_line(code))
+        .unwrap_or_else(|| SYNTAX_SET.find_syntax_plain_text());
+    let mut generator = ClassedHTMLGenerator::new_with_class_style(syntax, &SYNTAX_SET, HIGHLIGHT_CLASS_STYLE);
+    for line in LinesWithEndings::from(code) {
+        if generator.parse_html_for_line_which_includes_newline(line).is_err() {
+            // 语法解析失败时退回不高亮的转义文本
+            let mut escaped = String::new();
+            html::push_html(&mut escaped, std::iter::once(Event::Text(code.into())));
+            return format!("<pre class=\"hl-code\"><code>{}</code></pre>", escaped);
+        }
+    }
+    format!("<pre class=\"hl-code\"><code>{}</code></pre>", generator.finalize())
+}
+
+/// 代码片段渲染为净化后的高亮 HTML
+pub fn render_code(code: &str, lang: Option<&str>) -> String {
+    SANITIZER.clean(&highlight_code(code, lang)).to_string()
+}
+
+/// Markdown 渲染为净化后的 HTML：标签白名单、链接加 rel=nofollow、图片仅限本站上传，围栏代码块高亮
+pub fn render_markdown(source: &str) -> String {
+    let mut events: Vec<Event> = Vec::new();
+    let mut code_block: Option<(Option<String>, String)> = None;
+    for event in Parser::new_ext(source, markdown_options()) {
+        match event {
+            Event::Start(Tag::CodeBlock(kind)) => {
+                let lang = match kind {
+                    CodeBlockKind::Fenced(info) => info.split_whitespace().next().map(str::to_string),
+                    CodeBlockKind::Indented => None,
+                };
+                code_block = Some((lang, String::new()));
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                if let Some((lang, code)) = code_block.take() {
+                    events.push(Event::Html(highlight_code(&code, lang.as_deref()).into()));
+                }
+            }
+            Event::Text(text) if code_block.is_some() => {
+                if let Some((_, code)) = code_block.as_mut() {
+                    code.push_str(&text);
+                }
+            }
+            other => events.push(other),
+        }
+    }
+    let mut unsafe_html = String::new();
+    html::push_html(&mut unsafe_html, events.into_iter());
+    SANITIZER.clean(&unsafe_html).to_string()
+}
+
+/// 纯文本摘要：去掉 Markdown 语法、HTML、代码块与图片，合并空白后截断
+pub fn excerpt(source: &str, max_chars: usize) -> String {
+    let mut text = String::new();
+    let mut skip_depth: usize = 0;
+    for event in Parser::new_ext(source, markdown_options()) {
+        match event {
+            Event::Start(Tag::CodeBlock(_)) | Event::Start(Tag::Image { .. }) => skip_depth += 1,
+            Event::End(TagEnd::CodeBlock) | Event::End(TagEnd::Image) => skip_depth = skip_depth.saturating_sub(1),
+            // 内嵌的 script / style 内容不计入摘要
+            Event::Html(h) | Event::InlineHtml(h) => {
+                let tag = h.trim_start().to_ascii_lowercase();
+                if tag.starts_with("<script") || tag.starts_with("<style") {
+                    skip_depth += 1;
+                }
+                if tag.contains("</script") || tag.contains("</style") {
+                    skip_depth = skip_depth.saturating_sub(1);
+                }
+            }
+            Event::Text(t) | Event::Code(t) if skip_depth == 0 => {
+                text.push_str(&t);
+                text.push(' ');
+            }
+            Event::SoftBreak | Event::HardBreak | Event::End(_) => text.push(' '),
+            _ => {}
+        }
+    }
+    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.chars().count() <= max_chars {
+        return collapsed;
+    }
+    let mut truncated: String = collapsed.chars().take(max_chars).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// 渲染结果中的本站图片改为绝对地址（缓存中保存相对路径，按请求的公共前缀补全）
+pub fn absolutize_uploads(html: &str, base_prefix: &str) -> String {
+    html.replace(
+        &format!("src=\"{}", ALLOWED_IMAGE_PREFIX),
+        &format!("src=\"{}{}", base_prefix.trim_end_matches('/'), ALLOWED_IMAGE_PREFIX),
+    )
+}
+
+/// 代码高亮样式表
+pub fn highlight_css() -> String {
+    let themes = ThemeSet::load_defaults();
+    themes
+        .themes
+        .get(HIGHLIGHT_THEME)
+        .and_then(|theme| css_for_theme_with_class_style(theme, HIGHLIGHT_CLASS_STYLE).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_script_tags() {
+        let html = render_markdown("hello <script>alert(1)</script> world");
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("alert(1)"));
+        assert!(html.contains("hello"));
+    }
+
+    #[test]
+    fn strips_event_handler_attributes() {
+        let html = render_markdown(r#"<img src="/uploads/a.png" onerror="alert(1)"> <a href="https://example.com" onclick="x()">x</a>"#);
+        assert!(!html.contains("onerror"));
+        assert!(!html.contains("onclick"));
+        assert!(html.contains(r#"src="/uploads/a.png""#));
+    }
+
+    #[test]
+    fn rejects_script_and_data_urls() {
+        for source in [
+            "[x](javascript:alert(1))",
+            r#"<a href="javascript:alert(1)">x</a>"#,
+            r#"<a href="data:text/html;base64,PHNjcmlwdD4=">x</a>"#,
+            "![x](data:image/png;base64,AAAA)",
+        ] {
+            let html = render_markdown(source);
+            assert!(!html.contains("javascript:"), "{}", html);
+            assert!(!html.contains("data:"), "{}", html);
+        }
+    }
+
+    #[test]
+    fn rejects_external_image_src() {
+        let html = render_markdown("![x](https://evil.example.com/a.png) ![y](//evil.example.com/b.png)");
+        assert!(!html.contains("evil.example.com"));
+        let html = render_markdown("![x](/uploads/images/a.png)");
+        assert!(html.contains(r#"src="/uploads/images/a.png""#));
+    }
+
+    #[test]
+    fn rejects_upload_path_traversal() {
+        for src in [
+            "/uploads/../config.toml",
+            "/uploads/a/../../data.db",
+            "/uploads/%2e%2e/data.db",
+            "/uploads/%2E%2E%2Fdata.db",
+            "/uploads/./a.png",
+            "/uploads/..\\data.db",
+        ] {
+            let html = render_markdown(&format!("![x]({})", src));
+            assert!(!html.contains("src="), "{} -> {}", src, html);
+        }
+        assert!(is_upload_path("/uploads/a..b/c.png"));
+        assert!(is_upload_path("/uploads/%E5%A4%B4%E5%83%8F.png?v=1"));
+    }
+
+    #[test]
+    fn forces_link_rel() {
+        let html = render_markdown(r#"[x](https://example.com) <a href="https://example.com" rel="opener">y</a>"#);
+        assert_eq!(html.matches(r#"rel="nofollow noopener noreferrer""#).count(), 2, "{}", html);
+        assert!(!html.contains(r#"rel="opener""#));
+    }
+}
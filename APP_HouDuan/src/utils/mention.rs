@@ -0,0 +1,44 @@
+/// 单条内容最多触发的提及数，避免批量 @ 骚扰
+pub const MAX_MENTIONS: usize = 10;
+/// 用户名最大长度，与注册校验保持一致
+const MAX_USERNAME_LEN: usize = 10;
+
+fn is_username_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// 提取内容中的 `@username`（去重、保持出现顺序，最多 MAX_MENTIONS 个）
+///
+/// `@` 前紧跟用户名字符时（如邮箱地址 a@b.com）不视为提及。
+pub fn extract_mentions(content: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut prev: Option<char> = None;
+    let mut chars = content.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if c == '@' && !prev.map(is_username_char).unwrap_or(false) {
+            let start = i + 1;
+            let mut end = start;
+            while let Some(&(j, n)) = chars.peek() {
+                if !is_username_char(n) {
+                    break;
+                }
+                end = j + n.len_utf8();
+                chars.next();
+            }
+            let name = &content[start..end];
+            if !name.is_empty()
+                && name.len() <= MAX_USERNAME_LEN
+                && !names.iter().any(|n| n.eq_ignore_ascii_case(name))
+            {
+                names.push(name.to_string());
+                if names.len() >= MAX_MENTIONS {
+                    break;
+                }
+            }
+            prev = content[..end].chars().next_back();
+            continue;
+        }
+        prev = Some(c);
+    }
+    names
+}
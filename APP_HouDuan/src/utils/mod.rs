@@ -2,4 +2,12 @@ pub mod file;
 pub mod jwt;
 pub mod password;
 pub mod auth_helper;
-pub mod logger; 
\ No newline at end of file
+pub mod logger;
+pub mod template;
+pub mod time;
+pub mod mention;
+pub mod markdown;
+pub mod diff;
+pub mod version;
+pub mod client_info;
+pub mod error;
@@ -0,0 +1,314 @@
+use anyhow::Result;
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+/// 邮件模板语法：
+/// - `{{name}}` / `{{user.name}}` 输出变量（正文自动 HTML 转义，主题按纯文本输出）
+/// - `{{#if name}}...{{else}}...{{/if}}` 条件
+/// - `{{#each items}}...{{/each}}` 循环，循环体内可用 `{{this}}`、`{{this.field}}`、`{{field}}`、`{{@index}}`
+#[derive(Debug, Clone)]
+pub enum Node {
+    Text(String),
+    Var(String),
+    If { path: String, then: Vec<Node>, otherwise: Vec<Node> },
+    Each { path: String, body: Vec<Node> },
+}
+
+enum Token {
+    Text(String),
+    Var(String),
+    If(String),
+    Else,
+    EndIf,
+    Each(String),
+    EndEach,
+}
+
+fn tokenize(src: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut rest = src;
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            tokens.push(Token::Text(rest[..start].to_string()));
+        }
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| anyhow::anyhow!("模板语法错误：`{{{{` 缺少对应的 `}}}}`"))?;
+        let tag = after[..end].trim();
+        let token = if let Some(path) = tag.strip_prefix("#if ") {
+            Token::If(check_path(path.trim())?)
+        } else if let Some(path) = tag.strip_prefix("#each ") {
+            Token::Each(check_path(path.trim())?)
+        } else {
+            match tag {
+                "else" => Token::Else,
+                "/if" => Token::EndIf,
+                "/each" => Token::EndEach,
+                _ => Token::Var(check_path(tag)?),
+            }
+        };
+        tokens.push(token);
+        rest = &after[end + 2..];
+    }
+    if !rest.is_empty() {
+        tokens.push(Token::Text(rest.to_string()));
+    }
+    Ok(tokens)
+}
+
+fn check_path(path: &str) -> Result<String> {
+    let valid = !path.is_empty()
+        && path.split('.').all(|seg| {
+            !seg.is_empty() && seg.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '@')
+        });
+    if valid {
+        Ok(path.to_string())
+    } else {
+        Err(anyhow::anyhow!("模板语法错误：无效的变量 `{}`", path))
+    }
+}
+
+/// 解析模板，语法错误时返回错误
+pub fn parse(src: &str) -> Result<Vec<Node>> {
+    let tokens = tokenize(src)?;
+    let mut iter = tokens.into_iter();
+    let (nodes, end) = parse_block(&mut iter)?;
+    match end {
+        None => Ok(nodes),
+        Some(_) => Err(anyhow::anyhow!("模板语法错误：存在多余的结束标签")),
+    }
+}
+
+enum BlockEnd {
+    Else,
+    EndIf,
+    EndEach,
+}
+
+fn parse_block(iter: &mut impl Iterator<Item = Token>) -> Result<(Vec<Node>, Option<BlockEnd>)> {
+    let mut nodes = Vec::new();
+    while let Some(token) = iter.next() {
+        match token {
+            Token::Text(t) => nodes.push(Node::Text(t)),
+            Token::Var(p) => nodes.push(Node::Var(p)),
+            Token::If(path) => {
+                let (then, end) = parse_block(iter)?;
+                let otherwise = match end {
+                    Some(BlockEnd::EndIf) => Vec::new(),
+                    Some(BlockEnd::Else) => match parse_block(iter)? {
+                        (nodes, Some(BlockEnd::EndIf)) => nodes,
+                        _ => return Err(anyhow::anyhow!("模板语法错误：`{{{{#if {}}}}}` 缺少 `{{{{/if}}}}`", path)),
+                    },
+                    _ => return Err(anyhow::anyhow!("模板语法错误：`{{{{#if {}}}}}` 缺少 `{{{{/if}}}}`", path)),
+                };
+                nodes.push(Node::If { path, then, otherwise });
+            }
+            Token::Each(path) => match parse_block(iter)? {
+                (body, Some(BlockEnd::EndEach)) => nodes.push(Node::Each { path, body }),
+                _ => return Err(anyhow::anyhow!("模板语法错误：`{{{{#each {}}}}}` 缺少 `{{{{/each}}}}`", path)),
+            },
+            Token::Else => return Ok((nodes, Some(BlockEnd::Else))),
+            Token::EndIf => return Ok((nodes, Some(BlockEnd::EndIf))),
+            Token::EndEach => return Ok((nodes, Some(BlockEnd::EndEach))),
+        }
+    }
+    Ok((nodes, None))
+}
+
+/// 收集模板中引用的顶层变量名（不含循环体内的 this / @index）
+pub fn referenced_variables(nodes: &[Node]) -> BTreeSet<String> {
+    let mut vars = BTreeSet::new();
+    collect_variables(nodes, false, &mut vars);
+    vars
+}
+
+fn collect_variables(nodes: &[Node], in_loop: bool, vars: &mut BTreeSet<String>) {
+    for node in nodes {
+        match node {
+            Node::Text(_) => {}
+            Node::Var(p) => add_variable(p, in_loop, vars),
+            Node::If { path, then, otherwise } => {
+                add_variable(path, in_loop, vars);
+                collect_variables(then, in_loop, vars);
+                collect_variables(otherwise, in_loop, vars);
+            }
+            Node::Each { path, body } => {
+                add_variable(path, in_loop, vars);
+                collect_variables(body, true, vars);
+            }
+        }
+    }
+}
+
+fn add_variable(path: &str, in_loop: bool, vars: &mut BTreeSet<String>) {
+    let root = path.split('.').next().unwrap_or(path);
+    if !in_loop && root != "this" && !root.starts_with('@') {
+        vars.insert(root.to_string());
+    }
+}
+
+/// 渲染已解析的模板（变量 HTML 转义）
+pub fn render(nodes: &[Node], context: &Value) -> String {
+    render_with(nodes, context, true)
+}
+
+/// 解析并渲染模板（变量 HTML 转义），用于邮件正文
+pub fn render_str(src: &str, context: &Value) -> Result<String> {
+    Ok(render(&parse(src)?, context))
+}
+
+/// 解析并渲染纯文本模板（变量不转义），用于邮件主题
+pub fn render_plain_str(src: &str, context: &Value) -> Result<String> {
+    Ok(render_with(&parse(src)?, context, false))
+}
+
+fn render_with(nodes: &[Node], context: &Value, escape: bool) -> String {
+    let mut out = String::new();
+    let mut scopes = vec![context.clone()];
+    render_nodes(nodes, &mut scopes, escape, &mut out);
+    out
+}
+
+fn render_nodes(nodes: &[Node], scopes: &mut Vec<Value>, escape: bool, out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(t) => out.push_str(t),
+            Node::Var(p) => {
+                if let Some(v) = lookup(scopes, p) {
+                    let text = value_to_string(&v);
+                    if escape {
+                        out.push_str(&escape_html(&text));
+                    } else {
+                        out.push_str(&text);
+                    }
+                }
+            }
+            Node::If { path, then, otherwise } => {
+                let branch = if lookup(scopes, path).map(|v| is_truthy(&v)).unwrap_or(false) { then } else { otherwise };
+                render_nodes(branch, scopes, escape, out);
+            }
+            Node::Each { path, body } => {
+                if let Some(Value::Array(items)) = lookup(scopes, path) {
+                    for (index, item) in items.into_iter().enumerate() {
+                        let mut scope = match &item {
+                            Value::Object(map) => map.clone(),
+                            _ => serde_json::Map::new(),
+                        };
+                        scope.insert("this".to_string(), item);
+                        scope.insert("@index".to_string(), Value::from(index));
+                        scopes.push(Value::Object(scope));
+                        render_nodes(body, scopes, escape, out);
+                        scopes.pop();
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn lookup(scopes: &[Value], path: &str) -> Option<Value> {
+    let mut segments = path.split('.');
+    let root = segments.next()?;
+    let mut current = scopes.iter().rev().find_map(|s| s.get(root))?.clone();
+    for seg in segments {
+        current = current.get(seg)?.clone();
+    }
+    Some(current)
+}
+
+fn is_truthy(v: &Value) -> bool {
+    match v {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().map(|f| f != 0.0).unwrap_or(false),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(a) => !a.is_empty(),
+        Value::Object(o) => !o.is_empty(),
+    }
+}
+
+fn value_to_string(v: &Value) -> String {
+    match v {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// HTML 转义
+pub fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn escapes_html_special_chars_in_body() {
+        let ctx = json!({"name": "<script>alert(\"x\")</script> & 'y'"});
+        let html = render_str("Hi {{name}}", &ctx).unwrap();
+        assert_eq!(html, "Hi &lt;script&gt;alert(&quot;x&quot;)&lt;/script&gt; &amp; &#39;y&#39;");
+    }
+
+    #[test]
+    fn escapes_nested_and_loop_values() {
+        let ctx = json!({"user": {"name": "<b>"}, "items": [{"title": "a&b"}, "<i>"]});
+        let html = render_str("{{user.name}}{{#each items}}[{{title}}|{{this}}]{{/each}}", &ctx).unwrap();
+        assert!(!html.contains("<b>") && !html.contains("<i>"));
+        assert!(html.starts_with("&lt;b&gt;[a&amp;b|"));
+        assert!(html.contains("[|&lt;i&gt;]"));
+    }
+
+    #[test]
+    fn template_text_is_not_escaped() {
+        let html = render_str("<p>{{name}}</p>", &json!({"name": "a<b"})).unwrap();
+        assert_eq!(html, "<p>a&lt;b</p>");
+    }
+
+    #[test]
+    fn missing_variables_render_empty() {
+        let ctx = json!({"name": null});
+        assert_eq!(render_str("[{{name}}][{{missing}}][{{user.name}}]", &ctx).unwrap(), "[][][]");
+        assert_eq!(render_str("{{#if missing}}yes{{else}}no{{/if}}", &ctx).unwrap(), "no");
+        assert_eq!(render_str("{{#each missing}}x{{/each}}", &ctx).unwrap(), "");
+    }
+
+    #[test]
+    fn subject_is_rendered_without_escaping() {
+        let ctx = json!({"title": "Tom & Jerry <新版> \"1.0\" 'beta'"});
+        assert_eq!(
+            render_plain_str("更新：{{title}}", &ctx).unwrap(),
+            "更新：Tom & Jerry <新版> \"1.0\" 'beta'"
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_templates() {
+        assert!(parse("{{name").is_err());
+        assert!(parse("{{#if a}}x").is_err());
+        assert!(parse("{{#each a}}x{{/if}}").is_err());
+        assert!(parse("{{/if}}").is_err());
+        assert!(parse("{{a b}}").is_err());
+    }
+
+    #[test]
+    fn collects_top_level_variables() {
+        let nodes = parse("{{a}}{{b.c}}{{#each list}}{{this.x}}{{@index}}{{y}}{{/each}}{{#if d}}{{/if}}").unwrap();
+        let vars: Vec<_> = referenced_variables(&nodes).into_iter().collect();
+        assert_eq!(vars, vec!["a", "b", "d", "list"]);
+    }
+}
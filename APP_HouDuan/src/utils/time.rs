@@ -0,0 +1,24 @@
+use chrono::{DateTime, FixedOffset, Utc};
+
+/// 社区所在时区（北京时间 UTC+8），用于按自然日/周划分的业务逻辑
+pub fn community_offset() -> FixedOffset {
+    FixedOffset::east_opt(8 * 3600).expect("valid offset")
+}
+
+/// 社区时区下的当前时间
+pub fn local_now() -> DateTime<FixedOffset> {
+    Utc::now().with_timezone(&community_offset())
+}
+
+/// 解析数据库中的时间：兼容 RFC3339 与 SQLite `datetime('now')` / `CURRENT_TIMESTAMP`
+/// 的 `YYYY-MM-DD HH:MM:SS[.fff]` 格式（按 UTC 处理）
+pub fn parse_db_time(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f")
+                .ok()
+                .map(|dt| dt.and_utc())
+        })
+}
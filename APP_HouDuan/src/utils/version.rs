@@ -0,0 +1,90 @@
+use semver::{Version, VersionReq};
+
+/// 宽松解析版本号：去掉前缀 v，缺少的次版本号与修订号补 0（如 v4.6 → 4.6.0、4.6-beta1 → 4.6.0-beta1），
+/// 无法识别时返回 None
+pub fn parse_lenient(raw: &str) -> Option<Version> {
+    let trimmed = raw.trim().trim_start_matches(['v', 'V']);
+    if let Ok(version) = Version::parse(trimmed) {
+        return Some(version);
+    }
+    let (core, pre) = match trimmed.find(['-', '+']) {
+        Some(pos) => trimmed.split_at(pos),
+        None => (trimmed, ""),
+    };
+    let parts: Vec<&str> = core.split('.').collect();
+    if parts.is_empty() || parts.len() > 3 || parts.iter().any(|p| p.is_empty() || !p.bytes().all(|b| b.is_ascii_digit())) {
+        return None;
+    }
+    let mut padded = parts.join(".");
+    for _ in parts.len()..3 {
+        padded.push_str(".0");
+    }
+    Version::parse(&format!("{}{}", padded, pre)).ok()
+}
+
+/// 解析依赖的版本范围，空串视为任意版本
+pub fn parse_requirement(raw: &str) -> Result<VersionReq, semver::Error> {
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        return Ok(VersionReq::STAR);
+    }
+    VersionReq::parse(trimmed)
+}
+
+/// 版本是否满足范围；版本号无法识别时只满足任意版本（*）
+pub fn satisfies(version: Option<&str>, requirement: &str) -> bool {
+    let Ok(req) = parse_requirement(requirement) else {
+        return false;
+    };
+    match version.and_then(parse_lenient) {
+        Some(v) => req.matches(&v),
+        None => req == VersionReq::STAR,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_lenient_versions() {
+        assert_eq!(parse_lenient("1.2.3"), Some(Version::new(1, 2, 3)));
+        assert_eq!(parse_lenient(" v4.6 "), Some(Version::new(4, 6, 0)));
+        assert_eq!(parse_lenient("V4"), Some(Version::new(4, 0, 0)));
+        assert_eq!(parse_lenient("4.6-beta1"), Version::parse("4.6.0-beta1").ok());
+        assert_eq!(parse_lenient("4.6+build5"), Version::parse("4.6.0+build5").ok());
+    }
+
+    #[test]
+    fn rejects_unrecognized_versions() {
+        for raw in ["", "v", "abc", "1.2.3.4", "1..2", "1.x", "结绳 4.6"] {
+            assert_eq!(parse_lenient(raw), None, "{}", raw);
+        }
+    }
+
+    #[test]
+    fn matches_ranges() {
+        assert!(satisfies(Some("4.6"), ">=4.5"));
+        assert!(!satisfies(Some("4.4.9"), ">=4.5"));
+        assert!(satisfies(Some("1.3"), "^1.2"));
+        assert!(!satisfies(Some("2.0"), "^1.2"));
+        assert!(satisfies(Some("1.2.9"), "~1.2"));
+        assert!(!satisfies(Some("1.3.0"), "~1.2"));
+        assert!(satisfies(Some("v1.5"), ">=1.0, <2.0"));
+        assert!(satisfies(Some("1.0"), ""));
+    }
+
+    #[test]
+    fn unrecognized_versions_only_match_any() {
+        assert!(satisfies(None, "*"));
+        assert!(satisfies(Some("abc"), ""));
+        assert!(!satisfies(None, ">=1.0"));
+        assert!(!satisfies(Some("abc"), ">=1.0"));
+    }
+
+    #[test]
+    fn invalid_requirement_never_matches() {
+        assert!(parse_requirement(">=>1").is_err());
+        assert!(!satisfies(Some("1.0.0"), ">=>1"));
+    }
+}
@@ -1,8 +1,79 @@
 import { http } from './client'
 
-export interface Category { id: number|string; name: string }
+export interface Category {
+	id: number|string
+	name: string
+	description?: string | null
+	enabled?: boolean
+	subscription_locked?: boolean
+	parent_id?: number | null
+	sort_order?: number
+	icon?: string | null
+	// 以下规则为空时沿父分类继承，整条链都未设置时使用全局配置
+	requires_review?: boolean | null
+	allowed_extensions?: string[] | null
+	max_file_size?: number | null
+	post_permission?: CategoryPostPermission | null
+	count?: number
+}
+
+export type CategoryPostPermission = 'all' | 'elder' | 'admin'
+
+// 面包屑中的一级；资源与帖子的列表、详情带 category_path
+export interface CategoryCrumb { id: number; name: string; icon?: string | null }
+
+export interface CategoryNode extends Category {
+	id: number
+	// 直接属于本分类的已上架资源数（不含子分类）
+	count: number
+	children: CategoryNode[]
+}
+
+// 沿父分类继承后实际生效的规则；扩展名与文件大小为 null 表示不限制
+export interface CategoryRules {
+	category_id: number | null
+	requires_review: boolean
+	allowed_extensions: string[] | null
+	max_file_size: number | null
+	post_permission: CategoryPostPermission
+}
+
+export type CategoryRuleField = 'requires_review' | 'allowed_extensions' | 'max_file_size' | 'post_permission'
 
 export async function getCategories() {
 	const data = await http.get<{ items?: Category[]; list?: Category[] }>(`/categories`)
 	return (data.items || data.list || [])
-} 
\ No newline at end of file
+}
+
+export async function getCategoryTree() {
+	return http.get<CategoryNode[]>(`/categories/tree`)
+}
+
+export async function getCategory(id: number) {
+	return http.get<Category & { path: CategoryCrumb[]; effective_rules: CategoryRules }>(`/categories/${id}`)
+}
+
+export async function getCategoryPath(id: number) {
+	return http.get<CategoryCrumb[]>(`/categories/${id}/path`)
+}
+
+export async function getCategoryRules(id: number) {
+	return http.get<CategoryRules>(`/categories/${id}/rules`)
+}
+
+type CategoryInput = Omit<Category, 'id' | 'count'>
+
+// 以下管理接口仅管理员可用
+export async function createCategory(data: CategoryInput & { name: string }) {
+	return http.post<Category>(`/categories`, data)
+}
+
+// parent_id 传 0 移到顶级；inherit 中的规则恢复为继承
+export async function updateCategory(id: number, data: Partial<CategoryInput> & { inherit?: CategoryRuleField[] }) {
+	return http.put<Category>(`/categories/${id}`, data)
+}
+
+// 有子分类时会被拒绝
+export async function deleteCategory(id: number) {
+	return http.delete(`/categories/${id}`)
+}
@@ -21,7 +21,7 @@ const isDevelopment = import.meta.env.DEV || import.meta.env.MODE === 'developme
 
 // 允许通过环境变量覆盖（构建时注入）：VITE_API_BASE / VITE_STORAGE_API_BASE
 //|| (isDevelopment ? '/api/v1' : 'http://39.105.113.219:15201/api/v1')
-const API_BASE = import.meta.env.VITE_API_BASE
+export const API_BASE = import.meta.env.VITE_API_BASE
   || (isDevelopment ? 'http://localhost:15201/api/v1' : 'http://39.105.113.219:15201/api/v1')
 
 // Storage API（默认同 API_BASE，可单独覆盖）
@@ -0,0 +1,135 @@
+import { http } from './client'
+
+export type FavoriteTargetType = 'Package' | 'Post'
+
+export interface FavoriteTarget {
+  target_type: FavoriteTargetType
+  target_id: number
+}
+
+export interface FavoriteCollection {
+  id: number
+  user_id: number
+  name: string
+  description?: string
+  is_public: boolean
+  sort_order: number
+  item_count: number
+  follower_count: number
+  is_following: boolean
+  owner?: {
+    id: number
+    username: string
+    nickname?: string
+    avatar?: string
+  }
+  created_at: string
+  updated_at: string
+}
+
+export interface FavoriteItem extends FavoriteTarget {
+  title?: string
+  author?: string
+  cover_image?: string
+  // 内容已删除或下架时为 false
+  available: boolean
+  // 为空表示在默认收藏中
+  collection_id?: number
+  favorited_at: string
+}
+
+export interface PagedList<T> {
+  list: T[]
+  total: number
+  page: number
+  page_size: number
+}
+
+export interface CollectionPage {
+  collection: FavoriteCollection
+  items: PagedList<FavoriteItem>
+}
+
+// 我的收藏夹
+export async function getMyCollections(): Promise<FavoriteCollection[]> {
+  return http.get<FavoriteCollection[]>('/me/collections')
+}
+
+// 我的全部收藏（uncategorized 为 true 时只看默认收藏）
+export async function getMyFavorites(params?: {
+  page?: number
+  page_size?: number
+  type?: FavoriteTargetType
+  uncategorized?: boolean
+}): Promise<PagedList<FavoriteItem>> {
+  return http.get<PagedList<FavoriteItem>>('/me/favorites', params)
+}
+
+// 我关注的收藏夹
+export async function getFollowedCollections(): Promise<FavoriteCollection[]> {
+  return http.get<FavoriteCollection[]>('/me/followed-collections')
+}
+
+// 某用户的公开收藏夹
+export async function getUserCollections(userId: number): Promise<FavoriteCollection[]> {
+  return http.get<FavoriteCollection[]>(`/users/${userId}/collections`)
+}
+
+// 收藏夹分享页
+export async function getCollection(id: number, params?: { page?: number; page_size?: number }): Promise<CollectionPage> {
+  return http.get<CollectionPage>(`/collections/${id}`, params)
+}
+
+export async function createCollection(data: {
+  name: string
+  description?: string
+  is_public?: boolean
+}): Promise<FavoriteCollection> {
+  return http.post<FavoriteCollection>('/collections', data)
+}
+
+export async function updateCollection(id: number, data: {
+  name?: string
+  description?: string
+  is_public?: boolean
+}): Promise<FavoriteCollection> {
+  return http.put<FavoriteCollection>(`/collections/${id}`, data)
+}
+
+// 删除收藏夹，其中的收藏回到默认收藏
+export async function deleteCollection(id: number): Promise<void> {
+  return http.delete(`/collections/${id}`)
+}
+
+// 调整收藏夹顺序
+export async function reorderCollections(ids: number[]): Promise<FavoriteCollection[]> {
+  return http.put<FavoriteCollection[]>('/collections/order', { ids })
+}
+
+// 收藏并放入收藏夹
+export async function addToCollection(id: number, target: FavoriteTarget): Promise<void> {
+  return http.post(`/collections/${id}/items`, target)
+}
+
+// 移出收藏夹（仍保留收藏）
+export async function removeFromCollection(id: number, target: FavoriteTarget): Promise<void> {
+  return http.delete(`/collections/${id}/items/${target.target_type}/${target.target_id}`)
+}
+
+// 批量移动收藏，collectionId 为空表示移回默认收藏
+export async function moveFavorites(items: FavoriteTarget[], collectionId?: number): Promise<{ count: number }> {
+  return http.post<{ count: number }>('/collections/move', { items, collection_id: collectionId ?? null })
+}
+
+// 调整收藏夹内条目顺序
+export async function reorderCollectionItems(id: number, items: FavoriteTarget[]): Promise<void> {
+  return http.put(`/collections/${id}/items/order`, { items })
+}
+
+export async function followCollection(id: number): Promise<void> {
+  return http.post(`/collections/${id}/follow`)
+}
+
+export async function unfollowCollection(id: number): Promise<void> {
+  return http.delete(`/collections/${id}/follow`)
+}
@@ -0,0 +1,85 @@
+import { http } from './client'
+import type { PublishPostRequest, PublishResourceRequest } from './publish'
+
+export type DraftTargetType = 'Post' | 'Package'
+
+// draft 草稿 / scheduled 定时发布 / publishing 发布中 / failed 定时发布失败
+export type DraftStatus = 'draft' | 'scheduled' | 'publishing' | 'failed'
+
+// 草稿内容与发布接口的请求体相同，允许未填完
+export type DraftPayload = Partial<PublishPostRequest> | Partial<PublishResourceRequest>
+
+export interface Draft {
+  id: number
+  user_id: number
+  target_type: DraftTargetType
+  title: string
+  payload: DraftPayload
+  status: DraftStatus
+  publish_at?: string
+  last_error?: string
+  created_at: string
+  updated_at: string
+}
+
+export interface DraftRevision {
+  id: number
+  draft_id: number
+  title: string
+  payload: DraftPayload
+  created_at: string
+}
+
+export interface PublishedContent {
+  target_type: DraftTargetType
+  target_id: number
+  title: string
+}
+
+// 我的草稿（含定时发布与发布失败的）
+export async function getMyDrafts(type?: DraftTargetType): Promise<Draft[]> {
+  return http.get<Draft[]>('/me/drafts', type ? { type } : undefined)
+}
+
+// 新建草稿；带 publish_at 时直接进入定时发布
+export async function createDraft(data: {
+  target_type: DraftTargetType
+  payload?: DraftPayload
+  publish_at?: string
+}): Promise<Draft> {
+  return http.post<Draft>('/drafts', data)
+}
+
+export async function getDraft(id: number): Promise<Draft> {
+  return http.get<Draft>(`/drafts/${id}`)
+}
+
+// 自动保存（整体替换内容，服务端按间隔记录历史版本）
+export async function saveDraft(id: number, payload: DraftPayload): Promise<Draft> {
+  return http.put<Draft>(`/drafts/${id}`, { payload })
+}
+
+export async function deleteDraft(id: number): Promise<void> {
+  return http.delete(`/drafts/${id}`)
+}
+
+export async function scheduleDraft(id: number, publishAt: string): Promise<Draft> {
+  return http.put<Draft>(`/drafts/${id}/schedule`, { publish_at: publishAt })
+}
+
+export async function unscheduleDraft(id: number): Promise<Draft> {
+  return http.delete<Draft>(`/drafts/${id}/schedule`)
+}
+
+// 立即发布，提交后进入审核
+export async function publishDraft(id: number): Promise<PublishedContent> {
+  return http.post<PublishedContent>(`/drafts/${id}/publish`)
+}
+
+export async function getDraftRevisions(id: number): Promise<DraftRevision[]> {
+  return http.get<DraftRevision[]>(`/drafts/${id}/revisions`)
+}
+
+export async function restoreDraftRevision(id: number, revisionId: number): Promise<Draft> {
+  return http.post<Draft>(`/drafts/${id}/revisions/${revisionId}/restore`)
+}
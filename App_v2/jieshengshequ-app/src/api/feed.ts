@@ -38,4 +38,25 @@ export async function fetchFeed(params?: { type?: 'post'|'resource'|'announcemen
 		}
 	}))
 	return { items, total: data.total, page: data.page, pageSize: data.page_size }
-} 
\ No newline at end of file
+} 
+export type TimelineKind = 'post_published' | 'package_published' | 'package_updated' | 'achievement_earned'
+
+// 关注时间线条目
+export interface TimelineItem {
+	kind: TimelineKind
+	actor: { id: number; username: string; nickname?: string; avatar_url?: string } | null
+	target_type: 'Post' | 'Package' | 'Achievement'
+	target_id: number
+	title: string
+	summary?: string // 摘要、更新后的版本号或成就说明
+	category_id?: number
+	reason: 'following' | 'category'
+	created_at: string
+}
+
+export interface TimelinePage { list: TimelineItem[]; next_cursor: string | null; has_more: boolean }
+
+// 关注时间线（游标分页：传入上一页的 next_cursor）
+export async function fetchFollowingFeed(params?: { cursor?: string; limit?: number; type?: TimelineKind }) {
+	return http.get<TimelinePage>('/feed/following', params)
+}
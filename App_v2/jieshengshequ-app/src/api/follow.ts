@@ -28,6 +28,21 @@ export interface Following {
   followed_at: string
 }
 
+// 拉黑 / 屏蔽列表中的用户
+export interface RelatedUser {
+  id: number
+  username: string
+  nickname?: string
+  avatar?: string
+  created_at: string
+}
+
+export interface RelationStatus {
+  is_blocked: boolean
+  blocked_me: boolean
+  is_muted: boolean
+}
+
 export interface FollowListResponse {
   items: Follower[] | Following[]
   total: number
@@ -118,4 +133,39 @@ export async function getFollowingFeed(params?: {
   
   const queryString = query.toString()
   return http.get(`/me/following-feed${queryString ? `?${queryString}` : ''}`)
-} 
\ No newline at end of file
+} 
+
+// 拉黑用户（双方不能互相关注、回复、@ 与私信，并解除已有关注）
+export async function blockUser(userId: number): Promise<void> {
+  return http.post(`/users/${userId}/block`)
+}
+
+// 取消拉黑
+export async function unblockUser(userId: number): Promise<void> {
+  return http.delete(`/users/${userId}/block`)
+}
+
+// 屏蔽用户（其内容不出现在我的动态、评论与通知中）
+export async function muteUser(userId: number): Promise<void> {
+  return http.post(`/users/${userId}/mute`)
+}
+
+// 取消屏蔽
+export async function unmuteUser(userId: number): Promise<void> {
+  return http.delete(`/users/${userId}/mute`)
+}
+
+// 与某用户的拉黑 / 屏蔽关系
+export async function getRelationStatus(userId: number): Promise<RelationStatus> {
+  return http.get<RelationStatus>(`/users/${userId}/relation`)
+}
+
+// 我的拉黑列表
+export async function getMyBlocks(): Promise<{ list: RelatedUser[]; total: number }> {
+  return http.get(`/me/blocks`)
+}
+
+// 我的屏蔽列表
+export async function getMyMutes(): Promise<{ list: RelatedUser[]; total: number }> {
+  return http.get(`/me/mutes`)
+}
@@ -2,11 +2,13 @@ import { http } from './client'
 
 export interface Message {
   id: number
+  conversation_id: number
   sender_id: number
   receiver_id: number
   content: string
   message_type: 'text' | 'image' | 'file'
   is_read: boolean
+  read_at?: string
   created_at: string
   sender?: {
     id: number
@@ -29,6 +31,7 @@ export interface Conversation {
   participant_nickname?: string
   participant_avatar?: string
   last_message?: string
+  last_message_type?: 'text' | 'image' | 'file'
   last_message_time?: string
   unread_count: number
   is_pinned: boolean
@@ -36,6 +39,11 @@ export interface Conversation {
   created_at: string
 }
 
+export interface MessageSettings {
+  // everyone：所有人；following：仅我关注的人
+  allow_from: 'everyone' | 'following'
+}
+
 export interface SendMessageRequest {
   receiver_id: number
   content: string
@@ -100,4 +108,19 @@ export async function searchConversations(query: string): Promise<Conversation[]
 // 搜索消息
 export async function searchMessages(query: string, userId?: number): Promise<Message[]> {
   return http.get<Message[]>('/messages/search', { q: query, user_id: userId })
-} 
\ No newline at end of file
+} 
+
+// 获取私信接收设置
+export async function getMessageSettings(): Promise<MessageSettings> {
+  return http.get<MessageSettings>('/messages/settings')
+}
+
+// 更新私信接收设置
+export async function updateMessageSettings(data: MessageSettings): Promise<MessageSettings> {
+  return http.put<MessageSettings>('/messages/settings', data)
+}
+
+// 举报私信
+export async function reportMessage(messageId: number, reason?: string): Promise<{ id: number }> {
+  return http.post<{ id: number }>(`/messages/${messageId}/report`, { reason })
+}
@@ -5,10 +5,32 @@ export interface RankingQuery {
   page?: number;
   page_size?: number;
   order_by?: string;
+  period?: RankingPeriod;
+  sort?: 'downloads' | 'rating';
+  category_id?: number;
+}
+
+// 统计周期：自然周 / 自然月 / 自然年 / 累计
+export type RankingPeriod = 'week' | 'month' | 'year' | 'all';
+
+// 历史排行榜查询参数（仅已结束的周期）
+export interface RankingHistoryQuery {
+  board: 'users' | 'resources' | 'posts';
+  period: Exclude<RankingPeriod, 'all'>;
+  key: string; // 2025-W01、2025-01、2025
+  category_id?: number;
+  page?: number;
+  page_size?: number;
+}
+
+// 名次信息：rank_change 为相对上一周期的变化，正数上升，上期未上榜为 null
+interface RankInfo {
+  rank: number;
+  rank_change: number | null;
 }
 
 // 用户排行榜项目
-export interface UserRankingItem {
+export interface UserRankingItem extends RankInfo {
   id: number;
   username: string;
   nickname?: string;
@@ -21,12 +43,13 @@ export interface UserRankingItem {
 }
 
 // 资源排行榜项目
-export interface ResourceRankingItem {
+export interface ResourceRankingItem extends RankInfo {
   id: number;
   title: string;
   description?: string;
   downloads: number;
   likes: number;
+  views: number;
   rating: number;
   category: string;
   created_at: string;
@@ -40,7 +63,7 @@ export interface ResourceRankingItem {
 }
 
 // 帖子排行榜项目
-export interface PostRankingItem {
+export interface PostRankingItem extends RankInfo {
   id: number;
   title: string;
   content_preview?: string;
@@ -63,7 +86,12 @@ export interface RankingResponse<T> {
   total: number;
   page: number;
   page_size: number;
-  has_more: boolean;
+  total_pages: number;
+  period: RankingPeriod;
+  period_key: string | null;
+  period_start: string | null;
+  period_end: string | null;
+  category_id: number | null;
 }
 
 // 获取用户排行榜
@@ -79,4 +107,11 @@ export async function getResourceRanking(params: RankingQuery = {}): Promise<Ran
 // 获取帖子排行榜
 export async function getPostRanking(params: RankingQuery = {}): Promise<RankingResponse<PostRankingItem>> {
   return http.get<RankingResponse<PostRankingItem>>('/ranking/posts', params);
-} 
\ No newline at end of file
+}
+
+// 获取历史排行榜
+export async function getRankingHistory<T = UserRankingItem | ResourceRankingItem | PostRankingItem>(
+  params: RankingHistoryQuery
+): Promise<RankingResponse<T>> {
+  return http.get<RankingResponse<T>>('/ranking/history', params);
+}
@@ -0,0 +1,16 @@
+import { http, API_BASE } from './client'
+
+// 帖子详情返回 content_html / code_snippet_html / excerpt，资源详情返回 description_html / excerpt，
+// 列表与搜索结果带纯文本 excerpt；HTML 已在服务端净化，可直接渲染
+export interface RenderedPreview {
+  html: string
+  excerpt: string
+}
+
+// 编辑器预览，与详情页渲染结果一致；code 为 true 时按代码片段高亮
+export async function previewMarkdown(content: string, options?: { code?: boolean; language?: string }): Promise<RenderedPreview> {
+  return http.post<RenderedPreview>('/render/preview', { content, ...options })
+}
+
+// 代码高亮样式表（class 前缀 hl-），在页面中以 <link rel="stylesheet"> 引入
+export const highlightCssUrl = `${API_BASE}/render/highlight.css`
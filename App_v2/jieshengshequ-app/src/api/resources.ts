@@ -1,4 +1,4 @@
-import { http } from './client'
+import { http, API_BASE, getToken } from './client'
 
 export async function getResources(params?: { page?: number; page_size?: number; category_id?: number; tag?: string; search?: string }) {
 	const data = await http.get<{ list: any[]; total: number; page: number; page_size: number }>(`/resources`, params)
@@ -72,6 +72,217 @@ export async function updateResource(id: number, data: {
 	category_id?: number
 	tags?: string[]
 	requirements?: string[]
+	// 仅管理员可直接指定所有者，其他用户请使用 transferResource
+	owner_id?: number
 }) {
 	return http.put(`/resources/${id}`, data)
-} 
\ No newline at end of file
+} 
+// 推荐资源：资源字段 + 推荐得分与理由
+export type RecommendedResource = any & { score: number; reason?: string | null }
+
+// 下载/喜欢过此资源的用户也下载了
+export async function getSimilarResources(id: number, limit = 10) {
+	return http.get<{ list: RecommendedResource[] }>(`/packages/${id}/similar`, { limit })
+}
+
+// 为我推荐（需要登录；没有历史记录时返回热门资源）
+export async function getRecommendedResources(limit = 10) {
+	return http.get<{ list: RecommendedResource[] }>(`/packages/recommended`, { limit })
+}
+
+// 资源维护者：owner 可管理维护者、转让与删除；maintainer 可编辑与上传
+export interface ResourceMaintainer {
+	user_id: number
+	username: string
+	nickname?: string | null
+	avatar?: string | null
+	role: 'owner' | 'maintainer'
+	added_by?: number | null
+	created_at: string
+}
+
+export interface OwnershipTransfer {
+	id: number
+	package_id: number
+	package_name?: string | null
+	from_user_id: number
+	from_username?: string | null
+	to_user_id: number
+	to_username?: string | null
+	status: 'pending' | 'accepted' | 'declined' | 'cancelled'
+	created_at: string
+	responded_at?: string | null
+}
+
+export async function getResourceMaintainers(id: number) {
+	return http.get<ResourceMaintainer[]>(`/resources/${id}/maintainers`)
+}
+
+export async function addResourceMaintainer(id: number, userId: number) {
+	return http.post<ResourceMaintainer[]>(`/resources/${id}/maintainers`, { user_id: userId })
+}
+
+// 移除维护者；传入自己的 ID 即退出维护
+export async function removeResourceMaintainer(id: number, userId: number) {
+	return http.delete(`/resources/${id}/maintainers/${userId}`)
+}
+
+// 资源当前待对方确认的转让（所有者与管理员可见）
+export async function getPendingTransfer(id: number) {
+	return http.get<OwnershipTransfer | null>(`/resources/${id}/transfer`)
+}
+
+// 发起所有权转让，对方接受后生效，原所有者转为维护者
+export async function transferResource(id: number, toUserId: number) {
+	return http.post<OwnershipTransfer>(`/resources/${id}/transfer`, { to_user_id: toUserId })
+}
+
+export async function cancelResourceTransfer(id: number) {
+	return http.delete(`/resources/${id}/transfer`)
+}
+
+// 转给我的待确认转让
+export async function getMyIncomingTransfers() {
+	return http.get<OwnershipTransfer[]>('/me/package-transfers')
+}
+
+export async function acceptResourceTransfer(transferId: number) {
+	return http.post<OwnershipTransfer>(`/me/package-transfers/${transferId}/accept`)
+}
+
+export async function declineResourceTransfer(transferId: number) {
+	return http.post(`/me/package-transfers/${transferId}/decline`)
+}
+
+// 资源依赖：version_req 为 semver 范围（如 ^1.2、>=1.0, <2.0），缺省为任意版本
+export interface ResourceDependency {
+	depends_on_id: number
+	name?: string
+	version?: string
+	status?: string
+	version_req: string
+	optional: boolean
+	satisfied: boolean
+}
+
+export interface ResourceDependencyInfo {
+	package_id: number
+	version?: string
+	min_app_version?: string | null
+	max_app_version?: string | null
+	dependencies: ResourceDependency[]
+}
+
+export interface DependentResource {
+	package_id: number
+	name: string
+	version?: string
+	owner_id?: number
+	version_req: string
+	optional: boolean
+	satisfied: boolean
+}
+
+export interface DependencyConflict {
+	package_id: number
+	name?: string
+	version?: string
+	requirement?: string
+	required_by?: number
+	reason: string
+}
+
+// install_order 依赖在前、资源本身最后；resolvable 为 false 时查看 conflicts 与 cycles
+export interface DependencyResolution {
+	package_id: number
+	version?: string
+	app_version?: string
+	resolvable: boolean
+	install_order: { package_id: number; name: string; version?: string; file_url?: string; file_size?: number; required_by: number[] }[]
+	conflicts: DependencyConflict[]
+	cycles: number[][]
+}
+
+export async function getResourceDependencies(id: number) {
+	return http.get<ResourceDependencyInfo>(`/resources/${id}/dependencies`)
+}
+
+// 整体替换依赖与兼容的结绳版本（所有者、维护者或管理员）
+export async function updateResourceDependencies(id: number, data: {
+	dependencies: { package_id: number; version_req?: string; optional?: boolean }[]
+	min_app_version?: string | null
+	max_app_version?: string | null
+}) {
+	return http.put<ResourceDependencyInfo>(`/resources/${id}/dependencies`, data)
+}
+
+export async function resolveResourceDependencies(id: number, params?: { app_version?: string; include_optional?: boolean }) {
+	return http.get<DependencyResolution>(`/resources/${id}/resolve`, params)
+}
+
+export async function getResourceDependents(id: number) {
+	return http.get<DependentResource[]>(`/resources/${id}/dependents`)
+}
+
+// 移到其他分类，文件目录随之移动（所有者、维护者或管理员）；
+// 非管理员从免审核分类移入需要审核的分类时资源重新进入审核
+export async function moveResourceCategory(id: number, categoryId: number) {
+	return http.put(`/resources/${id}/category`, { category_id: categoryId })
+}
+
+// 数据分析（所有者、维护者或管理员）：按北京时间分日，days 默认 30，最多 365
+// 浏览来源取详情请求的 ?ref=（如 search、feed），没有时取 Referer 的域名
+export interface ResourceDailyStats {
+	date: string
+	views: number
+	unique_visitors: number
+	downloads: number
+	unique_downloaders: number
+	new_likes: number
+	new_favorites: number
+	like_total: number
+	favorite_total: number
+}
+
+export interface ResourceAnalytics {
+	package_id: number
+	package_name: string
+	current_version?: string | null
+	from: string
+	to: string
+	days: number
+	totals: {
+		views: number
+		unique_visitors: number
+		downloads: number
+		unique_downloaders: number
+		new_likes: number
+		new_favorites: number
+		like_count: number
+		favorite_count: number
+		download_rate: number
+	}
+	daily: ResourceDailyStats[]
+	referrers: { name: string; count: number; percent: number }[]
+	platforms: { name: string; views: number; downloads: number }[]
+	clients: { name: string; views: number; downloads: number }[]
+	// version 为空表示开始记录版本之前的下载
+	versions: { version?: string | null; downloads: number; unique_downloaders: number; first_download_at: string; last_download_at: string; is_current: boolean }[]
+}
+
+export async function getResourceAnalytics(id: number, days?: number) {
+	return http.get<ResourceAnalytics>(`/resources/${id}/analytics`, { days })
+}
+
+// 导出每日数据 CSV，返回文件内容
+export async function exportResourceAnalytics(id: number, days?: number): Promise<Blob> {
+	const token = getToken()
+	const resp = await fetch(`${API_BASE}/resources/${id}/analytics/export${days ? `?days=${days}` : ''}`, {
+		headers: token ? { Authorization: `Bearer ${token}` } : {},
+	})
+	if (!resp.ok) {
+		const body = await resp.json().catch(() => null)
+		throw new Error(body?.message || `导出失败: ${resp.status}`)
+	}
+	return resp.blob()
+}
@@ -0,0 +1,66 @@
+import { http } from './client'
+
+// 帖子、资源与评论的编辑历史；列表与详情带 edited / edit_count / last_edited_at 标记
+export type RevisionTargetType = 'post' | 'resource' | 'comment'
+
+export interface EditMarker {
+  edited: boolean
+  edit_count: number
+  last_edited_at: string | null
+}
+
+export interface ContentRevision {
+  id: number
+  target_type: 'Post' | 'Package' | 'Comment'
+  target_id: number
+  // 1 为首次编辑前的原始版本（editor_id 为空）
+  revision_no: number
+  editor_id?: number
+  editor_name?: string
+  snapshot: Record<string, unknown>
+  changed_fields: string[]
+  note?: string
+  created_at: string
+}
+
+export interface DiffLine {
+  op: 'equal' | 'insert' | 'delete'
+  text: string
+}
+
+export interface FieldDiff {
+  field: string
+  before: unknown
+  after: unknown
+  // 文本字段的按行比较结果
+  lines?: DiffLine[]
+}
+
+export interface RevisionDiff {
+  from: number
+  to: number
+  fields: FieldDiff[]
+}
+
+// 作者、资源维护者与版主可查看，最新的在前
+export async function getRevisions(type: RevisionTargetType, id: number): Promise<ContentRevision[]> {
+  return http.get<ContentRevision[]>(`/revisions/${type}/${id}`)
+}
+
+export async function getRevision(type: RevisionTargetType, id: number, revisionNo: number): Promise<ContentRevision> {
+  return http.get<ContentRevision>(`/revisions/${type}/${id}/${revisionNo}`)
+}
+
+// 不传版本号时比较最新版本与其上一版本
+export async function getRevisionDiff(
+  type: RevisionTargetType,
+  id: number,
+  range?: { from?: number; to?: number }
+): Promise<RevisionDiff> {
+  return http.get<RevisionDiff>(`/revisions/${type}/${id}/diff`, range)
+}
+
+// 版主回滚到指定版本，返回新的版本号
+export async function rollbackRevision(type: RevisionTargetType, id: number, revisionNo: number): Promise<{ revision_no: number }> {
+  return http.post<{ revision_no: number }>(`/revisions/${type}/${id}/${revisionNo}/rollback`)
+}
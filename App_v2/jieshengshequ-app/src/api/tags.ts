@@ -0,0 +1,82 @@
+import { http } from './client'
+
+// 标签：输入时规范化（去掉 #、全角转半角、合并空白，不区分大小写），别名换成目标标签；
+// 发帖时自动创建的新标签为待审核，白名单模式下只能使用已审核的标签
+export type TagStatus = 'approved' | 'pending' | 'banned'
+
+export interface Tag {
+  id: number
+  name: string
+  description?: string | null
+  color?: string | null
+  use_count: number
+  status: TagStatus
+  created_at: string
+  updated_at: string
+}
+
+export interface TagAlias {
+  id: number
+  alias: string
+  tag_id: number
+  tag_name: string
+  created_by?: number | null
+  created_at: string
+}
+
+export interface TagSettings {
+  whitelist_mode: boolean
+}
+
+export async function getTags(params?: { page?: number; page_size?: number; search?: string; sort_by?: 'name' | 'use_count' | 'created_at'; sort_order?: 'asc' | 'desc'; status?: TagStatus | 'all' }) {
+  return http.get<{ list: Tag[]; total: number; page: number; size: number }>(`/tags`, params)
+}
+
+export async function getPopularTags() {
+  return http.get<Tag[]>(`/tags/popular`)
+}
+
+// 可供选择的标签（不含已禁用的，白名单模式下只含已审核的）
+export async function getAllTags() {
+  return http.get<Tag[]>(`/tags/all`)
+}
+
+// 发布前校验：返回规范化后的名称，有不可用的标签时报错并说明原因
+export async function checkTags(tags: string[]) {
+  return http.post<{ tags: string[] }>(`/tags/check`, { tags })
+}
+
+export async function getTagSettings() {
+  return http.get<TagSettings>(`/tags/settings`)
+}
+
+// 以下为管理接口：设置与合并仅管理员，别名与审核管理员和元老
+export async function updateTagSettings(data: TagSettings) {
+  return http.put<TagSettings>(`/tags/settings`, data)
+}
+
+export async function getTagAliases(tagId?: number) {
+  return http.get<TagAlias[]>(tagId ? `/tags/${tagId}/aliases` : `/tags/aliases`)
+}
+
+export async function addTagAlias(tagId: number, alias: string) {
+  return http.post<TagAlias>(`/tags/${tagId}/aliases`, { alias })
+}
+
+export async function deleteTagAlias(aliasId: number) {
+  return http.delete(`/tags/aliases/${aliasId}`)
+}
+
+// 改名时默认把旧名称保留为别名
+export async function renameTag(tagId: number, name: string, keepAlias = true) {
+  return http.put(`/tags/${tagId}`, { name, keep_alias: keepAlias })
+}
+
+// 把 tagId 合并到 targetId：帖子与资源的关联一并改到目标标签
+export async function mergeTag(tagId: number, targetId: number) {
+  return http.post<Tag>(`/tags/${tagId}/merge`, { target_id: targetId })
+}
+
+export async function setTagStatus(tagId: number, status: TagStatus) {
+  return http.put<Tag>(`/tags/${tagId}/status`, { status })
+}
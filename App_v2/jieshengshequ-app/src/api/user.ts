@@ -32,8 +32,36 @@ export interface UserActivityStats {
   total_likes: number
   total_downloads: number
   level: string
+  level_num: number
   experience: number
-  next_level_exp: number
+  stars: number
+  next_level_exp: number | null
+  level_progress: number
+}
+
+// 等级与升级进度
+export interface LevelProgress {
+  level: number
+  title: string
+  experience: number
+  stars: number
+  current_level_xp: number
+  next_level_xp: number | null
+  next_title: string | null
+  progress: number
+}
+
+// 经验/星星流水
+export interface PointLedgerEntry {
+  id: number
+  user_id: number
+  action: string
+  xp: number
+  stars: number
+  reason: string
+  related_type?: string
+  related_id?: number
+  created_at: string
 }
 
 // 成就接口
@@ -43,6 +71,10 @@ export interface Achievement {
   icon: string
   description: string
   earned_at?: string
+  code?: string
+  threshold?: number
+  progress?: number
+  earned?: boolean
 }
 
 // 周报数据接口
@@ -53,6 +85,25 @@ export interface WeeklyReportData {
   today_activity: number
   weekly_posts: number[]
   achievements: Achievement[]
+  week: string
+  week_start: string
+  week_end: string
+  complete: boolean
+  daily_posts: number[]
+  daily_resources: number[]
+  posts_published: number
+  resources_published: number
+  downloads_received: number
+  likes_received: number
+  new_followers: number
+  comments_posted: number
+  check_in_days: number
+  xp_gained: number
+  stars_gained: number
+  rank_start: number
+  rank_end: number
+  rank_change: number
+  generated_at: string
 }
 
 // 获取我的统计数据
@@ -68,20 +119,84 @@ export async function getMyActivityStats(): Promise<UserActivityStats> {
 }
 
 // 获取我的周报
-export async function getMyWeeklyReport(): Promise<WeeklyReportData> {
-  const response = await http.get<WeeklyReportData>('/me/weekly-report')
+// week: ISO 周，如 2025-W01，默认本周
+export async function getMyWeeklyReport(week?: string): Promise<WeeklyReportData> {
+  const response = await http.get<WeeklyReportData>('/me/weekly-report', week ? { week } : undefined)
   return response
 }
 
 // 获取我的成就
-export async function getMyAchievements(): Promise<{ list: Achievement[]; total: number }> {
-  const response = await http.get<{ list: Achievement[]; total: number }>('/me/achievements')
+export async function getMyAchievements(): Promise<{ list: Achievement[]; total: number; earned: number }> {
+  const response = await http.get<{ list: Achievement[]; total: number; earned: number }>('/me/achievements')
+  return response
+}
+
+// 获取用户公开的成就墙（含未获得成就的进度）
+export async function getUserAchievements(userId: number): Promise<{ list: Achievement[]; total: number; earned: number }> {
+  const response = await http.get<{ list: Achievement[]; total: number; earned: number }>(`/users/${userId}/achievements`)
+  return response
+}
+
+// 获取我的等级
+export async function getMyLevel(): Promise<LevelProgress> {
+  const response = await http.get<LevelProgress>('/me/level')
+  return response
+}
+
+// 获取我的经验/星星流水
+export async function getMyPointsLedger(page = 1, pageSize = 20): Promise<{ list: PointLedgerEntry[]; total: number; page: number; page_size: number }> {
+  const response = await http.get<{ list: PointLedgerEntry[]; total: number; page: number; page_size: number }>('/me/points/ledger', { page, page_size: pageSize })
   return response
 }
 
+// 签到结果
+export interface CheckInResult {
+  date: string
+  already_checked_in: boolean
+  checked_in_today: boolean
+  current_streak: number
+  longest_streak: number
+  total_days: number
+  reward: PointLedgerEntry | null
+}
+
+// 补签配置
+export interface MakeupInfo {
+  enabled: boolean
+  cost: number
+  window_days: number
+  monthly_limit: number
+  remaining: number
+}
+
+// 月度签到日历
+export interface CheckInCalendar {
+  month: string
+  days: { date: string; checked_in: boolean; is_makeup: boolean; can_makeup: boolean }[]
+  checked_days: number
+  checked_in_today: boolean
+  current_streak: number
+  longest_streak: number
+  total_days: number
+  makeup: MakeupInfo
+}
+
 // 用户签到
-export async function userCheckIn(): Promise<void> {
-  await http.post<void>('/me/check-in')
+export async function userCheckIn(): Promise<CheckInResult> {
+  const response = await http.post<CheckInResult>('/me/check-in')
+  return response
+}
+
+// 获取签到日历（month: YYYY-MM，默认本月）
+export async function getCheckInCalendar(month?: string): Promise<CheckInCalendar> {
+  const response = await http.get<CheckInCalendar>('/me/check-in/calendar', month ? { month } : undefined)
+  return response
+}
+
+// 消耗星星补签（date: YYYY-MM-DD）
+export async function makeUpCheckIn(date: string): Promise<CheckInResult> {
+  const response = await http.post<CheckInResult>('/me/check-in/makeup', { date })
+  return response
 }
 
 // 上传头像